@@ -0,0 +1,115 @@
+// Measure the serialize/deserialize cycle of remote calls
+//
+// Every remote request and response goes through `serialize_value` on one
+// side and `deserialize_value` plus a typed read on the other. Each step is
+// measured on its own and as a round trip, for every backend compiled in:
+// run with `--features msgpack` to include MessagePack. The compact framing
+// group compares the default header with the type ID header of sealed
+// registries, and prints the frame sizes of both.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
+use runar_common::logging::{Component, Logger};
+use runar_common::types::{ArcValue, SerializationBackend, SerializerRegistry};
+
+fn registry(backend: SerializationBackend) -> SerializerRegistry {
+    SerializerRegistry::with_backend(
+        Arc::new(Logger::new_root(Component::Custom("Bench"), "bench-node")),
+        backend,
+    )
+}
+
+/// Map with 10 entries, the typical shape of action parameters
+fn map_value() -> ArcValue {
+    let map: HashMap<String, f64> = (0..10).map(|i| (format!("key{i}"), i as f64)).collect();
+    ArcValue::new_map(map)
+}
+
+fn bench_map(c: &mut Criterion, backend: SerializationBackend) {
+    let registry = registry(backend);
+    let value = map_value();
+    let bytes = registry.serialize_value(&value).unwrap();
+    let mut group = c.benchmark_group(format!("arcvalue_serde/{backend:?}/map_10_f64"));
+
+    group.bench_function("serialize", |b| {
+        b.iter(|| black_box(registry.serialize_value(black_box(&value)).unwrap()))
+    });
+    group.bench_function("deserialize_lazy", |b| {
+        b.iter(|| black_box(registry.deserialize_value(bytes.clone()).unwrap()))
+    });
+    // Lazy values share their decoded form between clones, so every
+    // iteration starts from freshly deserialized bytes
+    group.bench_function("as_map_ref", |b| {
+        b.iter_batched(
+            || registry.deserialize_value(bytes.clone()).unwrap(),
+            |mut lazy| black_box(lazy.as_map_ref::<String, f64>().unwrap()),
+            BatchSize::SmallInput,
+        )
+    });
+    group.bench_function("round_trip", |b| {
+        b.iter(|| {
+            let bytes = registry.serialize_value(black_box(&value)).unwrap();
+            let mut lazy = registry.deserialize_value(bytes).unwrap();
+            black_box(lazy.as_map_ref::<String, f64>().unwrap())
+        })
+    });
+
+    group.finish();
+}
+
+fn bench_bytes(c: &mut Criterion, backend: SerializationBackend) {
+    let registry = registry(backend);
+    let value = ArcValue::from_bytes(vec![42u8; 1024]);
+    let mut group = c.benchmark_group(format!("arcvalue_serde/{backend:?}/bytes_1kb"));
+
+    group.bench_function("round_trip", |b| {
+        b.iter(|| {
+            let bytes = registry.serialize_value(black_box(&value)).unwrap();
+            let mut value = registry.deserialize_value(bytes).unwrap();
+            black_box(value.as_bytes().unwrap())
+        })
+    });
+
+    group.finish();
+}
+
+fn bench_compact_framing(c: &mut Criterion, backend: SerializationBackend) {
+    let mut registry = registry(backend);
+    registry.seal();
+    let values = [
+        ("i32", ArcValue::new_primitive(42i32)),
+        ("map_10_f64", map_value()),
+    ];
+
+    for (name, value) in values {
+        let verbose = registry.serialize_value(&value).unwrap();
+        let compact = registry.serialize_value_compact(&value).unwrap();
+        let mut group = c.benchmark_group(format!("arcvalue_serde/{backend:?}/compact_{name}"));
+        group.bench_function("serialize_verbose", |b| {
+            b.iter(|| black_box(registry.serialize_value(black_box(&value)).unwrap()))
+        });
+        group.bench_function("serialize_compact", |b| {
+            b.iter(|| black_box(registry.serialize_value_compact(black_box(&value)).unwrap()))
+        });
+        group.bench_function("deserialize_verbose", |b| {
+            b.iter(|| black_box(registry.deserialize_value(verbose.clone()).unwrap()))
+        });
+        group.bench_function("deserialize_compact", |b| {
+            b.iter(|| black_box(registry.deserialize_value(compact.clone()).unwrap()))
+        });
+        group.finish();
+    }
+}
+
+fn bench_backends(c: &mut Criterion) {
+    for backend in SerializationBackend::available() {
+        bench_map(c, backend);
+        bench_bytes(c, backend);
+        bench_compact_framing(c, backend);
+    }
+}
+
+criterion_group!(benches, bench_backends);
+criterion_main!(benches);
@@ -0,0 +1,57 @@
+// Compare owned and borrowed reads of a lazy 1 MB payload
+//
+// `as_type` copies the bytes out of the network buffer; `as_type_borrowed`
+// decodes a view into it.
+
+use std::sync::Arc;
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use runar_common::logging::{Component, Logger};
+use runar_common::types::{ArcValue, SerializerRegistry};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Blob {
+    data: Vec<u8>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BlobRef<'a> {
+    data: &'a [u8],
+}
+
+fn lazy_blob() -> ArcValue {
+    let mut registry = SerializerRegistry::with_defaults(Arc::new(Logger::new_root(
+        Component::Custom("Bench"),
+        "bench-node",
+    )));
+    registry.register::<Blob>().unwrap();
+    let blob = Blob {
+        data: vec![42u8; 1024 * 1024],
+    };
+    let bytes = registry
+        .serialize_value(&ArcValue::from_struct(blob))
+        .unwrap();
+    registry.deserialize_value(bytes).unwrap()
+}
+
+fn bench_decode(c: &mut Criterion) {
+    let value = lazy_blob();
+    let mut group = c.benchmark_group("decode_1mb_vec_u8");
+
+    group.bench_function("as_type", |b| {
+        b.iter(|| {
+            // Work on a clone so every iteration decodes the lazy bytes again
+            let mut lazy = value.clone();
+            black_box(lazy.as_type::<Blob>().unwrap().data.len())
+        })
+    });
+    group.bench_function("as_type_borrowed", |b| {
+        b.iter(|| black_box(value.as_type_borrowed::<BlobRef>().unwrap().data.len()))
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_decode);
+criterion_main!(benches);
@@ -0,0 +1,68 @@
+// Compare registry reads under contention from 16 threads
+//
+// Callers share a SerializerRegistry behind a tokio RwLock and take the read
+// lock on every message; a sealed ConcurrentSerializerRegistry takes none.
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use runar_common::logging::{Component, Logger};
+use runar_common::types::{ArcValue, ConcurrentSerializerRegistry, SerializerRegistry};
+use tokio::sync::RwLock;
+
+const READER_THREADS: usize = 16;
+
+fn logger() -> Arc<Logger> {
+    Arc::new(Logger::new_root(Component::Custom("Bench"), "bench-node"))
+}
+
+/// Run `op` `iters` times on each reader thread, returning the wall time
+fn contended(iters: u64, op: impl Fn() + Sync) -> Duration {
+    let start = Instant::now();
+    std::thread::scope(|scope| {
+        for _ in 0..READER_THREADS {
+            scope.spawn(|| {
+                for _ in 0..iters {
+                    op();
+                }
+            });
+        }
+    });
+    start.elapsed()
+}
+
+fn bench_contention(c: &mut Criterion) {
+    let mut registry = SerializerRegistry::with_defaults(logger());
+    let bytes = registry
+        .serialize_value(&ArcValue::new_list(vec![1i64, 2, 3]))
+        .unwrap();
+    registry.seal();
+    let locked = RwLock::new(registry);
+
+    let concurrent = ConcurrentSerializerRegistry::with_defaults(logger());
+    concurrent.seal();
+
+    let mut group = c.benchmark_group("deserialize_value_16_threads");
+
+    group.bench_function("tokio_rwlock", |b| {
+        b.iter_custom(|iters| {
+            contended(iters, || {
+                let registry = locked.blocking_read();
+                black_box(registry.deserialize_value(bytes.clone()).unwrap());
+            })
+        })
+    });
+    group.bench_function("concurrent_sealed", |b| {
+        b.iter_custom(|iters| {
+            contended(iters, || {
+                black_box(concurrent.deserialize_value(bytes.clone()).unwrap());
+            })
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_contention);
+criterion_main!(benches);
@@ -5,6 +5,7 @@ pub use anyhow::{anyhow, Result};
 pub use thiserror::Error;
 
 // Export common error utilities
+#[cfg(feature = "type-erasure")]
 pub mod utils {
     use crate::types::ArcValue;
 
@@ -3,7 +3,12 @@
 // Common traits and utilities for the Runar P2P stack
 
 // Export modules
+//
+// The `logging`, `serializer` and `type-erasure` features (all enabled by
+// default) gate the modules with heavy dependencies, so crates that only need
+// part of this crate can build without the rest.
 pub mod errors;
+#[cfg(feature = "logging")]
 pub mod logging;
 pub mod macros;
 pub mod service_info;
@@ -11,7 +16,8 @@ pub mod types;
 pub mod utils;
 
 // Re-export traits and types at the root level
-pub use logging::{Component, Logger, LoggingContext};
+#[cfg(feature = "logging")]
+pub use logging::{Component, LogFilter, Logger, LoggingContext};
 pub use service_info::ServiceInfo;
 
 // Note: The logging macros have been removed in favor of direct logger usage.
@@ -6,8 +6,13 @@
 // - Context-aware logging for services
 // - Node ID tracking through logger inheritance
 // - Support for action and event path tracing
+// - Sampling of high-frequency messages
 
 use log::{debug, error, info, warn};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 // Include macros submodule
 pub mod macros;
@@ -45,6 +50,203 @@ impl Component {
     }
 }
 
+/// Default time between two summaries of the messages dropped by sampling
+pub const DEFAULT_SAMPLING_SUMMARY_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Per-call sampling of log messages
+///
+/// INTENTION: Keep a representative fraction of a high-frequency message
+/// stream, e.g. debug lines logged for every event, without paying for all of
+/// them. Only debug and trace calls are sampled; info, warnings and errors
+/// are always logged. Each sampled call is kept with probability `rate`,
+/// independently of the others. Dropped calls are counted and reported in a
+/// single summary line logged by the first call after `summary_interval` has
+/// passed, and by the owner of the sampler when it is dropped (see
+/// `flush`), so a final count is not lost when the calls stop.
+#[derive(Debug)]
+pub struct LogSampler {
+    rate: f64,
+    summary_interval: Duration,
+    dropped_log_count: AtomicU64,
+    last_summary: Mutex<Instant>,
+}
+
+impl LogSampler {
+    /// Create a sampler keeping `rate` (clamped to 0.0–1.0) of the calls
+    pub fn new(rate: f64) -> Self {
+        Self::with_summary_interval(rate, DEFAULT_SAMPLING_SUMMARY_INTERVAL)
+    }
+
+    /// Create a sampler summarizing its dropped calls every `summary_interval`
+    pub fn with_summary_interval(rate: f64, summary_interval: Duration) -> Self {
+        Self {
+            rate: if rate.is_nan() {
+                1.0
+            } else {
+                rate.clamp(0.0, 1.0)
+            },
+            summary_interval,
+            dropped_log_count: AtomicU64::new(0),
+            last_summary: Mutex::new(Instant::now()),
+        }
+    }
+
+    /// Fraction of the calls that are kept
+    pub fn rate(&self) -> f64 {
+        self.rate
+    }
+
+    /// Number of calls dropped since the last summary
+    pub fn dropped_log_count(&self) -> u64 {
+        self.dropped_log_count.load(Ordering::Relaxed)
+    }
+
+    /// Whether calls at `level` are sampled at all
+    pub fn samples(level: log::Level) -> bool {
+        level > log::Level::Info
+    }
+
+    /// Take the dropped count whether or not a summary is due
+    pub fn flush(&self) -> Option<u64> {
+        *self.last_summary.lock().unwrap_or_else(|e| e.into_inner()) = Instant::now();
+        match self.dropped_log_count.swap(0, Ordering::Relaxed) {
+            0 => None,
+            dropped => Some(dropped),
+        }
+    }
+
+    /// Decide whether to keep a call, counting it if dropped
+    fn sample(&self) -> bool {
+        let keep = self.rate >= 1.0 || rand::random::<f64>() < self.rate;
+        if !keep {
+            self.dropped_log_count.fetch_add(1, Ordering::Relaxed);
+        }
+        keep
+    }
+
+    /// Take the dropped count if a summary is due
+    fn take_summary(&self) -> Option<u64> {
+        if self.dropped_log_count() == 0 {
+            return None;
+        }
+        let mut last_summary = self.last_summary.lock().unwrap_or_else(|e| e.into_inner());
+        if last_summary.elapsed() < self.summary_interval {
+            return None;
+        }
+        *last_summary = Instant::now();
+        match self.dropped_log_count.swap(0, Ordering::Relaxed) {
+            0 => None,
+            dropped => Some(dropped),
+        }
+    }
+
+    /// Summary line for `dropped` calls
+    fn summary(&self, dropped: u64) -> String {
+        format!(
+            "{dropped} log messages dropped by sampling (rate {})",
+            self.rate
+        )
+    }
+}
+
+impl Drop for LogSampler {
+    /// Report the calls dropped since the last summary, e.g. when the
+    /// `LogFilter` holding the sampler goes away
+    fn drop(&mut self) {
+        if let Some(dropped) = self.flush() {
+            debug!("{}", self.summary(dropped));
+        }
+    }
+}
+
+/// Log level rules evaluated by `Logger` before emitting a message
+///
+/// INTENTION: Let operators quiet one part of the system (e.g. Network) while
+/// keeping another verbose (e.g. Service). The most specific rule wins:
+/// service path, then component, then the default level.
+#[derive(Debug, Clone)]
+pub struct LogFilter {
+    default_level: log::LevelFilter,
+    /// Levels keyed by `Component::as_str()`
+    component_levels: HashMap<String, log::LevelFilter>,
+    /// Levels keyed by service path
+    service_path_levels: HashMap<String, log::LevelFilter>,
+    /// Samplers keyed by `Component::as_str()`
+    component_samplers: HashMap<String, Arc<LogSampler>>,
+}
+
+impl LogFilter {
+    /// Create a filter that applies `default_level` to everything
+    pub fn new(default_level: log::LevelFilter) -> Self {
+        Self {
+            default_level,
+            component_levels: HashMap::new(),
+            service_path_levels: HashMap::new(),
+            component_samplers: HashMap::new(),
+        }
+    }
+
+    /// Set the level for loggers of the named component
+    pub fn with_component_level(
+        mut self,
+        component: impl Into<String>,
+        level: log::LevelFilter,
+    ) -> Self {
+        self.component_levels.insert(component.into(), level);
+        self
+    }
+
+    /// Set the level for requests and events of a service path
+    pub fn with_service_path_level(
+        mut self,
+        path: impl Into<String>,
+        level: log::LevelFilter,
+    ) -> Self {
+        self.service_path_levels.insert(path.into(), level);
+        self
+    }
+
+    /// Keep only `rate` of the messages of loggers of the named component
+    pub fn with_component_sampling_rate(mut self, component: impl Into<String>, rate: f64) -> Self {
+        self.component_samplers
+            .insert(component.into(), Arc::new(LogSampler::new(rate)));
+        self
+    }
+
+    /// The sampler applied to a component, if any
+    pub fn sampler_for(&self, component: Component) -> Option<&Arc<LogSampler>> {
+        self.component_samplers.get(component.as_str())
+    }
+
+    /// The most verbose level any rule allows
+    pub fn max_level(&self) -> log::LevelFilter {
+        self.component_levels
+            .values()
+            .chain(self.service_path_levels.values())
+            .copied()
+            .fold(self.default_level, std::cmp::max)
+    }
+
+    /// Resolve the level for a component and an optional action, event or service path
+    pub fn level_for(&self, component: Component, path: Option<&str>) -> log::LevelFilter {
+        // The longest matching service path is the most specific rule
+        let path_level = path.and_then(|path| {
+            self.service_path_levels
+                .iter()
+                .filter(|(service_path, _)| {
+                    path.strip_prefix(service_path.as_str())
+                        .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+                })
+                .max_by_key(|(service_path, _)| service_path.len())
+                .map(|(_, level)| *level)
+        });
+
+        path_level
+            .or_else(|| self.component_levels.get(component.as_str()).copied())
+            .unwrap_or(self.default_level)
+    }
+}
+
 /// A helper for creating component-specific loggers with node ID tracking
 #[derive(Clone)]
 pub struct Logger {
@@ -58,6 +260,8 @@ pub struct Logger {
     action_path: Option<String>,
     /// Event path for event subscription tracing
     event_path: Option<String>,
+    /// Level rules shared by all loggers derived from the root
+    filter: Option<Arc<LogFilter>>,
 }
 
 impl Logger {
@@ -70,6 +274,17 @@ impl Logger {
             parent_component: None,
             action_path: None,
             event_path: None,
+            filter: None,
+        }
+    }
+
+    /// Create a copy of this logger that applies the given level rules
+    ///
+    /// Loggers derived from the returned one share the same filter.
+    pub fn with_filter(&self, filter: Arc<LogFilter>) -> Self {
+        Self {
+            filter: Some(filter),
+            ..self.clone()
         }
     }
 
@@ -82,6 +297,7 @@ impl Logger {
             parent_component: Some(self.component),
             action_path: self.action_path.clone(),
             event_path: self.event_path.clone(),
+            filter: self.filter.clone(),
         }
     }
 
@@ -94,6 +310,7 @@ impl Logger {
             parent_component: self.parent_component,
             action_path: Some(path.into()),
             event_path: self.event_path.clone(),
+            filter: self.filter.clone(),
         }
     }
 
@@ -106,6 +323,7 @@ impl Logger {
             parent_component: self.parent_component,
             action_path: self.action_path.clone(),
             event_path: Some(path.into()),
+            filter: self.filter.clone(),
         }
     }
 
@@ -130,6 +348,23 @@ impl Logger {
         self.event_path.as_deref()
     }
 
+    /// Check whether a message at `level` would be emitted by this logger
+    ///
+    /// Applies the filter rules (if any) on top of the global `log` level.
+    pub fn should_log(&self, level: log::Level) -> bool {
+        let path = self.action_path.as_deref().or(self.event_path.as_deref());
+        self.is_enabled(level, self.component, path)
+    }
+
+    fn is_enabled(&self, level: log::Level, component: Component, path: Option<&str>) -> bool {
+        if let Some(filter) = &self.filter {
+            if level > filter.level_for(component, path) {
+                return false;
+            }
+        }
+        log::log_enabled!(level)
+    }
+
     /// Get the component prefix for logging, including parent if available
     fn component_prefix(&self) -> String {
         match self.parent_component {
@@ -162,69 +397,159 @@ impl Logger {
 
     /// Log a debug message
     pub fn debug(&self, message: impl Into<String>) {
-        if log::log_enabled!(log::Level::Debug) {
-            // Skip displaying the component if it's Node to avoid redundancy
-            if self.component == Component::Node && self.parent_component.is_none() {
-                debug!("[{}] {}", self.node_id, message.into());
-            } else {
-                debug!(
-                    "[{}][{}] {}",
-                    self.node_id,
-                    self.full_prefix(),
-                    message.into()
-                );
-            }
-        }
+        self.log(log::Level::Debug, message);
     }
 
     /// Log an info message
     pub fn info(&self, message: impl Into<String>) {
-        if log::log_enabled!(log::Level::Info) {
-            // Skip displaying the component if it's Node to avoid redundancy
-            if self.component == Component::Node && self.parent_component.is_none() {
-                info!("[{}] {}", self.node_id, message.into());
-            } else {
-                info!(
-                    "[{}][{}] {}",
-                    self.node_id,
-                    self.full_prefix(),
-                    message.into()
-                );
-            }
-        }
+        self.log(log::Level::Info, message);
     }
 
     /// Log a warning message
     pub fn warn(&self, message: impl Into<String>) {
-        if log::log_enabled!(log::Level::Warn) {
-            // Skip displaying the component if it's Node to avoid redundancy
-            if self.component == Component::Node && self.parent_component.is_none() {
-                warn!("[{}] {}", self.node_id, message.into());
-            } else {
-                warn!(
-                    "[{}][{}] {}",
-                    self.node_id,
-                    self.full_prefix(),
-                    message.into()
-                );
-            }
-        }
+        self.log(log::Level::Warn, message);
     }
 
     /// Log an error message
     pub fn error(&self, message: impl Into<String>) {
-        if log::log_enabled!(log::Level::Error) {
-            // Skip displaying the component if it's Node to avoid redundancy
-            if self.component == Component::Node && self.parent_component.is_none() {
-                error!("[{}] {}", self.node_id, message.into());
-            } else {
-                error!(
-                    "[{}][{}] {}",
-                    self.node_id,
-                    self.full_prefix(),
-                    message.into()
-                );
-            }
+        self.log(log::Level::Error, message);
+    }
+
+    /// Wrap this logger so that only `rate` (0.0–1.0) of its debug calls are logged
+    ///
+    /// Each call is sampled on its own; see `LogSampler`.
+    pub fn sampled(&self, rate: f64) -> SampledLogger {
+        SampledLogger {
+            logger: self.clone(),
+            sampler: Arc::new(LogSampler::new(rate)),
+        }
+    }
+
+    /// Log a message, applying the component's sampler from the filter
+    fn log(&self, level: log::Level, message: impl Into<String>) {
+        if !self.should_log(level) {
+            return;
+        }
+        match self
+            .filter
+            .as_ref()
+            .and_then(|filter| filter.sampler_for(self.component))
+        {
+            Some(sampler) => self.log_sampled(sampler, level, message),
+            None => self.emit(level, &message.into()),
+        }
+    }
+
+    /// Log a message kept by `sampler`, followed by the summary of the dropped ones when due
+    ///
+    /// Messages above debug level bypass the sampler.
+    fn log_sampled(&self, sampler: &LogSampler, level: log::Level, message: impl Into<String>) {
+        if !LogSampler::samples(level) {
+            self.emit(level, &message.into());
+            return;
+        }
+        if sampler.sample() {
+            self.emit(level, &message.into());
+        }
+        // Logged at debug level, which is enabled since this call got here
+        if let Some(dropped) = sampler.take_summary() {
+            self.emit(log::Level::Debug, &sampler.summary(dropped));
+        }
+    }
+
+    /// Emit a message that passed the filter
+    fn emit(&self, level: log::Level, message: &str) {
+        // Skip displaying the component if it's Node to avoid redundancy
+        if self.component == Component::Node && self.parent_component.is_none() {
+            log::log!(level, "[{}] {}", self.node_id, message);
+        } else {
+            log::log!(
+                level,
+                "[{}][{}] {}",
+                self.node_id,
+                self.full_prefix(),
+                message
+            );
+        }
+    }
+}
+
+/// A `Logger` that only logs a sampled fraction of its debug calls
+///
+/// INTENTION: Let code on hot paths keep its debug lines without logging one
+/// per event. Created with `Logger::sampled`; clones share the sampler and its
+/// count of dropped calls, which the last clone reports when dropped.
+#[derive(Clone)]
+pub struct SampledLogger {
+    logger: Logger,
+    sampler: Arc<LogSampler>,
+}
+
+impl SampledLogger {
+    /// Report the dropped calls every `interval` instead of the default 10 seconds
+    pub fn with_summary_interval(self, interval: Duration) -> Self {
+        Self {
+            sampler: Arc::new(LogSampler::with_summary_interval(
+                self.sampler.rate(),
+                interval,
+            )),
+            logger: self.logger.clone(),
+        }
+    }
+
+    /// Fraction of the calls that are logged
+    pub fn rate(&self) -> f64 {
+        self.sampler.rate()
+    }
+
+    /// Number of calls dropped since the last summary
+    pub fn dropped_log_count(&self) -> u64 {
+        self.sampler.dropped_log_count()
+    }
+
+    /// The wrapped logger
+    pub fn logger(&self) -> &Logger {
+        &self.logger
+    }
+
+    /// Log a debug message if sampled
+    pub fn debug(&self, message: impl Into<String>) {
+        self.log(log::Level::Debug, message);
+    }
+
+    /// Log an info message; info messages are not sampled
+    pub fn info(&self, message: impl Into<String>) {
+        self.log(log::Level::Info, message);
+    }
+
+    /// Log a warning message; warnings are not sampled
+    pub fn warn(&self, message: impl Into<String>) {
+        self.log(log::Level::Warn, message);
+    }
+
+    /// Log an error message; errors are not sampled
+    pub fn error(&self, message: impl Into<String>) {
+        self.log(log::Level::Error, message);
+    }
+
+    fn log(&self, level: log::Level, message: impl Into<String>) {
+        // Calls filtered out by level are not counted as dropped
+        if self.logger.should_log(level) {
+            self.logger.log_sampled(&self.sampler, level, message);
+        }
+    }
+}
+
+impl Drop for SampledLogger {
+    /// Report the calls dropped since the last summary when the last clone goes
+    fn drop(&mut self) {
+        if Arc::strong_count(&self.sampler) > 1 {
+            return;
+        }
+        if let Some(dropped) = self.sampler.flush() {
+            // Only debug calls are dropped, so debug level is enabled
+            self.logger
+                .emit(log::Level::Debug, &self.sampler.summary(dropped));
         }
     }
 }
@@ -250,9 +575,18 @@ pub trait LoggingContext {
     /// Get the logger
     fn logger(&self) -> &Logger;
 
+    /// Check whether a message at `level` would be emitted for this context
+    fn should_log(&self, level: log::Level) -> bool {
+        let path = self
+            .action_path()
+            .or(self.event_path())
+            .or(self.service_path());
+        self.logger().is_enabled(level, self.component(), path)
+    }
+
     /// Log at debug level
     fn log_debug(&self, message: String) {
-        if log::log_enabled!(log::Level::Debug) {
+        if self.should_log(log::Level::Debug) {
             let prefix = self.log_prefix();
             let logger = self.logger();
 
@@ -267,7 +601,7 @@ pub trait LoggingContext {
 
     /// Log at info level
     fn log_info(&self, message: String) {
-        if log::log_enabled!(log::Level::Info) {
+        if self.should_log(log::Level::Info) {
             let prefix = self.log_prefix();
             let logger = self.logger();
 
@@ -282,7 +616,7 @@ pub trait LoggingContext {
 
     /// Log at warning level
     fn log_warn(&self, message: String) {
-        if log::log_enabled!(log::Level::Warn) {
+        if self.should_log(log::Level::Warn) {
             let prefix = self.log_prefix();
             let logger = self.logger();
 
@@ -297,7 +631,7 @@ pub trait LoggingContext {
 
     /// Log at error level
     fn log_error(&self, message: String) {
-        if log::log_enabled!(log::Level::Error) {
+        if self.should_log(log::Level::Error) {
             let prefix = self.log_prefix();
             let logger = self.logger();
 
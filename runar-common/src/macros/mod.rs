@@ -105,3 +105,56 @@ macro_rules! params {
         }
     };
 }
+
+/// Register several types with a `SerializerRegistry` in one call
+///
+/// Expands to a `SerializerRegistry::register_batch` call. Plain types are
+/// registered with `register::<T>()` and `HashMap<K, V>` types with
+/// `register_map::<K, V>()`. Registration stops at the first error; types
+/// registered before the failure stay registered.
+///
+/// # Examples
+/// ```
+/// use runar_common::logging::{Component, Logger};
+/// use runar_common::types::{register_all, SerializerRegistry};
+/// use serde::{Deserialize, Serialize};
+/// use std::collections::HashMap;
+/// use std::sync::Arc;
+///
+/// #[derive(Clone, Serialize, Deserialize)]
+/// struct User {
+///     name: String,
+/// }
+///
+/// let logger = Arc::new(Logger::new_root(Component::Custom("Doc"), "doc-node"));
+/// let mut registry = SerializerRegistry::new(logger);
+/// register_all!(registry, User, HashMap<String, User>).unwrap();
+/// ```
+#[macro_export]
+macro_rules! register_all {
+    ($registry:expr $(,)?) => {
+        $registry.register_batch(&[])
+    };
+    ($registry:expr, $($types:tt)+) => {
+        $crate::register_all!(@collect $registry; []; $($types)+)
+    };
+
+    // Internal rules: accumulate one registration function per type
+    (@collect $registry:expr; [$($registrations:expr,)*]; HashMap<$k:ty, $v:ty> $(, $($rest:tt)*)?) => {
+        $crate::register_all!(@collect $registry; [
+            $($registrations,)*
+            (|registry: &mut $crate::types::SerializerRegistry| registry.register_map::<$k, $v>())
+                as $crate::types::TypeRegistration,
+        ]; $($($rest)*)?)
+    };
+    (@collect $registry:expr; [$($registrations:expr,)*]; $t:ty $(, $($rest:tt)*)?) => {
+        $crate::register_all!(@collect $registry; [
+            $($registrations,)*
+            (|registry: &mut $crate::types::SerializerRegistry| registry.register::<$t>())
+                as $crate::types::TypeRegistration,
+        ]; $($($rest)*)?)
+    };
+    (@collect $registry:expr; [$($registrations:expr,)*];) => {
+        $registry.register_batch(&[$($registrations,)*])
+    };
+}
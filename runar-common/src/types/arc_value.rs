@@ -15,6 +15,8 @@ use std::marker::Copy;
 use std::sync::Arc;
 
 use anyhow::{anyhow, Result};
+use once_cell::sync::OnceCell;
+#[cfg(feature = "serializer")]
 use rustc_hash::FxHashMap;
 use serde::de::{self, MapAccess, SeqAccess, Visitor};
 use serde::ser::SerializeStruct;
@@ -22,6 +24,9 @@ use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use serde_json::Value as JsonValue;
 
 use super::erased_arc::ErasedArc;
+use super::serialization_backend::SerializationBackend;
+use super::type_descriptor::TypeDescriptor;
+#[cfg(feature = "serializer")]
 use crate::logging::Logger;
 use crate::types::AsArcValue; // Added import for the trait
 use base64::engine::general_purpose::STANDARD;
@@ -31,13 +36,60 @@ use base64::Engine as _;
 pub(crate) type DeserializationFn =
     Arc<dyn Fn(&[u8]) -> Result<Box<dyn Any + Send + Sync>> + Send + Sync>;
 // Type alias for the inner part of the complex serialization function signature
+#[cfg(feature = "serializer")]
 pub(crate) type SerializationFnInner = Box<dyn Fn(&dyn Any) -> Result<Vec<u8>> + Send + Sync>;
+// Encodes an error if it (or one of its causes) is of the registered type
+#[cfg(feature = "serializer")]
+type ErrorSerializationFn = Box<dyn Fn(&anyhow::Error) -> Option<Result<Vec<u8>>> + Send + Sync>;
+// Rebuilds a registered error type from its encoded bytes
+#[cfg(feature = "serializer")]
+type ErrorDeserializationFn = Box<dyn Fn(&[u8]) -> Result<anyhow::Error> + Send + Sync>;
+
+// Converts the encoded payload of a registered type to JSON
+#[cfg(feature = "serde_full")]
+type JsonEncodeFn = Arc<dyn Fn(&[u8]) -> Result<serde_json::Value> + Send + Sync>;
+// Encodes the JSON form of a registered type with the registry's backend
+#[cfg(feature = "serde_full")]
+type JsonDecodeFn = Arc<dyn Fn(serde_json::Value) -> Result<Vec<u8>> + Send + Sync>;
+
+/// Converts the encoded payload of a registered type to and from JSON
+#[cfg(feature = "serde_full")]
+#[derive(Clone)]
+pub(crate) struct JsonCodec {
+    pub(crate) to_json: JsonEncodeFn,
+    pub(crate) from_json: JsonDecodeFn,
+}
 
 // Type alias for the JSON serialization function
 // Takes an ErasedArc and attempts to serialize it to serde_json::Value
 pub(crate) type JsonSerializationFn =
     Arc<dyn Fn(&ErasedArc) -> Result<serde_json::Value, anyhow::Error> + Send + Sync>;
 
+/// Raw data of a bytes value, eager or still in its received buffer
+fn erased_bytes(erased_arc: &ErasedArc) -> Result<&[u8]> {
+    if erased_arc.is_lazy {
+        let lazy = erased_arc.lazy_data_ref()?;
+        return Ok(&lazy.original_buffer[lazy.start_offset..lazy.end_offset]);
+    }
+    erased_arc
+        .as_any()?
+        .downcast_ref::<Vec<u8>>()
+        .map(Vec::as_slice)
+        .ok_or_else(|| {
+            anyhow!(
+                "Bytes value doesn't contain Vec<u8> (actual: {})",
+                erased_arc.type_name()
+            )
+        })
+}
+
+/// JSON form of a bytes value: its base64 encoding
+fn bytes_to_json(erased_arc: &ErasedArc) -> Result<serde_json::Value> {
+    Ok(serde_json::Value::String(
+        STANDARD.encode(erased_bytes(erased_arc)?),
+    ))
+}
+
 /// Wrapper struct for deserializer function that implements Debug
 #[derive(Clone)]
 pub struct DeserializerFnWrapper {
@@ -79,9 +131,39 @@ pub struct LazyDataWithOffset {
     pub end_offset: usize,
     /// Optional deserializer captured from SerializerRegistry (encryption-aware)
     pub deserializer: Option<crate::types::arc_value::DeserializerFnWrapper>,
+    /// Backend the payload bytes were encoded with
+    pub backend: SerializationBackend,
+    /// First decoded value, shared by every owner of this lazy data
+    pub eager_cache: OnceCell<Arc<dyn Any + Send + Sync>>,
     // NOTE: We no longer store the deserializer function here, as we use direct bincode
 }
 
+impl LazyDataWithOffset {
+    /// Decode the payload as `T`, reusing an earlier decode of the same type
+    ///
+    /// INTENTION: Clones of a lazy ArcValue share this data, so in high fan-out
+    /// subscriptions only the first owner pays for deserialization. The cache
+    /// holds the first type decoded; a request for any other type decodes
+    /// the bytes again and leaves the cache untouched.
+    pub fn decode_cached<T>(&self, decode: impl FnOnce(&[u8]) -> Result<T>) -> Result<Arc<T>>
+    where
+        T: 'static + Send + Sync,
+    {
+        let data_slice = &self.original_buffer[self.start_offset..self.end_offset];
+        if let Some(cached) = self.eager_cache.get() {
+            return match cached.clone().downcast::<T>() {
+                Ok(value) => Ok(value),
+                Err(_) => decode(data_slice).map(Arc::new),
+            };
+        }
+
+        let value = Arc::new(decode(data_slice)?);
+        // Another owner may have decoded concurrently; either result is equivalent
+        let _ = self.eager_cache.set(value.clone());
+        Ok(value)
+    }
+}
+
 impl fmt::Debug for LazyDataWithOffset {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("LazyDataWithOffset")
@@ -90,6 +172,8 @@ impl fmt::Debug for LazyDataWithOffset {
             .field("data_segment_len", &(self.end_offset - self.start_offset))
             .field("start_offset", &self.start_offset)
             .field("end_offset", &self.end_offset)
+            .field("backend", &self.backend)
+            .field("cached", &self.eager_cache.get().is_some())
             .finish()
     }
 }
@@ -107,50 +191,100 @@ pub enum ValueCategory {
     Json,
 }
 
+/// Formats the encoded payload of a registered type with its `Debug` impl
+#[cfg(feature = "serializer")]
+pub(crate) type DebugFormatFn = Arc<dyn Fn(&[u8]) -> Result<String> + Send + Sync>;
+
+/// A single type registration, as accepted by `SerializerRegistry::register_batch`
+#[cfg(feature = "serializer")]
+pub type TypeRegistration = fn(&mut SerializerRegistry) -> Result<()>;
+
+/// Set on the category marker of frames naming their type by ID
+#[cfg(feature = "serializer")]
+const COMPACT_FRAME_FLAG: u8 = 0x80;
+
 /// Registry for type-specific serialization and deserialization handlers
+#[cfg(feature = "serializer")]
 pub struct SerializerRegistry {
     serializers: FxHashMap<String, SerializationFnInner>,
     deserializers: FxHashMap<String, DeserializerFnWrapper>,
+    /// Error types that can travel in error responses, in registration order
+    error_serializers: Vec<(String, ErrorSerializationFn)>,
+    error_deserializers: FxHashMap<String, ErrorDeserializationFn>,
+    /// JSON conversion of the payload of registered types
+    #[cfg(feature = "serde_full")]
+    json_codecs: FxHashMap<String, JsonCodec>,
+    /// `Debug` formatting of the payload of types registered with `register_debug`
+    debug_formatters: FxHashMap<String, DebugFormatFn>,
     is_sealed: bool,
+    /// ID of each serializable type in compact frames, assigned by `seal`
+    type_ids: FxHashMap<String, u16>,
+    /// Type names by ID, sorted alphabetically
+    type_names: Vec<String>,
+    /// Encoding used for the payload of registered types
+    backend: SerializationBackend,
     /// Logger for SerializerRegistry operations
     logger: Arc<Logger>,
 }
 
+#[cfg(feature = "serializer")]
 impl SerializerRegistry {
     /// Create a new registry with default logger
     pub fn new(logger: Arc<Logger>) -> Self {
         SerializerRegistry {
             serializers: FxHashMap::default(),
             deserializers: FxHashMap::default(),
+            error_serializers: Vec::new(),
+            error_deserializers: FxHashMap::default(),
+            #[cfg(feature = "serde_full")]
+            json_codecs: FxHashMap::default(),
+            debug_formatters: FxHashMap::default(),
             is_sealed: false,
+            type_ids: FxHashMap::default(),
+            type_names: Vec::new(),
+            backend: SerializationBackend::default(),
             logger,
         }
     }
 
     /// Initialize with default types
     pub fn with_defaults(logger: Arc<Logger>) -> Self {
+        Self::with_backend(logger, SerializationBackend::default())
+    }
+
+    /// Initialize with default types, encoding payloads with `backend`
+    ///
+    /// The backend is fixed for the lifetime of the registry: every type
+    /// registered on it, and every value it deserializes, uses that encoding.
+    pub fn with_backend(logger: Arc<Logger>, backend: SerializationBackend) -> Self {
         let mut registry = Self::new(logger);
+        registry.backend = backend;
         registry.register_defaults();
         registry
     }
 
+    /// Get the backend used to encode payloads
+    pub fn backend(&self) -> SerializationBackend {
+        self.backend
+    }
+
     /// Register default type handlers
     fn register_defaults(&mut self) {
         // Register primitive types
-        self.register::<i32>().unwrap();
-        self.register::<i64>().unwrap();
-        self.register::<f32>().unwrap();
-        self.register::<f64>().unwrap();
-        self.register::<bool>().unwrap();
-        self.register::<String>().unwrap();
+        self.register_debug::<i32>().unwrap();
+        self.register_debug::<i64>().unwrap();
+        self.register_debug::<f32>().unwrap();
+        self.register_debug::<f64>().unwrap();
+        self.register_debug::<bool>().unwrap();
+        self.register_debug::<String>().unwrap();
 
         // Register common container types
-        self.register::<Vec<i32>>().unwrap();
-        self.register::<Vec<i64>>().unwrap();
-        self.register::<Vec<f32>>().unwrap();
-        self.register::<Vec<f64>>().unwrap();
-        self.register::<Vec<bool>>().unwrap();
-        self.register::<Vec<String>>().unwrap();
+        self.register_debug::<Vec<i32>>().unwrap();
+        self.register_debug::<Vec<i64>>().unwrap();
+        self.register_debug::<Vec<f32>>().unwrap();
+        self.register_debug::<Vec<f64>>().unwrap();
+        self.register_debug::<Vec<bool>>().unwrap();
+        self.register_debug::<Vec<String>>().unwrap();
 
         // Register common map types
         self.register_map::<String, String>().unwrap();
@@ -158,12 +292,33 @@ impl SerializerRegistry {
         self.register_map::<String, i64>().unwrap();
         self.register_map::<String, f64>().unwrap();
         self.register_map::<String, bool>().unwrap();
+        self.register_debug_formatter::<HashMap<String, String>>();
+        self.register_debug_formatter::<HashMap<String, i32>>();
+        self.register_debug_formatter::<HashMap<String, i64>>();
+        self.register_debug_formatter::<HashMap<String, f64>>();
+        self.register_debug_formatter::<HashMap<String, bool>>();
 
-        self.register::<HashMap<String, ArcValue>>().unwrap();
+        self.register_debug::<HashMap<String, ArcValue>>().unwrap();
     }
 
     /// Seal the registry to prevent further modifications
+    ///
+    /// Sealing also numbers the serializable types in alphabetical order of
+    /// their names, so registries with the same types agree on the IDs used
+    /// by compact frames, whatever order the types were registered in.
     pub fn seal(&mut self) {
+        if self.is_sealed {
+            return;
+        }
+        let mut type_names: Vec<String> = self.serializers.keys().cloned().collect();
+        type_names.sort();
+        type_names.truncate(u16::MAX as usize + 1);
+        self.type_ids = type_names
+            .iter()
+            .enumerate()
+            .map(|(id, type_name)| (type_name.clone(), id as u16))
+            .collect();
+        self.type_names = type_names;
         self.is_sealed = true;
     }
 
@@ -172,6 +327,25 @@ impl SerializerRegistry {
         self.is_sealed
     }
 
+    /// Fingerprint of the type IDs assigned by `seal`, `None` while open
+    ///
+    /// Two sealed registries with the same fingerprint read each other's
+    /// compact frames. The hash (FNV-1a over the sorted type names) is stable
+    /// across processes and platforms.
+    pub fn type_table_fingerprint(&self) -> Option<u64> {
+        if !self.is_sealed {
+            return None;
+        }
+        let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+        for type_name in &self.type_names {
+            for byte in type_name.bytes().chain(std::iter::once(0)) {
+                hash ^= byte as u64;
+                hash = hash.wrapping_mul(0x0100_0000_01b3);
+            }
+        }
+        Some(hash)
+    }
+
     /// Register a type for serialization/deserialization
     pub fn register<T: 'static + Serialize + for<'de> Deserialize<'de> + Clone + Send + Sync>(
         &mut self,
@@ -191,11 +365,13 @@ impl SerializerRegistry {
         };
 
         // Register serializer using the full type name
+        let backend = self.backend;
         self.serializers.insert(
             type_name.to_string(),
-            Box::new(|value: &dyn Any| -> Result<Vec<u8>> {
+            Box::new(move |value: &dyn Any| -> Result<Vec<u8>> {
                 if let Some(typed_value) = value.downcast_ref::<T>() {
-                    bincode::serialize(typed_value)
+                    backend
+                        .encode(typed_value)
                         .map_err(|e| anyhow!("Serialization error: {}", e))
                 } else {
                     Err(anyhow!("Type mismatch during serialization"))
@@ -205,11 +381,14 @@ impl SerializerRegistry {
 
         // Create a deserializer function using DeserializerFnWrapper
         let deserializer =
-            DeserializerFnWrapper::new(|bytes: &[u8]| -> Result<Box<dyn Any + Send + Sync>> {
-                let value: T = bincode::deserialize(bytes)?;
+            DeserializerFnWrapper::new(move |bytes: &[u8]| -> Result<Box<dyn Any + Send + Sync>> {
+                let value: T = backend.decode(bytes)?;
                 Ok(Box::new(value))
             });
 
+        #[cfg(feature = "serde_full")]
+        self.register_json_codec::<T>(type_name, &simple_name);
+
         // Register deserializer using both full and simple type names
         self.deserializers
             .insert(type_name.to_string(), deserializer.clone());
@@ -222,6 +401,44 @@ impl SerializerRegistry {
         Ok(())
     }
 
+    /// Register a type along with its `Debug` formatting
+    ///
+    /// INTENTION: Let `ArcValue::to_display_string` show the content of lazy
+    /// values of the type, which otherwise only show their type and size.
+    pub fn register_debug<T>(&mut self) -> Result<()>
+    where
+        T: 'static + Serialize + for<'de> Deserialize<'de> + Clone + Send + Sync + Debug,
+    {
+        self.register::<T>()?;
+        self.register_debug_formatter::<T>();
+        Ok(())
+    }
+
+    /// Register the `Debug` formatting of a type under its full and simple names
+    fn register_debug_formatter<T>(&mut self)
+    where
+        T: 'static + for<'de> Deserialize<'de> + Debug,
+    {
+        let type_name = std::any::type_name::<T>();
+        let simple_name = type_name.split("::").last().unwrap_or(type_name);
+        let backend = self.backend;
+        let formatter: DebugFormatFn = Arc::new(move |bytes: &[u8]| {
+            let value: T = backend.decode(bytes)?;
+            Ok(format!("{value:?}"))
+        });
+        if simple_name != type_name && !self.debug_formatters.contains_key(simple_name) {
+            self.debug_formatters
+                .insert(simple_name.to_string(), formatter.clone());
+        }
+        self.debug_formatters
+            .insert(type_name.to_string(), formatter);
+    }
+
+    /// Get the `Debug` formatting of a type registered with `register_debug`
+    pub(crate) fn debug_formatter(&self, type_name: &str) -> Option<&DebugFormatFn> {
+        self.debug_formatters.get(type_name)
+    }
+
     /// Register a map type for serialization/deserialization
     pub fn register_map<K, V>(&mut self) -> Result<()>
     where
@@ -250,11 +467,14 @@ impl SerializerRegistry {
         };
 
         // Register serializer using the full type name
+        let backend = self.backend;
         self.serializers.insert(
             type_name.to_string(),
-            Box::new(|value: &dyn Any| -> Result<Vec<u8>> {
+            Box::new(move |value: &dyn Any| -> Result<Vec<u8>> {
                 if let Some(map) = value.downcast_ref::<HashMap<K, V>>() {
-                    bincode::serialize(map).map_err(|e| anyhow!("Map serialization error: {}", e))
+                    backend
+                        .encode(map)
+                        .map_err(|e| anyhow!("Map serialization error: {}", e))
                 } else {
                     Err(anyhow!("Type mismatch during map serialization"))
                 }
@@ -263,11 +483,14 @@ impl SerializerRegistry {
 
         // Create a deserializer function using DeserializerFnWrapper
         let deserializer =
-            DeserializerFnWrapper::new(|bytes: &[u8]| -> Result<Box<dyn Any + Send + Sync>> {
-                let map: HashMap<K, V> = bincode::deserialize(bytes)?;
+            DeserializerFnWrapper::new(move |bytes: &[u8]| -> Result<Box<dyn Any + Send + Sync>> {
+                let map: HashMap<K, V> = backend.decode(bytes)?;
                 Ok(Box::new(map))
             });
 
+        #[cfg(feature = "serde_full")]
+        self.register_json_codec::<HashMap<K, V>>(type_name, &simple_name);
+
         // Register deserializer using both full and simple type names
         self.deserializers
             .insert(type_name.to_string(), deserializer.clone());
@@ -280,6 +503,159 @@ impl SerializerRegistry {
         Ok(())
     }
 
+    /// Register the JSON conversion of a type under its full and simple names
+    #[cfg(feature = "serde_full")]
+    fn register_json_codec<T>(&mut self, type_name: &str, simple_name: &str)
+    where
+        T: 'static + Serialize + for<'de> Deserialize<'de>,
+    {
+        let backend = self.backend;
+        let codec = JsonCodec {
+            to_json: Arc::new(move |bytes: &[u8]| {
+                let value: T = backend.decode(bytes)?;
+                serde_json::to_value(&value).map_err(|e| anyhow!("JSON conversion error: {}", e))
+            }),
+            from_json: Arc::new(move |json: serde_json::Value| {
+                let value: T = serde_json::from_value(json)
+                    .map_err(|e| anyhow!("JSON conversion error: {}", e))?;
+                backend
+                    .encode(&value)
+                    .map_err(|e| anyhow!("Serialization error: {}", e))
+            }),
+        };
+        if simple_name != type_name && !self.json_codecs.contains_key(simple_name) {
+            self.json_codecs
+                .insert(simple_name.to_string(), codec.clone());
+        }
+        self.json_codecs.insert(type_name.to_string(), codec);
+    }
+
+    /// Get the JSON conversion of a registered type
+    #[cfg(feature = "serde_full")]
+    pub(crate) fn json_codec(&self, type_name: &str) -> Option<&JsonCodec> {
+        self.json_codecs.get(type_name)
+    }
+
+    /// Register an `Option<T>` type for serialization/deserialization
+    ///
+    /// INTENTION: Allow optional values (e.g. `Option<String>` state fields or
+    /// action results) to travel as struct payloads. `None` is encoded as a
+    /// single `0` byte and `Some(v)` as `1` followed by the bincode encoding of
+    /// `v`, which is bincode's own Option layout, so lazily deserialized values
+    /// decode the same way as eagerly deserialized ones.
+    pub fn register_option<T>(&mut self) -> Result<()>
+    where
+        T: 'static + Serialize + for<'de> Deserialize<'de> + Clone + Send + Sync,
+    {
+        self.register::<Option<T>>()
+    }
+
+    /// Register a `Result<T, E>` type for serialization/deserialization
+    ///
+    /// INTENTION: Allow action return types that carry their own error value
+    /// to be sent over the wire. The value is encoded with bincode's enum
+    /// layout: a little-endian `u32` variant index (`0` for `Ok`, `1` for
+    /// `Err`) followed by the encoded inner value.
+    pub fn register_result<T, E>(&mut self) -> Result<()>
+    where
+        T: 'static + Serialize + for<'de> Deserialize<'de> + Clone + Send + Sync,
+        E: 'static + Serialize + for<'de> Deserialize<'de> + Clone + Send + Sync,
+    {
+        self.register::<std::result::Result<T, E>>()
+    }
+
+    /// Register an error type that can be sent in error responses
+    ///
+    /// INTENTION: Keep the type of errors returned by remote actions. When an
+    /// action fails with an error of type `E` (anywhere in its cause chain),
+    /// the error is encoded into the error response; a node that registered
+    /// the same type rebuilds it, so callers can `downcast_ref::<E>()` the
+    /// error they receive. Errors of unregistered types travel as a message.
+    pub fn register_error<E>(&mut self) -> Result<()>
+    where
+        E: std::error::Error + Serialize + for<'de> Deserialize<'de> + Send + Sync + 'static,
+    {
+        if self.is_sealed {
+            return Err(anyhow!(
+                "Cannot register new types after registry is sealed"
+            ));
+        }
+
+        let type_name = std::any::type_name::<E>().to_string();
+        let backend = self.backend;
+        self.error_serializers
+            .retain(|(registered, _)| *registered != type_name);
+        self.error_serializers.push((
+            type_name.clone(),
+            Box::new(move |error: &anyhow::Error| {
+                let typed = error.chain().find_map(|cause| cause.downcast_ref::<E>())?;
+                Some(
+                    backend
+                        .encode(typed)
+                        .map_err(|e| anyhow!("Error serialization error: {}", e)),
+                )
+            }),
+        ));
+        self.error_deserializers.insert(
+            type_name,
+            Box::new(move |bytes: &[u8]| {
+                let error: E = backend.decode(bytes)?;
+                Ok(anyhow::Error::new(error))
+            }),
+        );
+        Ok(())
+    }
+
+    /// Encode an error of a registered error type
+    ///
+    /// Returns the type name and bytes of the first registered type found in
+    /// the error's cause chain, or `None` if there is none.
+    pub fn serialize_error(&self, error: &anyhow::Error) -> Option<(String, Vec<u8>)> {
+        self.error_serializers
+            .iter()
+            .find_map(|(type_name, serializer)| match serializer(error)? {
+                Ok(bytes) => Some((type_name.clone(), bytes)),
+                Err(e) => {
+                    self.logger.warn(format!(
+                        "Failed to serialize error of type {type_name}: {e}"
+                    ));
+                    None
+                }
+            })
+    }
+
+    /// Rebuild an error encoded by `serialize_error`
+    ///
+    /// Returns `None` if the type is not registered or the bytes do not decode.
+    pub fn deserialize_error(&self, type_name: &str, bytes: &[u8]) -> Option<anyhow::Error> {
+        let deserializer = self.error_deserializers.get(type_name)?;
+        match deserializer(bytes) {
+            Ok(error) => Some(error),
+            Err(e) => {
+                self.logger.warn(format!(
+                    "Failed to deserialize error of type {type_name}: {e}"
+                ));
+                None
+            }
+        }
+    }
+
+    /// Register several types in one call
+    ///
+    /// INTENTION: Remove the one-call-per-type boilerplate from service `init`
+    /// methods with large domain models. Registrations run in order and the
+    /// batch stops at the first error, which is returned.
+    ///
+    /// The batch is not transactional: registrations that succeeded before the
+    /// failing one are kept, exactly as if they had been registered one by one.
+    /// See the `register_all!` macro for a concise way to build the batch.
+    pub fn register_batch(&mut self, registrations: &[TypeRegistration]) -> Result<()> {
+        for registration in registrations {
+            registration(self)?;
+        }
+        Ok(())
+    }
+
     /// Register a custom deserializer with a specific type name
     pub fn register_custom_deserializer(
         &mut self,
@@ -315,6 +691,73 @@ impl SerializerRegistry {
         Ok(())
     }
 
+    /// Move all handlers from `other` into this registry
+    ///
+    /// INTENTION: Let modules build their own registry and compose them into
+    /// the node-level one. Type names already registered here keep their
+    /// existing handlers. Fails if this registry is sealed or the registries
+    /// encode payloads with different backends.
+    pub fn merge(&mut self, other: SerializerRegistry) -> Result<()> {
+        if self.is_sealed {
+            return Err(anyhow!("Cannot merge into a sealed registry"));
+        }
+        if self.backend != other.backend {
+            return Err(anyhow!(
+                "Cannot merge a {:?} registry into a {:?} registry",
+                other.backend,
+                self.backend
+            ));
+        }
+
+        let mut added = 0;
+        for (type_name, serializer) in other.serializers {
+            if let std::collections::hash_map::Entry::Vacant(entry) =
+                self.serializers.entry(type_name)
+            {
+                entry.insert(serializer);
+                added += 1;
+            }
+        }
+        for (type_name, deserializer) in other.deserializers {
+            self.deserializers.entry(type_name).or_insert(deserializer);
+        }
+        for (type_name, formatter) in other.debug_formatters {
+            self.debug_formatters.entry(type_name).or_insert(formatter);
+        }
+        for (type_name, serializer) in other.error_serializers {
+            if !self.error_deserializers.contains_key(&type_name) {
+                self.error_serializers.push((type_name, serializer));
+            }
+        }
+        for (type_name, deserializer) in other.error_deserializers {
+            self.error_deserializers
+                .entry(type_name)
+                .or_insert(deserializer);
+        }
+
+        self.logger
+            .debug(format!("Merged registry: {added} serializers added"));
+        Ok(())
+    }
+
+    /// Type names registered here but not in `other`, sorted
+    ///
+    /// Useful for diagnosing schema drift between node versions.
+    pub fn difference(&self, other: &SerializerRegistry) -> Vec<String> {
+        let mut names: Vec<String> = self
+            .serializers
+            .keys()
+            .chain(self.deserializers.keys())
+            .filter(|name| {
+                !other.serializers.contains_key(*name) && !other.deserializers.contains_key(*name)
+            })
+            .cloned()
+            .collect();
+        names.sort();
+        names.dedup();
+        names
+    }
+
     /// Serialize a value using the appropriate registered handler
     pub fn serialize(&self, value: &dyn Any, type_name: &str) -> Result<Vec<u8>> {
         if let Some(serializer) = self.serializers.get(type_name) {
@@ -326,7 +769,7 @@ impl SerializerRegistry {
     }
 
     /// Helper to extract the header from serialized bytes (slice view)
-    fn extract_header_from_slice<'a>(
+    pub(crate) fn extract_header_from_slice<'a>(
         &self,
         bytes: &'a [u8],
     ) -> Result<(ValueCategory, String, &'a [u8])> {
@@ -334,8 +777,9 @@ impl SerializerRegistry {
             return Err(anyhow!("Empty byte array"));
         }
 
-        // First byte is the category marker
-        let category = match bytes[0] {
+        // First byte is the category marker, flagged in compact frames
+        let compact = bytes[0] & COMPACT_FRAME_FLAG != 0;
+        let category = match bytes[0] & !COMPACT_FRAME_FLAG {
             0x01 => ValueCategory::Primitive,
             0x02 => ValueCategory::List,
             0x03 => ValueCategory::Map,
@@ -346,11 +790,24 @@ impl SerializerRegistry {
             _ => return Err(anyhow!("Invalid category marker: {}", bytes[0])),
         };
 
-        // For null, no type name is needed
-        if category == ValueCategory::Null {
+        // For null, the type name is only present for typed nulls
+        if category == ValueCategory::Null && bytes.len() < 2 {
             return Ok((category, String::new(), &[]));
         }
 
+        // Compact frames carry the ID assigned to the type by `seal`
+        if compact {
+            if bytes.len() < 3 {
+                return Err(anyhow!("Byte array too short for compact header"));
+            }
+            let type_id = u16::from_be_bytes([bytes[1], bytes[2]]);
+            let type_name = self
+                .type_names
+                .get(type_id as usize)
+                .ok_or_else(|| anyhow!("Unknown type ID in compact frame: {type_id}"))?;
+            return Ok((category, type_name.clone(), &bytes[3..]));
+        }
+
         // Extract the type name
         if bytes.len() < 2 {
             return Err(anyhow!("Byte array too short for header"));
@@ -374,6 +831,19 @@ impl SerializerRegistry {
 
     /// Deserialize bytes (owned Arc) to an ArcValue
     pub fn deserialize_value(&self, bytes_arc: Arc<[u8]>) -> Result<ArcValue> {
+        self.deserialize_value_with_backend(bytes_arc, self.backend)
+    }
+
+    /// Deserialize bytes encoded with `backend` instead of the registry's own
+    ///
+    /// INTENTION: Let nodes configured with different backends talk to each
+    /// other. The sender names the backend of each payload, and the value is
+    /// decoded with it whatever the backend of this registry.
+    pub fn deserialize_value_with_backend(
+        &self,
+        bytes_arc: Arc<[u8]>,
+        backend: SerializationBackend,
+    ) -> Result<ArcValue> {
         if bytes_arc.is_empty() {
             return Err(anyhow!("Empty byte array"));
         }
@@ -384,13 +854,34 @@ impl SerializerRegistry {
 
         // For null, just return a null value
         if original_category == ValueCategory::Null {
-            return Ok(ArcValue::null());
+            return Ok(ArcValue::null_with_type_name(type_name));
         }
 
         self.logger.debug(format!(
             "Deserializing value with type: {type_name} (category: {original_category:?})"
         ));
 
+        // Bytes are stored raw on the wire, so they need no registered deserializer
+        // and stay in the received buffer until copied out
+        if original_category == ValueCategory::Bytes {
+            let data_start_offset = (data_slice.as_ptr() as usize) - (bytes_arc.as_ptr() as usize);
+            let lazy_data = LazyDataWithOffset {
+                type_name: type_name.to_string(),
+                original_buffer: bytes_arc.clone(),
+                start_offset: data_start_offset,
+                end_offset: data_start_offset + data_slice.len(),
+                deserializer: None,
+                backend,
+                eager_cache: OnceCell::new(),
+            };
+            return Ok(ArcValue {
+                category: ValueCategory::Bytes,
+                value: Some(ErasedArc::from_value(lazy_data)),
+                json_serializer_fn: Some(Arc::new(bytes_to_json)),
+                null_type: None,
+            });
+        }
+
         // For complex types, store LazyDataWithOffset
         self.logger.debug(format!(
             "Lazy deserialization setup for complex type: {type_name}"
@@ -409,6 +900,8 @@ impl SerializerRegistry {
                 start_offset: data_start_offset,
                 end_offset: data_end_offset,
                 deserializer: None, // Default to None, specific constructors will populate
+                backend,
+                eager_cache: OnceCell::new(),
             };
 
             // Store Arc<LazyDataWithOffset> in value, keeping original category
@@ -417,6 +910,7 @@ impl SerializerRegistry {
                 category: original_category, // Keep original category (Map, Struct, etc.)
                 value: Some(value),
                 json_serializer_fn: None, // Default to None, specific constructors will populate
+                null_type: None,
             })
         } else {
             Err(anyhow!(
@@ -432,14 +926,76 @@ impl SerializerRegistry {
     }
 
     /// Print all registered deserializers for debugging
+    ///
+    /// Types whose values `ArcValue::to_display_string` can show are marked.
     pub fn debug_print_deserializers(&self) {
         for key in self.deserializers.keys() {
-            self.logger.debug(format!("  - {key}"));
+            if self.debug_formatters.contains_key(key) {
+                self.logger.debug(format!("  - {key} (displayable)"));
+            } else {
+                self.logger.debug(format!("  - {key}"));
+            }
         }
     }
 
     /// Serialize a value to bytes, returning an Arc<[u8]>
     pub fn serialize_value(&self, value: &ArcValue) -> Result<Arc<[u8]>> {
+        self.serialize_value_framed(value, false)
+    }
+
+    /// Serialize a value with a compact header, naming its type by ID
+    ///
+    /// INTENTION: Save the type name, up to 255 bytes, that prefixes every
+    /// value in the default format; for small primitives it outweighs the
+    /// data. Only registries sealed with the same types can read the result,
+    /// see `type_table_fingerprint`. Values whose type has no ID, and every
+    /// value while the registry is open, get the default header.
+    pub fn serialize_value_compact(&self, value: &ArcValue) -> Result<Arc<[u8]>> {
+        self.serialize_value_framed(value, true)
+    }
+
+    /// Rewrite a frame from `serialize_value` with a compact header
+    ///
+    /// Returns `None` when the frame cannot be made compact: its type has no
+    /// ID, the registry is open, or the frame already is compact.
+    pub fn compact_frame(&self, bytes: &[u8]) -> Option<Vec<u8>> {
+        if bytes
+            .first()
+            .is_none_or(|marker| marker & COMPACT_FRAME_FLAG != 0)
+        {
+            return None;
+        }
+        let (_, type_name, data) = self.extract_header_from_slice(bytes).ok()?;
+        let type_id = self.type_ids.get(&type_name)?;
+        let mut frame = Vec::with_capacity(3 + data.len());
+        frame.push(bytes[0] | COMPACT_FRAME_FLAG);
+        frame.extend_from_slice(&type_id.to_be_bytes());
+        frame.extend_from_slice(data);
+        Some(frame)
+    }
+
+    /// Append the type of a frame after its category marker
+    ///
+    /// Compact frames name the type by ID when it has one, and flag the
+    /// category marker accordingly.
+    fn write_type_header(&self, frame: &mut Vec<u8>, type_name: &str, compact: bool) -> Result<()> {
+        if compact {
+            if let Some(type_id) = self.type_ids.get(type_name) {
+                frame[0] |= COMPACT_FRAME_FLAG;
+                frame.extend_from_slice(&type_id.to_be_bytes());
+                return Ok(());
+            }
+        }
+        let type_bytes = type_name.as_bytes();
+        if type_bytes.len() > 255 {
+            return Err(anyhow!("Type name too long: {}", type_name));
+        }
+        frame.push(type_bytes.len() as u8);
+        frame.extend_from_slice(type_bytes);
+        Ok(())
+    }
+
+    fn serialize_value_framed(&self, value: &ArcValue, compact: bool) -> Result<Arc<[u8]>> {
         match value.value.as_ref() {
             Some(erased_arc_ref) => {
                 // value.value is Some(erased_arc_ref)
@@ -464,12 +1020,7 @@ impl SerializerRegistry {
                             ValueCategory::Json => 0x07,
                         };
                         result_vec.push(category_byte);
-                        let type_bytes = lazy.type_name.as_bytes();
-                        if type_bytes.len() > 255 {
-                            return Err(anyhow!("Type name too long: {}", lazy.type_name));
-                        }
-                        result_vec.push(type_bytes.len() as u8);
-                        result_vec.extend_from_slice(type_bytes);
+                        self.write_type_header(&mut result_vec, &lazy.type_name, compact)?;
                         result_vec.extend_from_slice(
                             &lazy.original_buffer[lazy.start_offset..lazy.end_offset],
                         );
@@ -505,12 +1056,7 @@ impl SerializerRegistry {
                     }
 
                     let type_name = erased_arc_ref.type_name();
-                    let type_bytes = type_name.as_bytes();
-                    if type_bytes.len() > 255 {
-                        return Err(anyhow!("Type name too long: {}", type_name));
-                    }
-                    result_vec.push(type_bytes.len() as u8);
-                    result_vec.extend_from_slice(type_bytes);
+                    self.write_type_header(&mut result_vec, type_name, compact)?;
 
                     let data_bytes = match value.category {
                         ValueCategory::Primitive
@@ -563,7 +1109,11 @@ impl SerializerRegistry {
                     "Serializing null value (category: {:?}, value is None)",
                     value.category
                 ));
-                let result_vec = vec![0x05]; // Null category marker
+                let mut result_vec = vec![0x05]; // Null category marker
+                                                 // Typed nulls carry their type name like other values
+                if let Some(type_name) = &value.null_type {
+                    self.write_type_header(&mut result_vec, type_name, compact)?;
+                }
                 Ok(Arc::from(result_vec))
             }
         }
@@ -581,6 +1131,8 @@ pub struct ArcValue {
     /// Optional function to directly serialize this value to serde_json::Value.
     /// This is populated for types that implement serde::Serialize.
     json_serializer_fn: Option<JsonSerializationFn>,
+    /// Type name of the absent value of a null created by `null_of_type`
+    null_type: Option<String>,
 }
 
 impl fmt::Debug for ArcValue {
@@ -596,20 +1148,15 @@ impl fmt::Debug for ArcValue {
                     "None"
                 },
             )
+            .field("null_type", &self.null_type)
             .finish()
     }
 }
 
+/// Values are equal when they have the same content (see `ArcValue::total_cmp`)
 impl PartialEq for ArcValue {
     fn eq(&self, other: &Self) -> bool {
-        if self.category != other.category {
-            return false;
-        }
-        match (&self.value, &other.value) {
-            (Some(v1), Some(v2)) => v1.eq_value(v2),
-            (None, None) => true,
-            _ => false,
-        }
+        self.total_cmp(other) == std::cmp::Ordering::Equal
     }
 }
 
@@ -703,6 +1250,7 @@ impl ArcValue {
             category,
             value: Some(value),
             json_serializer_fn: None, // Default to None, specific constructors will populate
+            null_type: None,
         }
     }
 
@@ -735,6 +1283,7 @@ impl ArcValue {
             category: ValueCategory::Primitive,
             value: Some(ErasedArc::new(arc)),
             json_serializer_fn: serializer_fn,
+            null_type: None,
         }
     }
 
@@ -767,31 +1316,29 @@ impl ArcValue {
             category: ValueCategory::Struct,
             value: Some(ErasedArc::new(arc)),
             json_serializer_fn: serializer_fn,
+            null_type: None,
         }
     }
 
     /// Create a new bytes value (Vec<u8>)
     pub fn new_bytes(bytes: Vec<u8>) -> Self {
         let arc_bytes = Arc::new(bytes);
-        let serializer_fn: Option<JsonSerializationFn> = Some(Arc::new(
-            move |erased_arc: &ErasedArc| -> Result<serde_json::Value, anyhow::Error> {
-                let typed_arc = erased_arc.as_arc::<Vec<u8>>().map_err(|e| {
-                    anyhow!(
-                        "Failed to downcast ErasedArc to Arc<Vec<u8>> for JSON serialization: {}",
-                        e
-                    )
-                })?;
-                Ok(serde_json::Value::String(STANDARD.encode(&**typed_arc)))
-            },
-        ));
-
         Self {
             category: ValueCategory::Bytes,
             value: Some(ErasedArc::new(arc_bytes)),
-            json_serializer_fn: serializer_fn,
+            json_serializer_fn: Some(Arc::new(bytes_to_json)),
+            null_type: None,
         }
     }
 
+    /// Create a bytes value from raw data
+    ///
+    /// The bytes are written to the wire as-is, without being encoded by the
+    /// serializer, which suits encrypted payloads and file content.
+    pub fn from_bytes(bytes: Vec<u8>) -> Self {
+        Self::new_bytes(bytes)
+    }
+
     /// Create a new list value
     pub fn new_list<T: 'static + fmt::Debug + Send + Sync>(values: Vec<T>) -> Self {
         let arc = Arc::new(values);
@@ -799,6 +1346,7 @@ impl ArcValue {
             category: ValueCategory::List,
             value: Some(ErasedArc::new(arc)),
             json_serializer_fn: None,
+            null_type: None,
         }
     }
 
@@ -818,6 +1366,7 @@ impl ArcValue {
             category: ValueCategory::Map,
             value: Some(ErasedArc::new(arc)),
             json_serializer_fn: None,
+            null_type: None,
         }
     }
 
@@ -836,6 +1385,7 @@ impl ArcValue {
             category: ValueCategory::Null,
             value: None,
             json_serializer_fn: None,
+            null_type: None,
         }
     }
 
@@ -855,6 +1405,25 @@ impl ArcValue {
             category: ValueCategory::Json,
             value: Some(ErasedArc::new(arc)),
             json_serializer_fn: serializer_fn,
+            null_type: None,
+        }
+    }
+
+    /// Create a null value standing for an absent value of type `T`
+    ///
+    /// INTENTION: Give nullable fields the meaning of `None` of an `Option<T>`,
+    /// so consumers such as schema generators know what the field would hold.
+    /// The value is null in every other respect; the type name is kept when
+    /// the value is serialized.
+    pub fn null_of_type<T: 'static + fmt::Debug + Send + Sync>() -> Self {
+        Self::null_with_type_name(std::any::type_name::<T>().to_string())
+    }
+
+    /// Create a null value with a type name, untyped when the name is empty
+    pub(crate) fn null_with_type_name(type_name: String) -> Self {
+        Self {
+            null_type: (!type_name.is_empty()).then_some(type_name),
+            ..Self::null()
         }
     }
 
@@ -863,6 +1432,81 @@ impl ArcValue {
         self.value.is_none() && self.category == ValueCategory::Null
     }
 
+    /// Check if this value is a null created by `null_of_type::<T>()`
+    pub fn is_null_of_type<T: 'static>(&self) -> bool {
+        self.is_null() && self.null_type_name() == Some(std::any::type_name::<T>())
+    }
+
+    /// Type name of the absent value of a typed null
+    pub fn null_type_name(&self) -> Option<&str> {
+        self.null_type.as_deref()
+    }
+
+    /// Describe the type this value holds
+    ///
+    /// Lazy values are described from the type name sent with the data, so
+    /// describing them does not deserialize anything.
+    pub fn type_descriptor(&self) -> TypeDescriptor {
+        let rust_type_name = match &self.value {
+            None => "()",
+            Some(erased_arc) => match erased_arc.lazy_data_ref() {
+                Ok(lazy_data) => lazy_data.type_name.as_str(),
+                Err(_) => erased_arc.type_name(),
+            },
+        };
+        TypeDescriptor::new(self.category, rust_type_name)
+    }
+
+    /// Get the raw data of a bytes value
+    ///
+    /// A value received from the network is copied out of its buffer the
+    /// first time; use `bytes_slice` to read it without copying.
+    pub fn as_bytes(&mut self) -> Result<Arc<Vec<u8>>> {
+        if self.category != ValueCategory::Bytes {
+            return Err(anyhow!(
+                "Category mismatch: Expected Bytes, found {:?}",
+                self.category
+            ));
+        }
+        match &self.value {
+            Some(erased_arc) if erased_arc.is_lazy => {
+                let bytes = erased_arc
+                    .lazy_data_ref()?
+                    .decode_cached(|data| Ok(data.to_vec()))?;
+                self.value = Some(ErasedArc::new(bytes.clone()));
+                Ok(bytes)
+            }
+            Some(erased_arc) => erased_arc.as_arc::<Vec<u8>>(),
+            None => Err(anyhow!("Bytes value has no data")),
+        }
+    }
+
+    /// Borrow the raw data of a bytes value without copying it
+    ///
+    /// INTENTION: Read-only consumers of file content or encrypted payloads
+    /// should not pay for a copy. A value received from the network borrows
+    /// straight from the buffer it arrived in, for as long as `self` lives.
+    pub fn bytes_slice(&self) -> Result<&[u8]> {
+        if self.category != ValueCategory::Bytes {
+            return Err(anyhow!(
+                "Category mismatch: Expected Bytes, found {:?}",
+                self.category
+            ));
+        }
+        match &self.value {
+            Some(erased_arc) => erased_bytes(erased_arc),
+            None => Err(anyhow!("Bytes value has no data")),
+        }
+    }
+
+    /// Length of the raw data of a bytes value
+    ///
+    /// Reads the length of the data segment of a received value without
+    /// materializing it.
+    pub fn bytes_len(&self) -> Result<usize> {
+        self.bytes_slice().map(<[u8]>::len)
+    }
+
     /// Get list as a reference of the specified element type
     pub fn as_list_ref<T>(&mut self) -> Result<Arc<Vec<T>>>
     where
@@ -878,20 +1522,11 @@ impl ArcValue {
         match &mut self.value {
             Some(ref mut actual_value) => {
                 if actual_value.is_lazy {
-                    let type_name_clone: String;
-                    let original_buffer_clone: Arc<[u8]>;
-                    let start_offset_val: usize;
-                    let end_offset_val: usize;
-
-                    {
-                        let lazy_data_arc = actual_value.get_lazy_data().map_err(|e| {
-                            anyhow!("Failed to get lazy data despite is_lazy flag: {}", e)
-                        })?;
-                        type_name_clone = lazy_data_arc.type_name.clone();
-                        original_buffer_clone = lazy_data_arc.original_buffer.clone();
-                        start_offset_val = lazy_data_arc.start_offset;
-                        end_offset_val = lazy_data_arc.end_offset;
-                    }
+                    let lazy_data_arc = actual_value.get_lazy_data().map_err(|e| {
+                        anyhow!("Failed to get lazy data despite is_lazy flag: {}", e)
+                    })?;
+                    let type_name_clone = lazy_data_arc.type_name.clone();
+                    let backend_val = lazy_data_arc.backend;
 
                     let expected_list_type_name = std::any::type_name::<Vec<T>>();
                     if !crate::types::erased_arc::compare_type_names(
@@ -906,18 +1541,18 @@ impl ArcValue {
                         ));
                     }
 
-                    let data_slice = &original_buffer_clone[start_offset_val..end_offset_val];
-                    let deserialized_list: Vec<T> =
-                        bincode::deserialize(data_slice).map_err(|e| {
+                    let deserialized_list = lazy_data_arc.decode_cached(|data_slice| {
+                        backend_val.decode::<Vec<T>>(data_slice).map_err(|e| {
                             anyhow!(
-                            "Failed to deserialize lazy list data for type '{}' into Vec<{}>: {}",
-                            type_name_clone,
-                            std::any::type_name::<T>(),
-                            e
-                        )
-                        })?;
+                                "Failed to deserialize lazy list data for type '{}' into Vec<{}>: {}",
+                                type_name_clone,
+                                std::any::type_name::<T>(),
+                                e
+                            )
+                        })
+                    })?;
 
-                    *actual_value = ErasedArc::new(Arc::new(deserialized_list));
+                    *actual_value = ErasedArc::new(deserialized_list);
                 }
                 actual_value.as_arc::<Vec<T>>().map_err(|e| {
                     anyhow!("Failed to cast eager value to list: {}. Expected Vec<{}>, got {}. Category: {:?}", 
@@ -977,23 +1612,14 @@ impl ArcValue {
         match &mut self.value {
             Some(ref mut actual_value) => {
                 if actual_value.is_lazy {
-                    let type_name_clone: String;
-                    let original_buffer_clone: Arc<[u8]>;
-                    let start_offset_val: usize;
-                    let end_offset_val: usize;
-
-                    {
-                        let lazy_data_arc = actual_value.get_lazy_data().map_err(|e| {
-                            anyhow!(
-                                "Failed to get lazy data from ErasedArc despite is_lazy flag: {}",
-                                e
-                            )
-                        })?;
-                        type_name_clone = lazy_data_arc.type_name.clone();
-                        original_buffer_clone = lazy_data_arc.original_buffer.clone();
-                        start_offset_val = lazy_data_arc.start_offset;
-                        end_offset_val = lazy_data_arc.end_offset;
-                    }
+                    let lazy_data_arc = actual_value.get_lazy_data().map_err(|e| {
+                        anyhow!(
+                            "Failed to get lazy data from ErasedArc despite is_lazy flag: {}",
+                            e
+                        )
+                    })?;
+                    let type_name_clone = lazy_data_arc.type_name.clone();
+                    let backend_val = lazy_data_arc.backend;
 
                     // Perform type name check before deserialization
                     let expected_type_name = std::any::type_name::<HashMap<K, V>>();
@@ -1009,18 +1635,19 @@ impl ArcValue {
                         ));
                     }
 
-                    let data_slice = &original_buffer_clone[start_offset_val..end_offset_val];
-                    let deserialized_map: HashMap<K, V> = bincode::deserialize(data_slice).map_err(|e| {
-                        anyhow!(
-                            "Failed to deserialize lazy map data for type '{}' into HashMap<{}, {}>: {}",
-                            type_name_clone,
-                            std::any::type_name::<K>(),
-                            std::any::type_name::<V>(),
-                            e
-                        )
+                    let deserialized_map = lazy_data_arc.decode_cached(|data_slice| {
+                        backend_val.decode::<HashMap<K, V>>(data_slice).map_err(|e| {
+                            anyhow!(
+                                "Failed to deserialize lazy map data for type '{}' into HashMap<{}, {}>: {}",
+                                type_name_clone,
+                                std::any::type_name::<K>(),
+                                std::any::type_name::<V>(),
+                                e
+                            )
+                        })
                     })?;
 
-                    *actual_value = ErasedArc::new(Arc::new(deserialized_map));
+                    *actual_value = ErasedArc::new(deserialized_map);
                 }
                 // Explicitly assign and return
                 actual_value.as_arc::<HashMap<K, V>>().map_err(|e| {
@@ -1035,6 +1662,27 @@ impl ArcValue {
         }
     }
 
+    /// Get a clone of the value at `key` in a `HashMap<String, ArcValue>` map
+    ///
+    /// INTENTION: Read one field of a map payload in a single call instead of
+    /// going through `as_map_ref`. A lazy map is deserialized in place, as with
+    /// `as_map_ref`. Fails if the value is not a map of `ArcValue`s keyed by
+    /// strings, or if `key` is absent.
+    pub fn index_map(&mut self, key: &str) -> Result<ArcValue> {
+        let map = self.as_map_ref::<String, ArcValue>()?;
+        map.get(key)
+            .cloned()
+            .ok_or_else(|| anyhow!("Key '{}' not found in map", key))
+    }
+
+    /// Check whether a `HashMap<String, ArcValue>` map has a value at `key`
+    ///
+    /// Fails like `index_map` when the value is not such a map.
+    pub fn contains_key(&mut self, key: &str) -> Result<bool> {
+        let map = self.as_map_ref::<String, ArcValue>()?;
+        Ok(map.contains_key(key))
+    }
+
     /// Get value as the specified type (makes a clone).
     pub fn as_type<T>(&mut self) -> Result<T>
     where
@@ -1044,6 +1692,113 @@ impl ArcValue {
         Ok((*arc_ref).clone())
     }
 
+    /// Get value as the specified type, widening numbers when needed
+    ///
+    /// INTENTION: Let a caller read a number as a wider type than the one the
+    /// sender stored, e.g. an `i32` primitive as `i64`. The direct conversion
+    /// of `as_type` is tried first; if it fails, the supported widenings are
+    /// `i32 → i64`, `i32 → f64`, `i64 → f64` and `f32 → f64`. When none
+    /// applies the error of the direct conversion is returned.
+    pub fn try_coerce<T>(&mut self) -> Result<T>
+    where
+        T: 'static + Clone + for<'de> Deserialize<'de> + fmt::Debug + Send + Sync,
+    {
+        let direct_error = match self.as_type::<T>() {
+            Ok(value) => return Ok(value),
+            Err(e) => e,
+        };
+
+        let target = TypeId::of::<T>();
+        let coerced: Option<Box<dyn Any>> = if target == TypeId::of::<i64>() {
+            self.as_type::<i32>()
+                .ok()
+                .map(|v| Box::new(i64::from(v)) as Box<dyn Any>)
+        } else if target == TypeId::of::<f64>() {
+            self.as_type::<i32>()
+                .map(f64::from)
+                .or_else(|_| self.as_type::<i64>().map(|v| v as f64))
+                .or_else(|_| self.as_type::<f32>().map(f64::from))
+                .ok()
+                .map(|v| Box::new(v) as Box<dyn Any>)
+        } else {
+            None
+        };
+
+        coerced
+            .and_then(|value| value.downcast::<T>().ok())
+            .map(|value| *value)
+            .ok_or(direct_error)
+    }
+
+    /// Get value as a type that borrows from this value's buffer
+    ///
+    /// INTENTION: Read large string or byte payloads without copying them.
+    /// `T` may hold `&'buf str`, `&'buf [u8]` or `#[serde(borrow)]` fields.
+    /// For a lazy value the data is decoded straight from the shared network
+    /// buffer and the value stays lazy; the stored type name is not checked,
+    /// so `T` must have the same serialized layout as the original type.
+    /// An eager value can only be borrowed when it holds a `String` or a
+    /// `Vec<u8>`; other eager values should be read with `as_type`.
+    pub fn as_type_borrowed<'buf, T>(&'buf self) -> Result<T>
+    where
+        T: Deserialize<'buf>,
+    {
+        let erased_arc = self.value.as_ref().ok_or_else(|| {
+            anyhow!(
+                "Cannot borrow value: ArcValue's internal value is None (category: {:?})",
+                self.category
+            )
+        })?;
+
+        if erased_arc.is_lazy {
+            let lazy_data = erased_arc.lazy_data_ref()?;
+            let data_slice =
+                &lazy_data.original_buffer[lazy_data.start_offset..lazy_data.end_offset];
+            if self.category == ValueCategory::Bytes {
+                // Raw bytes were not encoded by the backend
+                let borrowed: Result<T, de::value::Error> =
+                    T::deserialize(de::value::BorrowedBytesDeserializer::new(data_slice));
+                return borrowed.map_err(|e| {
+                    anyhow!(
+                        "Failed to borrow bytes as {}: {}",
+                        std::any::type_name::<T>(),
+                        e
+                    )
+                });
+            }
+            return lazy_data.backend.decode_borrowed(data_slice).map_err(|e| {
+                anyhow!(
+                    "Failed to borrow lazy data of type '{}' as {}: {}",
+                    lazy_data.type_name,
+                    std::any::type_name::<T>(),
+                    e
+                )
+            });
+        }
+
+        let stored = erased_arc.as_any()?;
+        let borrowed: Result<T, de::value::Error> =
+            if let Some(string) = stored.downcast_ref::<String>() {
+                T::deserialize(de::value::BorrowedStrDeserializer::new(string))
+            } else if let Some(bytes) = stored.downcast_ref::<Vec<u8>>() {
+                T::deserialize(de::value::BorrowedBytesDeserializer::new(bytes))
+            } else {
+                return Err(anyhow!(
+                    "Cannot borrow eager value of type {} as {}",
+                    erased_arc.type_name(),
+                    std::any::type_name::<T>()
+                ));
+            };
+        borrowed.map_err(|e| {
+            anyhow!(
+                "Failed to borrow {} as {}: {}",
+                erased_arc.type_name(),
+                std::any::type_name::<T>(),
+                e
+            )
+        })
+    }
+
     /// Get struct as a reference of the specified type.
     /// If the value is lazy, it will be deserialized and made eager in-place.
     pub fn as_struct_ref<T>(&mut self) -> Result<Arc<T>>
@@ -1060,20 +1815,11 @@ impl ArcValue {
         match &mut self.value {
             Some(ref mut actual_value) => {
                 if actual_value.is_lazy {
-                    let type_name_clone: String;
-                    let original_buffer_clone: Arc<[u8]>;
-                    let start_offset_val: usize;
-                    let end_offset_val: usize;
-
-                    {
-                        let lazy_data_arc = actual_value.get_lazy_data().map_err(|e| {
-                            anyhow!("Failed to get lazy data despite is_lazy flag: {}", e)
-                        })?;
-                        type_name_clone = lazy_data_arc.type_name.clone();
-                        original_buffer_clone = lazy_data_arc.original_buffer.clone();
-                        start_offset_val = lazy_data_arc.start_offset;
-                        end_offset_val = lazy_data_arc.end_offset;
-                    }
+                    let lazy_data_arc = actual_value.get_lazy_data().map_err(|e| {
+                        anyhow!("Failed to get lazy data despite is_lazy flag: {}", e)
+                    })?;
+                    let type_name_clone = lazy_data_arc.type_name.clone();
+                    let backend_val = lazy_data_arc.backend;
 
                     let expected_type_name = std::any::type_name::<T>();
                     if !crate::types::erased_arc::compare_type_names(
@@ -1087,33 +1833,31 @@ impl ArcValue {
                         ));
                     }
 
-                    let data_slice = &original_buffer_clone[start_offset_val..end_offset_val];
-                    // First try plain bincode into the requested T
-                    if let Ok(deserialized_struct) = bincode::deserialize::<T>(data_slice) {
-                        *actual_value = ErasedArc::new(Arc::new(deserialized_struct));
-                    } else {
+                    let deserialized_struct = lazy_data_arc.decode_cached(|data_slice| {
+                        // First try decoding the requested T with the registry's backend
+                        if let Ok(deserialized_struct) = backend_val.decode::<T>(data_slice) {
+                            return Ok(deserialized_struct);
+                        }
                         // Fallback: use the captured deserializer wrapper (may decrypt)
-                        let lazy_data_arc = actual_value.get_lazy_data()?;
-                        if let Some(wrapper) = &lazy_data_arc.deserializer {
-                            let boxed = wrapper
-                                .call(data_slice)
-                                .map_err(|e| anyhow!("Lazy deserializer error: {e}"))?;
-
-                            let concrete = boxed.downcast::<T>().map_err(|_| {
-                                anyhow!(
-                                    "Lazy deserializer returned unexpected type (expected {})",
-                                    std::any::type_name::<T>()
-                                )
-                            })?;
-
-                            *actual_value = ErasedArc::new(Arc::new(*concrete));
-                        } else {
+                        let Some(wrapper) = &lazy_data_arc.deserializer else {
                             return Err(anyhow!(
                                 "No deserializer available for lazy struct type {}",
                                 type_name_clone
                             ));
-                        }
-                    }
+                        };
+                        let boxed = wrapper
+                            .call(data_slice)
+                            .map_err(|e| anyhow!("Lazy deserializer error: {e}"))?;
+                        let concrete = boxed.downcast::<T>().map_err(|_| {
+                            anyhow!(
+                                "Lazy deserializer returned unexpected type (expected {})",
+                                std::any::type_name::<T>()
+                            )
+                        })?;
+                        Ok(*concrete)
+                    })?;
+
+                    *actual_value = ErasedArc::new(deserialized_struct);
                 }
                 // Explicitly assign and return
                 actual_value.as_arc::<T>().map_err(|e| {
@@ -1313,23 +2057,14 @@ impl ArcValue {
         };
 
         if current_erased_arc.is_lazy {
-            let type_name_clone: String;
-            let original_buffer_clone: Arc<[u8]>;
-            let start_offset_val: usize;
-            let end_offset_val: usize;
-
-            {
-                let lazy_data_arc = current_erased_arc.get_lazy_data().map_err(|e| {
-                    anyhow!(
-                        "Failed to get lazy data from ErasedArc despite is_lazy flag: {}",
-                        e
-                    )
-                })?;
-                type_name_clone = lazy_data_arc.type_name.clone();
-                original_buffer_clone = lazy_data_arc.original_buffer.clone();
-                start_offset_val = lazy_data_arc.start_offset;
-                end_offset_val = lazy_data_arc.end_offset;
-            }
+            let lazy_data_arc = current_erased_arc.get_lazy_data().map_err(|e| {
+                anyhow!(
+                    "Failed to get lazy data from ErasedArc despite is_lazy flag: {}",
+                    e
+                )
+            })?;
+            let type_name_clone = lazy_data_arc.type_name.clone();
+            let backend_val = lazy_data_arc.backend;
 
             // Perform type name check before deserialization
             let expected_type_name = std::any::type_name::<T>();
@@ -1342,20 +2077,27 @@ impl ArcValue {
                 ));
             }
 
-            let data_slice = &original_buffer_clone[start_offset_val..end_offset_val];
-            let deserialized_value: T = bincode::deserialize(data_slice).map_err(|e| {
-                // Note: Consider if current_erased_arc should be put back into self.value on deserialize error.
-                // Original code didn't, so maintaining that behavior for now.
-                anyhow!(
-                    "Failed to deserialize lazy struct data for type '{}' into {}: {}",
-                    type_name_clone,
-                    std::any::type_name::<T>(),
-                    e
-                )
-            })?;
+            if self.category == ValueCategory::Bytes {
+                // Raw bytes were not encoded by the backend; copy them out as Vec<u8>
+                let bytes = lazy_data_arc.decode_cached(|data_slice| Ok(data_slice.to_vec()))?;
+                current_erased_arc = ErasedArc::new(bytes);
+            } else {
+                let deserialized_value = lazy_data_arc.decode_cached(|data_slice| {
+                    backend_val.decode::<T>(data_slice).map_err(|e| {
+                        // Note: Consider if current_erased_arc should be put back into self.value on deserialize error.
+                        // Original code didn't, so maintaining that behavior for now.
+                        anyhow!(
+                            "Failed to deserialize lazy struct data for type '{}' into {}: {}",
+                            type_name_clone,
+                            std::any::type_name::<T>(),
+                            e
+                        )
+                    })
+                })?;
 
-            // Replace internal lazy value with the eager one
-            current_erased_arc = ErasedArc::new(Arc::new(deserialized_value));
+                // Replace internal lazy value with the eager one
+                current_erased_arc = ErasedArc::new(deserialized_value);
+            }
         }
 
         self.value = Some(current_erased_arc.clone()); // Put the (potentially updated) ErasedArc back
@@ -1496,6 +2238,52 @@ impl ArcValue {
     }
 }
 
+#[cfg(feature = "serializer")]
+impl ArcValue {
+    /// Format the value for humans, decoding lazy values with `registry`
+    ///
+    /// INTENTION: Show the content of values received from the network in
+    /// logs, where `Display` only shows their type and size. Values of types
+    /// registered with `SerializerRegistry::register_debug` are shown with
+    /// their `Debug` impl; lazy values of other types show as
+    /// `<TypeName>[N bytes]` and eager ones as with `Display`. Never panics:
+    /// a payload that fails to decode shows the error instead.
+    pub fn to_display_string(&self, registry: &SerializerRegistry) -> String {
+        let Some(value) = &self.value else {
+            return self.to_string();
+        };
+        if value.is_lazy {
+            let lazy = match value.get_lazy_data() {
+                Ok(lazy) => lazy,
+                Err(e) => return format!("<lazy value error: {e}>"),
+            };
+            let bytes = &lazy.original_buffer[lazy.start_offset..lazy.end_offset];
+            return match registry.debug_formatter(&lazy.type_name) {
+                Some(formatter) => formatter(bytes).unwrap_or_else(|e| {
+                    format!("<{}: deserialization failed: {e}>", lazy.type_name)
+                }),
+                None => format!("<{}>[{} bytes]", lazy.type_name, bytes.len()),
+            };
+        }
+
+        let type_name = value.type_name();
+        match (self.category, registry.debug_formatter(type_name)) {
+            (
+                ValueCategory::Primitive
+                | ValueCategory::List
+                | ValueCategory::Map
+                | ValueCategory::Struct,
+                Some(formatter),
+            ) => value
+                .as_any()
+                .and_then(|any| registry.serialize(any, type_name))
+                .and_then(|bytes| formatter(&bytes))
+                .unwrap_or_else(|e| format!("<{type_name}: formatting failed: {e}>")),
+            _ => self.to_string(),
+        }
+    }
+}
+
 impl fmt::Display for ArcValue {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match &self.value {
@@ -1,4 +1,5 @@
 use super::arc_value::{ArcValue, ValueCategory};
+use super::value_diff::ValueDiff;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use std::collections::HashMap;
@@ -118,3 +119,227 @@ fn test_from_json_object_to_struct_list() {
     assert_eq!(obj.name, "Test Struct");
     assert!(obj.active);
 }
+
+#[test]
+fn test_diff_primitives_and_categories() {
+    let a = ArcValue::new_primitive(1i64);
+    assert!(a.diff(&ArcValue::new_primitive(1i64)).unwrap().is_no_diff());
+
+    match a.diff(&ArcValue::new_primitive(2i64)).unwrap() {
+        ValueDiff::PrimitiveChanged { mut from, mut to } => {
+            assert_eq!(from.as_type::<i64>().unwrap(), 1);
+            assert_eq!(to.as_type::<i64>().unwrap(), 2);
+        }
+        other => panic!("unexpected diff: {other:?}"),
+    }
+
+    match a.diff(&ArcValue::null()).unwrap() {
+        ValueDiff::TypeChanged { from, to } => {
+            assert_eq!(from, ValueCategory::Primitive);
+            assert_eq!(to, ValueCategory::Null);
+        }
+        other => panic!("unexpected diff: {other:?}"),
+    }
+}
+
+#[test]
+fn test_diff_maps_and_lists() {
+    let old = ArcValue::new_map(HashMap::from([
+        (
+            "kept".to_string(),
+            ArcValue::new_primitive("same".to_string()),
+        ),
+        ("gone".to_string(), ArcValue::new_primitive(true)),
+        (
+            "nested".to_string(),
+            ArcValue::new_list(vec![
+                ArcValue::new_primitive(1i64),
+                ArcValue::new_primitive(2i64),
+                ArcValue::new_primitive(3i64),
+            ]),
+        ),
+    ]));
+    let new = ArcValue::new_map(HashMap::from([
+        (
+            "kept".to_string(),
+            ArcValue::new_primitive("same".to_string()),
+        ),
+        ("new".to_string(), ArcValue::new_primitive(5i64)),
+        (
+            "nested".to_string(),
+            ArcValue::new_list(vec![
+                ArcValue::new_primitive(1i64),
+                ArcValue::new_primitive(3i64),
+                ArcValue::new_primitive(4i64),
+            ]),
+        ),
+    ]));
+
+    assert!(old.diff(&old.clone()).unwrap().is_no_diff());
+
+    let ValueDiff::MapDiff {
+        added,
+        removed,
+        changed,
+    } = old.diff(&new).unwrap()
+    else {
+        panic!("expected a map diff");
+    };
+    assert_eq!(added.keys().collect::<Vec<_>>(), vec!["new"]);
+    assert_eq!(removed, vec!["gone".to_string()]);
+    assert_eq!(changed.len(), 1);
+    let ValueDiff::ListDiff {
+        added,
+        removed_indices,
+    } = &changed["nested"]
+    else {
+        panic!("expected a list diff");
+    };
+    assert_eq!(removed_indices, &vec![1]);
+    assert_eq!(added.len(), 1);
+    assert_eq!(added[0].clone().as_type::<i64>().unwrap(), 4);
+}
+
+#[test]
+fn test_diff_json_objects() {
+    let old = ArcValue::from_json(json!({ "name": "a", "count": 1 }));
+    let new = ArcValue::from_json(json!({ "name": "a", "count": 2 }));
+
+    let ValueDiff::MapDiff { changed, .. } = old.diff(&new).unwrap() else {
+        panic!("expected a map diff");
+    };
+    assert!(matches!(
+        changed["count"],
+        ValueDiff::PrimitiveChanged { .. }
+    ));
+}
+
+#[test]
+fn test_total_cmp_primitives() {
+    let mut values = [
+        ArcValue::new_primitive(f64::NAN),
+        ArcValue::new_primitive(3i64),
+        ArcValue::new_primitive(-1.5f64),
+        ArcValue::new_primitive(2i32),
+        ArcValue::new_primitive("b".to_string()),
+        ArcValue::new_primitive("a".to_string()),
+        ArcValue::new_primitive(true),
+    ];
+    values.sort();
+    let sorted: Vec<String> = values.iter().map(|v| v.to_string()).collect();
+    let expected: Vec<String> = [
+        ArcValue::new_primitive(true),
+        ArcValue::new_primitive(-1.5f64),
+        ArcValue::new_primitive(2i32),
+        ArcValue::new_primitive(3i64),
+        ArcValue::new_primitive(f64::NAN),
+        ArcValue::new_primitive("a".to_string()),
+        ArcValue::new_primitive("b".to_string()),
+    ]
+    .iter()
+    .map(|v| v.to_string())
+    .collect();
+    assert_eq!(sorted, expected);
+
+    // Equality follows content, not identity
+    assert_eq!(ArcValue::new_primitive(7i64), ArcValue::new_primitive(7i64));
+    assert_ne!(
+        ArcValue::new_primitive(7i64),
+        ArcValue::new_primitive(7.0f64)
+    );
+    assert_eq!(
+        ArcValue::new_primitive(f64::NAN),
+        ArcValue::new_primitive(f64::NAN)
+    );
+
+    let max = values
+        .iter()
+        .filter(|v| v.category == ValueCategory::Primitive)
+        .max();
+    assert_eq!(max, Some(&ArcValue::new_primitive("b".to_string())));
+}
+
+#[test]
+fn test_total_cmp_categories_and_containers() {
+    let list = |items: Vec<i64>| {
+        ArcValue::new_list(items.into_iter().map(ArcValue::new_primitive).collect())
+    };
+    let map = ArcValue::new_map(HashMap::from([(
+        "a".to_string(),
+        ArcValue::new_primitive(1i64),
+    )]));
+
+    let mut values = vec![
+        map.clone(),
+        list(vec![1, 2]),
+        ArcValue::null(),
+        ArcValue::new_primitive(10i64),
+        list(vec![1]),
+    ];
+    values.sort();
+    assert_eq!(
+        values,
+        vec![
+            ArcValue::null(),
+            ArcValue::new_primitive(10i64),
+            list(vec![1]),
+            list(vec![1, 2]),
+            map.clone(),
+        ]
+    );
+
+    let bigger_map = ArcValue::new_map(HashMap::from([(
+        "a".to_string(),
+        ArcValue::new_primitive(2i64),
+    )]));
+    assert!(map < bigger_map);
+    let same_map = ArcValue::new_map(HashMap::from([(
+        "a".to_string(),
+        ArcValue::new_primitive(1i64),
+    )]));
+    assert_eq!(map, same_map);
+
+    // Values can key ordered maps
+    let mut counts = std::collections::BTreeMap::new();
+    for key in [list(vec![2]), list(vec![1]), list(vec![2])] {
+        *counts.entry(key).or_insert(0) += 1;
+    }
+    assert_eq!(counts.into_values().collect::<Vec<_>>(), vec![1, 2]);
+}
+
+#[test]
+fn test_index_map_and_contains_key() {
+    let mut map = ArcValue::new_map(HashMap::from([
+        (
+            "name".to_string(),
+            ArcValue::new_primitive("runar".to_string()),
+        ),
+        ("port".to_string(), ArcValue::new_primitive(8080i64)),
+    ]));
+    assert_eq!(
+        map.index_map("name").unwrap().as_type::<String>().unwrap(),
+        "runar"
+    );
+    assert!(map.contains_key("port").unwrap());
+    assert!(!map.contains_key("host").unwrap());
+    assert!(map.index_map("host").is_err());
+
+    // Lazy JSON objects are converted to a map on first access
+    let mut json_map = ArcValue::from_json(json!({ "port": 8080 }));
+    assert!(json_map.contains_key("port").unwrap());
+    assert_eq!(json_map.category, ValueCategory::Map);
+    assert_eq!(
+        json_map
+            .index_map("port")
+            .unwrap()
+            .as_type::<i64>()
+            .unwrap(),
+        8080
+    );
+
+    // Other categories and map types are rejected
+    assert!(ArcValue::new_primitive(1i64).index_map("port").is_err());
+    let mut typed_map = ArcValue::new_map(HashMap::from([("port".to_string(), 8080i64)]));
+    assert!(typed_map.index_map("port").is_err());
+    assert!(typed_map.contains_key("port").is_err());
+}
@@ -0,0 +1,140 @@
+// Concurrent Serializer Registry
+//
+// A SerializerRegistry shared between threads that is read without locking
+// once sealed: registrations go through a mutex, and sealing publishes the
+// registry through an ArcSwap for the hot serialize/deserialize path.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use anyhow::{anyhow, Result};
+use arc_swap::ArcSwapOption;
+use serde::{Deserialize, Serialize};
+
+use super::arc_value::{ArcValue, SerializerRegistry};
+use crate::logging::Logger;
+
+/// A [`SerializerRegistry`] with lock-free reads after `seal()`
+///
+/// INTENTION: Remove the read lock callers take around the registry on every
+/// network message. Types are registered while the registry is open, under a
+/// mutex; `seal()` moves the registry behind an `ArcSwap`, after which
+/// `serialize_value` and `deserialize_value` take no lock at all. Reads made
+/// before sealing lock the mutex, exactly like a plain `Mutex` would.
+pub struct ConcurrentSerializerRegistry {
+    /// The registry while it is open, `None` once sealed
+    open: Mutex<Option<SerializerRegistry>>,
+    /// The registry once sealed
+    sealed_registry: ArcSwapOption<SerializerRegistry>,
+    is_sealed: AtomicBool,
+}
+
+impl ConcurrentSerializerRegistry {
+    /// Wrap an open registry
+    ///
+    /// A registry that is already sealed is published immediately.
+    pub fn new(registry: SerializerRegistry) -> Self {
+        let already_sealed = registry.is_sealed();
+        let concurrent = Self {
+            open: Mutex::new(Some(registry)),
+            sealed_registry: ArcSwapOption::empty(),
+            is_sealed: AtomicBool::new(false),
+        };
+        if already_sealed {
+            concurrent.seal();
+        }
+        concurrent
+    }
+
+    /// Initialize with default types
+    pub fn with_defaults(logger: Arc<Logger>) -> Self {
+        Self::new(SerializerRegistry::with_defaults(logger))
+    }
+
+    /// Register a type for serialization/deserialization
+    pub fn register<T: 'static + Serialize + for<'de> Deserialize<'de> + Clone + Send + Sync>(
+        &self,
+    ) -> Result<()> {
+        self.update(|registry| registry.register::<T>())
+    }
+
+    /// Modify the registry while it is open
+    ///
+    /// Gives access to every registration method of [`SerializerRegistry`].
+    /// Fails once the registry is sealed.
+    pub fn update<R>(&self, f: impl FnOnce(&mut SerializerRegistry) -> Result<R>) -> Result<R> {
+        let mut open = self
+            .open
+            .lock()
+            .map_err(|_| anyhow!("Serializer registry lock poisoned"))?;
+        match open.as_mut() {
+            Some(registry) => f(registry),
+            None => Err(anyhow!(
+                "Cannot register new types after registry is sealed"
+            )),
+        }
+    }
+
+    /// Seal the registry, making all further reads lock-free
+    ///
+    /// Sealing twice is a no-op.
+    pub fn seal(&self) {
+        let mut open = self.open.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(mut registry) = open.take() {
+            registry.seal();
+            // Publish before setting the flag, so readers that see the flag
+            // always find the registry
+            self.sealed_registry.store(Some(Arc::new(registry)));
+            self.is_sealed.store(true, Ordering::Release);
+        }
+    }
+
+    /// Check if the registry is sealed
+    pub fn is_sealed(&self) -> bool {
+        self.is_sealed.load(Ordering::Acquire)
+    }
+
+    /// Get the sealed registry, `None` while it is open
+    pub fn sealed(&self) -> Option<Arc<SerializerRegistry>> {
+        if self.is_sealed() {
+            self.sealed_registry.load_full()
+        } else {
+            None
+        }
+    }
+
+    /// Run `f` with the registry, without locking once sealed
+    pub fn read<R>(&self, f: impl FnOnce(&SerializerRegistry) -> Result<R>) -> Result<R> {
+        if self.is_sealed() {
+            if let Some(registry) = self.sealed_registry.load().as_ref() {
+                return f(registry);
+            }
+        }
+        let open = self
+            .open
+            .lock()
+            .map_err(|_| anyhow!("Serializer registry lock poisoned"))?;
+        match open.as_ref() {
+            Some(registry) => f(registry),
+            // Sealed between the flag check and the lock
+            None => {
+                drop(open);
+                let sealed = self.sealed_registry.load();
+                let registry = sealed
+                    .as_ref()
+                    .ok_or_else(|| anyhow!("Serializer registry is neither open nor sealed"))?;
+                f(registry)
+            }
+        }
+    }
+
+    /// Serialize a value to bytes, returning an Arc<[u8]>
+    pub fn serialize_value(&self, value: &ArcValue) -> Result<Arc<[u8]>> {
+        self.read(|registry| registry.serialize_value(value))
+    }
+
+    /// Deserialize bytes (owned Arc) to an ArcValue
+    pub fn deserialize_value(&self, bytes_arc: Arc<[u8]>) -> Result<ArcValue> {
+        self.read(|registry| registry.deserialize_value(bytes_arc))
+    }
+}
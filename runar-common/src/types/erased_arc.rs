@@ -24,6 +24,12 @@ pub trait ArcRead: fmt::Debug + Send + Sync {
 
     /// Get this value as a dynamic Any
     fn as_any(&self) -> &dyn Any;
+
+    /// Get the TypeId of the value the Arc holds
+    fn value_type_id(&self) -> TypeId;
+
+    /// Convert into the Arc itself, so that it can be downcast by value
+    fn into_any_arc(self: Box<Self>) -> Arc<dyn Any + Send + Sync>;
 }
 
 // Custom serde implementation for ErasedArc
@@ -172,6 +178,14 @@ impl<T: 'static + fmt::Debug + Send + Sync> ArcRead for ArcReader<T> {
         // For other types, return the Arc contents
         &*self.arc
     }
+
+    fn value_type_id(&self) -> TypeId {
+        TypeId::of::<T>()
+    }
+
+    fn into_any_arc(self: Box<Self>) -> Arc<dyn Any + Send + Sync> {
+        self.arc
+    }
 }
 
 impl fmt::Debug for ErasedArc {
@@ -393,6 +407,15 @@ impl ErasedArc {
 
     /// Try to extract an Arc<T> from this ErasedArc
     pub fn as_arc<T: 'static>(&self) -> Result<Arc<T>> {
+        // A lazy value is named after the type it decodes to, but the Arc
+        // holds its LazyDataWithOffset
+        if self.is_lazy {
+            return Err(anyhow!(
+                "Cannot access lazy value of type {} before it is deserialized",
+                self.type_name()
+            ));
+        }
+
         // Check if the type matches based on name (potentially overridden)
         if !self.is_type::<T>() {
             let expected_type_name = std::any::type_name::<T>();
@@ -418,6 +441,104 @@ impl ErasedArc {
         Ok(arc)
     }
 
+    /// Take the value out of this ErasedArc without cloning it
+    ///
+    /// Succeeds when the value is exactly a `T` and this ErasedArc holds its
+    /// only strong reference; otherwise `self` is returned unchanged so that
+    /// the caller can fall back to cloning. A lazy value is unwrapped from its
+    /// decode cache once a `T` was decoded, provided no clone of the lazy
+    /// value or of the decoded `Arc<T>` is left; a lazy value that was never
+    /// decoded as a `T` only holds bytes and is returned unchanged.
+    pub fn try_unwrap<T: 'static + fmt::Debug + Send + Sync>(self) -> Result<T, Self> {
+        if self.is_lazy {
+            return self.try_unwrap_cached();
+        }
+        if self.reader.value_type_id() != TypeId::of::<T>() || self.strong_count() != 1 {
+            return Err(self);
+        }
+
+        let arc = match self.reader.into_any_arc().downcast::<T>() {
+            Ok(arc) => arc,
+            Err(_) => unreachable!("type checked above"),
+        };
+        Arc::try_unwrap(arc).map_err(ErasedArc::new)
+    }
+
+    /// Take a decoded `T` out of the cache of a lazy value without other owners
+    fn try_unwrap_cached<T: 'static + Send + Sync>(self) -> Result<T, Self> {
+        let unique = self.strong_count() == 1
+            && self.lazy_data_ref().is_ok_and(|lazy| {
+                lazy.eager_cache
+                    .get()
+                    .is_some_and(|cached| cached.is::<T>() && Arc::strong_count(cached) == 1)
+            });
+        if !unique {
+            return Err(self);
+        }
+
+        // Both the lazy data and its cached value are only reachable from self
+        let lazy = match self
+            .reader
+            .into_any_arc()
+            .downcast::<crate::types::arc_value::LazyDataWithOffset>()
+            .map(Arc::try_unwrap)
+        {
+            Ok(Ok(lazy)) => lazy,
+            _ => unreachable!("lazy data checked above"),
+        };
+        match lazy
+            .eager_cache
+            .into_inner()
+            .map(|cached| cached.downcast::<T>().map(Arc::try_unwrap))
+        {
+            Some(Ok(Ok(value))) => Ok(value),
+            _ => unreachable!("cached value checked above"),
+        }
+    }
+
+    /// Take the value out of this ErasedArc, cloning it if it is shared
+    ///
+    /// INTENTION: Let value transformation pipelines mutate intermediate
+    /// results, which usually have a single reference, without paying for a
+    /// clone. The value must be exactly a `T`, checked by `TypeId` rather
+    /// than by type name. Unlike `Arc::unwrap_or_clone` this returns a
+    /// `Result` instead of a bare `T`: an ErasedArc can hold a value of any
+    /// other type, or a lazy value whose bytes were never decoded as a `T`
+    /// (decoding needs the serializer registry), and both are reported as
+    /// errors rather than panics.
+    pub fn try_unwrap_or_clone<T: 'static + Clone + fmt::Debug + Send + Sync>(self) -> Result<T> {
+        let erased = match self.try_unwrap::<T>() {
+            Ok(value) => return Ok(value),
+            Err(erased) => erased,
+        };
+        if erased.is_lazy {
+            let lazy = erased.lazy_data_ref()?;
+            return lazy
+                .eager_cache
+                .get()
+                .and_then(|cached| cached.downcast_ref::<T>())
+                .cloned()
+                .ok_or_else(|| {
+                    anyhow!(
+                        "Cannot unwrap lazy value of type {} before it is deserialized",
+                        lazy.type_name
+                    )
+                });
+        }
+        erased
+            .reader
+            .as_any()
+            .downcast_ref::<T>()
+            .cloned()
+            .ok_or_else(|| {
+                anyhow!(
+                    "Type mismatch: expected {}, but has {}",
+                    std::any::type_name::<T>(),
+                    erased.type_name()
+                )
+            })
+    }
+
     /// Directly get the LazyDataWithOffset when we know this contains one
     pub fn get_lazy_data(&self) -> Result<Arc<crate::types::arc_value::LazyDataWithOffset>> {
         if !self.is_lazy {
@@ -438,6 +559,21 @@ impl ErasedArc {
 
         Ok(arc)
     }
+
+    /// Borrow the LazyDataWithOffset when we know this contains one
+    ///
+    /// Unlike `get_lazy_data`, no reference count is taken; the data lives as
+    /// long as this ErasedArc.
+    pub fn lazy_data_ref(&self) -> Result<&crate::types::arc_value::LazyDataWithOffset> {
+        if !self.is_lazy {
+            return Err(anyhow!("Value is not lazy (is_lazy flag is false)"));
+        }
+
+        let ptr = self.reader.ptr() as *const crate::types::arc_value::LazyDataWithOffset;
+        // Safety: when is_lazy is true the pointed value is a LazyDataWithOffset,
+        // kept alive by the Arc held in self.reader
+        Ok(unsafe { &*ptr })
+    }
 }
 
 /// Helper to compare type names accounting for namespaces
@@ -7,24 +7,60 @@
 // Trait for converting types to ArcValue
 // ArcValue will be qualified from self::value_type directly in the trait
 
+#[cfg(feature = "type-erasure")]
 pub trait AsArcValue {
     fn into_arc_value_type(self) -> self::arc_value::ArcValue;
 }
 
+// Value types, gated by `type-erasure`; the SerializerRegistry inside
+// `arc_value` is additionally gated by `serializer`
+#[cfg(feature = "type-erasure")]
 pub mod arc_value;
-#[cfg(test)]
+#[cfg(all(test, feature = "type-erasure"))]
 mod arc_value_test;
+#[cfg(feature = "serializer")]
+pub mod concurrent_registry;
+#[cfg(feature = "type-erasure")]
 pub mod erased_arc;
 pub mod schemas;
+#[cfg(feature = "type-erasure")]
+pub mod serialization_backend;
+#[cfg(all(test, feature = "serializer"))]
+mod serialization_roundtrip_test;
+#[cfg(feature = "type-erasure")]
+pub mod type_descriptor;
+#[cfg(feature = "serde_full")]
+mod typed_json;
+#[cfg(feature = "type-erasure")]
+pub mod value_diff;
+#[cfg(feature = "type-erasure")]
+pub mod value_ord;
+#[cfg(feature = "type-erasure")]
 mod vmap;
 
 // Export our types
-pub use self::arc_value::{ArcValue, SerializerRegistry, ValueCategory};
+#[cfg(feature = "type-erasure")]
+pub use self::arc_value::{ArcValue, ValueCategory};
+#[cfg(feature = "serializer")]
+pub use self::arc_value::{SerializerRegistry, TypeRegistration};
+#[cfg(feature = "serializer")]
+pub use self::concurrent_registry::ConcurrentSerializerRegistry;
+#[cfg(feature = "type-erasure")]
 pub use self::erased_arc::ErasedArc;
 pub use self::schemas::{
-    ActionMetadata, EventMetadata, FieldSchema, SchemaDataType, ServiceMetadata,
+    ActionMetadata, EventMetadata, FieldSchema, SchemaDataType, ServiceMetadata, ServiceVisibility,
 };
+#[cfg(feature = "type-erasure")]
+pub use self::serialization_backend::SerializationBackend;
+#[cfg(feature = "type-erasure")]
+pub use self::type_descriptor::TypeDescriptor;
+#[cfg(feature = "type-erasure")]
+pub use self::value_diff::ValueDiff;
+// Allow `runar_common::types::register_all!` next to the registry it targets
+#[cfg(feature = "serializer")]
+pub use crate::register_all;
 // AsArcValue is already public in this module, no need to re-export 'self::AsArcValue'
+#[cfg(feature = "type-erasure")]
 pub use vmap::VMap;
 // Export the implement_from_for_valuetype macro
 #[macro_export]
@@ -52,6 +52,75 @@ pub struct ServiceMetadata {
     /// The timestamp when the service was last started (in seconds since UNIX epoch)
     /// This is None if the service has never been started
     pub last_start_time: Option<u64>,
+    /// Which peers the service is advertised to
+    ///
+    /// Only known to the node hosting the service: it is not sent to peers,
+    /// which see every service they receive as public.
+    #[serde(skip)]
+    pub visibility: ServiceVisibility,
+}
+
+/// Which peers a service is advertised to
+///
+/// INTENTION: Keep services such as internal admin services out of the node
+/// info sent to peers that should not discover them. A service that is not
+/// `public` is never advertised; a public service with `allowed_network_ids`
+/// is advertised only to peers in one of those networks, and never through
+/// discovery. Hiding a service does not protect it: peers that know its path
+/// can still call it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ServiceVisibility {
+    /// Whether the service is advertised at all
+    pub public: bool,
+    /// Networks whose peers the service is advertised to (None = all)
+    pub allowed_network_ids: Option<Vec<String>>,
+}
+
+impl Default for ServiceVisibility {
+    fn default() -> Self {
+        Self::public()
+    }
+}
+
+impl ServiceVisibility {
+    /// Advertised to every peer
+    pub fn public() -> Self {
+        Self {
+            public: true,
+            allowed_network_ids: None,
+        }
+    }
+
+    /// Never advertised
+    pub fn private() -> Self {
+        Self {
+            public: false,
+            allowed_network_ids: None,
+        }
+    }
+
+    /// Advertised only to peers in one of `network_ids`
+    pub fn networks(network_ids: Vec<String>) -> Self {
+        Self {
+            public: true,
+            allowed_network_ids: Some(network_ids),
+        }
+    }
+
+    /// Whether the service is advertised to a peer in `network_ids`
+    pub fn is_visible_to(&self, network_ids: &[String]) -> bool {
+        self.public
+            && self.allowed_network_ids.as_ref().is_none_or(|allowed| {
+                allowed
+                    .iter()
+                    .any(|network_id| network_ids.contains(network_id))
+            })
+    }
+
+    /// Whether the service is advertised to every peer
+    pub fn is_unrestricted(&self) -> bool {
+        self.public && self.allowed_network_ids.is_none()
+    }
 }
 
 /// Represents a field in a schema
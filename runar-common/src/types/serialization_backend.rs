@@ -0,0 +1,86 @@
+// runar_common/src/types/serialization_backend.rs
+//
+// Encodings available for the payload bytes written by SerializerRegistry.
+
+use anyhow::{anyhow, Result};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+/// Encoding used for the payload that follows the ArcValue wire header
+///
+/// INTENTION: Allow a registry to trade bincode's compactness for a format
+/// that non-Rust tooling can decode. The header (category byte, type-name
+/// length and type name) is the same for every backend; the receiver decodes
+/// with its own backend unless the sender names another one (see
+/// `SerializerRegistry::deserialize_value_with_backend`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum SerializationBackend {
+    /// bincode 1.x with its default options (the original wire format)
+    #[default]
+    Bincode,
+    /// MessagePack via `rmp_serde`, with structs encoded as maps keyed by
+    /// field name so the payload can be inspected without the Rust types.
+    ///
+    /// For `struct TestStruct { field1: String, field2: i32 }` holding
+    /// `("hello", 123)` the payload is:
+    ///
+    /// ```text
+    /// 82                    fixmap, 2 entries
+    /// a6 66 69 65 6c 64 31  fixstr "field1"
+    /// a5 68 65 6c 6c 6f     fixstr "hello"
+    /// a6 66 69 65 6c 64 32  fixstr "field2"
+    /// 7b                    positive fixint 123
+    /// ```
+    ///
+    /// which Python's `msgpack.unpackb(payload)` decodes to
+    /// `{"field1": "hello", "field2": 123}`.
+    #[cfg(feature = "msgpack")]
+    Msgpack,
+}
+
+impl SerializationBackend {
+    /// The backends compiled into this build, the default first
+    pub fn available() -> Vec<SerializationBackend> {
+        vec![
+            SerializationBackend::Bincode,
+            #[cfg(feature = "msgpack")]
+            SerializationBackend::Msgpack,
+        ]
+    }
+
+    /// Encode a value with this backend
+    pub fn encode<T: Serialize + ?Sized>(self, value: &T) -> Result<Vec<u8>> {
+        match self {
+            SerializationBackend::Bincode => {
+                bincode::serialize(value).map_err(|e| anyhow!("bincode encoding error: {}", e))
+            }
+            #[cfg(feature = "msgpack")]
+            SerializationBackend::Msgpack => rmp_serde::to_vec_named(value)
+                .map_err(|e| anyhow!("MessagePack encoding error: {}", e)),
+        }
+    }
+
+    /// Decode a value encoded with this backend
+    pub fn decode<T: DeserializeOwned>(self, bytes: &[u8]) -> Result<T> {
+        match self {
+            SerializationBackend::Bincode => {
+                bincode::deserialize(bytes).map_err(|e| anyhow!("bincode decoding error: {}", e))
+            }
+            #[cfg(feature = "msgpack")]
+            SerializationBackend::Msgpack => rmp_serde::from_slice(bytes)
+                .map_err(|e| anyhow!("MessagePack decoding error: {}", e)),
+        }
+    }
+
+    /// Decode a value that may borrow strings and byte slices from `bytes`
+    pub fn decode_borrowed<'a, T: Deserialize<'a>>(self, bytes: &'a [u8]) -> Result<T> {
+        match self {
+            SerializationBackend::Bincode => {
+                bincode::deserialize(bytes).map_err(|e| anyhow!("bincode decoding error: {}", e))
+            }
+            #[cfg(feature = "msgpack")]
+            SerializationBackend::Msgpack => rmp_serde::from_slice(bytes)
+                .map_err(|e| anyhow!("MessagePack decoding error: {}", e)),
+        }
+    }
+}
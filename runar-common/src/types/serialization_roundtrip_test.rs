@@ -0,0 +1,76 @@
+// Property-based round-trip tests for the ArcValue binary format
+//
+// Every default registered type must come back unchanged from
+// serialize_value -> deserialize_value -> as_type, whatever the value.
+
+use super::arc_value::{ArcValue, SerializerRegistry};
+use crate::logging::{Component, Logger};
+use proptest::prelude::*;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+fn registry() -> SerializerRegistry {
+    SerializerRegistry::with_defaults(Arc::new(Logger::new_root(
+        Component::Custom("Test"),
+        "proptest",
+    )))
+}
+
+/// Serialize `value` and read it back as a fresh, lazily decoded ArcValue
+fn round_trip(registry: &SerializerRegistry, value: &ArcValue) -> ArcValue {
+    let bytes = registry.serialize_value(value).expect("serialize");
+    registry.deserialize_value(bytes).expect("deserialize")
+}
+
+#[test]
+fn test_null_serializes_to_single_marker_byte() {
+    let registry = registry();
+    let bytes = registry.serialize_value(&ArcValue::null()).unwrap();
+    assert_eq!(&*bytes, &[0x05]);
+    assert!(registry.deserialize_value(bytes).unwrap().is_null());
+}
+
+proptest! {
+    #[test]
+    fn prop_i32_round_trip(v in any::<i32>()) {
+        let mut decoded = round_trip(&registry(), &ArcValue::new_primitive(v));
+        prop_assert_eq!(decoded.as_type::<i32>().unwrap(), v);
+    }
+
+    #[test]
+    fn prop_i64_round_trip(v in any::<i64>()) {
+        let mut decoded = round_trip(&registry(), &ArcValue::new_primitive(v));
+        prop_assert_eq!(decoded.as_type::<i64>().unwrap(), v);
+    }
+
+    // Compared bit for bit so NaN payloads and signed zeros are covered too
+    #[test]
+    fn prop_f64_round_trip(v in any::<f64>()) {
+        let mut decoded = round_trip(&registry(), &ArcValue::new_primitive(v));
+        prop_assert_eq!(decoded.as_type::<f64>().unwrap().to_bits(), v.to_bits());
+    }
+
+    #[test]
+    fn prop_bool_round_trip(v in any::<bool>()) {
+        let mut decoded = round_trip(&registry(), &ArcValue::new_primitive(v));
+        prop_assert_eq!(decoded.as_type::<bool>().unwrap(), v);
+    }
+
+    #[test]
+    fn prop_string_round_trip(v in any::<String>()) {
+        let mut decoded = round_trip(&registry(), &ArcValue::new_primitive(v.clone()));
+        prop_assert_eq!(decoded.as_type::<String>().unwrap(), v);
+    }
+
+    #[test]
+    fn prop_string_list_round_trip(v in prop::collection::vec(any::<String>(), 0..16)) {
+        let mut decoded = round_trip(&registry(), &ArcValue::new_list(v.clone()));
+        prop_assert_eq!(decoded.as_type::<Vec<String>>().unwrap(), v);
+    }
+
+    #[test]
+    fn prop_i64_map_round_trip(v in prop::collection::hash_map(any::<String>(), any::<i64>(), 0..16)) {
+        let mut decoded = round_trip(&registry(), &ArcValue::new_map(v.clone()));
+        prop_assert_eq!(decoded.as_type::<HashMap<String, i64>>().unwrap(), v);
+    }
+}
@@ -0,0 +1,94 @@
+// runar_common/src/types/type_descriptor.rs
+//
+// Runtime description of the type an ArcValue holds
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use super::arc_value::ValueCategory;
+
+/// Describes the type held by an `ArcValue`
+///
+/// INTENTION: Keep the category and the Rust type of a value together, so
+/// that logs and error messages can show what a value is without matching on
+/// `ValueCategory` and the erased type name separately. Lists also describe
+/// their element type.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TypeDescriptor {
+    pub category: ValueCategory,
+    pub rust_type_name: String,
+    /// Element type of a list, taken from the list's type name
+    pub element_type: Option<Box<TypeDescriptor>>,
+}
+
+impl TypeDescriptor {
+    /// Describe a type from its category and full Rust type name
+    ///
+    /// For lists the element type is read from the generic argument of the
+    /// type name, e.g. `Vec<String>` has `String` elements.
+    pub fn new(category: ValueCategory, rust_type_name: impl Into<String>) -> Self {
+        let rust_type_name = rust_type_name.into();
+        let element_type = match category {
+            ValueCategory::List => generic_argument(&rust_type_name)
+                .map(|element| Box::new(TypeDescriptor::from_type_name(element))),
+            _ => None,
+        };
+        Self {
+            category,
+            rust_type_name,
+            element_type,
+        }
+    }
+
+    /// Describe a type from its Rust type name alone, guessing the category
+    ///
+    /// Unknown types, including `ArcValue` itself, are described as structs.
+    pub fn from_type_name(rust_type_name: &str) -> Self {
+        Self::new(category_of(rust_type_name), rust_type_name)
+    }
+}
+
+impl fmt::Display for TypeDescriptor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}({})", self.category, self.rust_type_name)?;
+        if let Some(element) = &self.element_type {
+            write!(f, " of {element}")?;
+        }
+        Ok(())
+    }
+}
+
+/// The type name with its module path removed
+fn base_name(rust_type_name: &str) -> &str {
+    let outer = rust_type_name
+        .split('<')
+        .next()
+        .unwrap_or(rust_type_name)
+        .trim();
+    outer.rsplit("::").next().unwrap_or(outer)
+}
+
+/// The generic argument of a single-argument type such as `Vec<T>`
+fn generic_argument(rust_type_name: &str) -> Option<&str> {
+    let start = rust_type_name.find('<')?;
+    let end = rust_type_name.rfind('>')?;
+    (start < end).then(|| rust_type_name[start + 1..end].trim())
+}
+
+fn category_of(rust_type_name: &str) -> ValueCategory {
+    match base_name(rust_type_name) {
+        "()" => ValueCategory::Null,
+        "Vec" | "VecDeque" if generic_argument(rust_type_name) == Some("u8") => {
+            ValueCategory::Bytes
+        }
+        "Vec" | "VecDeque" | "HashSet" | "BTreeSet" => ValueCategory::List,
+        "HashMap" | "BTreeMap" => ValueCategory::Map,
+        "Value" if rust_type_name.starts_with("serde_json") => ValueCategory::Json,
+        "bool" | "char" | "String" | "str" | "&str" | "i8" | "i16" | "i32" | "i64" | "i128"
+        | "isize" | "u8" | "u16" | "u32" | "u64" | "u128" | "usize" | "f32" | "f64" => {
+            ValueCategory::Primitive
+        }
+        _ => ValueCategory::Struct,
+    }
+}
@@ -0,0 +1,153 @@
+// Typed JSON conversion of ArcValue
+//
+// INTENTION: Let values cross external JSON APIs without losing their type.
+// The `Serialize` impl of ArcValue only produces JSON for values that still
+// carry their JSON serializer, which values decoded from the wire do not. The
+// conversions below go through the SerializerRegistry instead, so they work
+// for every registered type, and keep the type name of the binary header next
+// to the JSON payload:
+//
+// {"category": "Struct", "type_name": "my_crate::Order", "value": {...}}
+//
+// The binary format and the standard serde impls are left unchanged.
+
+use std::sync::Arc;
+
+use anyhow::{anyhow, Result};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine as _;
+use serde_json::{json, Value as JsonValue};
+
+use super::arc_value::{ArcValue, SerializerRegistry, ValueCategory};
+
+impl ArcValue {
+    /// Convert the value to JSON tagged with its category and type name
+    ///
+    /// Struct, primitive, list and map payloads are converted with the JSON
+    /// codec of their registered type, lists and maps of values element by
+    /// element, bytes are encoded as base64 and JSON values are embedded as
+    /// they are.
+    pub fn to_typed_json(&self, registry: &SerializerRegistry) -> Result<JsonValue> {
+        let category = serde_json::to_value(self.category)?;
+        match self.category {
+            ValueCategory::Null => {
+                return Ok(match self.null_type_name() {
+                    Some(type_name) => json!({ "category": category, "type_name": type_name }),
+                    None => json!({ "category": category }),
+                })
+            }
+            ValueCategory::Json => {
+                let value = self.clone().as_type_ref::<JsonValue>()?;
+                return Ok(json!({ "category": category, "value": *value }));
+            }
+            // Lists and maps of values are converted element by element
+            ValueCategory::List => {
+                if let Ok(items) = self.clone().as_list_ref::<ArcValue>() {
+                    let items = items
+                        .iter()
+                        .map(|item| item.to_typed_json(registry))
+                        .collect::<Result<Vec<_>>>()?;
+                    return Ok(json!({ "category": category, "value": items }));
+                }
+            }
+            ValueCategory::Map => {
+                if let Ok(entries) = self.clone().as_map_ref::<String, ArcValue>() {
+                    let entries = entries
+                        .iter()
+                        .map(|(key, value)| Ok((key.clone(), value.to_typed_json(registry)?)))
+                        .collect::<Result<serde_json::Map<_, _>>>()?;
+                    return Ok(json!({ "category": category, "value": entries }));
+                }
+            }
+            _ => {}
+        }
+
+        let bytes = registry.serialize_value(self)?;
+        let (_, type_name, data) = registry.extract_header_from_slice(&bytes)?;
+        let value = match self.category {
+            ValueCategory::Bytes => JsonValue::String(STANDARD.encode(data)),
+            _ => {
+                let codec = registry
+                    .json_codec(&type_name)
+                    .ok_or_else(|| anyhow!("No JSON codec registered for type: {}", type_name))?;
+                (codec.to_json)(data)?
+            }
+        };
+        Ok(json!({ "category": category, "type_name": type_name, "value": value }))
+    }
+
+    /// Rebuild a value from the output of `to_typed_json`
+    ///
+    /// The value is re-encoded with the registry's backend, so it behaves
+    /// exactly like a value of the same type received over the network.
+    pub fn from_typed_json(json: JsonValue, registry: &SerializerRegistry) -> Result<ArcValue> {
+        let JsonValue::Object(mut fields) = json else {
+            return Err(anyhow!("Typed JSON value must be an object"));
+        };
+        let category: ValueCategory = serde_json::from_value(
+            fields
+                .remove("category")
+                .ok_or_else(|| anyhow!("Typed JSON value has no category"))?,
+        )?;
+        let value = fields.remove("value").unwrap_or(JsonValue::Null);
+        let type_name = match fields.remove("type_name") {
+            Some(JsonValue::String(type_name)) => Some(type_name),
+            _ => None,
+        };
+
+        let marker = match category {
+            ValueCategory::Null => {
+                return Ok(ArcValue::null_with_type_name(type_name.unwrap_or_default()))
+            }
+            ValueCategory::Json => return Ok(ArcValue::new_json(value)),
+            ValueCategory::Bytes => {
+                let JsonValue::String(encoded) = value else {
+                    return Err(anyhow!("Typed JSON bytes must be a base64 string"));
+                };
+                return Ok(ArcValue::from_bytes(STANDARD.decode(encoded)?));
+            }
+            ValueCategory::List if type_name.is_none() => {
+                let JsonValue::Array(items) = value else {
+                    return Err(anyhow!("Typed JSON list must be an array"));
+                };
+                let items = items
+                    .into_iter()
+                    .map(|item| ArcValue::from_typed_json(item, registry))
+                    .collect::<Result<Vec<_>>>()?;
+                return Ok(ArcValue::new_list(items));
+            }
+            ValueCategory::Map if type_name.is_none() => {
+                let JsonValue::Object(entries) = value else {
+                    return Err(anyhow!("Typed JSON map must be an object"));
+                };
+                let entries = entries
+                    .into_iter()
+                    .map(|(key, value)| Ok((key, ArcValue::from_typed_json(value, registry)?)))
+                    .collect::<Result<std::collections::HashMap<_, _>>>()?;
+                return Ok(ArcValue::new_map(entries));
+            }
+            ValueCategory::Primitive => 0x01,
+            ValueCategory::List => 0x02,
+            ValueCategory::Map => 0x03,
+            ValueCategory::Struct => 0x04,
+        };
+        let type_name = type_name
+            .ok_or_else(|| anyhow!("Typed JSON value of {category:?} has no type_name"))?;
+        let codec = registry
+            .json_codec(&type_name)
+            .ok_or_else(|| anyhow!("No JSON codec registered for type: {}", type_name))?;
+        let data = (codec.from_json)(value)?;
+
+        // Same header as `SerializerRegistry::serialize_value`
+        let type_bytes = type_name.as_bytes();
+        if type_bytes.len() > 255 {
+            return Err(anyhow!("Type name too long: {}", type_name));
+        }
+        let mut bytes = Vec::with_capacity(2 + type_bytes.len() + data.len());
+        bytes.push(marker);
+        bytes.push(type_bytes.len() as u8);
+        bytes.extend_from_slice(type_bytes);
+        bytes.extend_from_slice(&data);
+        registry.deserialize_value(Arc::from(bytes))
+    }
+}
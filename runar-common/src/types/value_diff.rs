@@ -0,0 +1,239 @@
+// runar_common/src/types/value_diff.rs
+//
+// Structural comparison of two ArcValues.
+
+use std::collections::HashMap;
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+
+use super::arc_value::{ArcValue, ValueCategory};
+
+/// Description of the changes between two values, as returned by `ArcValue::diff`
+///
+/// INTENTION: Give state synchronization and audit logging a description of
+/// what changed that can be inspected or sent as an event payload. Maps are
+/// compared key by key and nested changes are described recursively; every
+/// other value is compared as a whole.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ValueDiff {
+    /// Both values have the same content
+    NoDiff,
+    /// The values belong to different categories
+    TypeChanged {
+        from: ValueCategory,
+        to: ValueCategory,
+    },
+    /// A primitive, struct, bytes or JSON value was replaced
+    PrimitiveChanged { from: ArcValue, to: ArcValue },
+    /// Entries were added to, removed from or changed in a map
+    MapDiff {
+        added: HashMap<String, ArcValue>,
+        removed: Vec<String>,
+        changed: HashMap<String, ValueDiff>,
+    },
+    /// Elements were added to or removed from a list
+    ///
+    /// `removed_indices` refer to the old list and `added` holds the new
+    /// elements in order; elements kept in the same relative order are not
+    /// reported.
+    ListDiff {
+        added: Vec<ArcValue>,
+        removed_indices: Vec<usize>,
+    },
+}
+
+impl ValueDiff {
+    /// Check whether the compared values were equal
+    pub fn is_no_diff(&self) -> bool {
+        matches!(self, ValueDiff::NoDiff)
+    }
+}
+
+impl ArcValue {
+    /// Describe the changes needed to turn this value into `other`
+    ///
+    /// Maps must hold `ArcValue` entries (or be JSON objects) and lists must
+    /// hold `ArcValue` elements; lazy values are deserialized to inspect them.
+    /// Leaf values are compared by content, through their JSON form or, for
+    /// two lazy values, their serialized bytes. Returns an error for values
+    /// that cannot be inspected this way.
+    pub fn diff(&self, other: &ArcValue) -> Result<ValueDiff> {
+        if self.category != other.category {
+            return Ok(ValueDiff::TypeChanged {
+                from: self.category,
+                to: other.category,
+            });
+        }
+
+        match self.category {
+            ValueCategory::Null => Ok(ValueDiff::NoDiff),
+            ValueCategory::Map => diff_maps(map_entries(self)?, map_entries(other)?),
+            ValueCategory::List => diff_lists(&list_elements(self)?, &list_elements(other)?),
+            ValueCategory::Json => {
+                let (from_json, to_json) = (
+                    self.clone().to_json_value()?,
+                    other.clone().to_json_value()?,
+                );
+                match (from_json, to_json) {
+                    (JsonValue::Object(from), JsonValue::Object(to)) => {
+                        diff_maps(json_entries(from), json_entries(to))
+                    }
+                    (from_json, to_json) if from_json == to_json => Ok(ValueDiff::NoDiff),
+                    _ => Ok(self.replaced_by(other)),
+                }
+            }
+            ValueCategory::Primitive | ValueCategory::Struct | ValueCategory::Bytes => {
+                if same_content(self, other)? {
+                    Ok(ValueDiff::NoDiff)
+                } else {
+                    Ok(self.replaced_by(other))
+                }
+            }
+        }
+    }
+
+    fn replaced_by(&self, other: &ArcValue) -> ValueDiff {
+        ValueDiff::PrimitiveChanged {
+            from: self.clone(),
+            to: other.clone(),
+        }
+    }
+}
+
+/// Compare two values of the same category by content
+fn same_content(a: &ArcValue, b: &ArcValue) -> Result<bool> {
+    match (&a.value, &b.value) {
+        (None, None) => return Ok(true),
+        (Some(a_value), Some(b_value)) if a_value.is_lazy && b_value.is_lazy => {
+            let (a_lazy, b_lazy) = (a_value.get_lazy_data()?, b_value.get_lazy_data()?);
+            return Ok(a_lazy.type_name == b_lazy.type_name
+                && a_lazy.original_buffer[a_lazy.start_offset..a_lazy.end_offset]
+                    == b_lazy.original_buffer[b_lazy.start_offset..b_lazy.end_offset]);
+        }
+        _ => {}
+    }
+
+    // A list or map may hold nested values, so compare structurally
+    if matches!(
+        a.category,
+        ValueCategory::List | ValueCategory::Map | ValueCategory::Json
+    ) {
+        return Ok(a.diff(b)?.is_no_diff());
+    }
+
+    let a_json = a
+        .clone()
+        .to_json_value()
+        .map_err(|e| anyhow!("Cannot compare value: {e}"))?;
+    let b_json = b
+        .clone()
+        .to_json_value()
+        .map_err(|e| anyhow!("Cannot compare value: {e}"))?;
+    Ok(a_json == b_json)
+}
+
+fn map_entries(value: &ArcValue) -> Result<HashMap<String, ArcValue>> {
+    let mut value = value.clone();
+    let entries = value
+        .as_map_ref::<String, ArcValue>()
+        .map_err(|e| anyhow!("Cannot diff map: {e}"))?;
+    Ok((*entries).clone())
+}
+
+fn list_elements(value: &ArcValue) -> Result<Vec<ArcValue>> {
+    let mut value = value.clone();
+    let elements = value
+        .as_list_ref::<ArcValue>()
+        .map_err(|e| anyhow!("Cannot diff list: {e}"))?;
+    Ok((*elements).clone())
+}
+
+fn json_entries(object: serde_json::Map<String, JsonValue>) -> HashMap<String, ArcValue> {
+    object
+        .into_iter()
+        .map(|(key, value)| (key, ArcValue::from_json(value)))
+        .collect()
+}
+
+fn diff_maps(
+    from: HashMap<String, ArcValue>,
+    mut to: HashMap<String, ArcValue>,
+) -> Result<ValueDiff> {
+    let mut removed = Vec::new();
+    let mut changed = HashMap::new();
+
+    for (key, from_value) in from {
+        match to.remove(&key) {
+            Some(to_value) => {
+                let diff = from_value.diff(&to_value)?;
+                if !diff.is_no_diff() {
+                    changed.insert(key, diff);
+                }
+            }
+            None => removed.push(key),
+        }
+    }
+
+    // Whatever is left in `to` was not present before
+    let added = to;
+    if added.is_empty() && removed.is_empty() && changed.is_empty() {
+        return Ok(ValueDiff::NoDiff);
+    }
+    removed.sort();
+    Ok(ValueDiff::MapDiff {
+        added,
+        removed,
+        changed,
+    })
+}
+
+/// Diff two lists using their longest common subsequence
+fn diff_lists(from: &[ArcValue], to: &[ArcValue]) -> Result<ValueDiff> {
+    let mut equal = vec![vec![false; to.len()]; from.len()];
+    for (i, from_value) in from.iter().enumerate() {
+        for (j, to_value) in to.iter().enumerate() {
+            equal[i][j] =
+                from_value.category == to_value.category && same_content(from_value, to_value)?;
+        }
+    }
+
+    // lcs[i][j] is the length of the common subsequence of from[i..] and to[j..]
+    let mut lcs = vec![vec![0usize; to.len() + 1]; from.len() + 1];
+    for i in (0..from.len()).rev() {
+        for j in (0..to.len()).rev() {
+            lcs[i][j] = if equal[i][j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut added = Vec::new();
+    let mut removed_indices = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < from.len() && j < to.len() {
+        if equal[i][j] {
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            removed_indices.push(i);
+            i += 1;
+        } else {
+            added.push(to[j].clone());
+            j += 1;
+        }
+    }
+    removed_indices.extend(i..from.len());
+    added.extend(to[j..].iter().cloned());
+
+    if added.is_empty() && removed_indices.is_empty() {
+        return Ok(ValueDiff::NoDiff);
+    }
+    Ok(ValueDiff::ListDiff {
+        added,
+        removed_indices,
+    })
+}
@@ -0,0 +1,212 @@
+// runar_common/src/types/value_ord.rs
+//
+// Total ordering of ArcValues.
+
+use std::cmp::Ordering;
+
+use serde_json::Value as JsonValue;
+
+use super::arc_value::{ArcValue, ValueCategory};
+
+/// A primitive value decoded for comparison
+enum PrimitiveKey {
+    Bool(bool),
+    Int(i128),
+    Float(f64),
+    Str(String),
+}
+
+impl PrimitiveKey {
+    /// Rank of the kind of primitive, for primitives of different kinds
+    fn rank(&self) -> u8 {
+        match self {
+            PrimitiveKey::Bool(_) => 0,
+            PrimitiveKey::Int(_) | PrimitiveKey::Float(_) => 1,
+            PrimitiveKey::Str(_) => 2,
+        }
+    }
+
+    fn cmp(&self, other: &PrimitiveKey) -> Ordering {
+        match (self, other) {
+            (PrimitiveKey::Bool(a), PrimitiveKey::Bool(b)) => a.cmp(b),
+            (PrimitiveKey::Int(a), PrimitiveKey::Int(b)) => a.cmp(b),
+            (PrimitiveKey::Float(a), PrimitiveKey::Float(b)) => a.total_cmp(b),
+            // An integer sorts before a float of the same value
+            (PrimitiveKey::Int(a), PrimitiveKey::Float(b)) => {
+                (*a as f64).total_cmp(b).then(Ordering::Less)
+            }
+            (PrimitiveKey::Float(a), PrimitiveKey::Int(b)) => {
+                a.total_cmp(&(*b as f64)).then(Ordering::Greater)
+            }
+            (PrimitiveKey::Str(a), PrimitiveKey::Str(b)) => a.cmp(b),
+            _ => self.rank().cmp(&other.rank()),
+        }
+    }
+}
+
+impl ArcValue {
+    /// Compare two values by content
+    ///
+    /// Values of different categories sort Null < Primitive < List < Map <
+    /// Struct < Bytes < Json. Primitives sort booleans before numbers before
+    /// strings; integers use integer ordering, floats `f64::total_cmp` (so NaN
+    /// has a place in the order) and strings lexicographic order. Lists, maps
+    /// and structs are compared through their JSON form: element by element,
+    /// and for maps and structs entry by entry in key order. Values that cannot
+    /// be decoded are ordered by type name and then by identity.
+    pub fn total_cmp(&self, other: &ArcValue) -> Ordering {
+        let by_category = category_rank(self.category).cmp(&category_rank(other.category));
+        if by_category != Ordering::Equal {
+            return by_category;
+        }
+
+        let by_content = match self.category {
+            ValueCategory::Null => Some(Ordering::Equal),
+            ValueCategory::Primitive => match (primitive_key(self), primitive_key(other)) {
+                (Some(a), Some(b)) => Some(a.cmp(&b)),
+                _ => None,
+            },
+            ValueCategory::Bytes => match (self.bytes_slice(), other.bytes_slice()) {
+                (Ok(a), Ok(b)) => Some(a.cmp(b)),
+                _ => None,
+            },
+            ValueCategory::List
+            | ValueCategory::Map
+            | ValueCategory::Struct
+            | ValueCategory::Json => {
+                match (self.clone().to_json_value(), other.clone().to_json_value()) {
+                    (Ok(a), Ok(b)) => Some(cmp_json(&a, &b)),
+                    _ => None,
+                }
+            }
+        };
+        by_content.unwrap_or_else(|| cmp_undecoded(self, other))
+    }
+}
+
+impl PartialOrd for ArcValue {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for ArcValue {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.total_cmp(other)
+    }
+}
+
+fn category_rank(category: ValueCategory) -> u8 {
+    match category {
+        ValueCategory::Null => 0,
+        ValueCategory::Primitive => 1,
+        ValueCategory::List => 2,
+        ValueCategory::Map => 3,
+        ValueCategory::Struct => 4,
+        ValueCategory::Bytes => 5,
+        ValueCategory::Json => 6,
+    }
+}
+
+/// Decode a primitive of one of the standard scalar types
+fn primitive_key(value: &ArcValue) -> Option<PrimitiveKey> {
+    let erased = value.value.as_ref()?;
+    let type_name = if erased.is_lazy {
+        erased.get_lazy_data().ok()?.type_name.clone()
+    } else {
+        erased.type_name().to_string()
+    };
+
+    let mut value = value.clone();
+    let key = match type_name.rsplit("::").next().unwrap_or(&type_name) {
+        "bool" => PrimitiveKey::Bool(value.as_type::<bool>().ok()?),
+        "i8" => PrimitiveKey::Int(value.as_type::<i8>().ok()?.into()),
+        "i16" => PrimitiveKey::Int(value.as_type::<i16>().ok()?.into()),
+        "i32" => PrimitiveKey::Int(value.as_type::<i32>().ok()?.into()),
+        "i64" => PrimitiveKey::Int(value.as_type::<i64>().ok()?.into()),
+        "i128" => PrimitiveKey::Int(value.as_type::<i128>().ok()?),
+        "isize" => PrimitiveKey::Int(value.as_type::<isize>().ok()? as i128),
+        "u8" => PrimitiveKey::Int(value.as_type::<u8>().ok()?.into()),
+        "u16" => PrimitiveKey::Int(value.as_type::<u16>().ok()?.into()),
+        "u32" => PrimitiveKey::Int(value.as_type::<u32>().ok()?.into()),
+        "u64" => PrimitiveKey::Int(value.as_type::<u64>().ok()?.into()),
+        "usize" => PrimitiveKey::Int(value.as_type::<usize>().ok()? as i128),
+        "f32" => PrimitiveKey::Float(value.as_type::<f32>().ok()?.into()),
+        "f64" => PrimitiveKey::Float(value.as_type::<f64>().ok()?),
+        "String" | "str" | "&str" => PrimitiveKey::Str(value.as_type::<String>().ok()?),
+        "char" => PrimitiveKey::Str(value.as_type::<char>().ok()?.to_string()),
+        _ => return None,
+    };
+    Some(key)
+}
+
+/// Order JSON values: null < bool < number < string < array < object
+fn cmp_json(a: &JsonValue, b: &JsonValue) -> Ordering {
+    fn rank(value: &JsonValue) -> u8 {
+        match value {
+            JsonValue::Null => 0,
+            JsonValue::Bool(_) => 1,
+            JsonValue::Number(_) => 2,
+            JsonValue::String(_) => 3,
+            JsonValue::Array(_) => 4,
+            JsonValue::Object(_) => 5,
+        }
+    }
+    fn number_key(number: &serde_json::Number) -> PrimitiveKey {
+        match (number.as_i64(), number.as_u64()) {
+            (Some(i), _) => PrimitiveKey::Int(i.into()),
+            (_, Some(u)) => PrimitiveKey::Int(u.into()),
+            _ => PrimitiveKey::Float(number.as_f64().unwrap_or(f64::NAN)),
+        }
+    }
+
+    match (a, b) {
+        (JsonValue::Bool(a), JsonValue::Bool(b)) => a.cmp(b),
+        (JsonValue::Number(a), JsonValue::Number(b)) => number_key(a).cmp(&number_key(b)),
+        (JsonValue::String(a), JsonValue::String(b)) => a.cmp(b),
+        (JsonValue::Array(a), JsonValue::Array(b)) => {
+            for (a_value, b_value) in a.iter().zip(b) {
+                let ordering = cmp_json(a_value, b_value);
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+            }
+            a.len().cmp(&b.len())
+        }
+        (JsonValue::Object(a), JsonValue::Object(b)) => {
+            let mut a_entries: Vec<_> = a.iter().collect();
+            let mut b_entries: Vec<_> = b.iter().collect();
+            a_entries.sort_by(|x, y| x.0.cmp(y.0));
+            b_entries.sort_by(|x, y| x.0.cmp(y.0));
+            for ((a_key, a_value), (b_key, b_value)) in a_entries.iter().zip(&b_entries) {
+                let ordering = a_key.cmp(b_key).then_with(|| cmp_json(a_value, b_value));
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+            }
+            a_entries.len().cmp(&b_entries.len())
+        }
+        _ => rank(a).cmp(&rank(b)),
+    }
+}
+
+/// Fallback for values whose content cannot be decoded
+///
+/// Orders by type name, then by serialized bytes for two lazy values and by
+/// identity otherwise, which is consistent with `ArcValue`'s equality.
+fn cmp_undecoded(a: &ArcValue, b: &ArcValue) -> Ordering {
+    let (a_value, b_value) = match (&a.value, &b.value) {
+        (Some(a_value), Some(b_value)) => (a_value, b_value),
+        (a_value, b_value) => return a_value.is_some().cmp(&b_value.is_some()),
+    };
+    if let (Ok(a_lazy), Ok(b_lazy)) = (a_value.get_lazy_data(), b_value.get_lazy_data()) {
+        return a_lazy.type_name.cmp(&b_lazy.type_name).then_with(|| {
+            a_lazy.original_buffer[a_lazy.start_offset..a_lazy.end_offset]
+                .cmp(&b_lazy.original_buffer[b_lazy.start_offset..b_lazy.end_offset])
+        });
+    }
+    a_value
+        .type_name()
+        .cmp(b_value.type_name())
+        .then_with(|| (a_value.as_ptr() as usize).cmp(&(b_value.as_ptr() as usize)))
+}
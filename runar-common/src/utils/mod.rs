@@ -3,11 +3,15 @@
 // Common utility functions and helpers
 
 // Value converters and extractors
+#[cfg(feature = "type-erasure")]
 pub mod value_converters;
 
 // Logging utilities
+#[cfg(feature = "logging")]
 pub mod logging;
 
 // Re-export everything from submodules
+#[cfg(feature = "logging")]
 pub use logging::*;
+#[cfg(feature = "type-erasure")]
 pub use value_converters::*;
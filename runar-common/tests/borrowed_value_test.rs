@@ -0,0 +1,135 @@
+// Tests for ArcValue::as_type_borrowed
+//
+// Borrowed reads must point into the buffer the value was deserialized from
+// and must not allocate.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+use anyhow::Result;
+use runar_common::types::{ArcValue, SerializerRegistry};
+use serde::{Deserialize, Serialize};
+
+mod common;
+use common::create_test_registry;
+
+/// Counts the allocations made by the current thread while counting is enabled
+struct CountingAllocator;
+
+thread_local! {
+    static COUNTING: Cell<bool> = const { Cell::new(false) };
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        if COUNTING.with(Cell::get) {
+            ALLOCATIONS.with(|count| count.set(count.get() + 1));
+        }
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+/// Run `f` and return its result with the number of allocations it made
+fn count_allocations<R>(f: impl FnOnce() -> R) -> (R, usize) {
+    ALLOCATIONS.with(|count| count.set(0));
+    COUNTING.with(|counting| counting.set(true));
+    let result = f();
+    COUNTING.with(|counting| counting.set(false));
+    (result, ALLOCATIONS.with(Cell::get))
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Blob {
+    name: String,
+    data: Vec<u8>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BlobRef<'a> {
+    name: &'a str,
+    data: &'a [u8],
+}
+
+/// A test registry with the types of these tests registered
+fn registry_with_test_types() -> SerializerRegistry {
+    let mut registry = create_test_registry();
+    registry.register::<Blob>().unwrap();
+    registry
+}
+
+fn contains(buffer: &[u8], slice: &[u8]) -> bool {
+    let range = buffer.as_ptr_range();
+    range.contains(&slice.as_ptr()) && slice.len() <= buffer.len()
+}
+
+#[test]
+fn test_borrowed_lazy_struct_is_zero_copy() -> Result<()> {
+    let registry = registry_with_test_types();
+    let blob = Blob {
+        name: "payload".to_string(),
+        data: vec![7u8; 1024 * 1024],
+    };
+    let bytes = registry.serialize_value(&ArcValue::from_struct(blob))?;
+    let value = registry.deserialize_value(bytes)?;
+    let buffer = value
+        .value
+        .as_ref()
+        .unwrap()
+        .lazy_data_ref()?
+        .original_buffer
+        .clone();
+
+    let (borrowed, allocations) = count_allocations(|| value.as_type_borrowed::<BlobRef>());
+    let borrowed = borrowed?;
+    assert_eq!(allocations, 0);
+    assert_eq!(borrowed.name, "payload");
+    assert_eq!(borrowed.data.len(), 1024 * 1024);
+    assert!(borrowed.data.iter().all(|byte| *byte == 7));
+    assert!(contains(&buffer, borrowed.data));
+    assert!(contains(&buffer, borrowed.name.as_bytes()));
+
+    // The value stays lazy and can still be read as its owned type
+    assert!(value.value.as_ref().unwrap().is_lazy);
+    let mut owned_value = value.clone();
+    assert_eq!(owned_value.as_type::<Blob>()?.data.len(), 1024 * 1024);
+    Ok(())
+}
+
+#[test]
+fn test_borrowed_lazy_string() -> Result<()> {
+    let registry = registry_with_test_types();
+    let text = "x".repeat(4096);
+    let bytes = registry.serialize_value(&ArcValue::new_primitive(text.clone()))?;
+    let value = registry.deserialize_value(bytes)?;
+
+    let (borrowed, allocations) = count_allocations(|| value.as_type_borrowed::<&str>());
+    assert_eq!(borrowed?, text);
+    assert_eq!(allocations, 0);
+    Ok(())
+}
+
+#[test]
+fn test_borrowed_eager_values() -> Result<()> {
+    // Eager strings and bytes are borrowed from the stored value
+    let text = ArcValue::new_primitive("hello".to_string());
+    let borrowed: &str = text.as_type_borrowed()?;
+    assert_eq!(borrowed, "hello");
+
+    let bytes = ArcValue::new_bytes(vec![1, 2, 3]);
+    let borrowed: &[u8] = bytes.as_type_borrowed()?;
+    assert_eq!(borrowed, &[1, 2, 3]);
+
+    // Other eager values cannot be borrowed
+    let number = ArcValue::new_primitive(42i64);
+    assert!(number.as_type_borrowed::<&str>().is_err());
+    assert!(ArcValue::null().as_type_borrowed::<&str>().is_err());
+    Ok(())
+}
@@ -0,0 +1,56 @@
+// Tests for ArcValue::try_coerce
+//
+// Numbers must be readable as a wider type than the one they were stored as,
+// both for eager values and for values received over the wire.
+
+use anyhow::Result;
+use runar_common::types::ArcValue;
+use serde_json::json;
+
+mod common;
+use common::create_test_registry;
+
+#[test]
+fn test_numeric_widening() -> Result<()> {
+    let mut value = ArcValue::new_primitive(42i32);
+    assert_eq!(value.try_coerce::<i64>()?, 42i64);
+    assert_eq!(value.try_coerce::<f64>()?, 42.0);
+    // The stored value is left untouched
+    assert_eq!(value.as_type::<i32>()?, 42);
+
+    let mut value = ArcValue::new_primitive(7i64);
+    assert_eq!(value.try_coerce::<f64>()?, 7.0);
+
+    let mut value = ArcValue::new_primitive(1.5f32);
+    assert_eq!(value.try_coerce::<f64>()?, 1.5);
+
+    // Direct conversions need no widening
+    let mut value = ArcValue::new_primitive("text".to_string());
+    assert_eq!(value.try_coerce::<String>()?, "text");
+    Ok(())
+}
+
+#[test]
+fn test_narrowing_is_refused() {
+    let mut value = ArcValue::new_primitive(42i64);
+    assert!(value.try_coerce::<i32>().is_err());
+
+    let mut value = ArcValue::new_primitive(1.5f64);
+    assert!(value.try_coerce::<f32>().is_err());
+    assert!(value.try_coerce::<i64>().is_err());
+
+    let mut value = ArcValue::new_primitive("42".to_string());
+    assert!(value.try_coerce::<i64>().is_err());
+}
+
+#[test]
+fn test_widening_lazy_and_json_values() -> Result<()> {
+    let registry = create_test_registry();
+    let bytes = registry.serialize_value(&ArcValue::new_primitive(42i32))?;
+    let mut lazy = registry.deserialize_value(bytes)?;
+    assert_eq!(lazy.try_coerce::<i64>()?, 42i64);
+
+    let mut from_json = ArcValue::from_json(json!(42));
+    assert_eq!(from_json.try_coerce::<f64>()?, 42.0);
+    Ok(())
+}
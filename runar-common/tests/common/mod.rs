@@ -0,0 +1,16 @@
+// Helpers shared by the integration tests
+//
+// Each test file that needs them declares `mod common;`.
+
+use std::sync::Arc;
+
+use runar_common::logging::{Component, Logger};
+use runar_common::types::SerializerRegistry;
+
+/// A serializer registry with the default types, logging as a test node
+pub fn create_test_registry() -> SerializerRegistry {
+    SerializerRegistry::with_defaults(Arc::new(Logger::new_root(
+        Component::Custom("Test"),
+        "test-node",
+    )))
+}
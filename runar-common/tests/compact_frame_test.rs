@@ -0,0 +1,130 @@
+// Tests for compact frames
+//
+// A sealed registry numbers its types alphabetically and can name the type
+// of a value by that ID instead of its full name. Registries sealed with the
+// same types agree on the IDs whatever the registration order, and read
+// compact frames like regular ones.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use anyhow::Result;
+use runar_common::types::ArcValue;
+use serde::{Deserialize, Serialize};
+
+mod common;
+use common::create_test_registry;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Reading {
+    sensor: String,
+    value: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Alert {
+    level: u8,
+}
+
+#[test]
+fn test_compact_frame_round_trip() -> Result<()> {
+    let mut registry = create_test_registry();
+    registry.register::<Reading>()?;
+    registry.seal();
+
+    let value = ArcValue::new_primitive(42i32);
+    let verbose = registry.serialize_value(&value)?;
+    let compact = registry.serialize_value_compact(&value)?;
+    // Category marker and a 2 byte type ID instead of the name
+    assert_eq!(compact.len(), verbose.len() - "i32".len() + 1);
+    assert_eq!(registry.deserialize_value(compact)?.as_type::<i32>()?, 42);
+
+    let reading = Reading {
+        sensor: "probe".to_string(),
+        value: 21.5,
+    };
+    let compact = registry.serialize_value_compact(&ArcValue::from_struct(reading.clone()))?;
+    let mut decoded = registry.deserialize_value(compact.clone())?;
+    assert_eq!(*decoded.as_struct_ref::<Reading>()?, reading);
+
+    // A lazy value keeps its compact header when serialized again
+    let lazy = registry.deserialize_value(compact.clone())?;
+    assert_eq!(registry.serialize_value_compact(&lazy)?, compact);
+
+    let map: HashMap<String, i64> = HashMap::from([("a".to_string(), 1)]);
+    let compact = registry.serialize_value_compact(&ArcValue::new_map(map.clone()))?;
+    let mut decoded = registry.deserialize_value(compact)?;
+    assert_eq!(*decoded.as_map_ref::<String, i64>()?, map);
+
+    let compact = registry.serialize_value_compact(&ArcValue::null_of_type::<i64>())?;
+    assert_eq!(compact.len(), 3);
+    assert!(registry
+        .deserialize_value(compact)?
+        .is_null_of_type::<i64>());
+    Ok(())
+}
+
+#[test]
+fn test_compact_frame_falls_back_to_type_name() -> Result<()> {
+    // An open registry has no type IDs yet
+    let registry = create_test_registry();
+    let value = ArcValue::new_primitive(7i64);
+    assert_eq!(
+        registry.serialize_value_compact(&value)?,
+        registry.serialize_value(&value)?
+    );
+    assert_eq!(registry.type_table_fingerprint(), None);
+
+    let mut sealed = create_test_registry();
+    sealed.seal();
+    let verbose = sealed.serialize_value(&value)?;
+    let compact = sealed.compact_frame(&verbose).expect("i64 has a type ID");
+    assert_eq!(
+        sealed
+            .deserialize_value(Arc::from(compact.clone()))?
+            .as_type::<i64>()?,
+        7
+    );
+    assert!(sealed.compact_frame(&compact).is_none());
+    assert!(sealed
+        .compact_frame(&sealed.serialize_value(&ArcValue::null())?)
+        .is_none());
+    // Bytes have no registered serializer, so no type ID
+    let bytes = sealed.serialize_value(&ArcValue::from_bytes(vec![1, 2, 3]))?;
+    assert!(sealed.compact_frame(&bytes).is_none());
+    Ok(())
+}
+
+#[test]
+fn test_type_ids_do_not_depend_on_registration_order() -> Result<()> {
+    let mut first = create_test_registry();
+    first.register::<Reading>()?;
+    first.register::<Alert>()?;
+    first.seal();
+
+    let mut second = create_test_registry();
+    second.register::<Alert>()?;
+    second.register::<Reading>()?;
+    second.seal();
+
+    assert!(first.type_table_fingerprint().is_some());
+    assert_eq!(
+        first.type_table_fingerprint(),
+        second.type_table_fingerprint()
+    );
+
+    let alert = Alert { level: 3 };
+    let compact = first.serialize_value_compact(&ArcValue::from_struct(alert.clone()))?;
+    let mut decoded = second.deserialize_value(compact)?;
+    assert_eq!(*decoded.as_struct_ref::<Alert>()?, alert);
+
+    // A registry with other types disagrees on the IDs
+    let mut other = create_test_registry();
+    other.register::<Alert>()?;
+    other.seal();
+    assert_ne!(
+        first.type_table_fingerprint(),
+        other.type_table_fingerprint()
+    );
+    Ok(())
+}
@@ -0,0 +1,88 @@
+// Tests for the ConcurrentSerializerRegistry
+//
+// Types registered while open must be readable from many threads once the
+// registry is sealed, and registrations must fail after sealing.
+
+use std::sync::Arc;
+
+use anyhow::Result;
+use runar_common::logging::{Component, Logger};
+use runar_common::types::{ArcValue, ConcurrentSerializerRegistry, SerializerRegistry};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Reading {
+    sensor: String,
+    value: f64,
+}
+
+fn logger() -> Arc<Logger> {
+    Arc::new(Logger::new_root(Component::Custom("Test"), "test-node"))
+}
+
+fn reading() -> Reading {
+    Reading {
+        sensor: "temp".to_string(),
+        value: 21.5,
+    }
+}
+
+/// Test that a sealed registry serves readers on many threads
+///
+/// INTENTION: Values round-trip through the registry both before and after
+/// `seal()`, including from 16 threads at once once it is sealed.
+#[test]
+fn test_round_trip_before_and_after_seal() -> Result<()> {
+    let registry = Arc::new(ConcurrentSerializerRegistry::with_defaults(logger()));
+    registry.register::<Reading>()?;
+
+    let bytes = registry.serialize_value(&ArcValue::from_struct(reading()))?;
+    let mut value = registry.deserialize_value(bytes.clone())?;
+    assert_eq!(value.as_type::<Reading>()?, reading());
+
+    registry.seal();
+    assert!(registry.is_sealed());
+    assert!(registry.sealed().is_some_and(|sealed| sealed.is_sealed()));
+
+    let readers: Vec<_> = (0..16)
+        .map(|_| {
+            let registry = registry.clone();
+            let bytes = bytes.clone();
+            std::thread::spawn(move || -> Result<Reading> {
+                let mut value = registry.deserialize_value(bytes)?;
+                registry.serialize_value(&value)?;
+                value.as_type::<Reading>()
+            })
+        })
+        .collect();
+    for reader in readers {
+        assert_eq!(reader.join().unwrap()?, reading());
+    }
+    Ok(())
+}
+
+/// Test that registrations are rejected once sealed
+///
+/// INTENTION: Like `SerializerRegistry`, the registry is closed to new types
+/// after `seal()`, and wrapping an already sealed registry keeps it sealed.
+#[test]
+fn test_register_after_seal_fails() {
+    let registry = ConcurrentSerializerRegistry::with_defaults(logger());
+    assert!(!registry.is_sealed());
+    assert!(registry.sealed().is_none());
+    registry.seal();
+    // Sealing again is a no-op
+    registry.seal();
+
+    let error = registry.register::<Reading>().unwrap_err();
+    assert!(error.to_string().contains("sealed"));
+    assert!(registry
+        .update(|registry| registry.register_map::<String, Reading>())
+        .is_err());
+
+    let mut sealed = SerializerRegistry::with_defaults(logger());
+    sealed.seal();
+    let wrapped = ConcurrentSerializerRegistry::new(sealed);
+    assert!(wrapped.is_sealed());
+    assert!(wrapped.register::<Reading>().is_err());
+}
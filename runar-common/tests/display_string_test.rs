@@ -0,0 +1,86 @@
+// Tests for the human-readable formatting of values
+//
+// `to_display_string` decodes lazy values of types registered with
+// `register_debug` and falls back to the type name and size otherwise.
+
+use std::sync::Arc;
+
+use anyhow::Result;
+use runar_common::types::{ArcValue, SerializerRegistry};
+use serde::{Deserialize, Serialize};
+
+mod common;
+use common::create_test_registry;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Reading {
+    sensor: String,
+    value: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Opaque {
+    id: u32,
+}
+
+/// A test registry with the types of these tests registered
+fn registry_with_test_types() -> SerializerRegistry {
+    let mut registry = create_test_registry();
+    registry.register_debug::<Reading>().unwrap();
+    registry.register::<Opaque>().unwrap();
+    registry
+}
+
+fn reading() -> Reading {
+    Reading {
+        sensor: "temp".to_string(),
+        value: 21.5,
+    }
+}
+
+/// Test that lazy values of debug-registered types show their content
+///
+/// INTENTION: A struct received from the network is shown as its `Debug`
+/// output, the same as the eager value it was sent from.
+#[test]
+fn test_lazy_struct_uses_debug() -> Result<()> {
+    let registry = registry_with_test_types();
+    let eager = ArcValue::from_struct(reading());
+    let bytes = registry.serialize_value(&eager)?;
+    let lazy = registry.deserialize_value(bytes)?;
+
+    let expected = format!("{:?}", reading());
+    assert_eq!(lazy.to_display_string(&registry), expected);
+    assert_eq!(eager.to_display_string(&registry), expected);
+
+    let list = registry.deserialize_value(
+        registry.serialize_value(&ArcValue::new_list(vec!["a".to_string(), "b".to_string()]))?,
+    )?;
+    assert_eq!(list.to_display_string(&registry), r#"["a", "b"]"#);
+    assert_eq!(ArcValue::null().to_display_string(&registry), "null");
+    Ok(())
+}
+
+/// Test the fallbacks for types without a registered `Debug`
+///
+/// INTENTION: Unknown lazy types show their type name and size, and a
+/// payload that does not decode shows an error instead of panicking.
+#[test]
+fn test_unknown_and_corrupt_values_fall_back() -> Result<()> {
+    let registry = registry_with_test_types();
+    let opaque = ArcValue::from_struct(Opaque { id: 7 });
+    let lazy = registry.deserialize_value(registry.serialize_value(&opaque)?)?;
+    let display = lazy.to_display_string(&registry);
+    assert!(display.starts_with('<') && display.ends_with("Opaque>[4 bytes]"));
+    // Eager values without a formatter display as with Display
+    assert_eq!(opaque.to_display_string(&registry), opaque.to_string());
+
+    // Cut the payload short so it no longer decodes
+    let bytes = registry.serialize_value(&ArcValue::from_struct(reading()))?;
+    let truncated: Arc<[u8]> = Arc::from(&bytes[..bytes.len() - 4]);
+    let corrupt = registry.deserialize_value(truncated)?;
+    assert!(corrupt
+        .to_display_string(&registry)
+        .contains("deserialization failed"));
+    Ok(())
+}
@@ -0,0 +1,149 @@
+// Tests for taking the value out of an ErasedArc
+//
+// A value with a single reference is moved out without cloning; a shared
+// value or a value of another type is handed back to the caller. Lazy values
+// are unwrapped from their decode cache once decoded.
+
+use std::sync::Arc;
+
+use runar_common::types::arc_value::LazyDataWithOffset;
+use runar_common::types::{ErasedArc, SerializationBackend};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Frame {
+    pixels: Vec<u8>,
+}
+
+#[test]
+fn test_try_unwrap_moves_unique_value() {
+    let pixels = vec![1, 2, 3];
+    let pixels_ptr = pixels.as_ptr();
+    let erased = ErasedArc::from_value(Frame { pixels });
+
+    let frame: Frame = erased.try_unwrap().expect("single reference");
+    assert_eq!(frame.pixels, vec![1, 2, 3]);
+    // Moved, not cloned: the buffer is the original one
+    assert_eq!(frame.pixels.as_ptr(), pixels_ptr);
+}
+
+#[test]
+fn test_try_unwrap_returns_shared_value() {
+    let arc = Arc::new(Frame { pixels: vec![4] });
+    let erased = ErasedArc::new(arc.clone());
+
+    let erased = erased.try_unwrap::<Frame>().unwrap_err();
+    assert_eq!(erased.strong_count(), 2);
+
+    drop(arc);
+    assert_eq!(erased.try_unwrap::<Frame>().unwrap().pixels, vec![4]);
+}
+
+#[test]
+fn test_try_unwrap_returns_value_of_other_type() {
+    let erased = ErasedArc::from_value(Frame { pixels: vec![5] });
+
+    let erased = erased.try_unwrap::<String>().unwrap_err();
+    assert_eq!(
+        *erased.as_arc::<Frame>().unwrap(),
+        Frame { pixels: vec![5] }
+    );
+}
+
+#[test]
+fn test_try_unwrap_or_clone() {
+    let erased = ErasedArc::from_value(Frame { pixels: vec![6] });
+    let shared = erased.clone();
+
+    let mut frame: Frame = erased.try_unwrap_or_clone().unwrap();
+    frame.pixels.push(7);
+    assert_eq!(frame.pixels, vec![6, 7]);
+    assert_eq!(
+        *shared.as_arc::<Frame>().unwrap(),
+        Frame { pixels: vec![6] }
+    );
+
+    let frame: Frame = shared.try_unwrap_or_clone().unwrap();
+    assert_eq!(frame.pixels, vec![6]);
+}
+
+#[test]
+fn test_try_unwrap_or_clone_rejects_other_values() {
+    let erased = ErasedArc::from_value(Frame { pixels: vec![8] });
+    let error = erased.try_unwrap_or_clone::<String>().unwrap_err();
+    assert!(error.to_string().contains("Type mismatch"), "{error}");
+
+    // A lazy value carries the name of the type it decodes to, but holds bytes
+    let lazy = ErasedArc::from_value(LazyDataWithOffset {
+        type_name: std::any::type_name::<Frame>().to_string(),
+        original_buffer: Arc::from(vec![0u8; 16]),
+        start_offset: 0,
+        end_offset: 16,
+        deserializer: None,
+        backend: SerializationBackend::Bincode,
+        eager_cache: Default::default(),
+    });
+    assert!(lazy.is_lazy);
+    let error = lazy.try_unwrap_or_clone::<Frame>().unwrap_err();
+    assert!(error.to_string().contains("lazy"), "{error}");
+}
+
+/// A lazy value holding `frame` encoded, as received off the wire
+fn lazy_frame(frame: &Frame) -> ErasedArc {
+    let bytes = SerializationBackend::Bincode.encode(frame).unwrap();
+    ErasedArc::from_value(LazyDataWithOffset {
+        type_name: std::any::type_name::<Frame>().to_string(),
+        end_offset: bytes.len(),
+        original_buffer: Arc::from(bytes),
+        start_offset: 0,
+        deserializer: None,
+        backend: SerializationBackend::Bincode,
+        eager_cache: Default::default(),
+    })
+}
+
+/// Decode the lazy value as a `Frame`, filling its decode cache
+fn decode_frame(erased: &ErasedArc) -> Arc<Frame> {
+    erased
+        .lazy_data_ref()
+        .unwrap()
+        .decode_cached(|bytes| SerializationBackend::Bincode.decode::<Frame>(bytes))
+        .unwrap()
+}
+
+/// Test unwrapping a lazy value from its decode cache
+///
+/// INTENTION: The decode cache keeps its own reference to the decoded value,
+/// so the value is moved out of the cache once no decoded `Arc` or clone of
+/// the lazy value is left, rather than never being unwrappable.
+#[test]
+fn test_try_unwrap_lazy_value_from_cache() {
+    let lazy = lazy_frame(&Frame { pixels: vec![9] });
+    // Nothing was decoded yet
+    let lazy = lazy.try_unwrap::<Frame>().unwrap_err();
+
+    let decoded = decode_frame(&lazy);
+    let pixels_ptr = decoded.pixels.as_ptr();
+    let lazy = lazy.try_unwrap::<Frame>().unwrap_err();
+    drop(decoded);
+
+    let shared = lazy.clone();
+    let lazy = lazy.try_unwrap::<Frame>().unwrap_err();
+    drop(shared);
+
+    let frame: Frame = lazy.try_unwrap().expect("only owner of the decoded value");
+    assert_eq!(frame.pixels, vec![9]);
+    assert_eq!(frame.pixels.as_ptr(), pixels_ptr);
+}
+
+/// Test that a shared lazy value is cloned out of its decode cache
+#[test]
+fn test_try_unwrap_or_clone_lazy_value() {
+    let lazy = lazy_frame(&Frame { pixels: vec![10] });
+    decode_frame(&lazy);
+    let shared = lazy.clone();
+
+    let frame: Frame = lazy.try_unwrap_or_clone().unwrap();
+    assert_eq!(frame.pixels, vec![10]);
+    assert_eq!(*decode_frame(&shared), Frame { pixels: vec![10] });
+}
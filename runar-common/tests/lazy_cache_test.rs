@@ -0,0 +1,104 @@
+// Tests for the decode cache shared by clones of a lazy ArcValue
+//
+// Every owner of the same lazily deserialized value must get the same
+// decoded Arc back, while a different type still decodes on its own.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use anyhow::Result;
+use runar_common::types::{ArcValue, SerializerRegistry};
+use serde::{Deserialize, Serialize};
+
+mod common;
+use common::create_test_registry;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Reading {
+    sensor: String,
+    value: f64,
+}
+
+mod mirror {
+    use serde::{Deserialize, Serialize};
+
+    /// Same name and layout as the outer Reading, but a distinct type
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    pub struct Reading {
+        pub sensor: String,
+        pub value: f64,
+    }
+}
+
+/// A test registry with the types of these tests registered
+fn registry_with_test_types() -> SerializerRegistry {
+    let mut registry = create_test_registry();
+    registry.register::<Reading>().unwrap();
+    registry
+}
+
+fn lazy_reading(registry: &SerializerRegistry) -> Result<ArcValue> {
+    let reading = Reading {
+        sensor: "temp".to_string(),
+        value: 21.5,
+    };
+    let bytes = registry.serialize_value(&ArcValue::from_struct(reading))?;
+    registry.deserialize_value(bytes)
+}
+
+#[test]
+fn test_clones_share_decoded_struct() -> Result<()> {
+    let registry = registry_with_test_types();
+    let value = lazy_reading(&registry)?;
+    let mut first = value.clone();
+    let mut second = value.clone();
+
+    let first_ref = first.as_type_ref::<Reading>()?;
+    // The other owner is still lazy, but reads the cached decode
+    assert!(second.value.as_ref().unwrap().is_lazy);
+    let second_ref = second.as_struct_ref::<Reading>()?;
+
+    assert!(Arc::ptr_eq(&first_ref, &second_ref));
+    assert_eq!(first_ref.sensor, "temp");
+    assert!(value
+        .value
+        .as_ref()
+        .unwrap()
+        .lazy_data_ref()?
+        .eager_cache
+        .get()
+        .is_some());
+    Ok(())
+}
+
+#[test]
+fn test_clones_share_decoded_map() -> Result<()> {
+    let registry = registry_with_test_types();
+    let mut map = HashMap::new();
+    map.insert("a".to_string(), 1i64);
+    map.insert("b".to_string(), 2i64);
+    let bytes = registry.serialize_value(&ArcValue::new_map(map.clone()))?;
+    let value = registry.deserialize_value(bytes)?;
+
+    let first_ref = value.clone().as_map_ref::<String, i64>()?;
+    let second_ref = value.clone().as_type_ref::<HashMap<String, i64>>()?;
+    assert!(Arc::ptr_eq(&first_ref, &second_ref));
+    assert_eq!(*first_ref, map);
+    Ok(())
+}
+
+#[test]
+fn test_other_type_decodes_separately() -> Result<()> {
+    let registry = registry_with_test_types();
+    let value = lazy_reading(&registry)?;
+
+    let cached = value.clone().as_type_ref::<Reading>()?;
+    let mirrored = value.clone().as_type_ref::<mirror::Reading>()?;
+    assert_eq!(mirrored.sensor, "temp");
+    assert_eq!(mirrored.value, 21.5);
+
+    // The cache still holds the first type decoded
+    let again = value.clone().as_type_ref::<Reading>()?;
+    assert!(Arc::ptr_eq(&cached, &again));
+    Ok(())
+}
@@ -0,0 +1,180 @@
+// Tests for log sampling
+//
+// Sampled loggers keep each debug call with the configured probability, count
+// the dropped ones and report them in a periodic summary line, and once more
+// when the sampler goes away.
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use runar_common::logging::{Component, LogFilter, Logger};
+
+/// Captures every message so tests can count what was logged
+struct CaptureLogger {
+    messages: Mutex<Vec<String>>,
+}
+
+static CAPTURE: CaptureLogger = CaptureLogger {
+    messages: Mutex::new(Vec::new()),
+};
+
+impl log::Log for CaptureLogger {
+    fn enabled(&self, _metadata: &log::Metadata) -> bool {
+        true
+    }
+
+    fn log(&self, record: &log::Record) {
+        self.messages
+            .lock()
+            .unwrap()
+            .push(record.args().to_string());
+    }
+
+    fn flush(&self) {}
+}
+
+fn install_capture() {
+    let _ = log::set_logger(&CAPTURE);
+    log::set_max_level(log::LevelFilter::Debug);
+}
+
+/// Messages logged by the node with the given ID
+fn captured(node_id: &str) -> Vec<String> {
+    let prefix = format!("[{node_id}]");
+    CAPTURE
+        .messages
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|message| message.starts_with(&prefix))
+        .cloned()
+        .collect()
+}
+
+/// Test that the rate decides the fraction of calls logged
+///
+/// INTENTION: Rate 0 drops and counts every call, rate 1 keeps every call,
+/// and rates in between keep about that fraction.
+#[test]
+fn test_sampling_rates() {
+    install_capture();
+    let logger = Logger::new_root(Component::Custom("Sampling"), "sampling-rates");
+
+    let none = logger.sampled(0.0);
+    for _ in 0..100 {
+        none.debug("never logged");
+    }
+    assert_eq!(none.dropped_log_count(), 100);
+
+    let all = logger.sampled(1.0);
+    for _ in 0..100 {
+        all.debug("always logged");
+    }
+    assert_eq!(all.dropped_log_count(), 0);
+    assert_eq!(captured("sampling-rates").len(), 100);
+
+    let half = logger.sampled(0.5);
+    for _ in 0..10_000 {
+        half.debug("sometimes logged");
+    }
+    let dropped = half.dropped_log_count();
+    assert!((4_000..6_000).contains(&dropped), "dropped {dropped}");
+}
+
+/// Test that dropped calls are reported in a single summary line
+///
+/// INTENTION: Once the summary interval has passed, the next call logs how
+/// many calls were dropped and resets the count.
+#[test]
+fn test_dropped_calls_summary() {
+    install_capture();
+    let logger = Logger::new_root(Component::Custom("Sampling"), "sampling-summary")
+        .sampled(0.0)
+        .with_summary_interval(Duration::from_millis(50));
+
+    for _ in 0..10 {
+        logger.debug("dropped");
+    }
+    assert!(captured("sampling-summary").is_empty());
+    assert_eq!(logger.dropped_log_count(), 10);
+
+    std::thread::sleep(Duration::from_millis(60));
+    logger.debug("dropped");
+    let messages = captured("sampling-summary");
+    assert_eq!(messages.len(), 1);
+    assert!(
+        messages[0].ends_with("11 log messages dropped by sampling (rate 0)"),
+        "{}",
+        messages[0]
+    );
+    assert_eq!(logger.dropped_log_count(), 0);
+}
+
+/// Test that a filter samples the loggers of a component
+///
+/// INTENTION: Sampling configured for a component applies to every logger of
+/// that component derived from the filtered root, and not to the others.
+#[test]
+fn test_filter_samples_component() {
+    install_capture();
+    let filter = Arc::new(
+        LogFilter::new(log::LevelFilter::Debug).with_component_sampling_rate("Sampled", 0.0),
+    );
+    let root =
+        Logger::new_root(Component::Custom("Other"), "sampling-filter").with_filter(filter.clone());
+    let sampled = root.with_component(Component::Custom("Sampled"));
+
+    for _ in 0..10 {
+        sampled.debug("dropped");
+        root.debug("kept");
+    }
+    assert_eq!(captured("sampling-filter").len(), 10);
+    let sampler = filter
+        .sampler_for(Component::Custom("Sampled"))
+        .expect("sampler configured");
+    assert_eq!(sampler.dropped_log_count(), 10);
+    assert!(filter.sampler_for(Component::Custom("Other")).is_none());
+}
+
+/// Test that only debug calls are sampled
+///
+/// INTENTION: Info messages, warnings and errors are always logged, even at
+/// rate 0, and do not count as dropped.
+#[test]
+fn test_only_debug_calls_are_sampled() {
+    install_capture();
+    let logger = Logger::new_root(Component::Custom("Sampling"), "sampling-levels").sampled(0.0);
+
+    logger.debug("dropped");
+    logger.info("info");
+    logger.warn("warning");
+    logger.error("error");
+    assert_eq!(captured("sampling-levels").len(), 3);
+    assert_eq!(logger.dropped_log_count(), 1);
+}
+
+/// Test that the dropped calls are reported when the sampler goes away
+///
+/// INTENTION: Dropping the last clone of a sampled logger logs the summary
+/// of the calls dropped since the last one, even before the interval passed.
+#[test]
+fn test_summary_flushed_on_drop() {
+    install_capture();
+    let logger = Logger::new_root(Component::Custom("Sampling"), "sampling-drop").sampled(0.0);
+    let clone = logger.clone();
+
+    for _ in 0..5 {
+        clone.debug("dropped");
+    }
+    drop(clone);
+    assert!(captured("sampling-drop").is_empty());
+
+    drop(logger);
+    let messages = captured("sampling-drop");
+    assert_eq!(messages.len(), 1);
+    assert!(
+        messages[0].ends_with("5 log messages dropped by sampling (rate 0)"),
+        "{}",
+        messages[0]
+    );
+}
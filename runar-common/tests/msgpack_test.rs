@@ -0,0 +1,106 @@
+// Tests for the MessagePack serialization backend
+#![cfg(feature = "msgpack")]
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use anyhow::Result;
+use runar_common::logging::{Component, Logger};
+use runar_common::types::{ArcValue, SerializationBackend, SerializerRegistry};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct TestStruct {
+    field1: String,
+    field2: i32,
+}
+
+fn create_msgpack_registry() -> SerializerRegistry {
+    let mut registry = SerializerRegistry::with_backend(
+        Arc::new(Logger::new_root(Component::Custom("Test"), "test-node")),
+        SerializationBackend::Msgpack,
+    );
+    registry.register::<TestStruct>().unwrap();
+    registry
+}
+
+/// Split a serialized value into its type name and payload
+fn split_header(bytes: &[u8]) -> (&str, &[u8]) {
+    let type_name_len = bytes[1] as usize;
+    let type_name = std::str::from_utf8(&bytes[2..2 + type_name_len]).unwrap();
+    (type_name, &bytes[2 + type_name_len..])
+}
+
+#[test]
+fn test_msgpack_struct_round_trip() -> Result<()> {
+    let registry = create_msgpack_registry();
+    let original = TestStruct {
+        field1: "hello".to_string(),
+        field2: 123,
+    };
+    let value = ArcValue::from_struct(original.clone());
+
+    let bytes = registry.serialize_value(&value)?;
+
+    // The header is unchanged: struct category marker followed by the type name
+    let (type_name, payload) = split_header(&bytes);
+    assert_eq!(bytes[0], 0x04);
+    assert_eq!(type_name, std::any::type_name::<TestStruct>());
+
+    // The payload is the layout documented on SerializationBackend::Msgpack,
+    // i.e. what Python's msgpack.unpackb reads as {"field1": "hello", "field2": 123}
+    let mut expected = vec![0x82, 0xa6];
+    expected.extend_from_slice(b"field1");
+    expected.push(0xa5);
+    expected.extend_from_slice(b"hello");
+    expected.push(0xa6);
+    expected.extend_from_slice(b"field2");
+    expected.push(0x7b);
+    assert_eq!(payload, expected.as_slice());
+
+    let mut value_from_bytes = registry.deserialize_value(bytes)?;
+    assert_eq!(*value_from_bytes.as_struct_ref::<TestStruct>()?, original);
+
+    Ok(())
+}
+
+#[test]
+fn test_msgpack_primitive_list_and_map_round_trip() -> Result<()> {
+    let registry = create_msgpack_registry();
+
+    let value = ArcValue::new_primitive("text".to_string());
+    let bytes = registry.serialize_value(&value)?;
+    assert_eq!(split_header(&bytes).1, [0xa4, b't', b'e', b'x', b't']);
+    let mut value_from_bytes = registry.deserialize_value(bytes)?;
+    assert_eq!(value_from_bytes.as_type::<String>()?, "text");
+
+    let value = ArcValue::new_list(vec![1i64, 2, 3]);
+    let bytes = registry.serialize_value(&value)?;
+    assert_eq!(split_header(&bytes).1, [0x93, 0x01, 0x02, 0x03]);
+    let mut value_from_bytes = registry.deserialize_value(bytes)?;
+    assert_eq!(*value_from_bytes.as_list_ref::<i64>()?, vec![1, 2, 3]);
+
+    let mut map = HashMap::new();
+    map.insert("key".to_string(), "value".to_string());
+    let value = ArcValue::new_map(map.clone());
+    let bytes = registry.serialize_value(&value)?;
+    let mut value_from_bytes = registry.deserialize_value(bytes)?;
+    assert_eq!(*value_from_bytes.as_map_ref::<String, String>()?, map);
+
+    Ok(())
+}
+
+#[test]
+fn test_msgpack_index_map_decodes_lazy_map() -> Result<()> {
+    let registry = create_msgpack_registry();
+    let mut map = HashMap::new();
+    map.insert("answer".to_string(), ArcValue::new_primitive(42i64));
+    let bytes = registry.serialize_value(&ArcValue::new_map(map))?;
+
+    let mut value_from_bytes = registry.deserialize_value(bytes)?;
+    assert!(value_from_bytes.contains_key("answer")?);
+    assert_eq!(value_from_bytes.index_map("answer")?.as_type::<i64>()?, 42);
+    assert!(value_from_bytes.index_map("question").is_err());
+
+    Ok(())
+}
@@ -0,0 +1,105 @@
+// Tests for the typed JSON conversion of ArcValue
+#![cfg(feature = "serde_full")]
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use anyhow::Result;
+use runar_common::logging::{Component, Logger};
+use runar_common::types::{ArcValue, SerializerRegistry, ValueCategory};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Order {
+    id: String,
+    quantity: i32,
+}
+
+fn create_registry() -> SerializerRegistry {
+    let mut registry = SerializerRegistry::with_defaults(Arc::new(Logger::new_root(
+        Component::Custom("Test"),
+        "test-node",
+    )));
+    registry.register::<Order>().unwrap();
+    registry
+}
+
+#[test]
+fn test_struct_typed_json_round_trip() -> Result<()> {
+    let registry = create_registry();
+    let order = Order {
+        id: "o-1".to_string(),
+        quantity: 3,
+    };
+
+    let json = ArcValue::from_struct(order.clone()).to_typed_json(&registry)?;
+    assert_eq!(
+        json,
+        json!({
+            "category": "Struct",
+            "type_name": std::any::type_name::<Order>(),
+            "value": {"id": "o-1", "quantity": 3}
+        })
+    );
+
+    let mut value = ArcValue::from_typed_json(json.clone(), &registry)?;
+    assert_eq!(value.category, ValueCategory::Struct);
+    assert_eq!(*value.as_struct_ref::<Order>()?, order);
+
+    // A value decoded from the wire has no JSON serializer of its own
+    let bytes = registry.serialize_value(&ArcValue::from_struct(order))?;
+    let lazy = registry.deserialize_value(bytes)?;
+    assert_eq!(lazy.to_typed_json(&registry)?, json);
+    Ok(())
+}
+
+#[test]
+fn test_nested_typed_json_round_trip() -> Result<()> {
+    let registry = create_registry();
+    let mut map = HashMap::new();
+    map.insert("count".to_string(), ArcValue::new_primitive(7i64));
+    map.insert("payload".to_string(), ArcValue::from_bytes(vec![1, 2, 3]));
+    map.insert("missing".to_string(), ArcValue::null());
+    map.insert("note".to_string(), ArcValue::null_of_type::<String>());
+    map.insert(
+        "tags".to_string(),
+        ArcValue::new_list(vec![ArcValue::new_primitive("a".to_string())]),
+    );
+
+    let json = ArcValue::new_map(map).to_typed_json(&registry)?;
+    assert_eq!(json["category"], "Map");
+    assert_eq!(json["value"]["count"]["value"], 7);
+    assert_eq!(json["value"]["payload"]["value"], "AQID");
+    assert_eq!(json["value"]["missing"], json!({"category": "Null"}));
+    assert_eq!(
+        json["value"]["note"],
+        json!({"category": "Null", "type_name": "alloc::string::String"})
+    );
+
+    let mut value = ArcValue::from_typed_json(json.clone(), &registry)?;
+    let entries = value.as_map_ref::<String, ArcValue>()?;
+    let mut count = entries["count"].clone();
+    assert_eq!(*count.as_type_ref::<i64>()?, 7);
+    let mut payload = entries["payload"].clone();
+    assert_eq!(payload.as_bytes()?.as_slice(), &[1, 2, 3]);
+    assert!(entries["note"].is_null_of_type::<String>());
+    assert_eq!(value.to_typed_json(&registry)?, json);
+    Ok(())
+}
+
+#[test]
+fn test_typed_json_errors() {
+    let registry = create_registry();
+    assert!(ArcValue::from_typed_json(json!([1, 2]), &registry).is_err());
+    assert!(ArcValue::from_typed_json(
+        json!({"category": "Struct", "type_name": "Unknown", "value": {}}),
+        &registry
+    )
+    .is_err());
+    assert!(ArcValue::from_typed_json(
+        json!({"category": "Struct", "type_name": std::any::type_name::<Order>(), "value": {"id": 1}}),
+        &registry
+    )
+    .is_err());
+}
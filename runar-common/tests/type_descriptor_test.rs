@@ -0,0 +1,98 @@
+// Tests for ArcValue::type_descriptor
+//
+// The descriptor must report the category and Rust type of eager and lazy
+// values alike, and the element type of lists.
+
+use std::collections::HashMap;
+
+use anyhow::Result;
+use runar_common::types::{ArcValue, SerializerRegistry, TypeDescriptor, ValueCategory};
+use serde::{Deserialize, Serialize};
+
+mod common;
+use common::create_test_registry;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Reading {
+    sensor: String,
+    value: f64,
+}
+
+/// A test registry with the types of these tests registered
+fn registry_with_test_types() -> SerializerRegistry {
+    let mut registry = create_test_registry();
+    registry.register::<Reading>().unwrap();
+    registry.register::<Vec<String>>().unwrap();
+    registry
+}
+
+#[test]
+fn test_eager_values() {
+    let descriptor = ArcValue::new_primitive(42i32).type_descriptor();
+    assert_eq!(descriptor.category, ValueCategory::Primitive);
+    assert_eq!(descriptor.rust_type_name, "i32");
+    assert_eq!(descriptor.element_type, None);
+
+    let descriptor = ArcValue::null().type_descriptor();
+    assert_eq!(descriptor.category, ValueCategory::Null);
+    assert_eq!(descriptor.rust_type_name, "()");
+
+    let map: HashMap<String, i64> = HashMap::new();
+    let descriptor = ArcValue::new_map(map).type_descriptor();
+    assert_eq!(descriptor.category, ValueCategory::Map);
+    assert_eq!(descriptor.element_type, None);
+}
+
+#[test]
+fn test_list_element_type() {
+    let value = ArcValue::new_list(vec!["a".to_string(), "b".to_string()]);
+    let descriptor = value.type_descriptor();
+    assert_eq!(descriptor.category, ValueCategory::List);
+    assert_eq!(
+        descriptor.rust_type_name,
+        "alloc::vec::Vec<alloc::string::String>"
+    );
+    let element = descriptor.element_type.expect("list element type");
+    assert_eq!(element.category, ValueCategory::Primitive);
+    assert_eq!(element.rust_type_name, "alloc::string::String");
+
+    // Nested lists describe each level
+    let value = ArcValue::new_list(vec![vec![1u8, 2], vec![3]]);
+    let element = value.type_descriptor().element_type.unwrap();
+    assert_eq!(element.category, ValueCategory::Bytes);
+    assert_eq!(element.element_type, None);
+
+    assert_eq!(
+        TypeDescriptor::new(ValueCategory::List, "Vec<Vec<String>>").to_string(),
+        "List(Vec<Vec<String>>) of List(Vec<String>) of Primitive(String)"
+    );
+}
+
+#[test]
+fn test_lazy_values() -> Result<()> {
+    let registry = registry_with_test_types();
+
+    let reading = Reading {
+        sensor: "temp".to_string(),
+        value: 21.5,
+    };
+    let bytes = registry.serialize_value(&ArcValue::from_struct(reading))?;
+    let lazy = registry.deserialize_value(bytes)?;
+    assert!(lazy.value.as_ref().unwrap().is_lazy);
+    let descriptor = lazy.type_descriptor();
+    assert_eq!(descriptor.category, ValueCategory::Struct);
+    assert_eq!(descriptor.rust_type_name, std::any::type_name::<Reading>());
+
+    let list = ArcValue::new_list(vec!["a".to_string()]);
+    let bytes = registry.serialize_value(&list)?;
+    let lazy = registry.deserialize_value(bytes)?;
+    let descriptor = lazy.type_descriptor();
+    assert_eq!(descriptor.category, ValueCategory::List);
+    assert_eq!(
+        descriptor.element_type.unwrap().category,
+        ValueCategory::Primitive
+    );
+    // Describing a lazy value does not deserialize it
+    assert!(lazy.value.as_ref().unwrap().is_lazy);
+    Ok(())
+}
@@ -0,0 +1,41 @@
+// Tests for typed null values
+//
+// A null created with `null_of_type` is null like any other, remembers the
+// type it stands for, and keeps it through serialization.
+
+use anyhow::Result;
+use runar_common::types::ArcValue;
+
+mod common;
+use common::create_test_registry;
+
+#[test]
+fn test_typed_null() {
+    let value = ArcValue::null_of_type::<String>();
+    assert!(value.is_null());
+    assert!(value.is_null_of_type::<String>());
+    assert!(!value.is_null_of_type::<i64>());
+    assert_eq!(value.null_type_name(), Some("alloc::string::String"));
+    assert_eq!(value, ArcValue::null());
+
+    let untyped = ArcValue::null();
+    assert!(!untyped.is_null_of_type::<String>());
+    assert_eq!(untyped.null_type_name(), None);
+}
+
+#[test]
+fn test_typed_null_survives_serialization() -> Result<()> {
+    let registry = create_test_registry();
+
+    let bytes = registry.serialize_value(&ArcValue::null_of_type::<i64>())?;
+    let value = registry.deserialize_value(bytes)?;
+    assert!(value.is_null_of_type::<i64>());
+
+    // Untyped nulls keep their single byte encoding
+    let bytes = registry.serialize_value(&ArcValue::null())?;
+    assert_eq!(bytes.len(), 1);
+    let value = registry.deserialize_value(bytes)?;
+    assert!(value.is_null());
+    assert_eq!(value.null_type_name(), None);
+    Ok(())
+}
@@ -377,6 +377,60 @@ fn test_type_mismatch_errors() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_bytes_round_trip() -> Result<()> {
+    let data = vec![0x00, 0xFF, 0x10, 0x20];
+    let mut value = ArcValue::from_bytes(data.clone());
+    assert_eq!(value.category, ValueCategory::Bytes);
+    assert_eq!(*value.as_bytes()?, data);
+
+    // Bytes are written raw after the header, without bincode's length prefix
+    let registry = create_test_registry();
+    let bytes = registry.serialize_value(&value)?;
+    assert!(bytes.ends_with(&data));
+    assert_eq!(bytes.len(), 2 + bytes[1] as usize + data.len());
+
+    let mut value_from_bytes = registry.deserialize_value(bytes)?;
+    assert_eq!(value_from_bytes.category, ValueCategory::Bytes);
+    assert_eq!(*value_from_bytes.as_bytes()?, data);
+
+    // Other categories are rejected
+    let mut primitive = ArcValue::new_primitive(data);
+    assert!(primitive.as_bytes().is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_bytes_slice_borrows_received_buffer() -> Result<()> {
+    let data = vec![0x00, 0xFF, 0x10, 0x20];
+    let eager = ArcValue::from_bytes(data.clone());
+    assert_eq!(eager.bytes_slice()?, data.as_slice());
+    assert_eq!(eager.bytes_len()?, data.len());
+
+    let registry = create_test_registry();
+    let bytes = registry.serialize_value(&eager)?;
+    let mut received = registry.deserialize_value(bytes.clone())?;
+
+    // The slice points into the buffer the value was received in
+    let slice = received.bytes_slice()?;
+    assert_eq!(slice, data.as_slice());
+    assert_eq!(slice.as_ptr(), bytes[bytes.len() - data.len()..].as_ptr());
+    assert_eq!(received.bytes_len()?, data.len());
+
+    // Clones compare equal and convert like eager values
+    assert_eq!(received.clone(), eager);
+    assert_eq!(received.to_json_value()?, json!(STANDARD.encode(&data)));
+    assert_eq!(received.clone().as_type::<Vec<u8>>()?, data);
+    assert_eq!(received.as_type_borrowed::<&[u8]>()?, data.as_slice());
+    assert_eq!(*received.as_bytes()?, data);
+    assert_eq!(received.bytes_slice()?, data.as_slice());
+
+    assert!(ArcValue::new_primitive(1i32).bytes_slice().is_err());
+    assert!(ArcValue::null().bytes_len().is_err());
+    Ok(())
+}
+
 #[test]
 fn test_null_value() -> Result<()> {
     let value = ArcValue::null();
@@ -434,6 +488,138 @@ fn test_registry_with_defaults() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_registry_merge_and_difference() -> Result<()> {
+    let logger = Arc::new(Logger::new_root(Component::Custom("Test"), "test-node"));
+    let mut node_registry = SerializerRegistry::with_defaults(logger.clone());
+    let mut module_registry = SerializerRegistry::new(logger.clone());
+    module_registry.register::<TestStruct>()?;
+    module_registry.register::<String>()?;
+
+    let struct_name = std::any::type_name::<TestStruct>().to_string();
+    assert_eq!(
+        module_registry.difference(&node_registry),
+        vec!["TestStruct".to_string(), struct_name.clone()]
+    );
+    assert!(!node_registry.difference(&module_registry).is_empty());
+
+    // Already registered names are skipped, new ones are added
+    node_registry.merge(module_registry)?;
+    let value = ArcValue::from_struct(TestStruct {
+        field1: "merged".to_string(),
+        field2: 7,
+    });
+    let bytes = node_registry.serialize_value(&value)?;
+    let mut value_from_bytes = node_registry.deserialize_value(bytes)?;
+    assert_eq!(
+        value_from_bytes.as_struct_ref::<TestStruct>()?.field1,
+        "merged"
+    );
+
+    // A sealed registry refuses the merge
+    node_registry.seal();
+    assert!(node_registry
+        .merge(SerializerRegistry::new(logger))
+        .is_err());
+
+    Ok(())
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct BatchStruct {
+    id: u32,
+}
+
+#[test]
+fn test_register_all_macro() -> Result<()> {
+    let mut registry = SerializerRegistry::new(Arc::new(Logger::new_root(
+        Component::Custom("Test"),
+        "test-node",
+    )));
+
+    runar_common::types::register_all!(registry, BatchStruct, HashMap<String, BatchStruct>)?;
+
+    // Both the plain struct and the map type round-trip through the registry
+    let value = ArcValue::from_struct(BatchStruct { id: 7 });
+    let mut restored = registry.deserialize_value(registry.serialize_value(&value)?)?;
+    assert_eq!(restored.as_type::<BatchStruct>()?, BatchStruct { id: 7 });
+
+    let mut map = HashMap::new();
+    map.insert("a".to_string(), BatchStruct { id: 1 });
+    let value = ArcValue::from_map(map.clone());
+    let mut restored = registry.deserialize_value(registry.serialize_value(&value)?)?;
+    assert_eq!(restored.as_map_ref::<String, BatchStruct>()?.as_ref(), &map);
+
+    Ok(())
+}
+
+#[test]
+fn test_register_batch_stops_at_first_error() {
+    let mut registry = SerializerRegistry::new(Arc::new(Logger::new_root(
+        Component::Custom("Test"),
+        "test-node",
+    )));
+
+    let result = registry.register_batch(&[
+        |registry| registry.register::<TestStruct>(),
+        |_| Err(anyhow::anyhow!("registration failed")),
+        |registry| registry.register::<BatchStruct>(),
+    ]);
+    assert!(result.is_err());
+
+    // Registrations before the failure are kept, the ones after it never ran
+    let registered = ArcValue::from_struct(TestStruct {
+        field1: "kept".to_string(),
+        field2: 1,
+    });
+    assert!(registry.serialize_value(&registered).is_ok());
+    let skipped = ArcValue::from_struct(BatchStruct { id: 1 });
+    assert!(registry.serialize_value(&skipped).is_err());
+}
+
+#[test]
+fn test_register_option_and_result() -> Result<()> {
+    let mut registry = SerializerRegistry::new(Arc::new(Logger::new_root(
+        Component::Custom("Test"),
+        "test-node",
+    )));
+
+    registry.register_option::<BatchStruct>()?;
+    registry.register_result::<BatchStruct, String>()?;
+
+    // None is a single zero byte, Some is tagged with a one byte
+    let value = ArcValue::from_struct(None::<BatchStruct>);
+    let bytes = registry.serialize_value(&value)?;
+    assert_eq!(bytes.last(), Some(&0u8));
+    let mut restored = registry.deserialize_value(bytes)?;
+    assert_eq!(restored.as_type::<Option<BatchStruct>>()?, None);
+
+    let value = ArcValue::from_struct(Some(BatchStruct { id: 3 }));
+    let mut restored = registry.deserialize_value(registry.serialize_value(&value)?)?;
+    assert_eq!(
+        restored.as_type::<Option<BatchStruct>>()?,
+        Some(BatchStruct { id: 3 })
+    );
+
+    let ok: std::result::Result<BatchStruct, String> = Ok(BatchStruct { id: 4 });
+    let value = ArcValue::from_struct(ok.clone());
+    let mut restored = registry.deserialize_value(registry.serialize_value(&value)?)?;
+    assert_eq!(
+        restored.as_type::<std::result::Result<BatchStruct, String>>()?,
+        ok
+    );
+
+    let err: std::result::Result<BatchStruct, String> = Err("not found".to_string());
+    let value = ArcValue::from_struct(err.clone());
+    let mut restored = registry.deserialize_value(registry.serialize_value(&value)?)?;
+    assert_eq!(
+        restored.as_type::<std::result::Result<BatchStruct, String>>()?,
+        err
+    );
+
+    Ok(())
+}
+
 // ---------------- Tests moved from src/types/arc_value_test.rs ----------------
 
 #[derive(Clone, Serialize, Deserialize, Debug)]
@@ -18,6 +18,9 @@ use tokio::sync::oneshot;
 use tower_http::cors::{Any, CorsLayer};
 use tower_http::trace::TraceLayer;
 
+/// Action served by services built with `#[service_impl(openapi = true)]`
+const OPENAPI_ACTION: &str = "openapi_schema";
+
 // Import Uuid if still needed, otherwise remove if not used after refactor
 // use uuid::Uuid;
 // Import HashMap if still needed
@@ -77,6 +80,56 @@ impl GatwayService {
         self
     }
 
+    /// OpenAPI document merging those of the services built with `openapi = true`
+    ///
+    /// Each such service serves its own document through an `openapi_schema`
+    /// action; their paths and component schemas are combined, a schema name
+    /// used by several services keeping the last definition.
+    async fn openapi_document(ctx: &LifecycleContext) -> Result<JsonValue> {
+        let services = ctx
+            .request::<(), Vec<ServiceMetadata>>("$registry/services/list".to_string(), None)
+            .await?;
+        let mut paths = serde_json::Map::new();
+        let mut schemas = serde_json::Map::new();
+        for service_meta in services {
+            let has_openapi = service_meta
+                .actions
+                .iter()
+                .any(|action| action.name == OPENAPI_ACTION);
+            if !has_openapi {
+                continue;
+            }
+            let req_path = format!("{}/{OPENAPI_ACTION}", service_meta.service_path);
+            let mut document = match ctx.request::<(), ArcValue>(req_path.clone(), None).await {
+                Ok(mut arc_value) => arc_value.to_json_value()?,
+                Err(e) => {
+                    ctx.warn(format!("Skipping OpenAPI document of '{req_path}': {e}"));
+                    continue;
+                }
+            };
+            if let Some(JsonValue::Object(service_paths)) =
+                document.get_mut("paths").map(JsonValue::take)
+            {
+                paths.extend(service_paths);
+            }
+            if let Some(JsonValue::Object(service_schemas)) = document
+                .pointer_mut("/components/schemas")
+                .map(JsonValue::take)
+            {
+                schemas.extend(service_schemas);
+            }
+        }
+        Ok(serde_json::json!({
+            "openapi": "3.1.0",
+            "info": {
+                "title": "Runar Gateway",
+                "version": env!("CARGO_PKG_VERSION"),
+            },
+            "paths": paths,
+            "components": { "schemas": schemas },
+        }))
+    }
+
     fn add_route_to_router(
         &self,
         router: Router<LifecycleContext>,
@@ -283,6 +336,44 @@ impl AbstractService for GatwayService {
             }
         }
 
+        router = router.route(
+            "/openapi.json",
+            get(|State(ctx): State<LifecycleContext>| async move {
+                match Self::openapi_document(&ctx).await {
+                    Ok(document) => (StatusCode::OK, AxumJson(document)).into_response(),
+                    Err(e) => {
+                        ctx.error(format!("Error building the OpenAPI document: {e}"));
+                        (
+                            StatusCode::INTERNAL_SERVER_ERROR,
+                            format!("OpenAPI document error: {e}"),
+                        )
+                            .into_response()
+                    }
+                }
+            }),
+        );
+
+        router = router.route(
+            "/health/cluster",
+            get(|State(ctx): State<LifecycleContext>| async move {
+                match ctx
+                    .request::<(), ArcValue>("$registry/health/cluster".to_string(), None)
+                    .await
+                    .and_then(|mut reports| reports.to_json_value())
+                {
+                    Ok(reports) => (StatusCode::OK, AxumJson(reports)).into_response(),
+                    Err(e) => {
+                        ctx.error(format!("Error reading the cluster health: {e}"));
+                        (
+                            StatusCode::INTERNAL_SERVER_ERROR,
+                            format!("Cluster health error: {e}"),
+                        )
+                            .into_response()
+                    }
+                }
+            }),
+        );
+
         // Pass LifecycleContext as state to Axum handlers
         let app = router.with_state(context.clone());
 
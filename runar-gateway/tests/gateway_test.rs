@@ -5,6 +5,7 @@ use runar_gateway::GatwayService;
 use runar_macros::{action, service, service_impl};
 use runar_node::Node;
 use runar_test_utils::create_node_test_config;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value as JsonValue};
 use std::collections::HashMap;
@@ -22,7 +23,7 @@ struct MyTestData {
 // --- Mock EchoService ---
 #[service(
     name = "EchoService",
-    path = "echo-service",
+    path = "echo_service",
     description = "A simple service that echoes messages and pings.",
     version = "1.0.0"
 )]
@@ -59,6 +60,25 @@ impl EchoService {
     }
 }
 
+// --- Mock service documented with OpenAPI ---
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+struct Greeting {
+    text: String,
+}
+
+#[service(name = "GreeterService", path = "greeter")]
+struct GreeterService {}
+
+#[service_impl(openapi = true)]
+impl GreeterService {
+    #[action]
+    async fn greet(&self, name: String) -> Result<Greeting> {
+        Ok(Greeting {
+            text: format!("Hello, {name}!"),
+        })
+    }
+}
+
 #[tokio::test]
 async fn test_gateway_routes() -> Result<()> {
     // 1. Setup Node
@@ -73,7 +93,7 @@ async fn test_gateway_routes() -> Result<()> {
     let echo_service = EchoService::default();
     node.add_service(echo_service).await?;
 
-    let echo_service_path = "echo-service";
+    let echo_service_path = "echo_service";
 
     // 4. Setup and Add GatwayService
     let gateway_listen_addr: SocketAddr = "127.0.0.1:3001".parse()?;
@@ -91,7 +111,7 @@ async fn test_gateway_routes() -> Result<()> {
     let client = reqwest::Client::new();
     let base_url = format!("http://{gateway_listen_addr}");
 
-    // 6. Test GET endpoint (/echo-service/ping)
+    // 6. Test GET endpoint (/echo_service/ping)
     let ping_url = format!("{base_url}/{echo_service_path}/ping");
     println!("Testing GET: {ping_url}");
     let resp_get = client.get(&ping_url).send().await?;
@@ -106,7 +126,7 @@ async fn test_gateway_routes() -> Result<()> {
     assert_eq!(body_get, json!("pong"));
     println!("GET /{echo_service_path}/ping successful.");
 
-    // 7. Test POST endpoint (/echo-service/echo)
+    // 7. Test POST endpoint (/echo_service/echo)
     let echo_url = format!("{base_url}/{echo_service_path}/echo");
     let payload = json!({ "message": "hello from gateway test" });
     println!("Testing POST: {echo_url} with payload: {payload}");
@@ -196,3 +216,67 @@ async fn test_gateway_routes() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_gateway_openapi_document() -> Result<()> {
+    let node_config = create_node_test_config().expect("Error creating test config");
+    let mut node = Node::new(node_config).await?;
+    node.add_service(GreeterService::default()).await?;
+    // Services without `openapi = true` are left out of the document
+    node.add_service(EchoService::default()).await?;
+
+    let gateway_listen_addr: SocketAddr = "127.0.0.1:3002".parse()?;
+    let gateway_service =
+        GatwayService::new("TestGateway", "gateway").with_listen_addr(gateway_listen_addr);
+    node.add_service(gateway_service).await?;
+    node.start().await?;
+    sleep(Duration::from_millis(1000)).await;
+
+    let resp = reqwest::get(format!("http://{gateway_listen_addr}/openapi.json")).await?;
+    assert_eq!(resp.status(), HttpStatus::OK);
+    let document: JsonValue = resp.json().await?;
+
+    assert_eq!(document["openapi"], "3.1.0");
+    let paths = document["paths"].as_object().unwrap();
+    assert_eq!(paths.keys().collect::<Vec<_>>(), vec!["/greeter/greet"]);
+    assert_eq!(
+        paths["/greeter/greet"]["post"]["responses"]["200"]["content"]["application/json"]
+            ["schema"],
+        json!({ "$ref": "#/components/schemas/Greeting" })
+    );
+    assert_eq!(
+        document["components"]["schemas"]["Greeting"]["type"],
+        "object"
+    );
+
+    node.stop().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_gateway_cluster_health() -> Result<()> {
+    let node_config = create_node_test_config().expect("Error creating test config");
+    let mut node = Node::new(node_config).await?;
+    node.add_service(EchoService::default()).await?;
+
+    let gateway_listen_addr: SocketAddr = "127.0.0.1:3003".parse()?;
+    let gateway_service =
+        GatwayService::new("TestGateway", "gateway").with_listen_addr(gateway_listen_addr);
+    node.add_service(gateway_service).await?;
+    node.start().await?;
+    sleep(Duration::from_millis(1000)).await;
+
+    let resp = reqwest::get(format!("http://{gateway_listen_addr}/health/cluster")).await?;
+    assert_eq!(resp.status(), HttpStatus::OK);
+    let cluster: JsonValue = resp.json().await?;
+
+    // Without peers the cluster is this node alone
+    let peer_id = node.get_local_node_info().await?.peer_id.to_string();
+    let report = &cluster[&peer_id];
+    assert_eq!(report["healthy"], true);
+    assert_eq!(report["stale"], false);
+    assert_eq!(report["services"]["echo_service"], "Running");
+
+    node.stop().await?;
+    Ok(())
+}
@@ -132,6 +132,13 @@ impl NodeKeyManager {
         Ok(key_vec)
     }
 
+    /// Store a symmetric key under the given name, replacing any existing one
+    pub fn set_symmetric_key(&mut self, key_name: &str, key: Vec<u8>) {
+        self.symmetric_keys.insert(key_name.to_string(), key);
+        self.logger
+            .debug(format!("Stored symmetric key: {key_name}"));
+    }
+
     /// Encrypt local data using the node storage key
     pub fn encrypt_local_data(&self, data: &[u8]) -> Result<Vec<u8>> {
         let storage_key = self.get_storage_key();
@@ -13,6 +13,84 @@ use syn::{
     Lit, Pat, PathArguments, ReturnType, Type,
 };
 
+/// Get the value of a `name = "..."` attribute
+fn extract_name_value(attr_str: &str) -> Option<String> {
+    attr_str.split(',').find_map(|pair| {
+        let (key, value) = pair.split_once('=')?;
+        let value = value.trim();
+        (key.trim() == "name" && value.len() >= 2 && value.starts_with('"') && value.ends_with('"'))
+            .then(|| value[1..value.len() - 1].to_string())
+    })
+}
+
+/// Reject an action name or path that cannot be routed
+fn validate_action_attribute(kind: &str, value: &str) -> Result<(), TokenStream2> {
+    crate::utils::validate_route_path(value).map_err(|problem| {
+        let message = format!("Invalid action {kind}: {value:?}: {problem}");
+        quote! {
+            compile_error!(#message);
+        }
+    })
+}
+
+/// Name and path an action is registered under
+pub(crate) struct ActionRoute {
+    pub name: String,
+    pub path: String,
+    /// Attribute value that must be a valid route, with its kind ("name" or "path")
+    pub checked: Option<(&'static str, String)>,
+}
+
+/// Read the action name and path from the `#[action]` attribute arguments
+///
+/// Both default to the method name. `path = "..."` sets the path only,
+/// `name = "..."` is only validated and a bare string literal sets both.
+pub(crate) fn parse_action_route(attr: TokenStream2, fn_name: &str) -> ActionRoute {
+    let mut route = ActionRoute {
+        name: fn_name.to_string(),
+        path: fn_name.to_string(),
+        checked: None,
+    };
+    if attr.is_empty() {
+        return route;
+    }
+
+    // Convert attribute tokens to a string for simple parsing
+    let attr_str = attr.to_string();
+
+    // Extract attributes from the TokenStream
+    if attr_str.contains("path") {
+        // Try to parse as a name-value attribute
+        // For safety, we're using a simple string parsing approach
+        if attr_str.contains('=') && attr_str.contains('"') {
+            // Find the path value
+            let start_idx = attr_str.find("path").unwrap() + 4; // Skip 'path'
+            let equals_idx = attr_str[start_idx..].find('=').unwrap() + start_idx + 1; // Skip '='
+            let quote_start_idx = attr_str[equals_idx..].find('"').unwrap() + equals_idx + 1; // Skip opening quote
+            let quote_end_idx = attr_str[quote_start_idx..].find('"').unwrap() + quote_start_idx;
+
+            // Extract the path value
+            route.path = attr_str[quote_start_idx..quote_end_idx].to_string();
+            route.checked = Some(("path", route.path.clone()));
+        }
+    } else if let Some(name) = extract_name_value(&attr_str) {
+        // The name is only validated; the action keeps the method's name
+        route.checked = Some(("name", name));
+    } else {
+        // Try to parse as a simple string literal for backward compatibility
+        let parser = Punctuated::<Lit, Comma>::parse_terminated;
+        if let Ok(lit_args) = parser.parse2(attr) {
+            // Get the first argument as a string literal for the name
+            if let Some(Lit::Str(s)) = lit_args.first() {
+                route.name = s.value();
+                route.path = route.name.clone(); // Use the same value for path if not specified separately
+                route.checked = Some(("name", route.name.clone()));
+            }
+        }
+    }
+    route
+}
+
 /// Implementation of the action macro
 pub fn action_macro(attr: TokenStream, item: TokenStream) -> TokenStream {
     // Parse the input as a function
@@ -21,45 +99,49 @@ pub fn action_macro(attr: TokenStream, item: TokenStream) -> TokenStream {
     // Default to function name
     let fn_name = input.sig.ident.to_string();
 
-    // Parse the attributes
-    let mut action_name = fn_name.clone();
-    let mut action_path = fn_name.clone();
-
-    if !attr.is_empty() {
-        // Convert attribute tokens to a string for simple parsing
-        let attr_str = attr.to_string();
-
-        // Extract attributes from the TokenStream
-        if attr_str.contains("path") {
-            // Try to parse as a name-value attribute
-            // For safety, we're using a simple string parsing approach
-            let attr_str = attr.to_string();
-
-            if attr_str.contains("path") && attr_str.contains('=') && attr_str.contains('"') {
-                // Find the path value
-                let start_idx = attr_str.find("path").unwrap() + 4; // Skip 'path'
-                let equals_idx = attr_str[start_idx..].find('=').unwrap() + start_idx + 1; // Skip '='
-                let quote_start_idx = attr_str[equals_idx..].find('"').unwrap() + equals_idx + 1; // Skip opening quote
-                let quote_end_idx =
-                    attr_str[quote_start_idx..].find('"').unwrap() + quote_start_idx;
-
-                // Extract the path value
-                action_path = attr_str[quote_start_idx..quote_end_idx].to_string();
-            }
-        } else {
-            // Try to parse as a simple string literal for backward compatibility
-            let parser = Punctuated::<Lit, Comma>::parse_terminated;
-            if let Ok(lit_args) = parser.parse(attr.clone()) {
-                if !lit_args.is_empty() {
-                    // Get the first argument as a string literal for the name
-                    if let Lit::Str(s) = &lit_args[0] {
-                        action_name = s.value();
-                        action_path = action_name.clone(); // Use the same value for path if not specified separately
-                    }
-                }
-            }
-        }
+    // Mutable actions, and the others of their service, share it behind a lock
+    let (attr, mutable) = crate::utils::take_bool_flag(attr.into(), "mutable");
+    let (attr, locked) = crate::utils::take_bool_flag(attr, "locked");
+    // Record the latency and outcome of each call
+    let (attr, metrics) = crate::utils::take_bool_flag(attr, "metrics");
+    // Versioned actions are also registered under `v{version}/{path}`
+    let (attr, version) = crate::utils::take_int_arg(attr, "version");
+    // Registered by `#[service_impl]`, which reads them from the attribute itself
+    let (attr, register_types) = crate::utils::take_str_arg(attr, "register_types");
+    let register_types_error = register_types
+        .and_then(|list| crate::utils::parse_type_list(&list).err())
+        .map(|e| e.to_compile_error());
+    let (version, version_error) = match version.map(|lit| lit.base10_parse::<u8>()) {
+        Some(Ok(version)) => (Some(version), None),
+        Some(Err(e)) => (None, Some(e.to_compile_error())),
+        None => (None, None),
     };
+    let mutable = mutable.unwrap_or(false);
+    let lock = if mutable {
+        ServiceLock::Write
+    } else if locked.unwrap_or(false) {
+        ServiceLock::Read
+    } else {
+        ServiceLock::None
+    };
+    let receiver_error = match (mutable, crate::utils::has_mut_receiver(&input.sig)) {
+        (true, false) => Some(quote! {
+            compile_error!("`#[action(mutable = true)]` requires a `&mut self` receiver");
+        }),
+        (false, true) => Some(quote! {
+            compile_error!("Actions taking `&mut self` must be marked `#[action(mutable = true)]`");
+        }),
+        _ => None,
+    };
+
+    // Parse the attributes
+    let route = parse_action_route(attr, &fn_name);
+    let action_name = route.name;
+    let action_path = route.path;
+    // Reported next to the generated code so the service still type-checks
+    let validation_error: Option<TokenStream2> = route
+        .checked
+        .and_then(|(kind, value)| validate_action_attribute(kind, &value).err());
 
     // Extract parameters from the function signature
     let params = crate::utils::extract_parameters(&input);
@@ -180,7 +262,12 @@ pub fn action_macro(attr: TokenStream, item: TokenStream) -> TokenStream {
         &params,
         &return_type_info.is_primitive,
         &return_type_info.type_name,
+        return_type_info.result_kind,
         is_async,
+        return_type_info.awaits_call,
+        lock,
+        metrics.unwrap_or(false),
+        version,
         &lifecycle_ctx_ident, // Pass the ident for LifecycleContext
         original_fn_has_request_context_param,
         input_schema_tokens,
@@ -189,6 +276,11 @@ pub fn action_macro(attr: TokenStream, item: TokenStream) -> TokenStream {
 
     // Combine the original function with the generated register method
     let expanded = quote! {
+        #validation_error
+        #receiver_error
+        #version_error
+        #register_types_error
+
         #input
 
         #generated_code
@@ -200,6 +292,8 @@ pub fn action_macro(attr: TokenStream, item: TokenStream) -> TokenStream {
 /// Extract information about the return type for proper handling.
 // This function robustly supports all valid Rust types, including nested generics.
 fn extract_return_type_info(return_type: &ReturnType) -> ReturnTypeInfo {
+    let mut awaits_call = false;
+    let mut result_kind = ResultKind::Value;
     let (actual_type, is_unit, actual_type_is_option, is_primitive, type_name) = match return_type {
         ReturnType::Default => (
             syn::parse_quote! { () },
@@ -212,6 +306,12 @@ fn extract_return_type_info(return_type: &ReturnType) -> ReturnTypeInfo {
             let mut current_type = *original_ty.clone();
             let mut outer_is_option = false;
 
+            // A non-async fn returning impl Future<Output = Result<T>> is awaited first
+            if let Some(output_ty) = get_impl_future_output_type(&current_type) {
+                awaits_call = true;
+                current_type = output_ty.clone();
+            }
+
             // Check for outer Result<T, E>
             if let Some(inner_ty_of_result) = get_result_inner_type(&current_type) {
                 // is_result = true; // This info is not directly used by schema or ArcValue conversion logic anymore
@@ -224,6 +324,28 @@ fn extract_return_type_info(return_type: &ReturnType) -> ReturnTypeInfo {
                 current_type = inner_ty_of_option.clone();
             }
 
+            // Result<impl Future<Output = T>> is awaited after the call succeeds
+            if let Some(output_ty) = get_impl_future_output_type(&current_type) {
+                let fallible = get_result_inner_type(output_ty);
+                result_kind = ResultKind::Future {
+                    fallible: fallible.is_some(),
+                };
+                current_type = fallible.unwrap_or(output_ty).clone();
+            }
+            // ResponseStream<T> is streamed item by item; T describes each item
+            else if let Some(item_ty) = get_response_stream_inner_type(&current_type) {
+                result_kind = ResultKind::ResponseStream;
+                current_type = item_ty.clone();
+            }
+            // Box<dyn Stream<Item = Result<T>>> is streamed like a ResponseStream<T>
+            else if let Some(item_ty) = get_dyn_stream_item_type(&current_type) {
+                let fallible = get_result_inner_type(item_ty);
+                result_kind = ResultKind::DynStream {
+                    fallible: fallible.is_some(),
+                };
+                current_type = fallible.unwrap_or(item_ty).clone();
+            }
+
             // Now current_type is the innermost T
             let type_name_str = if let syn::Type::Path(type_path) = &current_type {
                 get_path_last_segment_ident_string(type_path)
@@ -240,10 +362,7 @@ fn extract_return_type_info(return_type: &ReturnType) -> ReturnTypeInfo {
                 quote!(#current_type).to_string().replace(" ", "")
             };
 
-            let is_primitive_val = matches!(
-                type_name_str.as_str(),
-                "String" | "str" | "i32" | "i64" | "f32" | "f64" | "bool" | "unit"
-            );
+            let is_primitive_val = is_primitive_type_name(&type_name_str);
 
             (
                 current_type,
@@ -261,6 +380,8 @@ fn extract_return_type_info(return_type: &ReturnType) -> ReturnTypeInfo {
         actual_type,
         actual_type_is_option,
         type_name,
+        awaits_call,
+        result_kind,
     }
 }
 
@@ -272,10 +393,84 @@ struct ReturnTypeInfo {
     actual_type: Type,
     actual_type_is_option: bool,
     type_name: String,
+    /// The method returns impl Future, so its result must be awaited
+    awaits_call: bool,
+    result_kind: ResultKind,
+}
+
+/// How the `Ok` value of an action method becomes the action's result
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ResultKind {
+    /// A value converted to an ArcValue
+    Value,
+    /// An impl Future yielding the value, possibly wrapped in a Result
+    Future { fallible: bool },
+    /// A ResponseStream whose items are converted one by one
+    ResponseStream,
+    /// A boxed dyn Stream forwarded through a ResponseStream
+    DynStream { fallible: bool },
+}
+
+/// How a handler reaches the service instance
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ServiceLock {
+    /// Each handler holds its own clone of the service
+    None,
+    /// Handlers share the service and take the read lock
+    Read,
+    /// Handlers share the service and take the write lock
+    Write,
+}
+
+/// Type to register with the serializer for an action's return type
+///
+/// Applies the same unwrapping as `extract_return_type_info`: futures, the
+/// outer Result and streams are reduced to the value type sent to the caller.
+/// Returns None when that type is an `impl Trait` or trait object, which
+/// cannot be registered.
+pub(crate) fn registrable_return_type(ty: &Type) -> Option<Type> {
+    let mut current_type = get_impl_future_output_type(ty).unwrap_or(ty);
+    current_type = get_result_inner_type(current_type).unwrap_or(current_type);
+    if let Some(output_ty) = get_impl_future_output_type(current_type) {
+        current_type = get_result_inner_type(output_ty).unwrap_or(output_ty);
+    } else if let Some(item_ty) = get_response_stream_inner_type(current_type) {
+        current_type = item_ty;
+    } else if let Some(item_ty) = get_dyn_stream_item_type(current_type) {
+        current_type = get_result_inner_type(item_ty).unwrap_or(item_ty);
+    }
+
+    match current_type {
+        Type::ImplTrait(_) | Type::TraitObject(_) => None,
+        _ => Some(current_type.clone()),
+    }
+}
+
+/// Type a caller receives from a request to the action, if it is a single value
+///
+/// Like `registrable_return_type`, but streaming actions yield None since
+/// their items cannot be read with a plain request.
+pub(crate) fn request_return_type(ty: &Type) -> Option<Type> {
+    let mut current_type = get_impl_future_output_type(ty).unwrap_or(ty);
+    current_type = get_result_inner_type(current_type).unwrap_or(current_type);
+    if get_response_stream_inner_type(current_type).is_some()
+        || get_dyn_stream_item_type(current_type).is_some()
+    {
+        return None;
+    }
+    registrable_return_type(ty)
+}
+
+/// Whether values of the named type are wrapped with `ArcValue::new_primitive`
+/// rather than `ArcValue::from_struct`
+pub(crate) fn is_primitive_type_name(type_name: &str) -> bool {
+    matches!(
+        type_name,
+        "String" | "str" | "i32" | "i64" | "f32" | "f64" | "bool" | "unit"
+    )
 }
 
 // Helper to get the last segment of a TypePath as a String
-fn get_path_last_segment_ident_string(type_path: &syn::TypePath) -> Option<String> {
+pub(crate) fn get_path_last_segment_ident_string(type_path: &syn::TypePath) -> Option<String> {
     type_path
         .path
         .segments
@@ -284,7 +479,7 @@ fn get_path_last_segment_ident_string(type_path: &syn::TypePath) -> Option<Strin
 }
 
 // Helper to extract T from Option<T>
-fn get_option_inner_type(ty: &syn::Type) -> Option<&syn::Type> {
+pub(crate) fn get_option_inner_type(ty: &syn::Type) -> Option<&syn::Type> {
     if let syn::Type::Path(type_path) = ty {
         if type_path.path.segments.len() == 1
             && type_path.path.segments.first().unwrap().ident == "Option"
@@ -318,6 +513,80 @@ fn get_result_inner_type(ty: &syn::Type) -> Option<&syn::Type> {
     None
 }
 
+// Helper to extract T from ResponseStream<T>, with or without a path prefix
+fn get_response_stream_inner_type(ty: &syn::Type) -> Option<&syn::Type> {
+    if let syn::Type::Path(type_path) = ty {
+        let segment = type_path.path.segments.last()?;
+        if segment.ident == "ResponseStream" {
+            if let PathArguments::AngleBracketed(params) = &segment.arguments {
+                if let Some(GenericArgument::Type(inner_ty)) = params.args.first() {
+                    return Some(inner_ty);
+                }
+            }
+        }
+    }
+    None
+}
+
+// Helper to extract T from impl Future<Output = T>
+fn get_impl_future_output_type(ty: &syn::Type) -> Option<&syn::Type> {
+    let syn::Type::ImplTrait(impl_trait) = ty else {
+        return None;
+    };
+    impl_trait.bounds.iter().find_map(|bound| {
+        let syn::TypeParamBound::Trait(trait_bound) = bound else {
+            return None;
+        };
+        get_assoc_type_of_trait(&trait_bound.path, "Future", "Output")
+    })
+}
+
+// Helper to extract T from Box<dyn Stream<Item = T>> or Pin<Box<dyn Stream<Item = T>>>
+fn get_dyn_stream_item_type(ty: &syn::Type) -> Option<&syn::Type> {
+    let mut current = ty;
+    for wrapper in ["Pin", "Box"] {
+        if let syn::Type::Path(type_path) = current {
+            let segment = type_path.path.segments.last()?;
+            if segment.ident == wrapper {
+                if let PathArguments::AngleBracketed(params) = &segment.arguments {
+                    if let Some(GenericArgument::Type(inner_ty)) = params.args.first() {
+                        current = inner_ty;
+                    }
+                }
+            }
+        }
+    }
+
+    let syn::Type::TraitObject(trait_object) = current else {
+        return None;
+    };
+    trait_object.bounds.iter().find_map(|bound| {
+        let syn::TypeParamBound::Trait(trait_bound) = bound else {
+            return None;
+        };
+        get_assoc_type_of_trait(&trait_bound.path, "Stream", "Item")
+    })
+}
+
+// Helper to extract T from a trait bound such as Future<Output = T>
+fn get_assoc_type_of_trait<'a>(
+    path: &'a syn::Path,
+    trait_name: &str,
+    assoc_name: &str,
+) -> Option<&'a syn::Type> {
+    let segment = path.segments.last()?;
+    if segment.ident != trait_name {
+        return None;
+    }
+    let PathArguments::AngleBracketed(params) = &segment.arguments else {
+        return None;
+    };
+    params.args.iter().find_map(|arg| match arg {
+        GenericArgument::AssocType(assoc) if assoc.ident == assoc_name => Some(&assoc.ty),
+        _ => None,
+    })
+}
+
 // Helper to extract T from Vec<T>
 fn get_vec_inner_type(ty: &syn::Type) -> Option<&syn::Type> {
     if let syn::Type::Path(type_path) = ty {
@@ -456,7 +725,12 @@ fn generate_register_action_method(
     params: &[(Ident, Type)],
     is_primitive: &bool,
     type_name: &String,
+    result_kind: ResultKind,
     is_async: bool,
+    awaits_call: bool,
+    lock: ServiceLock,
+    metrics: bool,
+    version: Option<u8>,
     _lifecycle_ctx_ident: &Ident, // Renamed as it's for the LifecycleContext, not the RequestContext for the handler
     original_fn_has_request_context_param: bool,
     input_schema_opt_tokens: TokenStream2,
@@ -490,9 +764,18 @@ fn generate_register_action_method(
         is_async,
         original_fn_has_request_context_param,
     );
+    // A method returning impl Future yields its Result only once awaited
+    let method_call = if awaits_call {
+        quote! { #method_call.await }
+    } else {
+        method_call
+    };
+
+    // The handler's RequestContext parameter is hardcoded to `ctx`
+    let handler_request_ctx_ident = format_ident!("ctx");
 
     // Generate the appropriate result handling based on the return type
-    let result_handling = if type_name == "()" {
+    let value_handling = if type_name == "()" {
         quote! {
             // For () return type, convert to ArcValue::null()
             Ok(runar_common::types::ArcValue::null())
@@ -517,18 +800,157 @@ fn generate_register_action_method(
         }
     };
 
+    // Streamed items are converted the same way a single result would be
+    let convert_item = if type_name == "ArcValue" {
+        quote! { |item| item }
+    } else if *is_primitive {
+        quote! { runar_common::types::ArcValue::new_primitive }
+    } else {
+        quote! { runar_common::types::ArcValue::from_struct }
+    };
+
+    let result_handling = match result_kind {
+        ResultKind::Value => value_handling,
+        ResultKind::Future { fallible: false } => quote! {
+            // The method returned a future; its output is the action result
+            let result = result.await;
+            #value_handling
+        },
+        ResultKind::Future { fallible: true } => quote! {
+            // The method returned a future; its output is the action result
+            let result = match result.await {
+                Ok(value) => value,
+                Err(err) => {
+                    #handler_request_ctx_ident.error(format!("Action '{}' failed: {}", #action_name, err));
+                    return Err(anyhow!(err.to_string()));
+                }
+            };
+            #value_handling
+        },
+        ResultKind::ResponseStream => quote! {
+            // Hand the stream to the node, which forwards it item by item
+            Ok(result.into_arc_value(#convert_item))
+        },
+        ResultKind::DynStream { fallible } => {
+            let constructor = if fallible {
+                quote! { from_try_stream }
+            } else {
+                quote! { from_stream }
+            };
+            quote! {
+                // Forward the stream through a ResponseStream so it is sent item by item
+                Ok(runar_node::services::ResponseStream::#constructor(result)
+                    .into_arc_value(#convert_item))
+            }
+        }
+    };
+
     // Generate a unique method name for the action registration
     let register_method_name = format_ident!("register_action_{}", fn_ident);
 
-    // The handler's RequestContext parameter is hardcoded to `ctx`
-    let handler_request_ctx_ident = format_ident!("ctx");
+    // Shared services are registered through the lock instead of a clone of self
+    let (register_receiver, shared_self) = match lock {
+        ServiceLock::None => (quote! { &self }, quote! { self.clone() }),
+        ServiceLock::Read | ServiceLock::Write => (
+            quote! { service: &runar_node::services::abstract_service::SharedService<Self> },
+            quote! { service.clone() },
+        ),
+    };
+    let acquire_lock = match lock {
+        ServiceLock::None => quote! {},
+        ServiceLock::Read => quote! {
+            let inner_self = inner_self.read().await;
+        },
+        ServiceLock::Write => quote! {
+            // Mutable actions run one at a time
+            let mut inner_self = inner_self.write().await;
+        },
+    };
+
+    let handler_body = quote! {
+        // Extract parameters from the map if available
+        let mut params_value = match params_opt {
+            Some(p) => p,
+            None => {
+                // Check if method expects parameters
+                if #has_params {
+                    #handler_request_ctx_ident.error("No parameters provided".to_string());
+                    return Err(anyhow!("No parameters provided"));
+                } else {
+                    // No parameters expected, so create an empty map
+                    runar_common::types::ArcValue::new_map(
+                        std::collections::HashMap::<String, runar_common::types::ArcValue>::new()
+                    )
+                }
+            }
+        };
+
+        // #param_extractions uses `ctx` internally, which should resolve to #handler_request_ctx_ident
+        #param_extractions
+        #acquire_lock
+
+        // Call the actual method with the extracted parameters
+        match #method_call {
+            Ok(result) => {
+                #result_handling
+            },
+            Err(err) => {
+                // Return an error response
+                #handler_request_ctx_ident.error(format!("Action '{}' failed: {}", #action_name, err));
+                return Err(anyhow!(err.to_string()));
+            }
+        }
+    };
+    // Handlers holding the service lock fail instead of deadlocking on re-entry
+    let handler_body = match lock {
+        ServiceLock::None => handler_body,
+        ServiceLock::Read | ServiceLock::Write => quote! {
+            let locked_self = inner_self.clone();
+            runar_node::services::abstract_service::run_locked_handler(&locked_self, async move {
+                #handler_body
+            })
+            .await
+        },
+    };
+    // Annotated actions run their body as an inner future so every exit is measured
+    let handler_body = if metrics {
+        quote! {
+            let metrics_ctx = #handler_request_ctx_ident.clone();
+            let started = std::time::Instant::now();
+            let result: Result<runar_common::types::ArcValue, anyhow::Error> = async move {
+                #handler_body
+            }
+            .await;
+            runar_node::services::action_metrics::record_action_call(
+                &metrics_ctx,
+                #action_name,
+                started.elapsed(),
+                result.is_ok(),
+            );
+            result
+        }
+    } else {
+        handler_body
+    };
+
+    // The unversioned path stays registered for existing callers
+    let register_versioned = version.map(|version| {
+        let versioned_path = format!("v{version}/{action_path}");
+        quote! {
+            context.register_action_with_options(
+                #versioned_path,
+                handler.clone(),
+                action_registration_options()
+            ).await?;
+        }
+    });
 
     quote! {
-        async fn #register_method_name(&self, context: &runar_node::services::LifecycleContext) -> anyhow::Result<()> {
+        async fn #register_method_name(#register_receiver, context: &runar_node::services::LifecycleContext) -> anyhow::Result<()> {
             context.logger.info(format!("Registering '{}' action", #action_name));
 
             // Create a clone of self that can be moved into the closure
-            let self_clone = self.clone();
+            let self_clone = #shared_self;
 
             // Create the action handler as an Arc to match what the register_action expects
             let handler = std::sync::Arc::new(move |params_opt: Option<runar_common::types::ArcValue>, #handler_request_ctx_ident: runar_node::services::RequestContext|
@@ -536,42 +958,12 @@ fn generate_register_action_method(
                 let inner_self = self_clone.clone();
 
                 Box::pin(async move {
-                    // Extract parameters from the map if available
-                    let mut params_value = match params_opt {
-                        Some(p) => p,
-                        None => {
-                            // Check if method expects parameters
-                            if #has_params {
-                                #handler_request_ctx_ident.error("No parameters provided".to_string());
-                                return Err(anyhow!("No parameters provided"));
-                            } else {
-                                // No parameters expected, so create an empty map
-                                runar_common::types::ArcValue::new_map(
-                                    std::collections::HashMap::<String, runar_common::types::ArcValue>::new()
-                                )
-                            }
-                        }
-                    };
-
-                    // #param_extractions uses `ctx` internally, which should resolve to #handler_request_ctx_ident
-                    #param_extractions
-
-                    // Call the actual method with the extracted parameters
-                    match #method_call {
-                        Ok(result) => {
-                            #result_handling
-                        },
-                        Err(err) => {
-                            // Return an error response
-                            #handler_request_ctx_ident.error(format!("Action '{}' failed: {}", #action_name, err));
-                            return Err(anyhow!(err.to_string()));
-                        }
-                    }
+                    #handler_body
                 })
             });
 
             // Construct ActionRegistrationOptions
-            let action_registration_options = ::runar_node::services::ActionRegistrationOptions {
+            let action_registration_options = || ::runar_node::services::ActionRegistrationOptions {
                 description: Some(#action_name.to_string()),
                 input_schema: #input_schema_opt_tokens,
                 output_schema: #output_schema_opt_tokens,
@@ -581,8 +973,9 @@ fn generate_register_action_method(
             context.register_action_with_options(
                 #action_path, // This is &str
                 handler.clone(),      // Pass the Arc'd handler closure
-                action_registration_options
+                action_registration_options()
             ).await?;
+            #register_versioned
 
             Ok(())
         }
@@ -6,29 +6,91 @@ extern crate proc_macro;
 
 mod action;
 mod meta_support;
+mod openapi;
 mod publish;
 mod service;
+mod service_client;
 mod service_meta;
 mod subscribe;
 mod utils;
 use proc_macro::TokenStream;
 
 /// Struct-level metadata macro (was `service_meta`)
+///
+/// The struct gets a generated `Clone` impl unless `mutable = true` is
+/// given, which services with mutable actions need.
+///
+/// The struct may have type parameters, with their bounds written inline or
+/// in a where clause; the generated impls carry the same bounds.
+///
+/// `access = "local_only"` rejects requests from remote peers and
+/// `access = "peers: peer1,peer2"` only accepts the listed peers (see
+/// `ServiceAccessPolicy`); the policy can be replaced at runtime with
+/// `Node::update_service_policy`.
 #[proc_macro_attribute]
 pub fn service(attr: TokenStream, item: TokenStream) -> TokenStream {
     service_meta::service_meta_impl(attr, item)
 }
 
 /// Impl-level macro that wires the service to the runtime (was `service`)
+///
+/// With `#[service_impl(openapi = true)]` it also generates
+/// `openapi_schema() -> serde_json::Value`, an OpenAPI 3.1 document with one
+/// path per `#[action]`, and serves it through an `openapi_schema` action for
+/// the gateway's `/openapi.json`. Action parameter and return types must then
+/// implement `schemars::JsonSchema`, and the crate must depend on `schemars`
+/// and `serde_json`.
+///
+/// The impl may be generic over the service's type parameters. Parameter and
+/// return types mentioning them are not registered with the serializer, since
+/// they have no concrete type yet; list the types to register with
+/// `#[action(register_types = "...")]`.
 #[proc_macro_attribute]
 pub fn service_impl(attr: TokenStream, item: TokenStream) -> TokenStream {
     service::service_macro(attr, item)
 }
 
+/// Impl-level macro that generates a typed client for the service's actions
+///
+/// Applied next to `#[service_impl]`, it generates a `{Service}Client` struct
+/// with one async method per `#[action]`, taking the action's parameters and
+/// returning its result. The service path defaults to the one given to
+/// `#[service]` and can be set with `#[service_client(path = "...")]`.
+#[proc_macro_attribute]
+pub fn service_client(attr: TokenStream, item: TokenStream) -> TokenStream {
+    service_client::service_client_macro(attr, item)
+}
+
 /// Action macro for registering service actions
 ///
 /// This macro generates the necessary code to register a method as an action
 /// that can be called via the request mechanism.
+///
+/// With `#[action(mutable = true)]` the method takes `&mut self`. The service
+/// is then a `MutableService`, declared with `#[service(mutable = true)]`,
+/// which must not be `Clone` or `Copy`, and added to the node wrapped in a
+/// `RwLockService`. All its handlers share one instance behind a
+/// `tokio::sync::RwLock`: mutable actions take the write lock, so concurrent
+/// calls to them are serialized, and the other handlers take the read lock.
+/// The lock is held until the handler returns, including while it awaits
+/// requests to other services, so a slow request stalls every other handler
+/// of the service. A handler holding the lock that calls back into its own
+/// service, directly or through other local services, gets an error instead
+/// of waiting forever on the lock.
+///
+/// With `#[action(metrics = true)]` every call records its latency in the
+/// `request_duration_ms` histogram and increments `request_count`, plus
+/// `error_count` when it fails, labeled with `service.path` and `action.name`.
+/// The metrics go to the node's `metrics_registry`, or to the global recorder
+/// of the `metrics` crate.
+///
+/// With `#[action(version = 2)]` the action is registered under both its path
+/// and `v2/{path}`, so callers requesting version 2 of the API reach it. See
+/// `runar-node/docs/api-versioning.md`.
+///
+/// With `#[action(register_types = "Vec<MyType>, Option<MyType>")]` the listed
+/// types are registered with the serializer when the service starts, for
+/// generic parameter types that `#[service_impl]` cannot register itself.
 #[proc_macro_attribute]
 pub fn action(attr: TokenStream, item: TokenStream) -> TokenStream {
     action::action_macro(attr, item)
@@ -38,6 +100,13 @@ pub fn action(attr: TokenStream, item: TokenStream) -> TokenStream {
 ///
 /// This macro generates the necessary code to register a method as an event
 /// handler that will be called when events are published to the specified path.
+///
+/// With `#[subscribe(path = "...", group = "workers")]` the subscriptions of a
+/// group compete for the events: each event is delivered to one member, chosen
+/// round-robin. An empty group (the default) delivers every event.
+///
+/// `#[subscribe(topics = ["user/created", "user/deleted"])]` registers the
+/// method for each of the listed topics.
 #[proc_macro_attribute]
 pub fn subscribe(attr: TokenStream, item: TokenStream) -> TokenStream {
     subscribe::subscribe_macro(attr, item)
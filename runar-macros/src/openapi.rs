@@ -0,0 +1,208 @@
+// OpenAPI generation for the service macro
+//
+// This module generates the `openapi_schema()` method added by
+// `#[service_impl(openapi = true)]`, describing each #[action] the way the
+// gateway exposes it over HTTP.
+
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{Expr, ImplItem, ImplItemFn, ItemImpl, Lit, Meta, ReturnType, Type};
+
+/// Name of the action through which the gateway fetches the OpenAPI document
+pub(crate) const OPENAPI_ACTION: &str = "openapi_schema";
+
+/// Generate `openapi_schema()` and the registration of its action
+///
+/// Parameter and return types must implement `schemars::JsonSchema`; their
+/// schemas end up in `components/schemas` and are referenced from the paths.
+pub(crate) fn generate_openapi_impl(input: &ItemImpl) -> TokenStream2 {
+    let path_entries = input.items.iter().filter_map(|item| match item {
+        ImplItem::Fn(method) => generate_path_entry(method),
+        _ => None,
+    });
+
+    let self_ty = &input.self_ty;
+    let (impl_generics, _, where_clause) = input.generics.split_for_impl();
+
+    quote! {
+        impl #impl_generics #self_ty #where_clause {
+            /// OpenAPI 3.1 document with one path per action, as exposed by the gateway
+            pub fn openapi_schema() -> serde_json::Value {
+                Self::__runar_openapi_schema(&<Self as ::core::default::Default>::default())
+            }
+
+            #[doc(hidden)]
+            fn __runar_openapi_schema(service: &Self) -> serde_json::Value {
+                let service_path = &service.__runar_path;
+                let mut generator = schemars::gen::SchemaSettings::draft2019_09()
+                    .with(|settings| {
+                        settings.definitions_path = "#/components/schemas/".to_string();
+                        settings.meta_schema = None;
+                    })
+                    .into_generator();
+                let mut paths = serde_json::Map::new();
+                #(#path_entries)*
+                let schemas = serde_json::to_value(generator.take_definitions())
+                    .expect("JSON schemas always serialize");
+                serde_json::json!({
+                    "openapi": "3.1.0",
+                    "info": {
+                        "title": service.__runar_name,
+                        "version": service.__runar_version,
+                        "description": service.__runar_description,
+                    },
+                    "paths": paths,
+                    "components": { "schemas": schemas },
+                })
+            }
+
+            async fn register_action_openapi_schema(&self, context: &runar_node::services::LifecycleContext) -> anyhow::Result<()> {
+                let schema = Self::__runar_openapi_schema(self);
+                let handler = std::sync::Arc::new(move |_params_opt: Option<runar_common::types::ArcValue>, _ctx: runar_node::services::RequestContext|
+                    -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<runar_common::types::ArcValue, anyhow::Error>> + Send>> {
+                    let schema = schema.clone();
+                    Box::pin(async move { Ok(runar_common::types::ArcValue::from_json(schema)) })
+                });
+                let action_registration_options = ::runar_node::services::ActionRegistrationOptions {
+                    description: Some("OpenAPI document of the service's actions".to_string()),
+                    input_schema: None,
+                    output_schema: Some(::runar_common::types::schemas::FieldSchema::new(
+                        "output_payload",
+                        ::runar_common::types::schemas::SchemaDataType::Any,
+                    )),
+                };
+                context.register_action_with_options(#OPENAPI_ACTION, handler, action_registration_options).await?;
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Generate the code inserting the path of an #[action] method into `paths`
+///
+/// Actions without parameters are described as GET and the others as POST
+/// with a JSON object of named parameters, matching the gateway routes.
+fn generate_path_entry(method: &ImplItemFn) -> Option<TokenStream2> {
+    let action_attr = method
+        .attrs
+        .iter()
+        .find(|attr| attr.path().is_ident("action"))?;
+    let attr_tokens = match &action_attr.meta {
+        Meta::List(list) => list.tokens.clone(),
+        _ => TokenStream2::new(),
+    };
+    let action_path =
+        crate::action::parse_action_route(attr_tokens, &method.sig.ident.to_string()).path;
+
+    let params: Vec<_> = crate::utils::extract_signature_parameters(&method.sig)
+        .into_iter()
+        .filter(|(_, ty)| !crate::service_client::is_request_context(ty))
+        .collect();
+    let (http_method, request_body) = if params.is_empty() {
+        ("get", quote! {})
+    } else {
+        let inserts = params.iter().map(|(ident, ty)| {
+            let name = ident.to_string();
+            quote! {
+                properties.insert(
+                    #name.to_string(),
+                    serde_json::to_value(generator.subschema_for::<#ty>())
+                        .expect("JSON schemas always serialize"),
+                );
+            }
+        });
+        let required = params
+            .iter()
+            .filter(|(_, ty)| crate::action::get_option_inner_type(ty).is_none())
+            .map(|(ident, _)| ident.to_string());
+        (
+            "post",
+            quote! {
+                let mut properties = serde_json::Map::new();
+                #(#inserts)*
+                operation.insert("requestBody".to_string(), serde_json::json!({
+                    "required": true,
+                    "content": {
+                        "application/json": {
+                            "schema": {
+                                "type": "object",
+                                "properties": properties,
+                                "required": [#(#required),*],
+                            }
+                        }
+                    }
+                }));
+            },
+        )
+    };
+
+    let ok_response = match &method.sig.output {
+        ReturnType::Type(_, ty) => match crate::action::request_return_type(ty) {
+            Some(Type::Tuple(tuple)) if tuple.elems.is_empty() => {
+                quote! { serde_json::json!({ "description": "Success" }) }
+            }
+            Some(value_ty) => quote! {
+                serde_json::json!({
+                    "description": "Success",
+                    "content": {
+                        "application/json": {
+                            "schema": serde_json::to_value(generator.subschema_for::<#value_ty>())
+                                .expect("JSON schemas always serialize"),
+                        }
+                    }
+                })
+            },
+            None => quote! { serde_json::json!({ "description": "Streamed response" }) },
+        },
+        ReturnType::Default => quote! { serde_json::json!({ "description": "Success" }) },
+    };
+
+    let description = doc_comment(method);
+    let description_insert = (!description.is_empty()).then(|| {
+        quote! {
+            operation.insert("description".to_string(), serde_json::Value::String(#description.to_string()));
+        }
+    });
+
+    Some(quote! {
+        {
+            let mut operation = serde_json::Map::new();
+            operation.insert(
+                "operationId".to_string(),
+                serde_json::Value::String(format!("{}/{}", service_path, #action_path)),
+            );
+            #description_insert
+            #request_body
+            operation.insert("responses".to_string(), serde_json::json!({
+                "200": #ok_response,
+                "500": { "description": "The action failed" },
+            }));
+            paths.insert(
+                format!("/{}/{}", service_path, #action_path),
+                serde_json::json!({ #http_method: operation }),
+            );
+        }
+    })
+}
+
+/// The method's doc comment, one line per `///` line
+fn doc_comment(method: &ImplItemFn) -> String {
+    method
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("doc"))
+        .filter_map(|attr| match &attr.meta {
+            Meta::NameValue(name_value) => match &name_value.value {
+                Expr::Lit(expr_lit) => match &expr_lit.lit {
+                    Lit::Str(doc) => Some(doc.value().trim().to_string()),
+                    _ => None,
+                },
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim()
+        .to_string()
+}
@@ -6,17 +6,19 @@
 
 use proc_macro::TokenStream;
 use proc_macro2::TokenStream as TokenStream2;
+use proc_macro2::TokenTree;
+use quote::ToTokens;
 use quote::{format_ident, quote};
 use std::collections::{HashMap, HashSet};
 use syn::{
-    parse_macro_input, FnArg, Ident, ImplItem, ImplItemFn, ItemImpl, Pat, PatType, ReturnType,
-    Type, TypePath,
+    parse_macro_input, FnArg, Generics, Ident, ImplItem, ImplItemFn, ItemImpl, Meta, Pat, PatType,
+    ReturnType, Type, TypePath,
 };
 
 /// Implementation of the service macro
 pub fn service_macro(attr: TokenStream, item: TokenStream) -> TokenStream {
     // Parse the input as a struct
-    let input = parse_macro_input!(item as ItemImpl);
+    let mut input = parse_macro_input!(item as ItemImpl);
 
     // Extract the struct name
     let struct_type = match &*input.self_ty {
@@ -27,21 +29,39 @@ pub fn service_macro(attr: TokenStream, item: TokenStream) -> TokenStream {
     // Extract the service attributes from the macro annotation
     let service_attrs = extract_service_attributes(attr);
 
+    // Services with mutable actions share one instance behind a lock
+    let mutable = has_mutable_actions(&input);
+    if mutable {
+        lock_handlers(&mut input);
+    }
+
     // Find all methods marked with #[action] or #[subscribe]
     let all_methods = collect_action_methods(&input);
 
     // Generate the trait implementation for the AbstractService trait
-    let service_impl = generate_abstract_service_impl(&struct_type, &all_methods, &service_attrs);
+    let service_impl = generate_abstract_service_impl(
+        &struct_type,
+        &input.self_ty,
+        &input.generics,
+        &all_methods,
+        &service_attrs,
+        mutable,
+    );
+
+    let openapi_impl = (openapi_enabled(&service_attrs) && !mutable)
+        .then(|| crate::openapi::generate_openapi_impl(&input));
 
     TokenStream::from(quote! {
         #input
 
         #service_impl
+
+        #openapi_impl
     })
 }
 
 /// Extract service attributes from the TokenStream
-fn extract_service_attributes(attr: TokenStream) -> HashMap<String, String> {
+pub(crate) fn extract_service_attributes(attr: TokenStream) -> HashMap<String, String> {
     let mut attrs = HashMap::new();
 
     if attr.is_empty() {
@@ -57,11 +77,13 @@ fn extract_service_attributes(attr: TokenStream) -> HashMap<String, String> {
         if parts.len() == 2 {
             let key = parts[0].trim().to_string();
 
-            // Extract the string value between quotes
+            // Extract the string value between quotes; flags are unquoted booleans
             let value_part = parts[1].trim();
             if value_part.starts_with('"') && value_part.ends_with('"') {
                 let value = value_part[1..value_part.len() - 1].to_string();
                 attrs.insert(key, value);
+            } else if value_part == "true" || value_part == "false" {
+                attrs.insert(key, value_part.to_string());
             }
         }
     }
@@ -69,6 +91,58 @@ fn extract_service_attributes(attr: TokenStream) -> HashMap<String, String> {
     attrs
 }
 
+/// Whether `openapi = true` was given, generating `openapi_schema()`
+fn openapi_enabled(service_attrs: &HashMap<String, String>) -> bool {
+    service_attrs.get("openapi").map(String::as_str) == Some("true")
+}
+
+/// Whether an action in the impl block is marked `#[action(mutable = true)]`
+fn has_mutable_actions(input: &ItemImpl) -> bool {
+    input.items.iter().any(|item| {
+        let ImplItem::Fn(method) = item else {
+            return false;
+        };
+        method.attrs.iter().any(|attr| match &attr.meta {
+            Meta::List(list) if list.path.is_ident("action") => {
+                crate::utils::take_bool_flag(list.tokens.clone(), "mutable").1 == Some(true)
+            }
+            _ => false,
+        })
+    })
+}
+
+/// Add `locked = true` to the #[action] and #[subscribe] attributes
+///
+/// The handlers then reach the service through its lock rather than a clone.
+fn lock_handlers(input: &mut ItemImpl) {
+    for item in &mut input.items {
+        let ImplItem::Fn(method) = item else {
+            continue;
+        };
+        for attr in &mut method.attrs {
+            if !(attr.path().is_ident("action") || attr.path().is_ident("subscribe")) {
+                continue;
+            }
+            match &mut attr.meta {
+                Meta::List(list) => {
+                    let tokens = &list.tokens;
+                    list.tokens = if tokens.is_empty() {
+                        quote! { locked = true }
+                    } else if tokens.to_string().trim_end().ends_with(',') {
+                        quote! { #tokens locked = true }
+                    } else {
+                        quote! { #tokens, locked = true }
+                    };
+                }
+                Meta::Path(path) => {
+                    *attr = syn::parse_quote! { #[#path(locked = true)] };
+                }
+                Meta::NameValue(_) => {}
+            }
+        }
+    }
+}
+
 /// Collect methods marked with #[action] or #[subscribe] in the impl block
 fn collect_action_methods(input: &ItemImpl) -> Vec<(Ident, &str, ImplItemFn)> {
     // Find all methods marked with #[action] or #[subscribe]
@@ -104,8 +178,32 @@ fn collect_action_methods(input: &ItemImpl) -> Vec<(Ident, &str, ImplItemFn)> {
 }
 
 /// Extract types from a method's parameters and return type
-fn extract_types_from_method(method: &ImplItemFn) -> Vec<String> {
+///
+/// Types mentioning a type parameter of the impl or the method are not
+/// resolved to a concrete type yet, so they are returned separately: the
+/// serializer cannot register them.
+fn extract_types_from_method(
+    method: &ImplItemFn,
+    impl_type_params: &HashSet<String>,
+) -> (Vec<String>, Vec<String>) {
+    let mut type_params = impl_type_params.clone();
+    type_params.extend(
+        method
+            .sig
+            .generics
+            .type_params()
+            .map(|param| param.ident.to_string()),
+    );
     let mut types = Vec::new();
+    let mut generic_types = Vec::new();
+    let mut push = |ty: &Type| {
+        let type_str = quote! { #ty }.to_string();
+        if mentions_type_param(ty.to_token_stream(), &type_params) {
+            generic_types.push(type_str);
+        } else {
+            types.push(type_str);
+        }
+    };
 
     // Extract parameter types
     for arg in &method.sig.inputs {
@@ -118,42 +216,45 @@ fn extract_types_from_method(method: &ImplItemFn) -> Vec<String> {
                 }
             }
 
-            // Get the type as a string
-            let type_str = quote! { #ty }.to_string();
-            types.push(type_str);
+            push(ty);
         }
     }
 
-    // Extract return type
+    // Extract return type, reduced to the value sent to the caller
     if let ReturnType::Type(_, ty) = &method.sig.output {
-        // Use syn AST to extract Ok type from Result<T, E>
-        if let syn::Type::Path(type_path) = &**ty {
-            let seg = type_path.path.segments.last();
-            if let Some(seg) = seg {
-                if seg.ident == "Result" {
-                    if let syn::PathArguments::AngleBracketed(ref ab) = seg.arguments {
-                        // Find the first type argument (the Ok type)
-                        for arg in &ab.args {
-                            if let syn::GenericArgument::Type(ref inner_ty) = arg {
-                                let ok_type_str = quote! { #inner_ty }.to_string();
-                                types.push(ok_type_str);
-                                break;
-                            }
-                        }
-                    }
-                } else {
-                    // Not a Result, just add the type directly
-                    let return_type_str = quote! { #ty }.to_string();
-                    types.push(return_type_str);
-                }
-            }
-        } else {
-            // For non-Result types, just add the type directly
-            types.push(quote! { #ty }.to_string());
+        if let Some(value_ty) = crate::action::registrable_return_type(ty) {
+            push(&value_ty);
         }
     }
 
-    types
+    (types, generic_types)
+}
+
+/// Whether a type refers to one of the given type parameters
+fn mentions_type_param(tokens: TokenStream2, type_params: &HashSet<String>) -> bool {
+    tokens.into_iter().any(|token| match token {
+        TokenTree::Ident(ident) => type_params.contains(&ident.to_string()),
+        TokenTree::Group(group) => mentions_type_param(group.stream(), type_params),
+        _ => false,
+    })
+}
+
+/// Types listed in `#[action(register_types = "...")]`
+///
+/// Invalid lists are reported by the action macro and ignored here.
+fn extra_registered_types(method: &ImplItemFn) -> Vec<String> {
+    method
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("action"))
+        .filter_map(|attr| match &attr.meta {
+            Meta::List(list) => crate::utils::take_str_arg(list.tokens.clone(), "register_types").1,
+            _ => None,
+        })
+        .filter_map(|list| crate::utils::parse_type_list(&list).ok())
+        .flatten()
+        .map(|ty| quote! { #ty }.to_string())
+        .collect()
 }
 
 /// Format type string to be more readable and filter out standard types
@@ -183,19 +284,36 @@ fn format_type_string(type_str: &str) -> Option<String> {
 #[allow(clippy::cmp_owned)]
 fn generate_abstract_service_impl(
     struct_type: &Ident,
+    self_ty: &Type,
+    generics: &Generics,
     all_methods: &[(Ident, &str, ImplItemFn)],
-    _service_attrs: &HashMap<String, String>,
+    service_attrs: &HashMap<String, String>,
+    mutable: bool,
 ) -> TokenStream2 {
     // Create method identifiers for action registration
-    let method_registrations = all_methods.iter().map(|(method_name, method_type, _)| {
-        if *method_type == "action" {
-            let register_method_name = format_ident!("register_action_{}", method_name);
+    let openapi_registration = openapi_enabled(service_attrs).then(|| {
+        if mutable {
             quote! {
-                self.#register_method_name(context_ref).await?;
+                compile_error!("`openapi = true` is not supported for services with mutable actions");
             }
+        } else {
+            quote! {
+                self.register_action_openapi_schema(context_ref).await?;
+            }
+        }
+    });
+    let method_registrations = all_methods.iter().map(|(method_name, method_type, _)| {
+        let register_method_name = if *method_type == "action" {
+            format_ident!("register_action_{}", method_name)
         } else {
             // Must be a subscription
-            let register_method_name = format_ident!("register_subscription_{}", method_name);
+            format_ident!("register_subscription_{}", method_name)
+        };
+        if mutable {
+            quote! {
+                Self::#register_method_name(&service, context_ref).await?;
+            }
+        } else {
             quote! {
                 self.#register_method_name(context_ref).await?;
             }
@@ -204,13 +322,29 @@ fn generate_abstract_service_impl(
 
     // Extract all types from methods
     let mut all_types = HashSet::new();
+    let mut generic_types = HashSet::new();
+    let impl_type_params = generics
+        .type_params()
+        .map(|param| param.ident.to_string())
+        .collect::<HashSet<_>>();
 
     for (_, _, method) in all_methods {
-        let types = extract_types_from_method(method);
+        let (mut types, generic) = extract_types_from_method(method, &impl_type_params);
+        generic_types.extend(generic.iter().filter_map(|t| format_type_string(t)));
+        types.extend(extra_registered_types(method));
         for type_str in types {
             if let Some(formatted) = format_type_string(&type_str) {
                 // Skip the service type itself
                 if formatted != struct_type.to_string() {
+                    // The payload of an Option<X> needs its own registration too
+                    if let Ok(ty) = syn::parse_str::<syn::Type>(&formatted) {
+                        if let Some(inner) = crate::action::get_option_inner_type(&ty) {
+                            let inner_str = quote! { #inner }.to_string();
+                            if let Some(inner_formatted) = format_type_string(&inner_str) {
+                                all_types.insert(inner_formatted);
+                            }
+                        }
+                    }
                     all_types.insert(formatted);
                 }
             }
@@ -220,6 +354,11 @@ fn generate_abstract_service_impl(
     // Convert to a vector and sort for consistent output
     let mut sorted_types: Vec<_> = all_types.into_iter().collect();
     sorted_types.sort();
+    let mut generic_types: Vec<_> = generic_types.into_iter().collect();
+    generic_types.sort();
+    let generic_type_notes = generic_types
+        .iter()
+        .map(|t| format!(" NOTE: generic type {t} must be registered manually"));
 
     // Create a string representation of all types (one per line) for logging
     let types_str = sorted_types.join("\n");
@@ -233,6 +372,15 @@ fn generate_abstract_service_impl(
         })
         .collect::<Vec<_>>();
 
+    // Option<X> types use the dedicated option registration
+    let type_registrations =
+        type_idents
+            .iter()
+            .map(|ty| match crate::action::get_option_inner_type(ty) {
+                Some(inner) => quote! { serializer.register_option::<#inner>()?; },
+                None => quote! { serializer.register::<#ty>()?; },
+            });
+
     // Generate logging code for collected types
     let type_collection_code = if sorted_types.is_empty() {
         // No complex types collected – generate a simple debug log
@@ -256,9 +404,78 @@ fn generate_abstract_service_impl(
         }
     };
 
+    // A shared service must not be duplicated, so it may not be Clone (or Copy).
+    // The check names the service type from a constant, which cannot refer to
+    // the impl's type parameters, so generic services go unchecked.
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+    let clone_check = (generics.params.is_empty()).then(|| {
+        quote! {
+            const _: fn() = || {
+                trait ServiceWithMutableActionsMustNotBeClone<T> {
+                    fn check() {}
+                }
+                impl<S: ?Sized> ServiceWithMutableActionsMustNotBeClone<()> for S {}
+                struct IsClone;
+                impl<S: ?Sized + ::core::clone::Clone> ServiceWithMutableActionsMustNotBeClone<IsClone> for S {}
+                let _ = <#struct_type as ServiceWithMutableActionsMustNotBeClone<_>>::check;
+            };
+        }
+    });
+    let lifecycle = if mutable {
+        quote! {
+            async fn init(
+                service: runar_node::services::abstract_service::SharedService<Self>,
+                context: runar_node::services::LifecycleContext,
+            ) -> anyhow::Result<()> {
+                #clone_check
+
+                // Create a reference to the context
+                let context_ref = &context;
+
+                // Register all action and subscription methods defined with the #[action] or #[subscribe] macro
+                #(#method_registrations)*
+                #openapi_registration
+
+                // Register complex types with the serializer
+                Self::register_types(context_ref).await?;
+
+                Ok(())
+            }
+        }
+    } else {
+        quote! {
+            async fn init(&self, context: runar_node::services::LifecycleContext) -> anyhow::Result<()> {
+                // Create a reference to the context
+                let context_ref = &context;
+
+                // Register all action and subscription methods defined with the #[action] or #[subscribe] macro
+                #(#method_registrations)*
+                #openapi_registration
+
+                // Register complex types with the serializer
+                Self::register_types(context_ref).await?;
+
+                Ok(())
+            }
+
+            async fn start(&self, _context: runar_node::services::LifecycleContext) -> anyhow::Result<()> {
+                Ok(())
+            }
+
+            async fn stop(&self, _context: runar_node::services::LifecycleContext) -> anyhow::Result<()> {
+                Ok(())
+            }
+        }
+    };
+    let service_trait = if mutable {
+        quote! { runar_node::services::abstract_service::MutableService }
+    } else {
+        quote! { runar_node::services::abstract_service::AbstractService }
+    };
+
     quote! {
         #[async_trait::async_trait]
-        impl runar_node::services::abstract_service::AbstractService  for #struct_type {
+        impl #impl_generics #service_trait for #self_ty #where_clause {
             fn name(&self) -> &str {
                 &self.__runar_name
             }
@@ -283,31 +500,17 @@ fn generate_abstract_service_impl(
                 self.__runar_network_id = Some(network_id);
             }
 
-            async fn init(&self, context: runar_node::services::LifecycleContext) -> anyhow::Result<()> {
-                // Create a reference to the context
-                let context_ref = &context;
-
-                // Register all action and subscription methods defined with the #[action] or #[subscribe] macro
-                #(#method_registrations)*
-
-                // Register complex types with the serializer
-                Self::register_types(context_ref).await?;
-
-                Ok(())
-            }
-
-            async fn start(&self, _context: runar_node::services::LifecycleContext) -> anyhow::Result<()> {
-                Ok(())
+            fn access_policy(&self) -> runar_node::services::access_policy::ServiceAccessPolicy {
+                self.__runar_access_policy.clone()
             }
 
-            async fn stop(&self, _context: runar_node::services::LifecycleContext) -> anyhow::Result<()> {
-                Ok(())
-            }
+            #lifecycle
         }
 
         // Helper utilities inherent to the service
-        impl #struct_type {
+        impl #impl_generics #self_ty #where_clause {
             // Helper method to register complex types with the serializer
+            #(#[doc = #generic_type_notes])*
             async fn register_types(context: &runar_node::services::LifecycleContext) -> anyhow::Result<()> {
                 // Acquire a write lock on the serializer
                 let mut serializer = context.serializer.write().await;
@@ -321,9 +524,7 @@ fn generate_abstract_service_impl(
                 })*
                 // Print all types being registered for macro transparency
                 #join_debug_code
-                #({
-                    serializer.register::<#type_idents>()?;
-                })*
+                #(#type_registrations)*
 
                 Ok(())
             }
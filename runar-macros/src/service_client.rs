@@ -0,0 +1,180 @@
+// Service client macro implementation
+//
+// This module implements the service_client macro, which generates a typed
+// client for the actions of a service so callers do not have to build payload
+// maps and action paths by hand.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{parse_macro_input, ImplItem, ImplItemFn, ItemImpl, Meta, ReturnType, Type, TypePath};
+
+/// Implementation of the service_client macro
+pub fn service_client_macro(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(item as ItemImpl);
+
+    let struct_type = match &*input.self_ty {
+        Type::Path(TypePath { ref path, .. }) => path.segments.last().unwrap().ident.clone(),
+        other => {
+            return syn::Error::new_spanned(
+                other,
+                "Service client macro can only be applied to structs",
+            )
+            .to_compile_error()
+            .into()
+        }
+    };
+    let client_ident = format_ident!("{}Client", struct_type);
+
+    // The service path comes from the attribute, or else from the defaults
+    // generated by #[service] on the struct
+    let client_attrs = crate::service::extract_service_attributes(attr);
+    let (service_path, path_error) = match client_attrs.get("path") {
+        Some(path) => {
+            let path_error = crate::utils::validate_route_path(path)
+                .err()
+                .map(|problem| {
+                    let message = format!("Invalid service path: {path:?}: {problem}");
+                    quote! { compile_error!(#message); }
+                });
+            (quote! { #path.to_string() }, path_error)
+        }
+        None => (
+            quote! { <#struct_type as ::core::default::Default>::default().get_path().to_string() },
+            None,
+        ),
+    };
+
+    let client_methods = input.items.iter().filter_map(|item| match item {
+        ImplItem::Fn(method) => generate_client_method(method),
+        _ => None,
+    });
+
+    let client_doc = format!("Typed client for the actions of [`{struct_type}`]");
+
+    TokenStream::from(quote! {
+        #path_error
+
+        #input
+
+        #[doc = #client_doc]
+        #[derive(Clone)]
+        pub struct #client_ident {
+            node: ::std::sync::Arc<runar_node::Node>,
+            service_path: ::std::string::String,
+            network_id: ::std::option::Option<::std::string::String>,
+        }
+
+        impl #client_ident {
+            /// Create a client for the service at its default path, in the given
+            /// network or in the node's default network
+            pub fn new(node: ::std::sync::Arc<runar_node::Node>, network_id: ::std::option::Option<&str>) -> Self {
+                Self {
+                    node,
+                    service_path: #service_path,
+                    network_id: network_id.map(|network_id| network_id.to_string()),
+                }
+            }
+
+            /// Target a service instance registered under another path
+            pub fn with_service_path(mut self, service_path: impl Into<String>) -> Self {
+                self.service_path = service_path.into();
+                self
+            }
+
+            pub fn service_path(&self) -> &str {
+                &self.service_path
+            }
+
+            fn action_path(&self, action: &str) -> ::std::string::String {
+                match &self.network_id {
+                    Some(network_id) => format!("{}:{}/{}", network_id, self.service_path, action),
+                    None => format!("{}/{}", self.service_path, action),
+                }
+            }
+
+            #(#client_methods)*
+        }
+    })
+}
+
+/// Generate the client method for an #[action] method
+///
+/// Methods that are not actions, and streaming actions, get no client method.
+fn generate_client_method(method: &ImplItemFn) -> Option<TokenStream2> {
+    let action_attr = method
+        .attrs
+        .iter()
+        .find(|attr| attr.path().is_ident("action"))?;
+    let attr_tokens = match &action_attr.meta {
+        Meta::List(list) => list.tokens.clone(),
+        _ => TokenStream2::new(),
+    };
+    let fn_ident = &method.sig.ident;
+    // A versioned action is requested at `v{version}/{path}`; an invalid
+    // version is reported by the #[action] macro itself
+    let (attr_tokens, version) = crate::utils::take_int_arg(attr_tokens, "version");
+    let action_path = crate::action::parse_action_route(attr_tokens, &fn_ident.to_string()).path;
+    let action_path = match version.and_then(|lit| lit.base10_parse::<u8>().ok()) {
+        Some(version) => format!("v{version}/{action_path}"),
+        None => action_path,
+    };
+
+    let return_type = match &method.sig.output {
+        ReturnType::Default => syn::parse_quote! { () },
+        ReturnType::Type(_, ty) => crate::action::request_return_type(ty)?,
+    };
+
+    // The payload is a map of parameter names, as the #[action] macro expects
+    let params: Vec<_> = crate::utils::extract_signature_parameters(&method.sig)
+        .into_iter()
+        .filter(|(_, ty)| !is_request_context(ty))
+        .collect();
+    let param_decls = params.iter().map(|(ident, ty)| quote! { #ident: #ty });
+    let payload = if params.is_empty() {
+        quote! { ::std::option::Option::None::<runar_common::types::ArcValue> }
+    } else {
+        let inserts = params.iter().map(|(ident, ty)| {
+            let name = ident.to_string();
+            let value = to_arc_value(ident, ty);
+            quote! { params.insert(#name.to_string(), #value); }
+        });
+        quote! {{
+            let mut params = ::std::collections::HashMap::<::std::string::String, runar_common::types::ArcValue>::new();
+            #(#inserts)*
+            ::std::option::Option::Some(runar_common::types::ArcValue::new_map(params))
+        }}
+    };
+
+    let doc = format!("Request the `{action_path}` action");
+    Some(quote! {
+        #[doc = #doc]
+        pub async fn #fn_ident(&self, #(#param_decls),*) -> anyhow::Result<#return_type> {
+            self.node.request(self.action_path(#action_path), #payload).await
+        }
+    })
+}
+
+pub(crate) fn is_request_context(ty: &Type) -> bool {
+    let ty = match ty {
+        Type::Reference(type_ref) => &*type_ref.elem,
+        ty => ty,
+    };
+    matches!(ty, Type::Path(type_path)
+        if crate::action::get_path_last_segment_ident_string(type_path).as_deref() == Some("RequestContext"))
+}
+
+/// Wrap a parameter the same way the #[action] macro wraps results
+fn to_arc_value(ident: &syn::Ident, ty: &Type) -> TokenStream2 {
+    let type_name = match ty {
+        Type::Path(type_path) => crate::action::get_path_last_segment_ident_string(type_path),
+        _ => None,
+    };
+    match type_name.as_deref() {
+        Some("ArcValue") => quote! { #ident },
+        Some(name) if crate::action::is_primitive_type_name(name) => {
+            quote! { runar_common::types::ArcValue::new_primitive(#ident) }
+        }
+        _ => quote! { runar_common::types::ArcValue::from_struct(#ident) },
+    }
+}
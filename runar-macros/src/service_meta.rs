@@ -13,8 +13,8 @@ fn parse_attrs(attr: TokenStream) -> HashMap<String, String> {
     }
 
     let attr_str = attr.to_string();
-    for pair in attr_str.split(',') {
-        let parts: Vec<&str> = pair.split('=').collect();
+    for pair in split_outside_quotes(&attr_str) {
+        let parts: Vec<&str> = pair.splitn(2, '=').collect();
         if parts.len() != 2 {
             continue;
         }
@@ -23,11 +23,64 @@ fn parse_attrs(attr: TokenStream) -> HashMap<String, String> {
         if value_part.starts_with('"') && value_part.ends_with('"') {
             let value = value_part[1..value_part.len() - 1].to_string();
             map.insert(key, value);
+        } else if value_part == "true" || value_part == "false" {
+            map.insert(key, value_part.to_string());
         }
     }
     map
 }
 
+/// Split an attribute list on the commas that are not inside a string
+fn split_outside_quotes(attr_str: &str) -> Vec<&str> {
+    let mut pairs = Vec::new();
+    let mut in_string = false;
+    let mut start = 0;
+    for (index, c) in attr_str.char_indices() {
+        match c {
+            '"' => in_string = !in_string,
+            ',' if !in_string => {
+                pairs.push(&attr_str[start..index]);
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    pairs.push(&attr_str[start..]);
+    pairs
+}
+
+/// Build the access policy given by `access = ".."`
+///
+/// `"local_only"` rejects remote callers and `"peers: peer1,peer2"` only
+/// accepts the listed peers; without the attribute every caller is allowed.
+fn access_policy_tokens(access: Option<&String>) -> Result<TokenStream2, String> {
+    let policy = quote! { runar_node::services::access_policy::ServiceAccessPolicy };
+    let Some(access) = access.map(|access| access.trim()) else {
+        return Ok(quote! { #policy::allow_all() });
+    };
+    if access == "local_only" {
+        return Ok(quote! { #policy::local_only() });
+    }
+    if let Some(peers) = access.strip_prefix("peers:") {
+        let peers: Vec<&str> = peers
+            .split(',')
+            .map(str::trim)
+            .filter(|peer| !peer.is_empty())
+            .collect();
+        if peers.is_empty() {
+            return Err(format!("Invalid service access {access:?}: no peer listed"));
+        }
+        return Ok(quote! {
+            #policy::peers(vec![
+                #(runar_node::network::transport::PeerId::new(#peers.to_string()),)*
+            ])
+        });
+    }
+    Err(format!(
+        "Invalid service access {access:?}: expected \"local_only\" or \"peers: peer1,peer2\""
+    ))
+}
+
 // Internal implementation called from lib.rs entry point.
 pub fn service_meta_impl(attr: TokenStream, item: TokenStream) -> TokenStream {
     // Parse the original struct
@@ -36,11 +89,26 @@ pub fn service_meta_impl(attr: TokenStream, item: TokenStream) -> TokenStream {
     let vis = &input_ast.vis;
     let attrs = &input_ast.attrs;
     let generics = &input_ast.generics;
-    let where_clause = &generics.where_clause;
+    // Bounds stay on the impl parameters; the type is named with bare ones
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
 
     // Parse macro attribute key/value pairs
     let attr_map = parse_attrs(attr);
 
+    // A path that cannot be routed would only fail at runtime, so reject it
+    // here; the struct is still generated so no unrelated errors follow
+    let path_error = attr_map.get("path").and_then(|path| {
+        crate::utils::validate_route_path(path)
+            .err()
+            .map(|problem| {
+                let message = format!("Invalid service path: {path:?}: {problem}");
+                quote! { compile_error!(#message); }
+            })
+    });
+
+    let access_policy = access_policy_tokens(attr_map.get("access"))
+        .unwrap_or_else(|message| quote! { compile_error!(#message) });
+
     let name_value = attr_map
         .get("name")
         .cloned()
@@ -98,29 +166,31 @@ pub fn service_meta_impl(attr: TokenStream, item: TokenStream) -> TokenStream {
         __runar_version: ::std::string::String,
         #[doc(hidden)]
         __runar_network_id: ::std::option::Option<::std::string::String>,
+        #[doc(hidden)]
+        __runar_access_policy: runar_node::services::access_policy::ServiceAccessPolicy,
     };
 
     // Build struct definition
     let struct_def = if matches!(input_ast.fields, Fields::Unit) {
         quote! {
             #(#attrs)*
-            #vis struct #struct_ident #generics {
+            #vis struct #struct_ident #generics #where_clause {
                 #meta_fields
-            } #where_clause
+            }
         }
     } else {
         quote! {
             #(#attrs)*
-            #vis struct #struct_ident #generics {
+            #vis struct #struct_ident #generics #where_clause {
                 #field_defs
                 #meta_fields
-            } #where_clause
+            }
         }
     };
 
     // Implement Default
     let default_impl = quote! {
-        impl #generics ::core::default::Default for #struct_ident #generics #where_clause {
+        impl #impl_generics ::core::default::Default for #struct_ident #ty_generics #where_clause {
             fn default() -> Self {
                 Self {
                     #default_inits
@@ -129,6 +199,7 @@ pub fn service_meta_impl(attr: TokenStream, item: TokenStream) -> TokenStream {
                     __runar_description: #description_value.to_string(),
                     __runar_version: #version_value.to_string(),
                     __runar_network_id: None,
+                    __runar_access_policy: #access_policy,
                 }
             }
         }
@@ -136,7 +207,7 @@ pub fn service_meta_impl(attr: TokenStream, item: TokenStream) -> TokenStream {
 
     // Implement helper getters & setters
     let helpers = quote! {
-        impl #generics #struct_ident #generics #where_clause {
+        impl #impl_generics #struct_ident #ty_generics #where_clause {
             #[inline]
             pub fn get_name(&self) -> &str { &self.__runar_name }
             #[inline]
@@ -147,32 +218,41 @@ pub fn service_meta_impl(attr: TokenStream, item: TokenStream) -> TokenStream {
             pub fn get_version(&self) -> &str { &self.__runar_version }
             #[inline]
             pub fn get_network_id(&self) -> Option<String> { self.__runar_network_id.clone() }
+            #[inline]
+            pub fn get_access_policy(&self) -> &runar_node::services::access_policy::ServiceAccessPolicy { &self.__runar_access_policy }
 
             pub fn set_name(&mut self, value: impl Into<String>) { self.__runar_name = value.into(); }
             pub fn set_path(&mut self, value: impl Into<String>) { self.__runar_path = value.into(); }
             pub fn set_description(&mut self, value: impl Into<String>) { self.__runar_description = value.into(); }
             pub fn set_version(&mut self, value: impl Into<String>) { self.__runar_version = value.into(); }
             pub fn set_network_id(&mut self, value: impl Into<String>) { self.__runar_network_id = Some(value.into()); }
+            pub fn set_access_policy(&mut self, value: runar_node::services::access_policy::ServiceAccessPolicy) { self.__runar_access_policy = value; }
         }
     };
 
-    // Implement Clone (deep clone of all fields)
-    let clone_impl = quote! {
-        impl #generics ::core::clone::Clone for #struct_ident #generics #where_clause {
-            fn clone(&self) -> Self {
-                Self {
-                    #clone_inits
-                    __runar_name: self.__runar_name.clone(),
-                    __runar_path: self.__runar_path.clone(),
-                    __runar_description: self.__runar_description.clone(),
-                    __runar_version: self.__runar_version.clone(),
-                    __runar_network_id: self.__runar_network_id.clone(),
+    // Implement Clone (deep clone of all fields), except for services with
+    // mutable actions, whose handlers share a single instance
+    let mutable = attr_map.get("mutable").map(String::as_str) == Some("true");
+    let clone_impl = (!mutable).then(|| {
+        quote! {
+            impl #impl_generics ::core::clone::Clone for #struct_ident #ty_generics #where_clause {
+                fn clone(&self) -> Self {
+                    Self {
+                        #clone_inits
+                        __runar_name: self.__runar_name.clone(),
+                        __runar_path: self.__runar_path.clone(),
+                        __runar_description: self.__runar_description.clone(),
+                        __runar_version: self.__runar_version.clone(),
+                        __runar_network_id: self.__runar_network_id.clone(),
+                        __runar_access_policy: self.__runar_access_policy.clone(),
+                    }
                 }
             }
         }
-    };
+    });
 
     let expanded = quote! {
+        #path_error
         #struct_def
         #default_impl
         #helpers
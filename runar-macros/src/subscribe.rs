@@ -5,53 +5,154 @@
 // parameter extraction and event handling.
 
 use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
 use quote::{format_ident, quote};
 use syn::{
-    parse::Parse, parse::ParseStream, parse_macro_input, Expr, ItemFn, Lit, LitStr, Meta, Result,
+    parse::Parse, parse::ParseStream, parse_macro_input, Expr, ExprArray, ExprLit, ItemFn, Lit,
+    LitStr, Meta, Result, Token,
 };
 
 // Define a struct to parse the macro attributes
 pub struct SubscribeImpl {
-    pub path: LitStr,
+    /// The subscribed path, or every path given with `topics = [...]`
+    pub paths: Vec<LitStr>,
+    /// Whether the paths were given with `topics = [...]`
+    pub topics: bool,
+    /// Whether failed deliveries go to the node's dead-letter queue
+    pub dead_letter: bool,
+    /// Subscription group whose members compete for the events; empty for fan-out
+    pub group: String,
+    /// Whether the service is shared behind a lock (set by `#[service_impl]`)
+    pub locked: bool,
 }
 
 impl Parse for SubscribeImpl {
     fn parse(input: ParseStream) -> Result<Self> {
-        // Check if we have path="value" format
-        if input.peek(syn::Ident) {
-            let meta = input.parse::<Meta>()?;
-            if let Meta::NameValue(name_value) = meta {
-                if name_value.path.is_ident("path") {
-                    // Extract the string literal from the expression
-                    if let Expr::Lit(expr_lit) = &name_value.value {
-                        if let Lit::Str(lit_str) = &expr_lit.lit {
-                            return Ok(SubscribeImpl {
-                                path: lit_str.clone(),
-                            });
-                        }
+        // Check if we have path="value" or topics=[...] format, otherwise expect a string literal
+        let mut topics = false;
+        let paths = if input.peek(syn::Ident) {
+            match input.parse::<Meta>()? {
+                Meta::NameValue(name_value) if name_value.path.is_ident("path") => {
+                    match name_value.value {
+                        Expr::Lit(ExprLit {
+                            lit: Lit::Str(lit_str),
+                            ..
+                        }) => vec![lit_str],
+                        _ => return Err(input.error("Expected path=\"value\" or a string literal")),
                     }
                 }
+                Meta::NameValue(name_value) if name_value.path.is_ident("topics") => {
+                    topics = true;
+                    parse_topics(name_value.value)?
+                }
+                _ => return Err(input.error("Expected path=\"value\" or a string literal")),
             }
-            return Err(input.error("Expected path=\"value\" or a string literal"));
-        }
+        } else {
+            vec![input.parse::<LitStr>()?]
+        };
 
-        // Otherwise, try to parse as a string literal followed by a handler
-        let path = input.parse::<LitStr>()?;
+        // Optional settings after the path, e.g. dead_letter = false
+        let mut dead_letter = true;
+        let mut group = String::new();
+        let mut locked = false;
+        while input.parse::<Option<Token![,]>>()?.is_some() && !input.is_empty() {
+            match input.parse::<Meta>()? {
+                Meta::NameValue(name_value) if name_value.path.is_ident("dead_letter") => {
+                    match name_value.value {
+                        Expr::Lit(ExprLit {
+                            lit: Lit::Bool(lit_bool),
+                            ..
+                        }) => dead_letter = lit_bool.value,
+                        _ => return Err(input.error("Expected dead_letter = true or false")),
+                    }
+                }
+                Meta::NameValue(name_value) if name_value.path.is_ident("group") => {
+                    match name_value.value {
+                        Expr::Lit(ExprLit {
+                            lit: Lit::Str(lit_str),
+                            ..
+                        }) => group = lit_str.value(),
+                        _ => return Err(input.error("Expected group = \"name\"")),
+                    }
+                }
+                Meta::NameValue(name_value) if name_value.path.is_ident("locked") => {
+                    match name_value.value {
+                        Expr::Lit(ExprLit {
+                            lit: Lit::Bool(lit_bool),
+                            ..
+                        }) => locked = lit_bool.value,
+                        _ => return Err(input.error("Expected locked = true or false")),
+                    }
+                }
+                _ => {
+                    return Err(
+                        input.error("Unknown subscribe option, expected dead_letter or group")
+                    )
+                }
+            }
+        }
 
-        // Just a path string
-        Ok(SubscribeImpl { path })
+        Ok(SubscribeImpl {
+            paths,
+            topics,
+            dead_letter,
+            group,
+            locked,
+        })
     }
 }
 
+/// Parse the `topics = ["a", "b"]` list of string literals
+fn parse_topics(value: Expr) -> Result<Vec<LitStr>> {
+    let Expr::Array(ExprArray { elems, .. }) = value else {
+        return Err(syn::Error::new_spanned(
+            value,
+            "Expected topics = [\"path\", ...]",
+        ));
+    };
+    elems
+        .into_iter()
+        .map(|elem| match elem {
+            Expr::Lit(ExprLit {
+                lit: Lit::Str(lit_str),
+                ..
+            }) => Ok(lit_str),
+            other => Err(syn::Error::new_spanned(
+                other,
+                "Expected a string literal topic path",
+            )),
+        })
+        .collect()
+}
+
 /// Implementation of the subscribe macro
+///
+/// With `topics = [...]` one `register_subscription_<fn>_<index>` method is
+/// generated per topic, and `register_subscription_<fn>` registers them all.
 pub fn subscribe_macro(attr: TokenStream, item: TokenStream) -> TokenStream {
     // Parse the input as a function
     let input = parse_macro_input!(item as ItemFn);
 
     // Parse the attributes
     let subscribe_impl = parse_macro_input!(attr as SubscribeImpl);
-    let path = &subscribe_impl.path;
-    let path_value = &path.value();
+    // Reported next to the generated code so the service still type-checks
+    let validation_error = (subscribe_impl.topics && subscribe_impl.paths.is_empty()).then(|| {
+        quote! { compile_error!("topics must list at least one path"); }
+    });
+    let dead_letter = subscribe_impl.dead_letter;
+    let group = if subscribe_impl.group.is_empty() {
+        quote! { None }
+    } else {
+        let group = &subscribe_impl.group;
+        quote! { Some(#group.to_string()) }
+    };
+    let options = quote! {
+        runar_node::services::EventRegistrationOptions {
+            dead_letter: #dead_letter,
+            group: #group,
+            ..::core::default::Default::default()
+        }
+    };
 
     // Get the function identifier
     let fn_ident = &input.sig.ident;
@@ -63,19 +164,129 @@ pub fn subscribe_macro(attr: TokenStream, item: TokenStream) -> TokenStream {
 
     // Generate a unique method name for the subscription registration
     let register_method_name = format_ident!("register_subscription_{}", fn_ident);
+    if params.len() > 1 {
+        // Multiple parameters case - this is not supported for subscriptions
+        return TokenStream::from(quote! {
+            #(#attrs)*
+            #vis #input
+
+            compile_error!("Subscription handlers can only have one parameter plus context");
+        });
+    }
+
+    // In a shared service the handler takes the lock matching its receiver
+    let (register_receiver, shared_self) = if subscribe_impl.locked {
+        (
+            quote! { service: &runar_node::services::abstract_service::SharedService<Self> },
+            quote! { service.clone() },
+        )
+    } else {
+        (quote! { &self }, quote! { self.clone() })
+    };
+    let acquire_lock = match (
+        subscribe_impl.locked,
+        crate::utils::has_mut_receiver(&input.sig),
+    ) {
+        (false, _) => quote! {},
+        (true, false) => quote! {
+            let self_clone = self_clone.read().await;
+        },
+        (true, true) => quote! {
+            let mut self_clone = self_clone.write().await;
+        },
+    };
 
     // Generate the registration method based on parameters
-    let register_method = if params.len() == 1 {
+    let register_method = |register_method_name: &syn::Ident, path: &LitStr| {
+        subscription_method(
+            register_method_name,
+            path,
+            &params,
+            fn_ident,
+            &register_receiver,
+            &shared_self,
+            &acquire_lock,
+            &options,
+        )
+    };
+    let register_methods = if subscribe_impl.topics {
+        let topic_method_names: Vec<syn::Ident> = (0..subscribe_impl.paths.len())
+            .map(|index| format_ident!("{}_{}", register_method_name, index))
+            .collect();
+        let topic_methods = topic_method_names
+            .iter()
+            .zip(&subscribe_impl.paths)
+            .map(|(name, path)| register_method(name, path));
+        let topic_calls = topic_method_names.iter().map(|name| {
+            if subscribe_impl.locked {
+                quote! { Self::#name(service, context).await?; }
+            } else {
+                quote! { self.#name(context).await?; }
+            }
+        });
+        quote! {
+            #(#topic_methods)*
+
+            async fn #register_method_name(#register_receiver, context: &runar_node::services::LifecycleContext) -> anyhow::Result<()> {
+                #(#topic_calls)*
+                Ok(())
+            }
+        }
+    } else {
+        register_method(&register_method_name, &subscribe_impl.paths[0])
+    };
+
+    // Combine the original function with the generated register methods
+    let expanded = quote! {
+        // Keep the original function
+        #(#attrs)*
+        #vis #input
+
+        // Add the registration methods
+        #register_methods
+
+        #validation_error
+    };
+
+    TokenStream::from(expanded)
+}
+
+/// Generate the method registering the handler for the event at `path`
+#[allow(clippy::too_many_arguments)]
+fn subscription_method(
+    register_method_name: &syn::Ident,
+    path: &LitStr,
+    params: &[(syn::Ident, syn::Type)],
+    fn_ident: &syn::Ident,
+    register_receiver: &TokenStream2,
+    shared_self: &TokenStream2,
+    acquire_lock: &TokenStream2,
+    options: &TokenStream2,
+) -> TokenStream2 {
+    let path_value = &path.value();
+    if params.len() == 1 {
         let (param_ident, param_type) = &params[0];
+        let handler_call = locked_handler_call(
+            acquire_lock,
+            quote! {
+                // Call the handler method with the extracted parameter
+                match self_clone.#fn_ident(#param_ident, &ctx).await {
+                    Ok(_) => Ok(()),
+                    Err(err) => {
+                        Err(anyhow!(format!("Error in event handler for {}: {}", #path_value, err)))
+                    }
+                }
+            },
+        );
         quote! {
-            async fn #register_method_name(&self, context: &runar_node::services::LifecycleContext) -> anyhow::Result<()> {
+            async fn #register_method_name(#register_receiver, context: &runar_node::services::LifecycleContext) -> anyhow::Result<()> {
                 context.info(format!("Subscribing to '{}' event", #path_value));
 
                 // Create a clone of self that can be moved into the closure
-                let self_clone = self.clone();
+                let self_clone = #shared_self;
 
                 // Register the event handler
-                context.subscribe(#path, Box::new(move |ctx, value| {
+                context.subscribe_with_options(#path, Box::new(move |ctx, value| {
                     // Create a boxed future that returns Result<(), anyhow::Error>
                     let self_clone = self_clone.clone();
                     Box::pin(async move {
@@ -93,64 +304,64 @@ pub fn subscribe_macro(attr: TokenStream, item: TokenStream) -> TokenStream {
                             }
                         };
 
-                        // Call the handler method with the extracted parameter
-                        match self_clone.#fn_ident(#param_ident, &ctx).await {
-                            Ok(_) => Ok(()),
-                            Err(err) => {
-                                Err(anyhow!(format!("Error in event handler for {}: {}", #path_value, err)))
-                            }
-                        }
+                        #handler_call
                     })
-                })).await?;
+                }), #options).await?;
 
                 context.info(format!("Registered event handler for {}", #path_value));
                 Ok(())
             }
         }
-    } else if params.is_empty() {
+    } else {
+        let handler_call = locked_handler_call(
+            acquire_lock,
+            quote! {
+                // Call the handler method directly with the event context
+                match self_clone.#fn_ident(&ctx).await {
+                    Ok(_) => Ok(()),
+                    Err(err) => {
+                        Err(anyhow!(format!("Error in event handler for {}: {}", #path_value, err)))
+                    }
+                }
+            },
+        );
         quote! {
-            async fn #register_method_name(&self, context: &runar_node::services::LifecycleContext) -> anyhow::Result<()> {
+            async fn #register_method_name(#register_receiver, context: &runar_node::services::LifecycleContext) -> anyhow::Result<()> {
                 context.info(format!("Subscribing to '{}' event", #path_value));
 
                 // Create a clone of self that can be moved into the closure
-                let self_clone = self.clone();
+                let self_clone = #shared_self;
 
                 // Register the event handler
-                context.subscribe(#path, Box::new(move |ctx, value| {
+                context.subscribe_with_options(#path, Box::new(move |ctx, value| {
                     // Create a boxed future that returns Result<(), anyhow::Error>
                     let self_clone = self_clone.clone();
                     Box::pin(async move {
-                        // Call the handler method directly with the event context
-                        match self_clone.#fn_ident(&ctx).await {
-                            Ok(_) => Ok(()),
-                            Err(err) => {
-                                ctx.error(format!("Error in event handler for {}: {}", #path_value, err));
-                                Ok(()) // Still return Ok to prevent subscription cancellation
-                            }
-                        }
+                        #handler_call
                     })
-                })).await?;
+                }), #options).await?;
 
                 context.info(format!("Registered event handler for {}", #path_value));
                 Ok(())
             }
         }
-    } else {
-        // Multiple parameters case - this is not supported for subscriptions
-        quote! {
-            compile_error!("Subscription handlers can only have one parameter plus context");
-        }
-    };
-
-    // Combine the original function with the generated register method
-    let expanded = quote! {
-        // Keep the original function
-        #(#attrs)*
-        #vis #input
-
-        // Add the registration method
-        #register_method
-    };
+    }
+}
 
-    TokenStream::from(expanded)
+/// Wrap a handler call so it takes the service lock, if it has one
+///
+/// Handlers holding the lock fail instead of deadlocking when they call back
+/// into their own service.
+fn locked_handler_call(acquire_lock: &TokenStream2, call: TokenStream2) -> TokenStream2 {
+    if acquire_lock.is_empty() {
+        return call;
+    }
+    quote! {
+        let locked_self = self_clone.clone();
+        runar_node::services::abstract_service::run_locked_handler(&locked_self, async move {
+            #acquire_lock
+            #call
+        })
+        .await
+    }
 }
@@ -3,13 +3,26 @@
 // This module provides utility functions for parsing and generating code
 // for the service and action macros.
 
-use syn::{FnArg, Ident, ItemFn, Pat, PatIdent, PatType, Type};
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::parse::Parser;
+use syn::punctuated::Punctuated;
+use syn::token::Comma;
+use syn::{
+    Expr, ExprLit, FnArg, Ident, ItemFn, Lit, LitInt, LitStr, Pat, PatIdent, PatType, Signature,
+    Type,
+};
 
 /// Extract parameters from the function signature, skipping `self` and `ctx` or `*_ctx` parameters.
 pub fn extract_parameters(input: &ItemFn) -> Vec<(Ident, Type)> {
+    extract_signature_parameters(&input.sig)
+}
+
+/// Same as `extract_parameters`, for a method signature inside an impl block.
+pub fn extract_signature_parameters(sig: &Signature) -> Vec<(Ident, Type)> {
     let mut params = Vec::new();
 
-    for arg in &input.sig.inputs {
+    for arg in &sig.inputs {
         if let FnArg::Typed(PatType { pat, ty, .. }) = arg {
             // Skip the self parameter and context parameter
             if let Pat::Ident(PatIdent { ident, .. }) = &**pat {
@@ -26,3 +39,145 @@ pub fn extract_parameters(input: &ItemFn) -> Vec<(Ident, Type)> {
 
     params
 }
+
+/// Check that a service path or action name can be routed
+///
+/// Only lowercase letters, digits, `_` and `/` are allowed; the value must not
+/// start or end with `/` nor contain consecutive slashes. Returns a
+/// description of the first problem found.
+pub fn validate_route_path(value: &str) -> Result<(), String> {
+    if value.is_empty() {
+        return Err("it must not be empty".to_string());
+    }
+    if let Some(invalid) = value
+        .chars()
+        .find(|c| !(c.is_ascii_lowercase() || c.is_ascii_digit() || *c == '_' || *c == '/'))
+    {
+        return Err(format!(
+            "{invalid:?} is not allowed; use only lowercase letters, digits, '_' and '/'"
+        ));
+    }
+    if value.starts_with('/') || value.ends_with('/') {
+        return Err("it must not start or end with '/'".to_string());
+    }
+    if value.contains("//") {
+        return Err("it must not contain consecutive slashes".to_string());
+    }
+    Ok(())
+}
+
+/// Remove a `flag = true` or `flag = false` argument from a macro attribute
+///
+/// Returns the remaining arguments and the flag's value, if it was given.
+/// Attributes that do not parse as a list of expressions are returned as is.
+pub fn take_bool_flag(attr: TokenStream2, flag: &str) -> (TokenStream2, Option<bool>) {
+    let Ok(args) = Punctuated::<Expr, Comma>::parse_terminated.parse2(attr.clone()) else {
+        return (attr, None);
+    };
+    let mut value = None;
+    let rest = args
+        .into_iter()
+        .filter(|arg| {
+            let Expr::Assign(assign) = arg else {
+                return true;
+            };
+            let Expr::Path(left) = &*assign.left else {
+                return true;
+            };
+            if !left.path.is_ident(flag) {
+                return true;
+            }
+            if let Expr::Lit(ExprLit {
+                lit: Lit::Bool(lit_bool),
+                ..
+            }) = &*assign.right
+            {
+                value = Some(lit_bool.value);
+            }
+            false
+        })
+        .collect::<Vec<_>>();
+    (quote! { #(#rest),* }, value)
+}
+
+/// Remove a `name = <integer>` argument from a macro attribute
+///
+/// Returns the remaining arguments and the integer literal, if it was given.
+/// Attributes that do not parse as a list of expressions are returned as is.
+pub fn take_int_arg(attr: TokenStream2, name: &str) -> (TokenStream2, Option<LitInt>) {
+    let Ok(args) = Punctuated::<Expr, Comma>::parse_terminated.parse2(attr.clone()) else {
+        return (attr, None);
+    };
+    let mut value = None;
+    let rest = args
+        .into_iter()
+        .filter(|arg| {
+            let Expr::Assign(assign) = arg else {
+                return true;
+            };
+            let Expr::Path(left) = &*assign.left else {
+                return true;
+            };
+            if !left.path.is_ident(name) {
+                return true;
+            }
+            if let Expr::Lit(ExprLit {
+                lit: Lit::Int(lit_int),
+                ..
+            }) = &*assign.right
+            {
+                value = Some(lit_int.clone());
+            }
+            false
+        })
+        .collect::<Vec<_>>();
+    (quote! { #(#rest),* }, value)
+}
+
+/// Remove a `name = "<string>"` argument from a macro attribute
+///
+/// Returns the remaining arguments and the string literal, if it was given.
+/// Attributes that do not parse as a list of expressions are returned as is.
+pub fn take_str_arg(attr: TokenStream2, name: &str) -> (TokenStream2, Option<LitStr>) {
+    let Ok(args) = Punctuated::<Expr, Comma>::parse_terminated.parse2(attr.clone()) else {
+        return (attr, None);
+    };
+    let mut value = None;
+    let rest = args
+        .into_iter()
+        .filter(|arg| {
+            let Expr::Assign(assign) = arg else {
+                return true;
+            };
+            let Expr::Path(left) = &*assign.left else {
+                return true;
+            };
+            if !left.path.is_ident(name) {
+                return true;
+            }
+            if let Expr::Lit(ExprLit {
+                lit: Lit::Str(lit_str),
+                ..
+            }) = &*assign.right
+            {
+                value = Some(lit_str.clone());
+            }
+            false
+        })
+        .collect::<Vec<_>>();
+    (quote! { #(#rest),* }, value)
+}
+
+/// Parse the comma separated types of a `register_types = "..."` argument
+pub fn parse_type_list(list: &LitStr) -> syn::Result<Vec<Type>> {
+    let types = list.parse_with(Punctuated::<Type, Comma>::parse_terminated)?;
+    Ok(types.into_iter().collect())
+}
+
+/// Whether a method takes `&mut self`
+pub fn has_mut_receiver(sig: &Signature) -> bool {
+    matches!(
+        sig.inputs.first(),
+        Some(FnArg::Receiver(receiver)) if receiver.reference.is_some() && receiver.mutability.is_some()
+    )
+}
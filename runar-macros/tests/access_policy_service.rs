@@ -0,0 +1,79 @@
+// Test for service access policies
+//
+// `#[service(access = "...")]` sets the policy a service is added with:
+// `local_only` rejects remote callers and `peers: ...` only accepts the
+// listed peers.
+
+use anyhow::{anyhow, Result};
+use runar_macros::{action, service, service_impl};
+
+#[service(name = "Vault", path = "vault", access = "local_only")]
+pub struct VaultService {}
+
+#[service_impl]
+impl VaultService {
+    #[action]
+    async fn secret(&self) -> Result<String> {
+        Ok("hunter2".to_string())
+    }
+}
+
+#[service(name = "Ledger", path = "ledger", access = "peers: peer1, peer2")]
+pub struct LedgerService {}
+
+#[service_impl]
+impl LedgerService {
+    #[action]
+    async fn balance(&self) -> Result<i64> {
+        Ok(42)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use runar_node::network::transport::PeerId;
+    use runar_node::{AbstractService, Node, ServiceAccessPolicy};
+    use runar_test_utils::create_node_test_config;
+
+    #[test]
+    fn test_access_attribute_sets_policy() {
+        assert_eq!(
+            VaultService::default().access_policy(),
+            ServiceAccessPolicy::local_only()
+        );
+        assert_eq!(
+            LedgerService::default().access_policy(),
+            ServiceAccessPolicy::peers(vec![
+                PeerId::new("peer1".to_string()),
+                PeerId::new("peer2".to_string()),
+            ])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_local_only_service_serves_local_callers() {
+        let config = create_node_test_config().expect("Error creating test config");
+        let mut node = Node::new(config).await.unwrap();
+        node.add_service(VaultService::default()).await.unwrap();
+        node.start().await.unwrap();
+
+        let secret: String = node.request("vault/secret", None::<()>).await.unwrap();
+        assert_eq!(secret, "hunter2");
+
+        // Restricting local callers to another service shuts the node out
+        node.update_service_policy(
+            "vault",
+            ServiceAccessPolicy::local_only().with_allowed_local_paths(vec!["ledger".to_string()]),
+        )
+        .await
+        .unwrap();
+        let error = node
+            .request::<(), String>("vault/secret", None)
+            .await
+            .unwrap_err();
+        assert_eq!(error.to_string(), "access denied");
+
+        node.stop().await.unwrap();
+    }
+}
@@ -0,0 +1,96 @@
+// Test for action metrics
+//
+// Actions annotated with `#[action(metrics = true)]` record their latency and
+// call and error counts in the node's metrics recorder.
+
+use anyhow::{anyhow, Result};
+use runar_macros::{action, service, service_impl};
+
+#[service(name = "Ledger", path = "ledger")]
+pub struct LedgerService {}
+
+#[service_impl]
+impl LedgerService {
+    #[action(metrics = true)]
+    async fn credit(&self, amount: i64) -> Result<i64> {
+        if amount < 0 {
+            return Err(anyhow!("Negative credit"));
+        }
+        Ok(amount)
+    }
+
+    #[action]
+    async fn balance(&self) -> Result<i64> {
+        Ok(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use metrics_util::debugging::{DebugValue, DebuggingRecorder};
+    use metrics_util::MetricKind;
+    use runar_common::types::ArcValue;
+    use runar_node::Node;
+    use runar_test_utils::create_node_test_config;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_action_metrics_are_recorded() {
+        let recorder = DebuggingRecorder::new();
+        let snapshotter = recorder.snapshotter();
+        let config = create_node_test_config()
+            .expect("Error creating test config")
+            .with_metrics_registry(Arc::new(recorder));
+        let mut node = Node::new(config).await.unwrap();
+        node.add_service(LedgerService::default()).await.unwrap();
+        node.start().await.unwrap();
+
+        for amount in [5i64, 7] {
+            let credited: i64 = node
+                .request("ledger/credit", Some(ArcValue::new_primitive(amount)))
+                .await
+                .unwrap();
+            assert_eq!(credited, amount);
+        }
+        assert!(node
+            .request::<_, i64>("ledger/credit", Some(ArcValue::new_primitive(-1i64)))
+            .await
+            .is_err());
+        let _: i64 = node
+            .request("ledger/balance", None::<ArcValue>)
+            .await
+            .unwrap();
+
+        let metrics = snapshotter.snapshot().into_vec();
+        let find = |kind: MetricKind, name: &str| {
+            metrics
+                .iter()
+                .find(|(key, _, _, _)| key.kind() == kind && key.key().name() == name)
+                .map(|(key, _, _, value)| (key.key().clone(), value))
+        };
+
+        let (key, requests) = find(MetricKind::Counter, "request_count").unwrap();
+        assert_eq!(requests, &DebugValue::Counter(3));
+        let labels: Vec<(String, String)> = key
+            .labels()
+            .map(|label| (label.key().to_string(), label.value().to_string()))
+            .collect();
+        assert!(labels.contains(&("service.path".to_string(), "ledger".to_string())));
+        assert!(labels.contains(&("action.name".to_string(), "credit".to_string())));
+
+        let (_, errors) = find(MetricKind::Counter, "error_count").unwrap();
+        assert_eq!(errors, &DebugValue::Counter(1));
+        match find(MetricKind::Histogram, "request_duration_ms")
+            .unwrap()
+            .1
+        {
+            DebugValue::Histogram(samples) => assert_eq!(samples.len(), 3),
+            other => panic!("expected a histogram, got {other:?}"),
+        }
+        // Actions without the annotation record nothing
+        assert_eq!(metrics.len(), 3);
+
+        node.stop().await.unwrap();
+    }
+}
@@ -0,0 +1,151 @@
+// Test for actions returning futures, boxed streams and impl Trait values
+//
+// The action macro awaits returned futures, forwards boxed streams item by
+// item and serializes opaque `impl Trait` results like any other struct.
+
+use std::fmt::Debug;
+use std::future::Future;
+use std::pin::Pin;
+
+use anyhow::{anyhow, Result};
+use futures::{Stream, StreamExt};
+use runar_common::types::ArcValue;
+use runar_macros::{action, service, service_impl};
+use runar_node::services::RequestContext;
+use runar_node::Node;
+use runar_test_utils::create_node_test_config;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+struct Summary {
+    total: i32,
+    label: String,
+}
+
+#[service(name = "Async Return Service", path = "async_returns")]
+pub struct AsyncReturnService;
+
+#[service_impl]
+impl AsyncReturnService {
+    #[action]
+    fn deferred_double(&self, value: i32) -> impl Future<Output = Result<i32>> {
+        async move {
+            if value < 0 {
+                return Err(anyhow!("negative value"));
+            }
+            Ok(value * 2)
+        }
+    }
+
+    #[action]
+    async fn deferred_greeting(&self, name: String) -> Result<impl Future<Output = String>> {
+        Ok(async move { format!("Hello, {name}") })
+    }
+
+    #[action]
+    async fn boxed_count(
+        &self,
+        up_to: i32,
+    ) -> Result<Box<dyn Stream<Item = Result<i32>> + Send + Unpin>> {
+        let items = (1..=up_to).map(|value| {
+            if value > 3 {
+                Err(anyhow!("count limit exceeded"))
+            } else {
+                Ok(value)
+            }
+        });
+        Ok(Box::new(futures::stream::iter(items)))
+    }
+
+    #[action]
+    async fn pinned_labels(&self) -> Result<Pin<Box<dyn Stream<Item = String> + Send>>> {
+        let labels = vec!["a".to_string(), "b".to_string()];
+        Ok(Box::pin(futures::stream::iter(labels)))
+    }
+
+    #[action]
+    async fn opaque_summary(
+        &self,
+        _ctx: &RequestContext,
+    ) -> Result<impl Serialize + Debug + Send + Sync + 'static> {
+        Ok(Summary {
+            total: 3,
+            label: "opaque".to_string(),
+        })
+    }
+}
+
+#[tokio::test]
+async fn test_async_return_types() -> Result<()> {
+    let config = create_node_test_config()?;
+    let mut node = Node::new(config).await?;
+    node.add_service(AsyncReturnService::default()).await?;
+    node.start().await?;
+
+    // The returned future is awaited and its Result becomes the action result
+    let doubled: i32 = node
+        .request(
+            "async_returns/deferred_double",
+            Some(ArcValue::new_primitive(21)),
+        )
+        .await?;
+    assert_eq!(doubled, 42);
+    let error = node
+        .request::<ArcValue, i32>(
+            "async_returns/deferred_double",
+            Some(ArcValue::new_primitive(-1)),
+        )
+        .await
+        .expect_err("a failing future should fail the action");
+    assert!(error.to_string().contains("negative value"));
+
+    // Result<impl Future<Output = T>> is flattened to T
+    let greeting: String = node
+        .request(
+            "async_returns/deferred_greeting",
+            Some(ArcValue::new_primitive("Runar".to_string())),
+        )
+        .await?;
+    assert_eq!(greeting, "Hello, Runar");
+
+    // Boxed streams are forwarded item by item, including their errors
+    let items: Vec<Result<i32>> = node
+        .request_stream(
+            "async_returns/boxed_count",
+            Some(ArcValue::new_primitive(5)),
+        )
+        .await?
+        .collect()
+        .await;
+    assert_eq!(items.len(), 4);
+    let values: Vec<i32> = items[..3].iter().map(|i| *i.as_ref().unwrap()).collect();
+    assert_eq!(values, vec![1, 2, 3]);
+    assert!(items[3]
+        .as_ref()
+        .unwrap_err()
+        .to_string()
+        .contains("count limit exceeded"));
+
+    let labels: Vec<String> = node
+        .request_stream::<_, String>("async_returns/pinned_labels", None::<ArcValue>)
+        .await?
+        .map(|item| item.unwrap())
+        .collect()
+        .await;
+    assert_eq!(labels, vec!["a".to_string(), "b".to_string()]);
+
+    // An opaque impl Trait result is serialized from its concrete type
+    let summary: Summary = node
+        .request("async_returns/opaque_summary", None::<ArcValue>)
+        .await?;
+    assert_eq!(
+        summary,
+        Summary {
+            total: 3,
+            label: "opaque".to_string()
+        }
+    );
+
+    node.stop().await?;
+    Ok(())
+}
@@ -0,0 +1,58 @@
+// Test for the dead_letter option of the subscribe macro
+//
+// A subscription declared with `dead_letter = false` keeps its failures out
+// of the node's dead-letter queue; other subscriptions are captured.
+
+use anyhow::{anyhow, Result};
+use runar_macros::{service, service_impl, subscribe};
+use runar_node::services::EventContext;
+
+#[service(name = "Order Audit", path = "order_audit")]
+pub struct OrderAuditService;
+
+#[service_impl]
+impl OrderAuditService {
+    #[subscribe(path = "orders/created")]
+    async fn on_order_created(&self, order: String, _ctx: &EventContext) -> Result<()> {
+        Err(anyhow!("cannot store {order}"))
+    }
+
+    #[subscribe(path = "orders/created", dead_letter = false)]
+    async fn on_order_created_best_effort(&self, order: String, _ctx: &EventContext) -> Result<()> {
+        Err(anyhow!("cannot index {order}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use runar_common::types::ArcValue;
+    use runar_node::services::PublishOptions;
+    use runar_node::{DispatchMode, Node};
+    use runar_test_utils::create_node_test_config;
+
+    #[tokio::test]
+    async fn test_subscribe_dead_letter_option() {
+        let config = create_node_test_config().expect("Error creating test config");
+        let mut node = Node::new(config).await.unwrap();
+        node.add_service(OrderAuditService::default())
+            .await
+            .unwrap();
+        node.start().await.unwrap();
+
+        node.publish_with_options(
+            "orders/created",
+            Some(ArcValue::new_primitive("order-1".to_string())),
+            PublishOptions {
+                dispatch: DispatchMode::Sync,
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+        let entries = node.dead_letters();
+        assert_eq!(entries.len(), 1, "{entries:?}");
+        assert!(entries[0].error.contains("cannot store order-1"));
+    }
+}
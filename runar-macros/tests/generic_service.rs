@@ -0,0 +1,157 @@
+// Test for services generic over their payload type
+//
+// Types mentioning a type parameter of the service are not registered with
+// the serializer by `#[service_impl]`; `#[action(register_types = "...")]`
+// lists the concrete types to register instead. Bounds may be written on
+// the struct's parameters as well as in a where clause.
+
+use anyhow::{anyhow, Result};
+use runar_macros::{action, service, service_impl};
+use serde::{Deserialize, Serialize};
+use std::fmt::Debug;
+use std::marker::PhantomData;
+use std::sync::{Arc, Mutex};
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Item {
+    pub id: u32,
+    pub name: String,
+}
+
+#[service(name = "Lists", path = "lists")]
+pub struct ListService<T> {
+    items: PhantomData<T>,
+}
+
+#[service_impl]
+impl<T> ListService<T>
+where
+    T: 'static + Clone + Send + Sync + Debug + Serialize + for<'de> Deserialize<'de>,
+{
+    #[action(register_types = "Vec<Item>, Option<Item>")]
+    async fn reverse(&self, items: Vec<T>) -> Result<Vec<T>> {
+        Ok(items.into_iter().rev().collect())
+    }
+
+    #[action]
+    async fn first(&self, items: Vec<T>) -> Result<Option<T>> {
+        Ok(items.into_iter().next())
+    }
+}
+
+#[service(name = "Cache", path = "cache")]
+pub struct CacheService<V: Serialize + for<'de> Deserialize<'de>> {
+    value: Arc<Mutex<Option<V>>>,
+}
+
+#[service_impl]
+impl<V: 'static + Clone + Send + Sync + Debug + Serialize + for<'de> Deserialize<'de>>
+    CacheService<V>
+{
+    /// Store a value, returning the one it replaces
+    #[action(register_types = "Option<Item>")]
+    async fn set(&self, value: V) -> Result<Option<V>> {
+        let mut cached = self.value.lock().map_err(|_| anyhow!("cache poisoned"))?;
+        Ok(cached.replace(value))
+    }
+
+    #[action]
+    async fn get(&self) -> Result<Option<V>> {
+        let cached = self.value.lock().map_err(|_| anyhow!("cache poisoned"))?;
+        Ok(cached.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use runar_common::types::ArcValue;
+    use runar_node::Node;
+    use runar_test_utils::create_node_test_config;
+
+    fn items() -> Vec<Item> {
+        vec![
+            Item {
+                id: 1,
+                name: "one".to_string(),
+            },
+            Item {
+                id: 2,
+                name: "two".to_string(),
+            },
+        ]
+    }
+
+    #[tokio::test]
+    async fn test_generic_list_actions() {
+        let config = create_node_test_config().expect("Error creating test config");
+        let mut node = Node::new(config).await.unwrap();
+        node.add_service(ListService::<Item>::default())
+            .await
+            .unwrap();
+        node.start().await.unwrap();
+
+        let reversed: Vec<Item> = node
+            .request("lists/reverse", Some(ArcValue::new_list(items())))
+            .await
+            .unwrap();
+        assert_eq!(reversed, items().into_iter().rev().collect::<Vec<_>>());
+
+        let first: Option<Item> = node
+            .request("lists/first", Some(ArcValue::new_list(items())))
+            .await
+            .unwrap();
+        assert_eq!(first, items().into_iter().next());
+
+        // Only the listed types were registered for the generic ones
+        let serializer = node.serializer.read().await;
+        assert!(serializer
+            .get_deserializer_arc(std::any::type_name::<Vec<Item>>())
+            .is_some());
+        assert!(serializer
+            .get_deserializer_arc(std::any::type_name::<Option<Item>>())
+            .is_some());
+
+        drop(serializer);
+        node.stop().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_generic_cache_actions() {
+        let config = create_node_test_config().expect("Error creating test config");
+        let mut node = Node::new(config).await.unwrap();
+        node.add_service(CacheService::<Item>::default())
+            .await
+            .unwrap();
+        let mut names = CacheService::<String>::default();
+        names.set_name("Names");
+        names.set_path("names");
+        node.add_service(names).await.unwrap();
+        node.start().await.unwrap();
+
+        let empty: Option<Item> = node.request("cache/get", None::<ArcValue>).await.unwrap();
+        assert_eq!(empty, None);
+        let item = items().remove(0);
+        let replaced: Option<Item> = node
+            .request("cache/set", Some(ArcValue::from_struct(item.clone())))
+            .await
+            .unwrap();
+        assert_eq!(replaced, None);
+        let cached: Option<Item> = node.request("cache/get", None::<ArcValue>).await.unwrap();
+        assert_eq!(cached, Some(item));
+
+        // A second instantiation of the service keeps its own values
+        let replaced: Option<String> = node
+            .request(
+                "names/set",
+                Some(ArcValue::new_primitive("ada".to_string())),
+            )
+            .await
+            .unwrap();
+        assert_eq!(replaced, None);
+        let cached: Option<String> = node.request("names/get", None::<ArcValue>).await.unwrap();
+        assert_eq!(cached.as_deref(), Some("ada"));
+
+        node.stop().await.unwrap();
+    }
+}
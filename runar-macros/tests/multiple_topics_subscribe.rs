@@ -0,0 +1,63 @@
+// Test for the topics option of the subscribe macro
+//
+// A method subscribed with `topics = [...]` handles the events of every
+// listed topic, and of no other.
+
+use anyhow::{anyhow, Result};
+use runar_macros::{service, service_impl, subscribe};
+use runar_node::services::EventContext;
+use std::sync::Mutex;
+
+static EVENTS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+#[service(name = "User Audit", path = "user_audit")]
+pub struct UserAuditService;
+
+#[service_impl]
+impl UserAuditService {
+    #[subscribe(topics = ["user/created", "user/updated", "user/deleted"])]
+    async fn on_user_changed(&self, user: String, _ctx: &EventContext) -> Result<()> {
+        EVENTS.lock().unwrap().push(user);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use runar_common::types::ArcValue;
+    use runar_node::services::PublishOptions;
+    use runar_node::{DispatchMode, Node};
+    use runar_test_utils::create_node_test_config;
+
+    #[tokio::test]
+    async fn test_subscribe_topics_option() {
+        let config = create_node_test_config().expect("Error creating test config");
+        let mut node = Node::new(config).await.unwrap();
+        node.add_service(UserAuditService::default()).await.unwrap();
+        node.start().await.unwrap();
+
+        for topic in [
+            "user/created",
+            "user/updated",
+            "user/renamed",
+            "user/deleted",
+        ] {
+            node.publish_with_options(
+                topic,
+                Some(ArcValue::new_primitive(topic.to_string())),
+                PublishOptions {
+                    dispatch: DispatchMode::Sync,
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        }
+
+        assert_eq!(
+            *EVENTS.lock().unwrap(),
+            ["user/created", "user/updated", "user/deleted"]
+        );
+    }
+}
@@ -0,0 +1,157 @@
+// Test for mutable actions
+//
+// A service with `#[action(mutable = true)]` keeps its state in plain fields:
+// its handlers share one instance behind a lock, so the state is never
+// duplicated and concurrent calls to a mutable action are serialized.
+
+use anyhow::{anyhow, Result};
+use runar_common::types::ArcValue;
+use runar_macros::{action, service, service_impl, subscribe};
+use runar_node::services::{EventContext, RequestContext};
+
+#[service(name = "Counter", path = "counter", mutable = true)]
+pub struct CounterService {
+    count: i64,
+    changes: Vec<i64>,
+}
+
+#[service_impl]
+impl CounterService {
+    #[action(mutable = true)]
+    async fn increment(&mut self, by: i64) -> Result<i64> {
+        let count = self.count;
+        // Give concurrent calls a chance to interleave
+        tokio::task::yield_now().await;
+        self.count = count + by;
+        self.changes.push(by);
+        Ok(self.count)
+    }
+
+    #[action]
+    async fn current(&self) -> Result<i64> {
+        Ok(self.count)
+    }
+
+    #[action]
+    async fn changes(&self) -> Result<Vec<i64>> {
+        Ok(self.changes.clone())
+    }
+
+    /// Calls back into the service while holding the write lock
+    #[action(mutable = true)]
+    async fn reenter(&mut self, ctx: &RequestContext) -> Result<i64> {
+        ctx.request::<ArcValue, i64>("counter/current", None).await
+    }
+
+    #[subscribe(path = "counter/reset")]
+    async fn reset(&mut self, value: i64, _ctx: &EventContext) -> Result<()> {
+        self.count = value;
+        self.changes.clear();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use runar_node::{Node, NodeDelegate, RwLockService};
+    use runar_test_utils::create_node_test_config;
+
+    #[tokio::test]
+    async fn test_mutable_actions_share_state() {
+        let config = create_node_test_config().expect("Error creating test config");
+        let mut node = Node::new(config).await.unwrap();
+        node.add_service(RwLockService::new(CounterService::default()))
+            .await
+            .unwrap();
+        node.start().await.unwrap();
+
+        let count: i64 = node
+            .request("counter/increment", Some(ArcValue::new_primitive(2i64)))
+            .await
+            .unwrap();
+        assert_eq!(count, 2);
+        let current: i64 = node
+            .request("counter/current", None::<ArcValue>)
+            .await
+            .unwrap();
+        assert_eq!(current, 2);
+
+        // Concurrent calls are serialized, so no increment is lost
+        let calls = (0..20).map(|_| {
+            let node = node.clone();
+            tokio::spawn(async move {
+                node.request::<_, i64>("counter/increment", Some(ArcValue::new_primitive(1i64)))
+                    .await
+            })
+        });
+        for call in futures::future::join_all(calls).await {
+            call.unwrap().unwrap();
+        }
+        let current: i64 = node
+            .request("counter/current", None::<ArcValue>)
+            .await
+            .unwrap();
+        assert_eq!(current, 22);
+        let changes: Vec<i64> = node
+            .request("counter/changes", None::<ArcValue>)
+            .await
+            .unwrap();
+        assert_eq!(changes.len(), 21);
+
+        // Subscriptions taking &mut self update the same instance
+        node.publish(
+            "counter/reset".to_string(),
+            Some(ArcValue::new_primitive(100i64)),
+        )
+        .await
+        .unwrap();
+        let current: i64 = node
+            .request("counter/current", None::<ArcValue>)
+            .await
+            .unwrap();
+        assert_eq!(current, 100);
+        let changes: Vec<i64> = node
+            .request("counter/changes", None::<ArcValue>)
+            .await
+            .unwrap();
+        assert!(changes.is_empty());
+
+        node.stop().await.unwrap();
+    }
+
+    /// Test that a handler calling back into its own service fails
+    ///
+    /// INTENTION: The write lock is held for the whole handler, so the
+    /// request back into the service would never get the lock; it must fail
+    /// right away, and the service must stay usable.
+    #[tokio::test]
+    async fn test_reentrant_request_fails() {
+        let config = create_node_test_config().expect("Error creating test config");
+        let mut node = Node::new(config).await.unwrap();
+        node.add_service(RwLockService::new(CounterService::default()))
+            .await
+            .unwrap();
+        node.start().await.unwrap();
+
+        let result = tokio::time::timeout(
+            std::time::Duration::from_secs(5),
+            node.request::<ArcValue, i64>("counter/reenter", None),
+        )
+        .await
+        .expect("A re-entrant request must not deadlock");
+        let error = result.expect_err("A re-entrant request must fail");
+        assert!(
+            format!("{error:#}").contains("called back into its own service"),
+            "{error:#}"
+        );
+
+        let current: i64 = node
+            .request("counter/current", None::<ArcValue>)
+            .await
+            .unwrap();
+        assert_eq!(current, 0);
+
+        node.stop().await.unwrap();
+    }
+}
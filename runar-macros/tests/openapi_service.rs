@@ -0,0 +1,134 @@
+// Test for the OpenAPI document generated by #[service_impl(openapi = true)]
+//
+// The document describes every action the way the gateway exposes it: actions
+// without parameters as GET, the others as POST with a JSON object of named
+// parameters.
+
+use anyhow::{anyhow, Result};
+use runar_common::types::ArcValue;
+use runar_macros::{action, service, service_impl};
+use runar_node::services::RequestContext;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+pub struct Point {
+    pub x: i32,
+    pub y: i32,
+}
+
+#[service(name = "Geometry", path = "geometry", version = "2.0.0")]
+pub struct GeometryService;
+
+#[service_impl(openapi = true)]
+impl GeometryService {
+    /// Mirror a point through the origin
+    #[action(path = "points/mirror")]
+    async fn mirror(&self, point: Point, _ctx: &RequestContext) -> Result<Point> {
+        Ok(Point {
+            x: -point.x,
+            y: -point.y,
+        })
+    }
+
+    #[action]
+    async fn origin(&self) -> Result<Point> {
+        Ok(Point { x: 0, y: 0 })
+    }
+
+    #[action]
+    async fn scale(&self, factor: f64, offset: Option<f64>) -> Result<f64> {
+        Ok(factor + offset.unwrap_or_default())
+    }
+
+    #[action]
+    async fn reset(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use runar_node::Node;
+    use runar_test_utils::create_node_test_config;
+
+    /// Test the paths, operations and schemas of the generated document
+    ///
+    /// INTENTION: Each action gets a path under the service path with the
+    /// method the gateway routes it with; struct types are described once in
+    /// `components/schemas` and referenced from the operations.
+    #[test]
+    fn test_openapi_schema() {
+        let schema = GeometryService::openapi_schema();
+
+        assert_eq!(schema["openapi"], "3.1.0");
+        assert_eq!(schema["info"]["title"], "Geometry");
+        assert_eq!(schema["info"]["version"], "2.0.0");
+
+        let paths = schema["paths"].as_object().unwrap();
+        let mut path_names: Vec<_> = paths.keys().cloned().collect();
+        path_names.sort();
+        assert_eq!(
+            path_names,
+            vec![
+                "/geometry/origin",
+                "/geometry/points/mirror",
+                "/geometry/reset",
+                "/geometry/scale",
+            ]
+        );
+
+        let mirror = &paths["/geometry/points/mirror"]["post"];
+        assert_eq!(mirror["operationId"], "geometry/points/mirror");
+        assert_eq!(mirror["description"], "Mirror a point through the origin");
+        let body = &mirror["requestBody"]["content"]["application/json"]["schema"];
+        assert_eq!(
+            body["properties"]["point"],
+            json!({ "$ref": "#/components/schemas/Point" })
+        );
+        assert_eq!(body["required"], json!(["point"]));
+        assert_eq!(
+            mirror["responses"]["200"]["content"]["application/json"]["schema"],
+            json!({ "$ref": "#/components/schemas/Point" })
+        );
+
+        // Optional parameters are not required
+        let scale = &paths["/geometry/scale"]["post"];
+        let body = &scale["requestBody"]["content"]["application/json"]["schema"];
+        assert_eq!(body["required"], json!(["factor"]));
+        assert_eq!(body["properties"]["factor"]["type"], "number");
+
+        // Actions without parameters are GET routes
+        assert!(paths["/geometry/origin"]["get"]["requestBody"].is_null());
+        assert!(paths["/geometry/reset"]["get"]["responses"]["200"]["content"].is_null());
+
+        let point = &schema["components"]["schemas"]["Point"];
+        assert_eq!(point["type"], "object");
+        assert_eq!(point["required"], json!(["x", "y"]));
+    }
+
+    /// Test that the document is served through the `openapi_schema` action
+    ///
+    /// INTENTION: The gateway fetches the document with a request, so the
+    /// action must return it with the path the service is registered under.
+    #[tokio::test]
+    async fn test_openapi_schema_action() {
+        let config = create_node_test_config().expect("Error creating test config");
+        let mut node = Node::new(config).await.unwrap();
+        let mut service = GeometryService::default();
+        service.set_path("shapes");
+        node.add_service(service).await.unwrap();
+        node.start().await.unwrap();
+
+        let mut schema: ArcValue = node
+            .request("shapes/openapi_schema", None::<ArcValue>)
+            .await
+            .unwrap();
+        let schema = schema.to_json_value().unwrap();
+        assert!(schema["paths"]["/shapes/points/mirror"]["post"].is_object());
+
+        node.stop().await.unwrap();
+    }
+}
@@ -0,0 +1,13 @@
+// Compile-time validation of service paths and action names
+//
+// Paths and names that cannot be routed are rejected by the macros instead
+// of failing silently at runtime, as are mutable actions in a service that
+// could be duplicated or that are not marked as mutable, service access
+// policies, `register_types` lists that cannot be parsed and subscriptions
+// without topics.
+
+#[test]
+fn test_invalid_paths_fail_to_compile() {
+    let cases = trybuild::TestCases::new();
+    cases.compile_fail("tests/ui/*.rs");
+}
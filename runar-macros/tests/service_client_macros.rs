@@ -0,0 +1,109 @@
+// Test for the service_client macro
+//
+// This test calls a macro-defined service through the typed client generated
+// by #[service_client] instead of building requests by hand.
+
+use anyhow::{anyhow, Result};
+use runar_macros::{action, service, service_client, service_impl};
+use runar_node::services::RequestContext;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Point {
+    pub x: i32,
+    pub y: i32,
+}
+
+#[service(name = "Calculator", path = "calculator")]
+pub struct CalculatorService;
+
+#[service_impl]
+#[service_client]
+impl CalculatorService {
+    #[action]
+    async fn add(&self, a: f64, b: f64, _ctx: &RequestContext) -> Result<f64> {
+        Ok(a + b)
+    }
+
+    #[action]
+    async fn negate(&self, value: i64) -> Result<i64> {
+        Ok(-value)
+    }
+
+    #[action(path = "points/mirror")]
+    async fn mirror(&self, point: Point) -> Result<Point> {
+        Ok(Point {
+            x: -point.x,
+            y: -point.y,
+        })
+    }
+
+    #[action("origin")]
+    async fn get_origin(&self) -> Result<Point> {
+        Ok(Point { x: 0, y: 0 })
+    }
+
+    #[action]
+    async fn divide(&self, a: f64, b: f64) -> Result<Option<f64>> {
+        Ok((b != 0.0).then(|| a / b))
+    }
+
+    #[action(version = 2)]
+    async fn scale(&self, value: f64, factor: f64) -> Result<f64> {
+        Ok(value * factor)
+    }
+
+    #[action]
+    async fn fail(&self, message: String) -> Result<()> {
+        Err(anyhow!(message))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use runar_node::Node;
+    use runar_test_utils::create_node_test_config;
+
+    /// Test calling every action through the generated client
+    ///
+    /// INTENTION: Verify that the client builds the payload map the action
+    /// expects, uses custom and versioned action paths and returns the typed results and
+    /// errors of the actions.
+    #[tokio::test]
+    async fn test_service_client() {
+        let config = create_node_test_config().expect("Error creating test config");
+        let network_id = config.default_network_id.clone();
+        let mut node = Node::new(config).await.unwrap();
+        node.add_service(CalculatorService::default())
+            .await
+            .unwrap();
+        node.start().await.unwrap();
+        let node = Arc::new(node);
+
+        let client = CalculatorServiceClient::new(node.clone(), None);
+        assert_eq!(client.service_path(), "calculator");
+
+        assert_eq!(client.add(1.5, 2.0).await.unwrap(), 3.5);
+        assert_eq!(client.negate(7).await.unwrap(), -7);
+        assert_eq!(
+            client.mirror(Point { x: 1, y: -2 }).await.unwrap(),
+            Point { x: -1, y: 2 }
+        );
+        assert_eq!(client.get_origin().await.unwrap(), Point { x: 0, y: 0 });
+        assert_eq!(client.divide(6.0, 3.0).await.unwrap(), Some(2.0));
+        assert_eq!(client.divide(6.0, 0.0).await.unwrap(), None);
+        assert_eq!(client.scale(1.5, 2.0).await.unwrap(), 3.0);
+        let error = client.fail("boom".to_string()).await.unwrap_err();
+        assert!(error.to_string().contains("boom"));
+
+        // An explicit network id addresses the service in that network
+        let client = CalculatorServiceClient::new(node.clone(), Some(network_id.as_str()));
+        assert_eq!(client.add(1.0, 1.0).await.unwrap(), 2.0);
+
+        // A service path with no service behind it fails the request
+        let client = CalculatorServiceClient::new(node, None).with_service_path("missing");
+        assert!(client.add(1.0, 1.0).await.is_err());
+    }
+}
@@ -83,6 +83,19 @@ impl TestService {
         Ok(user)
     }
 
+    #[action]
+    async fn find_user(&self, id: i32, _ctx: &RequestContext) -> Result<Option<User>> {
+        if id <= 0 {
+            return Ok(None);
+        }
+        Ok(Some(User {
+            id,
+            name: "John Doe".to_string(),
+            email: "john.doe@example.com".to_string(),
+            age: 30,
+        }))
+    }
+
     #[action]
     async fn echo(&self, message: String) -> Result<String> {
         Ok(message)
@@ -425,6 +438,19 @@ mod tests {
         // Verify the response
         assert_eq!(response.name, "John Doe");
 
+        // Make requests to the find_user action, which returns an Option
+        let found: Option<User> = node
+            .request("math/find_user", Some(ArcValue::new_primitive(42)))
+            .await
+            .expect("Failed to call find_user action");
+        assert_eq!(found.map(|user| user.id), Some(42));
+
+        let missing: Option<User> = node
+            .request("math/find_user", Some(ArcValue::new_primitive(0)))
+            .await
+            .expect("Failed to call find_user action");
+        assert!(missing.is_none());
+
         // Make a request to the get_my_data action
         let response: MyData = node
             .request("math/my_data", Some(ArcValue::new_primitive(100)))
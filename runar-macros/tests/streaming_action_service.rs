@@ -0,0 +1,87 @@
+// Test for actions returning a ResponseStream
+//
+// Streamed actions hand their values out one by one; callers read them with
+// Node::request_stream.
+
+use anyhow::{anyhow, Result};
+use futures::StreamExt;
+use runar_common::types::ArcValue;
+use runar_macros::{action, service, service_impl};
+use runar_node::services::{RequestContext, ResponseStream};
+use runar_node::Node;
+use runar_test_utils::create_node_test_config;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+struct Row {
+    id: i32,
+    name: String,
+}
+
+#[service(name = "Export Service", path = "export")]
+pub struct ExportService;
+
+#[service_impl]
+impl ExportService {
+    #[action]
+    async fn count(&self, up_to: i32, _ctx: &RequestContext) -> Result<ResponseStream<i32>> {
+        Ok(ResponseStream::from_items(1..=up_to))
+    }
+
+    #[action]
+    async fn rows(&self, _ctx: &RequestContext) -> Result<ResponseStream<Row>> {
+        let (sender, stream) = ResponseStream::channel(2);
+        tokio::spawn(async move {
+            for id in 0..5 {
+                let row = Row {
+                    id,
+                    name: format!("row {id}"),
+                };
+                if !sender.send(row).await {
+                    return;
+                }
+            }
+            sender.fail(anyhow!("export interrupted")).await;
+        });
+        Ok(stream)
+    }
+}
+
+#[tokio::test]
+async fn test_streaming_actions() -> Result<()> {
+    let config = create_node_test_config()?;
+    let mut node = Node::new(config).await?;
+    node.add_service(ExportService::default()).await?;
+    node.start().await?;
+
+    // Primitive items arrive in order and the stream ends after the last one
+    let values: Vec<i32> = node
+        .request_stream::<_, i32>("export/count", Some(ArcValue::new_primitive(4)))
+        .await?
+        .map(|item| item.unwrap())
+        .collect()
+        .await;
+    assert_eq!(values, vec![1, 2, 3, 4]);
+
+    // Struct items are converted back to their type; a producer error ends the stream
+    let items: Vec<Result<Row>> = node
+        .request_stream("export/rows", None::<ArcValue>)
+        .await?
+        .collect()
+        .await;
+    assert_eq!(items.len(), 6);
+    for (id, item) in items[..5].iter().enumerate() {
+        assert_eq!(item.as_ref().unwrap().id, id as i32);
+    }
+    let error = items[5].as_ref().unwrap_err();
+    assert!(error.to_string().contains("export interrupted"));
+
+    // Actions returning a single value cannot be consumed as a stream
+    node.request_stream::<ArcValue, i32>("$registry/services/export", None)
+        .await
+        .map(|_| ())
+        .expect_err("non-streaming action should be rejected");
+
+    node.stop().await?;
+    Ok(())
+}
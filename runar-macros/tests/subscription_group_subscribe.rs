@@ -0,0 +1,74 @@
+// Test for the group option of the subscribe macro
+//
+// Subscriptions declared with the same `group` share the events of their
+// topic, one member per event, while a subscription without a group still
+// receives every event.
+
+use anyhow::{anyhow, Result};
+use runar_macros::{service, service_impl, subscribe};
+use runar_node::services::EventContext;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static FIRST_WORKER: AtomicUsize = AtomicUsize::new(0);
+static SECOND_WORKER: AtomicUsize = AtomicUsize::new(0);
+static AUDIT: AtomicUsize = AtomicUsize::new(0);
+
+#[service(name = "Thumbnailer", path = "thumbnailer")]
+pub struct ThumbnailerService;
+
+#[service_impl]
+impl ThumbnailerService {
+    #[subscribe(path = "images/uploaded", group = "workers")]
+    async fn first_worker(&self, _image: String, _ctx: &EventContext) -> Result<()> {
+        FIRST_WORKER.fetch_add(1, Ordering::SeqCst);
+        Ok(())
+    }
+
+    #[subscribe(path = "images/uploaded", group = "workers")]
+    async fn second_worker(&self, _image: String, _ctx: &EventContext) -> Result<()> {
+        SECOND_WORKER.fetch_add(1, Ordering::SeqCst);
+        Ok(())
+    }
+
+    #[subscribe(path = "images/uploaded", group = "")]
+    async fn audit(&self, _image: String, _ctx: &EventContext) -> Result<()> {
+        AUDIT.fetch_add(1, Ordering::SeqCst);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use runar_common::types::ArcValue;
+    use runar_node::services::PublishOptions;
+    use runar_node::{DispatchMode, Node};
+    use runar_test_utils::create_node_test_config;
+
+    #[tokio::test]
+    async fn test_subscribe_group_option() {
+        let config = create_node_test_config().expect("Error creating test config");
+        let mut node = Node::new(config).await.unwrap();
+        node.add_service(ThumbnailerService::default())
+            .await
+            .unwrap();
+        node.start().await.unwrap();
+
+        for i in 0..4 {
+            node.publish_with_options(
+                "images/uploaded",
+                Some(ArcValue::new_primitive(format!("image-{i}"))),
+                PublishOptions {
+                    dispatch: DispatchMode::Sync,
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        }
+
+        assert_eq!(FIRST_WORKER.load(Ordering::SeqCst), 2);
+        assert_eq!(SECOND_WORKER.load(Ordering::SeqCst), 2);
+        assert_eq!(AUDIT.load(Ordering::SeqCst), 4);
+    }
+}
@@ -0,0 +1,15 @@
+use anyhow::{anyhow, Result};
+use runar_macros::{action, service, service_impl};
+
+#[service(name = "Users", path = "users")]
+pub struct UserService;
+
+#[service_impl]
+impl UserService {
+    #[action(name = "Get-User")]
+    async fn get_user(&self, id: i32) -> Result<i32> {
+        Ok(id)
+    }
+}
+
+fn main() {}
@@ -0,0 +1,16 @@
+use anyhow::{anyhow, Result};
+use runar_macros::{action, service, service_impl};
+
+#[service(name = "Lists", path = "lists")]
+pub struct ListService {}
+
+#[service_impl]
+impl ListService {
+    // Types are separated by commas
+    #[action(register_types = "Vec<u8>; Vec<u16>")]
+    async fn count(&self, items: Vec<u8>) -> Result<usize> {
+        Ok(items.len())
+    }
+}
+
+fn main() {}
@@ -0,0 +1,7 @@
+use runar_macros::service;
+
+// Peers are listed after a `peers:` prefix
+#[service(name = "Vault", path = "vault", access = "peer1,peer2")]
+pub struct VaultService;
+
+fn main() {}
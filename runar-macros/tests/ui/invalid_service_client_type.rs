@@ -0,0 +1,7 @@
+use runar_macros::service_client;
+
+// Clients are generated for services, which are named structs
+#[service_client]
+impl [u8; 4] {}
+
+fn main() {}
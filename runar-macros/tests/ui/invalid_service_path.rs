@@ -0,0 +1,7 @@
+use runar_macros::service;
+
+// The human-readable name reused as the path
+#[service(name = "Admin Service", path = "Admin Service")]
+pub struct AdminService;
+
+fn main() {}
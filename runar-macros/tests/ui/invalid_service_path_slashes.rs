@@ -0,0 +1,9 @@
+use runar_macros::service;
+
+#[service(name = "Leading Slash", path = "/admin")]
+pub struct LeadingSlashService;
+
+#[service(name = "Double Slash", path = "admin//users")]
+pub struct DoubleSlashService;
+
+fn main() {}
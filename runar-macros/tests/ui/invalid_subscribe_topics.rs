@@ -0,0 +1,16 @@
+use anyhow::Result;
+use runar_macros::{service, service_impl, subscribe};
+use runar_node::services::EventContext;
+
+#[service(name = "Users", path = "users")]
+pub struct UserService;
+
+#[service_impl]
+impl UserService {
+    #[subscribe(topics = [])]
+    async fn on_user_changed(&self, _ctx: &EventContext) -> Result<()> {
+        Ok(())
+    }
+}
+
+fn main() {}
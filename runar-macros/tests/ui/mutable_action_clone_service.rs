@@ -0,0 +1,19 @@
+use anyhow::{anyhow, Result};
+use runar_macros::{action, service, service_impl};
+
+#[service(name = "Counter", path = "counter", mutable = true)]
+#[derive(Clone)]
+pub struct CounterService {
+    count: i64,
+}
+
+#[service_impl]
+impl CounterService {
+    #[action(mutable = true)]
+    async fn increment(&mut self, by: i64) -> Result<i64> {
+        self.count += by;
+        Ok(self.count)
+    }
+}
+
+fn main() {}
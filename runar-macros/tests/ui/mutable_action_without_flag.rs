@@ -0,0 +1,18 @@
+use anyhow::{anyhow, Result};
+use runar_macros::{action, service, service_impl};
+
+#[service(name = "Counter", path = "counter")]
+pub struct CounterService {
+    count: i64,
+}
+
+#[service_impl]
+impl CounterService {
+    #[action]
+    async fn increment(&mut self, by: i64) -> Result<i64> {
+        self.count += by;
+        Ok(self.count)
+    }
+}
+
+fn main() {}
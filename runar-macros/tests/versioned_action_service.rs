@@ -0,0 +1,81 @@
+// Test for versioned actions
+//
+// `#[action(version = 2)]` registers an action under both its path and its
+// versioned path; requests for a version a service does not register fall
+// back to the unversioned action.
+
+use anyhow::{anyhow, Result};
+use runar_macros::{action, service, service_impl};
+
+#[service(name = "Greeter", path = "greeter")]
+pub struct GreeterService {}
+
+#[service_impl]
+impl GreeterService {
+    #[action]
+    async fn greet(&self) -> Result<String> {
+        Ok("hello".to_string())
+    }
+
+    #[action(path = "v3/greet")]
+    async fn greet_formally(&self) -> Result<String> {
+        Ok("good day".to_string())
+    }
+
+    #[action(version = 2)]
+    async fn count(&self) -> Result<i64> {
+        Ok(2)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use runar_common::types::ArcValue;
+    use runar_node::Node;
+    use runar_test_utils::create_node_test_config;
+
+    async fn start_node(default_api_version: Option<u8>) -> Node {
+        let mut config = create_node_test_config().expect("Error creating test config");
+        config.default_api_version = default_api_version;
+        let mut node = Node::new(config).await.unwrap();
+        node.add_service(GreeterService::default()).await.unwrap();
+        node.start().await.unwrap();
+        node
+    }
+
+    async fn request<T>(node: &Node, path: &str) -> T
+    where
+        T: 'static + Send + Sync + Clone + std::fmt::Debug + for<'de> serde::Deserialize<'de>,
+    {
+        node.request(path, None::<ArcValue>).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_versioned_routing() {
+        let node = start_node(None).await;
+
+        // Registered under both paths
+        assert_eq!(request::<i64>(&node, "greeter/count").await, 2);
+        assert_eq!(request::<i64>(&node, "greeter/v2/count").await, 2);
+
+        // An explicit versioned path, with a fallback for other versions
+        assert_eq!(request::<String>(&node, "greeter/greet").await, "hello");
+        assert_eq!(
+            request::<String>(&node, "greeter/v3/greet").await,
+            "good day"
+        );
+        assert_eq!(request::<String>(&node, "greeter/v2/greet").await, "hello");
+    }
+
+    #[tokio::test]
+    async fn test_default_api_version() {
+        let node = start_node(Some(3)).await;
+
+        assert_eq!(request::<String>(&node, "greeter/greet").await, "good day");
+        // Explicit versions take precedence over the default one
+        assert_eq!(request::<String>(&node, "greeter/v2/greet").await, "hello");
+        // Actions without the default version fall back to the unversioned one
+        assert_eq!(request::<i64>(&node, "greeter/count").await, 2);
+    }
+}
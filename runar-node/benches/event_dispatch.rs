@@ -0,0 +1,46 @@
+// Measure subscriber lookup for a published event as subscriptions grow
+//
+// Lookups walk the subscription trie one segment at a time instead of
+// scanning the subscriptions, so finding the single subscriber of a topic
+// should cost about the same with 10 or 10,000 other subscriptions registered;
+// only cache misses in the larger trie add to it.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use runar_node::routing::{PathTrie, TopicPath};
+
+const NETWORK: &str = "bench";
+
+fn subscriptions(count: usize) -> PathTrie<usize> {
+    let mut trie = PathTrie::new();
+    for i in 0..count {
+        let topic = TopicPath::new(&format!("service{}/event{}", i % 100, i), NETWORK).unwrap();
+        trie.set_value(topic, i);
+    }
+    trie.set_value(TopicPath::new("target/updated", NETWORK).unwrap(), count);
+    trie
+}
+
+fn bench_lookup(c: &mut Criterion) {
+    let topic = TopicPath::new("target/updated", NETWORK).unwrap();
+    let mut group = c.benchmark_group("lookup_single_subscriber");
+
+    for count in [10, 1_000, 10_000] {
+        let trie = subscriptions(count);
+        group.bench_with_input(BenchmarkId::from_parameter(count), &trie, |b, trie| {
+            b.iter(|| black_box(trie.find_matches(black_box(&topic)).len()))
+        });
+    }
+
+    // Wildcard subscribers sit on the walked path, so they add no scan
+    let mut trie = subscriptions(10_000);
+    trie.set_value(TopicPath::new("target/*", NETWORK).unwrap(), 0);
+    trie.set_value(TopicPath::new(">", NETWORK).unwrap(), 0);
+    group.bench_function("10000_with_wildcards", |b| {
+        b.iter(|| black_box(trie.find_matches(black_box(&topic)).len()))
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_lookup);
+criterion_main!(benches);
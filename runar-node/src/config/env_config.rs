@@ -0,0 +1,317 @@
+// Environment Configuration
+//
+// This module builds a NodeConfig from environment variables and TOML files,
+// for container deployments that receive their configuration from the outside.
+
+use crate::config::{LogLevel, LoggingConfig};
+use crate::network::network_config::{DiscoveryProviderConfig, NetworkConfig};
+use crate::network::transport::QuicTransportOptions;
+use crate::node::NodeConfig;
+use anyhow::Result;
+use rustls_pki_types::{CertificateDer, PrivateKeyDer, PrivatePkcs8KeyDer};
+use std::net::SocketAddr;
+use std::path::Path;
+use thiserror::Error;
+
+/// Prefix used by `NodeConfig::from_env`
+pub const DEFAULT_ENV_PREFIX: &str = "RUNAR";
+
+/// Error raised while loading or validating a node configuration
+#[derive(Error, Debug)]
+pub enum ConfigurationError {
+    #[error("missing required configuration: set {0}")]
+    Missing(String),
+    #[error("invalid value {value:?} for {name}: {reason}")]
+    InvalidValue {
+        name: String,
+        value: String,
+        reason: String,
+    },
+    #[error("failed to load {path}: {reason}")]
+    File { path: String, reason: String },
+    #[error("initial peers are configured but networking is disabled (no network config)")]
+    NetworkRequiredForDiscovery,
+    #[error("discovery is enabled but no discovery providers are configured")]
+    MissingDiscoveryProviders,
+    #[error("conflicting discovery modes: {0}")]
+    ConflictingDiscoveryModes(String),
+    #[error("invalid log level rule for {target:?}: {reason}")]
+    InvalidLogLevel { target: String, reason: String },
+}
+
+/// Settings that can be provided by a config file and overridden by the environment
+#[derive(Debug, Default)]
+struct ConfigSettings {
+    node_id: Option<String>,
+    network_id: Option<String>,
+    bind_addr: Option<String>,
+    log_level: Option<String>,
+    quic_cert_path: Option<String>,
+    quic_key_path: Option<String>,
+    seed_peers: Option<Vec<String>>,
+    multicast_enabled: Option<String>,
+}
+
+impl ConfigSettings {
+    /// Read the settings from a flat TOML document using the lowercase key names
+    fn from_toml_file(path: &Path) -> Result<Self, ConfigurationError> {
+        let file_error = |reason: String| ConfigurationError::File {
+            path: path.display().to_string(),
+            reason,
+        };
+        let contents = std::fs::read_to_string(path).map_err(|e| file_error(e.to_string()))?;
+        let document = contents
+            .parse::<toml_edit::Document>()
+            .map_err(|e| file_error(e.to_string()))?;
+
+        let string = |key: &str| -> Result<Option<String>, ConfigurationError> {
+            match document.get(key) {
+                None => Ok(None),
+                Some(item) => item
+                    .as_str()
+                    .map(|s| Some(s.to_string()))
+                    .ok_or_else(|| file_error(format!("`{key}` must be a string"))),
+            }
+        };
+
+        let seed_peers = match document.get("seed_peers") {
+            None => None,
+            Some(item) => {
+                let array = item
+                    .as_array()
+                    .ok_or_else(|| file_error("`seed_peers` must be an array".to_string()))?;
+                let peers = array
+                    .iter()
+                    .map(|value| value.as_str().map(str::to_string))
+                    .collect::<Option<Vec<_>>>()
+                    .ok_or_else(|| file_error("`seed_peers` must contain strings".to_string()))?;
+                Some(peers)
+            }
+        };
+
+        let multicast_enabled = match document.get("multicast_enabled") {
+            None => None,
+            Some(item) => Some(
+                item.as_bool()
+                    .ok_or_else(|| file_error("`multicast_enabled` must be a boolean".to_string()))?
+                    .to_string(),
+            ),
+        };
+
+        Ok(Self {
+            node_id: string("node_id")?,
+            network_id: string("network_id")?,
+            bind_addr: string("bind_addr")?,
+            log_level: string("log_level")?,
+            quic_cert_path: string("quic_cert_path")?,
+            quic_key_path: string("quic_key_path")?,
+            seed_peers,
+            multicast_enabled,
+        })
+    }
+
+    /// Override settings with the `{prefix}_*` environment variables that are set
+    fn apply_env(&mut self, prefix: &str) {
+        let var = |name: &str| std::env::var(env_name(prefix, name)).ok();
+
+        let overrides = [
+            (&mut self.node_id, var("NODE_ID")),
+            (&mut self.network_id, var("NETWORK_ID")),
+            (&mut self.bind_addr, var("BIND_ADDR")),
+            (&mut self.log_level, var("LOG_LEVEL")),
+            (&mut self.quic_cert_path, var("QUIC_CERT_PATH")),
+            (&mut self.quic_key_path, var("QUIC_KEY_PATH")),
+            (&mut self.multicast_enabled, var("MULTICAST_ENABLED")),
+        ];
+        for (setting, value) in overrides {
+            if value.is_some() {
+                *setting = value;
+            }
+        }
+
+        if let Some(peers) = var("SEED_PEERS") {
+            self.seed_peers = Some(
+                peers
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|peer| !peer.is_empty())
+                    .map(str::to_string)
+                    .collect(),
+            );
+        }
+    }
+
+    /// Build the node configuration, naming the `{prefix}_*` variables in errors
+    fn into_node_config(self, prefix: &str) -> Result<NodeConfig, ConfigurationError> {
+        let required = |value: Option<String>, name: &str| {
+            value.ok_or_else(|| ConfigurationError::Missing(env_name(prefix, name)))
+        };
+        let invalid = |name: &str, value: &str, reason: String| ConfigurationError::InvalidValue {
+            name: env_name(prefix, name),
+            value: value.to_string(),
+            reason,
+        };
+
+        let node_id = required(self.node_id, "NODE_ID")?;
+        let network_id = required(self.network_id, "NETWORK_ID")?;
+        let mut config = NodeConfig::new(node_id, network_id);
+
+        if let Some(level) = &self.log_level {
+            let log_level = parse_log_level(level)
+                .ok_or_else(|| invalid("LOG_LEVEL", level, "unknown log level".to_string()))?;
+            config = config.with_logging_config(LoggingConfig::new().with_default_level(log_level));
+        }
+
+        let multicast_enabled = match &self.multicast_enabled {
+            None => None,
+            Some(value) => Some(parse_bool(value).ok_or_else(|| {
+                invalid(
+                    "MULTICAST_ENABLED",
+                    value,
+                    "expected true or false".to_string(),
+                )
+            })?),
+        };
+
+        // Networking is only enabled when at least one network setting is present
+        let networked = self.bind_addr.is_some()
+            || self.quic_cert_path.is_some()
+            || self.quic_key_path.is_some()
+            || self.seed_peers.is_some()
+            || multicast_enabled == Some(true);
+        if !networked {
+            return Ok(config);
+        }
+
+        let mut quic_options = QuicTransportOptions::new();
+        match (&self.quic_cert_path, &self.quic_key_path) {
+            (Some(cert_path), Some(key_path)) => {
+                quic_options = quic_options
+                    .with_certificates(load_certificates(cert_path)?)
+                    .with_private_key(load_private_key(key_path)?);
+            }
+            (None, None) => {}
+            (Some(_), None) => {
+                return Err(ConfigurationError::Missing(env_name(
+                    prefix,
+                    "QUIC_KEY_PATH",
+                )))
+            }
+            (None, Some(_)) => {
+                return Err(ConfigurationError::Missing(env_name(
+                    prefix,
+                    "QUIC_CERT_PATH",
+                )))
+            }
+        }
+
+        let mut network_config = NetworkConfig::with_quic(quic_options);
+        if let Some(addr) = &self.bind_addr {
+            network_config.transport_options.bind_address = addr
+                .parse::<SocketAddr>()
+                .map_err(|e| invalid("BIND_ADDR", addr, e.to_string()))?;
+        }
+        if multicast_enabled == Some(true) {
+            network_config = network_config.with_multicast_discovery();
+        }
+        if let Some(peers) = self.seed_peers {
+            network_config = network_config
+                .with_discovery_provider(DiscoveryProviderConfig::default_static(peers));
+        }
+
+        Ok(config.with_network_config(network_config))
+    }
+}
+
+fn env_name(prefix: &str, name: &str) -> String {
+    format!("{prefix}_{name}")
+}
+
+fn parse_log_level(value: &str) -> Option<LogLevel> {
+    match value.to_ascii_lowercase().as_str() {
+        "error" => Some(LogLevel::Error),
+        "warn" | "warning" => Some(LogLevel::Warn),
+        "info" => Some(LogLevel::Info),
+        "debug" => Some(LogLevel::Debug),
+        "trace" => Some(LogLevel::Trace),
+        "off" => Some(LogLevel::Off),
+        _ => None,
+    }
+}
+
+fn parse_bool(value: &str) -> Option<bool> {
+    match value.to_ascii_lowercase().as_str() {
+        "1" | "true" | "yes" | "on" => Some(true),
+        "0" | "false" | "no" | "off" => Some(false),
+        _ => None,
+    }
+}
+
+fn load_certificates(path: &str) -> Result<Vec<CertificateDer<'static>>, ConfigurationError> {
+    let file_error = |reason: String| ConfigurationError::File {
+        path: path.to_string(),
+        reason,
+    };
+    let file = std::fs::File::open(path).map_err(|e| file_error(e.to_string()))?;
+    let certs = rustls_pemfile::certs(&mut std::io::BufReader::new(file))
+        .map_err(|e| file_error(e.to_string()))?;
+    if certs.is_empty() {
+        return Err(file_error("no PEM certificates found".to_string()));
+    }
+    Ok(certs.into_iter().map(CertificateDer::from).collect())
+}
+
+fn load_private_key(path: &str) -> Result<PrivateKeyDer<'static>, ConfigurationError> {
+    let file_error = |reason: String| ConfigurationError::File {
+        path: path.to_string(),
+        reason,
+    };
+    let file = std::fs::File::open(path).map_err(|e| file_error(e.to_string()))?;
+    let keys = rustls_pemfile::pkcs8_private_keys(&mut std::io::BufReader::new(file))
+        .map_err(|e| file_error(e.to_string()))?;
+    keys.into_iter()
+        .next()
+        .map(|key| PrivateKeyDer::Pkcs8(PrivatePkcs8KeyDer::from(key)))
+        .ok_or_else(|| file_error("no PEM PKCS#8 private key found".to_string()))
+}
+
+impl NodeConfig {
+    /// Create a configuration from the `RUNAR_*` environment variables
+    ///
+    /// INTENTION: Support 12-factor style deployments (Kubernetes, Docker
+    /// Compose) where configuration arrives through the environment.
+    /// See `from_env_with_prefix` for the variables that are read.
+    pub fn from_env() -> Result<NodeConfig> {
+        Self::from_env_with_prefix(DEFAULT_ENV_PREFIX)
+    }
+
+    /// Create a configuration from the `{prefix}_*` environment variables
+    ///
+    /// Reads `NODE_ID` and `NETWORK_ID` (required), `BIND_ADDR`, `LOG_LEVEL`,
+    /// `QUIC_CERT_PATH`, `QUIC_KEY_PATH` (PEM files, PKCS#8 key), `SEED_PEERS`
+    /// (comma-separated) and `MULTICAST_ENABLED`. When `CONFIG_FILE` is set the
+    /// file is loaded first with `from_toml_file` semantics and the variables
+    /// are applied on top of it. Networking is enabled only when a network
+    /// setting is present.
+    ///
+    /// The key manager state is never read from the environment; set it with
+    /// `with_key_manager_state` before creating the node. A QUIC key given here
+    /// must be the node's own key, as outgoing messages are signed with it.
+    pub fn from_env_with_prefix(prefix: &str) -> Result<NodeConfig> {
+        let mut settings = match std::env::var(env_name(prefix, "CONFIG_FILE")) {
+            Ok(path) => ConfigSettings::from_toml_file(Path::new(&path))?,
+            Err(_) => ConfigSettings::default(),
+        };
+        settings.apply_env(prefix);
+        Ok(settings.into_node_config(prefix)?)
+    }
+
+    /// Create a configuration from a TOML file
+    ///
+    /// The file uses the lowercase names of the `from_env` settings as
+    /// top-level keys, e.g. `node_id = "node-1"` or `seed_peers = ["10.0.0.2:50000"]`.
+    pub fn from_toml_file(path: impl AsRef<Path>) -> Result<NodeConfig> {
+        let settings = ConfigSettings::from_toml_file(path.as_ref())?;
+        Ok(settings.into_node_config(DEFAULT_ENV_PREFIX)?)
+    }
+}
@@ -2,7 +2,7 @@
 //
 // This module provides configuration options for logging in the Runar system.
 
-use runar_common::logging::Component;
+use runar_common::logging::{Component, LogFilter};
 use std::collections::HashMap;
 
 /// Logging configuration options
@@ -12,6 +12,10 @@ pub struct LoggingConfig {
     pub default_level: LogLevel,
     /// Component-specific log levels
     pub component_levels: HashMap<ComponentKey, LogLevel>,
+    /// Service-path-specific log levels, taking precedence over component levels
+    pub service_path_levels: HashMap<String, LogLevel>,
+    /// Fraction (0.0–1.0) of the messages logged per component
+    pub component_sampling_rates: HashMap<ComponentKey, f64>,
 }
 
 /// Component key for logging configuration
@@ -27,6 +31,21 @@ pub enum ComponentKey {
     Custom(String),
 }
 
+impl ComponentKey {
+    /// Names of the logger components (`Component::as_str`) this key covers
+    fn component_names(&self) -> Vec<&str> {
+        match self {
+            ComponentKey::Node => vec!["Node"],
+            ComponentKey::Registry => vec!["Registry"],
+            ComponentKey::Service => vec!["Service"],
+            ComponentKey::Database => vec!["DB"],
+            ComponentKey::Network => vec!["Network", "NetworkDiscovery"],
+            ComponentKey::System => vec!["System"],
+            ComponentKey::Custom(name) => vec![name.as_str()],
+        }
+    }
+}
+
 impl From<Component> for ComponentKey {
     fn from(component: Component) -> Self {
         match component {
@@ -81,6 +100,8 @@ impl LoggingConfig {
         Self {
             default_level: LogLevel::Info,
             component_levels: HashMap::new(),
+            service_path_levels: HashMap::new(),
+            component_sampling_rates: HashMap::new(),
         }
     }
 
@@ -101,6 +122,45 @@ impl LoggingConfig {
         self
     }
 
+    /// Set a log level for the requests and events of a service path
+    ///
+    /// A service path rule is more specific than a component rule, so it wins
+    /// when both apply.
+    pub fn with_service_path_level(mut self, path: &str, level: LogLevel) -> Self {
+        self.service_path_levels.insert(path.to_string(), level);
+        self
+    }
+
+    /// Log only `rate` (0.0–1.0) of the debug messages of a component
+    ///
+    /// Every message is kept or dropped on its own, so the logged ones are a
+    /// random sample; info messages, warnings and errors are always logged.
+    /// The number of dropped messages is logged in a summary line at most
+    /// every 10 seconds, and once more when the node's log filter is dropped.
+    pub fn with_sampling_rate(mut self, component: Component, rate: f64) -> Self {
+        self.component_sampling_rates.insert(component.into(), rate);
+        self
+    }
+
+    /// Build the filter that node loggers apply before emitting a message
+    pub fn log_filter(&self) -> LogFilter {
+        let mut filter = LogFilter::new(self.default_level.to_level_filter());
+        for (component, level) in &self.component_levels {
+            for name in component.component_names() {
+                filter = filter.with_component_level(name, level.to_level_filter());
+            }
+        }
+        for (path, level) in &self.service_path_levels {
+            filter = filter.with_service_path_level(path.as_str(), level.to_level_filter());
+        }
+        for (component, rate) in &self.component_sampling_rates {
+            for name in component.component_names() {
+                filter = filter.with_component_sampling_rate(name, *rate);
+            }
+        }
+        filter
+    }
+
     /// Apply this logging configuration
     ///
     /// INTENTION: Configure the global logger solely based on the settings in this
@@ -134,6 +194,10 @@ impl LoggingConfig {
             builder.filter(Some(target), level.to_level_filter());
         }
 
+        // Node loggers emit through runar_common and apply `log_filter` themselves,
+        // so let through everything that any rule may want to see
+        builder.filter(Some("runar_common::logging"), self.log_filter().max_level());
+
         // Try to initialize the global logger, but don't panic if it's already initialized
         // This is especially important for tests where multiple tests might try to initialize the logger
         let _ = builder.try_init();
@@ -2,7 +2,9 @@
 //
 // This module provides configuration options for the Runar system.
 
+pub mod env_config;
 pub mod logging_config;
 
 // Re-export configuration types
+pub use env_config::*;
 pub use logging_config::*;
@@ -40,20 +40,41 @@ pub mod network;
 pub mod node;
 pub mod routing;
 pub mod services;
+pub mod telemetry;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
 
 // Re-export the main types from the node module
-pub use node::{Node, NodeConfig};
+pub use node::{Node, NodeConfig, NodeConfigBuilder};
 
 // Re-export the main types from the services module
-pub use services::abstract_service::{AbstractService, ServiceState};
+pub use services::abstract_service::{
+    AbstractService, MutableService, RwLockService, ServiceState, SharedService,
+};
+pub use services::access_policy::ServiceAccessPolicy;
+pub use services::background_tasks::{TaskHandle, TaskInfo, TaskState};
+pub use services::circuit_breaker::{CircuitBreaker, CircuitBreakerConfig, CircuitState};
+pub use services::dead_letter::{DeadLetterEntry, DeadLetterQueue};
+pub use services::event_dedup::{event_dedup_id, sequenced_event_dedup_id, EventDedupCache};
+pub use services::event_dispatch::{DispatchMode, EventDispatcher};
+pub use services::event_ordering::OrderedEventBuffer;
+pub use services::event_replay::{EventReplayBuffer, ReplaySince};
+pub use services::health::{HealthGossip, HealthReport};
+pub use services::middleware::Middleware;
+pub use services::rate_limit::{RateLimitExceeded, RateLimitMiddleware, RateQuota};
+pub use services::retry_budget::{retry_backoff, RetryBucket, RetryBudget};
 pub use services::service_registry::ServiceRegistry;
+pub use services::service_restart::ServiceRestartPolicy;
+pub use services::subscription_groups::SubscriptionGroups;
 pub use services::{
     ActionHandler, EventContext, LifecycleContext, NodeDelegate, PublishOptions, RegistryDelegate,
-    RequestContext, ServiceRequest, SubscriptionOptions,
+    RequestContext, ResponseStream, ResponseStreamSender, ServiceRequest, SubscriptionOptions,
 };
 
 // Re-export the schema types from runar_common
-pub use runar_common::types::schemas::{ActionMetadata, EventMetadata, ServiceMetadata};
+pub use runar_common::types::schemas::{
+    ActionMetadata, EventMetadata, ServiceMetadata, ServiceVisibility,
+};
 
 // Re-export the main types from the routing module
 pub use routing::TopicPath;
@@ -61,10 +82,12 @@ pub use routing::TopicPath;
 // Re-export the main types from the network module
 pub use network::{
     DiscoveryOptions, NetworkMessage, NetworkMessageType, NetworkTransport, NodeDiscovery,
-    NodeInfo, PeerId, TransportOptions,
+    NodeInfo, PeerId, TransportOptions, TransportStats,
 };
 // Re-export peer registry types from transport
-pub use network::transport::{PeerEntry, PeerRegistry, PeerStatus};
+pub use network::transport::{PeerEntry, PeerEvent, PeerRegistry, PeerStatus};
+// Re-export request authentication types from transport
+pub use network::transport::{HmacAlgorithm, RequestAuthConfig};
 
 // Re-export common macros for convenience
 pub use runar_common::vmap;
@@ -0,0 +1,263 @@
+// mDNS / DNS-SD Node Discovery
+//
+// INTENTION: Provide an implementation of the NodeDiscovery trait that uses
+// mDNS (RFC 6762) with DNS-SD (RFC 6763) records. Networks that block the raw
+// multicast announcements of MulticastDiscovery often let standard mDNS
+// traffic through. Each node registers a `_runar._udp.local.` service whose
+// TXT records carry what MulticastDiscovery puts in its PeerInfo.
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use mdns_sd::{ServiceDaemon, ServiceEvent, ServiceInfo};
+use runar_common::logging::{Component, Logger};
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+use tokio::sync::{Mutex, RwLock};
+use tokio::task::JoinHandle;
+
+use super::multicast_discovery::PeerInfo;
+use super::{DiscoveryListener, DiscoveryOptions, NodeDiscovery, NodeInfo};
+
+/// DNS-SD service type nodes register under
+pub const DEFAULT_MDNS_SERVICE_TYPE: &str = "_runar._udp.local.";
+
+// TXT record keys
+const TXT_NETWORK_ID: &str = "network_id";
+const TXT_NODE_ID: &str = "node_id";
+const TXT_QUIC_PORT: &str = "quic_port";
+const TXT_SERVICES: &str = "services";
+
+/// A TXT entry holds at most 255 bytes of `key=value`
+const MAX_TXT_ENTRY: usize = 255;
+
+/// DNS labels, and so instance names, are limited to 63 bytes
+const MAX_INSTANCE_NAME: usize = 63;
+
+/// mDNS / DNS-SD based node discovery implementation
+pub struct MdnsDiscovery {
+    daemon: ServiceDaemon,
+    service_type: String,
+    local_node: NodeInfo,
+    options: Arc<RwLock<DiscoveryOptions>>,
+    // Keyed by the DNS-SD instance fullname, which is what removals report
+    discovered_nodes: Arc<RwLock<HashMap<String, PeerInfo>>>,
+    listeners: Arc<RwLock<Vec<DiscoveryListener>>>,
+    // Fullname of our own registration while announcing
+    registered: Mutex<Option<String>>,
+    browse_task: Mutex<Option<JoinHandle<()>>>,
+    logger: Logger,
+}
+
+impl MdnsDiscovery {
+    /// Create a new mDNS discovery instance browsing for `service_type`
+    pub async fn new(
+        local_node: NodeInfo,
+        service_type: impl Into<String>,
+        logger: Logger,
+    ) -> Result<Self> {
+        let service_type = service_type.into();
+        let daemon =
+            ServiceDaemon::new().map_err(|e| anyhow!("Failed to start mDNS daemon: {e}"))?;
+        let discovery_logger = logger.with_component(Component::Network);
+
+        let instance = Self {
+            daemon,
+            service_type,
+            local_node,
+            options: Arc::new(RwLock::new(DiscoveryOptions::default())),
+            discovered_nodes: Arc::new(RwLock::new(HashMap::new())),
+            listeners: Arc::new(RwLock::new(Vec::new())),
+            registered: Mutex::new(None),
+            browse_task: Mutex::new(None),
+            logger: discovery_logger,
+        };
+
+        let task = instance.start_browse_task()?;
+        *instance.browse_task.lock().await = Some(task);
+
+        Ok(instance)
+    }
+
+    /// Browse for other nodes and notify the listeners of every resolved one
+    fn start_browse_task(&self) -> Result<JoinHandle<()>> {
+        let receiver = self
+            .daemon
+            .browse(&self.service_type)
+            .map_err(|e| anyhow!("Failed to browse for {}: {e}", self.service_type))?;
+        let discovered_nodes = Arc::clone(&self.discovered_nodes);
+        let listeners = Arc::clone(&self.listeners);
+        let local_node = self.local_node.clone();
+        let logger = self.logger.clone();
+
+        Ok(tokio::spawn(async move {
+            while let Ok(event) = receiver.recv_async().await {
+                match event {
+                    ServiceEvent::ServiceResolved(info) => {
+                        let Some(peer_info) = peer_info_from_service(&info, &local_node) else {
+                            continue;
+                        };
+                        logger.debug(format!(
+                            "Resolved mDNS service {} for peer {}",
+                            info.get_fullname(),
+                            peer_info.public_key
+                        ));
+                        discovered_nodes
+                            .write()
+                            .await
+                            .insert(info.get_fullname().to_string(), peer_info.clone());
+
+                        let listeners_read = listeners.read().await;
+                        for listener in listeners_read.iter() {
+                            listener(peer_info.clone()).await;
+                        }
+                    }
+                    ServiceEvent::ServiceRemoved(_, fullname) => {
+                        logger.debug(format!("mDNS service removed: {fullname}"));
+                        discovered_nodes.write().await.remove(&fullname);
+                    }
+                    _ => {}
+                }
+            }
+        }))
+    }
+}
+
+/// Build the DNS-SD registration describing `node`
+fn service_info(node: &NodeInfo, service_type: &str) -> Result<ServiceInfo> {
+    let node_id = &node.peer_id.public_key;
+    let instance_name: String = node_id.chars().take(MAX_INSTANCE_NAME).collect();
+    let host_name = format!("{instance_name}.local.");
+    let quic_port = node
+        .addresses
+        .iter()
+        .find_map(|address| address.parse::<SocketAddr>().ok())
+        .map(|address| address.port())
+        .ok_or_else(|| anyhow!("Node has no IP address to announce over mDNS"))?;
+
+    let properties = [
+        (TXT_NETWORK_ID, node.network_ids.join(",")),
+        (TXT_NODE_ID, node_id.clone()),
+        (TXT_QUIC_PORT, quic_port.to_string()),
+        (TXT_SERVICES, services_txt(node)),
+    ];
+    ServiceInfo::new(
+        service_type,
+        &instance_name,
+        &host_name,
+        "",
+        quic_port,
+        &properties[..],
+    )
+    .map(ServiceInfo::enable_addr_auto)
+    .map_err(|e| anyhow!("Invalid mDNS service info: {e}"))
+}
+
+/// Comma separated service paths, cut at a whole path to fit one TXT entry
+fn services_txt(node: &NodeInfo) -> String {
+    let budget = MAX_TXT_ENTRY - TXT_SERVICES.len() - 1;
+    let mut services = String::new();
+    for service in &node.services {
+        let needed = service.service_path.len() + usize::from(!services.is_empty());
+        if services.len() + needed > budget {
+            break;
+        }
+        if !services.is_empty() {
+            services.push(',');
+        }
+        services.push_str(&service.service_path);
+    }
+    services
+}
+
+/// Turn a resolved registration into the PeerInfo MulticastDiscovery would report
+///
+/// Returns None for our own registration, for nodes outside our networks and
+/// for records missing the node ID.
+fn peer_info_from_service(info: &ServiceInfo, local_node: &NodeInfo) -> Option<PeerInfo> {
+    let node_id = info.get_property_val_str(TXT_NODE_ID)?;
+    if node_id == local_node.peer_id.public_key {
+        return None;
+    }
+    let shares_network = info
+        .get_property_val_str(TXT_NETWORK_ID)
+        .unwrap_or_default()
+        .split(',')
+        .any(|network_id| local_node.network_ids.iter().any(|id| id == network_id));
+    if !shares_network {
+        return None;
+    }
+
+    let quic_port = info
+        .get_property_val_str(TXT_QUIC_PORT)
+        .and_then(|port| port.parse::<u16>().ok())
+        .unwrap_or_else(|| info.get_port());
+    // IPv4 first, in a stable order
+    let mut ips: Vec<&IpAddr> = info.get_addresses().iter().collect();
+    ips.sort_by_key(|ip| (ip.is_ipv6(), **ip));
+    let addresses = ips
+        .into_iter()
+        .map(|ip| SocketAddr::new(*ip, quic_port).to_string())
+        .collect();
+
+    Some(PeerInfo::new(node_id.to_string(), addresses))
+}
+
+#[async_trait]
+impl NodeDiscovery for MdnsDiscovery {
+    async fn init(&self, options: DiscoveryOptions) -> Result<()> {
+        self.logger.info(format!(
+            "Initializing MdnsDiscovery for {} with options: {options:?}",
+            self.service_type
+        ));
+        *self.options.write().await = options;
+        Ok(())
+    }
+
+    async fn start_announcing(&self) -> Result<()> {
+        let info = service_info(&self.local_node, &self.service_type)?;
+        let fullname = info.get_fullname().to_string();
+        self.logger
+            .info(format!("Announcing node over mDNS as {fullname}"));
+
+        // The daemon answers queries and re-announces the record by itself
+        self.daemon
+            .register(info)
+            .map_err(|e| anyhow!("Failed to register mDNS service: {e}"))?;
+        *self.registered.lock().await = Some(fullname);
+        Ok(())
+    }
+
+    async fn stop_announcing(&self) -> Result<()> {
+        if let Some(fullname) = self.registered.lock().await.take() {
+            // Peers are told the service is gone before the daemon forgets it
+            self.daemon
+                .unregister(&fullname)
+                .map_err(|e| anyhow!("Failed to unregister mDNS service: {e}"))?;
+        }
+        Ok(())
+    }
+
+    async fn set_discovery_listener(&self, listener: DiscoveryListener) -> Result<()> {
+        self.logger.debug("Adding discovery listener".to_string());
+        self.listeners.write().await.push(listener);
+        Ok(())
+    }
+
+    async fn shutdown(&self) -> Result<()> {
+        self.logger.info("Shutting down MdnsDiscovery".to_string());
+
+        if let Err(e) = self.stop_announcing().await {
+            self.logger
+                .warn(format!("Error stopping announcements during shutdown: {e}"));
+        }
+        if let Some(task) = self.browse_task.lock().await.take() {
+            task.abort();
+        }
+        if let Err(e) = self.daemon.shutdown() {
+            self.logger
+                .warn(format!("Error shutting down mDNS daemon: {e}"));
+        }
+        Ok(())
+    }
+}
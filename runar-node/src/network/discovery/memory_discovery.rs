@@ -85,10 +85,7 @@ impl MemoryDiscovery {
             loop {
                 ticker.tick().await;
 
-                let peer_info = PeerInfo {
-                    public_key: node_info.peer_id.public_key.clone(),
-                    addresses: node_info.addresses.clone(),
-                };
+                let peer_info = PeerInfo::from_node_info(&node_info);
 
                 logger.debug(format!("Announcing local node: {}", node_info.peer_id));
 
@@ -144,10 +141,7 @@ impl MemoryDiscovery {
         self.logger
             .debug(format!("Added node to registry: {node_key}"));
 
-        let peer_info = PeerInfo {
-            public_key: node_info.peer_id.public_key.clone(),
-            addresses: node_info.addresses.clone(),
-        };
+        let peer_info = PeerInfo::from_node_info(&node_info);
 
         // Notify listeners
         let listeners_vec = {
@@ -282,6 +276,7 @@ mod tests {
             addresses: vec!["127.0.0.1:8000".to_string()],
             services: vec![],
             version: 0,
+            subscriptions: Vec::new(),
         };
         discovery.set_local_node(local_node);
 
@@ -50,10 +50,7 @@ impl MockNodeDiscovery {
         let key = node_info.peer_id.to_string();
         self.nodes.write().unwrap().insert(key, node_info.clone());
         // Notify listeners
-        let peer_info = crate::network::discovery::PeerInfo {
-            public_key: node_info.peer_id.public_key.clone(),
-            addresses: node_info.addresses.clone(),
-        };
+        let peer_info = crate::network::discovery::PeerInfo::from_node_info(&node_info);
         let listeners = {
             let guard = self.listeners.read().unwrap();
             guard.iter().cloned().collect::<Vec<_>>()
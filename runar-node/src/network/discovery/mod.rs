@@ -14,13 +14,15 @@ use std::time::Duration;
 use crate::network::transport::PeerId;
 use runar_common::types::ServiceMetadata;
 
+pub mod mdns_discovery;
 pub mod memory_discovery;
 pub mod mock;
 pub mod multicast_discovery;
 
+pub use mdns_discovery::{MdnsDiscovery, DEFAULT_MDNS_SERVICE_TYPE};
 pub use memory_discovery::MemoryDiscovery;
 pub use mock::MockNodeDiscovery;
-pub use multicast_discovery::MulticastDiscovery;
+pub use multicast_discovery::{MulticastDiscovery, ServiceAdvertisement};
 
 /// Configuration options for node discovery
 #[derive(Clone, Debug)]
@@ -73,6 +75,134 @@ pub struct NodeInfo {
     /// incremental version counter that change everytime the ndoe chagnes (new services added, new event subscriptions, etc)
     /// //when taht happens a new version is published to known peers.. and that is how peers know if  they need to update their own version of it
     pub version: i64,
+    /// Topics (or patterns) the node has subscribers for; peers only send it
+    /// the events published on a matching topic
+    pub subscriptions: Vec<String>,
+}
+
+impl NodeInfo {
+    /// Compute the service changes from this node info to `newer`
+    ///
+    /// Services are identified by network and path; a service whose metadata
+    /// changed is listed as added, replacing the old one.
+    pub fn diff(&self, newer: &NodeInfo) -> NodeInfoDiff {
+        let added_services = newer
+            .services
+            .iter()
+            .filter(|service| !self.services.contains(service))
+            .cloned()
+            .collect();
+        let removed_services = self
+            .services
+            .iter()
+            .filter(|service| {
+                !newer
+                    .services
+                    .iter()
+                    .any(|kept| service_key(kept) == service_key(service))
+            })
+            .map(service_key)
+            .collect();
+        NodeInfoDiff {
+            base_version: self.version,
+            version: newer.version,
+            added_services,
+            removed_services,
+            subscriptions: (self.subscriptions != newer.subscriptions)
+                .then(|| newer.subscriptions.clone()),
+        }
+    }
+
+    /// The node info as advertised to a peer in `network_ids`
+    ///
+    /// Leaves out the services whose `ServiceVisibility` hides them from
+    /// that peer.
+    pub fn visible_to(&self, network_ids: &[String]) -> NodeInfo {
+        NodeInfo {
+            services: self
+                .services
+                .iter()
+                .filter(|service| service.visibility.is_visible_to(network_ids))
+                .cloned()
+                .collect(),
+            ..self.clone()
+        }
+    }
+
+    /// Apply the service and subscription changes of `diff`
+    ///
+    /// Fails, leaving the node info unchanged, if the diff was computed from
+    /// another version than this one.
+    pub fn apply_diff(&mut self, diff: &NodeInfoDiff) -> Result<()> {
+        if diff.base_version != self.version {
+            return Err(anyhow::anyhow!(
+                "diff applies to version {} but node info has version {}",
+                diff.base_version,
+                self.version
+            ));
+        }
+        self.services.retain(|service| {
+            let key = service_key(service);
+            !diff.removed_services.contains(&key)
+                && !diff
+                    .added_services
+                    .iter()
+                    .any(|added| service_key(added) == key)
+        });
+        self.services.extend(diff.added_services.iter().cloned());
+        if let Some(subscriptions) = &diff.subscriptions {
+            self.subscriptions = subscriptions.clone();
+        }
+        self.version = diff.version;
+        Ok(())
+    }
+}
+
+/// The service changes between two versions of a node's `NodeInfo`
+///
+/// INTENTION: Tell peers only which services changed when a node adds or
+/// removes services, instead of resending its full `NodeInfo`. The full node
+/// info is still exchanged during handshakes; diffs only carry incremental
+/// updates, applied by peers holding the base version.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct NodeInfoDiff {
+    /// Version of the node info the diff applies to
+    pub base_version: i64,
+    /// Version of the node info once the diff is applied
+    pub version: i64,
+    /// Services added or changed since the base version
+    pub added_services: Vec<ServiceMetadata>,
+    /// Removed services, as `network_id:service_path`
+    pub removed_services: Vec<String>,
+    /// All the subscriptions of the node, if they changed since the base version
+    pub subscriptions: Option<Vec<String>>,
+}
+
+impl NodeInfoDiff {
+    /// Whether the diff changes no service and no subscription
+    pub fn is_empty(&self) -> bool {
+        self.added_services.is_empty()
+            && self.removed_services.is_empty()
+            && self.subscriptions.is_none()
+    }
+
+    /// The diff as sent to a peer in `network_ids`, see `NodeInfo::visible_to`
+    pub fn visible_to(&self, network_ids: &[String]) -> NodeInfoDiff {
+        NodeInfoDiff {
+            added_services: self
+                .added_services
+                .iter()
+                .filter(|service| service.visibility.is_visible_to(network_ids))
+                .cloned()
+                .collect(),
+            ..self.clone()
+        }
+    }
+}
+
+/// Key identifying a service in a `NodeInfoDiff`
+fn service_key(service: &ServiceMetadata) -> String {
+    format!("{}:{}", service.network_id, service.service_path)
 }
 
 /// Callback function type for discovery events
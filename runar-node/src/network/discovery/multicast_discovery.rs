@@ -11,6 +11,7 @@ use async_trait::async_trait;
 use bincode;
 use core::fmt;
 use runar_common::logging::{Component, Logger};
+use runar_common::types::ServiceMetadata;
 use serde::{Deserialize, Serialize};
 use socket2::{Domain, Protocol, Socket, Type};
 use std::collections::HashMap;
@@ -32,11 +33,24 @@ use super::{DiscoveryListener, DiscoveryOptions, NodeDiscovery, NodeInfo};
 // Default multicast address and port
 const DEFAULT_MULTICAST_PORT: u16 = 45678;
 
+/// Largest encoded multicast message a node sends
+///
+/// Keeps announcements within a single unfragmented datagram on common links.
+pub const MAX_ANNOUNCEMENT_SIZE: usize = 1200;
+
 /// Unique identifier for a node in the network
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct PeerInfo {
     pub public_key: String,
     pub addresses: Vec<String>,
+    /// Services the node hosted when it announced itself
+    ///
+    /// This is a hint to decide which peers are worth connecting to; routing
+    /// relies only on the `NodeInfo` exchanged during the handshake.
+    pub services: Vec<ServiceAdvertisement>,
+    /// False when services were left out to fit `MAX_ANNOUNCEMENT_SIZE`; the
+    /// full list is then only known from the node info after connecting
+    pub services_complete: bool,
 }
 
 impl PeerInfo {
@@ -45,6 +59,65 @@ impl PeerInfo {
         Self {
             public_key: peer_public_key,
             addresses,
+            services: Vec::new(),
+            services_complete: true,
+        }
+    }
+
+    /// Build the announcement of a local node, advertising its services
+    ///
+    /// Announcements reach every listener, so services restricted to some
+    /// networks are left out. Services that would grow the announcement past
+    /// `MAX_ANNOUNCEMENT_SIZE` are left out too, and the list is marked
+    /// incomplete.
+    pub fn from_node_info(info: &NodeInfo) -> Self {
+        let mut announcement = Self::new(info.peer_id.public_key.clone(), info.addresses.clone());
+        let mut size = announcement.encoded_size();
+        for service in info
+            .services
+            .iter()
+            .filter(|service| service.visibility.is_unrestricted())
+        {
+            let advertisement = ServiceAdvertisement::from(service);
+            let advertisement_size =
+                bincode::serialized_size(&advertisement).map_or(usize::MAX, |size| size as usize);
+            if size.saturating_add(advertisement_size) > MAX_ANNOUNCEMENT_SIZE {
+                announcement.services_complete = false;
+                break;
+            }
+            size += advertisement_size;
+            announcement.services.push(advertisement);
+        }
+        announcement
+    }
+
+    /// Whether the peer may host a service at `path`
+    ///
+    /// True when the peer advertised the service, or when its list is
+    /// incomplete and only the node info fetched after connecting can tell.
+    pub fn advertises_service(&self, path: &str) -> bool {
+        !self.services_complete || self.services.iter().any(|service| service.path == path)
+    }
+
+    /// Size of the announcement once encoded as a multicast message
+    pub fn encoded_size(&self) -> usize {
+        bincode::serialized_size(&MulticastMessage::Announce(self.clone()))
+            .map_or(usize::MAX, |size| size as usize)
+    }
+}
+
+/// A service advertised in a discovery announcement
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ServiceAdvertisement {
+    pub path: String,
+    pub version: String,
+}
+
+impl From<&ServiceMetadata> for ServiceAdvertisement {
+    fn from(metadata: &ServiceMetadata) -> Self {
+        Self {
+            path: metadata.service_path.clone(),
+            version: metadata.version.clone(),
         }
     }
 }
@@ -298,8 +371,7 @@ impl MulticastDiscovery {
             let mut ticker = time::interval(interval);
 
             // Create the discovery message once, outside the loop
-            let discovery_message =
-                PeerInfo::new(info.peer_id.public_key.clone(), info.addresses.clone());
+            let discovery_message = PeerInfo::from_node_info(&info);
 
             loop {
                 ticker.tick().await;
@@ -391,6 +463,10 @@ impl MulticastDiscovery {
                 }
 
                 match bincode::serialize(&message) {
+                    Ok(data) if data.len() > MAX_ANNOUNCEMENT_SIZE => logger.error(format!(
+                        "Not sending multicast message of {} bytes, above the {MAX_ANNOUNCEMENT_SIZE} byte limit",
+                        data.len()
+                    )),
                     Ok(data) => {
                         logger.debug(format!(
                             "Sending multicast message to {}, size: {}",
@@ -430,8 +506,8 @@ impl MulticastDiscovery {
                 return;
             }
         };
-        let local_peer_public_key = local_node_info.peer_id.public_key.clone();
-        let local_addresses = local_node_info.addresses.clone();
+        let local_info_msg = PeerInfo::from_node_info(local_node_info);
+        let local_peer_public_key = local_info_msg.public_key.clone();
         drop(local_node_guard);
 
         match message {
@@ -471,9 +547,6 @@ impl MulticastDiscovery {
 
                 // Only respond if this is a new peer we haven't seen before
                 if is_new_peer {
-                    // Build a discovery message with our own info
-                    let local_info_msg = PeerInfo::new(local_peer_public_key, local_addresses);
-
                     logger.debug(format!(
                         "Auto-responding to new peer announcement with our own info: {public_key}",
                         public_key = local_info_msg.public_key
@@ -572,10 +645,7 @@ impl NodeDiscovery for MulticastDiscovery {
         self.logger.info("Sending initial announcement".to_string());
 
         // Create a discovery message from the NodeInfo
-        let discovery_message = PeerInfo::new(
-            local_info.peer_id.public_key.clone(),
-            local_info.addresses.clone(),
-        );
+        let discovery_message = PeerInfo::from_node_info(&local_info);
 
         tx.send(MulticastMessage::Announce(discovery_message))
             .await
@@ -8,12 +8,17 @@ pub mod transport;
 
 pub use discovery::{
     DiscoveryListener, DiscoveryOptions, MemoryDiscovery, MulticastDiscovery, NodeDiscovery,
-    NodeInfo,
+    NodeInfo, NodeInfoDiff,
 };
 pub use runar_common::types::{ActionMetadata, EventMetadata, ServiceMetadata};
+#[cfg(unix)]
+pub use transport::UnixSocketTransport;
 pub use transport::{
-    MessageHandler, NetworkMessage, NetworkMessageType, NetworkTransport, PeerEntry, PeerId,
-    PeerRegistry, PeerStatus, QuicTransport, QuicTransportOptions, TransportOptions,
+    CompressionAlgorithm, ConnectionCallback, ConnectionEvent, ConnectionEventType, ErrorCode,
+    MessageHandler, MultiTransport, NetworkMessage, NetworkMessageType, NetworkTransport,
+    NodeCapabilities, PeerEntry, PeerEvent, PeerFilter, PeerId, PeerRegistry, PeerStatus,
+    QuicTransport, QuicTransportOptions, RequestAuthConfig, TransportOptions, TransportStats,
+    DEFAULT_MAX_HOPS, DEFAULT_MAX_VERSION_SKEW, PROTOCOL_VERSION,
 };
 
 // Implementation modules should be imported directly when needed:
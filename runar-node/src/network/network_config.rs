@@ -2,9 +2,12 @@
 //
 // This module provides configuration options for network functionality in the Runar system.
 
-use crate::network::discovery::{DiscoveryOptions, DEFAULT_MULTICAST_ADDR};
+use crate::network::discovery::{
+    DiscoveryOptions, DEFAULT_MDNS_SERVICE_TYPE, DEFAULT_MULTICAST_ADDR,
+};
 use crate::network::transport::{QuicTransportOptions, TransportOptions};
 use crate::services::load_balancing::RoundRobinLoadBalancer;
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -47,6 +50,10 @@ impl std::fmt::Display for NetworkConfig {
             self.connection_timeout_ms
         )?;
 
+        if let Some(path) = &self.transport_options.local_socket_path {
+            write!(f, " local_socket:{}", path.display())?;
+        }
+
         if let Some(discovery_options) = &self.discovery_options {
             if discovery_options.use_multicast {
                 write!(
@@ -79,6 +86,9 @@ pub enum DiscoveryProviderConfig {
 
     /// Static discovery configuration
     Static(StaticDiscoveryOptions),
+
+    /// mDNS / DNS-SD discovery configuration
+    Mdns(MdnsDiscoveryOptions),
     // Other discovery types can be added here as needed
 }
 
@@ -114,6 +124,13 @@ pub struct StaticDiscoveryOptions {
     pub refresh_interval: Duration,
 }
 
+/// Options specific to mDNS / DNS-SD discovery
+#[derive(Clone, Debug)]
+pub struct MdnsDiscoveryOptions {
+    /// DNS-SD service type nodes register and browse for
+    pub service_type: String,
+}
+
 impl DiscoveryProviderConfig {
     pub fn default_multicast() -> Self {
         DiscoveryProviderConfig::Multicast(MulticastDiscoveryOptions {
@@ -126,6 +143,12 @@ impl DiscoveryProviderConfig {
         })
     }
 
+    pub fn default_mdns() -> Self {
+        DiscoveryProviderConfig::Mdns(MdnsDiscoveryOptions {
+            service_type: DEFAULT_MDNS_SERVICE_TYPE.to_string(),
+        })
+    }
+
     pub fn default_static(addresses: Vec<String>) -> Self {
         DiscoveryProviderConfig::Static(StaticDiscoveryOptions {
             node_addresses: addresses,
@@ -201,6 +224,15 @@ impl NetworkConfig {
         self
     }
 
+    /// Also listen on a Unix socket for peers running on the same host
+    ///
+    /// The node then runs the QUIC and Unix socket transports side by side
+    /// (see `MultiTransport`) and advertises the socket as `unix:{path}`.
+    pub fn with_local_socket_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.transport_options.local_socket_path = Some(path.into());
+        self
+    }
+
     /// Enable multicast discovery with default settings
     ///
     /// This is a convenience method that configures:
@@ -217,4 +249,16 @@ impl NetworkConfig {
 
         self
     }
+
+    /// Enable mDNS / DNS-SD discovery with default settings
+    ///
+    /// Use this instead of multicast discovery on networks that only let
+    /// standard mDNS traffic through.
+    pub fn with_mdns_discovery(mut self) -> Self {
+        self.discovery_providers.clear();
+        self.discovery_providers
+            .push(DiscoveryProviderConfig::default_mdns());
+        self.discovery_options = Some(DiscoveryOptions::default());
+        self
+    }
 }
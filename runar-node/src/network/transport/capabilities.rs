@@ -0,0 +1,151 @@
+// Node Capabilities
+//
+// This module defines the capabilities two nodes exchange during the QUIC
+// handshake, before any application-level traffic, and the negotiation of the
+// capabilities both of them support.
+
+use runar_common::types::SerializationBackend;
+use serde::{Deserialize, Serialize};
+
+use super::{ErrorCode, NetworkError};
+
+/// Version of the wire protocol spoken by this node
+pub const PROTOCOL_VERSION: u8 = 1;
+
+/// Default largest difference between the protocol versions of two peers
+/// that can still talk to each other
+pub const DEFAULT_MAX_VERSION_SKEW: u8 = 1;
+
+/// Default largest message a node accepts, in bytes
+pub const DEFAULT_MAX_MESSAGE_SIZE: usize = 1024 * 1024;
+
+/// Message compression algorithms a node can support
+///
+/// INTENTION: Let both ends of a connection agree on an algorithm before
+/// compressed messages are sent. Only the negotiation exists for now; messages
+/// are sent uncompressed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum CompressionAlgorithm {
+    Zstd,
+    Lz4,
+    Deflate,
+}
+
+/// Capabilities a node advertises to its peers during the handshake
+///
+/// INTENTION: Each side sends its capabilities with its handshake message and
+/// keeps the result of `negotiate` for the connection, so features are only
+/// used when both peers support them.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NodeCapabilities {
+    /// Supported compression algorithms, in order of preference
+    pub compression_algorithms: Vec<CompressionAlgorithm>,
+    /// Largest message accepted, in bytes
+    pub max_message_size: usize,
+    /// Version of the wire protocol
+    pub protocol_version: u8,
+    /// Payload encodings the node can decode, in order of preference; the
+    /// first one is the encoding of the payloads it sends
+    pub supported_formats: Vec<SerializationBackend>,
+    /// Fingerprint of the type IDs the node reads in compact frames, see
+    /// `SerializerRegistry::type_table_fingerprint`; `None` when it does not
+    /// use compact framing
+    pub type_table: Option<u64>,
+}
+
+impl Default for NodeCapabilities {
+    fn default() -> Self {
+        Self {
+            compression_algorithms: Vec::new(),
+            max_message_size: DEFAULT_MAX_MESSAGE_SIZE,
+            protocol_version: PROTOCOL_VERSION,
+            supported_formats: SerializationBackend::available(),
+            type_table: None,
+        }
+    }
+}
+
+impl NodeCapabilities {
+    /// Advertise `format` as the encoding of the payloads this node sends
+    ///
+    /// Moves `format` to the front of `supported_formats`, adding it when
+    /// missing since a node always decodes its own encoding.
+    pub fn with_payload_format(mut self, format: SerializationBackend) -> Self {
+        self.supported_formats
+            .retain(|supported| *supported != format);
+        self.supported_formats.insert(0, format);
+        self
+    }
+
+    /// Compute the capabilities shared with a remote node
+    ///
+    /// The negotiated algorithms and formats keep the local order of
+    /// preference, and the message size and protocol version are the smaller
+    /// of both. The type table is kept only when both nodes use the same one.
+    /// Whether the versions are close enough to talk at all is checked
+    /// before negotiating, see `check_protocol_version`.
+    pub fn negotiate(&self, remote: &NodeCapabilities) -> NodeCapabilities {
+        NodeCapabilities {
+            compression_algorithms: self
+                .compression_algorithms
+                .iter()
+                .filter(|algorithm| remote.compression_algorithms.contains(algorithm))
+                .copied()
+                .collect(),
+            max_message_size: self.max_message_size.min(remote.max_message_size),
+            protocol_version: self.protocol_version.min(remote.protocol_version),
+            supported_formats: self
+                .supported_formats
+                .iter()
+                .filter(|format| remote.supported_formats.contains(format))
+                .copied()
+                .collect(),
+            type_table: self
+                .type_table
+                .filter(|type_table| remote.type_table == Some(*type_table)),
+        }
+    }
+}
+
+/// Check that a peer and this node decode the payloads they send each other
+///
+/// INTENTION: Every payload names its encoding, but a node built without that
+/// encoding still cannot read it. Refuse such peers at handshake time rather
+/// than failing each request. Nodes advertising no formats are not checked.
+pub fn check_payload_format(
+    local: &NodeCapabilities,
+    remote: &NodeCapabilities,
+) -> Result<(), NetworkError> {
+    let undecodable = |sender: &NodeCapabilities, receiver: &NodeCapabilities| {
+        sender
+            .supported_formats
+            .first()
+            .is_some_and(|format| !receiver.supported_formats.contains(format))
+    };
+    if undecodable(local, remote) || undecodable(remote, local) {
+        return Err(NetworkError::ConfigurationError(
+            ErrorCode::InvalidConfiguration,
+            format!(
+                "incompatible payload formats: local supports {:?}, remote supports {:?}",
+                local.supported_formats, remote.supported_formats
+            ),
+        ));
+    }
+    Ok(())
+}
+
+/// Check that a peer's protocol version is within `max_skew` of ours
+///
+/// INTENTION: Reject peers whose messages we may not be able to decode with a
+/// clear error at handshake time, instead of deserialization errors later.
+pub fn check_protocol_version(local: u8, remote: u8, max_skew: u8) -> Result<(), NetworkError> {
+    if local.abs_diff(remote) > max_skew {
+        return Err(NetworkError::ConfigurationError(
+            ErrorCode::InvalidConfiguration,
+            format!(
+                "incompatible protocol version: local {local}, remote {remote}, max skew {max_skew}"
+            ),
+        ));
+    }
+    Ok(())
+}
@@ -0,0 +1,312 @@
+// Message Chunking
+//
+// This module splits messages whose payload exceeds the largest message a
+// peer accepts into a sequence of chunk messages, and reassembles them on the
+// receiving side before they are processed.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use super::capabilities::DEFAULT_MAX_MESSAGE_SIZE;
+use super::{ErrorCode, NetworkError, NetworkMessage, PeerId};
+
+/// Room left in each chunk message for its header and signature, in bytes
+pub const CHUNK_FRAME_OVERHEAD: usize = 16 * 1024;
+
+/// Default time the chunks of a message may take to all arrive
+pub const DEFAULT_CHUNK_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Default largest message reassembled from chunks, in bytes
+pub const DEFAULT_MAX_REASSEMBLED_SIZE: usize = 64 * 1024 * 1024;
+
+/// Default number of chunked messages reassembled at the same time
+pub const DEFAULT_MAX_PENDING_MESSAGES: usize = 64;
+
+/// Default bytes held by the chunks of incomplete messages
+pub const DEFAULT_MAX_PENDING_BYTES: usize = 256 * 1024 * 1024;
+
+/// Largest payload carried by one chunk of a message limited to `max_message_size`
+pub fn chunk_size(max_message_size: usize) -> usize {
+    max_message_size.saturating_sub(CHUNK_FRAME_OVERHEAD).max(1)
+}
+
+/// Split `message` into chunks whose payloads hold at most `chunk_size` bytes
+///
+/// INTENTION: Let a service return a payload larger than the transport
+/// accepts in one frame, such as a database export. Only a message with a
+/// single payload larger than `chunk_size` is split; it becomes one message
+/// per chunk, each a copy of the original with a slice of the payload and
+/// its `chunk_index` and `total_chunks` set. Other messages are returned as
+/// they are.
+pub fn split_message(message: NetworkMessage, chunk_size: usize) -> Vec<NetworkMessage> {
+    let oversized = message.payloads.len() == 1
+        && message.payloads[0].value_bytes.len() > chunk_size
+        && chunk_size > 0;
+    if !oversized {
+        return vec![message];
+    }
+
+    let mut template = message;
+    let mut payload = template.payloads.remove(0);
+    let value_bytes = std::mem::take(&mut payload.value_bytes);
+    let total_chunks = value_bytes.len().div_ceil(chunk_size) as u32;
+    value_bytes
+        .chunks(chunk_size)
+        .enumerate()
+        .map(|(index, bytes)| {
+            let mut chunk_payload = payload.clone();
+            chunk_payload.value_bytes = bytes.to_vec();
+            chunk_payload.chunk_index = Some(index as u32);
+            chunk_payload.total_chunks = Some(total_chunks);
+            NetworkMessage {
+                payloads: vec![chunk_payload],
+                signature: None,
+                ..template.clone()
+            }
+        })
+        .collect()
+}
+
+/// Chunks received so far of one message
+struct PartialMessage {
+    /// First chunk received, whose header the reassembled message keeps
+    template: NetworkMessage,
+    chunks: Vec<Option<Vec<u8>>>,
+    received: usize,
+    /// Bytes of the chunks received
+    bytes: usize,
+    started_at: Instant,
+}
+
+/// Incomplete messages and the bytes their chunks hold
+#[derive(Default)]
+struct PendingChunks {
+    messages: HashMap<(PeerId, String), PartialMessage>,
+    bytes: usize,
+}
+
+impl PendingChunks {
+    fn remove(&mut self, key: &(PeerId, String)) -> Option<PartialMessage> {
+        let entry = self.messages.remove(key)?;
+        self.bytes -= entry.bytes;
+        Some(entry)
+    }
+
+    /// Remove the messages started longer than `timeout` ago, returning how many
+    fn expire(&mut self, timeout: Duration) -> usize {
+        let before = self.messages.len();
+        let mut freed = 0;
+        self.messages.retain(|_, entry| {
+            let keep = entry.started_at.elapsed() <= timeout;
+            if !keep {
+                freed += entry.bytes;
+            }
+            keep
+        });
+        self.bytes -= freed;
+        before - self.messages.len()
+    }
+}
+
+/// Reassembles the chunk messages received from the peers
+///
+/// INTENTION: Deliver a chunked message to the layers above the transport
+/// exactly as it was sent. Chunks are collected per source peer and
+/// correlation ID, in any order; once all arrived the payload is joined and
+/// the message is returned without chunk fields. A message whose chunks do
+/// not all arrive within the timeout is discarded.
+///
+/// Chunks are bounded so that a peer cannot make the node buffer without
+/// limit: a chunk larger than the chunk size, or a message of more chunks
+/// than `max_reassembled_size` needs, is refused, and so are new chunks once
+/// `max_pending_messages` messages or `max_pending_bytes` bytes are waiting
+/// for their remaining chunks.
+pub struct ChunkReassembler {
+    timeout: Duration,
+    chunk_size: usize,
+    max_reassembled_size: usize,
+    max_pending_messages: usize,
+    max_pending_bytes: usize,
+    pending: Mutex<PendingChunks>,
+}
+
+impl ChunkReassembler {
+    /// Create a reassembler for chunks of messages up to the default message size
+    pub fn new(timeout: Duration) -> Self {
+        Self {
+            timeout,
+            chunk_size: chunk_size(DEFAULT_MAX_MESSAGE_SIZE),
+            max_reassembled_size: DEFAULT_MAX_REASSEMBLED_SIZE,
+            max_pending_messages: DEFAULT_MAX_PENDING_MESSAGES,
+            max_pending_bytes: DEFAULT_MAX_PENDING_BYTES,
+            pending: Mutex::new(PendingChunks::default()),
+        }
+    }
+
+    /// Set the largest payload of one chunk, as computed by `chunk_size`
+    pub fn with_chunk_size(mut self, chunk_size: usize) -> Self {
+        self.chunk_size = chunk_size.max(1);
+        self
+    }
+
+    /// Set the largest payload reassembled from chunks, in bytes
+    pub fn with_max_reassembled_size(mut self, max_reassembled_size: usize) -> Self {
+        self.max_reassembled_size = max_reassembled_size;
+        self
+    }
+
+    /// Set how many incomplete messages, holding how many bytes, may wait for chunks
+    pub fn with_max_pending(mut self, messages: usize, bytes: usize) -> Self {
+        self.max_pending_messages = messages;
+        self.max_pending_bytes = bytes;
+        self
+    }
+
+    /// Add a received message
+    ///
+    /// Returns the message itself when it is not a chunk, the reassembled
+    /// message when this was its last missing chunk, and `None` while chunks
+    /// are missing. Fails with "chunk timeout" when the chunks of this
+    /// message took longer than the timeout to arrive, with `MessageTooLarge`
+    /// when the chunk or the message exceeds the size limits, and with
+    /// `RateLimited` when too many messages or bytes are already pending.
+    pub fn add(&self, mut message: NetworkMessage) -> Result<Option<NetworkMessage>, NetworkError> {
+        let Some(payload) = message.payloads.first() else {
+            return Ok(Some(message));
+        };
+        let (Some(chunk_index), Some(total_chunks)) = (payload.chunk_index, payload.total_chunks)
+        else {
+            return Ok(Some(message));
+        };
+        if message.payloads.len() != 1 || chunk_index >= total_chunks {
+            return Err(NetworkError::MessageError(
+                ErrorCode::InvalidMessage,
+                format!("invalid chunk {chunk_index} of {total_chunks}"),
+            ));
+        }
+        let max_chunks = self.max_reassembled_size.div_ceil(self.chunk_size);
+        if total_chunks as usize > max_chunks {
+            return Err(NetworkError::MessageError(
+                ErrorCode::MessageTooLarge,
+                format!("{total_chunks} chunks exceed the limit of {max_chunks}"),
+            ));
+        }
+        let chunk_len = payload.value_bytes.len();
+        if chunk_len > self.chunk_size {
+            return Err(NetworkError::MessageError(
+                ErrorCode::MessageTooLarge,
+                format!(
+                    "chunk of {chunk_len} bytes exceeds the chunk size of {}",
+                    self.chunk_size
+                ),
+            ));
+        }
+
+        let key = (message.source.clone(), payload.correlation_id.clone());
+        let mut pending = self.pending.lock().unwrap_or_else(|e| e.into_inner());
+        if pending
+            .messages
+            .get(&key)
+            .is_some_and(|entry| entry.started_at.elapsed() > self.timeout)
+        {
+            pending.remove(&key);
+            return Err(NetworkError::MessageError(
+                ErrorCode::MessageExpired,
+                "chunk timeout".to_string(),
+            ));
+        }
+
+        if !pending.messages.contains_key(&key) {
+            // Make room from stale messages before refusing a new one
+            pending.expire(self.timeout);
+            if pending.messages.len() >= self.max_pending_messages {
+                return Err(NetworkError::MessageError(
+                    ErrorCode::RateLimited,
+                    format!(
+                        "{} chunked messages already pending",
+                        pending.messages.len()
+                    ),
+                ));
+            }
+        }
+        if pending.bytes + chunk_len > self.max_pending_bytes {
+            pending.expire(self.timeout);
+            if pending.bytes + chunk_len > self.max_pending_bytes {
+                return Err(NetworkError::MessageError(
+                    ErrorCode::RateLimited,
+                    format!("{} bytes of chunks already pending", pending.bytes),
+                ));
+            }
+        }
+
+        let bytes = std::mem::take(&mut message.payloads[0].value_bytes);
+        let pending = &mut *pending;
+        let entry = pending
+            .messages
+            .entry(key.clone())
+            .or_insert_with(|| PartialMessage {
+                template: message,
+                chunks: vec![None; total_chunks as usize],
+                received: 0,
+                bytes: 0,
+                started_at: Instant::now(),
+            });
+        if entry.chunks.len() != total_chunks as usize {
+            pending.remove(&key);
+            return Err(NetworkError::MessageError(
+                ErrorCode::InvalidMessage,
+                format!("chunk count changed to {total_chunks}"),
+            ));
+        }
+        let slot = &mut entry.chunks[chunk_index as usize];
+        if slot.is_none() {
+            *slot = Some(bytes);
+            entry.received += 1;
+            entry.bytes += chunk_len;
+            pending.bytes += chunk_len;
+        }
+        if entry.received < entry.chunks.len() {
+            return Ok(None);
+        }
+
+        let entry = pending.remove(&key).expect("entry present");
+        let mut message = entry.template;
+        let payload = &mut message.payloads[0];
+        payload.value_bytes = entry.chunks.into_iter().flatten().flatten().collect();
+        payload.chunk_index = None;
+        payload.total_chunks = None;
+        message.signature = None;
+        Ok(Some(message))
+    }
+
+    /// Discard the messages whose chunks did not all arrive in time
+    ///
+    /// Returns a "chunk timeout" error for each discarded message.
+    pub fn discard_expired(&self) -> Vec<NetworkError> {
+        let discarded = self
+            .pending
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .expire(self.timeout);
+        (0..discarded)
+            .map(|_| {
+                NetworkError::MessageError(ErrorCode::MessageExpired, "chunk timeout".to_string())
+            })
+            .collect()
+    }
+
+    /// Number of messages with chunks still missing
+    pub fn pending(&self) -> usize {
+        self.pending
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .messages
+            .len()
+    }
+
+    /// Bytes held by the chunks of the messages still missing chunks
+    pub fn pending_bytes(&self) -> usize {
+        self.pending.lock().unwrap_or_else(|e| e.into_inner()).bytes
+    }
+}
@@ -2,10 +2,78 @@
 //!
 //! INTENTION: Handles lifecycle, lookup, and management of all peer connections for QUIC transport.
 
-use crate::network::transport::{NetworkError, PeerId, PeerState};
+use crate::network::transport::{ErrorCode, NetworkError, PeerId, PeerState, StreamPoolOptions};
 use dashmap::DashMap;
+use rand::Rng;
 use runar_common::logging::Logger;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
+use std::time::Instant;
+use tokio::sync::{Notify, Semaphore};
+
+/// Which pooled peer to evict when the pool is full
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum EvictionPolicy {
+    /// The peer looked up least recently
+    #[default]
+    Lru,
+    /// The peer looked up least often
+    Lfu,
+    /// Any peer, chosen at random
+    Random,
+}
+
+/// Limits applied to a ConnectionPool
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConnectionPoolOptions {
+    /// Maximum number of pooled peers (0 = unlimited)
+    pub max_size: usize,
+    /// Peer evicted to make room when `max_size` is reached
+    pub eviction_policy: EvictionPolicy,
+    /// Maximum number of callers parked in `wait_for_connection` at once
+    pub max_connection_waiters: usize,
+}
+
+impl Default for ConnectionPoolOptions {
+    fn default() -> Self {
+        Self {
+            max_size: 1000,
+            eviction_policy: EvictionPolicy::default(),
+            max_connection_waiters: 1024,
+        }
+    }
+}
+
+/// Snapshot of a ConnectionPool's usage
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ConnectionPoolStats {
+    /// Peers currently in the pool
+    pub current_size: usize,
+    /// Configured maximum number of peers (0 = unlimited)
+    pub max_size: usize,
+    /// Lookups that found a pooled peer
+    pub hits: u64,
+    /// Lookups that found no pooled peer
+    pub misses: u64,
+    /// Peers removed to make room or because they failed a health check
+    pub evictions: u64,
+}
+
+/// How often and how recently a pooled peer was looked up
+#[derive(Debug, Clone, Copy)]
+struct PeerUsage {
+    last_used: Instant,
+    uses: u64,
+}
+
+impl PeerUsage {
+    fn new() -> Self {
+        Self {
+            last_used: Instant::now(),
+            uses: 1,
+        }
+    }
+}
 
 /// ConnectionPool - Manages active connections
 ///
@@ -19,9 +87,20 @@ use std::sync::Arc;
 /// ConnectionPool - Manages active peer connections using a concurrent map
 ///
 /// INTENTION: Use DashMap for concurrent peer map access; PeerState is now granularly locked.
+/// When the pool holds `max_size` peers, adding another evicts one chosen by
+/// the eviction policy and closes its connection. Peers with requests in
+/// flight are not evicted; the pool grows past `max_size` when all have some.
 pub struct ConnectionPool {
     pub peers: DashMap<PeerId, Arc<PeerState>>,
     pub logger: Arc<Logger>,
+    options: ConnectionPoolOptions,
+    usage: DashMap<PeerId, PeerUsage>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+    evictions: AtomicU64,
+    /// Notifiers of the callers waiting for a connection, per peer
+    waiters: DashMap<PeerId, Arc<Notify>>,
+    waiter_permits: Semaphore,
 }
 
 impl ConnectionPool {
@@ -29,11 +108,91 @@ impl ConnectionPool {
     ///
     /// INTENTION: Initialize a pool for managing peer connections.
     pub fn new(logger: Arc<Logger>) -> Self {
+        Self::with_options(ConnectionPoolOptions::default(), logger)
+    }
+
+    /// Create a new ConnectionPool with explicit limits
+    pub fn with_options(options: ConnectionPoolOptions, logger: Arc<Logger>) -> Self {
         Self {
             peers: DashMap::new(),
             logger,
+            options,
+            usage: DashMap::new(),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            evictions: AtomicU64::new(0),
+            waiters: DashMap::new(),
+            waiter_permits: Semaphore::new(options.max_connection_waiters),
+        }
+    }
+
+    /// Get a snapshot of the pool's usage
+    pub fn stats(&self) -> ConnectionPoolStats {
+        ConnectionPoolStats {
+            current_size: self.peers.len(),
+            max_size: self.options.max_size,
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            evictions: self.evictions.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Record a lookup of a pooled peer
+    fn record_hit(&self, peer_id: &PeerId) {
+        self.hits.fetch_add(1, Ordering::Relaxed);
+        if let Some(mut usage) = self.usage.get_mut(peer_id) {
+            usage.last_used = Instant::now();
+            usage.uses += 1;
+        }
+    }
+
+    /// Choose the peer to evict according to the eviction policy
+    ///
+    /// Peers with requests in flight are never chosen, so their callers are
+    /// not left waiting for responses that can no longer arrive.
+    fn eviction_candidate(&self) -> Option<PeerId> {
+        let usage: Vec<(PeerId, PeerUsage)> = self
+            .usage
+            .iter()
+            .filter(|entry| {
+                self.peers
+                    .get(entry.key())
+                    .is_none_or(|peer_state| !peer_state.has_in_flight_requests())
+            })
+            .map(|entry| (entry.key().clone(), *entry.value()))
+            .collect();
+        match self.options.eviction_policy {
+            EvictionPolicy::Lru => usage
+                .into_iter()
+                .min_by_key(|(_, usage)| usage.last_used)
+                .map(|(peer_id, _)| peer_id),
+            EvictionPolicy::Lfu => usage
+                .into_iter()
+                .min_by_key(|(_, usage)| (usage.uses, usage.last_used))
+                .map(|(peer_id, _)| peer_id),
+            EvictionPolicy::Random => {
+                if usage.is_empty() {
+                    return None;
+                }
+                let index = rand::rng().random_range(0..usage.len());
+                usage.into_iter().nth(index).map(|(peer_id, _)| peer_id)
+            }
+        }
+    }
+
+    /// Remove a peer to make room or because it is unhealthy, closing its connection
+    fn evict(&self, peer_id: &PeerId) {
+        self.usage.remove(peer_id);
+        if let Some((_, peer_state)) = self.peers.remove(peer_id) {
+            self.evictions.fetch_add(1, Ordering::Relaxed);
+            self.logger
+                .info(format!("Evicting peer {peer_id} from the connection pool"));
+            tokio::spawn(async move {
+                let _ = peer_state.close_connection().await;
+            });
         }
     }
+
     /// Get or create a peer state for the given peer ID and address
     ///
     /// INTENTION: Ensure we have a PeerState object for each peer we interact with.
@@ -42,18 +201,32 @@ impl ConnectionPool {
         peer_id: PeerId,
         address: String,
         max_idle_streams: usize,
+        stream_pool_options: StreamPoolOptions,
         logger: Arc<Logger>,
     ) -> Arc<PeerState> {
         if let Some(existing) = self.peers.get(&peer_id) {
-            existing.clone()
+            let existing = existing.clone();
+            self.record_hit(&peer_id);
+            existing
         } else {
+            self.misses.fetch_add(1, Ordering::Relaxed);
+            if self.options.max_size > 0 && self.peers.len() >= self.options.max_size {
+                match self.eviction_candidate() {
+                    Some(victim) => self.evict(&victim),
+                    None => self.logger.warn(format!(
+                        "Connection pool is full and every peer has requests in flight, adding {peer_id} anyway"
+                    )),
+                }
+            }
             let peer_state = Arc::new(PeerState::new(
                 peer_id.clone(),
                 address,
                 max_idle_streams,
+                stream_pool_options,
                 logger,
             ));
             self.peers.insert(peer_id.clone(), peer_state.clone());
+            self.usage.insert(peer_id, PeerUsage::new());
             peer_state
         }
     }
@@ -62,13 +235,82 @@ impl ConnectionPool {
     ///
     /// INTENTION: Retrieve the state for a specific peer connection.
     pub fn get_peer(&self, peer_id: &PeerId) -> Option<Arc<PeerState>> {
-        self.peers.get(peer_id).map(|entry| entry.clone())
+        let peer_state = self.peers.get(peer_id).map(|entry| entry.clone());
+        match peer_state {
+            Some(_) => self.record_hit(peer_id),
+            None => {
+                self.misses.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+        peer_state
+    }
+
+    /// Set the connection of a pooled peer and wake the callers waiting for it
+    ///
+    /// INTENTION: Single place where connections are handed to peers, so that
+    /// `wait_for_connection` learns about every new connection.
+    pub async fn set_peer_connection(&self, peer_state: &PeerState, connection: quinn::Connection) {
+        peer_state.set_connection(connection).await;
+        if let Some((_, notify)) = self.waiters.remove(&peer_state.peer_id) {
+            notify.notify_waiters();
+        }
+    }
+
+    /// Wait until a peer is connected, up to `deadline`
+    ///
+    /// INTENTION: Let callers that expect a peer to connect soon, e.g. while
+    /// it is in its reconnect backoff, park until `set_peer_connection` is
+    /// called for it instead of retrying in a loop. The number of parked
+    /// callers is bounded by `max_connection_waiters`; callers beyond it fail
+    /// right away.
+    pub async fn wait_for_connection(
+        &self,
+        peer_id: &PeerId,
+        deadline: Instant,
+    ) -> Result<Arc<PeerState>, NetworkError> {
+        let _permit = self.waiter_permits.try_acquire().map_err(|_| {
+            NetworkError::ConnectionError(
+                ErrorCode::RateLimited,
+                "too many callers waiting for connections".to_string(),
+            )
+        })?;
+
+        let result = loop {
+            let notify = self.waiters.entry(peer_id.clone()).or_default().clone();
+            let notified = notify.notified();
+            tokio::pin!(notified);
+            // Register before checking so a connection set in between is not missed
+            notified.as_mut().enable();
+
+            let peer_state = self.peers.get(peer_id).map(|entry| entry.clone());
+            if let Some(peer_state) = peer_state {
+                if peer_state.is_connected().await {
+                    self.record_hit(peer_id);
+                    break Ok(peer_state);
+                }
+            }
+
+            let timeout =
+                tokio::time::timeout_at(tokio::time::Instant::from_std(deadline), notified).await;
+            if timeout.is_err() {
+                break Err(NetworkError::ConnectionError(
+                    ErrorCode::NotConnected,
+                    "timeout waiting for connection".to_string(),
+                ));
+            }
+        };
+
+        // Drop the notifier once its last waiter is gone
+        self.waiters
+            .remove_if(peer_id, |_, notify| Arc::strong_count(notify) == 1);
+        result
     }
 
     /// Remove a peer from the connection pool
     ///
     /// INTENTION: Clean up resources when a peer is disconnected.
     pub async fn remove_peer(&self, peer_id: &PeerId) -> Result<(), NetworkError> {
+        self.usage.remove(peer_id);
         if let Some((_, peer_state)) = self.peers.remove(peer_id) {
             let mut connection = peer_state.connection.lock().await;
             *connection = None;
@@ -80,7 +322,8 @@ impl ConnectionPool {
     ///
     /// INTENTION: Determine if we have an active connection to a specific peer.
     pub async fn is_peer_connected(&self, peer_id: &PeerId) -> bool {
-        if let Some(peer_state) = self.get_peer(peer_id) {
+        let peer_state = self.peers.get(peer_id).map(|entry| entry.clone());
+        if let Some(peer_state) = peer_state {
             peer_state.is_connected().await
         } else {
             false
@@ -101,6 +344,27 @@ impl ConnectionPool {
         }
         connected_peers
     }
+
+    /// Probe every pooled connection, evicting the unhealthy ones
+    ///
+    /// INTENTION: Let operators clear out peers whose connection was closed or
+    /// never established. Returns each probed peer with whether it was healthy.
+    pub async fn health_check_all(&self) -> Vec<(PeerId, bool)> {
+        let peers: Vec<(PeerId, Arc<PeerState>)> = self
+            .peers
+            .iter()
+            .map(|entry| (entry.key().clone(), entry.value().clone()))
+            .collect();
+        let mut results = Vec::with_capacity(peers.len());
+        for (peer_id, peer_state) in peers {
+            let healthy = peer_state.is_connected().await;
+            if !healthy {
+                self.evict(&peer_id);
+            }
+            results.push((peer_id, healthy));
+        }
+        results
+    }
 }
 
 impl std::fmt::Debug for ConnectionPool {
@@ -2,32 +2,57 @@
 use anyhow::Result;
 use async_trait::async_trait;
 use rand;
+use runar_common::types::SerializationBackend;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fmt;
 use std::future::Future;
 use std::net::{IpAddr, Ipv4Addr, SocketAddr, TcpListener};
 use std::ops::Range;
+use std::path::PathBuf;
 use std::pin::Pin;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use thiserror::Error;
 
 // Import the new rustls types
+use p256::ecdsa::signature::{Signer, Verifier};
+use p256::ecdsa::{Signature, SigningKey, VerifyingKey};
+use p256::pkcs8::DecodePrivateKey;
 use rustls::client::danger::{ServerCertVerified, ServerCertVerifier};
-use rustls_pki_types::{CertificateDer, ServerName};
+use rustls_pki_types::{CertificateDer, PrivateKeyDer, ServerName};
 
 // Internal module declarations
+pub mod capabilities;
 pub mod cert_utils;
+pub mod chunking;
 pub mod connection_pool;
+pub mod multi_transport;
+pub mod peer_filter;
 pub mod peer_registry;
 pub mod peer_state;
+pub mod proxy;
 pub mod quic_transport;
+pub mod request_auth;
 pub mod stream_pool;
+#[cfg(unix)]
+pub mod unix_socket_transport;
 
+pub use capabilities::{
+    CompressionAlgorithm, NodeCapabilities, DEFAULT_MAX_VERSION_SKEW, PROTOCOL_VERSION,
+};
 pub use cert_utils::generate_self_signed_cert;
-pub use connection_pool::ConnectionPool;
+pub use chunking::ChunkReassembler;
+pub use connection_pool::{
+    ConnectionPool, ConnectionPoolOptions, ConnectionPoolStats, EvictionPolicy,
+};
+pub use peer_filter::PeerFilter;
 pub use peer_state::PeerState;
-pub use stream_pool::StreamPool;
+pub use proxy::{ProxyConfig, ProxyKind};
+pub use request_auth::{
+    AuthenticatedTransport, HmacAlgorithm, RequestAuthConfig, RequestAuthenticator,
+};
+pub use stream_pool::{StreamPool, StreamPoolOptions, StreamPoolStats, StreamSlot};
 
 // --- Moved from quic_transport.rs ---
 /// Custom certificate verifier that skips verification for testing
@@ -88,13 +113,30 @@ impl ServerCertVerifier for SkipServerVerification {
 // Removed WebSocket module completely
 
 // Re-export types/traits from submodules or parent modules
-pub use peer_registry::{PeerEntry, PeerRegistry, PeerRegistryOptions, PeerStatus};
+pub use multi_transport::MultiTransport;
+pub use peer_registry::{PeerEntry, PeerEvent, PeerRegistry, PeerRegistryOptions, PeerStatus};
 pub use quic_transport::{QuicTransport, QuicTransportOptions};
+#[cfg(unix)]
+pub use unix_socket_transport::UnixSocketTransport;
 // Don't re-export pick_free_port since it's defined in this module
 
 use super::discovery::multicast_discovery::PeerInfo;
 // Import NodeInfo from the discovery module
-use super::discovery::NodeInfo;
+use super::discovery::{NodeInfo, NodeInfoDiff};
+
+/// Load the node key used to sign messages from the TLS private key
+///
+/// Returns None for keys that are not PKCS#8 encoded.
+pub(crate) fn message_signing_key(
+    private_key: Option<&PrivateKeyDer<'static>>,
+) -> Result<Option<SigningKey>, String> {
+    match private_key {
+        Some(PrivateKeyDer::Pkcs8(key)) => SigningKey::from_pkcs8_der(key.secret_pkcs8_der())
+            .map(Some)
+            .map_err(|e| format!("Failed to load message signing key: {e}")),
+        _ => Ok(None),
+    }
+}
 
 /// Type alias for async-returning function
 pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
@@ -130,6 +172,40 @@ pub struct TransportOptions {
     pub max_message_size: Option<usize>,
     /// Bind address for the transport
     pub bind_address: SocketAddr,
+    /// Maximum number of relays a message may pass through
+    #[serde(default = "default_max_hops")]
+    pub max_hops: u8,
+    /// Unix socket to listen on for co-located peers, next to the network transport
+    #[serde(default)]
+    pub local_socket_path: Option<PathBuf>,
+}
+
+/// Prefix marking an address as a Unix socket path (e.g. `unix:/tmp/node.sock`)
+pub const UNIX_ADDRESS_PREFIX: &str = "unix:";
+
+/// Default for `TransportOptions::max_hops`
+pub const DEFAULT_MAX_HOPS: u8 = 16;
+
+fn default_max_hops() -> u8 {
+    DEFAULT_MAX_HOPS
+}
+
+/// Aggregate transport-level statistics
+///
+/// INTENTION: Give operators a cheap snapshot of connection churn, traffic
+/// volume and error rates for capacity planning. Counters are cumulative
+/// since the transport was created.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TransportStats {
+    pub total_connections_established: u64,
+    pub total_connections_dropped: u64,
+    pub current_connections: u32,
+    pub total_bytes_sent: u64,
+    pub total_bytes_received: u64,
+    pub total_messages_sent: u64,
+    pub total_messages_received: u64,
+    /// Error counts keyed by `NetworkError` variant name (e.g. "ConnectionError")
+    pub errors_by_kind: HashMap<String, u64>,
 }
 
 #[allow(clippy::derivable_impls)]
@@ -142,6 +218,8 @@ impl Default for TransportOptions {
             timeout: Some(Duration::from_secs(30)),
             max_message_size: Some(1024 * 1024), // 1MB default
             bind_address,
+            max_hops: DEFAULT_MAX_HOPS,
+            local_socket_path: None,
         }
     }
 }
@@ -220,6 +298,49 @@ pub struct NetworkMessagePayloadItem {
 
     /// Correlation ID for request/response tracking
     pub correlation_id: String,
+
+    /// `ErrorCode` of a relayed error (only set on "Error" and "StreamEnd" messages)
+    pub error_code: Option<u16>,
+
+    /// Position of a streamed response frame (only set on "StreamItem" and
+    /// "StreamEnd" messages; a "StreamEnd" carries the number of items sent),
+    /// or sequence number of an ordered "Event" within its topic
+    pub sequence: Option<u64>,
+
+    /// Content hash identifying a published event (only set on "Event"
+    /// messages; see `event_dedup_id`)
+    pub dedup_id: Option<[u8; 8]>,
+
+    /// Backend `value_bytes` were encoded with; the receiver decodes with its
+    /// own backend when None
+    pub format: Option<SerializationBackend>,
+
+    /// Position of this chunk of a payload split by the transport (see
+    /// `chunking::split_message`); None when the payload was sent whole
+    pub chunk_index: Option<u32>,
+
+    /// Number of chunks the payload was split into, set with `chunk_index`
+    pub total_chunks: Option<u32>,
+}
+
+/// Error carried by the payload of an "Error" message
+///
+/// INTENTION: Let the caller of a failed remote action get the original error
+/// back. The payload's `value_bytes` hold this struct as a serialized
+/// `ArcValue`. When the error's type is registered with
+/// `SerializerRegistry::register_error`, `serialized_error` holds the encoded
+/// error, and a receiver that registered the same type rebuilds it; otherwise
+/// the receiver falls back to `message`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct NetworkErrorPayload {
+    /// `ErrorCode` of the error
+    pub code: u16,
+    /// Human-readable description of the error
+    pub message: String,
+    /// Rust type name of the error, used to find its deserializer
+    pub type_name: String,
+    /// The encoded error, if its type is registered
+    pub serialized_error: Option<Vec<u8>>,
 }
 
 impl NetworkMessagePayloadItem {
@@ -229,11 +350,27 @@ impl NetworkMessagePayloadItem {
             path,
             value_bytes,
             correlation_id,
+            error_code: None,
+            sequence: None,
+            dedup_id: None,
+            format: None,
+            chunk_index: None,
+            total_chunks: None,
         }
     }
+
+    /// Name the backend the payload was encoded with
+    pub fn with_format(mut self, format: SerializationBackend) -> Self {
+        self.format = Some(format);
+        self
+    }
 }
 
 /// Represents a message exchanged between nodes
+///
+/// Messages are encoded with bincode, which is not self-describing: peers
+/// must agree on the exact field list, so adding a field here (or to
+/// `NetworkMessagePayloadItem`) requires bumping `PROTOCOL_VERSION`.
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct NetworkMessage {
     /// Source node identifier
@@ -245,8 +382,189 @@ pub struct NetworkMessage {
     /// Message type (Request, Response, Event, etc.)
     pub message_type: String,
 
-    /// List of payloads  
+    /// List of payloads
     pub payloads: Vec<NetworkMessagePayloadItem>,
+
+    /// ECDSA P-256 signature (r || s) over `signing_digest()`, made with the
+    /// source node's private key. Set by the transport on the send path.
+    #[serde(with = "signature_bytes")]
+    pub signature: Option<[u8; 64]>,
+
+    /// Number of times the message has been relayed (0 for direct messages)
+    pub hop_count: u8,
+
+    /// Peers that relayed the message, in order
+    pub visited_peers: Vec<PeerId>,
+
+    /// HMAC tag proving the sender holds the shared request authentication
+    /// key. Set by the Node when `NodeConfig::request_auth` is configured.
+    pub auth_tag: Option<Vec<u8>>,
+
+    /// Time after which the receiver drops the message instead of dispatching
+    /// it. Set for events published with `PublishOptions::ttl`; None never expires.
+    pub expires_at: Option<SystemTime>,
+}
+
+impl NetworkMessage {
+    /// Compute the digest that is signed to authenticate the message source
+    ///
+    /// INTENTION: Bind the signature to every field a forger could tamper with:
+    /// SHA-256 over source, destination, message type and all payloads. Each
+    /// field is length-prefixed so that field boundaries cannot be shifted.
+    /// The expiry time is covered too, so a relay cannot extend a message's life.
+    /// The hop fields are left out because relays update them in transit.
+    pub fn signing_digest(&self) -> [u8; 32] {
+        fn update_field(ctx: &mut ring::digest::Context, bytes: &[u8]) {
+            ctx.update(&(bytes.len() as u64).to_be_bytes());
+            ctx.update(bytes);
+        }
+
+        let mut ctx = ring::digest::Context::new(&ring::digest::SHA256);
+        update_field(&mut ctx, self.source.public_key.as_bytes());
+        update_field(&mut ctx, self.destination.public_key.as_bytes());
+        update_field(&mut ctx, self.message_type.as_bytes());
+        ctx.update(&(self.payloads.len() as u64).to_be_bytes());
+        for payload in &self.payloads {
+            update_field(&mut ctx, payload.path.as_bytes());
+            update_field(&mut ctx, &payload.value_bytes);
+            update_field(&mut ctx, payload.correlation_id.as_bytes());
+            match payload.error_code {
+                Some(code) => update_field(&mut ctx, &code.to_be_bytes()),
+                None => update_field(&mut ctx, &[]),
+            }
+            match payload.sequence {
+                Some(sequence) => update_field(&mut ctx, &sequence.to_be_bytes()),
+                None => update_field(&mut ctx, &[]),
+            }
+            match payload.dedup_id {
+                Some(dedup_id) => update_field(&mut ctx, &dedup_id),
+                None => update_field(&mut ctx, &[]),
+            }
+            match payload.format {
+                Some(format) => update_field(&mut ctx, format!("{format:?}").as_bytes()),
+                None => update_field(&mut ctx, &[]),
+            }
+            match (payload.chunk_index, payload.total_chunks) {
+                (Some(index), Some(total)) => update_field(
+                    &mut ctx,
+                    &[index.to_be_bytes(), total.to_be_bytes()].concat(),
+                ),
+                _ => update_field(&mut ctx, &[]),
+            }
+        }
+        match self.expires_at.map(|t| t.duration_since(UNIX_EPOCH)) {
+            Some(Ok(since_epoch)) => update_field(&mut ctx, &since_epoch.as_nanos().to_be_bytes()),
+            // Times before the epoch are already expired, the exact value does not matter
+            Some(Err(_)) => update_field(&mut ctx, &0u128.to_be_bytes()),
+            None => update_field(&mut ctx, &[]),
+        }
+
+        let mut digest = [0u8; 32];
+        digest.copy_from_slice(ctx.finish().as_ref());
+        digest
+    }
+
+    /// Sign the message with the node key, see `verify_source_signature`
+    pub fn sign(&mut self, signing_key: &SigningKey) {
+        let signature: Signature = signing_key.sign(&self.signing_digest());
+        self.signature = Some(signature.to_bytes().into());
+    }
+
+    /// Verify that the message was signed by the node named in `source`
+    ///
+    /// INTENTION: Reject messages whose `source` was forged. The peer ID is the
+    /// hex-encoded SEC1 public key of the sending node, so the key to verify
+    /// against is derived directly from the claimed source.
+    pub fn verify_source_signature(&self) -> Result<(), NetworkError> {
+        let invalid = || {
+            NetworkError::MessageError(ErrorCode::SignatureInvalid, "signature invalid".to_string())
+        };
+
+        let signature_bytes = self.signature.as_ref().ok_or_else(invalid)?;
+        let signature = Signature::from_slice(signature_bytes).map_err(|_| invalid())?;
+        let public_key = hex::decode(&self.source.public_key).map_err(|_| invalid())?;
+        let verifying_key = VerifyingKey::from_sec1_bytes(&public_key).map_err(|_| invalid())?;
+
+        verifying_key
+            .verify(&self.signing_digest(), &signature)
+            .map_err(|_| invalid())
+    }
+
+    /// Whether the message's expiry time has passed on the local clock
+    pub fn is_expired(&self) -> bool {
+        self.expires_at
+            .is_some_and(|expires_at| SystemTime::now() >= expires_at)
+    }
+
+    /// Fail with `ErrorCode::MessageExpired` if the message has expired
+    ///
+    /// INTENTION: Let transports drop stale messages, such as events whose
+    /// TTL ran out while a peer was slow or a relay held them, before they
+    /// reach the node.
+    pub fn check_expiry(&self) -> Result<(), NetworkError> {
+        if self.is_expired() {
+            return Err(NetworkError::MessageError(
+                ErrorCode::MessageExpired,
+                "message expired".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Record that `relay` is forwarding the message
+    ///
+    /// INTENTION: Stop messages from circulating forever when routing tables
+    /// are wrong. Fails once the hop count would exceed `max_hops`.
+    pub fn record_hop(&mut self, relay: PeerId, max_hops: u8) -> Result<(), NetworkError> {
+        if self.hop_count >= max_hops {
+            return Err(NetworkError::MessageError(
+                ErrorCode::MaxHopsExceeded,
+                "max hops exceeded".to_string(),
+            ));
+        }
+        self.hop_count += 1;
+        self.visited_peers.push(relay);
+        Ok(())
+    }
+
+    /// Check whether the message already passed through `peer`
+    pub fn has_visited(&self, peer: &PeerId) -> bool {
+        self.visited_peers.contains(peer)
+    }
+}
+
+/// Serde helpers for the fixed-size message signature
+///
+/// serde only implements (de)serialization for arrays up to 32 elements,
+/// so the signature travels as an optional byte sequence.
+mod signature_bytes {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S>(signature: &Option<[u8; 64]>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        signature
+            .as_ref()
+            .map(|s| s.as_slice())
+            .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<[u8; 64]>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        match Option::<Vec<u8>>::deserialize(deserializer)? {
+            Some(bytes) => {
+                let len = bytes.len();
+                let signature: [u8; 64] = bytes.try_into().map_err(|_| {
+                    serde::de::Error::custom(format!("expected 64-byte signature, got {len}"))
+                })?;
+                Ok(Some(signature))
+            }
+            None => Ok(None),
+        }
+    }
 }
 
 /// Handler function type for incoming network messages
@@ -256,9 +574,36 @@ pub type MessageHandler = Box<dyn Fn(NetworkMessage) -> Result<()> + Send + Sync
 pub type MessageCallback =
     Arc<dyn Fn(NetworkMessage) -> BoxFuture<'static, Result<()>> + Send + Sync>;
 
+/// Kind of change in the connection to a peer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConnectionEventType {
+    /// The peer completed its first handshake with this transport
+    FirstConnect,
+    /// The peer completed a handshake again after being disconnected
+    Reconnect {
+        /// How long the peer was disconnected
+        disconnect_duration: Duration,
+    },
+    /// The connection to the peer was lost or closed
+    Disconnect,
+}
+
+/// Change in the connection to a peer, passed to a `ConnectionCallback`
+///
+/// INTENTION: Let services tell a reconnect from a first connect, so they can
+/// restore state kept on the remote side for the lifetime of a connection,
+/// e.g. re-subscribe to the peer's events.
+#[derive(Debug, Clone)]
+pub struct ConnectionEvent {
+    pub peer_id: PeerId,
+    pub event_type: ConnectionEventType,
+    /// Node info received in the handshake; `None` for disconnects
+    pub node_info: Option<NodeInfo>,
+}
+
 /// Callback type for connection status changes
 pub type ConnectionCallback =
-    Arc<dyn Fn(PeerId, bool, Option<NodeInfo>) -> BoxFuture<'static, Result<()>> + Send + Sync>;
+    Arc<dyn Fn(ConnectionEvent) -> BoxFuture<'static, Result<()>> + Send + Sync>;
 
 /// Network transport interface
 #[async_trait]
@@ -280,6 +625,14 @@ pub trait NetworkTransport: Send + Sync {
     /// Send a message to a remote node
     async fn send_message(&self, message: NetworkMessage) -> Result<(), NetworkError>;
 
+    /// Rewrite an outgoing message the way `send_message` would send it
+    ///
+    /// INTENTION: Let a wrapping transport authenticate the bytes the peer
+    /// receives. `send_message` sends messages carrying an auth tag
+    /// unchanged, so a message prepared and then tagged reaches the peer as
+    /// it was tagged. Transports that send messages unchanged do nothing.
+    async fn prepare_message(&self, _message: &mut NetworkMessage) {}
+
     /// connect to a discovered node
     ///
     /// Returns the NodeInfo of the connected peer after successful handshake
@@ -291,6 +644,13 @@ pub trait NetworkTransport: Send + Sync {
     /// Update the list of connected peers with the latest node info
     async fn update_peers(&self, node_info: NodeInfo) -> Result<(), NetworkError>;
 
+    /// Send the service changes since the last update to the connected peers
+    ///
+    /// INTENTION: Keep service updates small for nodes with many services.
+    /// Peers holding the base version of the node info apply the diff; the
+    /// transport also applies it to the node info it sends in handshakes.
+    async fn broadcast_service_diff(&self, diff: NodeInfoDiff) -> Result<(), NetworkError>;
+
     // /// Register a message handler for incoming messages
     // async fn register_message_handler(
     //     &self,
@@ -302,19 +662,231 @@ pub trait NetworkTransport: Send + Sync {
     /// INTENTION: Allow callers to subscribe to peer node info updates when they are received
     /// during handshakes. This is used by the Node to create RemoteService instances.
     async fn subscribe_to_peer_node_info(&self) -> tokio::sync::broadcast::Receiver<NodeInfo>;
+
+    /// Get a snapshot of the transport statistics
+    ///
+    /// Transports that do not collect metrics report zeroed statistics.
+    async fn transport_stats(&self) -> TransportStats {
+        TransportStats::default()
+    }
+
+    /// Move the connections to the current local address
+    ///
+    /// INTENTION: Keep connections alive across a network change, e.g. when
+    /// the OS reports a switch from Wi-Fi to ethernet. Transports without
+    /// connection migration do nothing.
+    async fn migrate_connections(&self) -> Result<(), NetworkError> {
+        Ok(())
+    }
+
+    /// Get the capabilities negotiated with a connected peer
+    ///
+    /// Transports without a capability exchange return `None`.
+    async fn peer_capabilities(&self, _peer_id: &PeerId) -> Option<NodeCapabilities> {
+        None
+    }
+}
+
+/// Stable numeric codes identifying the cause of a `NetworkError`
+///
+/// INTENTION: Let callers (and remote nodes) tell retryable failures from
+/// fatal ones without parsing messages. Codes are part of the public API and
+/// never change meaning; the thousands digit gives the category:
+///
+/// | Code | Meaning |
+/// |------|---------|
+/// | 1000 | Connection failed |
+/// | 1001 | Peer unreachable |
+/// | 1002 | Handshake failed |
+/// | 1003 | Not connected to the peer |
+/// | 1004 | Request authentication failed |
+/// | 1005 | No response before the request timeout |
+/// | 2000 | Invalid message |
+/// | 2001 | Message signature invalid |
+/// | 2002 | Message (de)serialization failed |
+/// | 2003 | Message too large |
+/// | 2004 | Stream read/write failed |
+/// | 2005 | The remote handler returned an error |
+/// | 2006 | Message exceeded the maximum hop count |
+/// | 2007 | Message expired before it was delivered |
+/// | 2008 | No service handles the requested path |
+/// | 3000 | Discovery failed |
+/// | 4000 | Transport failed |
+/// | 4001 | Transport not running |
+/// | 4002 | Circuit open for the target service |
+/// | 4003 | Rate limit exceeded for the target service |
+/// | 5000 | Invalid configuration |
+/// | 5001 | Missing certificates or private key |
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[repr(u16)]
+pub enum ErrorCode {
+    ConnectionFailed = 1000,
+    PeerUnreachable = 1001,
+    HandshakeFailed = 1002,
+    NotConnected = 1003,
+    AuthenticationFailed = 1004,
+    Timeout = 1005,
+    InvalidMessage = 2000,
+    SignatureInvalid = 2001,
+    SerializationFailed = 2002,
+    MessageTooLarge = 2003,
+    StreamFailed = 2004,
+    RemoteError = 2005,
+    MaxHopsExceeded = 2006,
+    MessageExpired = 2007,
+    ServiceNotFound = 2008,
+    DiscoveryFailed = 3000,
+    TransportFailed = 4000,
+    NotRunning = 4001,
+    CircuitOpen = 4002,
+    RateLimited = 4003,
+    InvalidConfiguration = 5000,
+    MissingCredentials = 5001,
+}
+
+impl ErrorCode {
+    /// Numeric value of the code
+    pub fn as_u16(self) -> u16 {
+        self as u16
+    }
+
+    /// Look up a code by its numeric value
+    pub fn from_u16(code: u16) -> Option<Self> {
+        let code = match code {
+            1000 => ErrorCode::ConnectionFailed,
+            1001 => ErrorCode::PeerUnreachable,
+            1002 => ErrorCode::HandshakeFailed,
+            1003 => ErrorCode::NotConnected,
+            1004 => ErrorCode::AuthenticationFailed,
+            1005 => ErrorCode::Timeout,
+            2000 => ErrorCode::InvalidMessage,
+            2001 => ErrorCode::SignatureInvalid,
+            2002 => ErrorCode::SerializationFailed,
+            2003 => ErrorCode::MessageTooLarge,
+            2004 => ErrorCode::StreamFailed,
+            2005 => ErrorCode::RemoteError,
+            2006 => ErrorCode::MaxHopsExceeded,
+            2007 => ErrorCode::MessageExpired,
+            2008 => ErrorCode::ServiceNotFound,
+            3000 => ErrorCode::DiscoveryFailed,
+            4000 => ErrorCode::TransportFailed,
+            4001 => ErrorCode::NotRunning,
+            4002 => ErrorCode::CircuitOpen,
+            4003 => ErrorCode::RateLimited,
+            5000 => ErrorCode::InvalidConfiguration,
+            5001 => ErrorCode::MissingCredentials,
+            _ => return None,
+        };
+        Some(code)
+    }
+
+    /// Whether the same operation may succeed if tried again later
+    pub fn is_retryable(self) -> bool {
+        matches!(
+            self,
+            ErrorCode::ConnectionFailed
+                | ErrorCode::PeerUnreachable
+                | ErrorCode::HandshakeFailed
+                | ErrorCode::NotConnected
+                | ErrorCode::Timeout
+                | ErrorCode::StreamFailed
+                | ErrorCode::DiscoveryFailed
+                | ErrorCode::CircuitOpen
+        )
+    }
+
+    /// Whether the error points at a setup or security problem that retrying cannot fix
+    pub fn is_fatal(self) -> bool {
+        matches!(
+            self,
+            ErrorCode::SignatureInvalid
+                | ErrorCode::AuthenticationFailed
+                | ErrorCode::InvalidConfiguration
+                | ErrorCode::MissingCredentials
+        )
+    }
+}
+
+impl fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_u16())
+    }
 }
 
 /// Error type for network operations
 #[derive(Error, Debug)]
 pub enum NetworkError {
-    #[error("Connection error: {0}")]
-    ConnectionError(String),
-    #[error("Message error: {0}")]
-    MessageError(String),
-    #[error("Discovery error: {0}")]
-    DiscoveryError(String),
-    #[error("Transport error: {0}")]
-    TransportError(String),
-    #[error("Configuration error: {0}")]
-    ConfigurationError(String),
+    #[error("Connection error [{0}]: {1}")]
+    ConnectionError(ErrorCode, String),
+    #[error("Message error [{0}]: {1}")]
+    MessageError(ErrorCode, String),
+    #[error("Discovery error [{0}]: {1}")]
+    DiscoveryError(ErrorCode, String),
+    #[error("Transport error [{0}]: {1}")]
+    TransportError(ErrorCode, String),
+    #[error("Configuration error [{0}]: {1}")]
+    ConfigurationError(ErrorCode, String),
+}
+
+impl NetworkError {
+    /// Name of the error variant, used to bucket errors in `TransportStats`
+    pub fn kind_name(&self) -> &'static str {
+        match self {
+            NetworkError::ConnectionError(..) => "ConnectionError",
+            NetworkError::MessageError(..) => "MessageError",
+            NetworkError::DiscoveryError(..) => "DiscoveryError",
+            NetworkError::TransportError(..) => "TransportError",
+            NetworkError::ConfigurationError(..) => "ConfigurationError",
+        }
+    }
+
+    /// Stable code identifying the cause of the error
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            NetworkError::ConnectionError(code, _)
+            | NetworkError::MessageError(code, _)
+            | NetworkError::DiscoveryError(code, _)
+            | NetworkError::TransportError(code, _)
+            | NetworkError::ConfigurationError(code, _) => *code,
+        }
+    }
+
+    /// Human-readable description of the error, without the variant prefix
+    pub fn message(&self) -> &str {
+        match self {
+            NetworkError::ConnectionError(_, message)
+            | NetworkError::MessageError(_, message)
+            | NetworkError::DiscoveryError(_, message)
+            | NetworkError::TransportError(_, message)
+            | NetworkError::ConfigurationError(_, message) => message,
+        }
+    }
+
+    /// Whether the failed operation may succeed if tried again later
+    pub fn is_retryable(&self) -> bool {
+        self.code().is_retryable()
+    }
+
+    /// Whether the error cannot be resolved by retrying
+    pub fn is_fatal(&self) -> bool {
+        self.code().is_fatal()
+    }
+
+    /// Rebuild an error from a code, e.g. one relayed by a remote node
+    ///
+    /// The variant is chosen from the code's category; unknown codes become
+    /// a `MessageError` with `ErrorCode::RemoteError`.
+    pub fn from_code(code: u16, message: impl Into<String>) -> Self {
+        let message = message.into();
+        match ErrorCode::from_u16(code) {
+            Some(code) => match code.as_u16() / 1000 {
+                1 => NetworkError::ConnectionError(code, message),
+                3 => NetworkError::DiscoveryError(code, message),
+                4 => NetworkError::TransportError(code, message),
+                5 => NetworkError::ConfigurationError(code, message),
+                _ => NetworkError::MessageError(code, message),
+            },
+            None => NetworkError::MessageError(ErrorCode::RemoteError, message),
+        }
+    }
 }
@@ -0,0 +1,200 @@
+// Multi Transport Implementation
+//
+// This module combines a network transport with a local IPC transport so a
+// Node can reach remote peers and co-located peers at the same time.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use runar_common::logging::Logger;
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+
+use super::{
+    NetworkError, NetworkMessage, NetworkTransport, NodeCapabilities, NodeInfo, NodeInfoDiff,
+    PeerId, PeerInfo, TransportStats, UNIX_ADDRESS_PREFIX,
+};
+
+/// Transport dispatching between a network transport and a local transport
+///
+/// INTENTION: Let one node serve remote peers over QUIC and co-located peers
+/// over Unix sockets without the rest of the Node knowing about either.
+/// Connections go to the local transport for `unix:` addresses and to the
+/// network transport otherwise; messages go to whichever transport holds a
+/// connection to the destination, preferring the local one. Peer node info
+/// from both transports is forwarded to a single channel.
+pub struct MultiTransport {
+    network: Box<dyn NetworkTransport>,
+    local: Box<dyn NetworkTransport>,
+    peer_node_info_sender: broadcast::Sender<NodeInfo>,
+    forwarders: std::sync::Mutex<Vec<JoinHandle<()>>>,
+    logger: Arc<Logger>,
+}
+
+impl MultiTransport {
+    /// Combine a network transport with a local transport
+    pub fn new(
+        network: Box<dyn NetworkTransport>,
+        local: Box<dyn NetworkTransport>,
+        logger: Arc<Logger>,
+    ) -> Self {
+        let (peer_node_info_sender, _) = broadcast::channel(32);
+        Self {
+            network,
+            local,
+            peer_node_info_sender,
+            forwarders: std::sync::Mutex::new(Vec::new()),
+            logger,
+        }
+    }
+
+    fn forward_node_info(&self, mut receiver: broadcast::Receiver<NodeInfo>) -> JoinHandle<()> {
+        let sender = self.peer_node_info_sender.clone();
+        let logger = self.logger.clone();
+        tokio::spawn(async move {
+            loop {
+                match receiver.recv().await {
+                    Ok(node_info) => {
+                        // No subscribers is not an error
+                        let _ = sender.send(node_info);
+                    }
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        logger.warn(format!(
+                            "Peer node info forwarder lagged, skipped {skipped} messages"
+                        ));
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        })
+    }
+
+    fn lock_forwarders(&self) -> std::sync::MutexGuard<'_, Vec<JoinHandle<()>>> {
+        self.forwarders.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[async_trait]
+impl NetworkTransport for MultiTransport {
+    async fn start(&self) -> Result<(), NetworkError> {
+        // Subscribe before starting so no handshake can be missed
+        let network_receiver = self.network.subscribe_to_peer_node_info().await;
+        let local_receiver = self.local.subscribe_to_peer_node_info().await;
+        {
+            let mut forwarders = self.lock_forwarders();
+            forwarders.push(self.forward_node_info(network_receiver));
+            forwarders.push(self.forward_node_info(local_receiver));
+        }
+
+        self.network.start().await?;
+        self.local.start().await
+    }
+
+    async fn stop(&self) -> Result<(), NetworkError> {
+        let local_result = self.local.stop().await;
+        let network_result = self.network.stop().await;
+        for forwarder in self.lock_forwarders().drain(..) {
+            forwarder.abort();
+        }
+        local_result.and(network_result)
+    }
+
+    async fn disconnect(&self, node_id: PeerId) -> Result<(), NetworkError> {
+        if self.local.is_connected(node_id.clone()).await {
+            self.local.disconnect(node_id).await
+        } else {
+            self.network.disconnect(node_id).await
+        }
+    }
+
+    async fn is_connected(&self, node_id: PeerId) -> bool {
+        self.local.is_connected(node_id.clone()).await || self.network.is_connected(node_id).await
+    }
+
+    async fn send_message(&self, message: NetworkMessage) -> Result<(), NetworkError> {
+        if self.local.is_connected(message.destination.clone()).await {
+            self.local.send_message(message).await
+        } else {
+            self.network.send_message(message).await
+        }
+    }
+
+    async fn prepare_message(&self, message: &mut NetworkMessage) {
+        if self.local.is_connected(message.destination.clone()).await {
+            self.local.prepare_message(message).await
+        } else {
+            self.network.prepare_message(message).await
+        }
+    }
+
+    async fn connect_peer(&self, discovery_msg: PeerInfo) -> Result<(), NetworkError> {
+        let (local_addresses, network_addresses): (Vec<String>, Vec<String>) = discovery_msg
+            .addresses
+            .iter()
+            .cloned()
+            .partition(|address| address.starts_with(UNIX_ADDRESS_PREFIX));
+
+        if !local_addresses.is_empty() {
+            let local_info = PeerInfo::new(discovery_msg.public_key.clone(), local_addresses);
+            match self.local.connect_peer(local_info).await {
+                Ok(()) => return Ok(()),
+                Err(e) if !network_addresses.is_empty() => self.logger.debug(format!(
+                    "Local connection to {} failed, falling back to the network: {e}",
+                    discovery_msg.public_key
+                )),
+                Err(e) => return Err(e),
+            }
+        }
+
+        let network_info = PeerInfo::new(discovery_msg.public_key, network_addresses);
+        self.network.connect_peer(network_info).await
+    }
+
+    fn get_local_address(&self) -> String {
+        self.network.get_local_address()
+    }
+
+    async fn update_peers(&self, node_info: NodeInfo) -> Result<(), NetworkError> {
+        let local_result = self.local.update_peers(node_info.clone()).await;
+        let network_result = self.network.update_peers(node_info).await;
+        local_result.and(network_result)
+    }
+
+    async fn broadcast_service_diff(&self, diff: NodeInfoDiff) -> Result<(), NetworkError> {
+        let local_result = self.local.broadcast_service_diff(diff.clone()).await;
+        let network_result = self.network.broadcast_service_diff(diff).await;
+        local_result.and(network_result)
+    }
+
+    async fn subscribe_to_peer_node_info(&self) -> broadcast::Receiver<NodeInfo> {
+        self.peer_node_info_sender.subscribe()
+    }
+
+    async fn transport_stats(&self) -> TransportStats {
+        let mut stats = self.network.transport_stats().await;
+        let local = self.local.transport_stats().await;
+        stats.total_connections_established += local.total_connections_established;
+        stats.total_connections_dropped += local.total_connections_dropped;
+        stats.current_connections += local.current_connections;
+        stats.total_bytes_sent += local.total_bytes_sent;
+        stats.total_bytes_received += local.total_bytes_received;
+        stats.total_messages_sent += local.total_messages_sent;
+        stats.total_messages_received += local.total_messages_received;
+        for (kind, count) in local.errors_by_kind {
+            *stats.errors_by_kind.entry(kind).or_insert(0) += count;
+        }
+        stats
+    }
+
+    async fn migrate_connections(&self) -> Result<(), NetworkError> {
+        // Local socket connections have no network address to migrate
+        self.network.migrate_connections().await
+    }
+
+    async fn peer_capabilities(&self, peer_id: &PeerId) -> Option<NodeCapabilities> {
+        match self.network.peer_capabilities(peer_id).await {
+            Some(capabilities) => Some(capabilities),
+            None => self.local.peer_capabilities(peer_id).await,
+        }
+    }
+}
@@ -0,0 +1,77 @@
+// Peer Filter
+//
+// This module decides which peers a node talks to, from an optional whitelist
+// and blacklist of peer IDs that can be replaced while the node runs.
+
+use std::collections::HashSet;
+use std::sync::RwLock;
+
+use super::{ErrorCode, NetworkError, PeerId};
+
+/// Whitelist and blacklist of the peers a node accepts
+///
+/// INTENTION: Keep a production node from talking to arbitrary nodes that
+/// discover it. Without a whitelist every peer not blacklisted is accepted;
+/// with one, only the listed peers are. The blacklist wins over the
+/// whitelist. The lists are shared by the transports, which refuse
+/// connections with rejected peers in both directions, and the peer
+/// registry, which does not record them.
+#[derive(Debug, Default)]
+pub struct PeerFilter {
+    whitelist: RwLock<Option<HashSet<PeerId>>>,
+    blacklist: RwLock<Option<HashSet<PeerId>>>,
+}
+
+impl PeerFilter {
+    pub fn new(whitelist: Option<HashSet<PeerId>>, blacklist: Option<HashSet<PeerId>>) -> Self {
+        Self {
+            whitelist: RwLock::new(whitelist),
+            blacklist: RwLock::new(blacklist),
+        }
+    }
+
+    /// Replace the whitelist; `None` accepts every peer not blacklisted
+    pub fn set_whitelist(&self, whitelist: Option<HashSet<PeerId>>) {
+        *self.whitelist.write().unwrap_or_else(|e| e.into_inner()) = whitelist;
+    }
+
+    /// Replace the blacklist
+    pub fn set_blacklist(&self, blacklist: Option<HashSet<PeerId>>) {
+        *self.blacklist.write().unwrap_or_else(|e| e.into_inner()) = blacklist;
+    }
+
+    /// Check that `peer` may connect, naming the list that rejects it
+    pub fn check(&self, peer: &PeerId) -> Result<(), NetworkError> {
+        let blacklisted = self
+            .blacklist
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .as_ref()
+            .is_some_and(|blacklist| blacklist.contains(peer));
+        if blacklisted {
+            return Err(NetworkError::ConfigurationError(
+                ErrorCode::InvalidConfiguration,
+                "peer blacklisted".to_string(),
+            ));
+        }
+
+        let whitelisted = self
+            .whitelist
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .as_ref()
+            .is_none_or(|whitelist| whitelist.contains(peer));
+        if !whitelisted {
+            return Err(NetworkError::ConfigurationError(
+                ErrorCode::InvalidConfiguration,
+                "peer not whitelisted".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Whether `peer` may connect
+    pub fn allows(&self, peer: &PeerId) -> bool {
+        self.check(peer).is_ok()
+    }
+}
@@ -2,15 +2,18 @@
 //
 // INTENTION: Maintain a registry of known peers in the network,
 // track their status, and provide lookup capabilities to find specific
-// peers based on identifiers or network.
+// peers based on identifiers or network. Changes are broadcast as PeerEvents
+// so components can react to peers joining or leaving instead of polling.
 
 use anyhow::Result;
 use std::collections::HashMap;
-use std::sync::RwLock;
+use std::sync::{Arc, RwLock};
 use std::time::{Duration, SystemTime};
+use tokio::sync::broadcast;
 
-use super::PeerId;
+use super::{NodeCapabilities, PeerFilter, PeerId};
 use crate::network::discovery::multicast_discovery::PeerInfo;
+use crate::network::discovery::NodeInfo;
 
 /// Status of a peer in the registry
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -41,6 +44,12 @@ pub struct PeerEntry {
     pub connection_attempts: u32,
     /// Additional metadata about the peer (arbitrary key-value pairs)
     pub metadata: HashMap<String, String>,
+    /// Node information received in the handshake, once connected
+    pub node_info: Option<NodeInfo>,
+    /// Capabilities negotiated in the handshake, once connected
+    pub capabilities: Option<NodeCapabilities>,
+    /// Paths of the services the peer hosts, from its node information
+    pub services: Vec<String>,
 }
 
 impl PeerEntry {
@@ -53,6 +62,9 @@ impl PeerEntry {
             status_changed: SystemTime::now(),
             connection_attempts: 0,
             metadata: HashMap::new(),
+            node_info: None,
+            capabilities: None,
+            services: Vec::new(),
         }
     }
 
@@ -68,6 +80,28 @@ impl PeerEntry {
     }
 }
 
+/// A change to the peer registry
+///
+/// INTENTION: Let components such as the Node's remote service management
+/// react to peers joining or leaving. Receivers obtained with
+/// `PeerRegistry::subscribe_to_changes` get every event sent after they
+/// subscribed.
+// Connected carries the entry by value so subscribers can match on it directly
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug, Clone)]
+pub enum PeerEvent {
+    /// A peer was added, or completed a handshake
+    Connected(PeerEntry),
+    /// A peer was removed from the registry
+    Disconnected(PeerId),
+    /// A peer's status changed
+    StatusChanged {
+        peer: PeerId,
+        old: PeerStatus,
+        new: PeerStatus,
+    },
+}
+
 /// Options for the peer registry
 #[derive(Debug, Clone)]
 pub struct PeerRegistryOptions {
@@ -77,6 +111,8 @@ pub struct PeerRegistryOptions {
     pub peer_ttl: Duration,
     /// How often to run cleanup of stale peers
     pub cleanup_interval: Duration,
+    /// Number of events buffered for each change subscriber before it lags
+    pub event_channel_capacity: usize,
 }
 
 impl Default for PeerRegistryOptions {
@@ -85,6 +121,7 @@ impl Default for PeerRegistryOptions {
             max_peers_per_network: 100,
             peer_ttl: Duration::from_secs(3600),        // 1 hour
             cleanup_interval: Duration::from_secs(300), // 5 minutes
+            event_channel_capacity: 64,
         }
     }
 }
@@ -93,8 +130,14 @@ impl Default for PeerRegistryOptions {
 pub struct PeerRegistry {
     /// Peers indexed by their peer_public_key
     peers: RwLock<HashMap<String, PeerEntry>>,
+    /// Peers hosting each service path, always locked after `peers`
+    service_index: RwLock<HashMap<String, Vec<PeerId>>>,
     /// Configuration options
     options: PeerRegistryOptions,
+    /// Sender side of the change notification channel
+    event_sender: broadcast::Sender<PeerEvent>,
+    /// Peers that are not recorded, when set
+    peer_filter: Option<Arc<PeerFilter>>,
 }
 
 impl Default for PeerRegistry {
@@ -111,17 +154,74 @@ impl PeerRegistry {
 
     /// Create a new peer registry with custom options
     pub fn with_options(options: PeerRegistryOptions) -> Self {
+        let (event_sender, _) = broadcast::channel(options.event_channel_capacity.max(1));
         Self {
             peers: RwLock::new(HashMap::new()),
+            service_index: RwLock::new(HashMap::new()),
             // network_index: RwLock::new(HashMap::new()),
             options,
+            event_sender,
+            peer_filter: None,
         }
     }
 
+    /// Leave out the peers rejected by `peer_filter`
+    ///
+    /// Adding a rejected peer, or recording its node information, is a no-op.
+    pub fn with_peer_filter(mut self, peer_filter: Arc<PeerFilter>) -> Self {
+        self.peer_filter = Some(peer_filter);
+        self
+    }
+
+    /// Whether the peer filter, if any, rejects `peer_id`
+    fn is_rejected(&self, peer_id: &PeerId) -> bool {
+        self.peer_filter
+            .as_ref()
+            .is_some_and(|filter| !filter.allows(peer_id))
+    }
+
+    /// Subscribe to changes in the registry
+    ///
+    /// INTENTION: Allow reacting to peers joining or leaving without polling.
+    /// A receiver that falls more than `event_channel_capacity` events behind
+    /// gets a `Lagged` error and skips the oldest events.
+    pub fn subscribe_to_changes(&self) -> broadcast::Receiver<PeerEvent> {
+        self.event_sender.subscribe()
+    }
+
+    /// Move `peer_id` from the index entries of `old` services to those of `new` ones
+    fn reindex_services(&self, peer_id: &PeerId, old: &[String], new: &[String]) {
+        let mut index = self.service_index.write().unwrap();
+        for service_path in old.iter().filter(|path| !new.contains(path)) {
+            if let Some(peer_ids) = index.get_mut(service_path) {
+                peer_ids.retain(|id| id != peer_id);
+                if peer_ids.is_empty() {
+                    index.remove(service_path);
+                }
+            }
+        }
+        for service_path in new.iter().filter(|path| !old.contains(path)) {
+            index
+                .entry(service_path.clone())
+                .or_default()
+                .push(peer_id.clone());
+        }
+    }
+
+    /// Send an event to the current subscribers, if any
+    fn notify(&self, event: PeerEvent) {
+        let _ = self.event_sender.send(event);
+    }
+
     /// Add a peer to the registry
+    ///
+    /// Sends `PeerEvent::Connected` when the peer was not known yet.
     pub fn add_peer(&self, discovery_msg: PeerInfo) -> Result<()> {
         // Use public key from the peer_id as the unique identifier
         let peer_public_key = discovery_msg.public_key.clone();
+        if self.is_rejected(&PeerId::new(peer_public_key.clone())) {
+            return Ok(());
+        }
 
         // If we've passed the limit checks, now we can modify the data structures
         let mut peers = self.peers.write().unwrap();
@@ -134,18 +234,104 @@ impl PeerRegistry {
         } else {
             // Create new peer entry with all its networks
             let peer_entry = PeerEntry::new(discovery_msg);
-            peers.insert(peer_public_key, peer_entry);
+            peers.insert(peer_public_key, peer_entry.clone());
+            drop(peers);
+            self.notify(PeerEvent::Connected(peer_entry));
         }
 
         Ok(())
     }
 
+    /// Record the node information a peer sent in its handshake
+    ///
+    /// INTENTION: Track connected peers together with their capabilities.
+    /// The peer is added if needed and marked as connected, keeping the
+    /// newest version of its node information. Sends `PeerEvent::Connected`
+    /// for every handshake, and `PeerEvent::StatusChanged` when the peer's
+    /// status changes.
+    pub fn add_peer_node_info(&self, node_info: NodeInfo) -> Result<()> {
+        self.add_peer_node_info_with_capabilities(node_info, None)
+    }
+
+    /// Record a peer's handshake node information and negotiated capabilities
+    ///
+    /// Same as `add_peer_node_info`, also storing the capabilities negotiated
+    /// with the peer. Without capabilities the previous ones are kept.
+    pub fn add_peer_node_info_with_capabilities(
+        &self,
+        node_info: NodeInfo,
+        capabilities: Option<NodeCapabilities>,
+    ) -> Result<()> {
+        let peer_id = node_info.peer_id.clone();
+        if self.is_rejected(&peer_id) {
+            return Ok(());
+        }
+        let mut peers = self.peers.write().unwrap();
+        let entry = peers.entry(peer_id.public_key.clone()).or_insert_with(|| {
+            let mut entry = PeerEntry::new(PeerInfo::new(peer_id.public_key.clone(), Vec::new()));
+            // A new entry has no previous status to report
+            entry.status = PeerStatus::Connected;
+            entry
+        });
+
+        entry.last_seen = SystemTime::now();
+        if !node_info.addresses.is_empty() {
+            entry.peer_info.addresses = node_info.addresses.clone();
+        }
+        let old_status = entry.status.clone();
+        if old_status != PeerStatus::Connected {
+            entry.set_status(PeerStatus::Connected);
+        }
+        let is_newer = entry
+            .node_info
+            .as_ref()
+            .is_none_or(|known| node_info.version >= known.version);
+        if is_newer {
+            let mut services: Vec<String> = node_info
+                .services
+                .iter()
+                .map(|service| service.service_path.clone())
+                .collect();
+            services.sort();
+            services.dedup();
+            self.reindex_services(&peer_id, &entry.services, &services);
+            entry.services = services;
+            entry.node_info = Some(node_info);
+        }
+        if capabilities.is_some() {
+            entry.capabilities = capabilities;
+        }
+        let entry = entry.clone();
+        drop(peers);
+
+        if old_status != PeerStatus::Connected {
+            self.notify(PeerEvent::StatusChanged {
+                peer: peer_id,
+                old: old_status,
+                new: PeerStatus::Connected,
+            });
+        }
+        self.notify(PeerEvent::Connected(entry));
+        Ok(())
+    }
+
     /// Update a peer's status
+    ///
+    /// Sends `PeerEvent::StatusChanged` when the status differs from the current one.
     pub fn update_peer_status(&self, peer_id: &PeerId, status: PeerStatus) -> Result<()> {
         let mut peers = self.peers.write().unwrap();
 
         if let Some(peer) = peers.get_mut(&peer_id.public_key) {
-            peer.set_status(status);
+            let old = peer.status.clone();
+            peer.set_status(status.clone());
+            drop(peers);
+            if old != status {
+                self.notify(PeerEvent::StatusChanged {
+                    peer: peer_id.clone(),
+                    old,
+                    new: status,
+                });
+            }
             Ok(())
         } else {
             anyhow::bail!("Peer not found: {}", peer_id)
@@ -183,6 +369,25 @@ impl PeerRegistry {
             .collect()
     }
 
+    /// Find the peers hosting the service at `service_path`
+    ///
+    /// INTENTION: Route requests to a remote service without scanning every
+    /// peer. Uses the index of the services peers advertised in their node
+    /// information.
+    pub fn find_peers_by_service(&self, service_path: &str) -> Vec<PeerEntry> {
+        let peers = self.peers.read().unwrap();
+        let index = self.service_index.read().unwrap();
+        index
+            .get(service_path)
+            .map(|peer_ids| {
+                peer_ids
+                    .iter()
+                    .filter_map(|peer_id| peers.get(&peer_id.public_key).cloned())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
     /// Get all known peers
     pub fn get_all_peers(&self) -> Vec<PeerEntry> {
         let peers = self.peers.read().unwrap();
@@ -190,11 +395,16 @@ impl PeerRegistry {
     }
 
     /// Remove a peer from the registry
+    ///
+    /// Sends `PeerEvent::Disconnected` for the removed peer.
     pub fn remove_peer(&self, id: &PeerId) -> Result<()> {
         let mut peers = self.peers.write().unwrap();
 
         // Remove peer
-        if peers.remove(&id.public_key).is_some() {
+        if let Some(entry) = peers.remove(&id.public_key) {
+            self.reindex_services(id, &entry.services, &[]);
+            drop(peers);
+            self.notify(PeerEvent::Disconnected(id.clone()));
             Ok(())
         } else {
             anyhow::bail!("Peer not found: {}", id.public_key)
@@ -228,8 +438,12 @@ impl PeerRegistry {
 
             for key in stale_keys {
                 // Remove peer
-                peers.remove(&key);
-                removed_count += 1;
+                if let Some(entry) = peers.remove(&key) {
+                    let peer_id = PeerId::new(key);
+                    self.reindex_services(&peer_id, &entry.services, &[]);
+                    removed_count += 1;
+                    self.notify(PeerEvent::Disconnected(peer_id));
+                }
             }
         }
 
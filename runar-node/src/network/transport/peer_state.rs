@@ -3,13 +3,22 @@
 //! INTENTION: Tracks state, manages stream pools, and handles connection health for a single peer.
 
 use crate::network::discovery::NodeInfo;
-use crate::network::transport::{NetworkError, PeerId, StreamPool};
+use crate::network::transport::{
+    ErrorCode, NetworkError, NetworkMessage, NodeCapabilities, PeerId, StreamPool,
+    StreamPoolOptions,
+};
 use runar_common::logging::Logger;
+use std::collections::HashMap;
 use std::fmt;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex as StdMutex};
+use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
 use tokio::sync::{mpsc, Mutex};
 
+/// How long a request exchanged with a peer may wait for its response before
+/// it is assumed lost and no longer keeps the peer busy
+pub const IN_FLIGHT_REQUEST_EXPIRY: Duration = Duration::from_secs(120);
+
 /// PeerState - Manages the state of a connection to a remote peer
 ///
 /// INTENTION: This component tracks the state of individual peer connections,
@@ -36,6 +45,11 @@ pub struct PeerState {
     pub status_rx: Mutex<mpsc::Receiver<bool>>,
     /// Optional node information received during handshake
     pub node_info: RwLock<Option<NodeInfo>>,
+    /// Capabilities negotiated with the peer during handshake
+    pub capabilities: RwLock<Option<NodeCapabilities>>,
+    /// Requests sent to or received from the peer awaiting their response,
+    /// by correlation id, with when they were seen
+    in_flight: StdMutex<HashMap<String, Instant>>,
 }
 
 impl PeerState {
@@ -46,22 +60,58 @@ impl PeerState {
         peer_id: PeerId,
         address: String,
         max_idle_streams: usize,
+        stream_pool_options: StreamPoolOptions,
         logger: Arc<Logger>,
     ) -> Self {
         let (status_tx, status_rx) = mpsc::channel(10);
         Self {
             peer_id,
             address,
-            stream_pool: StreamPool::new(max_idle_streams, logger.clone()),
+            stream_pool: StreamPool::with_options(
+                max_idle_streams,
+                stream_pool_options,
+                logger.clone(),
+            ),
             connection: Mutex::new(None),
             last_activity: Mutex::new(std::time::Instant::now()),
             logger,
             status_tx,
             status_rx: Mutex::new(status_rx),
             node_info: RwLock::new(None),
+            capabilities: RwLock::new(None),
+            in_flight: StdMutex::new(HashMap::new()),
         }
     }
 
+    /// Track the requests exchanged with this peer from a sent or received message
+    ///
+    /// INTENTION: Know when the connection still carries requests or response
+    /// streams, so that it is not closed for being idle or evicted under them.
+    /// A request stays in flight until its response, error or end of stream.
+    pub fn track_message(&self, message: &NetworkMessage) {
+        let mut in_flight = self.in_flight.lock().unwrap_or_else(|e| e.into_inner());
+        for payload in &message.payloads {
+            match message.message_type.as_str() {
+                "Request" => {
+                    in_flight.insert(payload.correlation_id.clone(), Instant::now());
+                }
+                "Response" | "Error" | "StreamEnd" => {
+                    in_flight.remove(&payload.correlation_id);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Whether requests exchanged with this peer still await their response
+    ///
+    /// Requests older than `IN_FLIGHT_REQUEST_EXPIRY` are forgotten.
+    pub fn has_in_flight_requests(&self) -> bool {
+        let mut in_flight = self.in_flight.lock().unwrap_or_else(|e| e.into_inner());
+        in_flight.retain(|_, seen_at| seen_at.elapsed() < IN_FLIGHT_REQUEST_EXPIRY);
+        !in_flight.is_empty()
+    }
+
     /// Set the node info for this peer
     ///
     /// INTENTION: Store the node information received during handshake.
@@ -73,6 +123,29 @@ impl PeerState {
             peer_id = self.peer_id
         ));
     }
+
+    /// Set the capabilities negotiated with this peer
+    ///
+    /// INTENTION: Keep the outcome of the capability exchange for the lifetime
+    /// of the connection.
+    pub async fn set_capabilities(&self, capabilities: NodeCapabilities) {
+        *self.capabilities.write().await = Some(capabilities);
+    }
+
+    /// Get the capabilities negotiated with this peer, if the handshake completed
+    pub async fn get_capabilities(&self) -> Option<NodeCapabilities> {
+        self.capabilities.read().await.clone()
+    }
+
+    /// Get the largest message both sides accept, if the handshake completed
+    pub async fn max_message_size(&self) -> Option<usize> {
+        self.capabilities
+            .read()
+            .await
+            .as_ref()
+            .map(|capabilities| capabilities.max_message_size)
+    }
+
     /// Set the connection for this peer
     ///
     /// INTENTION: Establish a connection to the peer and update the state.
@@ -126,14 +199,16 @@ impl PeerState {
 
     /// Get a stream for sending messages to this peer
     ///
-    /// INTENTION: Obtain a QUIC stream for sending data to this peer.
+    /// INTENTION: Obtain a QUIC stream for sending data to this peer. Waits while
+    /// the stream pool's maximum number of streams is in use; the stream must be
+    /// handed back with `return_stream`, or its slot released with `release_stream`.
     pub async fn get_send_stream(&self) -> Result<quinn::SendStream, NetworkError> {
         self.logger.debug(format!(
             "🔄 [PeerState] Checking for idle stream for peer {}",
             self.peer_id
         ));
 
-        if let Some(stream) = self.stream_pool.get_idle_stream().await {
+        if let Some(stream) = self.stream_pool.acquire().await {
             self.logger.debug(format!(
                 "✅ [PeerState] Found idle stream for peer {}",
                 self.peer_id
@@ -162,6 +237,7 @@ impl PeerState {
                     Ok(send_stream)
                 }
                 Err(e) => {
+                    self.stream_pool.release();
                     self.logger.error(format!(
                         "❌ [PeerState] Failed to open stream to peer {}: {}",
                         self.peer_id, e
@@ -173,17 +249,20 @@ impl PeerState {
                         self.peer_id, e
                     ));
 
-                    Err(NetworkError::ConnectionError(format!(
-                        "Failed to open stream: {e}"
-                    )))
+                    Err(NetworkError::ConnectionError(
+                        ErrorCode::StreamFailed,
+                        format!("Failed to open stream: {e}"),
+                    ))
                 }
             }
         } else {
+            self.stream_pool.release();
             self.logger.error(format!(
                 "❌ [PeerState] No connection available for peer {} - cannot create stream",
                 self.peer_id
             ));
             Err(NetworkError::ConnectionError(
+                ErrorCode::NotConnected,
                 "Not connected to peer".to_string(),
             ))
         }
@@ -196,6 +275,14 @@ impl PeerState {
         self.stream_pool.return_stream(stream).await
     }
 
+    /// Give up a stream obtained with `get_send_stream` without reusing it
+    ///
+    /// INTENTION: Free the stream's slot in the pool once the stream has been
+    /// finished or failed.
+    pub fn release_stream(&self) {
+        self.stream_pool.release();
+    }
+
     /// Get a clone of the connection for direct use
     ///
     /// INTENTION: Provide access to the connection for operations that need it directly
@@ -0,0 +1,323 @@
+// Proxy Tunnels
+//
+// This module carries QUIC through HTTP CONNECT and SOCKS5 proxies for
+// networks that block outbound UDP. A TCP tunnel is opened through the proxy
+// to the peer and QUIC datagrams travel over it, each prefixed with its
+// length. The peer accepts such tunnels on a TCP listener bound to its QUIC
+// port and feeds the datagrams into the same QUIC endpoint.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{self, IoSliceMut};
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex as StdMutex};
+use std::task::{Context, Poll};
+
+use base64::Engine;
+use quinn::udp::{RecvMeta, Transmit};
+use quinn::{AsyncUdpSocket, UdpPoller};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::sync::mpsc;
+
+use super::{ErrorCode, NetworkError};
+
+/// Longest proxy response header accepted for a CONNECT request
+const MAX_CONNECT_RESPONSE: usize = 8 * 1024;
+
+/// Protocol spoken with the proxy
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProxyKind {
+    /// HTTP proxy supporting the CONNECT method
+    HttpConnect,
+    /// SOCKS5 proxy
+    Socks5,
+}
+
+impl fmt::Display for ProxyKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProxyKind::HttpConnect => write!(f, "HTTP CONNECT"),
+            ProxyKind::Socks5 => write!(f, "SOCKS5"),
+        }
+    }
+}
+
+/// Proxy that outgoing QUIC connections are tunneled through
+///
+/// INTENTION: Let nodes on networks that only allow traffic through a proxy
+/// still reach their peers. Tunneled QUIC runs over TCP and loses the
+/// benefits of UDP, so this is a fallback mode.
+#[derive(Clone)]
+pub struct ProxyConfig {
+    /// Protocol spoken with the proxy
+    pub kind: ProxyKind,
+    /// Address of the proxy
+    pub addr: SocketAddr,
+    /// Username and password for the proxy, if it requires them
+    pub credentials: Option<(String, String)>,
+}
+
+impl ProxyConfig {
+    /// Create a configuration for a proxy that needs no credentials
+    pub fn new(kind: ProxyKind, addr: SocketAddr) -> Self {
+        Self {
+            kind,
+            addr,
+            credentials: None,
+        }
+    }
+
+    /// Authenticate to the proxy with a username and password
+    pub fn with_credentials(
+        mut self,
+        username: impl Into<String>,
+        password: impl Into<String>,
+    ) -> Self {
+        self.credentials = Some((username.into(), password.into()));
+        self
+    }
+}
+
+// The password must never end up in logs
+impl fmt::Debug for ProxyConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ProxyConfig")
+            .field("kind", &self.kind)
+            .field("addr", &self.addr)
+            .field(
+                "credentials",
+                &self
+                    .credentials
+                    .as_ref()
+                    .map(|(username, _)| (username, "<redacted>")),
+            )
+            .finish()
+    }
+}
+
+fn proxy_error(proxy: &ProxyConfig, target: SocketAddr, reason: impl fmt::Display) -> NetworkError {
+    NetworkError::ConnectionError(
+        ErrorCode::ConnectionFailed,
+        format!(
+            "Failed to tunnel to {target} through {} proxy {}: {reason}",
+            proxy.kind, proxy.addr
+        ),
+    )
+}
+
+/// Open a TCP tunnel to `target` through the proxy
+pub async fn open_tunnel(
+    proxy: &ProxyConfig,
+    target: SocketAddr,
+) -> Result<TcpStream, NetworkError> {
+    match proxy.kind {
+        ProxyKind::HttpConnect => http_connect(proxy, target).await,
+        ProxyKind::Socks5 => {
+            let stream = match &proxy.credentials {
+                Some((username, password)) => {
+                    tokio_socks::tcp::Socks5Stream::connect_with_password(
+                        proxy.addr, target, username, password,
+                    )
+                    .await
+                }
+                None => tokio_socks::tcp::Socks5Stream::connect(proxy.addr, target).await,
+            }
+            .map_err(|e| proxy_error(proxy, target, e))?;
+            Ok(stream.into_inner())
+        }
+    }
+}
+
+async fn http_connect(proxy: &ProxyConfig, target: SocketAddr) -> Result<TcpStream, NetworkError> {
+    let mut stream = TcpStream::connect(proxy.addr)
+        .await
+        .map_err(|e| proxy_error(proxy, target, e))?;
+
+    let mut request = format!("CONNECT {target} HTTP/1.1\r\nHost: {target}\r\n");
+    if let Some((username, password)) = &proxy.credentials {
+        let token =
+            base64::engine::general_purpose::STANDARD.encode(format!("{username}:{password}"));
+        request.push_str(&format!("Proxy-Authorization: Basic {token}\r\n"));
+    }
+    request.push_str("\r\n");
+    stream
+        .write_all(request.as_bytes())
+        .await
+        .map_err(|e| proxy_error(proxy, target, e))?;
+
+    // Read the response header byte by byte, so nothing after it is consumed
+    let mut response = Vec::new();
+    while !response.ends_with(b"\r\n\r\n") {
+        if response.len() >= MAX_CONNECT_RESPONSE {
+            return Err(proxy_error(proxy, target, "response header too long"));
+        }
+        let byte = stream
+            .read_u8()
+            .await
+            .map_err(|e| proxy_error(proxy, target, e))?;
+        response.push(byte);
+    }
+
+    let response = String::from_utf8_lossy(&response);
+    let status_line = response.lines().next().unwrap_or_default();
+    let status = status_line.split_whitespace().nth(1).unwrap_or_default();
+    if !status.starts_with('2') {
+        return Err(proxy_error(
+            proxy,
+            target,
+            format!("proxy answered '{status_line}'"),
+        ));
+    }
+    Ok(stream)
+}
+
+/// QUIC socket that also sends and receives datagrams over TCP tunnels
+///
+/// INTENTION: Serve tunneled and direct peers from a single QUIC endpoint.
+/// Datagrams to an address with an attached tunnel are framed onto it; all
+/// others go out over the UDP socket as usual.
+pub struct TunnelSocket {
+    udp: Arc<dyn AsyncUdpSocket>,
+    tunnels: StdMutex<HashMap<SocketAddr, mpsc::UnboundedSender<Vec<u8>>>>,
+    incoming_sender: mpsc::UnboundedSender<(SocketAddr, Vec<u8>)>,
+    incoming: StdMutex<mpsc::UnboundedReceiver<(SocketAddr, Vec<u8>)>>,
+}
+
+impl TunnelSocket {
+    /// Wrap the UDP socket of a QUIC endpoint
+    pub fn new(udp: Arc<dyn AsyncUdpSocket>) -> Self {
+        let (incoming_sender, incoming) = mpsc::unbounded_channel();
+        Self {
+            udp,
+            tunnels: StdMutex::new(HashMap::new()),
+            incoming_sender,
+            incoming: StdMutex::new(incoming),
+        }
+    }
+
+    /// Route datagrams for `remote` through `stream`
+    ///
+    /// Replaces any tunnel attached for the same address. The tunnel is
+    /// detached again once the stream closes.
+    pub fn attach(self: &Arc<Self>, remote: SocketAddr, stream: TcpStream) {
+        let _ = stream.set_nodelay(true);
+        let (mut reader, mut writer) = stream.into_split();
+        let (sender, mut outgoing) = mpsc::unbounded_channel::<Vec<u8>>();
+        self.tunnels
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(remote, sender.clone());
+
+        tokio::spawn(async move {
+            while let Some(datagram) = outgoing.recv().await {
+                let frame_len = (datagram.len() as u16).to_be_bytes();
+                if writer.write_all(&frame_len).await.is_err()
+                    || writer.write_all(&datagram).await.is_err()
+                {
+                    break;
+                }
+            }
+        });
+
+        let socket = Arc::clone(self);
+        tokio::spawn(async move {
+            while let Ok(len) = reader.read_u16().await {
+                let mut datagram = vec![0u8; len as usize];
+                if reader.read_exact(&mut datagram).await.is_err()
+                    || socket.incoming_sender.send((remote, datagram)).is_err()
+                {
+                    break;
+                }
+            }
+            let mut tunnels = socket.tunnels.lock().unwrap_or_else(|e| e.into_inner());
+            if tunnels
+                .get(&remote)
+                .is_some_and(|s| s.same_channel(&sender))
+            {
+                tunnels.remove(&remote);
+            }
+        });
+    }
+
+    /// Whether datagrams for `remote` go through a tunnel
+    pub fn has_tunnel(&self, remote: &SocketAddr) -> bool {
+        self.tunnels
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .contains_key(remote)
+    }
+}
+
+impl fmt::Debug for TunnelSocket {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let tunnels = self.tunnels.lock().unwrap_or_else(|e| e.into_inner());
+        f.debug_struct("TunnelSocket")
+            .field("udp", &self.udp)
+            .field("tunnels", &tunnels.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+impl AsyncUdpSocket for TunnelSocket {
+    fn create_io_poller(self: Arc<Self>) -> Pin<Box<dyn UdpPoller>> {
+        self.udp.clone().create_io_poller()
+    }
+
+    fn try_send(&self, transmit: &Transmit) -> io::Result<()> {
+        let tunnel = self
+            .tunnels
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(&transmit.destination)
+            .cloned();
+        match tunnel {
+            // max_transmit_segments is 1, so each transmit is a single datagram.
+            // A closed tunnel loses the datagram, as UDP would.
+            Some(tunnel) => {
+                let _ = tunnel.send(transmit.contents.to_vec());
+                Ok(())
+            }
+            None => self.udp.try_send(transmit),
+        }
+    }
+
+    fn poll_recv(
+        &self,
+        cx: &mut Context,
+        bufs: &mut [IoSliceMut<'_>],
+        meta: &mut [RecvMeta],
+    ) -> Poll<io::Result<usize>> {
+        let tunneled = self
+            .incoming
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .poll_recv(cx);
+        if let Poll::Ready(Some((addr, datagram))) = tunneled {
+            let len = datagram.len().min(bufs[0].len());
+            bufs[0][..len].copy_from_slice(&datagram[..len]);
+            meta[0] = RecvMeta {
+                addr,
+                len,
+                stride: len,
+                ecn: None,
+                dst_ip: None,
+            };
+            return Poll::Ready(Ok(1));
+        }
+        self.udp.poll_recv(cx, bufs, meta)
+    }
+
+    fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.udp.local_addr()
+    }
+
+    fn max_receive_segments(&self) -> usize {
+        self.udp.max_receive_segments()
+    }
+
+    fn may_fragment(&self) -> bool {
+        self.udp.may_fragment()
+    }
+}
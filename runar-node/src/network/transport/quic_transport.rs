@@ -8,20 +8,24 @@
 //! - ConnectionPool: Managing active connections and their lifecycle
 //! - StreamPool: Managing stream reuse and resource cleanup
 
+use std::collections::HashMap;
 use std::fmt;
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::{Arc, RwLock as StdRwLock};
 use std::time::Duration;
 use std::time::SystemTime;
 
 use async_trait::async_trait;
 use bincode;
+use dashmap::DashMap;
 use quinn::{self, Endpoint};
 use quinn::{ClientConfig, ServerConfig};
 // Using Quinn 0.11.x API - no need for proto imports
 use runar_common::logging::Logger;
-use tokio::sync::Mutex;
+use runar_common::types::SerializerRegistry;
+use socket2::{Domain, Protocol, Socket, Type};
+use tokio::sync::{mpsc, Mutex, RwLock};
 use tokio::task::JoinHandle;
 
 // Import rustls explicitly - these types need clear namespacing to avoid conflicts with quinn's types
@@ -29,13 +33,26 @@ use tokio::task::JoinHandle;
 use rustls;
 use rustls_pki_types::{CertificateDer, PrivateKeyDer, ServerName};
 
+use p256::ecdsa::SigningKey;
+
+use super::capabilities::{
+    check_payload_format, check_protocol_version, DEFAULT_MAX_MESSAGE_SIZE,
+    DEFAULT_MAX_VERSION_SKEW,
+};
+use super::chunking::{
+    self, ChunkReassembler, DEFAULT_CHUNK_TIMEOUT, DEFAULT_MAX_REASSEMBLED_SIZE,
+};
+use super::message_signing_key;
+use super::proxy::{self, ProxyConfig, TunnelSocket};
 use super::{
-    ConnectionPool, NetworkError, NetworkMessage, NetworkMessagePayloadItem, NetworkTransport,
-    PeerId, PeerState,
+    ConnectionCallback, ConnectionEvent, ConnectionEventType, ConnectionPool,
+    ConnectionPoolOptions, ConnectionPoolStats, ErrorCode, NetworkError, NetworkMessage,
+    NetworkMessagePayloadItem, NetworkTransport, NodeCapabilities, PeerFilter, PeerId, PeerState,
+    StreamPoolOptions, TransportStats,
 };
 // Import PeerInfo and NodeInfo consistently with the module structure
 use crate::network::discovery::multicast_discovery::PeerInfo;
-use crate::network::discovery::NodeInfo;
+use crate::network::discovery::{NodeInfo, NodeInfoDiff};
 
 type MessageHandlerFn =
     Box<dyn Fn(NetworkMessage) -> Result<(), NetworkError> + Send + Sync + 'static>;
@@ -81,6 +98,64 @@ struct TransportMessage {
     stream_correlation: Option<StreamCorrelation>,
 }
 
+/// Lock-free counters backing `TransportStats`
+///
+/// INTENTION: Updated from the hot send/receive paths, so every counter is a
+/// plain atomic and no lock is ever taken to record a metric.
+#[derive(Debug, Default)]
+struct TransportMetrics {
+    connections_established: AtomicU64,
+    connections_dropped: AtomicU64,
+    bytes_sent: AtomicU64,
+    bytes_received: AtomicU64,
+    messages_sent: AtomicU64,
+    messages_received: AtomicU64,
+    connection_errors: AtomicU64,
+    message_errors: AtomicU64,
+    discovery_errors: AtomicU64,
+    transport_errors: AtomicU64,
+    configuration_errors: AtomicU64,
+}
+
+impl TransportMetrics {
+    fn record_error(&self, error: &NetworkError) {
+        let counter = match error {
+            NetworkError::ConnectionError(..) => &self.connection_errors,
+            NetworkError::MessageError(..) => &self.message_errors,
+            NetworkError::DiscoveryError(..) => &self.discovery_errors,
+            NetworkError::TransportError(..) => &self.transport_errors,
+            NetworkError::ConfigurationError(..) => &self.configuration_errors,
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn snapshot(&self, current_connections: u32) -> TransportStats {
+        let errors_by_kind = [
+            ("ConnectionError", &self.connection_errors),
+            ("MessageError", &self.message_errors),
+            ("DiscoveryError", &self.discovery_errors),
+            ("TransportError", &self.transport_errors),
+            ("ConfigurationError", &self.configuration_errors),
+        ]
+        .into_iter()
+        .map(|(kind, counter)| (kind, counter.load(Ordering::Relaxed)))
+        .filter(|(_, count)| *count > 0)
+        .map(|(kind, count)| (kind.to_string(), count))
+        .collect::<HashMap<_, _>>();
+
+        TransportStats {
+            total_connections_established: self.connections_established.load(Ordering::Relaxed),
+            total_connections_dropped: self.connections_dropped.load(Ordering::Relaxed),
+            current_connections,
+            total_bytes_sent: self.bytes_sent.load(Ordering::Relaxed),
+            total_bytes_received: self.bytes_received.load(Ordering::Relaxed),
+            total_messages_sent: self.messages_sent.load(Ordering::Relaxed),
+            total_messages_received: self.messages_received.load(Ordering::Relaxed),
+            errors_by_kind,
+        }
+    }
+}
+
 /// QuicTransportImpl - Core implementation of QUIC transport
 ///
 /// INTENTION: This component is the core implementation of the QUIC transport,
@@ -95,14 +170,31 @@ struct TransportMessage {
 /// - Returns task handles to QuicTransport for lifecycle management
 struct QuicTransportImpl {
     node_id: PeerId,
-    bind_addr: SocketAddr,
+    // Updated when connections migrate to a new local address
+    bind_addr: StdRwLock<SocketAddr>,
     // Using Mutex for proper interior mutability instead of unsafe pointer casting
     endpoint: Mutex<Option<Endpoint>>,
+    // Socket under the endpoint when proxy tunnels are in use
+    tunnel_socket: Mutex<Option<Arc<TunnelSocket>>>,
+    // Accept loop for incoming tunnels; aborted on stop as it never ends by itself
+    tunnel_listener: Mutex<Option<JoinHandle<()>>>,
+    // Local address polling for connection migration; aborted on stop
+    migration_monitor: Mutex<Option<JoinHandle<()>>>,
+    // Disconnection of idle peers; aborted on stop
+    idle_monitor: Mutex<Option<JoinHandle<()>>>,
+    // When a message was last sent to or received from each peer
+    last_message_at: DashMap<PeerId, std::time::Instant>,
+    // Peers disconnected for being idle, reconnected by the next message to them
+    idle_peers: DashMap<PeerId, PeerInfo>,
+    // Every peer that completed a handshake, with when it was disconnected
+    // (`None` while connected); tells reconnects from first connects
+    peer_connections: DashMap<PeerId, Option<std::time::Instant>>,
     connection_pool: Arc<ConnectionPool>,
     options: QuicTransportOptions,
     logger: Arc<Logger>,
     message_handler: Arc<StdRwLock<MessageHandlerFn>>,
-    local_node: NodeInfo,
+    // Sent in handshakes, kept up to date by update_peers and service diffs
+    local_node: StdRwLock<NodeInfo>,
     // Channel for sending peer node info updates
     peer_node_info_sender: tokio::sync::broadcast::Sender<NodeInfo>,
     running: Arc<AtomicBool>,
@@ -111,6 +203,14 @@ struct QuicTransportImpl {
         Arc<tokio::sync::RwLock<std::collections::HashMap<String, BidirectionalStream>>>,
     stream_correlations:
         Arc<tokio::sync::RwLock<std::collections::HashMap<String, StreamCorrelation>>>,
+    // Node key used to sign outgoing messages (derived from the TLS private key)
+    signing_key: Option<SigningKey>,
+    // Counters reported through transport_stats
+    metrics: TransportMetrics,
+    // Chunks received of messages split by their sender
+    chunk_reassembler: ChunkReassembler,
+    // Queue of the task running the connection callback, one event at a time
+    connection_events: Option<mpsc::UnboundedSender<ConnectionEvent>>,
 }
 
 /// Main QUIC transport implementation - Public API
@@ -133,6 +233,24 @@ pub struct QuicTransport {
     background_tasks: Mutex<Vec<JoinHandle<()>>>,
 }
 
+/// Default delay before local service changes are sent to peers
+pub const DEFAULT_SERVICE_UPDATE_DEBOUNCE_MS: u64 = 2000;
+
+/// Application close code of connections to peers that were idle too long
+const IDLE_DISCONNECT_CODE: quinn::VarInt = quinn::VarInt::from_u32(1);
+
+/// Application close code of connections to peers with an incompatible protocol version
+const INCOMPATIBLE_VERSION_CODE: quinn::VarInt = quinn::VarInt::from_u32(5);
+
+/// Application close code of connections refused by the `PeerFilter`
+const PEER_NOT_ALLOWED_CODE: quinn::VarInt = quinn::VarInt::from_u32(6);
+
+/// Application close code of connections to peers whose payloads either side cannot decode
+const INCOMPATIBLE_FORMAT_CODE: quinn::VarInt = quinn::VarInt::from_u32(7);
+
+/// Path of the handshake payload carrying the sender's `NodeCapabilities`
+const CAPABILITIES_PAYLOAD_PATH: &str = "$capabilities";
+
 /// QUIC-specific transport options
 pub struct QuicTransportOptions {
     verify_certificates: bool,
@@ -140,6 +258,10 @@ pub struct QuicTransportOptions {
     connection_idle_timeout: Duration,
     stream_idle_timeout: Duration,
     max_idle_streams_per_peer: usize,
+    /// Idle timeout and size limit of each peer's stream pool
+    stream_pool_options: StreamPoolOptions,
+    /// Size limit and eviction policy of the peer connection pool
+    connection_pool_options: ConnectionPoolOptions,
     /// TLS certificates for secure connections (REQUIRED)
     certificates: Option<Vec<CertificateDer<'static>>>,
     /// Private key corresponding to the certificates (REQUIRED)
@@ -150,6 +272,40 @@ pub struct QuicTransportOptions {
     root_certificates: Option<Vec<CertificateDer<'static>>>,
     /// Log level for Quinn-related logs (default: Warn to reduce noisy connection logs)
     quinn_log_level: log::LevelFilter,
+    /// Verify the source signature of incoming messages (default: true)
+    verify_message_signatures: bool,
+    /// Proxy that outgoing connections are tunneled through (default: none)
+    proxy: Option<ProxyConfig>,
+    /// Accept connections tunneled through a proxy on a TCP listener bound
+    /// to the QUIC port (default: false)
+    accept_tunneled_connections: bool,
+    /// Migrate connections when the local address changes (default: false)
+    enable_connection_migration: bool,
+    /// How often the local address is checked for changes (default: 5s)
+    migration_check_interval: Duration,
+    /// Delay before local service changes are sent to peers (default: 2000ms)
+    service_update_debounce_ms: u64,
+    /// Disconnect peers that exchanged no message for this long (default: none)
+    idle_disconnect_timeout: Option<Duration>,
+    /// Capabilities advertised to peers during the handshake
+    capabilities: NodeCapabilities,
+    /// Largest protocol version difference accepted from peers (default: 1)
+    max_version_skew: u8,
+    /// Called when peers connect, reconnect and disconnect (default: none)
+    connection_callback: Option<ConnectionCallback>,
+    /// Name payload types by registry ID for peers sharing our type table
+    /// (default: false)
+    compact_framing: bool,
+    /// Registry whose type IDs compact frames use, set by the node
+    serializer: Option<Arc<RwLock<SerializerRegistry>>>,
+    /// Peers connections are accepted with (default: all)
+    peer_filter: Option<Arc<PeerFilter>>,
+    /// Largest message sent or received in one frame (default: 1MB)
+    max_message_size: usize,
+    /// Time the chunks of a message may take to all arrive (default: 30s)
+    chunk_timeout: Duration,
+    /// Largest message reassembled from chunks (default: 64MB)
+    max_reassembled_size: usize,
 }
 
 impl Clone for QuicTransportOptions {
@@ -160,11 +316,29 @@ impl Clone for QuicTransportOptions {
             connection_idle_timeout: self.connection_idle_timeout,
             stream_idle_timeout: self.stream_idle_timeout,
             max_idle_streams_per_peer: self.max_idle_streams_per_peer,
+            stream_pool_options: self.stream_pool_options,
+            connection_pool_options: self.connection_pool_options,
             certificates: self.certificates.clone(),
             private_key: self.private_key.as_ref().map(|k| k.clone_key()),
             certificate_verifier: self.certificate_verifier.clone(),
             root_certificates: self.root_certificates.clone(),
             quinn_log_level: self.quinn_log_level,
+            verify_message_signatures: self.verify_message_signatures,
+            proxy: self.proxy.clone(),
+            accept_tunneled_connections: self.accept_tunneled_connections,
+            enable_connection_migration: self.enable_connection_migration,
+            migration_check_interval: self.migration_check_interval,
+            service_update_debounce_ms: self.service_update_debounce_ms,
+            idle_disconnect_timeout: self.idle_disconnect_timeout,
+            capabilities: self.capabilities.clone(),
+            max_version_skew: self.max_version_skew,
+            connection_callback: self.connection_callback.clone(),
+            compact_framing: self.compact_framing,
+            serializer: self.serializer.clone(),
+            peer_filter: self.peer_filter.clone(),
+            max_message_size: self.max_message_size,
+            chunk_timeout: self.chunk_timeout,
+            max_reassembled_size: self.max_reassembled_size,
         }
     }
 }
@@ -177,6 +351,8 @@ impl fmt::Debug for QuicTransportOptions {
             .field("connection_idle_timeout", &self.connection_idle_timeout)
             .field("stream_idle_timeout", &self.stream_idle_timeout)
             .field("max_idle_streams_per_peer", &self.max_idle_streams_per_peer)
+            .field("stream_pool_options", &self.stream_pool_options)
+            .field("connection_pool_options", &self.connection_pool_options)
             .field(
                 "certificates",
                 &self.certificates.as_ref().map(|_| "[redacted]"),
@@ -197,6 +373,33 @@ impl fmt::Debug for QuicTransportOptions {
                 &self.root_certificates.as_ref().map(|_| "[redacted]"),
             )
             .field("quinn_log_level", &self.quinn_log_level)
+            .field("verify_message_signatures", &self.verify_message_signatures)
+            .field("proxy", &self.proxy)
+            .field(
+                "accept_tunneled_connections",
+                &self.accept_tunneled_connections,
+            )
+            .field(
+                "enable_connection_migration",
+                &self.enable_connection_migration,
+            )
+            .field("migration_check_interval", &self.migration_check_interval)
+            .field(
+                "service_update_debounce_ms",
+                &self.service_update_debounce_ms,
+            )
+            .field("idle_disconnect_timeout", &self.idle_disconnect_timeout)
+            .field("capabilities", &self.capabilities)
+            .field("max_version_skew", &self.max_version_skew)
+            .field(
+                "connection_callback",
+                &self.connection_callback.as_ref().map(|_| "[callback]"),
+            )
+            .field("compact_framing", &self.compact_framing)
+            .field("peer_filter", &self.peer_filter)
+            .field("max_message_size", &self.max_message_size)
+            .field("chunk_timeout", &self.chunk_timeout)
+            .field("max_reassembled_size", &self.max_reassembled_size)
             .finish()
     }
 }
@@ -228,6 +431,219 @@ impl QuicTransportOptions {
         self
     }
 
+    /// Enable or disable signature verification of incoming messages
+    ///
+    /// INTENTION: Outgoing messages are always signed, but verification can be
+    /// skipped on trusted, performance-sensitive internal networks. Default is true.
+    pub fn with_verify_message_signatures(mut self, verify: bool) -> Self {
+        self.verify_message_signatures = verify;
+        self
+    }
+
+    pub fn verify_message_signatures(&self) -> bool {
+        self.verify_message_signatures
+    }
+
+    /// Tunnel outgoing connections through an HTTP CONNECT or SOCKS5 proxy
+    ///
+    /// INTENTION: Reach peers from networks that block UDP. QUIC then runs
+    /// over TCP, which brings back head-of-line blocking, so use this only as
+    /// a fallback. The peers must accept tunneled connections.
+    pub fn with_proxy(mut self, proxy: ProxyConfig) -> Self {
+        self.proxy = Some(proxy);
+        self
+    }
+
+    pub fn proxy(&self) -> Option<&ProxyConfig> {
+        self.proxy.as_ref()
+    }
+
+    /// Accept connections from peers behind a proxy
+    ///
+    /// Opens a TCP listener on the QUIC port that proxied peers tunnel to.
+    /// Default is false.
+    pub fn with_accept_tunneled_connections(mut self, accept: bool) -> Self {
+        self.accept_tunneled_connections = accept;
+        self
+    }
+
+    /// Migrate connections when the local address changes
+    ///
+    /// INTENTION: Keep connections alive on mobile and laptop nodes that
+    /// switch networks, e.g. from Wi-Fi to ethernet. The transport checks the
+    /// local address every `migration_check_interval`; when it changed, the
+    /// endpoint is rebound to a new socket and QUIC migrates every connection
+    /// to it (RFC 9000 §9). Not available with proxy tunnels. Default is false.
+    pub fn with_connection_migration(mut self, enable: bool) -> Self {
+        self.enable_connection_migration = enable;
+        self
+    }
+
+    pub fn enable_connection_migration(&self) -> bool {
+        self.enable_connection_migration
+    }
+
+    /// Set how often the local address is checked when migration is enabled
+    pub fn with_migration_check_interval(mut self, interval: Duration) -> Self {
+        self.migration_check_interval = interval;
+        self
+    }
+
+    /// Set how long local service changes are batched before peers are told
+    ///
+    /// Adding or removing a service (or subscription) on a running node sends
+    /// the updated node info to every peer once this period has passed since
+    /// the first change; changes made in the meantime go out together.
+    pub fn with_service_update_debounce_ms(mut self, debounce_ms: u64) -> Self {
+        self.service_update_debounce_ms = debounce_ms;
+        self
+    }
+
+    pub fn service_update_debounce_ms(&self) -> u64 {
+        self.service_update_debounce_ms
+    }
+
+    /// Disconnect peers that exchanged no message for `timeout`
+    ///
+    /// INTENTION: Free the connections of peers whose services stopped
+    /// talking to us. Quinn's `connection_idle_timeout` never fires for them
+    /// because keep-alives hold the connection open; this timeout only counts
+    /// messages sent and received. The connection is re-established by the
+    /// next message sent to the peer. The node sets it from
+    /// `TransportOptions::timeout` unless it is set here. Default is none.
+    pub fn with_idle_disconnect_timeout(mut self, timeout: Duration) -> Self {
+        self.idle_disconnect_timeout = Some(timeout);
+        self
+    }
+
+    pub fn idle_disconnect_timeout(&self) -> Option<Duration> {
+        self.idle_disconnect_timeout
+    }
+
+    /// Set the capabilities advertised to peers during the handshake
+    ///
+    /// INTENTION: Peers negotiate the capabilities both of them support when
+    /// connecting, see `NodeCapabilities::negotiate`. The protocol version is
+    /// checked against `max_version_skew`, and peers are refused when either
+    /// side cannot decode the first of the other's `supported_formats`. The
+    /// advertised message size is always the one set by
+    /// `with_max_message_size`, since it is the limit this transport enforces.
+    /// Default is no compression, the current protocol version and every
+    /// compiled format.
+    pub fn with_capabilities(mut self, capabilities: NodeCapabilities) -> Self {
+        self.capabilities = NodeCapabilities {
+            max_message_size: self.max_message_size,
+            ..capabilities
+        };
+        self
+    }
+
+    pub fn capabilities(&self) -> &NodeCapabilities {
+        &self.capabilities
+    }
+
+    /// Set the largest protocol version difference accepted from peers
+    ///
+    /// INTENTION: Let nodes of adjacent versions keep talking during a rolling
+    /// upgrade while refusing peers too old or too new to understand. Peers
+    /// further apart are disconnected during the handshake; closer peers with
+    /// another version are accepted with a warning. Default is 1.
+    pub fn with_max_version_skew(mut self, max_version_skew: u8) -> Self {
+        self.max_version_skew = max_version_skew;
+        self
+    }
+
+    pub fn max_version_skew(&self) -> u8 {
+        self.max_version_skew
+    }
+
+    /// Call `callback` when peers connect, reconnect and disconnect
+    ///
+    /// INTENTION: Let services restore what a peer forgets when its connection
+    /// drops, such as event subscriptions. A peer counts as connected once its
+    /// handshake completes; later node info updates are not reported. Events
+    /// are passed one at a time, in the order they happened, so a slow
+    /// callback delays the next ones. Errors returned by the callback are
+    /// logged. Default is none.
+    pub fn with_connection_callback(mut self, callback: ConnectionCallback) -> Self {
+        self.connection_callback = Some(callback);
+        self
+    }
+
+    /// Name the type of payloads by ID instead of by name
+    ///
+    /// INTENTION: Shrink messages carrying small values, whose type name can
+    /// outweigh the data. The node seals its serializer registry when the
+    /// transport starts, so every type must be registered before, and
+    /// services cannot be added to the running node; peers
+    /// advertise a fingerprint of their type IDs in the handshake and compact
+    /// frames are only sent to peers with the same one. Others get the
+    /// regular frames. Default is false.
+    pub fn with_compact_framing(mut self, compact_framing: bool) -> Self {
+        self.compact_framing = compact_framing;
+        self
+    }
+
+    pub fn compact_framing(&self) -> bool {
+        self.compact_framing
+    }
+
+    /// Set the sealed registry whose type IDs compact frames use
+    pub(crate) fn with_serializer(mut self, serializer: Arc<RwLock<SerializerRegistry>>) -> Self {
+        self.serializer = Some(serializer);
+        self
+    }
+
+    /// Only connect with the peers accepted by `peer_filter`
+    ///
+    /// INTENTION: Refuse connections with unknown or banned nodes, in both
+    /// directions. Connecting to a rejected peer fails with a
+    /// `ConfigurationError`, and incoming connections from one are closed
+    /// once its handshake identifies it. The filter can be updated while the
+    /// transport runs. Default is none.
+    pub fn with_peer_filter(mut self, peer_filter: Arc<PeerFilter>) -> Self {
+        self.peer_filter = Some(peer_filter);
+        self
+    }
+
+    /// Set the largest message sent or received in one frame, in bytes
+    ///
+    /// INTENTION: The limit is advertised to peers in the handshake
+    /// capabilities, and each connection uses the smaller of both limits. A
+    /// message with a larger payload is split into chunks of that size, sent
+    /// one after the other and reassembled by the receiver, so callers above
+    /// the transport never see the limit. Frames larger than this are refused.
+    /// The node sets it from `TransportOptions::max_message_size`. Default is
+    /// 1MB.
+    pub fn with_max_message_size(mut self, max_message_size: usize) -> Self {
+        self.max_message_size = max_message_size;
+        self.capabilities.max_message_size = max_message_size;
+        self
+    }
+
+    pub fn max_message_size(&self) -> usize {
+        self.max_message_size
+    }
+
+    /// Set the time the chunks of a message may take to all arrive
+    ///
+    /// The chunks received of a message still incomplete after this long are
+    /// discarded. The node sets it from `TransportOptions::timeout`. Default
+    /// is 30s.
+    pub fn with_chunk_timeout(mut self, timeout: Duration) -> Self {
+        self.chunk_timeout = timeout;
+        self
+    }
+
+    /// Set the largest message reassembled from chunks, in bytes
+    ///
+    /// Chunks announcing a larger message are refused before any of it is
+    /// buffered. Default is 64MB.
+    pub fn with_max_reassembled_size(mut self, max_reassembled_size: usize) -> Self {
+        self.max_reassembled_size = max_reassembled_size;
+        self
+    }
+
     pub fn with_verify_certificates(mut self, verify: bool) -> Self {
         self.verify_certificates = verify;
         self
@@ -253,6 +669,18 @@ impl QuicTransportOptions {
         self
     }
 
+    /// Set the idle timeout and size limit of each peer's stream pool
+    pub fn with_stream_pool_options(mut self, options: StreamPoolOptions) -> Self {
+        self.stream_pool_options = options;
+        self
+    }
+
+    /// Set the size limit and eviction policy of the peer connection pool
+    pub fn with_connection_pool_options(mut self, options: ConnectionPoolOptions) -> Self {
+        self.connection_pool_options = options;
+        self
+    }
+
     pub fn with_certificates(mut self, certs: Vec<CertificateDer<'static>>) -> Self {
         self.certificates = Some(certs);
         self
@@ -285,11 +713,29 @@ impl Default for QuicTransportOptions {
             connection_idle_timeout: Duration::from_secs(60),
             stream_idle_timeout: Duration::from_secs(30),
             max_idle_streams_per_peer: 100,
+            stream_pool_options: StreamPoolOptions::default(),
+            connection_pool_options: ConnectionPoolOptions::default(),
             certificates: None,
             private_key: None,
             certificate_verifier: None,
             root_certificates: None,
             quinn_log_level: log::LevelFilter::Warn, // Default to Warn to reduce noisy logs
+            verify_message_signatures: true,
+            proxy: None,
+            accept_tunneled_connections: false,
+            enable_connection_migration: false,
+            migration_check_interval: Duration::from_secs(5),
+            service_update_debounce_ms: DEFAULT_SERVICE_UPDATE_DEBOUNCE_MS,
+            idle_disconnect_timeout: None,
+            capabilities: NodeCapabilities::default(),
+            max_version_skew: DEFAULT_MAX_VERSION_SKEW,
+            connection_callback: None,
+            compact_framing: false,
+            serializer: None,
+            peer_filter: None,
+            max_message_size: DEFAULT_MAX_MESSAGE_SIZE,
+            chunk_timeout: DEFAULT_CHUNK_TIMEOUT,
+            max_reassembled_size: DEFAULT_MAX_REASSEMBLED_SIZE,
         }
     }
 }
@@ -300,7 +746,7 @@ impl fmt::Debug for QuicTransportImpl {
         // We can't access the Mutex in fmt because it might block, so we just indicate it exists
         f.debug_struct("QuicTransportImpl")
             .field("node_id", &self.node_id)
-            .field("bind_addr", &self.bind_addr)
+            .field("bind_addr", &self.local_addr())
             .field("endpoint", &"<mutex>") // Can't access Mutex contents in fmt
             .field("options", &self.options)
             .finish()
@@ -321,22 +767,56 @@ impl QuicTransportImpl {
     ///
     /// INTENTION: Initialize the core implementation with the provided parameters.
     fn new(config: QuicTransportConfig) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
-        let connection_pool = Arc::new(ConnectionPool::new(config.logger.clone()));
+        let connection_pool = Arc::new(ConnectionPool::with_options(
+            config.options.connection_pool_options,
+            config.logger.clone(),
+        ));
 
         // Create a broadcast channel for peer node info updates
         // The channel size determines how many messages can be buffered before lagging
         let (peer_node_info_sender, _) = tokio::sync::broadcast::channel(32);
 
+        // The TLS private key is the node key, so it doubles as the message signing key
+        let signing_key = message_signing_key(config.options.private_key())?;
+
+        let chunk_reassembler = ChunkReassembler::new(config.options.chunk_timeout)
+            .with_chunk_size(chunking::chunk_size(config.options.max_message_size))
+            .with_max_reassembled_size(config.options.max_reassembled_size);
+
+        // A single task runs the connection callback, so that it sees the
+        // events of a peer in the order they happened
+        let connection_events = config
+            .options
+            .connection_callback
+            .clone()
+            .and_then(|callback| {
+                let handle = tokio::runtime::Handle::try_current().ok()?;
+                let (sender, receiver) = mpsc::unbounded_channel();
+                handle.spawn(Self::deliver_connection_events(
+                    callback,
+                    receiver,
+                    config.logger.clone(),
+                ));
+                Some(sender)
+            });
+
         Ok(Self {
             node_id: config.local_node_info.peer_id.clone(),
-            bind_addr: config.bind_addr,
+            bind_addr: StdRwLock::new(config.bind_addr),
             // Initialize with Mutex for proper interior mutability
             endpoint: Mutex::new(None),
+            tunnel_socket: Mutex::new(None),
+            tunnel_listener: Mutex::new(None),
+            migration_monitor: Mutex::new(None),
+            idle_monitor: Mutex::new(None),
+            last_message_at: DashMap::new(),
+            idle_peers: DashMap::new(),
+            peer_connections: DashMap::new(),
             connection_pool,
             options: config.options,
             logger: config.logger,
             message_handler: Arc::new(StdRwLock::new(config.message_handler)),
-            local_node: config.local_node_info,
+            local_node: StdRwLock::new(config.local_node_info),
             peer_node_info_sender,
             running: Arc::new(AtomicBool::new(false)),
             // Initialize enhanced stream management
@@ -346,9 +826,71 @@ impl QuicTransportImpl {
             stream_correlations: Arc::new(tokio::sync::RwLock::new(
                 std::collections::HashMap::new(),
             )),
+            signing_key,
+            metrics: TransportMetrics::default(),
+            chunk_reassembler,
+            connection_events,
         })
     }
 
+    /// Give the payloads of a message compact frames, if the peer reads them
+    ///
+    /// Only payloads serialized by the node, those naming their format, are
+    /// rewritten; a payload whose type has no ID keeps its regular frame.
+    async fn compact_payloads(&self, peer_state: &PeerState, message: &mut NetworkMessage) {
+        let Some(serializer) = &self.options.serializer else {
+            return;
+        };
+        if !self.options.compact_framing {
+            return;
+        }
+        let shares_type_table = peer_state
+            .get_capabilities()
+            .await
+            .is_some_and(|capabilities| capabilities.type_table.is_some());
+        if !shares_type_table {
+            return;
+        }
+
+        let serializer = serializer.read().await;
+        for payload in message
+            .payloads
+            .iter_mut()
+            .filter(|payload| payload.format.is_some())
+        {
+            if let Some(frame) = serializer.compact_frame(&payload.value_bytes) {
+                payload.value_bytes = frame;
+            }
+        }
+    }
+
+    /// Largest message a peer accepts from us
+    ///
+    /// Before the handshake negotiated a limit, the peer is assumed to accept
+    /// the default one.
+    async fn peer_max_message_size(&self, peer_state: &PeerState) -> usize {
+        peer_state
+            .max_message_size()
+            .await
+            .unwrap_or_else(|| self.options.max_message_size.min(DEFAULT_MAX_MESSAGE_SIZE))
+    }
+
+    /// Sign an outgoing message with the node key
+    ///
+    /// INTENTION: Let receivers authenticate the message source. Messages are
+    /// left unsigned only when the transport has no usable key.
+    fn sign_message(&self, message: &mut NetworkMessage) {
+        match &self.signing_key {
+            Some(signing_key) => message.sign(signing_key),
+            None => {
+                self.logger.warn(format!(
+                    "⚠️ [QuicTransport] No signing key available, sending unsigned {} message",
+                    message.message_type
+                ));
+            }
+        }
+    }
+
     /// Determine the communication pattern for a message
     ///
     /// INTENTION: Classify messages to use appropriate stream types and lifecycle management
@@ -362,6 +904,9 @@ impl QuicTransportImpl {
             "Request" => MessagePattern::OneWay,
             // Response messages are sent back via separate unidirectional streams
             "Response" | "Error" => MessagePattern::OneWay,
+            // Frames of a streamed response each travel on their own stream;
+            // the receiver reorders them by sequence number
+            "StreamItem" | "StreamEnd" => MessagePattern::OneWay,
             // Default to one-way for unknown message types
             _ => {
                 self.logger.warn(format!(
@@ -429,18 +974,25 @@ impl QuicTransportImpl {
 
         let peer_state = self.get_peer_state(peer_id)?;
         if !peer_state.is_connected().await {
-            return Err(NetworkError::ConnectionError(format!(
-                "Peer {peer_id} is not connected"
-            )));
+            return Err(NetworkError::ConnectionError(
+                ErrorCode::NotConnected,
+                format!("Peer {peer_id} is not connected"),
+            ));
         }
 
         // Get bidirectional stream for request-response
         let connection = peer_state.get_connection().await.ok_or_else(|| {
-            NetworkError::ConnectionError(format!("No connection to peer {peer_id}"))
+            NetworkError::ConnectionError(
+                ErrorCode::NotConnected,
+                format!("No connection to peer {peer_id}"),
+            )
         })?;
 
         let (mut send_stream, recv_stream) = connection.open_bi().await.map_err(|e| {
-            NetworkError::ConnectionError(format!("Failed to open bidirectional stream: {e}"))
+            NetworkError::ConnectionError(
+                ErrorCode::StreamFailed,
+                format!("Failed to open bidirectional stream: {e}"),
+            )
         })?;
 
         // Extract correlation ID for concurrent processing
@@ -474,9 +1026,10 @@ impl QuicTransportImpl {
 
         // Close the bidirectional streams since we won't use them
         send_stream.finish().map_err(|e| {
-            NetworkError::MessageError(format!(
-                "Failed to finish unused bidirectional send stream: {e}"
-            ))
+            NetworkError::MessageError(
+                ErrorCode::StreamFailed,
+                format!("Failed to finish unused bidirectional send stream: {e}"),
+            )
         })?;
         drop(recv_stream); // Don't need the bidirectional recv stream
 
@@ -496,7 +1049,7 @@ impl QuicTransportImpl {
     async fn send_oneway_message(
         &self,
         peer_id: &PeerId,
-        message: NetworkMessage,
+        mut message: NetworkMessage,
     ) -> Result<(), NetworkError> {
         self.logger.debug(format!(
             "📡 [QuicTransport] Sending one-way message to peer {peer_id}"
@@ -504,27 +1057,53 @@ impl QuicTransportImpl {
 
         let peer_state = self.get_peer_state(peer_id)?;
         if !peer_state.is_connected().await {
-            return Err(NetworkError::ConnectionError(format!(
-                "Peer {peer_id} is not connected"
-            )));
+            return Err(NetworkError::ConnectionError(
+                ErrorCode::NotConnected,
+                format!("Peer {peer_id} is not connected"),
+            ));
         }
 
         // Get unidirectional stream for one-way messages
         let connection = peer_state.get_connection().await.ok_or_else(|| {
-            NetworkError::ConnectionError(format!("No connection to peer {peer_id}"))
+            NetworkError::ConnectionError(
+                ErrorCode::NotConnected,
+                format!("No connection to peer {peer_id}"),
+            )
         })?;
 
-        let mut stream = connection.open_uni().await.map_err(|e| {
-            NetworkError::ConnectionError(format!("Failed to open unidirectional stream: {e}"))
-        })?;
+        peer_state.track_message(&message);
+        // A tagged message was prepared before its tag was computed
+        if message.auth_tag.is_none() {
+            self.compact_payloads(&peer_state, &mut message).await;
+        }
+
+        // A payload too large for one frame is sent as chunks, each on its own
+        // stream, sized for the smaller of our limit and the peer's
+        let chunk_size = chunking::chunk_size(self.peer_max_message_size(&peer_state).await);
+        for mut message in chunking::split_message(message, chunk_size) {
+            let _slot = peer_state.stream_pool.reserve().await;
+            let mut stream = connection.open_uni().await.map_err(|e| {
+                NetworkError::ConnectionError(
+                    ErrorCode::StreamFailed,
+                    format!("Failed to open unidirectional stream: {e}"),
+                )
+            })?;
 
-        // Send the message and finish the stream immediately
-        self.write_message_to_stream(&mut stream, &message, peer_id)
-            .await?;
+            self.sign_message(&mut message);
 
-        stream.finish().map_err(|e| {
-            NetworkError::MessageError(format!("Failed to finish unidirectional stream: {e}"))
-        })?;
+            // Send the message and finish the stream immediately
+            self.write_message_to_stream(&mut stream, &message, peer_id)
+                .await?;
+
+            stream.finish().map_err(|e| {
+                NetworkError::MessageError(
+                    ErrorCode::StreamFailed,
+                    format!("Failed to finish unidirectional stream: {e}"),
+                )
+            })?;
+            self.metrics.messages_sent.fetch_add(1, Ordering::Relaxed);
+        }
+        self.record_activity(peer_id);
 
         self.logger.debug(format!(
             "✅ [QuicTransport] One-way message sent and stream finished for peer {peer_id}"
@@ -552,7 +1131,10 @@ impl QuicTransportImpl {
             .first()
             .map(|p| p.correlation_id.clone())
             .ok_or_else(|| {
-                NetworkError::MessageError("Response message has no correlation ID".to_string())
+                NetworkError::MessageError(
+                    ErrorCode::InvalidMessage,
+                    "Response message has no correlation ID".to_string(),
+                )
             })?;
 
         // **NEW APPROACH**: Send response via unidirectional stream
@@ -595,9 +1177,12 @@ impl QuicTransportImpl {
 
     /// Helper method to get peer state with error handling
     fn get_peer_state(&self, peer_id: &PeerId) -> Result<Arc<PeerState>, NetworkError> {
-        self.connection_pool
-            .get_peer(peer_id)
-            .ok_or_else(|| NetworkError::ConnectionError(format!("Peer {peer_id} not found")))
+        self.connection_pool.get_peer(peer_id).ok_or_else(|| {
+            NetworkError::ConnectionError(
+                ErrorCode::NotConnected,
+                format!("Peer {peer_id} not found"),
+            )
+        })
     }
 
     /// Helper method to write a message to any stream type
@@ -613,19 +1198,33 @@ impl QuicTransportImpl {
         use tokio::io::AsyncWriteExt;
 
         // Serialize the message
-        let serialized_message = bincode::serialize(message)
-            .map_err(|e| NetworkError::MessageError(format!("Failed to serialize message: {e}")))?;
+        let serialized_message = bincode::serialize(message).map_err(|e| {
+            NetworkError::MessageError(
+                ErrorCode::SerializationFailed,
+                format!("Failed to serialize message: {e}"),
+            )
+        })?;
 
         // Write message length first (4 bytes)
         let len_bytes = (serialized_message.len() as u32).to_be_bytes();
         stream.write_all(&len_bytes).await.map_err(|e| {
-            NetworkError::MessageError(format!("Failed to write message length: {e}"))
+            NetworkError::MessageError(
+                ErrorCode::StreamFailed,
+                format!("Failed to write message length: {e}"),
+            )
         })?;
 
         // Write the serialized message
         stream.write_all(&serialized_message).await.map_err(|e| {
-            NetworkError::MessageError(format!("Failed to write message data: {e}"))
+            NetworkError::MessageError(
+                ErrorCode::StreamFailed,
+                format!("Failed to write message data: {e}"),
+            )
         })?;
+        self.metrics.bytes_sent.fetch_add(
+            (len_bytes.len() + serialized_message.len()) as u64,
+            Ordering::Relaxed,
+        );
 
         self.logger.debug(format!(
             "✅ [QuicTransport] Message written to stream - Peer: {}, Size: {} bytes",
@@ -647,6 +1246,7 @@ impl QuicTransportImpl {
     ) -> Result<JoinHandle<()>, NetworkError> {
         if !self.running.load(Ordering::Relaxed) {
             return Err(NetworkError::TransportError(
+                ErrorCode::NotRunning,
                 "Transport not running".to_string(),
             ));
         }
@@ -654,12 +1254,16 @@ impl QuicTransportImpl {
         // Ensure we have at least one address to try
         if discovery_msg.addresses.is_empty() {
             return Err(NetworkError::ConnectionError(
+                ErrorCode::PeerUnreachable,
                 "No addresses found for peer".to_string(),
             ));
         }
 
         // Get the peer ID based on the public_key from PeerInfo
         let peer_id = PeerId::new(discovery_msg.public_key.clone());
+        if let Some(peer_filter) = &self.options.peer_filter {
+            peer_filter.check(&peer_id)?;
+        }
 
         // Check if we're already connected to this peer
         if self.connection_pool.is_peer_connected(&peer_id).await {
@@ -676,6 +1280,7 @@ impl QuicTransportImpl {
             Some(endpoint) => endpoint.clone(),
             None => {
                 return Err(NetworkError::TransportError(
+                    ErrorCode::NotRunning,
                     "Transport not initialized".to_string(),
                 ))
             }
@@ -691,9 +1296,10 @@ impl QuicTransportImpl {
                 Err(e) => {
                     self.logger
                         .warn(format!("Invalid address {peer_addr}: {e}"));
-                    last_error = Some(NetworkError::ConnectionError(format!(
-                        "Invalid address {peer_addr}: {e}"
-                    )));
+                    last_error = Some(NetworkError::ConnectionError(
+                        ErrorCode::ConnectionFailed,
+                        format!("Invalid address {peer_addr}: {e}"),
+                    ));
                     continue; // Try the next address
                 }
             };
@@ -708,6 +1314,14 @@ impl QuicTransportImpl {
                 self.node_id, peer_id, socket_addr
             ));
 
+            if let Err(e) = self.tunnel_through_proxy(socket_addr).await {
+                self.logger.warn(format!(
+                    "Failed to reach peer {peer_id} at {socket_addr}: {e}"
+                ));
+                last_error = Some(e);
+                continue; // Try the next address
+            }
+
             // Create a new connection to the peer
             // For testing, we use "localhost" as the server name to avoid certificate validation issues
             // In production, we would use the peer_id or a proper domain name
@@ -726,11 +1340,17 @@ impl QuicTransportImpl {
                                 peer_id.clone(),
                                 peer_addr.clone(),
                                 self.options.max_idle_streams_per_peer,
+                                self.options.stream_pool_options,
                                 self.logger.clone(),
                             );
 
                             // Set the connection in the peer state
-                            peer_state.set_connection(connection).await;
+                            self.connection_pool
+                                .set_peer_connection(&peer_state, connection)
+                                .await;
+                            self.metrics
+                                .connections_established
+                                .fetch_add(1, Ordering::Relaxed);
 
                             // Successfully connected to this address
 
@@ -751,9 +1371,10 @@ impl QuicTransportImpl {
                             self.logger.warn(format!(
                                 "Failed to connect to peer {peer_id} at {socket_addr}: {e}"
                             ));
-                            last_error = Some(NetworkError::ConnectionError(format!(
-                                "Failed to establish connection to {socket_addr}: {e}"
-                            )));
+                            last_error = Some(NetworkError::ConnectionError(
+                                ErrorCode::PeerUnreachable,
+                                format!("Failed to establish connection to {socket_addr}: {e}"),
+                            ));
                             // Continue to the next address
                         }
                     }
@@ -762,9 +1383,10 @@ impl QuicTransportImpl {
                     self.logger.warn(format!(
                         "Failed to initiate connection to peer {peer_id} at {socket_addr}: {e}"
                     ));
-                    last_error = Some(NetworkError::ConnectionError(format!(
-                        "Failed to initiate connection to {socket_addr}: {e}"
-                    )));
+                    last_error = Some(NetworkError::ConnectionError(
+                        ErrorCode::PeerUnreachable,
+                        format!("Failed to initiate connection to {socket_addr}: {e}"),
+                    ));
                     // Continue to the next address
                 }
             }
@@ -772,35 +1394,216 @@ impl QuicTransportImpl {
 
         // If we get here, all connection attempts failed
         Err(last_error.unwrap_or_else(|| {
-            NetworkError::ConnectionError(format!(
-                "Failed to connect to peer {peer_id} on any address"
-            ))
+            NetworkError::ConnectionError(
+                ErrorCode::PeerUnreachable,
+                format!("Failed to connect to peer {peer_id} on any address"),
+            )
         }))
     }
 
     async fn update_peers(self: &Arc<Self>, node_info: NodeInfo) -> Result<(), NetworkError> {
+        *self.local_node.write().unwrap_or_else(|e| e.into_inner()) = node_info;
+
         //for each connected peer send a NODE_INFO_UPDATE message
+        // One unreachable peer must not keep the update from the others
+        let peers = self.connection_pool.get_connected_peers().await;
+        for peer_id in peers {
+            let node_info = self.local_node_for(&peer_id).await;
+            if let Err(e) = self.send_node_info_update(&peer_id, &node_info).await {
+                self.logger.warn(format!(
+                    "Failed to send NODE_INFO_UPDATE to peer {peer_id}: {e}"
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    async fn send_node_info_update(
+        self: &Arc<Self>,
+        peer_id: &PeerId,
+        node_info: &NodeInfo,
+    ) -> Result<(), NetworkError> {
+        let value_bytes = bincode::serialize(node_info).map_err(|e| {
+            NetworkError::MessageError(
+                ErrorCode::SerializationFailed,
+                format!("Failed to serialize node info: {e}"),
+            )
+        })?;
+        let message = NetworkMessage {
+            source: self.node_id.clone(),
+            destination: peer_id.clone(),
+            message_type: "NODE_INFO_UPDATE".to_string(),
+            payloads: vec![NetworkMessagePayloadItem {
+                path: "".to_string(),
+                value_bytes,
+                correlation_id: "".to_string(),
+                error_code: None,
+                sequence: None,
+                dedup_id: None,
+                format: None,
+                chunk_index: None,
+                total_chunks: None,
+            }],
+            signature: None,
+            hop_count: 0,
+            visited_peers: Vec::new(),
+            auth_tag: None,
+            expires_at: None,
+        };
+        self.send_message(message).await?;
+        self.logger
+            .info(format!("Sent NODE_INFO_UPDATE message to peer {peer_id}"));
+        Ok(())
+    }
+
+    /// Send a service diff to each connected peer in a NODE_INFO_DIFF message
+    ///
+    /// A peer that missed an earlier diff cannot apply this one and asks for
+    /// the full node info with a NODE_INFO_REQUEST instead.
+    async fn broadcast_service_diff(
+        self: &Arc<Self>,
+        diff: NodeInfoDiff,
+    ) -> Result<(), NetworkError> {
+        self.local_node
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .apply_diff(&diff)
+            .map_err(|e| {
+                NetworkError::MessageError(ErrorCode::InvalidMessage, format!("Invalid diff: {e}"))
+            })?;
+
         let peers = self.connection_pool.get_connected_peers().await;
         for peer_id in peers {
+            let diff = diff.visible_to(&self.peer_network_ids(&peer_id).await);
+            let value_bytes = bincode::serialize(&diff).map_err(|e| {
+                NetworkError::MessageError(
+                    ErrorCode::SerializationFailed,
+                    format!("Failed to serialize node info diff: {e}"),
+                )
+            })?;
             let message = NetworkMessage {
                 source: self.node_id.clone(),
                 destination: peer_id.clone(),
-                message_type: "NODE_INFO_UPDATE".to_string(),
-                payloads: vec![NetworkMessagePayloadItem {
-                    path: "".to_string(),
-                    value_bytes: bincode::serialize(&node_info).unwrap(),
-                    correlation_id: "".to_string(),
-                }],
+                message_type: "NODE_INFO_DIFF".to_string(),
+                payloads: vec![NetworkMessagePayloadItem::new(
+                    String::new(),
+                    value_bytes,
+                    String::new(),
+                )],
+                signature: None,
+                hop_count: 0,
+                visited_peers: Vec::new(),
+                auth_tag: None,
+                expires_at: None,
             };
-            self.send_message(message).await?;
-            self.logger
-                .info(format!("Sent NODE_INFO_UPDATE message to peer {peer_id}"));
+            // One unreachable peer must not keep the diff from the others
+            if let Err(e) = self.send_message(message).await {
+                self.logger.warn(format!(
+                    "Failed to send NODE_INFO_DIFF to peer {peer_id}: {e}"
+                ));
+                continue;
+            }
+            self.logger.info(format!(
+                "Sent NODE_INFO_DIFF message to peer {peer_id}: {} added, {} removed",
+                diff.added_services.len(),
+                diff.removed_services.len()
+            ));
+        }
+        Ok(())
+    }
+
+    /// Apply a service diff received from a peer to its node info
+    ///
+    /// INTENTION: Publish the updated node info exactly as if the peer had
+    /// sent it in full. A diff that does not apply to the node info we hold
+    /// (e.g. one sent while we were handshaking, or after we missed an
+    /// earlier diff) is dropped, and the peer is asked for its full node info
+    /// with a NODE_INFO_REQUEST.
+    async fn handle_node_info_diff(
+        self: &Arc<Self>,
+        message: &NetworkMessage,
+    ) -> Result<(), NetworkError> {
+        let Some(payload) = message.payloads.first() else {
+            return Ok(());
+        };
+        let diff: NodeInfoDiff = bincode::deserialize(&payload.value_bytes).map_err(|e| {
+            NetworkError::MessageError(
+                ErrorCode::InvalidMessage,
+                format!("Failed to deserialize node info diff: {e}"),
+            )
+        })?;
+        let Some(peer_state) = self.connection_pool.get_peer(&message.source) else {
+            return Ok(());
+        };
+        let Some(mut node_info) = peer_state.node_info.read().await.clone() else {
+            return Ok(());
+        };
+        if let Err(e) = node_info.apply_diff(&diff) {
+            self.logger.warn(format!(
+                "Dropping node info diff from {}, requesting full node info: {e}",
+                message.source
+            ));
+            let request = NetworkMessage {
+                source: self.node_id.clone(),
+                destination: message.source.clone(),
+                message_type: "NODE_INFO_REQUEST".to_string(),
+                payloads: vec![NetworkMessagePayloadItem::new(
+                    String::new(),
+                    Vec::new(),
+                    String::new(),
+                )],
+                signature: None,
+                hop_count: 0,
+                visited_peers: Vec::new(),
+                auth_tag: None,
+                expires_at: None,
+            };
+            return self.send_message(request).await;
         }
+        peer_state.set_node_info(node_info.clone()).await;
+        // Send to the channel - ignore errors if there are no subscribers
+        let _ = self.peer_node_info_sender.send(node_info);
         Ok(())
     }
 
+    /// Node info sent in handshakes
+    fn local_node(&self) -> NodeInfo {
+        self.local_node
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// Networks of a peer, empty until its handshake is received
+    async fn peer_network_ids(&self, peer_id: &PeerId) -> Vec<String> {
+        match self.connection_pool.get_peer(peer_id) {
+            Some(peer_state) => peer_state
+                .node_info
+                .read()
+                .await
+                .as_ref()
+                .map(|node_info| node_info.network_ids.clone())
+                .unwrap_or_default(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Node info as advertised to `peer_id`
+    ///
+    /// Services restricted to some networks are only included once the
+    /// peer's networks are known from its handshake.
+    async fn local_node_for(&self, peer_id: &PeerId) -> NodeInfo {
+        self.local_node()
+            .visible_to(&self.peer_network_ids(peer_id).await)
+    }
+
     fn get_local_address(self: &Arc<Self>) -> String {
-        self.bind_addr.to_string()
+        self.local_addr().to_string()
+    }
+
+    /// Address the transport is bound to, as last updated by a migration
+    fn local_addr(&self) -> SocketAddr {
+        *self.bind_addr.read().unwrap_or_else(|e| e.into_inner())
     }
 
     /// Perform handshake with a peer after connection is established
@@ -811,6 +1614,7 @@ impl QuicTransportImpl {
     async fn handshake_peer(self: &Arc<Self>, discovery_msg: PeerInfo) -> Result<(), NetworkError> {
         if !self.running.load(Ordering::Relaxed) {
             return Err(NetworkError::TransportError(
+                ErrorCode::NotRunning,
                 "Transport not running".to_string(),
             ));
         }
@@ -823,9 +1627,10 @@ impl QuicTransportImpl {
 
         // Check if we're connected to this peer
         if !self.connection_pool.is_peer_connected(&peer_id).await {
-            return Err(NetworkError::ConnectionError(format!(
-                "Not connected to peer {peer_id}, cannot perform handshake"
-            )));
+            return Err(NetworkError::ConnectionError(
+                ErrorCode::NotConnected,
+                format!("Not connected to peer {peer_id}, cannot perform handshake"),
+            ));
         }
 
         let correlation_id = format!(
@@ -837,18 +1642,38 @@ impl QuicTransportImpl {
                 .as_millis()
         );
 
-        // Create a handshake message containing our node info
+        // Create a handshake message containing our node info and capabilities
+        let capabilities_payload = self.capabilities_payload(correlation_id.clone())?;
         let handshake_message = NetworkMessage {
             source: self.node_id.clone(),
             destination: peer_id.clone(),
             message_type: "NODE_INFO_HANDSHAKE".to_string(),
-            payloads: vec![NetworkMessagePayloadItem {
-                path: "".to_string(),
-                value_bytes: bincode::serialize(&self.local_node).map_err(|e| {
-                    NetworkError::MessageError(format!("Failed to serialize node info: {e}"))
-                })?,
-                correlation_id,
-            }],
+            payloads: vec![
+                NetworkMessagePayloadItem {
+                    path: "".to_string(),
+                    value_bytes: bincode::serialize(&self.local_node_for(&peer_id).await).map_err(
+                        |e| {
+                            NetworkError::MessageError(
+                                ErrorCode::SerializationFailed,
+                                format!("Failed to serialize node info: {e}"),
+                            )
+                        },
+                    )?,
+                    correlation_id,
+                    error_code: None,
+                    sequence: None,
+                    dedup_id: None,
+                    format: None,
+                    chunk_index: None,
+                    total_chunks: None,
+                },
+                capabilities_payload,
+            ],
+            signature: None,
+            hop_count: 0,
+            visited_peers: Vec::new(),
+            auth_tag: None,
+            expires_at: None,
         };
 
         // Send the handshake message
@@ -863,6 +1688,131 @@ impl QuicTransportImpl {
         Ok(())
     }
 
+    /// Advertise the services restricted to the peer's networks after a handshake we started
+    ///
+    /// INTENTION: Our handshake was sent before we knew the peer's networks, so
+    /// it left out every restricted service. Once the response tells us the
+    /// networks, send the node info again if the peer may see more services.
+    async fn send_restricted_services(
+        self: &Arc<Self>,
+        peer_id: &PeerId,
+        peer_node_info: &NodeInfo,
+    ) -> Result<(), NetworkError> {
+        let local = self.local_node();
+        let visible = local.visible_to(&peer_node_info.network_ids);
+        if visible.services.len() > local.visible_to(&[]).services.len() {
+            self.send_node_info_update(peer_id, &visible).await?;
+        }
+        Ok(())
+    }
+
+    /// Payload advertising our capabilities in a handshake message
+    fn capabilities_payload(
+        &self,
+        correlation_id: String,
+    ) -> Result<NetworkMessagePayloadItem, NetworkError> {
+        let value_bytes = bincode::serialize(&self.options.capabilities).map_err(|e| {
+            NetworkError::MessageError(
+                ErrorCode::SerializationFailed,
+                format!("Failed to serialize capabilities: {e}"),
+            )
+        })?;
+        Ok(NetworkMessagePayloadItem::new(
+            CAPABILITIES_PAYLOAD_PATH.to_string(),
+            value_bytes,
+            correlation_id,
+        ))
+    }
+
+    /// Capabilities advertised in a handshake message
+    ///
+    /// A peer that sent no capabilities is assumed to have the defaults.
+    fn remote_capabilities(message: &NetworkMessage) -> Result<NodeCapabilities, NetworkError> {
+        match message
+            .payloads
+            .iter()
+            .find(|payload| payload.path == CAPABILITIES_PAYLOAD_PATH)
+        {
+            Some(payload) => bincode::deserialize::<NodeCapabilities>(&payload.value_bytes)
+                .map_err(|e| {
+                    NetworkError::MessageError(
+                        ErrorCode::InvalidMessage,
+                        format!("Failed to deserialize capabilities: {e}"),
+                    )
+                }),
+            None => Ok(NodeCapabilities::default()),
+        }
+    }
+
+    /// Negotiate capabilities with the sender of a handshake message
+    ///
+    /// INTENTION: Keep the capabilities both sides support on the peer state.
+    async fn negotiate_capabilities(
+        &self,
+        peer_id: &PeerId,
+        remote: &NodeCapabilities,
+        peer_state: &PeerState,
+    ) {
+        let negotiated = self.options.capabilities.negotiate(remote);
+        self.logger.debug(format!(
+            "Negotiated capabilities with {peer_id}: {negotiated:?}"
+        ));
+        peer_state.set_capabilities(negotiated).await;
+    }
+
+    /// Check the protocol version and payload formats of the sender of a
+    /// handshake message
+    ///
+    /// INTENTION: Disconnect a peer whose protocol version, advertised in its
+    /// capabilities, is further from ours than `max_version_skew`, or whose
+    /// payload formats do not match ours, before its node info is published,
+    /// so it never becomes a known peer. A peer with another but compatible
+    /// version is accepted with a warning.
+    async fn check_peer_compatibility(
+        self: &Arc<Self>,
+        peer_id: &PeerId,
+        remote: &NodeCapabilities,
+    ) -> Result<(), NetworkError> {
+        if let Err((close_code, e)) = Self::check_compatibility(&self.options, remote) {
+            self.logger
+                .warn(format!("🚫 [QuicTransport] Rejecting peer {peer_id}: {e}"));
+            if let Some(peer_state) = self.connection_pool.get_peer(peer_id) {
+                if let Some(connection) = peer_state.get_connection().await {
+                    connection.close(close_code, e.message().as_bytes());
+                }
+            }
+            let _ = self.disconnect(peer_id.clone()).await;
+            return Err(e);
+        }
+        let local = self.options.capabilities.protocol_version;
+        let remote = remote.protocol_version;
+        if local != remote {
+            self.logger.warn(format!(
+                "Peer {peer_id} speaks protocol version {remote}, this node speaks {local}; consider upgrading the older node"
+            ));
+        }
+        Ok(())
+    }
+
+    /// Check that we can talk to a peer advertising `remote` capabilities
+    ///
+    /// Returns the application close code for the connection along with the
+    /// error when the protocol versions are too far apart or the peer cannot
+    /// decode our payloads.
+    fn check_compatibility(
+        options: &QuicTransportOptions,
+        remote: &NodeCapabilities,
+    ) -> Result<(), (quinn::VarInt, NetworkError)> {
+        check_protocol_version(
+            options.capabilities.protocol_version,
+            remote.protocol_version,
+            options.max_version_skew,
+        )
+        .map_err(|e| (INCOMPATIBLE_VERSION_CODE, e))?;
+        check_payload_format(&options.capabilities, remote)
+            .map_err(|e| (INCOMPATIBLE_FORMAT_CODE, e))
+    }
+
     /// Process an incoming message
     ///
     /// INTENTION: Route an incoming message to registered handlers.
@@ -870,6 +1820,44 @@ impl QuicTransportImpl {
         self: &Arc<Self>,
         message: NetworkMessage,
     ) -> Result<(), NetworkError> {
+        if self.options.verify_message_signatures {
+            if let Err(e) = message.verify_source_signature() {
+                self.logger.warn(format!(
+                    "🚫 [QuicTransport] Rejecting {} message claiming source {}: {e}",
+                    message.message_type, message.source
+                ));
+                return Err(e);
+            }
+        }
+
+        // Each chunk was verified on its own; the message is processed once complete
+        let message = match self.chunk_reassembler.add(message) {
+            Ok(Some(message)) => message,
+            Ok(None) => return Ok(()),
+            Err(e) => {
+                self.logger
+                    .warn(format!("🚫 [QuicTransport] Dropping chunked message: {e}"));
+                self.metrics.record_error(&e);
+                return Err(e);
+            }
+        };
+
+        if message.message_type == "NODE_INFO_DIFF" {
+            self.logger.debug(format!(
+                "Received message from {} with type: {}",
+                message.source, message.message_type
+            ));
+            return self.handle_node_info_diff(&message).await;
+        }
+
+        // A peer that could not apply our last diff asks for the full node info
+        if message.message_type == "NODE_INFO_REQUEST" {
+            let node_info = self.local_node_for(&message.source).await;
+            return self
+                .send_node_info_update(&message.source, &node_info)
+                .await;
+        }
+
         // Special handling for handshake messages
         if message.message_type == "NODE_INFO_HANDSHAKE"
             || message.message_type == "NODE_INFO_HANDSHAKE_RESPONSE"
@@ -889,8 +1877,26 @@ impl QuicTransportImpl {
                             message.source, peer_node_info
                         ));
 
+                        // Every handshake is checked, whether or not the peer has a state yet
+                        let remote_capabilities = if message.message_type != "NODE_INFO_UPDATE" {
+                            let remote_capabilities = Self::remote_capabilities(&message)?;
+                            self.check_peer_compatibility(&message.source, &remote_capabilities)
+                                .await?;
+                            Some(remote_capabilities)
+                        } else {
+                            None
+                        };
+
                         // Store the node info in the peer state
                         if let Some(peer_state) = self.connection_pool.get_peer(&message.source) {
+                            if let Some(remote_capabilities) = &remote_capabilities {
+                                self.negotiate_capabilities(
+                                    &message.source,
+                                    remote_capabilities,
+                                    &peer_state,
+                                )
+                                .await;
+                            }
                             peer_state.set_node_info(peer_node_info.clone()).await;
 
                             if message.message_type == "NODE_INFO_HANDSHAKE" {
@@ -899,18 +1905,36 @@ impl QuicTransportImpl {
                                     source: self.node_id.clone(),
                                     destination: message.source.clone(),
                                     message_type: "NODE_INFO_HANDSHAKE_RESPONSE".to_string(),
-                                    payloads: vec![NetworkMessagePayloadItem {
-                                        // Preserve the original path from the request
-                                        path: payload.path.clone(),
-                                        value_bytes: bincode::serialize(&self.local_node).map_err(
-                                            |e| {
-                                                NetworkError::MessageError(format!(
-                                                    "Failed to serialize node info: {e}"
-                                                ))
-                                            },
-                                        )?,
-                                        correlation_id: payload.correlation_id.clone(),
-                                    }],
+                                    payloads: vec![
+                                        NetworkMessagePayloadItem {
+                                            // Preserve the original path from the request
+                                            path: payload.path.clone(),
+                                            value_bytes: bincode::serialize(
+                                                &self
+                                                    .local_node()
+                                                    .visible_to(&peer_node_info.network_ids),
+                                            )
+                                            .map_err(|e| {
+                                                NetworkError::MessageError(
+                                                    ErrorCode::SerializationFailed,
+                                                    format!("Failed to serialize node info: {e}"),
+                                                )
+                                            })?,
+                                            correlation_id: payload.correlation_id.clone(),
+                                            error_code: None,
+                                            sequence: None,
+                                            dedup_id: None,
+                                            format: None,
+                                            chunk_index: None,
+                                            total_chunks: None,
+                                        },
+                                        self.capabilities_payload(payload.correlation_id.clone())?,
+                                    ],
+                                    signature: None,
+                                    hop_count: 0,
+                                    visited_peers: Vec::new(),
+                                    auth_tag: None,
+                                    expires_at: None,
                                 };
 
                                 // Send the response
@@ -919,9 +1943,16 @@ impl QuicTransportImpl {
                                     "Sent handshake response to {}",
                                     message.source
                                 ));
+                            } else if message.message_type == "NODE_INFO_HANDSHAKE_RESPONSE" {
+                                self.send_restricted_services(&message.source, &peer_node_info)
+                                    .await?;
                             }
                         }
 
+                        if message.message_type != "NODE_INFO_UPDATE" {
+                            self.notify_connected(&message.source, &peer_node_info);
+                        }
+
                         // Send to the channel - ignore errors if there are no subscribers
                         let _ = self.peer_node_info_sender.send(peer_node_info);
                     }
@@ -941,6 +1972,14 @@ impl QuicTransportImpl {
             ));
         }
 
+        if let Err(e) = message.check_expiry() {
+            self.logger.debug(format!(
+                "Dropping {} message from {}: {e}",
+                message.message_type, message.source
+            ));
+            return Ok(());
+        }
+
         // Get a read lock on the handlers
         match self.message_handler.read() {
             Ok(handler) => {
@@ -950,6 +1989,7 @@ impl QuicTransportImpl {
                 Ok(())
             }
             Err(_) => Err(NetworkError::TransportError(
+                ErrorCode::TransportFailed,
                 "Failed to acquire read lock on message handlers".to_string(),
             )),
         }
@@ -1004,6 +2044,16 @@ impl QuicTransportImpl {
                     // **STEP 2**: Read and parse the handshake message to get real peer ID
                     match inner_arc.read_handshake_message(recv_stream).await {
                         Ok(message) => {
+                            if inner_arc.options.verify_message_signatures {
+                                if let Err(e) = message.verify_source_signature() {
+                                    logger.warn(format!(
+                                        "🚫 [QuicTransport] Handshake from {remote_addr} failed source authentication: {e}"
+                                    ));
+                                    connection.close(4u32.into(), b"Invalid signature");
+                                    return;
+                                }
+                            }
+
                             if message.message_type == "NODE_INFO_HANDSHAKE" {
                                 // **STEP 3**: Extract the real peer ID from the handshake message
                                 let real_peer_id = message.source.clone();
@@ -1012,6 +2062,32 @@ impl QuicTransportImpl {
                                     "✅ [QuicTransport] Identified peer: {real_peer_id} from {remote_addr}"
                                 ));
 
+                                if let Some(peer_filter) = &inner_arc.options.peer_filter {
+                                    if let Err(e) = peer_filter.check(&real_peer_id) {
+                                        logger.warn(format!(
+                                            "🚫 [QuicTransport] Refusing connection from {real_peer_id} at {remote_addr}: {e}"
+                                        ));
+                                        connection
+                                            .close(PEER_NOT_ALLOWED_CODE, b"Peer not allowed");
+                                        return;
+                                    }
+                                }
+
+                                // Incompatible peers get no peer state at all
+                                let compatible = match Self::remote_capabilities(&message) {
+                                    Ok(remote) => {
+                                        Self::check_compatibility(&inner_arc.options, &remote)
+                                    }
+                                    Err(e) => Err((INCOMPATIBLE_VERSION_CODE, e)),
+                                };
+                                if let Err((close_code, e)) = compatible {
+                                    logger.warn(format!(
+                                        "🚫 [QuicTransport] Refusing connection from {real_peer_id} at {remote_addr}: {e}"
+                                    ));
+                                    connection.close(close_code, e.message().as_bytes());
+                                    return;
+                                }
+
                                 // **STEP 4**: Check if we already have a connection to this peer
                                 if inner_arc
                                     .connection_pool
@@ -1035,11 +2111,19 @@ impl QuicTransportImpl {
                                         real_peer_id.clone(),
                                         remote_addr.to_string(),
                                         inner_arc.options.max_idle_streams_per_peer,
+                                        inner_arc.options.stream_pool_options,
                                         inner_arc.logger.clone(),
                                     );
 
                                     // Set the connection for the real peer
-                                    peer_state.set_connection(connection.clone()).await;
+                                    inner_arc
+                                        .connection_pool
+                                        .set_peer_connection(&peer_state, connection.clone())
+                                        .await;
+                                    inner_arc
+                                        .metrics
+                                        .connections_established
+                                        .fetch_add(1, Ordering::Relaxed);
 
                                     // **STEP 6**: Process the handshake message
                                     if let Err(e) =
@@ -1072,6 +2156,7 @@ impl QuicTransportImpl {
                             }
                         }
                         Err(e) => {
+                            inner_arc.metrics.record_error(&e);
                             logger.error(format!(
                                 "❌ [QuicTransport] Failed to read handshake from {remote_addr}: {e}"
                             ));
@@ -1098,15 +2183,19 @@ impl QuicTransportImpl {
         // Read message length (4 bytes)
         let mut len_bytes = [0u8; 4];
         recv_stream.read_exact(&mut len_bytes).await.map_err(|e| {
-            NetworkError::MessageError(format!("Failed to read handshake message length: {e}"))
+            NetworkError::ConnectionError(
+                ErrorCode::HandshakeFailed,
+                format!("Failed to read handshake message length: {e}"),
+            )
         })?;
 
         let message_len = u32::from_be_bytes(len_bytes) as usize;
         if message_len > 1024 * 1024 {
             // 1MB limit
-            return Err(NetworkError::MessageError(format!(
-                "Handshake message too large: {message_len} bytes"
-            )));
+            return Err(NetworkError::MessageError(
+                ErrorCode::MessageTooLarge,
+                format!("Handshake message too large: {message_len} bytes"),
+            ));
         }
 
         // Read the message data
@@ -1115,15 +2204,33 @@ impl QuicTransportImpl {
             .read_exact(&mut message_data)
             .await
             .map_err(|e| {
-                NetworkError::MessageError(format!("Failed to read handshake message data: {e}"))
+                NetworkError::ConnectionError(
+                    ErrorCode::HandshakeFailed,
+                    format!("Failed to read handshake message data: {e}"),
+                )
             })?;
 
+        self.record_received(message_len);
+
         // Deserialize the message
         bincode::deserialize(&message_data).map_err(|e| {
-            NetworkError::MessageError(format!("Failed to deserialize handshake message: {e}"))
+            NetworkError::ConnectionError(
+                ErrorCode::HandshakeFailed,
+                format!("Failed to deserialize handshake message: {e}"),
+            )
         })
     }
 
+    /// Count a received message frame (length prefix plus payload)
+    fn record_received(&self, message_len: usize) {
+        self.metrics
+            .bytes_received
+            .fetch_add((4 + message_len) as u64, Ordering::Relaxed);
+        self.metrics
+            .messages_received
+            .fetch_add(1, Ordering::Relaxed);
+    }
+
     /// Start the persistent message receiver task for an identified peer
     ///
     /// INTENTION: Handle ongoing message processing for a peer with known identity
@@ -1168,6 +2275,7 @@ impl QuicTransportImpl {
                                     .receive_message(peer_id_clone.clone(), recv_stream, None)
                                     .await
                                 {
+                                    inner_arc.metrics.record_error(&e);
                                     logger.error(format!(
                                         "Error receiving unidirectional message from {peer_id_clone}: {e}"
                                     ));
@@ -1196,6 +2304,7 @@ impl QuicTransportImpl {
                                     .receive_message(peer_id_clone.clone(), recv_stream, Some(send_stream))
                                     .await
                                 {
+                                    inner_arc.metrics.record_error(&e);
                                     logger.error(format!(
                                         "Error receiving bidirectional message from {peer_id_clone}: {e}"
                                     ));
@@ -1231,12 +2340,41 @@ impl QuicTransportImpl {
                 "🔚 [QuicTransport] Message receiver stopped for peer {peer_id_clone}"
             ));
 
+            inner_arc
+                .metrics
+                .connections_dropped
+                .fetch_add(1, Ordering::Relaxed);
+
+            // A peer that found us idle reconnects lazily too, so keep its addresses
+            if let Some(quinn::ConnectionError::ApplicationClosed(close)) =
+                connection.close_reason()
+            {
+                if close.error_code == IDLE_DISCONNECT_CODE {
+                    inner_arc.remember_idle_peer(&peer_state).await;
+                } else if close.error_code == INCOMPATIBLE_VERSION_CODE {
+                    logger.warn(format!(
+                        "🚫 [QuicTransport] Peer {peer_id_clone} disconnected: incompatible protocol version (local {}, max skew {})",
+                        inner_arc.options.capabilities.protocol_version, inner_arc.options.max_version_skew
+                    ));
+                } else if close.error_code == INCOMPATIBLE_FORMAT_CODE {
+                    logger.warn(format!(
+                        "🚫 [QuicTransport] Peer {peer_id_clone} disconnected: incompatible payload formats (local {:?})",
+                        inner_arc.options.capabilities.supported_formats
+                    ));
+                } else if close.error_code == PEER_NOT_ALLOWED_CODE {
+                    logger.warn(format!(
+                        "🚫 [QuicTransport] Peer {peer_id_clone} disconnected: this node is not allowed by its peer filter"
+                    ));
+                }
+            }
+
             // Clean up peer state when connection ends
             inner_arc
                 .connection_pool
                 .remove_peer(&peer_id_clone)
                 .await
                 .ok();
+            inner_arc.notify_disconnected(&peer_id_clone);
         });
     }
 
@@ -1282,15 +2420,20 @@ impl QuicTransportImpl {
         // Read message length (4 bytes)
         let mut len_bytes = [0u8; 4];
         recv_stream.read_exact(&mut len_bytes).await.map_err(|e| {
-            NetworkError::MessageError(format!("Failed to read message length: {e}"))
+            NetworkError::MessageError(
+                ErrorCode::StreamFailed,
+                format!("Failed to read message length: {e}"),
+            )
         })?;
 
+        // Peers size their frames for the smaller of both limits, see
+        // `peer_max_message_size`
         let message_len = u32::from_be_bytes(len_bytes) as usize;
-        if message_len > 1024 * 1024 {
-            // 1MB limit
-            return Err(NetworkError::MessageError(format!(
-                "Message too large: {message_len} bytes"
-            )));
+        if message_len > self.options.max_message_size {
+            return Err(NetworkError::MessageError(
+                ErrorCode::MessageTooLarge,
+                format!("Message too large: {message_len} bytes"),
+            ));
         }
 
         // Read the message data
@@ -1298,11 +2441,21 @@ impl QuicTransportImpl {
         recv_stream
             .read_exact(&mut message_data)
             .await
-            .map_err(|e| NetworkError::MessageError(format!("Failed to read message data: {e}")))?;
+            .map_err(|e| {
+                NetworkError::MessageError(
+                    ErrorCode::StreamFailed,
+                    format!("Failed to read message data: {e}"),
+                )
+            })?;
+        self.record_received(message_len);
+        self.record_activity(&peer_id);
 
         // Deserialize the message
         let message: NetworkMessage = bincode::deserialize(&message_data).map_err(|e| {
-            NetworkError::MessageError(format!("Failed to deserialize message: {e}"))
+            NetworkError::MessageError(
+                ErrorCode::SerializationFailed,
+                format!("Failed to deserialize message: {e}"),
+            )
         })?;
 
         self.logger.debug(format!(
@@ -1316,6 +2469,10 @@ impl QuicTransportImpl {
                 .unwrap_or(&"".to_string())
         ));
 
+        if let Some(peer_state) = self.connection_pool.get_peer(&peer_id) {
+            peer_state.track_message(&message);
+        }
+
         // For bidirectional streams with requests, store the send stream for direct response
         if let Some(send_stream) = send_stream {
             if let Some(payload) = message.payloads.first() {
@@ -1380,6 +2537,13 @@ impl QuicTransportImpl {
     ///
     /// INTENTION: Remove old correlations to prevent memory leaks
     async fn cleanup_expired_correlations(&self) {
+        for error in self.chunk_reassembler.discard_expired() {
+            self.logger.warn(format!(
+                "🧹 [QuicTransport] Discarded incomplete chunked message: {error}"
+            ));
+            self.metrics.record_error(&error);
+        }
+
         let now = std::time::Instant::now();
         let timeout = Duration::from_secs(300); // 5 minutes
 
@@ -1428,11 +2592,17 @@ impl QuicTransportImpl {
 
         // Get certificates, private key, and verifier from options
         let certificates = self.options.certificates().ok_or_else(|| {
-            NetworkError::ConfigurationError("No certificates provided".to_string())
+            NetworkError::ConfigurationError(
+                ErrorCode::MissingCredentials,
+                "No certificates provided".to_string(),
+            )
         })?;
 
         let private_key = self.options.private_key().ok_or_else(|| {
-            NetworkError::ConfigurationError("No private key provided".to_string())
+            NetworkError::ConfigurationError(
+                ErrorCode::MissingCredentials,
+                "No private key provided".to_string(),
+            )
         })?;
 
         self.logger.info(format!(
@@ -1464,7 +2634,10 @@ impl QuicTransportImpl {
         let mut server_config =
             ServerConfig::with_single_cert(certificates.clone(), private_key.clone_key()).map_err(
                 |e| {
-                    NetworkError::ConfigurationError(format!("Failed to create server config: {e}"))
+                    NetworkError::ConfigurationError(
+                        ErrorCode::InvalidConfiguration,
+                        format!("Failed to create server config: {e}"),
+                    )
                 },
             )?;
 
@@ -1480,9 +2653,10 @@ impl QuicTransportImpl {
         let mut client_config = ClientConfig::new(Arc::new(
             quinn::crypto::rustls::QuicClientConfig::try_from(rustls_client_config).map_err(
                 |e| {
-                    NetworkError::ConfigurationError(format!(
-                        "Failed to convert rustls config: {e}"
-                    ))
+                    NetworkError::ConfigurationError(
+                        ErrorCode::InvalidConfiguration,
+                        format!("Failed to convert rustls config: {e}"),
+                    )
                 },
             )?,
         ));
@@ -1510,20 +2684,48 @@ impl QuicTransportImpl {
 
         self.logger.info(format!(
             "Starting QUIC transport on {bind_addr}",
-            bind_addr = self.bind_addr
+            bind_addr = self.local_addr()
         ));
 
         // Create configurations for the QUIC endpoint
         let (server_config, client_config) = self.create_quinn_configs()?;
 
         // Create the endpoint with the server configuration
-        let bind_addr =
-            SocketAddr::new(IpAddr::V4(Ipv4Addr::new(0, 0, 0, 0)), self.bind_addr.port());
+        let bind_addr = SocketAddr::new(
+            IpAddr::V4(Ipv4Addr::new(0, 0, 0, 0)),
+            self.local_addr().port(),
+        );
+        let tunneling = self.options.proxy.is_some() || self.options.accept_tunneled_connections;
+        let migrating = self.options.enable_connection_migration && !tunneling;
         self.logger
             .info(format!("Creating endpoint bound to {bind_addr}"));
 
-        let mut endpoint = Endpoint::server(server_config, bind_addr)
-            .map_err(|e| NetworkError::TransportError(format!("Failed to create endpoint: {e}")))?;
+        let mut endpoint = if tunneling {
+            self.create_tunneling_endpoint(server_config, bind_addr)
+                .await?
+        } else if migrating {
+            // The socket must allow the next one to bind the same port
+            let socket = bind_rebindable_socket(bind_addr)?;
+            Endpoint::new(
+                quinn::EndpointConfig::default(),
+                Some(server_config),
+                socket,
+                Arc::new(quinn::TokioRuntime),
+            )
+            .map_err(|e| {
+                NetworkError::TransportError(
+                    ErrorCode::TransportFailed,
+                    format!("Failed to create endpoint: {e}"),
+                )
+            })?
+        } else {
+            Endpoint::server(server_config, bind_addr).map_err(|e| {
+                NetworkError::TransportError(
+                    ErrorCode::TransportFailed,
+                    format!("Failed to create endpoint: {e}"),
+                )
+            })?
+        };
 
         endpoint.set_default_client_config(client_config);
 
@@ -1533,6 +2735,9 @@ impl QuicTransportImpl {
         let mut endpoint_guard = self.endpoint.lock().await;
         *endpoint_guard = Some(endpoint.clone());
 
+        // The accept loop stops once this is false, and may first run at any
+        // of the awaits below
+        self.running.store(true, Ordering::Relaxed);
         let inner_arc = Arc::clone(self);
         let task = tokio::spawn(async move {
             inner_arc.accept_connections(endpoint).await;
@@ -1541,12 +2746,263 @@ impl QuicTransportImpl {
         let mut tasks = background_tasks.lock().await;
         tasks.push(task);
 
-        self.running.store(true, Ordering::Relaxed);
+        if migrating {
+            let inner_arc = Arc::clone(self);
+            let monitor = tokio::spawn(async move {
+                inner_arc.monitor_local_address().await;
+            });
+            *self.migration_monitor.lock().await = Some(monitor);
+        }
+
+        if let Some(timeout) = self.options.idle_disconnect_timeout {
+            let inner_arc = Arc::clone(self);
+            let monitor = tokio::spawn(async move {
+                inner_arc.monitor_idle_peers(timeout).await;
+            });
+            *self.idle_monitor.lock().await = Some(monitor);
+        }
+
         self.logger.info("QUIC transport started successfully");
 
         Ok(())
     }
 
+    /// Create an endpoint whose socket can also carry QUIC over proxy tunnels
+    ///
+    /// INTENTION: Keep direct UDP peers working next to tunneled ones. When
+    /// accepting tunneled connections, a TCP listener on the QUIC port
+    /// attaches every incoming tunnel to the endpoint's socket.
+    async fn create_tunneling_endpoint(
+        self: &Arc<Self>,
+        server_config: ServerConfig,
+        bind_addr: SocketAddr,
+    ) -> Result<Endpoint, NetworkError> {
+        let endpoint_error = |e: std::io::Error| {
+            NetworkError::TransportError(
+                ErrorCode::TransportFailed,
+                format!("Failed to create endpoint: {e}"),
+            )
+        };
+
+        if let Some(proxy) = &self.options.proxy {
+            self.logger.warn(format!(
+                "QUIC connections are tunneled over TCP through {} proxy {}; transport is degraded",
+                proxy.kind, proxy.addr
+            ));
+        }
+
+        let runtime = Arc::new(quinn::TokioRuntime);
+        let udp_socket = std::net::UdpSocket::bind(bind_addr).map_err(endpoint_error)?;
+        let udp_socket =
+            quinn::Runtime::wrap_udp_socket(&*runtime, udp_socket).map_err(endpoint_error)?;
+        let tunnel_socket = Arc::new(TunnelSocket::new(udp_socket));
+        let endpoint = Endpoint::new_with_abstract_socket(
+            quinn::EndpointConfig::default(),
+            Some(server_config),
+            tunnel_socket.clone(),
+            runtime,
+        )
+        .map_err(endpoint_error)?;
+        *self.tunnel_socket.lock().await = Some(tunnel_socket.clone());
+
+        if self.options.accept_tunneled_connections {
+            // Same port as the UDP socket, also when bind_addr asked for any port
+            let quic_port = endpoint.local_addr().map_err(endpoint_error)?.port();
+            let listen_addr = SocketAddr::new(bind_addr.ip(), quic_port);
+            let listener = tokio::net::TcpListener::bind(listen_addr)
+                .await
+                .map_err(endpoint_error)?;
+            self.logger.info(format!(
+                "Accepting tunneled QUIC connections on {listen_addr}"
+            ));
+            let logger = self.logger.clone();
+            let task = tokio::spawn(async move {
+                loop {
+                    match listener.accept().await {
+                        Ok((stream, remote_addr)) => {
+                            logger.debug(format!("Accepted QUIC tunnel from {remote_addr}"));
+                            tunnel_socket.attach(remote_addr, stream);
+                        }
+                        Err(e) => {
+                            logger.warn(format!("Failed to accept QUIC tunnel: {e}"));
+                        }
+                    }
+                }
+            });
+            *self.tunnel_listener.lock().await = Some(task);
+        }
+
+        Ok(endpoint)
+    }
+
+    /// Open a tunnel to `peer_addr` through the configured proxy, if any
+    async fn tunnel_through_proxy(&self, peer_addr: SocketAddr) -> Result<(), NetworkError> {
+        let Some(proxy_config) = &self.options.proxy else {
+            return Ok(());
+        };
+        let Some(tunnel_socket) = self.tunnel_socket.lock().await.clone() else {
+            return Err(NetworkError::TransportError(
+                ErrorCode::NotRunning,
+                "Transport not initialized".to_string(),
+            ));
+        };
+        let stream = proxy::open_tunnel(proxy_config, peer_addr).await?;
+        tunnel_socket.attach(peer_addr, stream);
+        self.logger.debug(format!(
+            "Tunneling QUIC to {peer_addr} through {} proxy {}",
+            proxy_config.kind, proxy_config.addr
+        ));
+        Ok(())
+    }
+
+    /// Poll the local address and migrate the connections when it changes
+    async fn monitor_local_address(self: &Arc<Self>) {
+        let mut last_ip = None;
+        loop {
+            tokio::time::sleep(self.options.migration_check_interval).await;
+            // Nothing to migrate, and no route to check, without connections
+            let Some(ip) = self.current_local_ip().await else {
+                continue;
+            };
+            if let Some(old_ip) = last_ip.filter(|old_ip| *old_ip != ip) {
+                self.logger
+                    .info(format!("Local address changed from {old_ip} to {ip}"));
+                if let Err(e) = self.migrate_connections(Some(ip)).await {
+                    self.logger
+                        .warn(format!("Failed to migrate connections: {e}"));
+                }
+            }
+            last_ip = Some(ip);
+        }
+    }
+
+    /// Disconnect the peers that exchanged no message for `timeout`
+    ///
+    /// Runs every `timeout / 2`; peers connected since the last run start
+    /// their idle time when first seen. Peers with requests awaiting their
+    /// response or streams still open are not idle, however long ago their
+    /// last message was.
+    async fn monitor_idle_peers(self: &Arc<Self>, timeout: Duration) {
+        loop {
+            tokio::time::sleep(timeout / 2).await;
+            let now = std::time::Instant::now();
+            for peer_id in self.connection_pool.get_connected_peers().await {
+                let last_message_at = *self.last_message_at.entry(peer_id.clone()).or_insert(now);
+                if now.duration_since(last_message_at) > timeout
+                    && !self.is_peer_busy(&peer_id).await
+                {
+                    self.disconnect_idle_peer(peer_id).await;
+                }
+            }
+        }
+    }
+
+    /// Whether a peer has requests in flight or streams open
+    async fn is_peer_busy(&self, peer_id: &PeerId) -> bool {
+        let Some(peer_state) = self.connection_pool.get_peer(peer_id) else {
+            return false;
+        };
+        peer_state.has_in_flight_requests() || peer_state.stream_pool.stats().await.active > 0
+    }
+
+    /// Close the connection to an idle peer, keeping its addresses to reconnect
+    async fn disconnect_idle_peer(self: &Arc<Self>, peer_id: PeerId) {
+        let Some(peer_state) = self.connection_pool.get_peer(&peer_id) else {
+            return;
+        };
+        self.logger
+            .info(format!("Disconnecting idle peer {peer_id}"));
+        self.remember_idle_peer(&peer_state).await;
+        if let Some(connection) = peer_state.get_connection().await {
+            connection.close(IDLE_DISCONNECT_CODE, b"Idle timeout");
+        }
+        self.last_message_at.remove(&peer_id);
+        if let Err(e) = self.disconnect(peer_id.clone()).await {
+            self.logger
+                .warn(format!("Failed to disconnect idle peer {peer_id}: {e}"));
+        }
+    }
+
+    /// Keep the addresses of a peer disconnected for being idle
+    async fn remember_idle_peer(&self, peer_state: &PeerState) {
+        let addresses = match peer_state.node_info.read().await.as_ref() {
+            Some(node_info) if !node_info.addresses.is_empty() => node_info.addresses.clone(),
+            _ => vec![peer_state.address.clone()],
+        };
+        self.idle_peers.insert(
+            peer_state.peer_id.clone(),
+            PeerInfo::new(peer_state.peer_id.public_key.clone(), addresses),
+        );
+    }
+
+    /// Addresses of a peer to reconnect to before sending it a message
+    ///
+    /// Returns None unless the peer was disconnected for being idle and has
+    /// not reconnected since.
+    async fn take_idle_peer(&self, peer_id: &PeerId) -> Option<PeerInfo> {
+        let (_, peer_info) = self.idle_peers.remove(peer_id)?;
+        if self.connection_pool.is_peer_connected(peer_id).await {
+            return None;
+        }
+        Some(peer_info)
+    }
+
+    /// Note that a message was sent to or received from a peer
+    fn record_activity(&self, peer_id: &PeerId) {
+        self.last_message_at
+            .insert(peer_id.clone(), std::time::Instant::now());
+    }
+
+    /// Local IP address that traffic to the connected peers leaves from
+    async fn current_local_ip(&self) -> Option<IpAddr> {
+        for peer_id in self.connection_pool.get_connected_peers().await {
+            let Some(peer_state) = self.connection_pool.get_peer(&peer_id) else {
+                continue;
+            };
+            if let Some(connection) = peer_state.get_connection().await {
+                if let Some(ip) = route_local_ip(connection.remote_address()) {
+                    return Some(ip);
+                }
+            }
+        }
+        None
+    }
+
+    /// Rebind the endpoint to a new socket, migrating every connection to it
+    ///
+    /// INTENTION: Move the connections to the current network path. The new
+    /// socket keeps the endpoint's port; `new_ip`, when known, becomes the
+    /// reported local address.
+    async fn migrate_connections(&self, new_ip: Option<IpAddr>) -> Result<(), NetworkError> {
+        let not_running = || {
+            NetworkError::TransportError(ErrorCode::NotRunning, "Transport not running".to_string())
+        };
+        let endpoint = self.endpoint.lock().await.clone().ok_or_else(not_running)?;
+        let rebind_error = |e: std::io::Error| {
+            NetworkError::TransportError(
+                ErrorCode::TransportFailed,
+                format!("Failed to rebind endpoint: {e}"),
+            )
+        };
+
+        let socket_addr = endpoint.local_addr().map_err(rebind_error)?;
+        let socket = bind_rebindable_socket(socket_addr)?;
+        endpoint.rebind(socket).map_err(rebind_error)?;
+
+        let (old_addr, new_addr) = {
+            let mut bind_addr = self.bind_addr.write().unwrap_or_else(|e| e.into_inner());
+            let old_addr = *bind_addr;
+            let ip = new_ip.unwrap_or(old_addr.ip());
+            *bind_addr = SocketAddr::new(ip, socket_addr.port());
+            (old_addr, *bind_addr)
+        };
+        let connections = self.connection_pool.get_connected_peers().await.len();
+        self.logger.info(format!(
+            "Migrated {connections} connections from {old_addr} to {new_addr}"
+        ));
+        Ok(())
+    }
+
     /// Accept incoming connections
     ///
     /// INTENTION: Listen for and handle incoming QUIC connections.
@@ -1599,6 +3055,17 @@ impl QuicTransportImpl {
             endpoint.close(0u32.into(), b"Transport stopped");
         }
 
+        if let Some(listener) = self.tunnel_listener.lock().await.take() {
+            listener.abort();
+        }
+        if let Some(monitor) = self.migration_monitor.lock().await.take() {
+            monitor.abort();
+        }
+        if let Some(monitor) = self.idle_monitor.lock().await.take() {
+            monitor.abort();
+        }
+        self.tunnel_socket.lock().await.take();
+
         let mut tasks = background_tasks.lock().await;
         for task in tasks.drain(..) {
             let _ = task.await;
@@ -1614,11 +3081,78 @@ impl QuicTransportImpl {
     async fn disconnect(self: &Arc<Self>, peer_id: PeerId) -> Result<(), NetworkError> {
         if !self.running.load(Ordering::Relaxed) {
             return Err(NetworkError::TransportError(
+                ErrorCode::NotRunning,
                 "Transport not running".to_string(),
             ));
         }
 
-        self.connection_pool.remove_peer(&peer_id).await
+        let result = self.connection_pool.remove_peer(&peer_id).await;
+        self.notify_disconnected(&peer_id);
+        result
+    }
+
+    /// Report a completed handshake to the connection callback
+    ///
+    /// Peers that already completed a handshake on their current connection
+    /// are not reported again.
+    fn notify_connected(&self, peer_id: &PeerId, node_info: &NodeInfo) {
+        let now = std::time::Instant::now();
+        let event_type = match self.peer_connections.insert(peer_id.clone(), None) {
+            None => ConnectionEventType::FirstConnect,
+            Some(Some(disconnected_at)) => ConnectionEventType::Reconnect {
+                disconnect_duration: now.duration_since(disconnected_at),
+            },
+            Some(None) => return,
+        };
+        self.fire_connection_event(ConnectionEvent {
+            peer_id: peer_id.clone(),
+            event_type,
+            node_info: Some(node_info.clone()),
+        });
+    }
+
+    /// Report a lost connection to the connection callback, once per connection
+    fn notify_disconnected(&self, peer_id: &PeerId) {
+        let disconnected = match self.peer_connections.get_mut(peer_id) {
+            Some(mut disconnected_at) if disconnected_at.is_none() => {
+                *disconnected_at = Some(std::time::Instant::now());
+                true
+            }
+            _ => false,
+        };
+        if disconnected {
+            self.fire_connection_event(ConnectionEvent {
+                peer_id: peer_id.clone(),
+                event_type: ConnectionEventType::Disconnect,
+                node_info: None,
+            });
+        }
+    }
+
+    /// Queue an event for the connection callback
+    fn fire_connection_event(&self, event: ConnectionEvent) {
+        if let Some(events) = &self.connection_events {
+            // The receiving task only stops once the transport is dropped
+            let _ = events.send(event);
+        }
+    }
+
+    /// Run the connection callback on each queued event in turn, logging its errors
+    ///
+    /// Stops once the transport, and with it the sender, is dropped.
+    async fn deliver_connection_events(
+        callback: ConnectionCallback,
+        mut events: mpsc::UnboundedReceiver<ConnectionEvent>,
+        logger: Arc<Logger>,
+    ) {
+        while let Some(event) = events.recv().await {
+            let peer_id = event.peer_id.clone();
+            if let Err(e) = callback(event).await {
+                logger.warn(format!(
+                    "Connection callback failed for peer {peer_id}: {e}"
+                ));
+            }
+        }
     }
 
     /// Check if connected to a specific peer
@@ -1636,6 +3170,7 @@ impl QuicTransportImpl {
             self.logger
                 .error("🚫 [QuicTransport] Transport not running - cannot send message");
             return Err(NetworkError::TransportError(
+                ErrorCode::NotRunning,
                 "Transport not running".to_string(),
             ));
         }
@@ -1726,16 +3261,17 @@ impl QuicTransportImpl {
             Ok(_) => {}
             Err(e) => {
                 // Handle Quinn-specific errors - ReadExactError doesn't have kind() method
-                return Err(NetworkError::MessageError(format!(
-                    "Failed to read response length for {correlation_id}: {e}"
-                )));
+                return Err(NetworkError::MessageError(
+                    ErrorCode::StreamFailed,
+                    format!("Failed to read response length for {correlation_id}: {e}"),
+                ));
             }
         }
 
         let message_len = u32::from_be_bytes(len_bytes) as usize;
         if message_len > 1024 * 1024 {
             // 1MB limit
-            return Err(NetworkError::MessageError(format!(
+            return Err(NetworkError::MessageError(ErrorCode::MessageTooLarge, format!(
                   "Response message too large: {message_len} bytes for correlation ID: {correlation_id}"
               )));
         }
@@ -1746,16 +3282,18 @@ impl QuicTransportImpl {
             .read_exact(&mut message_data)
             .await
             .map_err(|e| {
-                NetworkError::MessageError(format!(
-                    "Failed to read response data for {correlation_id}: {e}"
-                ))
+                NetworkError::MessageError(
+                    ErrorCode::StreamFailed,
+                    format!("Failed to read response data for {correlation_id}: {e}"),
+                )
             })?;
 
         // Deserialize the response message
         let message: NetworkMessage = bincode::deserialize(&message_data).map_err(|e| {
-            NetworkError::MessageError(format!(
-                "Failed to deserialize response for {correlation_id}: {e}"
-            ))
+            NetworkError::MessageError(
+                ErrorCode::SerializationFailed,
+                format!("Failed to deserialize response for {correlation_id}: {e}"),
+            )
         })?;
 
         logger.debug(format!(
@@ -1770,7 +3308,8 @@ impl QuicTransportImpl {
 #[async_trait]
 impl NetworkTransport for QuicTransport {
     async fn start(&self) -> Result<(), NetworkError> {
-        self.inner.start(&self.background_tasks).await
+        let result = self.inner.start(&self.background_tasks).await;
+        self.track_error(result)
     }
 
     async fn stop(&self) -> Result<(), NetworkError> {
@@ -1778,7 +3317,8 @@ impl NetworkTransport for QuicTransport {
     }
 
     async fn disconnect(&self, peer_id: PeerId) -> Result<(), NetworkError> {
-        self.inner.disconnect(peer_id).await
+        let result = self.inner.disconnect(peer_id).await;
+        self.track_error(result)
     }
 
     async fn is_connected(&self, peer_id: PeerId) -> bool {
@@ -1786,10 +3326,97 @@ impl NetworkTransport for QuicTransport {
     }
 
     async fn send_message(&self, message: NetworkMessage) -> Result<(), NetworkError> {
-        self.inner.send_message(message).await
+        // Peers disconnected for being idle are reconnected on demand
+        if let Some(peer_info) = self.inner.take_idle_peer(&message.destination).await {
+            if let Err(e) = self.connect_and_handshake(peer_info.clone()).await {
+                self.inner
+                    .idle_peers
+                    .insert(message.destination.clone(), peer_info);
+                return self.track_error(Err(e));
+            }
+        }
+        let result = self.inner.send_message(message).await;
+        self.track_error(result)
+    }
+
+    async fn prepare_message(&self, message: &mut NetworkMessage) {
+        if let Some(peer_state) = self.inner.connection_pool.get_peer(&message.destination) {
+            self.inner.compact_payloads(&peer_state, message).await;
+        }
     }
 
     async fn connect_peer(&self, discovery_msg: PeerInfo) -> Result<(), NetworkError> {
+        let result = self.connect_and_handshake(discovery_msg).await;
+        self.track_error(result)
+    }
+
+    /// Update the list of connected peers with the latest node info
+    async fn update_peers(&self, node_info: NodeInfo) -> Result<(), NetworkError> {
+        self.inner.update_peers(node_info).await
+    }
+
+    async fn broadcast_service_diff(&self, diff: NodeInfoDiff) -> Result<(), NetworkError> {
+        self.inner.broadcast_service_diff(diff).await
+    }
+
+    fn get_local_address(&self) -> String {
+        self.inner.get_local_address()
+    }
+
+    /// Subscribe to peer node info updates
+    ///
+    /// INTENTION: Allow callers to subscribe to peer node info updates when they are received
+    /// during handshakes. This is used by the Node to create RemoteService instances.
+    async fn subscribe_to_peer_node_info(&self) -> tokio::sync::broadcast::Receiver<NodeInfo> {
+        self.inner.peer_node_info_sender.subscribe()
+    }
+
+    /// Get a snapshot of the transport statistics
+    ///
+    /// INTENTION: Report the lock-free counters maintained by the send/receive
+    /// paths; only the current connection count is computed on demand.
+    async fn transport_stats(&self) -> TransportStats {
+        let current_connections = self.inner.connection_pool.get_connected_peers().await.len();
+        self.inner.metrics.snapshot(current_connections as u32)
+    }
+
+    async fn migrate_connections(&self) -> Result<(), NetworkError> {
+        // Only an endpoint started with migration enabled has a rebindable socket
+        if self.inner.migration_monitor.lock().await.is_none() {
+            return Ok(());
+        }
+        self.inner.migrate_connections(None).await
+    }
+
+    async fn peer_capabilities(&self, peer_id: &PeerId) -> Option<NodeCapabilities> {
+        let peer_state = self.inner.connection_pool.get_peer(peer_id)?;
+        peer_state.get_capabilities().await
+    }
+}
+
+impl QuicTransport {
+    /// Get a snapshot of the peer connection pool's usage
+    pub fn connection_pool_stats(&self) -> ConnectionPoolStats {
+        self.inner.connection_pool.stats()
+    }
+
+    /// Probe every pooled peer connection, evicting the unhealthy ones
+    ///
+    /// See `ConnectionPool::health_check_all`.
+    pub async fn health_check_connections(&self) -> Vec<(PeerId, bool)> {
+        self.inner.connection_pool.health_check_all().await
+    }
+
+    /// Record failed operations in the transport error counters
+    fn track_error<T>(&self, result: Result<T, NetworkError>) -> Result<T, NetworkError> {
+        if let Err(e) = &result {
+            self.inner.metrics.record_error(e);
+        }
+        result
+    }
+
+    /// Connect to a peer and start the handshake
+    async fn connect_and_handshake(&self, discovery_msg: PeerInfo) -> Result<(), NetworkError> {
         // Call the inner implementation which returns a task handle
         match self.inner.connect_peer(discovery_msg.clone()).await {
             Ok(task) => {
@@ -1812,25 +3439,6 @@ impl NetworkTransport for QuicTransport {
         }
     }
 
-    /// Update the list of connected peers with the latest node info
-    async fn update_peers(&self, node_info: NodeInfo) -> Result<(), NetworkError> {
-        self.inner.update_peers(node_info).await
-    }
-
-    fn get_local_address(&self) -> String {
-        self.inner.get_local_address()
-    }
-
-    /// Subscribe to peer node info updates
-    ///
-    /// INTENTION: Allow callers to subscribe to peer node info updates when they are received
-    /// during handshakes. This is used by the Node to create RemoteService instances.
-    async fn subscribe_to_peer_node_info(&self) -> tokio::sync::broadcast::Receiver<NodeInfo> {
-        self.inner.peer_node_info_sender.subscribe()
-    }
-}
-
-impl QuicTransport {
     /// Create a new QuicTransport instance
     ///
     /// INTENTION: Create a new QuicTransport with the given node ID, bind address,
@@ -1870,6 +3478,37 @@ impl QuicTransport {
     }
 }
 
+/// Bind a UDP socket that a later socket may bind to the same address
+///
+/// Rebinding for connection migration opens the new socket while the old one
+/// still holds the port.
+fn bind_rebindable_socket(addr: SocketAddr) -> Result<std::net::UdpSocket, NetworkError> {
+    let bind_error = |e: std::io::Error| {
+        NetworkError::TransportError(
+            ErrorCode::TransportFailed,
+            format!("Failed to bind socket to {addr}: {e}"),
+        )
+    };
+    let socket = Socket::new(Domain::for_address(addr), Type::DGRAM, Some(Protocol::UDP))
+        .map_err(bind_error)?;
+    socket.set_reuse_address(true).map_err(bind_error)?;
+    socket.bind(&addr.into()).map_err(bind_error)?;
+    Ok(socket.into())
+}
+
+/// Local IP address the OS routes traffic to `remote` from
+///
+/// Connecting a UDP socket only selects the route; no packet is sent.
+fn route_local_ip(remote: SocketAddr) -> Option<IpAddr> {
+    let unspecified: SocketAddr = match remote {
+        SocketAddr::V4(_) => (Ipv4Addr::UNSPECIFIED, 0).into(),
+        SocketAddr::V6(_) => (std::net::Ipv6Addr::UNSPECIFIED, 0).into(),
+    };
+    let socket = std::net::UdpSocket::bind(unspecified).ok()?;
+    socket.connect(remote).ok()?;
+    socket.local_addr().ok().map(|addr| addr.ip())
+}
+
 // Custom server name verifier that accepts node IDs as valid server names
 #[derive(Debug)]
 struct NodeIdServerNameVerifier;
@@ -0,0 +1,269 @@
+// Request Authentication
+//
+// This module authenticates messages between nodes that share a secret key,
+// below the application layer: every outgoing message carries an HMAC tag
+// that the receiving node checks before dispatching it to a service.
+
+use std::fmt;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use ring::hmac;
+
+use super::{
+    ErrorCode, NetworkError, NetworkMessage, NetworkTransport, NodeCapabilities, NodeInfo,
+    NodeInfoDiff, PeerId, PeerInfo, TransportStats,
+};
+
+/// Hash function used for the message authentication tag
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HmacAlgorithm {
+    /// HMAC-SHA256 (32-byte tags)
+    #[default]
+    Sha256,
+    /// HMAC-SHA384 (48-byte tags)
+    Sha384,
+    /// HMAC-SHA512 (64-byte tags)
+    Sha512,
+}
+
+impl HmacAlgorithm {
+    fn ring_algorithm(self) -> hmac::Algorithm {
+        match self {
+            HmacAlgorithm::Sha256 => hmac::HMAC_SHA256,
+            HmacAlgorithm::Sha384 => hmac::HMAC_SHA384,
+            HmacAlgorithm::Sha512 => hmac::HMAC_SHA512,
+        }
+    }
+}
+
+/// Shared-secret authentication for requests between nodes
+///
+/// INTENTION: Restrict services such as an admin service to peers that were
+/// given the secret key. Nodes without a matching key have their requests
+/// rejected with `NetworkError::ConnectionError("authentication failed")`.
+#[derive(Clone)]
+pub struct RequestAuthConfig {
+    /// Secret key shared by all authorized nodes
+    pub secret_key: Vec<u8>,
+    /// Hash function used for the tags
+    pub algorithm: HmacAlgorithm,
+}
+
+impl RequestAuthConfig {
+    /// Create a configuration using HMAC-SHA256
+    pub fn new(secret_key: Vec<u8>) -> Self {
+        Self {
+            secret_key,
+            algorithm: HmacAlgorithm::default(),
+        }
+    }
+
+    /// Set the hash function used for the tags
+    pub fn with_algorithm(mut self, algorithm: HmacAlgorithm) -> Self {
+        self.algorithm = algorithm;
+        self
+    }
+}
+
+// The secret key must never end up in logs
+impl fmt::Debug for RequestAuthConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RequestAuthConfig")
+            .field("secret_key", &"<redacted>")
+            .field("algorithm", &self.algorithm)
+            .finish()
+    }
+}
+
+struct AuthKeys {
+    current: hmac::Key,
+    /// Key replaced by the last rotation, accepted until the instant it expires
+    previous: Option<(hmac::Key, Instant)>,
+}
+
+/// Computes and checks message authentication tags
+///
+/// INTENTION: Hold the node's secret key and support rotating it. The tag is
+/// an HMAC over the message's signing digest, which covers the source,
+/// destination, correlation ids and a hash of the payloads.
+pub struct RequestAuthenticator {
+    algorithm: HmacAlgorithm,
+    keys: RwLock<AuthKeys>,
+}
+
+impl RequestAuthenticator {
+    /// Create an authenticator from its configuration
+    pub fn new(config: &RequestAuthConfig) -> Self {
+        let algorithm = config.algorithm;
+        Self {
+            algorithm,
+            keys: RwLock::new(AuthKeys {
+                current: hmac::Key::new(algorithm.ring_algorithm(), &config.secret_key),
+                previous: None,
+            }),
+        }
+    }
+
+    /// Compute the tag of a message with the current key
+    pub fn tag(&self, message: &NetworkMessage) -> Vec<u8> {
+        let keys = self.keys.read().unwrap_or_else(|e| e.into_inner());
+        hmac::sign(&keys.current, &message.signing_digest())
+            .as_ref()
+            .to_vec()
+    }
+
+    /// Set the tag of an outgoing message
+    pub fn sign(&self, message: &mut NetworkMessage) {
+        message.auth_tag = Some(self.tag(message));
+    }
+
+    /// Check the tag of an incoming message
+    ///
+    /// The key replaced by the last rotation is accepted until its overlap
+    /// window ends.
+    pub fn verify(&self, message: &NetworkMessage) -> bool {
+        let Some(tag) = &message.auth_tag else {
+            return false;
+        };
+        let digest = message.signing_digest();
+        let keys = self.keys.read().unwrap_or_else(|e| e.into_inner());
+        if hmac::verify(&keys.current, &digest, tag).is_ok() {
+            return true;
+        }
+        match &keys.previous {
+            Some((key, expires)) if Instant::now() < *expires => {
+                hmac::verify(key, &digest, tag).is_ok()
+            }
+            _ => false,
+        }
+    }
+
+    /// Replace the secret key
+    ///
+    /// New tags use `new_key` right away; tags made with the old key are
+    /// still accepted for `overlap`, so requests in flight complete and
+    /// other nodes can be rotated in the meantime.
+    pub fn rotate_key(&self, new_key: &[u8], overlap: Duration) {
+        let mut keys = self.keys.write().unwrap_or_else(|e| e.into_inner());
+        let new_key = hmac::Key::new(self.algorithm.ring_algorithm(), new_key);
+        let old_key = std::mem::replace(&mut keys.current, new_key);
+        keys.previous = Some((old_key, Instant::now() + overlap));
+    }
+}
+
+/// Check whether a message reports that the sender failed authentication
+///
+/// Such replies cannot carry a tag the requester accepts, since the two
+/// nodes do not share a key, so they are let through untagged.
+pub fn is_authentication_failure(message: &NetworkMessage) -> bool {
+    message.message_type == "Error"
+        && !message.payloads.is_empty()
+        && message
+            .payloads
+            .iter()
+            .all(|payload| payload.error_code == Some(ErrorCode::AuthenticationFailed.as_u16()))
+}
+
+/// Keep the payloads of an authentication failure that answer a request
+/// sent to the failure's source
+///
+/// Untagged failures cannot be authenticated, so a node must not be able to
+/// fail requests it was never sent. `sent_to` gives the peer a pending request
+/// was sent to, by correlation id. Returns the number of payloads discarded.
+pub fn retain_sent_request_failures(
+    message: &mut NetworkMessage,
+    sent_to: impl Fn(&str) -> Option<PeerId>,
+) -> usize {
+    let payload_count = message.payloads.len();
+    let source = message.source.clone();
+    message
+        .payloads
+        .retain(|payload| sent_to(&payload.correlation_id).is_some_and(|peer| peer == source));
+    payload_count - message.payloads.len()
+}
+
+/// Transport that tags every outgoing message
+///
+/// INTENTION: Authenticate all messages a Node sends, whichever component
+/// sends them, without the transports knowing about the shared key.
+pub struct AuthenticatedTransport {
+    inner: Box<dyn NetworkTransport>,
+    authenticator: Arc<RequestAuthenticator>,
+}
+
+impl AuthenticatedTransport {
+    /// Wrap a transport so its outgoing messages are tagged
+    pub fn new(inner: Box<dyn NetworkTransport>, authenticator: Arc<RequestAuthenticator>) -> Self {
+        Self {
+            inner,
+            authenticator,
+        }
+    }
+}
+
+#[async_trait]
+impl NetworkTransport for AuthenticatedTransport {
+    async fn start(&self) -> Result<(), NetworkError> {
+        self.inner.start().await
+    }
+
+    async fn stop(&self) -> Result<(), NetworkError> {
+        self.inner.stop().await
+    }
+
+    async fn disconnect(&self, node_id: PeerId) -> Result<(), NetworkError> {
+        self.inner.disconnect(node_id).await
+    }
+
+    async fn is_connected(&self, node_id: PeerId) -> bool {
+        self.inner.is_connected(node_id).await
+    }
+
+    async fn send_message(&self, mut message: NetworkMessage) -> Result<(), NetworkError> {
+        // Authentication failures are reported untagged, see is_authentication_failure
+        if !is_authentication_failure(&message) {
+            // Tag the payloads in the form they are sent, e.g. compact frames
+            self.inner.prepare_message(&mut message).await;
+            self.authenticator.sign(&mut message);
+        }
+        self.inner.send_message(message).await
+    }
+
+    async fn prepare_message(&self, message: &mut NetworkMessage) {
+        self.inner.prepare_message(message).await
+    }
+
+    async fn connect_peer(&self, discovery_msg: PeerInfo) -> Result<(), NetworkError> {
+        self.inner.connect_peer(discovery_msg).await
+    }
+
+    fn get_local_address(&self) -> String {
+        self.inner.get_local_address()
+    }
+
+    async fn update_peers(&self, node_info: NodeInfo) -> Result<(), NetworkError> {
+        self.inner.update_peers(node_info).await
+    }
+
+    async fn broadcast_service_diff(&self, diff: NodeInfoDiff) -> Result<(), NetworkError> {
+        self.inner.broadcast_service_diff(diff).await
+    }
+
+    async fn subscribe_to_peer_node_info(&self) -> tokio::sync::broadcast::Receiver<NodeInfo> {
+        self.inner.subscribe_to_peer_node_info().await
+    }
+
+    async fn transport_stats(&self) -> TransportStats {
+        self.inner.transport_stats().await
+    }
+
+    async fn migrate_connections(&self) -> Result<(), NetworkError> {
+        self.inner.migrate_connections().await
+    }
+
+    async fn peer_capabilities(&self, peer_id: &PeerId) -> Option<NodeCapabilities> {
+        self.inner.peer_capabilities(peer_id).await
+    }
+}
@@ -2,24 +2,82 @@
 //!
 //! INTENTION: Handles stream reuse, lifecycle, and timeouts for QUIC transport.
 
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use crate::network::transport::NetworkError;
 use runar_common::logging::Logger;
-use tokio::sync::RwLock;
+use tokio::sync::{RwLock, Semaphore, SemaphorePermit};
+use tokio::task::JoinHandle;
+
+/// Limits applied to a StreamPool
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StreamPoolOptions {
+    /// How long a stream may sit unused in the pool before it is closed
+    pub max_idle_time: Duration,
+    /// Maximum number of streams handed out at the same time
+    pub max_pool_size: usize,
+}
+
+impl Default for StreamPoolOptions {
+    fn default() -> Self {
+        Self {
+            max_idle_time: Duration::from_secs(30),
+            max_pool_size: 100,
+        }
+    }
+}
+
+/// Snapshot of a StreamPool's usage
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StreamPoolStats {
+    /// Streams currently handed out
+    pub active: usize,
+    /// Streams waiting in the pool for reuse
+    pub idle: usize,
+    /// Idle streams closed because they exceeded the maximum idle time
+    pub evicted_total: u64,
+}
+
+/// A pooled stream and the time it was returned to the pool
+pub struct IdleStream {
+    pub stream: quinn::SendStream,
+    pub last_used: Instant,
+}
+
+/// A slot of a StreamPool taken with `StreamPool::reserve`, freed on drop
+pub struct StreamSlot<'a> {
+    active: &'a AtomicUsize,
+    _permit: Option<SemaphorePermit<'a>>,
+}
+
+impl Drop for StreamSlot<'_> {
+    fn drop(&mut self) {
+        self.active.fetch_sub(1, Ordering::SeqCst);
+    }
+}
 
 /// StreamPool - Manages the reuse of QUIC streams
 ///
 /// INTENTION: This component manages stream reuse, implements stream lifecycle,
-/// and handles stream timeouts.
+/// and handles stream timeouts. At most `max_pool_size` streams are handed out
+/// at once; further acquisitions wait until a stream is returned or released.
+/// A background task closes streams that stay idle longer than `max_idle_time`
+/// so they stop counting against the peer's concurrent-stream limit.
 ///
 /// ARCHITECTURAL BOUNDARIES:
 /// - Only accessed by PeerState
 /// - Manages creation, reuse, and cleanup of streams
 pub struct StreamPool {
-    pub idle_streams: RwLock<Vec<quinn::SendStream>>,
+    pub idle_streams: Arc<RwLock<Vec<IdleStream>>>,
     pub max_idle_streams: usize,
+    pub options: StreamPoolOptions,
     pub logger: Arc<Logger>,
+    slots: Semaphore,
+    active: AtomicUsize,
+    evicted_total: Arc<AtomicU64>,
+    cleanup_task: Option<JoinHandle<()>>,
 }
 
 impl StreamPool {
@@ -27,31 +85,108 @@ impl StreamPool {
     ///
     /// INTENTION: Initialize a stream pool with a capacity for idle streams reuse.
     pub fn new(max_idle_streams: usize, logger: Arc<Logger>) -> Self {
+        Self::with_options(max_idle_streams, StreamPoolOptions::default(), logger)
+    }
+
+    /// Create a new StreamPool with the given limits
+    ///
+    /// The idle cleanup task is only started when called from within a Tokio
+    /// runtime; it stops when the pool is dropped.
+    pub fn with_options(
+        max_idle_streams: usize,
+        options: StreamPoolOptions,
+        logger: Arc<Logger>,
+    ) -> Self {
+        let idle_streams = Arc::new(RwLock::new(Vec::with_capacity(max_idle_streams)));
+        let evicted_total = Arc::new(AtomicU64::new(0));
+        let cleanup_task = tokio::runtime::Handle::try_current().ok().map(|handle| {
+            handle.spawn(Self::run_cleanup(
+                idle_streams.clone(),
+                evicted_total.clone(),
+                options.max_idle_time,
+                logger.clone(),
+            ))
+        });
+
         Self {
-            idle_streams: RwLock::new(Vec::with_capacity(max_idle_streams)),
+            idle_streams,
             max_idle_streams,
+            options,
             logger,
+            slots: Semaphore::new(options.max_pool_size.max(1)),
+            active: AtomicUsize::new(0),
+            evicted_total,
+            cleanup_task,
         }
     }
-    /// Get an idle stream from the pool if available
+
+    /// Take a slot in the pool, with an idle stream if one is available
     ///
     /// INTENTION: Reuse existing streams to avoid the overhead of creating new ones.
-    pub async fn get_idle_stream(&self) -> Option<quinn::SendStream> {
+    /// Waits while `max_pool_size` streams are in use. When None is returned
+    /// the caller should open a new stream, and must hand it back with
+    /// `return_stream` or give up the slot with `release`.
+    pub async fn acquire(&self) -> Option<quinn::SendStream> {
+        // The semaphore is never closed, so acquiring only waits for a free slot
+        if let Ok(permit) = self.slots.acquire().await {
+            permit.forget();
+        }
+        self.active.fetch_add(1, Ordering::SeqCst);
+
         let mut streams = self.idle_streams.write().await;
-        streams.pop()
+        streams.pop().map(|idle| idle.stream)
+    }
+
+    /// Take a slot in the pool for a stream that will not be reused
+    ///
+    /// INTENTION: Count one-shot streams against `max_pool_size` too. Waits
+    /// while the pool is full; the slot is freed when the returned guard is
+    /// dropped, so it cannot be released twice.
+    pub async fn reserve(&self) -> StreamSlot<'_> {
+        // The semaphore is never closed, so acquiring only waits for a free slot
+        let permit = self.slots.acquire().await.ok();
+        self.active.fetch_add(1, Ordering::SeqCst);
+        StreamSlot {
+            active: &self.active,
+            _permit: permit,
+        }
     }
 
     /// Return a stream to the pool for future reuse
     ///
     /// INTENTION: Efficiently manage QUIC stream resources.
     pub async fn return_stream(&self, stream: quinn::SendStream) -> Result<(), NetworkError> {
-        let mut streams = self.idle_streams.write().await;
-        if streams.len() < self.max_idle_streams {
-            streams.push(stream);
-            Ok(())
+        {
+            let mut streams = self.idle_streams.write().await;
+            if streams.len() < self.max_idle_streams {
+                streams.push(IdleStream {
+                    stream,
+                    last_used: Instant::now(),
+                });
+            } else {
+                self.logger.debug("Dropping stream: pool is full");
+            }
+        }
+        self.release();
+        Ok(())
+    }
+
+    /// Give up a slot taken with `acquire` without returning a stream
+    ///
+    /// INTENTION: Unblock waiting callers when a stream could not be opened or
+    /// was consumed instead of being returned.
+    pub fn release(&self) {
+        let released = self
+            .active
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |active| {
+                active.checked_sub(1)
+            })
+            .is_ok();
+        if released {
+            self.slots.add_permits(1);
         } else {
-            self.logger.debug("Dropping stream: pool is full");
-            Ok(())
+            self.logger
+                .warn("Released a stream slot that was not acquired");
         }
     }
 
@@ -63,10 +198,63 @@ impl StreamPool {
         streams.clear();
         Ok(())
     }
+
+    /// Get the current usage of the pool
+    pub async fn stats(&self) -> StreamPoolStats {
+        StreamPoolStats {
+            active: self.active.load(Ordering::SeqCst),
+            idle: self.idle_streams.read().await.len(),
+            evicted_total: self.evicted_total.load(Ordering::SeqCst),
+        }
+    }
+
+    /// Close idle streams older than `max_idle_time`, every `max_idle_time / 2`
+    async fn run_cleanup(
+        idle_streams: Arc<RwLock<Vec<IdleStream>>>,
+        evicted_total: Arc<AtomicU64>,
+        max_idle_time: Duration,
+        logger: Arc<Logger>,
+    ) {
+        let period = (max_idle_time / 2).max(Duration::from_millis(1));
+        let mut interval = tokio::time::interval(period);
+        loop {
+            interval.tick().await;
+            let evicted = Self::evict_idle(&idle_streams, max_idle_time).await;
+            if evicted > 0 {
+                evicted_total.fetch_add(evicted as u64, Ordering::SeqCst);
+                logger.debug(format!("Closed {evicted} idle QUIC streams"));
+            }
+        }
+    }
+
+    async fn evict_idle(idle_streams: &RwLock<Vec<IdleStream>>, max_idle_time: Duration) -> usize {
+        let mut streams = idle_streams.write().await;
+        let before = streams.len();
+        streams.retain_mut(|idle| {
+            if idle.last_used.elapsed() < max_idle_time {
+                return true;
+            }
+            // The stream may already be finished or reset by the peer
+            let _ = idle.stream.finish();
+            false
+        });
+        before - streams.len()
+    }
+}
+
+impl Drop for StreamPool {
+    fn drop(&mut self) {
+        if let Some(task) = self.cleanup_task.take() {
+            task.abort();
+        }
+    }
 }
 
 impl std::fmt::Debug for StreamPool {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("StreamPool").finish()
+        f.debug_struct("StreamPool")
+            .field("max_idle_streams", &self.max_idle_streams)
+            .field("options", &self.options)
+            .finish()
     }
 }
@@ -0,0 +1,756 @@
+// Unix Domain Socket Transport Implementation
+//
+// This module provides a NetworkTransport for nodes running on the same host.
+// It is meant to be combined with a network transport through MultiTransport.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use p256::ecdsa::SigningKey;
+use runar_common::logging::Logger;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::unix::{OwnedReadHalf, OwnedWriteHalf};
+use tokio::net::{UnixListener, UnixStream};
+use tokio::sync::{broadcast, Mutex, RwLock};
+use tokio::task::JoinHandle;
+
+use super::{
+    ErrorCode, NetworkError, NetworkMessage, NetworkMessagePayloadItem, NetworkTransport, NodeInfo,
+    NodeInfoDiff, PeerFilter, PeerId, PeerInfo, UNIX_ADDRESS_PREFIX,
+};
+
+/// Handler invoked for every non-handshake message received over a socket
+pub type UnixMessageHandler =
+    Box<dyn Fn(NetworkMessage) -> Result<(), NetworkError> + Send + Sync + 'static>;
+
+/// Write half of a socket connection, shared by every id the peer is known under
+struct UnixPeerConnection {
+    writer: Mutex<OwnedWriteHalf>,
+    // Networks of the peer, known once its node info is received
+    network_ids: std::sync::RwLock<Vec<String>>,
+}
+
+impl UnixPeerConnection {
+    fn new(writer: OwnedWriteHalf) -> Self {
+        Self {
+            writer: Mutex::new(writer),
+            network_ids: std::sync::RwLock::new(Vec::new()),
+        }
+    }
+
+    fn network_ids(&self) -> Vec<String> {
+        self.network_ids
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+}
+
+struct UnixTransportImpl {
+    node_id: PeerId,
+    socket_path: PathBuf,
+    local_node: RwLock<NodeInfo>,
+    message_handler: UnixMessageHandler,
+    max_message_size: usize,
+    // Key proving our node id in handshakes
+    signing_key: Option<SigningKey>,
+    // Peers refused once their node id is verified
+    peer_filter: Option<Arc<PeerFilter>>,
+    // Connections keyed by the peer's node id and by its socket path alias
+    peers: RwLock<HashMap<PeerId, Arc<UnixPeerConnection>>>,
+    peer_node_info_sender: broadcast::Sender<NodeInfo>,
+    running: AtomicBool,
+    logger: Arc<Logger>,
+}
+
+/// Transport exchanging messages over Unix domain sockets
+///
+/// INTENTION: Give co-located nodes (e.g. a node and its sidecars) a fast IPC
+/// path that needs no certificates. Access is controlled by the permissions
+/// of the socket file, so messages are not signed. The handshake is: each
+/// side signs the other's random nonce with its node key, proving that it
+/// owns the node id it claims, so that a local process cannot take over the
+/// traffic of a remote peer by claiming its id.
+///
+/// Addresses have the form `unix:/path/to/socket`. Before the handshake a
+/// peer is identified by `peer_id_for_path`; afterwards it is reachable both
+/// under that id and under its verified node id. Messages relayed to us by
+/// the peer under another node id are dropped. Frames use the
+/// same layout as the QUIC transport: a big-endian u32 length followed by the
+/// bincode encoded `NetworkMessage`.
+pub struct UnixSocketTransport {
+    inner: Arc<UnixTransportImpl>,
+    background_tasks: std::sync::Mutex<Vec<JoinHandle<()>>>,
+}
+
+impl UnixSocketTransport {
+    /// Create a transport that will listen on `socket_path` once started
+    ///
+    /// Handshakes fail without a `signing_key`, since the node could not
+    /// prove its id.
+    pub fn new(
+        local_node_info: NodeInfo,
+        socket_path: impl Into<PathBuf>,
+        message_handler: UnixMessageHandler,
+        max_message_size: usize,
+        signing_key: Option<SigningKey>,
+        logger: Arc<Logger>,
+    ) -> Self {
+        let (peer_node_info_sender, _) = broadcast::channel(32);
+        Self {
+            inner: Arc::new(UnixTransportImpl {
+                node_id: local_node_info.peer_id.clone(),
+                socket_path: socket_path.into(),
+                local_node: RwLock::new(local_node_info),
+                message_handler,
+                max_message_size,
+                signing_key,
+                peer_filter: None,
+                peers: RwLock::new(HashMap::new()),
+                peer_node_info_sender,
+                running: AtomicBool::new(false),
+                logger,
+            }),
+            background_tasks: std::sync::Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Only keep connections with the peers accepted by `peer_filter`
+    ///
+    /// Socket peers are only known by path until their handshake tells
+    /// their node id, so the filter is applied during the handshake, on both
+    /// sides. Must be called before the transport is started.
+    pub fn with_peer_filter(mut self, peer_filter: Arc<PeerFilter>) -> Self {
+        Arc::get_mut(&mut self.inner)
+            .expect("the transport is not shared before it starts")
+            .peer_filter = Some(peer_filter);
+        self
+    }
+
+    /// Address advertised for a socket path, e.g. `unix:/tmp/node.sock`
+    pub fn address_for_path(path: &Path) -> String {
+        format!("{UNIX_ADDRESS_PREFIX}{}", path.display())
+    }
+
+    /// Socket path of a `unix:` address, or None for any other address
+    pub fn path_from_address(address: &str) -> Option<PathBuf> {
+        address.strip_prefix(UNIX_ADDRESS_PREFIX).map(PathBuf::from)
+    }
+
+    /// Peer id used for the node listening on `path` until its handshake completes
+    pub fn peer_id_for_path(path: &Path) -> PeerId {
+        PeerId::new(Self::address_for_path(path))
+    }
+
+    /// Path of the socket this transport listens on
+    pub fn socket_path(&self) -> &Path {
+        &self.inner.socket_path
+    }
+
+    fn track_task(&self, task: JoinHandle<()>) {
+        self.background_tasks
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(task);
+    }
+}
+
+impl UnixTransportImpl {
+    async fn write_frame(
+        &self,
+        connection: &UnixPeerConnection,
+        message: &NetworkMessage,
+    ) -> Result<(), NetworkError> {
+        let bytes = bincode::serialize(message).map_err(|e| {
+            NetworkError::MessageError(
+                ErrorCode::SerializationFailed,
+                format!("Failed to serialize message: {e}"),
+            )
+        })?;
+        if bytes.len() > self.max_message_size {
+            return Err(NetworkError::MessageError(
+                ErrorCode::MessageTooLarge,
+                format!(
+                    "Message of {} bytes exceeds the limit of {} bytes",
+                    bytes.len(),
+                    self.max_message_size
+                ),
+            ));
+        }
+
+        let mut writer = connection.writer.lock().await;
+        let stream_error = |e: std::io::Error| {
+            NetworkError::MessageError(
+                ErrorCode::StreamFailed,
+                format!("Failed to write to socket: {e}"),
+            )
+        };
+        writer
+            .write_all(&(bytes.len() as u32).to_be_bytes())
+            .await
+            .map_err(stream_error)?;
+        writer.write_all(&bytes).await.map_err(stream_error)
+    }
+
+    /// Read the next frame, returning None when the peer closed the connection
+    async fn read_frame(
+        &self,
+        reader: &mut OwnedReadHalf,
+    ) -> Result<Option<NetworkMessage>, NetworkError> {
+        let stream_error = |e: std::io::Error| {
+            NetworkError::MessageError(
+                ErrorCode::StreamFailed,
+                format!("Failed to read from socket: {e}"),
+            )
+        };
+
+        let mut len_bytes = [0u8; 4];
+        match reader.read_exact(&mut len_bytes).await {
+            Ok(_) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(None),
+            Err(e) => return Err(stream_error(e)),
+        }
+        let len = u32::from_be_bytes(len_bytes) as usize;
+        if len > self.max_message_size {
+            return Err(NetworkError::MessageError(
+                ErrorCode::MessageTooLarge,
+                format!(
+                    "Incoming message of {len} bytes exceeds the limit of {} bytes",
+                    self.max_message_size
+                ),
+            ));
+        }
+
+        let mut bytes = vec![0u8; len];
+        reader.read_exact(&mut bytes).await.map_err(stream_error)?;
+        bincode::deserialize(&bytes).map(Some).map_err(|e| {
+            NetworkError::MessageError(
+                ErrorCode::SerializationFailed,
+                format!("Failed to deserialize message: {e}"),
+            )
+        })
+    }
+
+    /// Message carrying our node info as advertised to a peer in `network_ids`
+    async fn node_info_message(
+        &self,
+        destination: PeerId,
+        message_type: &str,
+        network_ids: &[String],
+    ) -> Result<NetworkMessage, NetworkError> {
+        let node_info = self.local_node.read().await.visible_to(network_ids);
+        let value_bytes = bincode::serialize(&node_info).map_err(|e| {
+            NetworkError::MessageError(
+                ErrorCode::SerializationFailed,
+                format!("Failed to serialize node info: {e}"),
+            )
+        })?;
+        Ok(NetworkMessage {
+            source: self.node_id.clone(),
+            destination,
+            message_type: message_type.to_string(),
+            payloads: vec![NetworkMessagePayloadItem::new(
+                String::new(),
+                value_bytes,
+                String::new(),
+            )],
+            signature: None,
+            hop_count: 0,
+            visited_peers: Vec::new(),
+            auth_tag: None,
+            expires_at: None,
+        })
+    }
+
+    /// Sign a handshake message with the node key
+    fn sign(&self, message: &mut NetworkMessage) -> Result<(), NetworkError> {
+        let signing_key = self.signing_key.as_ref().ok_or_else(|| {
+            NetworkError::ConfigurationError(
+                ErrorCode::MissingCredentials,
+                "No private key to prove the node id in socket handshakes".to_string(),
+            )
+        })?;
+        message.sign(signing_key);
+        Ok(())
+    }
+
+    /// Refuse a verified peer rejected by the peer filter
+    fn check_peer_filter(&self, peer_id: &PeerId) -> Result<(), NetworkError> {
+        let Some(peer_filter) = &self.peer_filter else {
+            return Ok(());
+        };
+        peer_filter.check(peer_id).map_err(|e| {
+            NetworkError::ConnectionError(
+                ErrorCode::HandshakeFailed,
+                format!("Refusing socket connection with {peer_id}: {e}"),
+            )
+        })
+    }
+
+    /// Check that `message` answers our `nonce`, signed by `peer_id`
+    fn verify_challenge(
+        message: &NetworkMessage,
+        peer_id: &PeerId,
+        nonce: &str,
+    ) -> Result<(), NetworkError> {
+        let answered = message
+            .payloads
+            .first()
+            .is_some_and(|payload| payload.correlation_id == nonce);
+        if message.source != *peer_id || !answered {
+            return Err(NetworkError::ConnectionError(
+                ErrorCode::HandshakeFailed,
+                format!(
+                    "{} from {} does not answer the handshake challenge",
+                    message.message_type, message.source
+                ),
+            ));
+        }
+        message.verify_source_signature()
+    }
+
+    fn handshake_node_info(message: &NetworkMessage) -> Result<NodeInfo, NetworkError> {
+        let payload = message.payloads.first().ok_or_else(|| {
+            NetworkError::ConnectionError(
+                ErrorCode::HandshakeFailed,
+                format!("{} message without node info", message.message_type),
+            )
+        })?;
+        bincode::deserialize(&payload.value_bytes).map_err(|e| {
+            NetworkError::ConnectionError(
+                ErrorCode::HandshakeFailed,
+                format!("Failed to deserialize node info: {e}"),
+            )
+        })
+    }
+
+    /// Register a connection under the peer's verified node id and its socket path aliases
+    ///
+    /// The aliases come from the addresses the peer advertises, so they never
+    /// replace the connection of another peer.
+    async fn register_peer(&self, node_info: &NodeInfo, connection: Arc<UnixPeerConnection>) {
+        *connection
+            .network_ids
+            .write()
+            .unwrap_or_else(|e| e.into_inner()) = node_info.network_ids.clone();
+        let mut peers = self.peers.write().await;
+        for address in &node_info.addresses {
+            if let Some(path) = UnixSocketTransport::path_from_address(address) {
+                peers
+                    .entry(UnixSocketTransport::peer_id_for_path(&path))
+                    .or_insert_with(|| connection.clone());
+            }
+        }
+        peers.insert(node_info.peer_id.clone(), connection);
+    }
+
+    async fn remove_connection(&self, connection: &Arc<UnixPeerConnection>) {
+        self.peers
+            .write()
+            .await
+            .retain(|_, existing| !Arc::ptr_eq(existing, connection));
+    }
+
+    fn publish_node_info(&self, node_info: NodeInfo) {
+        // No subscribers is not an error
+        let _ = self.peer_node_info_sender.send(node_info);
+    }
+
+    /// Dispatch messages from `peer_id` until the connection closes
+    async fn read_loop(
+        self: Arc<Self>,
+        mut reader: OwnedReadHalf,
+        connection: Arc<UnixPeerConnection>,
+        peer_id: PeerId,
+    ) {
+        while self.running.load(Ordering::SeqCst) {
+            match self.read_frame(&mut reader).await {
+                Ok(Some(message))
+                    if message.visited_peers.last().unwrap_or(&message.source) != &peer_id =>
+                {
+                    self.logger.warn(format!(
+                        "Dropping {} message from {} sent over the connection of {peer_id}",
+                        message.message_type, message.source
+                    ));
+                }
+                Ok(Some(message)) => match message.message_type.as_str() {
+                    "NODE_INFO_UPDATE" => match Self::handshake_node_info(&message) {
+                        Ok(node_info) if node_info.peer_id == peer_id => {
+                            self.publish_node_info(node_info)
+                        }
+                        Ok(node_info) => self.logger.warn(format!(
+                            "Dropping node info of {} sent by {peer_id}",
+                            node_info.peer_id
+                        )),
+                        Err(e) => self.logger.warn(format!(
+                            "Invalid NODE_INFO_UPDATE from {}: {e}",
+                            message.source
+                        )),
+                    },
+                    _ => {
+                        if let Err(e) = message.check_expiry() {
+                            self.logger.debug(format!(
+                                "Dropping {} message from {}: {e}",
+                                message.message_type, message.source
+                            ));
+                        } else if let Err(e) = (self.message_handler)(message) {
+                            self.logger
+                                .error(format!("Error handling socket message: {e}"));
+                        }
+                    }
+                },
+                Ok(None) => break,
+                Err(e) => {
+                    self.logger.warn(format!("Closing socket connection: {e}"));
+                    break;
+                }
+            }
+        }
+        self.remove_connection(&connection).await;
+    }
+
+    /// Accept side of the handshake
+    ///
+    /// Wait for the peer's node info and nonce, reply with ours and the signed
+    /// nonce, then wait for the peer to sign our nonce.
+    async fn handle_incoming(self: Arc<Self>, stream: UnixStream) -> Result<(), NetworkError> {
+        let (mut reader, writer) = stream.into_split();
+        let message = self.read_frame(&mut reader).await?.ok_or_else(|| {
+            NetworkError::ConnectionError(
+                ErrorCode::HandshakeFailed,
+                "Connection closed before the handshake".to_string(),
+            )
+        })?;
+        if message.message_type != "NODE_INFO_HANDSHAKE" {
+            return Err(NetworkError::ConnectionError(
+                ErrorCode::HandshakeFailed,
+                format!(
+                    "Expected NODE_INFO_HANDSHAKE but got {}",
+                    message.message_type
+                ),
+            ));
+        }
+        let node_info = Self::handshake_node_info(&message)?;
+        // The id is only verified below, but the peer may as well be refused first
+        self.check_peer_filter(&node_info.peer_id)?;
+        let peer_nonce = message.payloads[0].correlation_id.clone();
+
+        let connection = Arc::new(UnixPeerConnection::new(writer));
+        let nonce = uuid::Uuid::new_v4().to_string();
+        let mut response = self
+            .node_info_message(
+                node_info.peer_id.clone(),
+                "NODE_INFO_HANDSHAKE_RESPONSE",
+                &node_info.network_ids,
+            )
+            .await?;
+        response.payloads[0].path = nonce.clone();
+        response.payloads[0].correlation_id = peer_nonce;
+        self.sign(&mut response)?;
+        self.write_frame(&connection, &response).await?;
+
+        let confirm = self.read_frame(&mut reader).await?.ok_or_else(|| {
+            NetworkError::ConnectionError(
+                ErrorCode::HandshakeFailed,
+                "Connection closed during the handshake".to_string(),
+            )
+        })?;
+        Self::verify_challenge(&confirm, &node_info.peer_id, &nonce)?;
+
+        self.logger.info(format!(
+            "Accepted socket connection from {}",
+            node_info.peer_id
+        ));
+        self.register_peer(&node_info, connection.clone()).await;
+        let peer_id = node_info.peer_id.clone();
+        self.publish_node_info(node_info);
+        self.read_loop(reader, connection, peer_id).await;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl NetworkTransport for UnixSocketTransport {
+    async fn start(&self) -> Result<(), NetworkError> {
+        if self.inner.running.swap(true, Ordering::SeqCst) {
+            return Ok(());
+        }
+
+        // A socket file left behind by a previous run would make bind fail
+        match std::fs::remove_file(&self.inner.socket_path) {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => {
+                self.inner.running.store(false, Ordering::SeqCst);
+                return Err(NetworkError::TransportError(
+                    ErrorCode::TransportFailed,
+                    format!(
+                        "Failed to remove stale socket {}: {e}",
+                        self.inner.socket_path.display()
+                    ),
+                ));
+            }
+        }
+        let listener = UnixListener::bind(&self.inner.socket_path).map_err(|e| {
+            self.inner.running.store(false, Ordering::SeqCst);
+            NetworkError::TransportError(
+                ErrorCode::TransportFailed,
+                format!(
+                    "Failed to bind socket {}: {e}",
+                    self.inner.socket_path.display()
+                ),
+            )
+        })?;
+        self.inner.logger.info(format!(
+            "Unix socket transport listening on {}",
+            self.inner.socket_path.display()
+        ));
+
+        let inner = self.inner.clone();
+        self.track_task(tokio::spawn(async move {
+            loop {
+                match listener.accept().await {
+                    Ok((stream, _)) => {
+                        let inner = inner.clone();
+                        tokio::spawn(async move {
+                            if let Err(e) = inner.clone().handle_incoming(stream).await {
+                                inner
+                                    .logger
+                                    .warn(format!("Incoming socket connection failed: {e}"));
+                            }
+                        });
+                    }
+                    Err(e) => {
+                        inner.logger.error(format!("Socket accept failed: {e}"));
+                        break;
+                    }
+                }
+            }
+        }));
+        Ok(())
+    }
+
+    async fn stop(&self) -> Result<(), NetworkError> {
+        if !self.inner.running.swap(false, Ordering::SeqCst) {
+            return Ok(());
+        }
+        let tasks = std::mem::take(
+            &mut *self
+                .background_tasks
+                .lock()
+                .unwrap_or_else(|e| e.into_inner()),
+        );
+        for task in tasks {
+            task.abort();
+        }
+        for connection in self.inner.peers.write().await.drain().map(|(_, c)| c) {
+            let _ = connection.writer.lock().await.shutdown().await;
+        }
+        let _ = std::fs::remove_file(&self.inner.socket_path);
+        Ok(())
+    }
+
+    async fn disconnect(&self, node_id: PeerId) -> Result<(), NetworkError> {
+        let connection = self.inner.peers.read().await.get(&node_id).cloned();
+        match connection {
+            Some(connection) => {
+                self.inner.remove_connection(&connection).await;
+                let _ = connection.writer.lock().await.shutdown().await;
+                Ok(())
+            }
+            None => Err(NetworkError::ConnectionError(
+                ErrorCode::NotConnected,
+                format!("Not connected to peer {node_id}"),
+            )),
+        }
+    }
+
+    async fn is_connected(&self, node_id: PeerId) -> bool {
+        self.inner.peers.read().await.contains_key(&node_id)
+    }
+
+    async fn send_message(&self, message: NetworkMessage) -> Result<(), NetworkError> {
+        let connection = self
+            .inner
+            .peers
+            .read()
+            .await
+            .get(&message.destination)
+            .cloned()
+            .ok_or_else(|| {
+                NetworkError::ConnectionError(
+                    ErrorCode::NotConnected,
+                    format!("Not connected to peer {}", message.destination),
+                )
+            })?;
+        self.inner.write_frame(&connection, &message).await
+    }
+
+    async fn connect_peer(&self, discovery_msg: PeerInfo) -> Result<(), NetworkError> {
+        if !self.inner.running.load(Ordering::SeqCst) {
+            return Err(NetworkError::TransportError(
+                ErrorCode::NotRunning,
+                "Unix socket transport is not running".to_string(),
+            ));
+        }
+        let path = discovery_msg
+            .addresses
+            .iter()
+            .find_map(|address| Self::path_from_address(address))
+            .ok_or_else(|| {
+                NetworkError::ConnectionError(
+                    ErrorCode::ConnectionFailed,
+                    format!(
+                        "Peer {} has no {UNIX_ADDRESS_PREFIX} address",
+                        discovery_msg.public_key
+                    ),
+                )
+            })?;
+
+        let stream = UnixStream::connect(&path).await.map_err(|e| {
+            NetworkError::ConnectionError(
+                ErrorCode::PeerUnreachable,
+                format!("Failed to connect to socket {}: {e}", path.display()),
+            )
+        })?;
+        let (mut reader, writer) = stream.into_split();
+        let connection = Arc::new(UnixPeerConnection::new(writer));
+
+        let nonce = uuid::Uuid::new_v4().to_string();
+        let mut handshake = self
+            .inner
+            // The peer's networks are not known yet
+            .node_info_message(Self::peer_id_for_path(&path), "NODE_INFO_HANDSHAKE", &[])
+            .await?;
+        handshake.payloads[0].correlation_id = nonce.clone();
+        self.inner.write_frame(&connection, &handshake).await?;
+
+        let response = self.inner.read_frame(&mut reader).await?.ok_or_else(|| {
+            NetworkError::ConnectionError(
+                ErrorCode::HandshakeFailed,
+                "Connection closed during the handshake".to_string(),
+            )
+        })?;
+        if response.message_type != "NODE_INFO_HANDSHAKE_RESPONSE" {
+            return Err(NetworkError::ConnectionError(
+                ErrorCode::HandshakeFailed,
+                format!(
+                    "Expected NODE_INFO_HANDSHAKE_RESPONSE but got {}",
+                    response.message_type
+                ),
+            ));
+        }
+        let node_info = UnixTransportImpl::handshake_node_info(&response)?;
+        UnixTransportImpl::verify_challenge(&response, &node_info.peer_id, &nonce)?;
+        self.inner.check_peer_filter(&node_info.peer_id)?;
+
+        let mut confirm = NetworkMessage {
+            source: self.inner.node_id.clone(),
+            destination: node_info.peer_id.clone(),
+            message_type: "NODE_INFO_HANDSHAKE_CONFIRM".to_string(),
+            payloads: vec![NetworkMessagePayloadItem::new(
+                String::new(),
+                Vec::new(),
+                response.payloads[0].path.clone(),
+            )],
+            signature: None,
+            hop_count: 0,
+            visited_peers: Vec::new(),
+            auth_tag: None,
+            expires_at: None,
+        };
+        self.inner.sign(&mut confirm)?;
+        self.inner.write_frame(&connection, &confirm).await?;
+
+        self.inner.logger.info(format!(
+            "Connected to {} over socket {}",
+            node_info.peer_id,
+            path.display()
+        ));
+        self.inner
+            .peers
+            .write()
+            .await
+            .insert(Self::peer_id_for_path(&path), connection.clone());
+        self.inner
+            .register_peer(&node_info, connection.clone())
+            .await;
+        // Now that they are known, advertise the services restricted to the peer's networks
+        let local_node = self.inner.local_node.read().await.clone();
+        if local_node.visible_to(&node_info.network_ids).services.len()
+            > local_node.visible_to(&[]).services.len()
+        {
+            let update = self
+                .inner
+                .node_info_message(
+                    node_info.peer_id.clone(),
+                    "NODE_INFO_UPDATE",
+                    &node_info.network_ids,
+                )
+                .await?;
+            self.inner.write_frame(&connection, &update).await?;
+        }
+        let peer_id = node_info.peer_id.clone();
+        self.inner.publish_node_info(node_info);
+
+        let inner = self.inner.clone();
+        self.track_task(tokio::spawn(inner.read_loop(reader, connection, peer_id)));
+        Ok(())
+    }
+
+    fn get_local_address(&self) -> String {
+        Self::address_for_path(&self.inner.socket_path)
+    }
+
+    async fn update_peers(&self, node_info: NodeInfo) -> Result<(), NetworkError> {
+        *self.inner.local_node.write().await = node_info;
+
+        // Peers are registered under several ids; notify each connection once
+        let mut notified: Vec<(PeerId, Arc<UnixPeerConnection>)> = Vec::new();
+        for (peer_id, connection) in self.inner.peers.read().await.iter() {
+            if notified.iter().any(|(_, c)| Arc::ptr_eq(c, connection)) {
+                continue;
+            }
+            notified.push((peer_id.clone(), connection.clone()));
+        }
+        for (peer_id, connection) in notified {
+            let message = self
+                .inner
+                .node_info_message(
+                    peer_id.clone(),
+                    "NODE_INFO_UPDATE",
+                    &connection.network_ids(),
+                )
+                .await?;
+            // One closed connection must not keep the update from the others
+            if let Err(e) = self.inner.write_frame(&connection, &message).await {
+                self.inner.logger.warn(format!(
+                    "Failed to send NODE_INFO_UPDATE to peer {peer_id}: {e}"
+                ));
+                continue;
+            }
+            self.inner
+                .logger
+                .info(format!("Sent NODE_INFO_UPDATE message to peer {peer_id}"));
+        }
+        Ok(())
+    }
+
+    /// Apply the diff to the local node info and send it in full
+    ///
+    /// Local peers are cheap to update, so they always get the full node info.
+    async fn broadcast_service_diff(&self, diff: NodeInfoDiff) -> Result<(), NetworkError> {
+        let mut node_info = self.inner.local_node.read().await.clone();
+        node_info.apply_diff(&diff).map_err(|e| {
+            NetworkError::MessageError(ErrorCode::InvalidMessage, format!("Invalid diff: {e}"))
+        })?;
+        self.update_peers(node_info).await
+    }
+
+    async fn subscribe_to_peer_node_info(&self) -> broadcast::Receiver<NodeInfo> {
+        self.inner.peer_node_info_sender.subscribe()
+    }
+}
@@ -7,48 +7,132 @@
 use anyhow::{anyhow, Context, Result};
 use async_trait::async_trait;
 use hex;
+use p256::ecdsa::SigningKey;
+use rand::Rng;
 use runar_common::logging::{Component, Logger};
-use runar_common::types::schemas::{ActionMetadata, ServiceMetadata};
-use runar_common::types::{ArcValue, EventMetadata, SerializerRegistry};
+use runar_common::types::schemas::{ActionMetadata, ServiceMetadata, ServiceVisibility};
+use runar_common::types::{ArcValue, EventMetadata, SerializationBackend, SerializerRegistry};
 use runar_keys::{node::NodeKeyManagerState, NodeKeyManager};
 use socket2;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fmt::Debug;
 use std::future::Future;
+use std::net::SocketAddr;
 use std::pin::Pin;
+use std::task::{Context as TaskContext, Poll};
 use tokio::time::{sleep, Duration};
+use tokio_stream::{Stream, StreamExt};
+use tokio_util::sync::CancellationToken;
 
-use std::sync::atomic::{AtomicBool, AtomicI64, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicI64, AtomicUsize, Ordering};
 use std::sync::Arc;
-use tokio::sync::{oneshot, RwLock};
+use tokio::sync::{mpsc, RwLock};
 
 use crate::network::discovery::multicast_discovery::PeerInfo;
-use crate::network::discovery::{DiscoveryOptions, MulticastDiscovery, NodeDiscovery, NodeInfo};
+use crate::network::discovery::{
+    DiscoveryOptions, MdnsDiscovery, MulticastDiscovery, NodeDiscovery, NodeInfo,
+};
+use crate::network::transport::quic_transport::DEFAULT_SERVICE_UPDATE_DEBOUNCE_MS;
+#[cfg(unix)]
+use crate::network::transport::UnixSocketTransport;
+use crate::network::transport::{
+    message_signing_key, ErrorCode, MultiTransport, NetworkError, NetworkErrorPayload,
+    NetworkMessage, NetworkMessagePayloadItem, NetworkTransport, NodeCapabilities, PeerEvent,
+    PeerFilter, PeerId, PeerRegistry, PeerStatus, QuicTransport, TransportStats, DEFAULT_MAX_HOPS,
+};
 use crate::network::transport::{
-    NetworkMessage, NetworkMessagePayloadItem, NetworkTransport, PeerId, QuicTransport,
+    request_auth, AuthenticatedTransport, RequestAuthConfig, RequestAuthenticator,
 };
 
 pub(crate) type NodeDiscoveryList = Vec<Arc<dyn NodeDiscovery>>;
 // Certificate and PrivateKey types are now imported via the cert_utils module
-use crate::config::LoggingConfig;
+use crate::config::{ComponentKey, ConfigurationError, LoggingConfig};
 use crate::network::network_config::{DiscoveryProviderConfig, NetworkConfig, TransportType};
 
 use crate::routing::TopicPath;
+use crate::services::abstract_service::with_held_service_locks;
+use crate::services::access_policy::{self, ServiceAccessPolicy, CALLING_SERVICE};
+use crate::services::action_metrics::{MetricsRecorder, MetricsRegistry};
+use crate::services::background_tasks::BackgroundTasks;
+use crate::services::circuit_breaker::{CircuitBreaker, CircuitBreakerConfig};
+use crate::services::dead_letter::{
+    DeadLetterEntry, DeadLetterQueue, DEFAULT_DEAD_LETTER_QUEUE_SIZE,
+};
+use crate::services::event_dedup::{
+    event_dedup_id, sequenced_event_dedup_id, EventDedupCache, DEFAULT_EVENT_DEDUP_WINDOW,
+};
+use crate::services::event_dispatch::{DispatchMode, EventDispatcher};
+use crate::services::event_ordering::{
+    OrderedEventBuffer, DEFAULT_ORDERED_EVENT_BUFFER_SIZE, DEFAULT_ORDERED_EVENT_TIMEOUT,
+};
+use crate::services::event_replay::{
+    EventReplayBuffer, ReplaySince, DEFAULT_RETAIN_MAX_BYTES_PER_TOPIC,
+};
+use crate::services::health::{HealthGossip, HealthReport, DEFAULT_HEALTH_GOSSIP_INTERVAL};
 use crate::services::keys_service::KeysService;
 use crate::services::load_balancing::{LoadBalancingStrategy, RoundRobinLoadBalancer};
+use crate::services::middleware::Middleware;
+use crate::services::rate_limit::RateLimitExceeded;
 use crate::services::registry_service::RegistryService;
 use crate::services::remote_service::{
-    CreateRemoteServicesConfig, RemoteService, RemoteServiceDependencies,
+    CreateRemoteServicesConfig, PendingRequest, PendingRequests, RemoteService,
+    RemoteServiceDependencies,
+};
+use crate::services::response_stream::{
+    DEFAULT_RESPONSE_STREAM_BUFFER, MAX_BUFFERED_STREAM_FRAMES,
 };
+use crate::services::retry_budget::{retry_backoff, RetryBucket, RetryBudget};
 use crate::services::service_registry::{ServiceEntry, ServiceRegistry};
+use crate::services::service_restart::{RestartDecision, ServiceRestarts};
+use crate::services::subscription_groups::{
+    PendingGroupEvent, SubscriptionGroups, DEFAULT_SUBSCRIPTION_GROUP_QUEUE_SIZE,
+    DEFAULT_SUBSCRIPTION_GROUP_QUEUE_TTL,
+};
 use crate::services::NodeDelegate;
 use crate::services::{
     ActionHandler, /* EventContext, NodeDelegate, */ EventCallback, EventRegistrationOptions,
-    PublishOptions, RegistryDelegate, RemoteLifecycleContext, RequestContext,
+    PublishOptions, RegistryDelegate, RemoteLifecycleContext, RequestContext, ResponseStream,
+    ResponseStreamSender, ServiceFuture,
 };
 use crate::services::{EventContext, KeysDelegate}; // Explicit import for EventContext
+use crate::telemetry::{OtlpConfig, OtlpExporter};
 use crate::{AbstractService, ServiceState};
+use opentelemetry_sdk::trace::SdkTracer;
 use runar_common::types::AsArcValue;
+use tracing::Instrument;
+use tracing_opentelemetry::OpenTelemetryLayer;
+
+/// Handler a transport passes the messages it receives to
+pub type TransportMessageHandler =
+    Box<dyn Fn(NetworkMessage) -> Result<(), NetworkError> + Send + Sync + 'static>;
+
+/// Builds the network transport of a node in place of the configured one
+///
+/// INTENTION: Let tests run nodes over an in-memory transport, such as the
+/// one of `testing::NetworkPartition`. The factory is given the local node
+/// info and the handler of the messages the transport receives.
+#[derive(Clone)]
+pub struct TransportFactory(
+    Arc<dyn Fn(NodeInfo, TransportMessageHandler) -> Box<dyn NetworkTransport> + Send + Sync>,
+);
+
+impl TransportFactory {
+    /// Wrap the function building the transport
+    pub fn new(
+        build: impl Fn(NodeInfo, TransportMessageHandler) -> Box<dyn NetworkTransport>
+            + Send
+            + Sync
+            + 'static,
+    ) -> Self {
+        Self(Arc::new(build))
+    }
+}
+
+impl Debug for TransportFactory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("TransportFactory")
+    }
+}
 
 /// Node Configuration
 ///
@@ -75,6 +159,75 @@ pub struct NodeConfig {
     //FIX: move this to the network config.. local sercvies shuold not have timeout checks.
     /// Request timeout in milliseconds
     pub request_timeout_ms: u64,
+
+    /// Circuit breakers for remote requests, keyed by service path
+    pub circuit_breakers: HashMap<String, CircuitBreakerConfig>,
+
+    /// Retry budgets for the retries of `Node::request_with_retries`, keyed by service path
+    pub retry_budgets: HashMap<String, RetryBudget>,
+
+    /// Visibility of services to peers, overriding `AbstractService::visibility`, keyed by service path
+    pub capability_advertisement: HashMap<String, ServiceVisibility>,
+
+    /// Peers connected during `Node::start`, without waiting for discovery
+    pub initial_peers: Vec<(SocketAddr, PeerId)>,
+
+    /// Shared-secret authentication of messages between nodes (None = disabled)
+    pub request_auth: Option<RequestAuthConfig>,
+
+    /// Number of recent events remembered to drop duplicates (0 = disabled)
+    pub event_dedup_window: usize,
+
+    /// Timeout applied to `Node::request` calls without their own (None = disabled)
+    pub default_request_timeout: Option<Duration>,
+
+    /// Maximum number of failed event deliveries kept for replay (0 = disabled)
+    pub dead_letter_queue_size: usize,
+
+    /// Maximum number of out-of-order events buffered for ordered delivery
+    pub ordered_event_buffer_size: usize,
+
+    /// How long an out-of-order event waits for the events before it
+    pub ordered_event_timeout: Duration,
+
+    /// Maximum number of events queued per subscription group without members
+    pub subscription_group_queue_size: usize,
+
+    /// How long events queued for a subscription group, and groups without members, are kept
+    pub subscription_group_queue_ttl: Duration,
+
+    /// Maximum number of bytes of events retained for replay per topic
+    pub retain_max_bytes_per_topic: usize,
+
+    /// Export of the node's spans to an OTLP endpoint (None = disabled)
+    pub telemetry: Option<OtlpConfig>,
+
+    /// Recorder of the action metrics (None = the global `metrics` recorder)
+    pub metrics_registry: Option<MetricsRegistry>,
+
+    /// API version added to requested paths without one (None = disabled)
+    pub default_api_version: Option<u8>,
+
+    /// Stop the node when the process receives SIGTERM or SIGINT
+    pub unix_signal_handling: bool,
+
+    /// How long `Node::stop` waits for in-flight requests before stopping services
+    pub shutdown_drain_period: Duration,
+
+    /// Interval between two health reports sent to the peers (None = disabled)
+    pub health_gossip_interval: Option<Duration>,
+
+    /// Encoding of the payloads this node serializes
+    pub serialization_backend: SerializationBackend,
+
+    /// Only peers this node connects with (None = every peer not blacklisted)
+    pub peer_whitelist: Option<HashSet<PeerId>>,
+
+    /// Peers this node never connects with (None = none)
+    pub peer_blacklist: Option<HashSet<PeerId>>,
+
+    /// Builds the network transport instead of the network config (None = disabled)
+    pub transport_factory: Option<TransportFactory>,
 }
 
 impl NodeConfig {
@@ -91,6 +244,29 @@ impl NodeConfig {
             logging_config: Some(LoggingConfig::default_info()), // Default to Info logging
             key_manager_state: None, // Must be set via with_key_manager_state()
             request_timeout_ms: 30000, // 30 seconds
+            circuit_breakers: HashMap::new(),
+            retry_budgets: HashMap::new(),
+            capability_advertisement: HashMap::new(),
+            initial_peers: Vec::new(),
+            request_auth: None,
+            event_dedup_window: DEFAULT_EVENT_DEDUP_WINDOW,
+            default_request_timeout: None,
+            dead_letter_queue_size: DEFAULT_DEAD_LETTER_QUEUE_SIZE,
+            ordered_event_buffer_size: DEFAULT_ORDERED_EVENT_BUFFER_SIZE,
+            ordered_event_timeout: DEFAULT_ORDERED_EVENT_TIMEOUT,
+            subscription_group_queue_size: DEFAULT_SUBSCRIPTION_GROUP_QUEUE_SIZE,
+            subscription_group_queue_ttl: DEFAULT_SUBSCRIPTION_GROUP_QUEUE_TTL,
+            retain_max_bytes_per_topic: DEFAULT_RETAIN_MAX_BYTES_PER_TOPIC,
+            telemetry: None,
+            metrics_registry: None,
+            default_api_version: None,
+            unix_signal_handling: false,
+            shutdown_drain_period: Duration::ZERO,
+            health_gossip_interval: None,
+            serialization_backend: SerializationBackend::default(),
+            peer_whitelist: None,
+            peer_blacklist: None,
+            transport_factory: None,
         }
     }
 
@@ -118,6 +294,238 @@ impl NodeConfig {
         self
     }
 
+    /// Guard remote requests to a service path with a circuit breaker
+    pub fn with_circuit_breaker(
+        mut self,
+        service_path: impl Into<String>,
+        config: CircuitBreakerConfig,
+    ) -> Self {
+        self.circuit_breakers.insert(service_path.into(), config);
+        self
+    }
+
+    /// Limit the retries of requests to a service path with a retry budget
+    pub fn with_retry_budget(
+        mut self,
+        service_path: impl Into<String>,
+        budget: RetryBudget,
+    ) -> Self {
+        self.retry_budgets.insert(service_path.into(), budget);
+        self
+    }
+
+    /// Set which peers the service at `service_path` is advertised to
+    ///
+    /// INTENTION: Restrict the advertisement of services the application does
+    /// not implement itself, or change it per deployment. Takes precedence over
+    /// the service's own `AbstractService::visibility`.
+    pub fn with_capability_advertisement(
+        mut self,
+        service_path: impl Into<String>,
+        visibility: ServiceVisibility,
+    ) -> Self {
+        self.capability_advertisement
+            .insert(service_path.into(), visibility);
+        self
+    }
+
+    /// Set the peers to connect to when the node starts
+    ///
+    /// INTENTION: Support static cluster membership where multicast discovery
+    /// is unavailable. Each peer is connected with `Node::add_remote_peer`.
+    pub fn with_initial_peers(mut self, peers: Vec<(SocketAddr, PeerId)>) -> Self {
+        self.initial_peers = peers;
+        self
+    }
+
+    /// Authenticate messages between nodes with a shared secret key
+    ///
+    /// INTENTION: Only peers configured with the same key can call this
+    /// node's services; keys can later be changed with `Node::rotate_auth_key`.
+    pub fn with_request_auth(mut self, config: RequestAuthConfig) -> Self {
+        self.request_auth = Some(config);
+        self
+    }
+
+    /// Set how many recent events are remembered to drop duplicates
+    ///
+    /// INTENTION: Bound the memory spent on deduplication; an event that
+    /// arrives again after `window` newer events is delivered again. A
+    /// window of 0 disables deduplication.
+    pub fn with_event_dedup_window(mut self, window: usize) -> Self {
+        self.event_dedup_window = window;
+        self
+    }
+
+    /// Set the default timeout for all service requests
+    ///
+    /// INTENTION: Give operators a single safety net against requests that
+    /// never complete. The timeout covers the total round-trip: serialization,
+    /// network, remote deserialization and handler execution. A timeout given
+    /// to `Node::request_with_timeout` takes precedence.
+    pub fn with_service_timeout(mut self, timeout: Duration) -> Self {
+        self.default_request_timeout = Some(timeout);
+        self
+    }
+
+    /// Set how many failed event deliveries are kept in the dead-letter queue
+    ///
+    /// INTENTION: Bound the memory spent on failed deliveries; once the queue
+    /// is full the oldest entry is evicted. A size of 0 disables the queue.
+    pub fn with_dead_letter_queue_size(mut self, size: usize) -> Self {
+        self.dead_letter_queue_size = size;
+        self
+    }
+
+    /// Set how out-of-order events published with `ordered: true` are buffered
+    ///
+    /// INTENTION: Bound the memory and latency spent waiting for missing
+    /// events. At most `size` events are held back across all publishers;
+    /// when a gap is not filled within `timeout` the events behind it are
+    /// discarded. A size of 0 disables buffering.
+    pub fn with_ordered_event_buffer(mut self, size: usize, timeout: Duration) -> Self {
+        self.ordered_event_buffer_size = size;
+        self.ordered_event_timeout = timeout;
+        self
+    }
+
+    /// Set how many events are queued for a subscription group without members
+    ///
+    /// INTENTION: Keep the events of a worker group while all its members are
+    /// away, e.g. during a restart, without unbounded growth. Once a group's
+    /// queue is full its oldest event is dropped. A size of 0 drops the events
+    /// right away.
+    pub fn with_subscription_group_queue_size(mut self, size: usize) -> Self {
+        self.subscription_group_queue_size = size;
+        self
+    }
+
+    /// Set how long events stay queued for a subscription group
+    ///
+    /// Events older than `ttl` are dropped instead of being handed to the
+    /// next member, and a group whose members all left is forgotten once it
+    /// had neither members nor queued events for `ttl`.
+    pub fn with_subscription_group_queue_ttl(mut self, ttl: Duration) -> Self {
+        self.subscription_group_queue_ttl = ttl;
+        self
+    }
+
+    /// Set how many bytes of events are retained for replay per topic
+    ///
+    /// INTENTION: Bound the memory spent on events published with
+    /// `PublishOptions::retain_last`. The oldest events of a topic are evicted
+    /// once its serialized events exceed `max_bytes`; a larger event is not
+    /// retained at all.
+    pub fn with_retain_max_bytes_per_topic(mut self, max_bytes: usize) -> Self {
+        self.retain_max_bytes_per_topic = max_bytes;
+        self
+    }
+
+    /// Export the node's spans to an OTLP endpoint such as Jaeger
+    ///
+    /// The node does not install a `tracing` subscriber; add the layer
+    /// returned by `Node::telemetry_layer` to the application's subscriber.
+    pub fn with_telemetry(mut self, config: OtlpConfig) -> Self {
+        self.telemetry = Some(config);
+        self
+    }
+
+    /// Record the metrics of `#[action(metrics = true)]` actions in `recorder`
+    ///
+    /// The recorder only applies to the actions of this node, so several nodes
+    /// of a process can report to different backends.
+    pub fn with_metrics_registry(mut self, recorder: Arc<MetricsRecorder>) -> Self {
+        self.metrics_registry = Some(MetricsRegistry::new(recorder));
+        self
+    }
+
+    /// Request version `version` of actions whose path has no version
+    ///
+    /// `Node::request("math/add")` then routes to `math/v{version}/add`,
+    /// falling back to `math/add` for services without that version.
+    pub fn with_default_api_version(mut self, version: u8) -> Self {
+        self.default_api_version = Some(version);
+        self
+    }
+
+    /// Stop the node gracefully when the process receives SIGTERM or SIGINT
+    ///
+    /// INTENTION: Let containerized nodes shut down cleanly instead of being
+    /// killed with requests in flight. `Node::start` installs the signal
+    /// handlers and a signal runs `Node::stop`, including the shutdown drain
+    /// period; `Node::wait_for_shutdown` returns once the node has stopped.
+    /// Ignored on platforms without Unix signals.
+    pub fn with_unix_signal_handling(mut self, enabled: bool) -> Self {
+        self.unix_signal_handling = enabled;
+        self
+    }
+
+    /// Set how long `Node::stop` waits for in-flight requests
+    ///
+    /// Requests still being handled by local actions when the period is over
+    /// are not waited for. Defaults to zero (no waiting).
+    pub fn with_shutdown_drain_period(mut self, period: Duration) -> Self {
+        self.shutdown_drain_period = period;
+        self
+    }
+
+    /// Send the node's health report to its peers every `interval`
+    ///
+    /// INTENTION: Give every node of a mesh a view of the whole cluster's
+    /// health through `Node::cluster_health`. Reports of a peer not refreshed
+    /// within two intervals are marked as stale, so all nodes of a network
+    /// should use the same interval.
+    pub fn with_health_gossip_interval(mut self, interval: Duration) -> Self {
+        self.health_gossip_interval = Some(interval);
+        self
+    }
+
+    /// Encode the payloads this node sends with `backend`
+    ///
+    /// INTENTION: Let a node use another encoding, e.g. one readable by
+    /// debugging tools, in a cluster using the default. Every payload names
+    /// its encoding, so peers decode it whatever their own backend is, as
+    /// long as it is compiled into them. Peers that cannot decode `backend`
+    /// are refused in the handshake (see `NodeCapabilities::supported_formats`).
+    pub fn with_serialization_backend(mut self, backend: SerializationBackend) -> Self {
+        self.serialization_backend = backend;
+        self
+    }
+
+    /// Only connect with the peers in `whitelist`
+    ///
+    /// INTENTION: Keep a production node from talking to arbitrary nodes
+    /// that discover it. Connections with other peers are refused in both
+    /// directions and the peers are not recorded in the peer registry. Can
+    /// be changed while the node runs with `Node::update_peer_whitelist`.
+    pub fn with_peer_whitelist(mut self, whitelist: HashSet<PeerId>) -> Self {
+        self.peer_whitelist = Some(whitelist);
+        self
+    }
+
+    /// Never connect with the peers in `blacklist`
+    ///
+    /// Takes precedence over the whitelist. Can be changed while the node
+    /// runs with `Node::update_peer_blacklist`.
+    pub fn with_peer_blacklist(mut self, blacklist: HashSet<PeerId>) -> Self {
+        self.peer_blacklist = Some(blacklist);
+        self
+    }
+
+    /// Build the network transport with `factory` instead of from the network config
+    ///
+    /// The network config is still required, for networking to be enabled
+    /// and for discovery. Request authentication still wraps the transport.
+    pub fn with_transport_factory(mut self, factory: TransportFactory) -> Self {
+        self.transport_factory = Some(factory);
+        self
+    }
+
+    /// Timeout applied to requests that do not set their own
+    pub fn default_request_timeout(&self) -> Option<Duration> {
+        self.default_request_timeout
+    }
+
     /// Set the key manager state from serialized bytes
     pub fn with_key_manager_state(mut self, key_state_bytes: Vec<u8>) -> Self {
         self.key_manager_state = Some(key_state_bytes);
@@ -125,6 +533,275 @@ impl NodeConfig {
     }
 }
 
+impl NodeConfig {
+    /// Create a builder that validates the configuration it builds
+    pub fn builder(
+        node_id: impl Into<String>,
+        default_network_id: impl Into<String>,
+    ) -> NodeConfigBuilder {
+        NodeConfigBuilder {
+            config: Self::new(node_id, default_network_id),
+        }
+    }
+
+    /// Check for combinations of options that cannot work together
+    ///
+    /// INTENTION: Report every problem at once, before the node is created,
+    /// instead of failing on the first one at `Node::start`.
+    pub fn validate(&self) -> Result<(), Vec<ConfigurationError>> {
+        let mut errors = Vec::new();
+
+        match &self.network_config {
+            None => {
+                if !self.initial_peers.is_empty() {
+                    errors.push(ConfigurationError::NetworkRequiredForDiscovery);
+                }
+            }
+            Some(network_config) => {
+                let providers = &network_config.discovery_providers;
+                if network_config.discovery_options.is_some() && providers.is_empty() {
+                    errors.push(ConfigurationError::MissingDiscoveryProviders);
+                }
+
+                let count = |matches: fn(&DiscoveryProviderConfig) -> bool| {
+                    providers.iter().filter(|p| matches(p)).count()
+                };
+                let multicast = count(|p| matches!(p, DiscoveryProviderConfig::Multicast(_)));
+                let mdns = count(|p| matches!(p, DiscoveryProviderConfig::Mdns(_)));
+                let static_lists = count(|p| matches!(p, DiscoveryProviderConfig::Static(_)));
+                if multicast > 1 || mdns > 1 || static_lists > 1 {
+                    errors.push(ConfigurationError::ConflictingDiscoveryModes(
+                        "a discovery provider is configured more than once".to_string(),
+                    ));
+                }
+                if static_lists > 0 && !self.initial_peers.is_empty() {
+                    errors.push(ConfigurationError::ConflictingDiscoveryModes(
+                        "both a static discovery provider and initial peers are configured"
+                            .to_string(),
+                    ));
+                }
+            }
+        }
+
+        if let Some(logging_config) = &self.logging_config {
+            let empty_component = logging_config
+                .component_levels
+                .keys()
+                .any(|key| matches!(key, ComponentKey::Custom(name) if name.trim().is_empty()));
+            if empty_component {
+                errors.push(ConfigurationError::InvalidLogLevel {
+                    target: String::new(),
+                    reason: "component name is empty".to_string(),
+                });
+            }
+            for path in logging_config.service_path_levels.keys() {
+                if path.trim().is_empty() || path.contains(char::is_whitespace) {
+                    errors.push(ConfigurationError::InvalidLogLevel {
+                        target: path.clone(),
+                        reason: "service path must be non-empty and contain no whitespace"
+                            .to_string(),
+                    });
+                }
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+/// Builder for a `NodeConfig` that is checked with `NodeConfig::validate`
+///
+/// INTENTION: Make an invalid configuration impossible to pass to
+/// `Node::new`; `build` returns all problems found.
+#[derive(Clone, Debug)]
+pub struct NodeConfigBuilder {
+    config: NodeConfig,
+}
+
+impl NodeConfigBuilder {
+    /// See `NodeConfig::with_network_config`
+    pub fn network_config(mut self, config: NetworkConfig) -> Self {
+        self.config = self.config.with_network_config(config);
+        self
+    }
+
+    /// See `NodeConfig::with_logging_config`
+    pub fn logging_config(mut self, config: LoggingConfig) -> Self {
+        self.config = self.config.with_logging_config(config);
+        self
+    }
+
+    /// See `NodeConfig::with_additional_networks`
+    pub fn additional_networks(mut self, network_ids: Vec<String>) -> Self {
+        self.config = self.config.with_additional_networks(network_ids);
+        self
+    }
+
+    /// See `NodeConfig::with_request_timeout`
+    pub fn request_timeout(mut self, timeout_ms: u64) -> Self {
+        self.config = self.config.with_request_timeout(timeout_ms);
+        self
+    }
+
+    /// See `NodeConfig::with_circuit_breaker`
+    pub fn circuit_breaker(
+        mut self,
+        service_path: impl Into<String>,
+        config: CircuitBreakerConfig,
+    ) -> Self {
+        self.config = self.config.with_circuit_breaker(service_path, config);
+        self
+    }
+
+    /// See `NodeConfig::with_retry_budget`
+    pub fn retry_budget(mut self, service_path: impl Into<String>, budget: RetryBudget) -> Self {
+        self.config = self.config.with_retry_budget(service_path, budget);
+        self
+    }
+
+    /// See `NodeConfig::with_capability_advertisement`
+    pub fn capability_advertisement(
+        mut self,
+        service_path: impl Into<String>,
+        visibility: ServiceVisibility,
+    ) -> Self {
+        self.config = self
+            .config
+            .with_capability_advertisement(service_path, visibility);
+        self
+    }
+
+    /// See `NodeConfig::with_initial_peers`
+    pub fn initial_peers(mut self, peers: Vec<(SocketAddr, PeerId)>) -> Self {
+        self.config = self.config.with_initial_peers(peers);
+        self
+    }
+
+    /// See `NodeConfig::with_request_auth`
+    pub fn request_auth(mut self, config: RequestAuthConfig) -> Self {
+        self.config = self.config.with_request_auth(config);
+        self
+    }
+
+    /// See `NodeConfig::with_event_dedup_window`
+    pub fn event_dedup_window(mut self, window: usize) -> Self {
+        self.config = self.config.with_event_dedup_window(window);
+        self
+    }
+
+    /// See `NodeConfig::with_service_timeout`
+    pub fn service_timeout(mut self, timeout: Duration) -> Self {
+        self.config = self.config.with_service_timeout(timeout);
+        self
+    }
+
+    /// See `NodeConfig::with_dead_letter_queue_size`
+    pub fn dead_letter_queue_size(mut self, size: usize) -> Self {
+        self.config = self.config.with_dead_letter_queue_size(size);
+        self
+    }
+
+    /// See `NodeConfig::with_ordered_event_buffer`
+    pub fn ordered_event_buffer(mut self, size: usize, timeout: Duration) -> Self {
+        self.config = self.config.with_ordered_event_buffer(size, timeout);
+        self
+    }
+
+    /// See `NodeConfig::with_subscription_group_queue_size`
+    pub fn subscription_group_queue_size(mut self, size: usize) -> Self {
+        self.config = self.config.with_subscription_group_queue_size(size);
+        self
+    }
+
+    /// See `NodeConfig::with_subscription_group_queue_ttl`
+    pub fn subscription_group_queue_ttl(mut self, ttl: Duration) -> Self {
+        self.config = self.config.with_subscription_group_queue_ttl(ttl);
+        self
+    }
+
+    /// See `NodeConfig::with_retain_max_bytes_per_topic`
+    pub fn retain_max_bytes_per_topic(mut self, max_bytes: usize) -> Self {
+        self.config = self.config.with_retain_max_bytes_per_topic(max_bytes);
+        self
+    }
+
+    /// See `NodeConfig::with_telemetry`
+    pub fn telemetry(mut self, config: OtlpConfig) -> Self {
+        self.config = self.config.with_telemetry(config);
+        self
+    }
+
+    /// See `NodeConfig::with_metrics_registry`
+    pub fn metrics_registry(mut self, recorder: Arc<MetricsRecorder>) -> Self {
+        self.config = self.config.with_metrics_registry(recorder);
+        self
+    }
+
+    /// See `NodeConfig::with_default_api_version`
+    pub fn default_api_version(mut self, version: u8) -> Self {
+        self.config = self.config.with_default_api_version(version);
+        self
+    }
+
+    /// See `NodeConfig::with_unix_signal_handling`
+    pub fn unix_signal_handling(mut self, enabled: bool) -> Self {
+        self.config = self.config.with_unix_signal_handling(enabled);
+        self
+    }
+
+    /// See `NodeConfig::with_shutdown_drain_period`
+    pub fn shutdown_drain_period(mut self, period: Duration) -> Self {
+        self.config = self.config.with_shutdown_drain_period(period);
+        self
+    }
+
+    /// See `NodeConfig::with_health_gossip_interval`
+    pub fn health_gossip_interval(mut self, interval: Duration) -> Self {
+        self.config = self.config.with_health_gossip_interval(interval);
+        self
+    }
+
+    /// See `NodeConfig::with_serialization_backend`
+    pub fn serialization_backend(mut self, backend: SerializationBackend) -> Self {
+        self.config = self.config.with_serialization_backend(backend);
+        self
+    }
+
+    /// See `NodeConfig::with_peer_whitelist`
+    pub fn peer_whitelist(mut self, whitelist: HashSet<PeerId>) -> Self {
+        self.config = self.config.with_peer_whitelist(whitelist);
+        self
+    }
+
+    /// See `NodeConfig::with_peer_blacklist`
+    pub fn peer_blacklist(mut self, blacklist: HashSet<PeerId>) -> Self {
+        self.config = self.config.with_peer_blacklist(blacklist);
+        self
+    }
+
+    /// See `NodeConfig::with_transport_factory`
+    pub fn transport_factory(mut self, factory: TransportFactory) -> Self {
+        self.config = self.config.with_transport_factory(factory);
+        self
+    }
+
+    /// See `NodeConfig::with_key_manager_state`
+    pub fn key_manager_state(mut self, key_state_bytes: Vec<u8>) -> Self {
+        self.config = self.config.with_key_manager_state(key_state_bytes);
+        self
+    }
+
+    /// Validate and return the configuration
+    pub fn build(self) -> Result<NodeConfig, Vec<ConfigurationError>> {
+        self.config.validate()?;
+        Ok(self.config)
+    }
+}
+
 // Implement Display for NodeConfig to enable logging it directly
 impl std::fmt::Display for NodeConfig {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -152,10 +829,16 @@ pub struct Node {
     /// Debounce state for notify_node_change.
     ///
     /// INTENTION: Ensures that rapid successive calls to notify_node_change only trigger a single
-    /// notification after a 5s debounce window. This prevents unnecessary network traffic and ensures
+    /// notification at the end of the debounce window. This prevents unnecessary network traffic and ensures
     /// only the latest node state is broadcast. Internal use only; not exposed outside Node.
     debounce_notify_task: std::sync::Arc<tokio::sync::Mutex<Option<tokio::task::JoinHandle<()>>>>,
 
+    /// Node info last sent to peers by notify_node_change_impl
+    ///
+    /// INTENTION: Later changes are sent as a diff against it. Shared between clones, since the
+    /// debounced notification runs on a clone.
+    last_broadcast_node_info: Arc<tokio::sync::Mutex<Option<NodeInfo>>>,
+
     /// Default network id to be used when service are added without a network ID
     pub(crate) network_id: String,
 
@@ -173,12 +856,27 @@ pub struct Node {
 
     pub(crate) known_peers: Arc<RwLock<HashMap<PeerId, NodeInfo>>>,
 
+    /// Registry of connected peers; its change events drive remote service management
+    pub(crate) peer_registry: Arc<PeerRegistry>,
+
+    /// Peers connections are accepted with, shared with the transport
+    pub(crate) peer_filter: Arc<PeerFilter>,
+
+    /// Tags outgoing messages and checks incoming ones when request auth is configured
+    pub(crate) request_authenticator: Option<Arc<RequestAuthenticator>>,
+
     /// Logger instance
     pub(crate) logger: Arc<Logger>,
 
     /// Flag indicating if the node is running
     pub(crate) running: AtomicBool,
 
+    /// Whether the node has fully stopped, shared by all clones of the node
+    pub(crate) stopped: Arc<tokio::sync::watch::Sender<bool>>,
+
+    /// Number of requests being handled by local actions
+    pub(crate) in_flight_requests: Arc<AtomicUsize>,
+
     /// Flag indicating if this node supports networking
     /// This is set when networking is enabled in the config
     pub(crate) supports_networking: bool,
@@ -191,10 +889,58 @@ pub struct Node {
     /// Load balancer for selecting remote handlers
     pub(crate) load_balancer: Arc<RwLock<dyn LoadBalancingStrategy>>,
 
-    /// Pending requests waiting for responses, keyed by correlation ID
-    pub(crate) pending_requests: Arc<RwLock<HashMap<String, oneshot::Sender<Result<ArcValue>>>>>,
+    /// Circuit breakers guarding remote requests, keyed by service path
+    pub(crate) circuit_breakers: Arc<HashMap<String, Arc<CircuitBreaker>>>,
 
-    pub serializer: Arc<RwLock<SerializerRegistry>>,
+    /// Retry budgets shared by all callers retrying requests, keyed by service path
+    pub(crate) retry_budgets: Arc<HashMap<String, Arc<RetryBucket>>>,
+
+    /// Middleware run before every local action handler, in registration order
+    pub(crate) middleware: Arc<RwLock<Vec<Arc<dyn Middleware>>>>,
+
+    /// Access policy of each local service, keyed by service path
+    pub(crate) access_policies: Arc<RwLock<HashMap<TopicPath, ServiceAccessPolicy>>>,
+
+    /// IDs of recently published and received events, to drop duplicates
+    pub(crate) event_dedup: Arc<std::sync::Mutex<EventDedupCache>>,
+
+    /// Event deliveries whose subscriber failed, kept for replay
+    pub(crate) dead_letters: Arc<std::sync::Mutex<DeadLetterQueue>>,
+
+    /// Subscriptions competing for the events of their group
+    pub(crate) subscription_groups: Arc<std::sync::Mutex<SubscriptionGroups>>,
+
+    /// Queues of the events published to local subscribers in the background
+    pub(crate) event_dispatcher: Arc<EventDispatcher<QueuedEvent>>,
+
+    /// Async tasks spawned by the local services, aborted when they stop
+    pub(crate) background_tasks: Arc<BackgroundTasks>,
+
+    /// Restarts of the local services whose actions panicked
+    pub(crate) service_restarts: Arc<ServiceRestarts>,
+
+    /// Exporter of the node's spans, when telemetry is configured
+    pub(crate) telemetry: Option<Arc<OtlpExporter>>,
+
+    /// Last health report received from each peer
+    pub(crate) health_gossip: Arc<std::sync::Mutex<HealthGossip>>,
+
+    /// Recent events of each topic, retained for subscribers joining late
+    pub(crate) event_replay: Arc<std::sync::Mutex<EventReplayBuffer<Option<ArcValue>>>>,
+
+    /// Last sequence number of the ordered and retained events published per topic
+    pub(crate) event_sequences: Arc<std::sync::Mutex<HashMap<String, u64>>>,
+
+    /// Remote ordered events held back until the events before them arrive
+    pub(crate) ordered_events: Arc<std::sync::Mutex<OrderedEventBuffer<Option<ArcValue>>>>,
+
+    /// Pending requests waiting for responses, keyed by correlation ID
+    pub(crate) pending_requests: PendingRequests,
+
+    /// Streamed responses being received, keyed by correlation ID
+    pending_streams: Arc<RwLock<HashMap<String, Arc<std::sync::Mutex<PendingStream>>>>>,
+
+    pub serializer: Arc<RwLock<SerializerRegistry>>,
 
     pub registry_version: Arc<AtomicI64>,
 
@@ -212,19 +958,31 @@ impl Node {
     /// This constructor does not start services - call start() separately
     /// after registering services.
     pub async fn new(config: NodeConfig) -> Result<Self> {
+        if let Err(errors) = config.validate() {
+            let messages: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+            return Err(anyhow!(
+                "Invalid node configuration: {}",
+                messages.join("; ")
+            ));
+        }
+
         let node_id = config.node_id.clone();
-        let logger = Arc::new(Logger::new_root(Component::Node, &node_id));
+        let root_logger = Logger::new_root(Component::Node, &node_id);
 
         // Apply logging configuration (default to Info level if none provided)
-        if let Some(logging_config) = &config.logging_config {
+        let logger = if let Some(logging_config) = &config.logging_config {
             logging_config.apply();
+            let logger = Arc::new(root_logger.with_filter(Arc::new(logging_config.log_filter())));
             logger.debug("Applied custom logging configuration");
+            logger
         } else {
             // Apply default Info logging when no configuration is provided
             let default_config = LoggingConfig::default_info();
             default_config.apply();
+            let logger = Arc::new(root_logger.with_filter(Arc::new(default_config.log_filter())));
             logger.debug("Applied default Info logging configuration");
-        }
+            logger
+        };
 
         // Clone fields before moving config
         let default_network_id = config.default_network_id.clone();
@@ -240,8 +998,15 @@ impl Node {
             "Initializing node '{node_id}' in network '{default_network_id}'...",
         ));
 
+        let peer_filter = Arc::new(PeerFilter::new(
+            config.peer_whitelist.clone(),
+            config.peer_blacklist.clone(),
+        ));
         let service_registry = Arc::new(ServiceRegistry::new(logger.clone()));
         let serializer_logger = Arc::new(logger.with_component(Component::Custom("Serializer")));
+        let mut serializer =
+            SerializerRegistry::with_backend(serializer_logger, config.serialization_backend);
+        serializer.register::<NetworkErrorPayload>()?;
 
         // at this stage the node credentials must already exist and must be in a secure store
         let key_manager_state_bytes = config
@@ -262,8 +1027,53 @@ impl Node {
         logger.info("Successfully loaded existing node credentials.");
         logger.info(format!("Node peer ID (public key): {peer_id}"));
 
+        let circuit_breakers = config
+            .circuit_breakers
+            .iter()
+            .map(|(path, cb_config)| {
+                (
+                    path.clone(),
+                    Arc::new(CircuitBreaker::new(cb_config.clone())),
+                )
+            })
+            .collect::<HashMap<_, _>>();
+        let retry_budgets = config
+            .retry_budgets
+            .iter()
+            .map(|(path, budget)| (path.clone(), Arc::new(RetryBucket::new(budget.clone()))))
+            .collect::<HashMap<_, _>>();
+        let request_authenticator = config
+            .request_auth
+            .as_ref()
+            .map(|auth| Arc::new(RequestAuthenticator::new(auth)));
+        let event_dedup = EventDedupCache::new(config.event_dedup_window);
+        let dead_letters = DeadLetterQueue::new(config.dead_letter_queue_size);
+        let subscription_groups = SubscriptionGroups::new(config.subscription_group_queue_size)
+            .with_queue_ttl(config.subscription_group_queue_ttl);
+        let event_replay = EventReplayBuffer::new(config.retain_max_bytes_per_topic);
+        let health_gossip = HealthGossip::new(
+            config
+                .health_gossip_interval
+                .unwrap_or(DEFAULT_HEALTH_GOSSIP_INTERVAL),
+        );
+        let ordered_events = OrderedEventBuffer::new(
+            config.ordered_event_buffer_size,
+            config.ordered_event_timeout,
+        );
+        let telemetry = match config.telemetry.as_ref() {
+            Some(otlp) => match OtlpExporter::new(otlp, &config.node_id, &default_network_id) {
+                Ok(exporter) => exporter.map(Arc::new),
+                Err(e) => {
+                    logger.warn(format!("Telemetry disabled: {e}"));
+                    None
+                }
+            },
+            None => None,
+        };
+
         let mut node = Self {
             debounce_notify_task: std::sync::Arc::new(tokio::sync::Mutex::new(None)),
+            last_broadcast_node_info: Arc::new(tokio::sync::Mutex::new(None)),
             network_id: default_network_id,
             network_ids,
             peer_id,
@@ -271,15 +1081,34 @@ impl Node {
             logger: logger.clone(),
             service_registry,
             known_peers: Arc::new(RwLock::new(HashMap::new())),
+            peer_registry: Arc::new(PeerRegistry::new().with_peer_filter(peer_filter.clone())),
+            peer_filter,
+            request_authenticator,
             running: AtomicBool::new(false),
+            stopped: Arc::new(tokio::sync::watch::channel(false).0),
+            in_flight_requests: Arc::new(AtomicUsize::new(0)),
             supports_networking: networking_enabled,
             network_transport: Arc::new(RwLock::new(None)),
             network_discovery_providers: Arc::new(RwLock::new(None)),
             load_balancer: Arc::new(RwLock::new(RoundRobinLoadBalancer::new())),
+            circuit_breakers: Arc::new(circuit_breakers),
+            retry_budgets: Arc::new(retry_budgets),
+            middleware: Arc::new(RwLock::new(Vec::new())),
+            access_policies: Arc::new(RwLock::new(HashMap::new())),
+            event_dedup: Arc::new(std::sync::Mutex::new(event_dedup)),
+            dead_letters: Arc::new(std::sync::Mutex::new(dead_letters)),
+            subscription_groups: Arc::new(std::sync::Mutex::new(subscription_groups)),
+            event_dispatcher: Arc::new(EventDispatcher::new()),
+            background_tasks: Arc::new(BackgroundTasks::new()),
+            service_restarts: Arc::new(ServiceRestarts::new()),
+            telemetry,
+            health_gossip: Arc::new(std::sync::Mutex::new(health_gossip)),
+            event_replay: Arc::new(std::sync::Mutex::new(event_replay)),
+            event_sequences: Arc::new(std::sync::Mutex::new(HashMap::new())),
+            ordered_events: Arc::new(std::sync::Mutex::new(ordered_events)),
             pending_requests: Arc::new(RwLock::new(HashMap::new())),
-            serializer: Arc::new(RwLock::new(SerializerRegistry::with_defaults(
-                serializer_logger,
-            ))),
+            pending_streams: Arc::new(RwLock::new(HashMap::new())),
+            serializer: Arc::new(RwLock::new(serializer)),
             registry_version: Arc::new(AtomicI64::new(0)),
             keys_manager: Arc::new(tokio::sync::RwLock::new(keys_manager)),
         };
@@ -300,6 +1129,82 @@ impl Node {
         Ok(node)
     }
 
+    /// Add middleware run before every local action handler
+    ///
+    /// INTENTION: Apply node-wide request policies, such as rate limits, to
+    /// all services. Middleware runs in the order it was added; the first one
+    /// returning an error rejects the request and the handler is not called.
+    pub async fn add_middleware(&self, middleware: impl Middleware + 'static) {
+        self.middleware.write().await.push(Arc::new(middleware));
+    }
+
+    /// Run the registered middleware for a request about to reach its handler
+    async fn run_middleware(&self, context: &RequestContext) -> Result<()> {
+        for middleware in self.middleware.read().await.iter() {
+            if let Err(e) = middleware.before_request(context) {
+                self.logger.warn(format!(
+                    "Request rejected by middleware: {} ({e})",
+                    context.topic_path
+                ));
+                return Err(e);
+            }
+        }
+        Ok(())
+    }
+
+    /// Check the caller of a request against the target service's access policy
+    async fn check_access(&self, context: &RequestContext) -> Result<()> {
+        let service_topic = TopicPath::new_service(
+            &context.topic_path.network_id(),
+            &context.topic_path.service_path(),
+        );
+        let Some(policy) = self
+            .access_policies
+            .read()
+            .await
+            .get(&service_topic)
+            .cloned()
+        else {
+            return Ok(());
+        };
+        let calling_service = access_policy::calling_service();
+        if let Err(e) = policy.check(context.peer_info(), calling_service.as_deref()) {
+            let caller = match (context.peer_info(), calling_service) {
+                (Some(peer), _) => format!("peer {peer}"),
+                (None, Some(service)) => format!("service {service}"),
+                (None, None) => "local caller".to_string(),
+            };
+            self.logger.warn(format!(
+                "Request denied by access policy: {} from {caller}",
+                context.topic_path
+            ));
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    /// Replace the access policy of a local service
+    ///
+    /// INTENTION: Let trust decisions change while the node runs, for example
+    /// when a peer is revoked. The new policy applies to requests dispatched
+    /// after this call returns.
+    pub async fn update_service_policy(
+        &self,
+        path: &str,
+        policy: ServiceAccessPolicy,
+    ) -> Result<()> {
+        let topic = TopicPath::new(path, &self.network_id)
+            .map_err(|e| anyhow!("Invalid service path {path}: {e}"))?;
+        let service_topic = TopicPath::new_service(&topic.network_id(), &topic.service_path());
+        match self.access_policies.write().await.get_mut(&service_topic) {
+            Some(current) => {
+                *current = policy;
+                Ok(())
+            }
+            None => Err(anyhow!("No local service found at path {service_topic}")),
+        }
+    }
+
     /// Add a service to this node
     ///
     /// 1: validate service path    
@@ -311,10 +1216,21 @@ impl Node {
     /// INTENTION: Register a service with this node, making its actions available
     /// for requests and allowing it to receive events. This method initializes the
     /// service but does not start it - services are started when the node is started.
+    ///
+    /// With compact framing the serializer registry is sealed when the node starts,
+    /// since peers negotiate its type IDs in the handshake; services can then no
+    /// longer register types, and adding one to the running node fails.
     pub async fn add_service<S: AbstractService + 'static>(
         &mut self,
         mut service: S,
     ) -> Result<()> {
+        if self.running.load(Ordering::SeqCst) && self.serializer.read().await.is_sealed() {
+            return Err(anyhow!(
+                "Cannot add service '{}' to a running node with compact framing: \
+                 its type registry was sealed at start",
+                service.name()
+            ));
+        }
         let default_network_id = self.network_id.to_string();
         let service_network_id = match service.network_id() {
             Some(id) => id,
@@ -380,6 +1296,9 @@ impl Node {
             .unwrap_or_default()
             .as_secs();
 
+        let access_policy = service.access_policy();
+        let policy_topic =
+            TopicPath::new_service(&service_topic.network_id(), &service_topic.service_path());
         let service_entry = ServiceEntry {
             service: Arc::new(service),
             service_topic,
@@ -387,6 +1306,11 @@ impl Node {
             registration_time: now,
             last_start_time: None, // Will be set when the service is started
         };
+        // Applies from the moment the service becomes reachable
+        self.access_policies
+            .write()
+            .await
+            .insert(policy_topic, access_policy);
         registry
             .register_local_service(Arc::new(service_entry))
             .await?;
@@ -401,6 +1325,199 @@ impl Node {
         Ok(())
     }
 
+    /// Abort the background tasks the service spawned through its context
+    fn abort_background_tasks(&self, service_topic: &TopicPath) {
+        let aborted = self
+            .background_tasks
+            .abort_service(&service_topic.service_path());
+        if aborted > 0 {
+            self.logger.debug(format!(
+                "Aborted {aborted} background task(s) of service {service_topic}"
+            ));
+        }
+    }
+
+    /// Stop and unregister a local service at runtime
+    ///
+    /// INTENTION: Let services that come and go while the node runs, such as
+    /// plugins or tenant shards, be removed again. The service is stopped, its
+    /// actions and subscriptions are unregistered and peers receive updated
+    /// node info. Requests already running complete; new requests for the
+    /// path fail with `ErrorCode::ServiceNotFound`.
+    pub async fn remove_service(&mut self, path: &str) -> Result<()> {
+        let service_topic = TopicPath::new(path, &self.network_id)
+            .map_err(|e| anyhow!("Invalid service path {path}: {e}"))?;
+        self.logger
+            .info(format!("Removing service at path {service_topic}"));
+
+        let service_entry = self
+            .service_registry
+            .remove_local_service(&service_topic)
+            .await?;
+        self.access_policies
+            .write()
+            .await
+            .remove(&TopicPath::new_service(
+                &service_topic.network_id(),
+                &service_topic.service_path(),
+            ));
+
+        let stop_context = crate::services::LifecycleContext::new(
+            &service_topic,
+            self.serializer.clone(),
+            Arc::new(self.clone()), // Node delegate
+            Arc::new(
+                self.logger
+                    .clone()
+                    .with_component(runar_common::Component::Service),
+            ),
+        );
+        if let Err(e) = service_entry.service.stop(stop_context).await {
+            self.logger.error(format!(
+                "Failed to stop removed service: {service_topic}, error: {e}"
+            ));
+        }
+        self.abort_background_tasks(&service_topic);
+        self.service_restarts.remove(&service_topic);
+
+        if self.running.load(Ordering::SeqCst) {
+            self.registry_version.fetch_add(1, Ordering::SeqCst);
+            let _ = self.notify_node_change().await;
+        }
+
+        Ok(())
+    }
+
+    /// Apply the restart policy of the service whose action just panicked
+    ///
+    /// INTENTION: A panicking action may leave its service in a broken state,
+    /// so after the backoff of `AbstractService::restart_policy` the service
+    /// is stopped, unregistered, initialized and started again, and a
+    /// `$registry/service/restarted` event carries its path and restart count.
+    /// Further panics before the restart completed are covered by it. Once
+    /// the restarts are used up the service is marked `Unhealthy` and its
+    /// actions are no longer dispatched.
+    async fn handle_action_panic(&self, topic_path: &TopicPath) {
+        let service_path = topic_path.service_path();
+        let Ok(service_topic) = TopicPath::new(&service_path, &topic_path.network_id()) else {
+            return;
+        };
+        let Some(service_entry) = self
+            .service_registry
+            .get_local_services()
+            .await
+            .remove(&service_topic)
+        else {
+            return;
+        };
+
+        let policy = service_entry.service.restart_policy();
+        let restart = match self.service_restarts.record_panic(&service_topic, &policy) {
+            RestartDecision::Restart(restart) => restart,
+            RestartDecision::Pending => return,
+            RestartDecision::Unhealthy => {
+                self.logger.error(format!(
+                    "Service {service_topic} panicked after {} restart(s), marking it unhealthy",
+                    policy.max_restarts
+                ));
+                let _ = self
+                    .service_registry
+                    .update_service_state(&service_topic, ServiceState::Unhealthy)
+                    .await;
+                return;
+            }
+        };
+
+        self.logger.warn(format!(
+            "Action of service {service_topic} panicked, restarting it in {:?} (restart {restart} of {})",
+            policy.restart_backoff, policy.max_restarts
+        ));
+        let node = self.clone();
+        tokio::spawn(async move {
+            sleep(policy.restart_backoff).await;
+            let result = node.restart_service(&service_entry).await;
+            node.service_restarts
+                .restart_finished(&service_entry.service_topic);
+            match result {
+                Ok(()) => {
+                    let event = ArcValue::new_map(runar_common::hmap! {
+                        "service" => ArcValue::new_primitive(service_path.clone()),
+                        "restarts" => ArcValue::new_primitive(restart)
+                    });
+                    if let Err(e) = node
+                        .publish("$registry/service/restarted".to_string(), Some(event))
+                        .await
+                    {
+                        node.logger
+                            .warn(format!("Failed to publish restart of {service_path}: {e}"));
+                    }
+                }
+                Err(e) => {
+                    node.logger
+                        .error(format!("Failed to restart service {service_path}: {e}"));
+                    let _ = node
+                        .service_registry
+                        .update_service_state(&service_entry.service_topic, ServiceState::Error)
+                        .await;
+                }
+            }
+        });
+    }
+
+    /// Stop a local service and set it up again from `init`
+    async fn restart_service(&self, service_entry: &ServiceEntry) -> Result<()> {
+        let service_topic = &service_entry.service_topic;
+        let service = service_entry.service.clone();
+        let lifecycle_context = || {
+            crate::services::LifecycleContext::new(
+                service_topic,
+                self.serializer.clone(),
+                Arc::new(self.clone()), // Node delegate
+                Arc::new(
+                    self.logger
+                        .clone()
+                        .with_component(runar_common::Component::Service),
+                ),
+            )
+        };
+
+        // Drops the handlers and subscriptions the service registered in init
+        self.service_registry
+            .remove_local_service(service_topic)
+            .await?;
+        if let Err(e) = service.stop(lifecycle_context()).await {
+            self.logger.error(format!(
+                "Failed to stop panicked service: {service_topic}, error: {e}"
+            ));
+        }
+        self.abort_background_tasks(service_topic);
+
+        let init_result = service.init(lifecycle_context()).await;
+        let service_state = if init_result.is_ok() {
+            ServiceState::Initialized
+        } else {
+            ServiceState::Error
+        };
+        self.service_registry
+            .register_local_service(Arc::new(ServiceEntry {
+                service: service.clone(),
+                service_topic: service_topic.clone(),
+                service_state,
+                registration_time: service_entry.registration_time,
+                last_start_time: service_entry.last_start_time,
+            }))
+            .await?;
+        init_result?;
+
+        if self.running.load(Ordering::SeqCst) {
+            service.start(lifecycle_context()).await?;
+            self.service_registry
+                .update_service_state(service_topic, ServiceState::Running)
+                .await?;
+        }
+        Ok(())
+    }
+
     /// Start the Node and all registered services
     ///
     /// INTENTION: Initialize the Node's internal systems and start all registered services.
@@ -415,7 +1532,7 @@ impl Node {
     pub async fn start(&mut self) -> Result<()> {
         self.logger.info("Starting node...");
 
-        if self.running.load(Ordering::SeqCst) {
+        if self.running.load(Ordering::SeqCst) && !*self.stopped.borrow() {
             self.logger.warn("Node already running");
             return Ok(());
         }
@@ -466,10 +1583,35 @@ impl Node {
                     .error(format!("Failed to start networking components: {e}"));
                 return Err(e);
             }
+
+            // Connect the static peers before reporting the node as started, so
+            // their services can be requested as soon as start returns
+            for (peer_addr, peer_id) in self.config.initial_peers.clone() {
+                if let Err(e) = self.add_remote_peer(peer_addr, peer_id.clone()).await {
+                    self.logger.warn(format!(
+                        "Failed to connect to initial peer {peer_id} at {peer_addr}: {e}"
+                    ));
+                }
+            }
         }
 
         self.logger.info("Node started successfully");
         self.running.store(true, Ordering::SeqCst);
+        self.stopped.send_replace(false);
+
+        if self.config.unix_signal_handling {
+            self.spawn_signal_handler()?;
+        }
+
+        if let Some(interval) = self.config.health_gossip_interval {
+            if self.supports_networking {
+                self.spawn_health_gossip(interval);
+            }
+        }
+
+        if self.supports_networking && self.config.ordered_event_buffer_size > 0 {
+            self.spawn_ordered_event_expiry(self.config.ordered_event_timeout);
+        }
 
         self.registry_version.fetch_add(1, Ordering::SeqCst);
 
@@ -480,20 +1622,25 @@ impl Node {
     ///
     /// INTENTION: Gracefully stop the Node and all registered services. This method:
     /// 1. Transitions the Node to the Stopping state
-    /// 2. Stops all registered services in the reverse order they were started
-    /// 3. Updates the service state in the metadata as each service stops
-    /// 4. Handles any errors during service shutdown
-    /// 5. Transitions the Node to the Stopped state
+    /// 2. Waits up to the shutdown drain period for in-flight requests
+    /// 3. Stops all registered services in the reverse order they were started
+    /// 4. Updates the service state in the metadata as each service stops
+    /// 5. Handles any errors during service shutdown
+    /// 6. Transitions the Node to the Stopped state
     pub async fn stop(&mut self) -> Result<()> {
         self.logger.info("Stopping node...");
 
-        if !self.running.load(Ordering::SeqCst) {
+        // A clone of the node (e.g. the signal handler's) may have stopped it
+        if !self.running.load(Ordering::SeqCst) || *self.stopped.borrow() {
+            self.running.store(false, Ordering::SeqCst);
             self.logger.warn("Node already stopped");
             return Ok(());
         }
 
         self.running.store(false, Ordering::SeqCst);
 
+        self.drain_in_flight_requests().await;
+
         // Get services directly and stop them
         let registry = Arc::clone(&self.service_registry);
         let local_services = registry.get_local_services().await;
@@ -520,7 +1667,9 @@ impl Node {
             );
 
             // Stop the service using the context
-            if let Err(e) = service.stop(stop_context).await {
+            let stopped = service.stop(stop_context).await;
+            self.abort_background_tasks(&service_topic);
+            if let Err(e) = stopped {
                 self.logger.error(format!(
                     "Failed to stop service: {service_topic}, error: {e}"
                 ));
@@ -532,6 +1681,9 @@ impl Node {
                 .await?;
         }
 
+        // Let the dispatch tasks end once their queued events are delivered
+        self.event_dispatcher.clear();
+
         self.logger.info("Stopping networking...");
 
         // Shut down networking if enabled
@@ -542,8 +1694,244 @@ impl Node {
             }
         }
 
+        // Export the spans still buffered before the process goes away
+        if let Some(exporter) = self.telemetry.clone() {
+            match tokio::task::spawn_blocking(move || exporter.flush()).await {
+                Ok(Err(e)) => self.logger.warn(format!("{e}")),
+                Err(e) => self.logger.warn(format!("Failed to flush spans: {e}")),
+                Ok(Ok(())) => {}
+            }
+        }
+
         self.logger.info("Node stopped successfully");
+        self.stopped.send_replace(true);
+
+        Ok(())
+    }
+
+    /// Wait until the node has fully stopped
+    ///
+    /// INTENTION: Let `main` park on the node after starting it with
+    /// `NodeConfig::with_unix_signal_handling`; the future completes once
+    /// `stop` has finished, whether a signal or another task called it.
+    pub fn wait_for_shutdown(&self) -> impl Future<Output = ()> + Send + 'static {
+        let mut stopped = self.stopped.subscribe();
+        async move {
+            // The sender lives as long as any clone of the node
+            let _ = stopped.wait_for(|stopped| *stopped).await;
+        }
+    }
+
+    /// A `tracing` layer exporting spans through the node's OTLP exporter
+    ///
+    /// INTENTION: Let the application add the node's exporter to the
+    /// subscriber it installs, e.g.
+    /// `tracing_subscriber::registry().with(node.telemetry_layer()).init()`.
+    /// None when the node has no telemetry configured or it was disabled.
+    pub fn telemetry_layer<S>(&self) -> Option<OpenTelemetryLayer<S, SdkTracer>>
+    where
+        S: tracing::Subscriber + for<'span> tracing_subscriber::registry::LookupSpan<'span>,
+    {
+        self.telemetry.as_ref().map(|exporter| exporter.layer())
+    }
+
+    /// Wait up to the shutdown drain period for in-flight requests
+    async fn drain_in_flight_requests(&self) {
+        let drain_period = self.config.shutdown_drain_period;
+        if drain_period.is_zero() {
+            return;
+        }
+        let deadline = tokio::time::Instant::now() + drain_period;
+        while self.in_flight_requests.load(Ordering::SeqCst) > 0 {
+            if tokio::time::Instant::now() >= deadline {
+                self.logger.warn(format!(
+                    "Stopping with {} requests still in flight",
+                    self.in_flight_requests.load(Ordering::SeqCst)
+                ));
+                return;
+            }
+            sleep(Duration::from_millis(10)).await;
+        }
+    }
+
+    /// Stop the node when the process receives SIGTERM or SIGINT
+    #[cfg(unix)]
+    fn spawn_signal_handler(&self) -> Result<()> {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        let mut terminate = signal(SignalKind::terminate())?;
+        let mut interrupt = signal(SignalKind::interrupt())?;
+        let mut stopped = self.stopped.subscribe();
+        let mut node = self.clone();
+        tokio::spawn(async move {
+            let signal_name = tokio::select! {
+                _ = terminate.recv() => "SIGTERM",
+                _ = interrupt.recv() => "SIGINT",
+                // Stopped by other means, nothing left to handle
+                _ = stopped.wait_for(|stopped| *stopped) => return,
+            };
+            node.logger
+                .info(format!("Received {signal_name}, stopping node"));
+            if let Err(e) = node.stop().await {
+                node.logger
+                    .error(format!("Failed to stop node on {signal_name}: {e}"));
+            }
+        });
+        Ok(())
+    }
+
+    /// Stop the node when the process receives SIGTERM or SIGINT
+    #[cfg(not(unix))]
+    fn spawn_signal_handler(&self) -> Result<()> {
+        self.logger
+            .warn("Unix signal handling is not supported on this platform");
+        Ok(())
+    }
+
+    /// Report the health of the local services
+    ///
+    /// INTENTION: Summarize the state of every local service, the node being
+    /// healthy when none of them is in the `Error` state. This is the report
+    /// gossiped to the peers with `NodeConfig::with_health_gossip_interval`.
+    pub async fn health_check(&self) -> HealthReport {
+        let mut services = HashMap::new();
+        for service_topic in self.service_registry.get_local_services().await.into_keys() {
+            let state = self
+                .service_registry
+                .get_service_state(&service_topic)
+                .await
+                .unwrap_or(ServiceState::Unknown);
+            services.insert(service_topic.service_path(), state);
+        }
+        HealthReport::new(&self.peer_id, services)
+    }
+
+    /// Health of every node of the cluster known to this node
+    ///
+    /// INTENTION: Let operators see the health of the whole mesh from any
+    /// node. Holds this node's own report and the last report gossiped by each
+    /// peer; reports older than two gossip intervals are marked as stale.
+    pub async fn cluster_health(&self) -> HashMap<PeerId, HealthReport> {
+        let mut reports = self
+            .health_gossip
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .reports();
+        reports.insert(self.peer_id.clone(), self.health_check().await);
+        reports
+    }
+
+    /// Send the health report to the peers every `interval` until the node stops
+    fn spawn_health_gossip(&self, interval: Duration) {
+        let mut stopped = self.stopped.subscribe();
+        let node = self.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                let stopping = tokio::select! {
+                    _ = ticker.tick() => false,
+                    _ = stopped.wait_for(|stopped| *stopped) => true,
+                };
+                if stopping {
+                    break;
+                }
+                if let Err(e) = node.gossip_health().await {
+                    node.logger.warn(format!("Failed to gossip health: {e}"));
+                }
+            }
+        });
+    }
+
+    /// Give up on ordered event gaps every `timeout` until the node stops
+    fn spawn_ordered_event_expiry(&self, timeout: Duration) {
+        let mut stopped = self.stopped.subscribe();
+        let node = self.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(timeout);
+            loop {
+                let stopping = tokio::select! {
+                    _ = ticker.tick() => false,
+                    _ = stopped.wait_for(|stopped| *stopped) => true,
+                };
+                if stopping {
+                    break;
+                }
+                let discarded = node
+                    .ordered_events
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .expire(std::time::Instant::now());
+                if discarded > 0 {
+                    node.logger.warn(format!(
+                        "Discarded {discarded} ordered events that waited too long for earlier events"
+                    ));
+                }
+            }
+        });
+    }
+
+    /// Send the health report to every known peer as a heartbeat
+    async fn gossip_health(&self) -> Result<()> {
+        let transport_guard = self.network_transport.read().await;
+        let Some(transport) = transport_guard.as_ref() else {
+            return Ok(());
+        };
+        let peers: Vec<PeerId> = self.known_peers.read().await.keys().cloned().collect();
+        if peers.is_empty() {
+            return Ok(());
+        }
+
+        let report = ArcValue::from_struct(self.health_check().await);
+        let value_bytes = self
+            .serializer
+            .read()
+            .await
+            .serialize_value(&report)?
+            .to_vec();
+        let payload =
+            NetworkMessagePayloadItem::new("$health".to_string(), value_bytes, String::new())
+                .with_format(self.config.serialization_backend);
+
+        for peer in peers {
+            let message = NetworkMessage {
+                source: self.peer_id.clone(),
+                destination: peer.clone(),
+                message_type: "Heartbeat".to_string(),
+                payloads: vec![payload.clone()],
+                signature: None,
+                hop_count: 0,
+                visited_peers: Vec::new(),
+                auth_tag: None,
+                expires_at: None,
+            };
+            // One unreachable peer must not stop the gossip to the others
+            if let Err(e) = transport.send_message(message).await {
+                self.logger
+                    .debug(format!("Failed to send health report to {peer}: {e}"));
+            }
+        }
+        Ok(())
+    }
 
+    /// Record the health report carried by a heartbeat
+    async fn handle_network_heartbeat(&self, message: NetworkMessage) -> Result<()> {
+        for payload in message.payloads {
+            let mut value = {
+                let serializer = self.serializer.read().await;
+                let value = deserialize_payload(&serializer, payload)?;
+                self.logger.debug(format!(
+                    "Health report from {}: {}",
+                    message.source,
+                    value.to_display_string(&serializer)
+                ));
+                value
+            };
+            let report = value.as_type::<HealthReport>()?;
+            self.health_gossip
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .record(message.source.clone(), report);
+        }
         Ok(())
     }
 
@@ -576,6 +1964,14 @@ impl Node {
             // Create network transport using the factory pattern based on transport_type
             // let node_identifier = self.peer_id.clone();
             let transport = self.create_transport(network_config).await?;
+            // Tag every outgoing message when request authentication is configured
+            let transport: Box<dyn NetworkTransport> = match &self.request_authenticator {
+                Some(authenticator) => Box::new(AuthenticatedTransport::new(
+                    transport,
+                    authenticator.clone(),
+                )),
+                None => transport,
+            };
 
             // Store the transport
             let mut transport_guard = self.network_transport.write().await;
@@ -663,7 +2059,11 @@ impl Node {
     ) -> Result<Box<dyn NetworkTransport>> {
         // Get the local node info to pass to the transport
         let local_node_info = self.get_local_node_info().await?;
-        let self_arc = Arc::new(self.clone());
+        if let Some(factory) = &self.config.transport_factory {
+            self.logger
+                .debug("Creating transport from the configured factory");
+            return Ok((factory.0)(local_node_info, self.network_message_handler()));
+        }
         match network_config.transport_type {
             TransportType::Quic => {
                 self.logger.debug("Creating QUIC transport");
@@ -674,36 +2074,78 @@ impl Node {
                     .quic_options
                     .clone()
                     .ok_or_else(|| anyhow!("QUIC options not provided"))?;
+                // Idle peers are disconnected after the transport timeout,
+                // unless the QUIC options set their own
+                let quic_options = match (
+                    quic_options.idle_disconnect_timeout(),
+                    network_config.transport_options.timeout,
+                ) {
+                    (None, Some(timeout)) => quic_options.with_idle_disconnect_timeout(timeout),
+                    _ => quic_options,
+                };
+                // Larger messages are sent in chunks of the transport limit,
+                // which is also the limit advertised to peers
+                let max_message_size = network_config
+                    .transport_options
+                    .max_message_size
+                    .unwrap_or(network_config.max_message_size);
+                let quic_options = quic_options.with_max_message_size(max_message_size);
+                // Chunks of a message must all arrive within the transport timeout
+                let quic_options = match network_config.transport_options.timeout {
+                    Some(timeout) => quic_options.with_chunk_timeout(timeout),
+                    None => quic_options,
+                };
+                let quic_options = quic_options.with_peer_filter(self.peer_filter.clone());
+                // Peers check that they can decode the payloads this node sends
+                let capabilities = quic_options
+                    .capabilities()
+                    .clone()
+                    .with_payload_format(self.config.serialization_backend);
+                let quic_options = quic_options.with_capabilities(capabilities);
+                // Compact frames use the type IDs assigned when the registry
+                // is sealed, advertised to peers through their fingerprint
+                let quic_options = if quic_options.compact_framing() {
+                    let mut serializer = self.serializer.write().await;
+                    serializer.seal();
+                    let capabilities = NodeCapabilities {
+                        type_table: serializer.type_table_fingerprint(),
+                        ..quic_options.capabilities().clone()
+                    };
+                    drop(serializer);
+                    quic_options
+                        .with_capabilities(capabilities)
+                        .with_serializer(self.serializer.clone())
+                } else {
+                    quic_options
+                };
 
-                let message_handler = Box::new(move |message: NetworkMessage| {
-                    let self_arc = self_arc.clone();
-                    tokio::spawn(async move {
-                        if let Err(e) = self_arc.handle_network_message(message).await {
-                            self_arc
-                                .logger
-                                .error(format!("Error handling network message: {e}"));
-                        }
-                    });
-                    // Return success immediately since we've spawned the task
-                    Ok(())
-                });
-
-                let cert_config = self
-                    .keys_manager
-                    .read()
-                    .await
-                    .get_quic_certificate_config()
-                    .context("Failed to get QUIC certificates")?;
+                let message_handler = self.network_message_handler();
 
-                // Configure QUIC options with certificates and private key from key manager
+                // Configure QUIC options with certificates and private key from key manager,
+                // unless both were provided explicitly (e.g. loaded from files by from_env)
                 // Standard QUIC/TLS will handle certificate validation using the CA certificate
-                let configured_quic_options = quic_options
-                    .with_certificates(cert_config.certificate_chain)
-                    .with_private_key(cert_config.private_key);
-
-                let transport = QuicTransport::new(
-                    local_node_info,
-                    bind_addr,
+                let configured_quic_options = if quic_options.certificates().is_some()
+                    && quic_options.private_key().is_some()
+                {
+                    quic_options
+                } else {
+                    let cert_config = self
+                        .keys_manager
+                        .read()
+                        .await
+                        .get_quic_certificate_config()
+                        .context("Failed to get QUIC certificates")?;
+                    quic_options
+                        .with_certificates(cert_config.certificate_chain)
+                        .with_private_key(cert_config.private_key)
+                };
+
+                // The Unix socket transport proves the node id with the same key
+                let signing_key = message_signing_key(configured_quic_options.private_key())
+                    .map_err(|e| anyhow!(e))?;
+                let transport = QuicTransport::new(
+                    local_node_info.clone(),
+                    bind_addr,
                     message_handler,
                     configured_quic_options,
                     self.logger.clone(),
@@ -711,11 +2153,85 @@ impl Node {
                 .map_err(|e| anyhow!("Failed to create QUIC transport: {}", e))?;
 
                 self.logger.debug("QUIC transport created");
-                Ok(Box::new(transport))
+
+                match &network_config.transport_options.local_socket_path {
+                    None => Ok(Box::new(transport)),
+                    Some(socket_path) => {
+                        let local = self.create_local_transport(
+                            local_node_info,
+                            socket_path,
+                            network_config,
+                            signing_key,
+                        )?;
+                        Ok(Box::new(MultiTransport::new(
+                            Box::new(transport),
+                            local,
+                            self.logger.clone(),
+                        )))
+                    }
+                }
             } // Add other transport types here as needed in the future
         }
     }
 
+    /// Handler of the messages a transport receives, each handled on its own task
+    fn network_message_handler(&self) -> TransportMessageHandler {
+        let self_arc = Arc::new(self.clone());
+        Box::new(move |message: NetworkMessage| {
+            let self_arc = self_arc.clone();
+            tokio::spawn(async move {
+                if let Err(e) = self_arc.handle_network_message(message).await {
+                    self_arc
+                        .logger
+                        .error(format!("Error handling network message: {e}"));
+                }
+            });
+            // Return success immediately since we've spawned the task
+            Ok(())
+        })
+    }
+
+    /// Create the Unix socket transport that runs next to the network transport
+    #[cfg(unix)]
+    fn create_local_transport(
+        &self,
+        local_node_info: NodeInfo,
+        socket_path: &std::path::Path,
+        network_config: &NetworkConfig,
+        signing_key: Option<SigningKey>,
+    ) -> Result<Box<dyn NetworkTransport>> {
+        let message_handler = self.network_message_handler();
+
+        self.logger.debug(format!(
+            "Creating Unix socket transport at {}",
+            socket_path.display()
+        ));
+        Ok(Box::new(
+            UnixSocketTransport::new(
+                local_node_info,
+                socket_path,
+                message_handler,
+                network_config.max_message_size,
+                signing_key,
+                self.logger.clone(),
+            )
+            .with_peer_filter(self.peer_filter.clone()),
+        ))
+    }
+
+    #[cfg(not(unix))]
+    fn create_local_transport(
+        &self,
+        _local_node_info: NodeInfo,
+        _socket_path: &std::path::Path,
+        _network_config: &NetworkConfig,
+        _signing_key: Option<SigningKey>,
+    ) -> Result<Box<dyn NetworkTransport>> {
+        Err(anyhow!(
+            "Unix socket transport is not supported on this platform"
+        ))
+    }
+
     /// Create a discovery provider based on the provider type
     async fn create_discovery_provider(
         &self,
@@ -737,6 +2253,19 @@ impl Node {
                 .await?;
                 Ok(Arc::new(discovery))
             }
+            DiscoveryProviderConfig::Mdns(options) => {
+                self.logger.info(format!(
+                    "Creating MdnsDiscovery provider for {}",
+                    options.service_type
+                ));
+                let discovery = MdnsDiscovery::new(
+                    node_info,
+                    options.service_type.clone(),
+                    self.logger.with_component(Component::NetworkDiscovery),
+                )
+                .await?;
+                Ok(Arc::new(discovery))
+            }
             DiscoveryProviderConfig::Static(_options) => {
                 self.logger.info("Static discovery provider configured");
                 // Implement static discovery when needed
@@ -808,6 +2337,126 @@ impl Node {
         Ok(())
     }
 
+    /// Register a service of a peer that the peer does not advertise
+    ///
+    /// INTENTION: Let callers reach a service hidden by its `ServiceVisibility`
+    /// when they know its metadata. Requests to it are routed to `peer_id` like
+    /// those to advertised services. The service is not part of the peer's
+    /// node info, so node info updates from the peer leave it in place.
+    pub async fn add_remote_service(
+        &self,
+        peer_id: PeerId,
+        service: ServiceMetadata,
+    ) -> Result<()> {
+        let node_info = NodeInfo {
+            peer_id,
+            network_ids: vec![service.network_id.clone()],
+            addresses: Vec::new(),
+            services: vec![service],
+            version: 0,
+            subscriptions: Vec::new(),
+        };
+        self.add_new_peer(node_info).await?;
+        Ok(())
+    }
+
+    /// Connect to a known peer without discovery
+    ///
+    /// INTENTION: Provide the building block for seeded cluster membership.
+    /// Opens a QUIC connection to `peer_addr`, performs the normal handshake and
+    /// returns once the peer's services are registered locally. The peer's
+    /// identity is checked by the transport during the handshake. Waits at most
+    /// the configured request timeout for the peer's node info.
+    pub async fn add_remote_peer(&mut self, peer_addr: SocketAddr, peer_id: PeerId) -> Result<()> {
+        let expected_peer = peer_id.clone();
+        self.connect_known_peer(
+            PeerInfo::new(peer_id.public_key.clone(), vec![peer_addr.to_string()]),
+            peer_id,
+            move |node_info| node_info.peer_id == expected_peer,
+        )
+        .await
+    }
+
+    /// Connect to a node on the same host through its Unix socket
+    ///
+    /// INTENTION: Attach co-located nodes over IPC. Requires this node to be
+    /// configured with `NetworkConfig::with_local_socket_path`. The peer is
+    /// known by its socket path until the handshake proves its node id.
+    #[cfg(unix)]
+    pub async fn add_local_peer(&mut self, socket_path: impl AsRef<std::path::Path>) -> Result<()> {
+        let socket_path = socket_path.as_ref();
+        let address = UnixSocketTransport::address_for_path(socket_path);
+        let path_peer_id = UnixSocketTransport::peer_id_for_path(socket_path);
+        let expected_address = address.clone();
+        self.connect_known_peer(
+            PeerInfo::new(path_peer_id.public_key.clone(), vec![address]),
+            path_peer_id,
+            move |node_info| node_info.addresses.contains(&expected_address),
+        )
+        .await
+    }
+
+    /// Connect to a peer and register its services once its node info arrives
+    async fn connect_known_peer(
+        &self,
+        peer_info: PeerInfo,
+        peer_id: PeerId,
+        is_peer: impl Fn(&NodeInfo) -> bool,
+    ) -> Result<()> {
+        if !self.supports_networking {
+            return Err(anyhow!("Networking is disabled"));
+        }
+
+        let transport_guard = self.network_transport.read().await;
+        let transport = transport_guard
+            .as_ref()
+            .ok_or_else(|| anyhow!("Network transport is not started"))?;
+
+        if transport.is_connected(peer_id.clone()).await {
+            self.logger
+                .info(format!("Already connected to peer {peer_id}"));
+            return Ok(());
+        }
+
+        // Subscribe before connecting so the handshake response cannot be missed
+        let mut receiver = self.peer_registry.subscribe_to_changes();
+
+        let peer_addr = peer_info.addresses.join(", ");
+        self.logger
+            .info(format!("Connecting to peer {peer_id} at {peer_addr}"));
+        transport
+            .connect_peer(peer_info)
+            .await
+            .map_err(|e| anyhow!("Failed to connect to peer {peer_id} at {peer_addr}: {e}"))?;
+        drop(transport_guard);
+
+        let timeout = Duration::from_millis(self.config.request_timeout_ms);
+        let node_info = tokio::time::timeout(timeout, async {
+            loop {
+                match receiver.recv().await {
+                    Ok(PeerEvent::Connected(entry)) => match entry.node_info {
+                        Some(node_info) if is_peer(&node_info) => return Ok(node_info),
+                        _ => continue,
+                    },
+                    Ok(_) | Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(tokio::sync::broadcast::error::RecvError::Closed) => {
+                        return Err(anyhow!("Peer registry channel closed"))
+                    }
+                }
+            }
+        })
+        .await
+        .map_err(|_| anyhow!("Timed out waiting for the handshake with peer {peer_id}"))??;
+
+        // The peer event listener processes the same node info; whichever call
+        // comes second is a no-op, and both run under the known_peers lock
+        self.process_remote_capabilities(node_info).await?;
+
+        self.logger
+            .info(format!("Added peer {peer_id} at {peer_addr}"));
+        Ok(())
+    }
+
     /// Handle a network message
     async fn handle_network_message(&self, message: NetworkMessage) -> Result<()> {
         // Skip if networking is not enabled
@@ -820,11 +2469,57 @@ impl Node {
         self.logger
             .debug(format!("Received network message: {message:?}"));
 
+        // A relayed message that already passed through this node has looped
+        if message.has_visited(&self.peer_id) {
+            self.logger.warn(format!(
+                "Discarding looped {} message from {} after {} hops",
+                message.message_type, message.source, message.hop_count
+            ));
+            return Ok(());
+        }
+
+        // With request auth configured, only messages tagged with the shared key
+        // are dispatched; failure reports are the one untagged exception
+        let mut message = message;
+        if let Some(authenticator) = &self.request_authenticator {
+            if !authenticator.verify(&message) {
+                if request_auth::is_authentication_failure(&message) {
+                    self.retain_sent_request_failures(&mut message).await;
+                }
+                // Also rejected once no payload is left
+                if !request_auth::is_authentication_failure(&message) {
+                    self.logger.warn(format!(
+                        "Rejecting unauthenticated {} message from {}",
+                        message.message_type, message.source
+                    ));
+                    if message.message_type == "Request" {
+                        self.reject_unauthenticated_request(message).await?;
+                    }
+                    return Ok(());
+                }
+            }
+        }
+
+        let max_hops = self
+            .config
+            .network_config
+            .as_ref()
+            .map_or(DEFAULT_MAX_HOPS, |config| config.transport_options.max_hops);
+        if message.hop_count > max_hops {
+            return Err(NetworkError::MessageError(
+                ErrorCode::MaxHopsExceeded,
+                "max hops exceeded".to_string(),
+            )
+            .into());
+        }
+
         // Match on message type
         match message.message_type.as_str() {
             "Request" => self.handle_network_request(message).await,
-            "Response" => self.handle_network_response(message).await,
+            "Response" | "Error" => self.handle_network_response(message).await,
+            "StreamItem" | "StreamEnd" => self.handle_network_stream_frame(message).await,
             "Event" => self.handle_network_event(message).await,
+            "Heartbeat" => self.handle_network_heartbeat(message).await,
             // "Discovery" => self.handle_network_discovery(message).await,
             _ => {
                 self.logger.warn(format!(
@@ -836,6 +2531,74 @@ impl Node {
         }
     }
 
+    /// Drop the payloads of an untagged authentication failure that do not
+    /// answer a request this node sent to the failure's source
+    ///
+    /// INTENTION: Each accepted payload only ends the request with its
+    /// correlation ID; a peer cannot fail the requests sent to other peers.
+    async fn retain_sent_request_failures(&self, message: &mut NetworkMessage) {
+        let pending_requests = self.pending_requests.read().await;
+        let discarded = request_auth::retain_sent_request_failures(message, |correlation_id| {
+            pending_requests
+                .get(correlation_id)
+                .map(|request| request.peer_id.clone())
+        });
+        if discarded > 0 {
+            self.logger.warn(format!(
+                "Discarding {discarded} authentication failure(s) from {} for requests not sent to it",
+                message.source
+            ));
+        }
+    }
+
+    /// Answer each payload of a request that failed authentication
+    ///
+    /// The replies carry `ErrorCode::AuthenticationFailed` and are sent
+    /// untagged, since the requester does not hold a matching key.
+    async fn reject_unauthenticated_request(&self, message: NetworkMessage) -> Result<()> {
+        let error_value = ArcValue::new_primitive("authentication failed".to_string());
+        let serialized_error = self
+            .serializer
+            .read()
+            .await
+            .serialize_value(&error_value)?
+            .to_vec();
+
+        let payloads = message
+            .payloads
+            .iter()
+            .map(|payload| NetworkMessagePayloadItem {
+                path: payload.path.clone(),
+                value_bytes: serialized_error.clone(),
+                correlation_id: payload.correlation_id.clone(),
+                error_code: Some(ErrorCode::AuthenticationFailed.as_u16()),
+                sequence: None,
+                dedup_id: None,
+                format: Some(self.config.serialization_backend),
+                chunk_index: None,
+                total_chunks: None,
+            })
+            .collect();
+        let response_message = NetworkMessage {
+            source: self.peer_id.clone(),
+            destination: message.source.clone(),
+            message_type: "Error".to_string(),
+            payloads,
+            signature: None,
+            hop_count: 0,
+            visited_peers: Vec::new(),
+            auth_tag: None,
+            expires_at: None,
+        };
+
+        let transport_guard = self.network_transport.read().await;
+        let transport = transport_guard
+            .as_ref()
+            .ok_or_else(|| anyhow!("Network transport is not started"))?;
+        transport.send_message(response_message).await?;
+        Ok(())
+    }
+
     /// Handle a network request
     async fn handle_network_request(&self, message: NetworkMessage) -> Result<()> {
         // Skip if networking is not enabled
@@ -869,9 +2632,7 @@ impl Node {
             ));
 
             // Deserialize the value from bytes
-            let params = match serializer
-                .deserialize_value(Arc::from(payload_item.value_bytes.clone()))
-            {
+            let params = match deserialize_payload(&serializer, payload_item.clone()) {
                 Ok(value) => value,
                 Err(e) => {
                     self.logger.error(format!(
@@ -888,12 +2649,39 @@ impl Node {
             self.logger.info(format!(
                 "⚙️ [Node] Processing local request for path: {path} (correlation: {correlation_id})"
             ));
-            match self.local_request(path.as_str(), params_option).await {
+            let span = tracing::info_span!(
+                "remote_request",
+                node.id = %self.config.node_id,
+                network.id = %self.network_id,
+                peer.id = %message.source,
+                service = tracing::field::Empty,
+                action = tracing::field::Empty,
+            );
+            if let Ok(topic_path) = TopicPath::new(&path, &self.network_id) {
+                span.record("service", topic_path.service_path());
+                span.record("action", topic_path.action_path());
+            }
+            match self
+                .local_request_from(path.as_str(), params_option, Some(message.source.clone()))
+                .instrument(span)
+                .await
+            {
                 Ok(response) => {
                     self.logger.info(format!(
                         "✅ [Node] Local request completed successfully - Path: {path}, Correlation: {correlation_id}"
                     ));
 
+                    // Streamed results are sent frame by frame from their own task
+                    if let Some(stream) = ResponseStream::take_from(&response) {
+                        let node = self.clone();
+                        let destination = message.source.clone();
+                        tokio::spawn(async move {
+                            node.forward_response_stream(stream, destination, path, correlation_id)
+                                .await;
+                        });
+                        continue;
+                    }
+
                     // Serialize the response data
                     let serialized_data_result = serializer.serialize_value(&response);
                     let serialized_data = match serialized_data_result {
@@ -922,6 +2710,12 @@ impl Node {
                         path,
                         value_bytes: serialized_data,
                         correlation_id: correlation_id.clone(),
+                        error_code: None,
+                        sequence: None,
+                        dedup_id: None,
+                        format: Some(self.config.serialization_backend),
+                        chunk_index: None,
+                        total_chunks: None,
                     };
 
                     // Create response message - destination is the original source
@@ -930,6 +2724,11 @@ impl Node {
                         destination: message.source.clone(), // Destination is the original request source
                         message_type: "Response".to_string(),
                         payloads: vec![response_payload],
+                        signature: None,
+                        hop_count: 0,
+                        visited_peers: Vec::new(),
+                        auth_tag: None,
+                        expires_at: None,
                     };
 
                     // Check if networking is still enabled before trying to send response
@@ -969,22 +2768,34 @@ impl Node {
                         "❌ [Node] Local request failed - Path: {path}, Correlation: {correlation_id}, Error: {e}",
                     ));
 
-                    // Create a map for the error response
-                    let mut error_map = HashMap::new();
-                    error_map.insert("error".to_string(), ArcValue::new_primitive(true));
-                    error_map.insert(
-                        "message".to_string(),
-                        ArcValue::new_primitive(e.to_string()),
-                    );
-                    let error_value = ArcValue::from_map(error_map);
+                    // Network errors keep their code, anything else is reported as a remote error
+                    let (error_code, error_message) = match e.downcast_ref::<NetworkError>() {
+                        Some(network_error) => {
+                            (network_error.code(), network_error.message().to_string())
+                        }
+                        None if e.is::<RateLimitExceeded>() => {
+                            (ErrorCode::RateLimited, e.to_string())
+                        }
+                        None => (ErrorCode::RemoteError, e.to_string()),
+                    };
+
+                    // The payload describes the error, and carries the error itself if its type is registered
+                    let serializer = self.serializer.read().await;
+                    let (type_name, serialized_error) = match serializer.serialize_error(&e) {
+                        Some((type_name, bytes)) => (type_name, Some(bytes)),
+                        None => (std::any::type_name::<anyhow::Error>().to_string(), None),
+                    };
+                    let error_value = ArcValue::from_struct(NetworkErrorPayload {
+                        code: error_code.as_u16(),
+                        message: error_message,
+                        type_name,
+                        serialized_error,
+                    });
 
                     // Serialize the error value
-                    let serialized_error = match self
-                        .serializer
-                        .read()
-                        .await
-                        .serialize_value(&error_value)
-                    {
+                    let serialized_error = serializer.serialize_value(&error_value);
+                    drop(serializer);
+                    let serialized_error = match serialized_error {
                         Ok(bytes) => bytes.to_vec(),
                         Err(e) => {
                             self.logger
@@ -1005,6 +2816,12 @@ impl Node {
                         path,
                         value_bytes: serialized_error,
                         correlation_id: correlation_id.clone(),
+                        error_code: Some(error_code.as_u16()),
+                        sequence: None,
+                        dedup_id: None,
+                        format: Some(self.config.serialization_backend),
+                        chunk_index: None,
+                        total_chunks: None,
                     };
 
                     let response_message = NetworkMessage {
@@ -1012,6 +2829,11 @@ impl Node {
                         destination: message.source.clone(), // Destination is the original request source
                         message_type: "Error".to_string(),   // Use Error type
                         payloads: vec![error_payload],
+                        signature: None,
+                        hop_count: 0,
+                        visited_peers: Vec::new(),
+                        auth_tag: None,
+                        expires_at: None,
                     };
 
                     // Check if networking is still enabled before trying to send error response
@@ -1077,17 +2899,17 @@ impl Node {
             ));
 
             // Find any pending response handlers
-            if let Some(pending_request_sender) =
-                self.pending_requests.write().await.remove(correlation_id)
+            if let Some(PendingRequest {
+                sender: pending_request_sender,
+                ..
+            }) = self.pending_requests.write().await.remove(correlation_id)
             {
                 self.logger.debug(format!(
                     "Found response handler for correlation ID: {correlation_id}"
                 ));
 
                 // Deserialize the payload data
-                let payload_data = match serializer
-                    .deserialize_value(Arc::from(payload_item.value_bytes.clone()))
-                {
+                let payload_data = match deserialize_payload(&serializer, payload_item.clone()) {
                     Ok(value) => value,
                     Err(e) => {
                         self.logger
@@ -1104,10 +2926,16 @@ impl Node {
                     }
                 };
 
+                // Relayed errors are turned back into a NetworkError carrying the remote code
+                let response = match payload_item.error_code {
+                    Some(code) => Err(self.relayed_response_error(code, payload_data).await),
+                    None => Ok(payload_data),
+                };
+
                 // Send the response (which is ArcValue) through the oneshot channel
                 // payload_data is already ArcValue. If the original response was 'None',
                 // serializer.deserialize_value should produce ArcValue::null().
-                match pending_request_sender.send(Ok(payload_data)) {
+                match pending_request_sender.send(response) {
                     Ok(_) => self.logger.debug(format!(
                         "Successfully sent response for correlation ID: {correlation_id}"
                     )),
@@ -1125,6 +2953,277 @@ impl Node {
         Ok(())
     } // Closes async fn handle_network_response
 
+    /// Handle a frame of a streamed response
+    ///
+    /// INTENTION: The first frame for a correlation ID answers the pending
+    /// request with a stream handle; the frames are then delivered to that
+    /// stream in sequence order until the end frame arrives.
+    async fn handle_network_stream_frame(&self, message: NetworkMessage) -> Result<()> {
+        if !self.supports_networking {
+            self.logger
+                .warn("Received stream frame but networking is disabled");
+            return Ok(());
+        }
+
+        for payload_item in message.payloads {
+            let correlation_id = payload_item.correlation_id.clone();
+            let Some(sequence) = payload_item.sequence else {
+                self.logger.warn(format!(
+                    "Discarding stream frame without sequence number (correlation: {correlation_id})"
+                ));
+                continue;
+            };
+
+            let value = deserialize_payload(&*self.serializer.read().await, payload_item.clone())?;
+            let frame = if message.message_type == "StreamEnd" {
+                StreamFrame::End(
+                    payload_item
+                        .error_code
+                        .map(|code| Self::relayed_error(code, value)),
+                )
+            } else {
+                StreamFrame::Item(value)
+            };
+
+            let Some(pending_stream) = self.pending_stream_for(&correlation_id).await else {
+                self.logger.debug(format!(
+                    "No stream waiting for correlation ID: {correlation_id}"
+                ));
+                continue;
+            };
+
+            let finished = pending_stream
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .add(sequence, frame);
+            if finished {
+                self.pending_streams.write().await.remove(&correlation_id);
+            }
+        }
+        Ok(())
+    }
+
+    /// Find the stream receiving frames for a correlation ID
+    ///
+    /// On the first frame the pending request is answered with a new stream;
+    /// returns None when nobody is waiting for the frames any more.
+    async fn pending_stream_for(
+        &self,
+        correlation_id: &str,
+    ) -> Option<Arc<std::sync::Mutex<PendingStream>>> {
+        let mut pending_streams = self.pending_streams.write().await;
+        if let Some(pending_stream) = pending_streams.get(correlation_id) {
+            return Some(pending_stream.clone());
+        }
+
+        let request_sender = self
+            .pending_requests
+            .write()
+            .await
+            .remove(correlation_id)?
+            .sender;
+        let (sender, stream) = ResponseStream::channel(DEFAULT_RESPONSE_STREAM_BUFFER);
+        if request_sender
+            .send(Ok(stream.into_arc_value(|value| value)))
+            .is_err()
+        {
+            return None;
+        }
+
+        // The consumer is awaited by a task of its own, never by a frame handler
+        let (frames, receiver) = tokio::sync::mpsc::channel(MAX_BUFFERED_STREAM_FRAMES);
+        tokio::spawn(forward_stream_frames(receiver, sender));
+
+        let pending_stream = Arc::new(std::sync::Mutex::new(PendingStream {
+            frames: Some(frames),
+            next_sequence: 0,
+            buffered: BTreeMap::new(),
+            last_frame: std::time::Instant::now(),
+        }));
+        pending_streams.insert(correlation_id.to_string(), pending_stream.clone());
+        self.spawn_stream_idle_check(correlation_id.to_string(), pending_stream.clone());
+        Some(pending_stream)
+    }
+
+    /// Fail a stream once no frame arrived for the request timeout
+    ///
+    /// INTENTION: A remote node that stops sending frames, or never sends the
+    /// end frame, must not leave the consumer waiting forever.
+    fn spawn_stream_idle_check(
+        &self,
+        correlation_id: String,
+        pending_stream: Arc<std::sync::Mutex<PendingStream>>,
+    ) {
+        let pending_streams = self.pending_streams.clone();
+        let idle_timeout = Duration::from_millis(self.config.request_timeout_ms);
+        tokio::spawn(async move {
+            let mut deadline = tokio::time::Instant::now() + idle_timeout;
+            loop {
+                tokio::time::sleep_until(deadline).await;
+                {
+                    let mut stream = pending_stream.lock().unwrap_or_else(|e| e.into_inner());
+                    if stream.frames.is_none() {
+                        return;
+                    }
+                    let idle = stream.last_frame.elapsed();
+                    if idle < idle_timeout {
+                        deadline += idle_timeout - idle;
+                        continue;
+                    }
+                    stream.fail(NetworkError::ConnectionError(
+                        ErrorCode::Timeout,
+                        format!(
+                            "No response stream frame received for {}ms",
+                            idle_timeout.as_millis()
+                        ),
+                    ));
+                }
+                pending_streams.write().await.remove(&correlation_id);
+                return;
+            }
+        });
+    }
+
+    /// Send the values of a streamed action result to the requesting node
+    ///
+    /// INTENTION: Each value travels in its own StreamItem message so large
+    /// result sets are never held in one response. A StreamEnd message closes
+    /// the stream; it carries the number of items sent, or the error code and
+    /// message if the action's stream failed.
+    async fn forward_response_stream(
+        &self,
+        mut stream: ResponseStream<ArcValue>,
+        destination: PeerId,
+        path: String,
+        correlation_id: String,
+    ) {
+        let frame = |sequence: u64, value: ArcValue, error_code: Option<u16>| {
+            let path = path.clone();
+            let correlation_id = correlation_id.clone();
+            async move {
+                let value_bytes = self.serializer.read().await.serialize_value(&value)?;
+                Ok::<_, anyhow::Error>(NetworkMessagePayloadItem {
+                    path,
+                    value_bytes: value_bytes.to_vec(),
+                    correlation_id,
+                    error_code,
+                    sequence: Some(sequence),
+                    dedup_id: None,
+                    format: Some(self.config.serialization_backend),
+                    chunk_index: None,
+                    total_chunks: None,
+                })
+            }
+        };
+
+        let mut sequence = 0;
+        let end_error = loop {
+            let item = match stream.next().await {
+                Some(Ok(item)) => item,
+                Some(Err(e)) => break Some(e),
+                None => break None,
+            };
+            let sent = match frame(sequence, item, None).await {
+                Ok(payload) => {
+                    self.send_stream_frame(&destination, "StreamItem", payload)
+                        .await
+                }
+                Err(e) => Err(e),
+            };
+            if let Err(e) = sent {
+                self.logger.error(format!(
+                    "❌ [Node] Failed to send stream item - To: {destination}, Correlation: {correlation_id}, Error: {e}"
+                ));
+                return;
+            }
+            sequence += 1;
+        };
+
+        let (end_value, error_code) = match end_error {
+            Some(e) => {
+                let (code, message) = match e.downcast_ref::<NetworkError>() {
+                    Some(network_error) => {
+                        (network_error.code(), network_error.message().to_string())
+                    }
+                    None => (ErrorCode::RemoteError, e.to_string()),
+                };
+                (ArcValue::new_primitive(message), Some(code.as_u16()))
+            }
+            None => (ArcValue::null(), None),
+        };
+        let sent = match frame(sequence, end_value, error_code).await {
+            Ok(payload) => {
+                self.send_stream_frame(&destination, "StreamEnd", payload)
+                    .await
+            }
+            Err(e) => Err(e),
+        };
+        if let Err(e) = sent {
+            self.logger.error(format!(
+                "❌ [Node] Failed to end response stream - To: {destination}, Correlation: {correlation_id}, Error: {e}"
+            ));
+        }
+    }
+
+    /// Send one frame of a streamed response
+    async fn send_stream_frame(
+        &self,
+        destination: &PeerId,
+        message_type: &str,
+        payload: NetworkMessagePayloadItem,
+    ) -> Result<()> {
+        let message = NetworkMessage {
+            source: self.peer_id.clone(),
+            destination: destination.clone(),
+            message_type: message_type.to_string(),
+            payloads: vec![payload],
+            signature: None,
+            hop_count: 0,
+            visited_peers: Vec::new(),
+            auth_tag: None,
+            expires_at: None,
+        };
+
+        let transport_guard = self.network_transport.read().await;
+        let transport = transport_guard
+            .as_ref()
+            .ok_or_else(|| anyhow!("No network transport available"))?;
+        transport.send_message(message).await?;
+        Ok(())
+    }
+
+    /// Rebuild the error described by an "Error" message payload
+    ///
+    /// An error of a type registered with `SerializerRegistry::register_error`
+    /// is rebuilt and returned with the NetworkError as its context, so callers
+    /// can downcast to either. Anything else yields the NetworkError alone.
+    async fn relayed_response_error(&self, code: u16, mut payload: ArcValue) -> anyhow::Error {
+        let Ok(error_payload) = payload.as_type::<NetworkErrorPayload>() else {
+            return Self::relayed_error(code, payload).into();
+        };
+        let network_error = NetworkError::from_code(code, error_payload.message);
+        let typed_error = match error_payload.serialized_error {
+            Some(bytes) => self
+                .serializer
+                .read()
+                .await
+                .deserialize_error(&error_payload.type_name, &bytes),
+            None => None,
+        };
+        match typed_error {
+            Some(error) => error.context(network_error),
+            None => network_error.into(),
+        }
+    }
+
+    /// Rebuild the NetworkError described by an error response payload
+    fn relayed_error(code: u16, mut payload: ArcValue) -> NetworkError {
+        let message = payload
+            .as_type::<String>()
+            .unwrap_or_else(|_| "Remote request failed".to_string());
+        NetworkError::from_code(code, message)
+    }
+
     /// Handle a network event
     async fn handle_network_event(&self, message: NetworkMessage) -> Result<()> {
         // Skip if networking is not enabled
@@ -1158,69 +3257,132 @@ impl Node {
                 }
             };
 
-            // Deserialize the payload data
-            let payload = match self
-                .serializer
-                .read()
-                .await
-                .deserialize_value(Arc::from(payload_item.value_bytes.clone()))
-            {
-                Ok(value) => value,
-                Err(e) => {
+            // Drop copies of events already delivered, e.g. via another relay
+            if let Some(dedup_id) = payload_item.dedup_id {
+                if !self.record_event(dedup_id) {
                     self.logger
-                        .error(format!("Failed to deserialize event payload: {e}"));
+                        .debug(format!("Dropping duplicate event for topic: {topic}"));
                     continue;
                 }
-            };
-
-            // Create proper event context
-            let event_context = Arc::new(EventContext::new(
-                &topic_path,
-                Arc::new(self.clone()),
-                self.logger.clone(),
-            ));
+            }
 
-            // Get subscribers for this topic
-            let subscribers = self
-                .service_registry
-                .get_local_event_subscribers(&topic_path)
-                .await;
+            // Deserialize the payload data
+            let payload =
+                match deserialize_payload(&*self.serializer.read().await, payload_item.clone()) {
+                    Ok(value) => value,
+                    Err(e) => {
+                        self.logger
+                            .error(format!("Failed to deserialize event payload: {e}"));
+                        continue;
+                    }
+                };
 
-            if subscribers.is_empty() {
-                self.logger
-                    .debug(format!("No subscribers found for topic: {topic}"));
-                continue;
-            }
             let payload_option = if payload.is_null() {
                 None
             } else {
                 Some(payload)
             };
-            // Notify all subscribers
-            for (_subscription_id, callback) in subscribers {
-                let ctx = event_context.clone();
-                // Invoke callback. errors are logged but not propagated to avoid affecting other subscribers
-                let result = callback(ctx, payload_option.clone()).await;
-                if let Err(e) = result {
-                    self.logger
-                        .error(format!("Error in subscriber callback: {e}"));
-                }
-            }
-        }
 
-        Ok(())
-    }
+            let Some(sequence) = payload_item.sequence else {
+                self.deliver_remote_event(&topic_path, None, payload_option)
+                    .await;
+                continue;
+            };
+
+            // Hold the stream's delivery lock while delivering so that events
+            // released by a later message cannot overtake these
+            let delivery_lock = self
+                .ordered_events
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .delivery_lock(&message.source.public_key, topic_path.as_str());
+            let _delivering = delivery_lock.lock().await;
+            let ready = self
+                .ordered_events
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .accept(
+                    &message.source.public_key,
+                    topic_path.as_str(),
+                    sequence,
+                    payload_option,
+                );
+            if ready.is_empty() {
+                self.logger.debug(format!(
+                    "Holding back ordered event {sequence} for topic: {topic}"
+                ));
+            }
+            for (sequence, payload) in ready {
+                self.deliver_remote_event(&topic_path, Some(sequence), payload)
+                    .await;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Deliver an event received from a remote node to the local subscribers
+    async fn deliver_remote_event(
+        &self,
+        topic_path: &TopicPath,
+        sequence: Option<u64>,
+        payload: Option<ArcValue>,
+    ) {
+        // Create proper event context
+        let mut event_context =
+            EventContext::new(topic_path, Arc::new(self.clone()), self.logger.clone());
+        event_context.sequence = sequence;
+        let event_context = Arc::new(event_context);
+
+        // Get subscribers for this topic
+        let subscribers = self
+            .service_registry
+            .get_local_event_subscribers(topic_path)
+            .await;
+        let subscribers =
+            self.select_event_subscribers(topic_path, subscribers, &payload, sequence);
+
+        if subscribers.is_empty() {
+            self.logger
+                .debug(format!("No subscribers found for topic: {topic_path}"));
+            return;
+        }
+        // Notify all subscribers
+        for (subscription_id, callback) in subscribers {
+            // Errors are captured but not propagated to avoid affecting other subscribers
+            self.deliver_event(
+                topic_path,
+                &subscription_id,
+                &callback,
+                event_context.clone(),
+                payload.clone(),
+                1,
+            )
+            .await;
+        }
+    }
 
     pub async fn local_request(
         &self,
         path: impl Into<String>,
         payload: Option<ArcValue>,
+    ) -> Result<ArcValue> {
+        self.local_request_from(path, payload, None).await
+    }
+
+    /// Handle a request locally on behalf of `caller`, `None` meaning this node
+    async fn local_request_from(
+        &self,
+        path: impl Into<String>,
+        payload: Option<ArcValue>,
+        caller: Option<PeerId>,
     ) -> Result<ArcValue> {
         let path_string = path.into();
         let topic_path = match TopicPath::new(&path_string, &self.network_id) {
             Ok(tp) => tp,
             Err(e) => return Err(anyhow!("Failed to parse topic path: {path_string} : {e}",)),
         };
+        let topic_path = self.route_version(topic_path).await;
 
         self.logger
             .debug(format!("Processing request: {topic_path}"));
@@ -1234,9 +3396,14 @@ impl Node {
             self.logger
                 .debug(format!("Executing local handler for: {topic_path}"));
 
-            // Create request context
+            // Create request context with a fresh cancel token for this request
+            let cancel_token = CancellationToken::new();
             let mut context =
-                RequestContext::new(&topic_path, Arc::new(self.clone()), self.logger.clone());
+                RequestContext::new(&topic_path, Arc::new(self.clone()), self.logger.clone())
+                    .with_cancel_token(cancel_token.clone());
+            if let Some(caller) = caller {
+                context = context.with_peer_info(caller);
+            }
 
             // Extract parameters using the original registration path
             if let Ok(params) = topic_path.extract_params(&registration_path.action_path()) {
@@ -1247,9 +3414,16 @@ impl Node {
                     context.path_params
                 ));
             }
+            self.check_access(&context).await?;
+            self.run_middleware(&context).await?;
+
+            // Counted until the handler completes, for the shutdown drain
+            let _in_flight = InFlightRequest::new(&self.in_flight_requests);
 
             // Execute the handler and return result
-            return handler(payload, context).await;
+            return self
+                .execute_cancellable(&topic_path, handler(payload, context), cancel_token)
+                .await;
         } else {
             Err(anyhow!("No local handler found for topic: {topic_path}"))
         }
@@ -1267,12 +3441,453 @@ impl Node {
         P: AsArcValue + Send + Sync,
         T: 'static + Send + Sync + Clone + Debug + for<'de> serde::Deserialize<'de>,
     {
-        let request_payload_av = payload.map(P::into_arc_value_type);
+        self.request_with_cancel_token(path, payload, CancellationToken::new())
+            .await
+    }
+
+    /// Handle a request that the caller can cancel
+    ///
+    /// INTENTION: Same routing as `request`, but the action receives a child of
+    /// `cancel_token` through `RequestContext::cancel_token`. Cancelling the token,
+    /// dropping the returned future or hitting the request timeout cancels the
+    /// action's token, so long-running actions can stop at their next yield point.
+    pub async fn request_with_cancel_token<P, T>(
+        &self,
+        path: impl Into<String>,
+        payload: Option<P>,
+        cancel_token: CancellationToken,
+    ) -> Result<T>
+    where
+        P: AsArcValue + Send + Sync,
+        T: 'static + Send + Sync + Clone + Debug + for<'de> serde::Deserialize<'de>,
+    {
+        let timeout = self.config.default_request_timeout;
+        self.request_within(path.into(), payload, cancel_token, timeout)
+            .await
+    }
+
+    /// Handle a request that must complete within `timeout`
+    ///
+    /// INTENTION: Same routing as `request`, with a per-call timeout that
+    /// takes precedence over `NodeConfig::default_request_timeout`. The timeout
+    /// covers the total round-trip; when it fires the action's cancel token is
+    /// cancelled.
+    pub async fn request_with_timeout<P, T>(
+        &self,
+        path: impl Into<String>,
+        payload: Option<P>,
+        timeout: Duration,
+    ) -> Result<T>
+    where
+        P: AsArcValue + Send + Sync,
+        T: 'static + Send + Sync + Clone + Debug + for<'de> serde::Deserialize<'de>,
+    {
+        self.request_within(
+            path.into(),
+            payload,
+            CancellationToken::new(),
+            Some(timeout),
+        )
+        .await
+    }
+
+    /// Handle a request, retrying it up to `retries` times when it fails
+    ///
+    /// INTENTION: Ride out transient failures of a service without every
+    /// caller writing its own retry loop. Each attempt is bounded by
+    /// `NodeConfig::default_request_timeout`. Only `NetworkError`s that are
+    /// retryable (see `NetworkError::is_retryable`), such as timeouts or an
+    /// unreachable peer, are retried, after a jittered exponential backoff
+    /// (see `retry_backoff`); other errors are returned at once. When the
+    /// service path has a `RetryBudget` and it is exhausted, the last error
+    /// is returned instead of retrying, so that callers retrying together do
+    /// not amplify the load on a failing service.
+    pub async fn request_with_retries<P, T>(
+        &self,
+        path: impl Into<String>,
+        payload: Option<P>,
+        retries: u32,
+    ) -> Result<T>
+    where
+        P: AsArcValue + Send + Sync,
+        T: 'static + Send + Sync + Clone + Debug + for<'de> serde::Deserialize<'de>,
+    {
+        let path = path.into();
+        let payload = payload.map(P::into_arc_value_type);
+        let service_path = TopicPath::new(&path, &self.network_id)
+            .map(|topic_path| topic_path.service_path())
+            .map_err(|e| anyhow!("Failed to parse topic path: {path} : {e}"))?;
+        let retry_budget = self.retry_budgets.get(&service_path);
+        if let Some(bucket) = retry_budget {
+            bucket.record_request();
+        }
+
+        let timeout = self.config.default_request_timeout;
+        let mut attempt = 0;
+        loop {
+            let result = self
+                .request_within(
+                    path.clone(),
+                    payload.clone(),
+                    CancellationToken::new(),
+                    timeout,
+                )
+                .await;
+            let error = match result {
+                Ok(response) => return Ok(response),
+                Err(e) => e,
+            };
+            let retryable = error
+                .downcast_ref::<NetworkError>()
+                .is_some_and(NetworkError::is_retryable);
+            if !retryable || attempt >= retries {
+                return Err(error);
+            }
+            if let Some(bucket) = retry_budget {
+                if !bucket.try_retry() {
+                    self.logger
+                        .warn(format!("retry budget exhausted for {service_path}"));
+                    return Err(error);
+                }
+            }
+            attempt += 1;
+            let backoff = retry_backoff(attempt);
+            self.logger.debug(format!(
+                "Retrying request to {path} in {}ms ({attempt} of {retries}): {error}",
+                backoff.as_millis()
+            ));
+            sleep(backoff).await;
+        }
+    }
+
+    /// Handle a request, answering from `fallback` when the service cannot be reached
+    ///
+    /// INTENTION: Let a service serve a cached or default response while the
+    /// remote it depends on is offline or degraded. `fallback` is only called
+    /// when the request fails with a retryable `NetworkError` (for instance
+    /// when the peer is unreachable or the circuit for the service is open)
+    /// or does not complete within `NodeConfig::default_request_timeout`;
+    /// other errors are returned as they are.
+    ///
+    /// Example:
+    /// ```ignore
+    /// let rate: f64 = node
+    ///     .request_with_fallback("rates/usd", Some(currency), |_error| async move {
+    ///         Ok(cached_rate)
+    ///     })
+    ///     .await?;
+    /// ```
+    pub async fn request_with_fallback<P, T, F, Fut>(
+        &self,
+        path: impl Into<String>,
+        payload: Option<P>,
+        fallback: F,
+    ) -> Result<T>
+    where
+        P: AsArcValue + Send + Sync,
+        T: 'static + Send + Sync + Clone + Debug + for<'de> serde::Deserialize<'de>,
+        F: FnOnce(NetworkError) -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let path = path.into();
+        let cancel_token = CancellationToken::new();
+        let request = self.request_value(
+            path.clone(),
+            payload.map(P::into_arc_value_type),
+            cancel_token.clone(),
+        );
+        let result = match self.config.default_request_timeout {
+            Some(timeout) => match tokio::time::timeout(timeout, request).await {
+                Ok(result) => result,
+                Err(_) => {
+                    cancel_token.cancel();
+                    Err(NetworkError::ConnectionError(
+                        ErrorCode::Timeout,
+                        format!(
+                            "Request to {path} timed out after {}ms",
+                            timeout.as_millis()
+                        ),
+                    )
+                    .into())
+                }
+            },
+            None => request.await,
+        };
+
+        let error = match result {
+            Ok(mut response_av) => return response_av.as_type::<T>(),
+            Err(error) => error,
+        };
+        match error.downcast::<NetworkError>() {
+            Ok(error) if error.is_retryable() => {
+                self.logger
+                    .debug(format!("Request to {path} failed, using fallback: {error}"));
+                fallback(error).await
+            }
+            Ok(error) => Err(error.into()),
+            Err(error) => Err(error),
+        }
+    }
+
+    /// Send a request to every node hosting the service and collect the results
+    ///
+    /// INTENTION: Fan a request out to the local node (if it hosts the service)
+    /// and to every known peer that advertises it, so that callers can build
+    /// map-reduce and read-quorum operations without a coordinator service. The
+    /// calls run concurrently, each bounded by `timeout`. A failed call is
+    /// reported next to its peer instead of failing the whole request; the
+    /// result is only an error when the path is invalid or no node hosts the
+    /// service.
+    pub async fn request_all<P, T>(
+        &self,
+        path: impl Into<String>,
+        payload: Option<P>,
+        timeout: Duration,
+    ) -> Result<Vec<(PeerId, Result<T>)>>
+    where
+        P: AsArcValue + Send + Sync,
+        T: 'static + Send + Sync + Clone + Debug + for<'de> serde::Deserialize<'de>,
+    {
+        let path_string = path.into();
+        let topic_path = TopicPath::new(&path_string, &self.network_id)
+            .map_err(|e| anyhow!("Failed to parse topic path: {path_string} : {e}"))?;
+        let service_path = topic_path.service_path();
+        let action_name = topic_path.get_segments()[1..].join("/");
+        let payload = payload.map(P::into_arc_value_type);
+
+        let mut targets: Vec<(PeerId, Option<ActionHandler>)> = Vec::new();
+        if self
+            .service_registry
+            .get_local_action_handler(&topic_path)
+            .await
+            .is_some()
+        {
+            targets.push((self.peer_id.clone(), None));
+        }
+
+        // Every peer advertising the service gets its own proxy, since the
+        // registry only keeps one remote handler per service
+        let hosting_peers: Vec<(PeerId, Vec<ServiceMetadata>)> = self
+            .peer_registry
+            .find_peers_by_service(&service_path)
+            .into_iter()
+            .filter_map(|entry| {
+                let node_info = entry.node_info?;
+                let services: Vec<ServiceMetadata> = node_info
+                    .services
+                    .into_iter()
+                    .filter(|service| {
+                        service.service_path == service_path
+                            && service.network_id == topic_path.network_id()
+                    })
+                    .collect();
+                (!services.is_empty()).then_some((node_info.peer_id, services))
+            })
+            .collect();
+        for (peer_id, capabilities) in hosting_peers {
+            let config = CreateRemoteServicesConfig {
+                capabilities,
+                peer_id: peer_id.clone(),
+                request_timeout_ms: timeout.as_millis() as u64,
+            };
+            let dependencies = RemoteServiceDependencies {
+                network_transport: self.network_transport.clone(),
+                serializer: self.serializer.clone(),
+                local_node_id: self.peer_id.clone(),
+                pending_requests: self.pending_requests.clone(),
+                logger: self.logger.clone(),
+            };
+            let services = RemoteService::create_from_capabilities(config, dependencies).await?;
+            if let Some(service) = services.first() {
+                targets.push((
+                    peer_id,
+                    Some(service.create_action_handler(action_name.clone())),
+                ));
+            }
+        }
+
+        if targets.is_empty() {
+            return Err(NetworkError::MessageError(
+                ErrorCode::ServiceNotFound,
+                format!("service not found: {topic_path}"),
+            )
+            .into());
+        }
+        self.logger
+            .debug(format!("Sending {topic_path} to {} nodes", targets.len()));
+
+        let calls = targets.into_iter().map(|(peer_id, handler)| {
+            let payload = payload.clone();
+            let topic_path = &topic_path;
+            let path_string = &path_string;
+            async move {
+                let cancel_token = CancellationToken::new();
+                let request = async {
+                    match handler {
+                        Some(handler) => {
+                            let context = RequestContext::new(
+                                topic_path,
+                                Arc::new(self.clone()),
+                                self.logger.clone(),
+                            )
+                            .with_cancel_token(cancel_token.child_token());
+                            handler(payload, context).await
+                        }
+                        None => {
+                            self.request_value(path_string.clone(), payload, cancel_token.clone())
+                                .await
+                        }
+                    }
+                };
+                let result = match tokio::time::timeout(timeout, request).await {
+                    Ok(result) => result.and_then(|mut value| value.as_type::<T>()),
+                    Err(_) => {
+                        cancel_token.cancel();
+                        Err(NetworkError::ConnectionError(
+                            ErrorCode::Timeout,
+                            format!(
+                                "Request to {path_string} on {peer_id} timed out after {}ms",
+                                timeout.as_millis()
+                            ),
+                        )
+                        .into())
+                    }
+                };
+                (peer_id, result)
+            }
+        });
+        Ok(futures_util::future::join_all(calls).await)
+    }
+
+    /// Route a request, failing it if it takes longer than `timeout`
+    pub(crate) async fn request_within<P, T>(
+        &self,
+        path: String,
+        payload: Option<P>,
+        cancel_token: CancellationToken,
+        timeout: Option<Duration>,
+    ) -> Result<T>
+    where
+        P: AsArcValue + Send + Sync,
+        T: 'static + Send + Sync + Clone + Debug + for<'de> serde::Deserialize<'de>,
+    {
+        let request = self.request_value(
+            path.clone(),
+            payload.map(P::into_arc_value_type),
+            cancel_token.clone(),
+        );
+        let mut response_av = match timeout {
+            Some(timeout) => match tokio::time::timeout(timeout, request).await {
+                Ok(result) => result?,
+                Err(_) => {
+                    cancel_token.cancel();
+                    return Err(NetworkError::ConnectionError(
+                        ErrorCode::Timeout,
+                        format!(
+                            "Request to {path} timed out after {}ms",
+                            timeout.as_millis()
+                        ),
+                    )
+                    .into());
+                }
+            },
+            None => request.await?,
+        };
+        response_av.as_type::<T>()
+    }
+
+    /// Request an action that returns a `ResponseStream`
+    ///
+    /// INTENTION: Consume a streamed action result value by value, whether the
+    /// action runs locally or on a remote node. Routing, load balancing and the
+    /// circuit breaker apply as for `request`; the request timeout covers the
+    /// wait for the stream to start, not its whole length. The stream ends with
+    /// an error if the action's stream fails or a value does not convert to T.
+    ///
+    /// Example:
+    /// ```ignore
+    /// use tokio_stream::StreamExt;
+    ///
+    /// let mut rows = node.request_stream::<(), Row>("db/export", None).await?;
+    /// while let Some(row) = rows.next().await {
+    ///     println!("{:?}", row?);
+    /// }
+    /// ```
+    pub async fn request_stream<P, T>(
+        &self,
+        path: impl Into<String>,
+        payload: Option<P>,
+    ) -> Result<impl Stream<Item = Result<T>>>
+    where
+        P: AsArcValue + Send + Sync,
+        T: 'static + Send + Sync + Clone + Debug + for<'de> serde::Deserialize<'de>,
+    {
         let path_string = path.into();
+        let response_av = self
+            .request_value(
+                path_string.clone(),
+                payload.map(P::into_arc_value_type),
+                CancellationToken::new(),
+            )
+            .await?;
+        let stream = ResponseStream::take_from(&response_av)
+            .ok_or_else(|| anyhow!("Action {path_string} did not return a stream"))?;
+        Ok(stream.try_map(|mut value| value.as_type::<T>()))
+    }
+
+    /// Choose between a versioned action path and its unversioned fallback
+    ///
+    /// INTENTION: Let services evolve their API without breaking callers. A
+    /// versioned path is routed as is when a local or remote handler serves
+    /// it, and to the unversioned action otherwise, so callers asking for a
+    /// version a service does not register still reach its original action.
+    async fn route_version(&self, topic_path: TopicPath) -> TopicPath {
+        if topic_path.version().is_none()
+            || self
+                .service_registry
+                .get_local_action_handler(&topic_path)
+                .await
+                .is_some()
+            || !self
+                .service_registry
+                .get_remote_action_handlers(&topic_path)
+                .await
+                .is_empty()
+        {
+            return topic_path;
+        }
+        topic_path.without_version()
+    }
+
+    /// Route a request to a local or remote handler and return its raw result
+    #[tracing::instrument(
+        name = "request",
+        skip_all,
+        fields(
+            node.id = %self.config.node_id,
+            network.id = %self.network_id,
+            service = tracing::field::Empty,
+            action = tracing::field::Empty,
+        )
+    )]
+    async fn request_value(
+        &self,
+        path_string: String,
+        request_payload_av: Option<ArcValue>,
+        cancel_token: CancellationToken,
+    ) -> Result<ArcValue> {
         let topic_path = match TopicPath::new(&path_string, &self.network_id) {
             Ok(tp) => tp,
             Err(e) => return Err(anyhow!("Failed to parse topic path: {path_string} : {e}",)),
         };
+        let topic_path = match (self.config.default_api_version, topic_path.version()) {
+            (Some(version), None) => topic_path.with_version(version),
+            _ => topic_path,
+        };
+        let topic_path = self.route_version(topic_path).await;
+        let span = tracing::Span::current();
+        span.record("service", topic_path.service_path());
+        span.record("action", topic_path.action_path());
 
         self.logger
             .debug(format!("Processing request: {topic_path}"));
@@ -1286,9 +3901,11 @@ impl Node {
             self.logger
                 .debug(format!("Executing local handler for: {topic_path}"));
 
-            // Create request context
+            // Create request context with a per-request token derived from the caller's
+            let request_token = cancel_token.child_token();
             let mut context =
-                RequestContext::new(&topic_path, Arc::new(self.clone()), self.logger.clone());
+                RequestContext::new(&topic_path, Arc::new(self.clone()), self.logger.clone())
+                    .with_cancel_token(request_token.clone());
 
             // Extract parameters using the original registration path
             if let Ok(path_params) = topic_path.extract_params(&registration_path.action_path()) {
@@ -1299,11 +3916,20 @@ impl Node {
                     context.path_params
                 ));
             }
+            self.check_access(&context).await?;
+            self.run_middleware(&context).await?;
 
-            // Execute the handler and return result
-            let mut response_av = handler(request_payload_av.clone(), context).await?;
+            // Counted until the handler completes, for the shutdown drain
+            let _in_flight = InFlightRequest::new(&self.in_flight_requests);
 
-            return response_av.as_type::<T>();
+            // Execute the handler and return result
+            return self
+                .execute_cancellable(
+                    &topic_path,
+                    handler(request_payload_av.clone(), context),
+                    request_token,
+                )
+                .await;
         }
 
         // If no local handler found, look for remote handlers
@@ -1337,64 +3963,699 @@ impl Node {
 
             // Create request context
             let context =
-                RequestContext::new(&topic_path, Arc::new(self.clone()), self.logger.clone());
+                RequestContext::new(&topic_path, Arc::new(self.clone()), self.logger.clone())
+                    .with_cancel_token(cancel_token.child_token());
 
             // For remote handlers, we don't have the registration path
             // In the future, we should enhance the remote handler registry to include registration paths
 
+            // Fail fast while the circuit for this service is open
+            let circuit_breaker = self.circuit_breakers.get(&topic_path.service_path());
+            if let Some(breaker) = circuit_breaker {
+                if !breaker.try_acquire() {
+                    self.logger
+                        .warn(format!("Circuit open, rejecting request: {topic_path}"));
+                    return Err(NetworkError::TransportError(
+                        ErrorCode::CircuitOpen,
+                        "circuit open".to_string(),
+                    )
+                    .into());
+                }
+            }
+
             // Execute the selected handler
-            let mut response_av = handler(request_payload_av.clone(), context).await?;
-            return response_av.as_type::<T>();
+            let result = handler(request_payload_av.clone(), context).await;
+            if let Some(breaker) = circuit_breaker {
+                match &result {
+                    Err(error) if CircuitBreaker::is_failure(error) => breaker.record_failure(),
+                    _ => breaker.record_success(),
+                }
+            }
+            return result;
+        }
+
+        // No handler found
+        self.logger
+            .warn(format!("No handler found for action: {topic_path}"));
+        Err(NetworkError::MessageError(
+            ErrorCode::ServiceNotFound,
+            format!("service not found: {topic_path}"),
+        )
+        .into())
+    }
+
+    /// Run a local action handler so that it can observe cancellation
+    ///
+    /// INTENTION: The handler runs in its own task, so it keeps running (and can
+    /// see its cancel token fire) when the caller goes away. The token is
+    /// cancelled when the caller drops the request future or when the request
+    /// timeout fires; in both cases the caller stops waiting immediately.
+    async fn execute_cancellable(
+        &self,
+        topic_path: &TopicPath,
+        handler_future: ServiceFuture,
+        cancel_token: CancellationToken,
+    ) -> Result<ArcValue> {
+        if self.service_restarts.is_unhealthy(topic_path) {
+            return Err(anyhow!(
+                "Service {} is unhealthy",
+                topic_path.service_path()
+            ));
+        }
+
+        // Cancels the token if this future is dropped before the handler completes
+        let drop_guard = cancel_token.clone().drop_guard();
+        // Requests the handler makes are attributed to its service, and see the
+        // service locks held by the handler that made this request
+        let handler_task = tokio::spawn(CALLING_SERVICE.scope(
+            topic_path.service_path(),
+            with_held_service_locks(handler_future),
+        ));
+        let timeout_ms = self.config.request_timeout_ms;
+
+        let result = tokio::select! {
+            joined = handler_task => match joined {
+                Ok(result) => result,
+                Err(e) => {
+                    if e.is_panic() {
+                        self.handle_action_panic(topic_path).await;
+                    }
+                    Err(anyhow!("Action handler for {topic_path} failed: {e}"))
+                }
+            },
+            _ = sleep(Duration::from_millis(timeout_ms)) => {
+                cancel_token.cancel();
+                Err(NetworkError::ConnectionError(
+                    ErrorCode::Timeout,
+                    format!("Request to {topic_path} timed out after {timeout_ms}ms"),
+                )
+                .into())
+            }
+        };
+
+        drop_guard.disarm();
+        result
+    }
+
+    /// Subscribe to a topic and receive its events as a typed async stream
+    ///
+    /// INTENTION: Offer an alternative to callback registration that composes with
+    /// `StreamExt` and async-for-await loops. Uses the default channel capacity;
+    /// see subscribe_stream_with_options to configure it.
+    ///
+    /// Example:
+    /// ```ignore
+    /// use tokio_stream::StreamExt;
+    ///
+    /// let mut stream = node.subscribe_stream::<String>("math/added").await?;
+    /// while let Some(event) = stream.next().await {
+    ///     println!("received: {}", event?);
+    /// }
+    /// ```
+    pub async fn subscribe_stream<T>(
+        &self,
+        path: impl Into<String>,
+    ) -> Result<impl Stream<Item = Result<T>>>
+    where
+        T: 'static + Send + Sync + Clone + Debug + for<'de> serde::Deserialize<'de>,
+    {
+        self.subscribe_stream_with_options(path, PublishOptions::default())
+            .await
+    }
+
+    /// Subscribe to a topic as a typed async stream using the given options
+    ///
+    /// INTENTION: Bridge event callbacks into a bounded mpsc channel whose capacity is
+    /// taken from `options.stream_channel_size`. When the channel is full, delivery
+    /// waits for the consumer, which applies backpressure to the publisher.
+    /// Dropping the returned stream cancels the subscription.
+    pub async fn subscribe_stream_with_options<T>(
+        &self,
+        path: impl Into<String>,
+        options: PublishOptions,
+    ) -> Result<impl Stream<Item = Result<T>>>
+    where
+        T: 'static + Send + Sync + Clone + Debug + for<'de> serde::Deserialize<'de>,
+    {
+        let channel_size = options
+            .stream_channel_size
+            .unwrap_or(DEFAULT_STREAM_CHANNEL_SIZE)
+            .max(1);
+        let (sender, receiver) = mpsc::channel::<Result<T>>(channel_size);
+
+        let callback: EventCallback = Box::new(move |_ctx, data| {
+            let sender = sender.clone();
+            Box::pin(async move {
+                let item = match data {
+                    Some(mut value) => value.as_type::<T>(),
+                    None => Err(anyhow!("Received event without payload")),
+                };
+                // The receiver is gone once the stream is dropped; the pending
+                // unsubscribe will remove this callback shortly.
+                let _ = sender.send(item).await;
+                Ok(())
+            })
+        });
+
+        let subscription_id = self.subscribe(path.into(), callback).await?;
+
+        Ok(SubscriptionStream {
+            receiver,
+            subscription_id,
+            node: self.clone(),
+        })
+    }
+
+    /// Publish an event with explicit delivery options
+    ///
+    /// INTENTION: Deliver the event to local subscribers and, when
+    /// `options.broadcast` is set, to the known peers subscribed to the topic.
+    /// With `options.ttl` the remote copies carry an expiry time, and peers
+    /// drop them once it passes.
+    /// Broadcast events are stamped with an ID (`event_dedup_id`) so that a
+    /// node receiving the same event twice delivers it once.
+    ///
+    /// With `options.ordered` the event gets the next sequence number of its
+    /// topic, and receiving nodes deliver the events of this node on that topic
+    /// in sequence order (see `OrderedEventBuffer`). Sequence numbers start at
+    /// 1 again when the node restarts.
+    ///
+    /// With `options.retain_last` the event is numbered the same way and kept
+    /// for subscribers joining later (see `subscribe_with_replay`).
+    ///
+    /// `options.dispatch` decides whether local subscribers run before this
+    /// returns. By default the event is queued for each subscriber, and each
+    /// subscriber receives its events in publish order without slowing down
+    /// the others; `BoundedAsync` also waits while a subscriber's queue is
+    /// full, and `Sync` runs the subscribers in turn.
+    #[tracing::instrument(
+        name = "publish",
+        skip_all,
+        fields(
+            node.id = %self.config.node_id,
+            network.id = %self.network_id,
+            service = tracing::field::Empty,
+            event = tracing::field::Empty,
+        )
+    )]
+    pub async fn publish_with_options(
+        &self,
+        topic: impl Into<String>,
+        data: Option<ArcValue>,
+        options: PublishOptions,
+    ) -> Result<()> {
+        let topic_string = topic.into();
+        // Check for valid topic path
+        let topic_path = match TopicPath::new(&topic_string, &self.network_id) {
+            Ok(tp) => tp,
+            Err(e) => return Err(anyhow!("Invalid topic path: {e}")),
+        };
+        let span = tracing::Span::current();
+        span.record("service", topic_path.service_path());
+        span.record("event", topic_path.action_path());
+        let retain_last = options.retain_last.filter(|retain_last| *retain_last > 0);
+        let sequence = (options.ordered || retain_last.is_some())
+            .then(|| self.next_event_sequence(&topic_path));
+
+        // Retain before delivering, so a subscriber joining meanwhile sees the event once
+        if let (Some(retain_last), Some(sequence)) = (retain_last, sequence) {
+            self.retain_event(&topic_path, sequence, &data, retain_last)
+                .await?;
+        }
+
+        // Publish to local subscribers
+        let local_subscribers = self
+            .service_registry
+            .get_local_event_subscribers(&topic_path)
+            .await;
+        let local_subscribers =
+            self.select_event_subscribers(&topic_path, local_subscribers, &data, sequence);
+        for (subscription_id, callback) in local_subscribers {
+            // Create an event context for this subscriber
+            let mut event_context =
+                EventContext::new(&topic_path, Arc::new(self.clone()), self.logger.clone());
+            event_context.sequence = sequence;
+            let event_context = Arc::new(event_context);
+            let queue_size = match options.dispatch {
+                DispatchMode::Sync => {
+                    self.deliver_event(
+                        &topic_path,
+                        &subscription_id,
+                        &callback,
+                        event_context,
+                        data.clone(),
+                        1,
+                    )
+                    .await;
+                    continue;
+                }
+                DispatchMode::Async => None,
+                DispatchMode::BoundedAsync { queue_size } => Some(queue_size),
+            };
+            let event = QueuedEvent {
+                topic_path: topic_path.clone(),
+                callback,
+                event_context,
+                data: data.clone(),
+            };
+            self.event_dispatcher
+                .dispatch(&subscription_id, event, queue_size, || {
+                    let node = self.clone();
+                    let subscription_id = subscription_id.clone();
+                    move |event: QueuedEvent| {
+                        let node = node.clone();
+                        let subscription_id = subscription_id.clone();
+                        async move {
+                            node.deliver_event(
+                                &event.topic_path,
+                                &subscription_id,
+                                &event.callback,
+                                event.event_context,
+                                event.data,
+                                1,
+                            )
+                            .await;
+                        }
+                    }
+                })
+                .await;
+        }
+
+        // Broadcast to remote nodes if requested and network is available
+        if options.broadcast && self.supports_networking {
+            let value_bytes = self
+                .serializer
+                .read()
+                .await
+                .serialize_value(&data.unwrap_or_else(ArcValue::null))
+                .map_err(|e| anyhow!("Failed to serialize event payload: {e}"))?
+                .to_vec();
+            // Only ordered events are numbered for remote subscribers
+            let sequence = sequence.filter(|_| options.ordered);
+            // Ordered events with the same payload are still distinct events
+            let publisher = self.peer_id.public_key.as_str();
+            let dedup_id = match sequence {
+                Some(sequence) => {
+                    sequenced_event_dedup_id(publisher, topic_path.as_str(), sequence, &value_bytes)
+                }
+                None => event_dedup_id(
+                    publisher,
+                    rand::rng().random(),
+                    topic_path.as_str(),
+                    &value_bytes,
+                ),
+            };
+            // Our own event coming back through a peer is a duplicate too
+            self.record_event(dedup_id);
+            self.broadcast_event(&topic_path, value_bytes, dedup_id, sequence, options.ttl)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Keep a published event for replay to subscribers joining later
+    async fn retain_event(
+        &self,
+        topic_path: &TopicPath,
+        sequence: u64,
+        data: &Option<ArcValue>,
+        retain_last: usize,
+    ) -> Result<()> {
+        let size = self
+            .serializer
+            .read()
+            .await
+            .serialize_value(data.as_ref().unwrap_or(&ArcValue::null()))
+            .map_err(|e| anyhow!("Failed to serialize event payload: {e}"))?
+            .len();
+        let retained = self
+            .event_replay
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .retain(
+                topic_path.as_str(),
+                sequence,
+                data.clone(),
+                size,
+                retain_last,
+            );
+        if !retained {
+            self.logger.debug(format!(
+                "Event on {topic_path} not retained: {size} bytes exceed the per-topic limit"
+            ));
+        }
+        Ok(())
+    }
+
+    /// Subscribe to a topic, first receiving the events retained on it
+    ///
+    /// INTENTION: Let subscribers that join late, such as a restarted service,
+    /// catch up on the events published with `PublishOptions::retain_last`.
+    /// The retained events selected by `since` are delivered in publish order
+    /// before any live event; their contexts carry their sequence numbers.
+    ///
+    /// Replay is best effort: only events still held in the topic's ring
+    /// buffer are replayed, and only events published on this node are
+    /// retained. Topic patterns with wildcards cannot be replayed.
+    pub async fn subscribe_with_replay(
+        &self,
+        topic: impl Into<String>,
+        callback: EventCallback,
+        since: ReplaySince,
+    ) -> Result<String> {
+        let topic = topic.into();
+        let topic_path = TopicPath::new(&topic, &self.network_id)
+            .map_err(|e| anyhow!("Invalid topic path: {e}"))?;
+        if topic_path.is_pattern() {
+            return Err(anyhow!("Cannot replay the events of topic pattern {topic}"));
+        }
+        let callback: crate::services::service_registry::EventCallback = callback.into();
+
+        // Live events wait for the replay, and are skipped when already replayed
+        let last_replayed = Arc::new(tokio::sync::Mutex::new(None::<u64>));
+        let mut replay_guard = last_replayed.clone().lock_owned().await;
+        let live_callback: EventCallback = {
+            let callback = callback.clone();
+            Box::new(move |ctx, data| {
+                let callback = callback.clone();
+                let last_replayed = last_replayed.clone();
+                Box::pin(async move {
+                    let last_replayed = *last_replayed.lock().await;
+                    if let (Some(last_replayed), Some(sequence)) = (last_replayed, ctx.sequence) {
+                        if sequence <= last_replayed {
+                            return Ok(());
+                        }
+                    }
+                    callback(ctx, data).await
+                })
+            })
+        };
+        let subscription_id = self.subscribe(topic, live_callback).await?;
+
+        let retained = self
+            .event_replay
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .replay(topic_path.as_str(), since);
+        for (sequence, data) in retained {
+            let mut event_context =
+                EventContext::new(&topic_path, Arc::new(self.clone()), self.logger.clone());
+            event_context.sequence = Some(sequence);
+            self.deliver_event(
+                &topic_path,
+                &subscription_id,
+                &callback,
+                Arc::new(event_context),
+                data,
+                1,
+            )
+            .await;
+            *replay_guard = Some(sequence);
+        }
+        drop(replay_guard);
+
+        Ok(subscription_id)
+    }
+
+    /// Assign the next sequence number for an ordered event on a topic
+    fn next_event_sequence(&self, topic_path: &TopicPath) -> u64 {
+        let mut sequences = self
+            .event_sequences
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        let sequence = sequences
+            .entry(topic_path.as_str().to_string())
+            .or_insert(0);
+        *sequence += 1;
+        *sequence
+    }
+
+    /// Pick the local subscribers an event is delivered to
+    ///
+    /// Subscriptions outside a group all receive the event. Each subscription
+    /// group receives it once, through the member whose turn it is, or queues
+    /// it when none of its members is subscribed anymore.
+    fn select_event_subscribers(
+        &self,
+        topic_path: &TopicPath,
+        subscribers: Vec<(String, crate::services::service_registry::EventCallback)>,
+        data: &Option<ArcValue>,
+        sequence: Option<u64>,
+    ) -> Vec<(String, crate::services::service_registry::EventCallback)> {
+        let mut groups = self
+            .subscription_groups
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        let available: HashSet<&str> = subscribers
+            .iter()
+            .map(|(subscription_id, _)| subscription_id.as_str())
+            .filter(|subscription_id| groups.is_member(subscription_id))
+            .collect();
+        let event = PendingGroupEvent {
+            topic_path: topic_path.clone(),
+            data: data.clone(),
+            sequence,
+        };
+        let chosen = groups.route(&event, &available);
+        subscribers
+            .into_iter()
+            .filter(|(subscription_id, _)| {
+                !groups.is_member(subscription_id) || chosen.contains(subscription_id)
+            })
+            .collect()
+    }
+
+    /// Deliver the events a subscription group queued to its new member
+    async fn deliver_pending_group_events(
+        &self,
+        subscription_id: &str,
+        pending: Vec<PendingGroupEvent>,
+    ) {
+        for event in pending {
+            let callback = self
+                .service_registry
+                .get_local_event_subscribers(&event.topic_path)
+                .await
+                .into_iter()
+                .find(|(id, _)| id == subscription_id)
+                .map(|(_, callback)| callback);
+            let Some(callback) = callback else {
+                self.logger.warn(format!(
+                    "Dropping queued group event for {}: subscription {subscription_id} no longer exists",
+                    event.topic_path
+                ));
+                continue;
+            };
+            let mut event_context = EventContext::new(
+                &event.topic_path,
+                Arc::new(self.clone()),
+                self.logger.clone(),
+            );
+            event_context.sequence = event.sequence;
+            self.deliver_event(
+                &event.topic_path,
+                subscription_id,
+                &callback,
+                Arc::new(event_context),
+                event.data,
+                1,
+            )
+            .await;
         }
-
-        // No handler found
-        Err(anyhow!("No handler found for action: {topic_path}"))
     }
 
-    /// Publish with options - Helper method to implement the publish_with_options functionality
-    async fn publish_with_options(
+    /// Run a local subscriber, capturing a failure in the dead-letter queue
+    ///
+    /// Returns whether the subscriber handled the event. `attempt` is the
+    /// number of this delivery attempt, recorded if it fails.
+    async fn deliver_event(
         &self,
-        topic: impl Into<String>,
+        topic_path: &TopicPath,
+        subscription_id: &str,
+        callback: &crate::services::service_registry::EventCallback,
+        event_context: Arc<EventContext>,
         data: Option<ArcValue>,
-        options: PublishOptions,
-    ) -> Result<()> {
-        let topic_string = topic.into();
-        // Check for valid topic path
-        let topic_path = match TopicPath::new(&topic_string, &self.network_id) {
-            Ok(tp) => tp,
-            Err(e) => return Err(anyhow!("Invalid topic path: {e}")),
+        attempt: u32,
+    ) -> bool {
+        let error = match callback(event_context, data.clone()).await {
+            Ok(()) => return true,
+            Err(e) => e,
         };
+        self.logger
+            .error(format!("Error in event handler for {topic_path}: {error}"));
 
-        // Publish to local subscribers
-        let local_subscribers = self
+        let subscriber_path = self
             .service_registry
-            .get_local_event_subscribers(&topic_path)
-            .await;
-        for (_subscription_id, callback) in local_subscribers {
-            // Create an event context for this subscriber
+            .get_subscription_topic(subscription_id)
+            .await
+            .map(|topic| topic.as_str().to_string())
+            .unwrap_or_default();
+        let entry = DeadLetterEntry {
+            event_topic: topic_path.as_str().to_string(),
+            payload: data.unwrap_or_else(ArcValue::null),
+            subscriber_path,
+            subscription_id: subscription_id.to_string(),
+            error: error.to_string(),
+            failed_at: std::time::SystemTime::now(),
+            attempt_count: attempt,
+        };
+        self.dead_letters
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(entry);
+        false
+    }
+
+    /// Event deliveries whose subscriber failed, oldest first
+    pub fn dead_letters(&self) -> Vec<DeadLetterEntry> {
+        self.dead_letters
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .entries()
+    }
+
+    /// Deliver dead-lettered events again to the subscribers that failed them
+    ///
+    /// INTENTION: Recover events lost to a subscriber failure once its cause
+    /// is fixed. `filter` is a topic or topic pattern (e.g. `orders/*`);
+    /// None replays every entry. Entries are removed from the queue when
+    /// replayed; a delivery that fails again is queued again with its attempt
+    /// count increased, and entries whose subscription is gone are dropped.
+    /// Returns the number of events delivered successfully.
+    pub async fn replay_dead_letters(&self, filter: Option<String>) -> Result<u32> {
+        let filter = match filter {
+            Some(filter) => Some(
+                TopicPath::new(&filter, &self.network_id)
+                    .map_err(|e| anyhow!("Invalid dead letter filter: {e}"))?,
+            ),
+            None => None,
+        };
+        let entries = self
+            .dead_letters
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .take_matching(|entry| match &filter {
+                Some(filter) => TopicPath::new(&entry.event_topic, &self.network_id)
+                    .is_ok_and(|topic| filter.matches(&topic)),
+                None => true,
+            });
+
+        let mut delivered = 0;
+        for entry in entries {
+            let topic_path = TopicPath::new(&entry.event_topic, &self.network_id)
+                .map_err(|e| anyhow!("Invalid dead letter topic: {e}"))?;
+            let callback = self
+                .service_registry
+                .get_local_event_subscribers(&topic_path)
+                .await
+                .into_iter()
+                .find(|(subscription_id, _)| *subscription_id == entry.subscription_id)
+                .map(|(_, callback)| callback);
+            let Some(callback) = callback else {
+                self.logger.debug(format!(
+                    "Dropping dead letter for {}: subscription {} no longer exists",
+                    entry.event_topic, entry.subscription_id
+                ));
+                continue;
+            };
+
             let event_context = Arc::new(EventContext::new(
                 &topic_path,
                 Arc::new(self.clone()),
                 self.logger.clone(),
             ));
-            // Execute the callback with correct arguments
-            if let Err(e) = callback(event_context, data.clone()).await {
-                self.logger.error(format!(
-                    "Error in local event handler for {topic_string}: {e}"
-                ));
+            let payload = if entry.payload.is_null() {
+                None
+            } else {
+                Some(entry.payload)
+            };
+            if self
+                .deliver_event(
+                    &topic_path,
+                    &entry.subscription_id,
+                    &callback,
+                    event_context,
+                    payload,
+                    entry.attempt_count + 1,
+                )
+                .await
+            {
+                delivered += 1;
             }
         }
+        Ok(delivered)
+    }
 
-        // Broadcast to remote nodes if requested and network is available
-        if options.broadcast && self.supports_networking {
-            if let Some(_transport) = &*self.network_transport.read().await {
-                //TODO
-                // Log message since we can't implement send yet
+    /// Record an event in the dedup window, returning false if it was seen
+    fn record_event(&self, dedup_id: [u8; 8]) -> bool {
+        self.event_dedup
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(dedup_id)
+    }
+
+    /// Send an event to the known peers that subscribed to its topic
+    ///
+    /// Peers advertise their subscriptions in their `NodeInfo`, so an event
+    /// nobody else listens to stays on this node.
+    async fn broadcast_event(
+        &self,
+        topic_path: &TopicPath,
+        value_bytes: Vec<u8>,
+        dedup_id: [u8; 8],
+        sequence: Option<u64>,
+        ttl: Option<Duration>,
+    ) -> Result<()> {
+        let transport_guard = self.network_transport.read().await;
+        let Some(transport) = transport_guard.as_ref() else {
+            return Ok(());
+        };
+        let peers: Vec<PeerId> = self
+            .known_peers
+            .read()
+            .await
+            .values()
+            .filter(|node_info| {
+                node_info.subscriptions.iter().any(|subscription| {
+                    TopicPath::new(subscription, &self.network_id)
+                        .is_ok_and(|pattern| pattern.matches(topic_path))
+                })
+            })
+            .map(|node_info| node_info.peer_id.clone())
+            .collect();
+        if peers.is_empty() {
+            return Ok(());
+        }
+
+        let mut payload = NetworkMessagePayloadItem::new(
+            topic_path.as_str().to_string(),
+            value_bytes,
+            String::new(),
+        )
+        .with_format(self.config.serialization_backend);
+        payload.dedup_id = Some(dedup_id);
+        payload.sequence = sequence;
+        let expires_at = ttl.map(|ttl| std::time::SystemTime::now() + ttl);
+
+        for peer in peers {
+            let message = NetworkMessage {
+                source: self.peer_id.clone(),
+                destination: peer.clone(),
+                message_type: "Event".to_string(),
+                payloads: vec![payload.clone()],
+                signature: None,
+                hop_count: 0,
+                visited_peers: Vec::new(),
+                auth_tag: None,
+                expires_at,
+            };
+            // One unreachable peer must not stop delivery to the others
+            if let Err(e) = transport.send_message(message).await {
                 self.logger
-                    .debug(format!("Would broadcast event {topic_string} to network"));
+                    .warn(format!("Failed to send event {topic_path} to {peer}: {e}"));
             }
         }
-
         Ok(())
     }
 
@@ -1410,12 +4671,29 @@ impl Node {
         let mut known_peers = self.known_peers.write().await;
         if let Some(existing_peer) = known_peers.get(&new_peer.peer_id) {
             //check if node info is older then the stored peer
-            if new_peer.version > existing_peer.version {
-                self.remove_peer_services(existing_peer).await?;
-                //remove and add again
-                known_peers.remove(&new_peer.peer_id);
+            // The same version is sent again with the services restricted to
+            // our networks once the peer knows them
+            if new_peer.version >= existing_peer.version {
+                // Only touch the services that changed, so calls to the
+                // others are not interrupted
+                let diff = existing_peer.diff(&new_peer);
+                let changed = NodeInfo {
+                    services: existing_peer
+                        .services
+                        .iter()
+                        .filter(|service| !new_peer.services.contains(service))
+                        .cloned()
+                        .collect(),
+                    ..existing_peer.clone()
+                };
+                self.remove_peer_services(&changed).await?;
                 known_peers.insert(new_peer.peer_id.clone(), new_peer.clone());
-                return self.add_new_peer(new_peer).await;
+                return self
+                    .add_new_peer(NodeInfo {
+                        services: diff.added_services,
+                        ..new_peer
+                    })
+                    .await;
             }
         } else {
             known_peers.insert(new_peer.peer_id.clone(), new_peer.clone());
@@ -1425,6 +4703,51 @@ impl Node {
         Ok(Vec::new())
     }
 
+    /// Bring the known peers in line with the peer registry
+    ///
+    /// INTENTION: Recover from peer events the listener missed. Connected
+    /// peers are processed again, which only adds the services not known yet,
+    /// and known peers the registry no longer lists as connected are removed.
+    async fn resync_known_peers(&self) -> Result<()> {
+        let mut connected = HashSet::new();
+        for entry in self.peer_registry.get_all_peers() {
+            if entry.status != PeerStatus::Connected {
+                continue;
+            }
+            let Some(peer_node_info) = entry.node_info else {
+                continue;
+            };
+            connected.insert(peer_node_info.peer_id.clone());
+            self.process_remote_capabilities(peer_node_info).await?;
+        }
+
+        let departed: Vec<PeerId> = self
+            .known_peers
+            .read()
+            .await
+            .keys()
+            .filter(|peer_id| !connected.contains(*peer_id))
+            .cloned()
+            .collect();
+        for peer_id in departed {
+            self.remove_known_peer(&peer_id).await?;
+        }
+        Ok(())
+    }
+
+    /// Forget a peer that left and remove the services it provided
+    async fn remove_known_peer(&self, peer_id: &PeerId) -> Result<()> {
+        let mut known_peers = self.known_peers.write().await;
+        if let Some(existing_peer) = known_peers.remove(peer_id) {
+            self.remove_peer_services(&existing_peer).await?;
+        }
+        self.ordered_events
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove_publisher(&peer_id.public_key);
+        Ok(())
+    }
+
     async fn remove_peer_services(
         &self,
         existing_peer: &NodeInfo,
@@ -1437,7 +4760,7 @@ impl Node {
             )
             .unwrap();
             self.service_registry
-                .remove_remote_service(&service_path)
+                .remove_remote_service(&service_path, &existing_peer.peer_id)
                 .await?;
         }
         Ok(Vec::new())
@@ -1534,42 +4857,58 @@ impl Node {
         Ok(remote_services)
     }
 
-    //this function is debounced since it can be called in rapid suyccession.. it is debounced for 5 seconds..
-    // it will then call the notify_node_change_impl  which will use the transposter to send a handshake message with the latest node info to all known peers.
     /// Debounced notification of node change.
     ///
     /// INTENTION: This function is debounced to avoid flooding the network with repeated notifications.
-    /// If called multiple times in rapid succession, only the last call within a 5 second window will
-    /// trigger the actual notification. After the debounce period, it delegates to notify_node_change_impl,
-    /// which sends the latest node info to all known peers via the transport.
+    /// The first call schedules a notification `QuicTransportOptions::service_update_debounce_ms`
+    /// later; calls made before it fires are batched into it, since it reads the node info only
+    /// when it fires. It then delegates to notify_node_change_impl, which sends the latest node
+    /// info to all known peers via the transport.
     pub async fn notify_node_change(&self) -> Result<()> {
         let debounce_task = self.debounce_notify_task.clone();
-        let this = self.clone();
-        // Cancel any existing debounce task
-        {
-            let mut guard = debounce_task.lock().await;
-            if let Some(handle) = guard.take() {
-                handle.abort();
-            }
+        let mut guard = debounce_task.lock().await;
+        if guard.is_some() {
+            // A notification is already scheduled and will include this change
+            return Ok(());
         }
-        // Spawn a new debounce task
-        let handle = tokio::spawn(async move {
-            sleep(Duration::from_secs(2)).await;
+
+        let deadline = tokio::time::Instant::now() + self.service_update_debounce();
+        let this = self.clone();
+        let scheduled = debounce_task.clone();
+        *guard = Some(tokio::spawn(async move {
+            tokio::time::sleep_until(deadline).await;
+            // Changes from now on schedule the next notification
+            scheduled.lock().await.take();
             // Ignore errors from notify_node_change_impl; log if needed
             if let Err(e) = this.notify_node_change_impl().await {
                 this.logger.warn(format!(
                     "notify_node_change_impl failed after debounce: {e}"
                 ));
             }
-        });
-        // Store the new handle
-        {
-            let mut guard = debounce_task.lock().await;
-            *guard = Some(handle);
-        }
+        }));
         Ok(())
     }
 
+    /// Delay before local changes are sent to peers
+    fn service_update_debounce(&self) -> Duration {
+        let debounce_ms = self
+            .config
+            .network_config
+            .as_ref()
+            .and_then(|network_config| network_config.quic_options.as_ref())
+            .map_or(DEFAULT_SERVICE_UPDATE_DEBOUNCE_MS, |options| {
+                options.service_update_debounce_ms()
+            });
+        Duration::from_millis(debounce_ms)
+    }
+
+    /// Send the latest node info to all known peers
+    ///
+    /// INTENTION: The first notification sends the full node info; later ones send only the
+    /// services that changed since the previous notification, as a `NodeInfoDiff`. The
+    /// transport records the node info before sending it, so the next diff is based on it
+    /// even when sending to some peers failed; a peer that cannot apply a diff asks for the
+    /// full node info instead.
     pub async fn notify_node_change_impl(&self) -> Result<()> {
         let local_node_info = self.get_local_node_info().await?;
 
@@ -1579,18 +4918,43 @@ impl Node {
         ));
 
         let transport_guard = self.network_transport.read().await;
-        if let Some(transport) = transport_guard.as_ref() {
-            transport.update_peers(local_node_info).await?;
-            Ok(())
-        } else {
-            Err(anyhow!("No transport available"))
+        let Some(transport) = transport_guard.as_ref() else {
+            return Err(anyhow!("No transport available"));
+        };
+        let mut last_broadcast = self.last_broadcast_node_info.lock().await;
+        match last_broadcast.as_ref() {
+            Some(last) => {
+                let diff = last.diff(&local_node_info);
+                if diff.is_empty() && diff.base_version == diff.version {
+                    // Peers already have this node info
+                    return Ok(());
+                }
+                self.logger.debug(format!(
+                    "Sending service diff {base} -> {version}: {added} added, {removed} removed",
+                    base = diff.base_version,
+                    version = diff.version,
+                    added = diff.added_services.len(),
+                    removed = diff.removed_services.len()
+                ));
+                let result = transport.broadcast_service_diff(diff).await;
+                *last_broadcast = Some(local_node_info);
+                result?;
+            }
+            None => {
+                let result = transport.update_peers(local_node_info.clone()).await;
+                *last_broadcast = Some(local_node_info);
+                result?;
+            }
         }
+        Ok(())
     }
 
     /// Collect capabilities of all local services
     ///
     /// INTENTION: Gather capability information from all local services.
-    /// This includes service metadata and all registered actions.
+    /// This includes service metadata and all registered actions. Services
+    /// that are not public are left out; the visibility of the others is kept
+    /// so the transport only advertises them to the peers allowed to see them.
     ///
     pub async fn collect_local_service_capabilities(&self) -> Result<Vec<ServiceMetadata>> {
         // Get all local services
@@ -1613,12 +4977,18 @@ impl Node {
             }
 
             // Get the service actions from registry
-            if let Some(meta) = self
+            if let Some(mut meta) = self
                 .service_registry
                 .get_service_metadata(&service_path)
                 .await
             {
-                services.push(meta);
+                if let Some(visibility) = self.config.capability_advertisement.get(service.path()) {
+                    meta.visibility = visibility.clone();
+                }
+                // Services that are not public are never advertised
+                if meta.visibility.public {
+                    services.push(meta);
+                }
             }
         }
 
@@ -1657,6 +5027,116 @@ impl Node {
         Ok(String::new())
     }
 
+    /// Get a snapshot of the network transport statistics
+    ///
+    /// INTENTION: Expose connection, traffic and error counters for monitoring.
+    /// Returns None when networking is disabled or the transport has not started.
+    pub async fn transport_stats(&self) -> Option<TransportStats> {
+        let transport_guard = self.network_transport.read().await;
+        match transport_guard.as_ref() {
+            Some(transport) => Some(transport.transport_stats().await),
+            None => None,
+        }
+    }
+
+    /// Move the network connections to the current local address
+    ///
+    /// INTENTION: Let applications react to OS network change notifications
+    /// (e.g. Wi-Fi to ethernet) without waiting for the transport's own
+    /// address polling, see `QuicTransportOptions::with_connection_migration`.
+    /// Does nothing when networking is disabled or the transport has not started.
+    pub async fn migrate_connections(&self) -> Result<()> {
+        let transport_guard = self.network_transport.read().await;
+        if let Some(transport) = transport_guard.as_ref() {
+            transport
+                .migrate_connections()
+                .await
+                .map_err(|e| anyhow!("Failed to migrate connections: {e}"))?;
+        }
+        Ok(())
+    }
+
+    /// Replace the secret key used to authenticate messages between nodes
+    ///
+    /// INTENTION: Allow rotating the key without downtime. Messages are
+    /// tagged with the new key right away; the old key is still accepted for
+    /// the request timeout, so in-flight requests complete and the other
+    /// nodes can be rotated in the meantime. Fails when the node was not
+    /// configured with `NodeConfig::with_request_auth`.
+    pub fn rotate_auth_key(&self, new_key: Vec<u8>) -> Result<()> {
+        let authenticator = self
+            .request_authenticator
+            .as_ref()
+            .ok_or_else(|| anyhow!("Request authentication is not configured"))?;
+        authenticator.rotate_key(
+            &new_key,
+            Duration::from_millis(self.config.request_timeout_ms),
+        );
+        self.logger.info("Rotated the request authentication key");
+        Ok(())
+    }
+
+    /// Replace the peers this node connects with
+    ///
+    /// INTENTION: Change the connection policy without restarting the node,
+    /// see `NodeConfig::with_peer_whitelist`. Connected peers left out of
+    /// the whitelist are disconnected.
+    pub async fn update_peer_whitelist(&self, whitelist: HashSet<PeerId>) -> Result<()> {
+        self.peer_filter.set_whitelist(Some(whitelist));
+        self.disconnect_rejected_peers().await
+    }
+
+    /// Replace the peers this node never connects with
+    ///
+    /// Connected peers on the new blacklist are disconnected.
+    pub async fn update_peer_blacklist(&self, blacklist: HashSet<PeerId>) -> Result<()> {
+        self.peer_filter.set_blacklist(Some(blacklist));
+        self.disconnect_rejected_peers().await
+    }
+
+    /// Disconnect and forget the recorded peers the filter now rejects
+    async fn disconnect_rejected_peers(&self) -> Result<()> {
+        let rejected: Vec<PeerId> = self
+            .peer_registry
+            .get_all_peers()
+            .into_iter()
+            .map(|entry| PeerId::new(entry.peer_info.public_key))
+            .filter(|peer_id| !self.peer_filter.allows(peer_id))
+            .collect();
+
+        for peer_id in rejected {
+            self.logger.info(format!(
+                "Disconnecting peer {peer_id} rejected by the peer filter"
+            ));
+            if let Some(transport) = self.network_transport.read().await.as_ref() {
+                if let Err(e) = transport.disconnect(peer_id.clone()).await {
+                    self.logger
+                        .warn(format!("Failed to disconnect peer {peer_id}: {e}"));
+                }
+            }
+            self.peer_registry.remove_peer(&peer_id)?;
+        }
+        Ok(())
+    }
+
+    /// Export the state of the node key manager
+    ///
+    /// INTENTION: Let the host persist keys created or replaced while the node
+    /// runs, such as a rotated database key, and pass them back with
+    /// `NodeConfig::with_key_manager_state` on the next start.
+    pub async fn key_manager_state(&self) -> Result<Vec<u8>> {
+        let state = self.keys_manager.read().await.export_state();
+        bincode::serialize(&state).context("Failed to serialize node keys state")
+    }
+
+    /// Get the registry of peers this node is connected to
+    ///
+    /// INTENTION: Let callers inspect connected peers or subscribe to peers
+    /// joining and leaving.
+    pub fn peer_registry(&self) -> Arc<PeerRegistry> {
+        self.peer_registry.clone()
+    }
+
     /// Get information about the local node
     ///
     /// INTENTION: Create a complete NodeInfo structure for this node,
@@ -1679,12 +5159,24 @@ impl Node {
             }
         }
 
+        let mut addresses = vec![address];
+        #[cfg(unix)]
+        if let Some(socket_path) = self
+            .config
+            .network_config
+            .as_ref()
+            .and_then(|config| config.transport_options.local_socket_path.as_ref())
+        {
+            addresses.push(UnixSocketTransport::address_for_path(socket_path));
+        }
+
         let node_info = NodeInfo {
             peer_id: self.peer_id.clone(),
             network_ids: self.network_ids.clone(),
-            addresses: vec![address],
+            addresses,
             services: self.collect_local_service_capabilities().await?,
             version: self.registry_version.load(Ordering::SeqCst),
+            subscriptions: self.service_registry.get_local_subscription_topics().await,
         };
 
         Ok(node_info)
@@ -1693,7 +5185,6 @@ impl Node {
     /// Get a non-loopback IP address from the local network interfaces
     fn get_non_loopback_ip(&self) -> Result<String> {
         use socket2::{Domain, Socket, Type};
-        use std::net::SocketAddr;
 
         // Create a UDP socket
         let socket = Socket::new(Domain::IPV4, Type::DGRAM, None)?;
@@ -1750,20 +5241,67 @@ impl Node {
 
     /// Set up a listener for peer node info updates from the transport
     ///
-    /// INTENTION: Subscribe to peer node info updates from the transport and process them
-    /// by creating RemoteService instances for each capability.
+    /// INTENTION: Record the node info peers send in their handshake in the
+    /// peer registry, and manage RemoteService instances by reacting to the
+    /// registry's change events: services are added when a peer connects and
+    /// removed when it leaves.
     async fn setup_peer_node_info_listener(&self) -> Result<()> {
         // Get the transport
         let transport = self.network_transport.read().await;
         if let Some(transport) = transport.as_ref() {
-            // Subscribe to peer node info updates directly using the Transport trait
+            // Subscribe to registry changes before feeding the registry
+            let mut peer_events = self.peer_registry.subscribe_to_changes();
             let mut receiver = transport.subscribe_to_peer_node_info().await;
 
-            // Clone what we need for the task
+            // Clone what we need for the tasks
             let node = self.clone();
             let logger = self.logger.clone();
 
-            // Spawn a task to listen for peer node info updates
+            // Spawn a task reacting to peers joining or leaving
+            tokio::spawn(async move {
+                logger.info("Started peer event listener");
+
+                loop {
+                    match peer_events.recv().await {
+                        Ok(PeerEvent::Connected(entry)) => {
+                            let Some(peer_node_info) = entry.node_info else {
+                                continue;
+                            };
+                            if let Err(e) = node.process_remote_capabilities(peer_node_info).await {
+                                logger.error(format!("Failed to process remote capabilities: {e}"));
+                            }
+                        }
+                        Ok(PeerEvent::Disconnected(peer_id)) => {
+                            logger.info(format!("Peer {peer_id} left, removing its services"));
+                            if let Err(e) = node.remove_known_peer(&peer_id).await {
+                                logger.error(format!("Failed to remove peer services: {e}"));
+                            }
+                        }
+                        Ok(PeerEvent::StatusChanged { .. }) => {}
+                        Err(tokio::sync::broadcast::error::RecvError::Closed) => {
+                            logger.info("Peer registry channel closed");
+                            break;
+                        }
+                        Err(tokio::sync::broadcast::error::RecvError::Lagged(skipped)) => {
+                            logger.warn(format!(
+                                "Peer event receiver lagged, skipped {skipped} events, resyncing peers",
+                            ));
+                            // The skipped joins and leaves are lost, so catch up
+                            // from the registry's current state
+                            if let Err(e) = node.resync_known_peers().await {
+                                logger.error(format!("Failed to resync peers: {e}"));
+                            }
+                        }
+                    }
+                }
+
+                logger.info("Peer event listener stopped");
+            });
+
+            // Spawn a task recording peer node info in the registry
+            let peer_registry = self.peer_registry.clone();
+            let network_transport = self.network_transport.clone();
+            let logger = self.logger.clone();
             tokio::spawn(async move {
                 logger.info("Started peer node info listener");
 
@@ -1776,9 +5314,17 @@ impl Node {
                                 peer_id = peer_node_info.peer_id
                             ));
 
-                            // Process the peer node info
-                            if let Err(e) = node.process_remote_capabilities(peer_node_info).await {
-                                logger.error(format!("Failed to process remote capabilities: {e}"));
+                            // Negotiated before the node info was published
+                            let capabilities = match network_transport.read().await.as_ref() {
+                                Some(transport) => {
+                                    transport.peer_capabilities(&peer_node_info.peer_id).await
+                                }
+                                None => None,
+                            };
+                            if let Err(e) = peer_registry
+                                .add_peer_node_info_with_capabilities(peer_node_info, capabilities)
+                            {
+                                logger.error(format!("Failed to record peer node info: {e}"));
                             }
                         }
                         Err(tokio::sync::broadcast::error::RecvError::Closed) => {
@@ -1831,6 +5377,11 @@ impl NodeDelegate for Node {
             guaranteed_delivery: false,
             retention_seconds: None,
             target: None,
+            stream_channel_size: None,
+            ttl: None,
+            ordered: false,
+            retain_last: None,
+            dispatch: DispatchMode::default(),
         };
 
         self.publish_with_options(topic, data, options).await
@@ -1886,6 +5437,28 @@ impl NodeDelegate for Node {
             .service_registry
             .register_local_event_subscription(&topic_path, callback.into(), Some(event_metadata))
             .await?;
+        if !options.dead_letter {
+            self.dead_letters
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .exclude_subscription(subscription_id.clone());
+        }
+        if let Some(group) = options.group.filter(|group| !group.is_empty()) {
+            let pending = self
+                .subscription_groups
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .join(&topic_path, &group, subscription_id.clone());
+            if !pending.is_empty() {
+                // Hand over in the background: the subscriber may still be initializing
+                let node = self.clone();
+                let subscription_id = subscription_id.clone();
+                tokio::spawn(async move {
+                    node.deliver_pending_group_events(&subscription_id, pending)
+                        .await;
+                });
+            }
+        }
 
         if self.running.load(Ordering::SeqCst) {
             self.registry_version.fetch_add(1, Ordering::SeqCst);
@@ -1903,6 +5476,15 @@ impl NodeDelegate for Node {
             let registry = self.service_registry.clone();
             match registry.unsubscribe_local(id).await {
                 Ok(_) => {
+                    self.dead_letters
+                        .lock()
+                        .unwrap_or_else(|e| e.into_inner())
+                        .remove_subscription(id);
+                    self.subscription_groups
+                        .lock()
+                        .unwrap_or_else(|e| e.into_inner())
+                        .leave(id);
+                    self.event_dispatcher.remove(id);
                     self.logger.debug(format!(
                         "Successfully unsubscribed locally from  with id {id}"
                     ));
@@ -1948,6 +5530,12 @@ impl KeysDelegate for Node {
         let key = keys_manager.ensure_symmetric_key(key_name)?;
         Ok(ArcValue::new_bytes(key))
     }
+
+    async fn store_symmetric_key(&self, key_name: &str, key: Vec<u8>) -> Result<()> {
+        let mut keys_manager = self.keys_manager.write().await;
+        keys_manager.set_symmetric_key(key_name, key);
+        Ok(())
+    }
 }
 
 #[async_trait]
@@ -2004,6 +5592,181 @@ impl RegistryDelegate for Node {
             .remove_remote_action_handler(topic_path)
             .await
     }
+
+    /// Get the health of every node of the cluster known to this node
+    async fn cluster_health(&self) -> HashMap<PeerId, HealthReport> {
+        Node::cluster_health(self).await
+    }
+}
+
+/// Default capacity of the channel backing a subscription stream
+const DEFAULT_STREAM_CHANNEL_SIZE: usize = 64;
+
+/// Typed event stream returned by Node::subscribe_stream
+///
+/// INTENTION: Deliver events received by a subscription callback through a bounded
+/// channel, and cancel the subscription when the consumer drops the stream.
+struct SubscriptionStream<T> {
+    receiver: mpsc::Receiver<Result<T>>,
+    subscription_id: String,
+    node: Node,
+}
+
+impl<T> Stream for SubscriptionStream<T> {
+    type Item = Result<T>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut TaskContext<'_>) -> Poll<Option<Self::Item>> {
+        self.receiver.poll_recv(cx)
+    }
+}
+
+impl<T> Drop for SubscriptionStream<T> {
+    fn drop(&mut self) {
+        // Unsubscribing is async, so hand it off to the runtime if one is available
+        if let Ok(handle) = tokio::runtime::Handle::try_current() {
+            let node = self.node.clone();
+            let subscription_id = self.subscription_id.clone();
+            handle.spawn(async move {
+                if let Err(e) = node.unsubscribe(Some(&subscription_id)).await {
+                    node.logger.warn(format!(
+                        "Failed to cancel stream subscription {subscription_id}: {e}"
+                    ));
+                }
+            });
+        }
+    }
+}
+
+/// Frame of a streamed response received from a remote node
+enum StreamFrame {
+    Item(ArcValue),
+    End(Option<NetworkError>),
+}
+
+/// Deserialize a payload with the backend its sender named, or our own
+fn deserialize_payload(
+    serializer: &SerializerRegistry,
+    payload: NetworkMessagePayloadItem,
+) -> Result<ArcValue> {
+    let backend = payload.format.unwrap_or_else(|| serializer.backend());
+    serializer.deserialize_value_with_backend(Arc::from(payload.value_bytes), backend)
+}
+
+/// Event queued for a local subscriber by `publish_with_options`
+pub(crate) struct QueuedEvent {
+    topic_path: TopicPath,
+    callback: crate::services::service_registry::EventCallback,
+    event_context: Arc<EventContext>,
+    data: Option<ArcValue>,
+}
+
+/// Counts a request as in flight until dropped
+struct InFlightRequest(Arc<AtomicUsize>);
+
+impl InFlightRequest {
+    fn new(counter: &Arc<AtomicUsize>) -> Self {
+        counter.fetch_add(1, Ordering::SeqCst);
+        Self(counter.clone())
+    }
+}
+
+impl Drop for InFlightRequest {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Streamed response being reassembled for a remote request
+///
+/// INTENTION: Frames are handled on separate tasks and can arrive out of
+/// order, so they are buffered by sequence number and passed on strictly in
+/// order to the task feeding the requester's stream. At most
+/// `MAX_BUFFERED_STREAM_FRAMES` frames are held, out of order or waiting for
+/// the consumer; past that the stream fails.
+struct PendingStream {
+    /// Frames in sequence order, None once the stream is finished
+    frames: Option<tokio::sync::mpsc::Sender<StreamFrame>>,
+    next_sequence: u64,
+    buffered: BTreeMap<u64, StreamFrame>,
+    last_frame: std::time::Instant,
+}
+
+impl PendingStream {
+    /// Buffer a frame and pass on every frame that is next in sequence
+    ///
+    /// Returns true once the stream is finished: the end frame was passed
+    /// on, the consumer dropped the stream, or too many frames are buffered.
+    fn add(&mut self, sequence: u64, frame: StreamFrame) -> bool {
+        self.last_frame = std::time::Instant::now();
+        if self.frames.is_none() || sequence < self.next_sequence {
+            return self.frames.is_none();
+        }
+        if sequence - self.next_sequence >= MAX_BUFFERED_STREAM_FRAMES as u64 {
+            self.fail(Self::overflow());
+            return true;
+        }
+        self.buffered.insert(sequence, frame);
+
+        while let Some(frame) = self.buffered.remove(&self.next_sequence) {
+            self.next_sequence += 1;
+            let Some(frames) = self.frames.as_ref() else {
+                return true;
+            };
+            let end = matches!(frame, StreamFrame::End(_));
+            // One slot stays free for the frame failing the stream
+            if !end && frames.capacity() <= 1 {
+                if frames.is_closed() {
+                    self.frames = None;
+                } else {
+                    self.fail(Self::overflow());
+                }
+                return true;
+            }
+            if frames.try_send(frame).is_err() || end {
+                // The consumer dropped the stream, or it is complete
+                self.frames = None;
+                return true;
+            }
+        }
+        false
+    }
+
+    /// End the stream with an error after the frames already passed on
+    fn fail(&mut self, error: NetworkError) {
+        self.buffered.clear();
+        if let Some(frames) = self.frames.take() {
+            let _ = frames.try_send(StreamFrame::End(Some(error)));
+        }
+    }
+
+    fn overflow() -> NetworkError {
+        NetworkError::MessageError(
+            ErrorCode::RateLimited,
+            format!("More than {MAX_BUFFERED_STREAM_FRAMES} response stream frames buffered"),
+        )
+    }
+}
+
+/// Feed the frames of a remote stream to the requester's stream, in order
+async fn forward_stream_frames(
+    mut frames: tokio::sync::mpsc::Receiver<StreamFrame>,
+    sender: ResponseStreamSender<ArcValue>,
+) {
+    while let Some(frame) = frames.recv().await {
+        match frame {
+            StreamFrame::Item(value) => {
+                if !sender.send(value).await {
+                    return;
+                }
+            }
+            StreamFrame::End(error) => {
+                if let Some(error) = error {
+                    sender.fail(error.into()).await;
+                }
+                return;
+            }
+        }
+    }
 }
 
 // Implement Clone for Node
@@ -2014,19 +5777,41 @@ impl Clone for Node {
     fn clone(&self) -> Self {
         Self {
             debounce_notify_task: std::sync::Arc::new(tokio::sync::Mutex::new(None)),
+            last_broadcast_node_info: self.last_broadcast_node_info.clone(),
             network_id: self.network_id.clone(),
             network_ids: self.network_ids.clone(),
             peer_id: self.peer_id.clone(),
             config: self.config.clone(),
             service_registry: self.service_registry.clone(),
             known_peers: self.known_peers.clone(),
+            peer_registry: self.peer_registry.clone(),
+            peer_filter: self.peer_filter.clone(),
+            request_authenticator: self.request_authenticator.clone(),
             logger: self.logger.clone(),
             running: AtomicBool::new(self.running.load(Ordering::SeqCst)),
+            stopped: self.stopped.clone(),
+            in_flight_requests: self.in_flight_requests.clone(),
             supports_networking: self.supports_networking,
             network_transport: self.network_transport.clone(),
             network_discovery_providers: self.network_discovery_providers.clone(),
             load_balancer: self.load_balancer.clone(),
+            circuit_breakers: self.circuit_breakers.clone(),
+            retry_budgets: self.retry_budgets.clone(),
+            middleware: self.middleware.clone(),
+            access_policies: self.access_policies.clone(),
+            event_dedup: self.event_dedup.clone(),
+            dead_letters: self.dead_letters.clone(),
+            subscription_groups: self.subscription_groups.clone(),
+            event_dispatcher: self.event_dispatcher.clone(),
+            background_tasks: self.background_tasks.clone(),
+            service_restarts: self.service_restarts.clone(),
+            telemetry: self.telemetry.clone(),
+            health_gossip: self.health_gossip.clone(),
+            event_replay: self.event_replay.clone(),
+            event_sequences: self.event_sequences.clone(),
+            ordered_events: self.ordered_events.clone(),
             pending_requests: self.pending_requests.clone(),
+            pending_streams: self.pending_streams.clone(),
             serializer: self.serializer.clone(),
             registry_version: self.registry_version.clone(),
             keys_manager: self.keys_manager.clone(),
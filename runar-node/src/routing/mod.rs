@@ -279,6 +279,25 @@ impl TopicPath {
         TopicPath::new(&full_path_string, &self.network_id)
     }
 
+    /// Creates the path of an action in the same network as `base`
+    ///
+    /// INTENTION: Let services address their own or a sibling service's
+    /// actions without embedding a network ID, so they can be deployed to
+    /// any network unchanged.
+    ///
+    /// Example:
+    /// ```
+    /// use runar_node::TopicPath;
+    /// let base = TopicPath::new("main:auth/login", "default").expect("Valid path");
+    /// let path = TopicPath::relative("users", "get", &base).expect("Valid action path");
+    ///
+    /// assert_eq!(path.as_str(), "main:users/get");
+    /// ```
+    pub fn relative(service_path: &str, action: &str, base: &TopicPath) -> Result<Self, String> {
+        let full_path_string = format!("{}:{}/{}", base.network_id, service_path, action);
+        TopicPath::new(&full_path_string, &base.network_id)
+    }
+
     /// Creates a new TopicPath for an event based on this service path
     ///
     /// INTENTION: Provide a simple way to create an event path from a service path,
@@ -301,6 +320,84 @@ impl TopicPath {
         Ok(path)
     }
 
+    /// Get the API version of this path
+    ///
+    /// INTENTION: Recognize versioned action paths, where the segment after
+    /// the service is `v` followed by the version number. Paths without an
+    /// action after the version segment are not versioned.
+    ///
+    /// Example:
+    /// ```
+    /// use runar_node::routing::TopicPath;
+    ///
+    /// let path = TopicPath::new("main:auth/v2/login", "default").expect("Valid path");
+    /// assert_eq!(path.version(), Some(2));
+    ///
+    /// let path = TopicPath::new("main:auth/login", "default").expect("Valid path");
+    /// assert_eq!(path.version(), None);
+    /// ```
+    pub fn version(&self) -> Option<u8> {
+        if self.segments.len() < 3 {
+            return None;
+        }
+        match &self.segments[1] {
+            PathSegment::Literal(segment) => segment
+                .strip_prefix('v')
+                .filter(|digits| digits.bytes().all(|b| b.is_ascii_digit()))
+                .and_then(|digits| digits.parse().ok()),
+            _ => None,
+        }
+    }
+
+    /// Create the path of the given API version of this action
+    ///
+    /// INTENTION: Address a specific version of an evolving service API. The
+    /// version segment is inserted after the service, replacing the version
+    /// the path already has. Paths without an action are returned unchanged.
+    ///
+    /// Example:
+    /// ```
+    /// use runar_node::routing::TopicPath;
+    ///
+    /// let path = TopicPath::new("main:auth/login", "default").expect("Valid path");
+    /// assert_eq!(path.with_version(2).as_str(), "main:auth/v2/login");
+    /// assert_eq!(path.with_version(2).with_version(3).as_str(), "main:auth/v3/login");
+    /// ```
+    pub fn with_version(&self, version: u8) -> TopicPath {
+        if self.segments.len() < 2 {
+            return self.clone();
+        }
+        let mut segments = self.without_version().get_segments();
+        segments.insert(1, format!("v{version}"));
+        TopicPath::new(
+            &format!("{}:{}", self.network_id, segments.join("/")),
+            &self.network_id,
+        )
+        .expect("inserting a version segment keeps the path valid")
+    }
+
+    /// Create the path of this action without its API version
+    ///
+    /// Example:
+    /// ```
+    /// use runar_node::routing::TopicPath;
+    ///
+    /// let path = TopicPath::new("main:auth/v2/login", "default").expect("Valid path");
+    /// assert_eq!(path.without_version().as_str(), "main:auth/login");
+    /// ```
+    pub fn without_version(&self) -> TopicPath {
+        if self.version().is_none() {
+            return self.clone();
+        }
+        let mut segments = self.get_segments();
+        segments.remove(1);
+        TopicPath::new(
+            &format!("{}:{}", self.network_id, segments.join("/")),
+            &self.network_id,
+        )
+        .expect("removing the version segment keeps the path valid")
+    }
+
     /// Create a new TopicPath from a string
     ///
     /// INTENTION: Validate and construct a TopicPath from a string input,
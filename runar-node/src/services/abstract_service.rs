@@ -12,9 +12,14 @@
 //! 5. Asynchronous Operations - All service methods are async for performance
 
 use anyhow::Result;
+use runar_common::types::ServiceVisibility;
 use serde::{Deserialize, Serialize};
 use std::fmt;
+use std::sync::Arc;
+use tokio::sync::RwLock;
 
+use crate::services::access_policy::ServiceAccessPolicy;
+use crate::services::service_restart::ServiceRestartPolicy;
 use crate::services::LifecycleContext;
 
 /// Represents a service's current state
@@ -35,6 +40,8 @@ pub enum ServiceState {
     Paused,
     /// Service has encountered an error
     Error,
+    /// Service panicked more often than its restart policy allows
+    Unhealthy,
     /// Service state is unknown
     Unknown,
 }
@@ -48,6 +55,7 @@ impl fmt::Display for ServiceState {
             ServiceState::Stopped => write!(f, "Stopped"),
             ServiceState::Paused => write!(f, "Paused"),
             ServiceState::Error => write!(f, "Error"),
+            ServiceState::Unhealthy => write!(f, "Unhealthy"),
             ServiceState::Unknown => write!(f, "Unknown"),
         }
     }
@@ -82,6 +90,29 @@ pub trait AbstractService: Send + Sync {
     /// Set service network id
     fn set_network_id(&mut self, network_id: String);
 
+    /// Get the callers allowed to invoke the service's actions
+    ///
+    /// Read when the service is added; `Node::update_service_policy` replaces
+    /// it afterwards.
+    fn access_policy(&self) -> ServiceAccessPolicy {
+        ServiceAccessPolicy::allow_all()
+    }
+
+    /// Get which peers the service is advertised to
+    ///
+    /// Read whenever the node info is built; `NodeConfig::with_capability_advertisement`
+    /// overrides it.
+    fn visibility(&self) -> ServiceVisibility {
+        ServiceVisibility::public()
+    }
+
+    /// Get how the service is restarted after one of its actions panicked
+    ///
+    /// Read each time an action panics.
+    fn restart_policy(&self) -> ServiceRestartPolicy {
+        ServiceRestartPolicy::default()
+    }
+
     /// Initialize the service
     ///
     /// INTENTION: Set up the service for operation, register handlers,
@@ -111,3 +142,201 @@ pub trait AbstractService: Send + Sync {
     /// can be cleanly shut down without data loss or corruption.
     async fn stop(&self, context: LifecycleContext) -> Result<()>;
 }
+
+/// A service instance shared by the handlers of a [`MutableService`]
+pub type SharedService<S> = Arc<RwLock<S>>;
+
+tokio::task_local! {
+    /// Addresses of the shared services whose lock the current task holds
+    static HELD_SERVICE_LOCKS: Vec<usize>;
+}
+
+/// Run a handler of a shared service, recording that the task holds its lock
+///
+/// INTENTION: A handler holds the service lock for its whole body, including
+/// the requests it makes. A request that reaches the same service again would
+/// wait forever on that lock, so it fails right away instead. The generated
+/// handlers of `#[service(mutable = true)]` services run through this.
+pub async fn run_locked_handler<S, T, F>(service: &SharedService<S>, handler: F) -> Result<T>
+where
+    F: std::future::Future<Output = Result<T>>,
+{
+    let address = Arc::as_ptr(service) as *const () as usize;
+    let mut held = HELD_SERVICE_LOCKS
+        .try_with(Clone::clone)
+        .unwrap_or_default();
+    if held.contains(&address) {
+        return Err(anyhow::anyhow!(
+            "Handler of {} called back into its own service while holding the service lock",
+            std::any::type_name::<S>()
+        ));
+    }
+    held.push(address);
+    HELD_SERVICE_LOCKS.scope(held, handler).await
+}
+
+/// Carry the service locks the current task holds over to a spawned handler
+///
+/// Local requests run their handler in a task of its own, which must still
+/// see the locks held by the handler that made the request.
+pub(crate) fn with_held_service_locks<F: std::future::Future>(
+    future: F,
+) -> impl std::future::Future<Output = F::Output> {
+    let held = HELD_SERVICE_LOCKS
+        .try_with(Clone::clone)
+        .unwrap_or_default();
+    HELD_SERVICE_LOCKS.scope(held, future)
+}
+
+/// Service whose handlers share one instance behind a lock
+///
+/// INTENTION: Let actions take `&mut self` instead of keeping their state in
+/// `Arc<Mutex<...>>` fields. The handlers hold the service through a
+/// [`SharedService`]: actions taking `&mut self` acquire the write lock and
+/// the others the read lock, so a mutable action runs alone while no other
+/// handler of the service runs, and concurrent calls to it are serialized.
+///
+/// This is implemented by `#[service_impl]` when an action is marked
+/// `#[action(mutable = true)]`; the service is then added to the node wrapped
+/// in a [`RwLockService`].
+#[async_trait::async_trait]
+pub trait MutableService: Send + Sync + Sized + 'static {
+    /// Get service name
+    fn name(&self) -> &str;
+
+    /// Get service version
+    fn version(&self) -> &str;
+
+    /// Get service path
+    fn path(&self) -> &str;
+
+    /// Get service description
+    fn description(&self) -> &str;
+
+    /// Get service network id
+    fn network_id(&self) -> Option<String>;
+
+    /// Set service network id
+    fn set_network_id(&mut self, network_id: String);
+
+    /// Get the callers allowed to invoke the service's actions
+    fn access_policy(&self) -> ServiceAccessPolicy {
+        ServiceAccessPolicy::allow_all()
+    }
+
+    /// Get which peers the service is advertised to
+    fn visibility(&self) -> ServiceVisibility {
+        ServiceVisibility::public()
+    }
+
+    /// Get how the service is restarted after one of its actions panicked
+    fn restart_policy(&self) -> ServiceRestartPolicy {
+        ServiceRestartPolicy::default()
+    }
+
+    /// Initialize the service, registering handlers that share `service`
+    async fn init(service: SharedService<Self>, context: LifecycleContext) -> Result<()>;
+
+    /// Start the service
+    async fn start(_service: SharedService<Self>, _context: LifecycleContext) -> Result<()> {
+        Ok(())
+    }
+
+    /// Stop the service
+    async fn stop(_service: SharedService<Self>, _context: LifecycleContext) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Adapter running a [`MutableService`] as an [`AbstractService`]
+///
+/// The metadata is copied when the adapter is created, so it can be read
+/// without taking the lock.
+pub struct RwLockService<S: MutableService> {
+    name: String,
+    version: String,
+    path: String,
+    description: String,
+    network_id: Option<String>,
+    access_policy: ServiceAccessPolicy,
+    visibility: ServiceVisibility,
+    restart_policy: ServiceRestartPolicy,
+    service: SharedService<S>,
+}
+
+impl<S: MutableService> RwLockService<S> {
+    /// Wrap `service` in the lock shared by its handlers
+    pub fn new(service: S) -> Self {
+        Self {
+            name: service.name().to_string(),
+            version: service.version().to_string(),
+            path: service.path().to_string(),
+            description: service.description().to_string(),
+            network_id: service.network_id(),
+            access_policy: service.access_policy(),
+            visibility: service.visibility(),
+            restart_policy: service.restart_policy(),
+            service: Arc::new(RwLock::new(service)),
+        }
+    }
+
+    /// The instance shared by the handlers
+    pub fn shared(&self) -> SharedService<S> {
+        self.service.clone()
+    }
+}
+
+#[async_trait::async_trait]
+impl<S: MutableService> AbstractService for RwLockService<S> {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn version(&self) -> &str {
+        &self.version
+    }
+
+    fn path(&self) -> &str {
+        &self.path
+    }
+
+    fn description(&self) -> &str {
+        &self.description
+    }
+
+    fn network_id(&self) -> Option<String> {
+        self.network_id.clone()
+    }
+
+    fn set_network_id(&mut self, network_id: String) {
+        // Called before the service is registered, while nothing else holds the lock
+        if let Ok(mut service) = self.service.try_write() {
+            service.set_network_id(network_id.clone());
+        }
+        self.network_id = Some(network_id);
+    }
+
+    fn access_policy(&self) -> ServiceAccessPolicy {
+        self.access_policy.clone()
+    }
+
+    fn visibility(&self) -> ServiceVisibility {
+        self.visibility.clone()
+    }
+
+    fn restart_policy(&self) -> ServiceRestartPolicy {
+        self.restart_policy
+    }
+
+    async fn init(&self, context: LifecycleContext) -> Result<()> {
+        S::init(self.service.clone(), context).await
+    }
+
+    async fn start(&self, context: LifecycleContext) -> Result<()> {
+        S::start(self.service.clone(), context).await
+    }
+
+    async fn stop(&self, context: LifecycleContext) -> Result<()> {
+        S::stop(self.service.clone(), context).await
+    }
+}
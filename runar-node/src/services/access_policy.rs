@@ -0,0 +1,106 @@
+// Service Access Policies
+//
+// This module defines which callers may reach the actions of a service. The
+// Node checks the policy of the target service before every local action
+// handler runs, after identifying the caller as a remote peer or as the local
+// service whose action made the request.
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::network::transport::PeerId;
+
+tokio::task_local! {
+    /// Path of the service whose action handler is running on this task
+    pub(crate) static CALLING_SERVICE: String;
+}
+
+/// Callers allowed to invoke the actions of a service
+///
+/// INTENTION: Keep internal services reachable only from trusted peers or
+/// from this node. Requests that the policy denies fail with "access denied"
+/// and the action is not called.
+///
+/// Remote requests must pass `allow_remote` and, when set, `allowed_peers`.
+/// Local requests pass unless `allowed_local_paths` is set, in which case they
+/// must be made from an action of one of the listed services; requests made
+/// outside any action handler, such as `Node::request` from the application,
+/// are then denied too.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ServiceAccessPolicy {
+    /// Whether peers may call the service at all
+    pub allow_remote: bool,
+    /// Peers allowed to call the service, all of them when None
+    pub allowed_peers: Option<Vec<PeerId>>,
+    /// Local services allowed to call the service, all callers when None
+    pub allowed_local_paths: Option<Vec<String>>,
+}
+
+impl Default for ServiceAccessPolicy {
+    fn default() -> Self {
+        Self::allow_all()
+    }
+}
+
+impl ServiceAccessPolicy {
+    /// Policy letting every caller through
+    pub fn allow_all() -> Self {
+        Self {
+            allow_remote: true,
+            allowed_peers: None,
+            allowed_local_paths: None,
+        }
+    }
+
+    /// Policy rejecting all remote callers
+    pub fn local_only() -> Self {
+        Self {
+            allow_remote: false,
+            ..Self::allow_all()
+        }
+    }
+
+    /// Policy letting only the given peers call remotely
+    pub fn peers(allowed_peers: Vec<PeerId>) -> Self {
+        Self {
+            allowed_peers: Some(allowed_peers),
+            ..Self::allow_all()
+        }
+    }
+
+    /// Restrict local callers to the actions of the given services
+    pub fn with_allowed_local_paths(mut self, allowed_local_paths: Vec<String>) -> Self {
+        self.allowed_local_paths = Some(allowed_local_paths);
+        self
+    }
+
+    /// Check a caller against the policy
+    ///
+    /// `peer` is the remote peer making the request, or None for a local
+    /// request, in which case `calling_service` is the path of the service
+    /// whose action made it, if any.
+    pub fn check(&self, peer: Option<&PeerId>, calling_service: Option<&str>) -> Result<()> {
+        let allowed = match peer {
+            Some(peer) => {
+                self.allow_remote
+                    && self
+                        .allowed_peers
+                        .as_ref()
+                        .is_none_or(|peers| peers.contains(peer))
+            }
+            None => self.allowed_local_paths.as_ref().is_none_or(|paths| {
+                calling_service.is_some_and(|service| paths.iter().any(|path| path == service))
+            }),
+        };
+        if allowed {
+            Ok(())
+        } else {
+            Err(anyhow!("access denied"))
+        }
+    }
+}
+
+/// Path of the service whose action is making the current request, if any
+pub(crate) fn calling_service() -> Option<String> {
+    CALLING_SERVICE.try_with(|path| path.clone()).ok()
+}
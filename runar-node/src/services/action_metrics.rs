@@ -0,0 +1,71 @@
+// Action Metrics
+//
+// This module records the latency and outcome of the actions annotated with
+// `#[action(metrics = true)]` through the `metrics` facade.
+
+use std::fmt;
+use std::ops::Deref;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::services::RequestContext;
+
+/// Histogram of action latencies, in milliseconds
+pub const REQUEST_DURATION_MS: &str = "request_duration_ms";
+/// Counter of action calls
+pub const REQUEST_COUNT: &str = "request_count";
+/// Counter of action calls that returned an error
+pub const ERROR_COUNT: &str = "error_count";
+
+/// Recorder receiving the metrics of a node
+pub type MetricsRecorder = dyn metrics::Recorder + Send + Sync;
+
+/// Shared recorder of a node's action metrics, see `NodeConfig::with_metrics_registry`
+///
+/// Recorders are not `Debug`, so this wrapper keeps `NodeConfig` printable.
+#[derive(Clone)]
+pub struct MetricsRegistry(Arc<MetricsRecorder>);
+
+impl MetricsRegistry {
+    pub fn new(recorder: Arc<MetricsRecorder>) -> Self {
+        Self(recorder)
+    }
+}
+
+impl Deref for MetricsRegistry {
+    type Target = MetricsRecorder;
+
+    fn deref(&self) -> &Self::Target {
+        self.0.as_ref()
+    }
+}
+
+impl fmt::Debug for MetricsRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("MetricsRegistry(<Recorder>)")
+    }
+}
+
+/// Record one call of an action
+///
+/// INTENTION: Keep the code generated for annotated actions to a single call.
+/// The metrics are labeled with `service.path` and `action.name` and go to
+/// the recorder of the node's `metrics_registry`, or to the global recorder
+/// of the `metrics` crate when none is configured.
+pub fn record_action_call(ctx: &RequestContext, action_name: &str, elapsed: Duration, ok: bool) {
+    let labels = [
+        ("service.path", ctx.service_path()),
+        ("action.name", action_name.to_string()),
+    ];
+    let record = || {
+        metrics::histogram!(REQUEST_DURATION_MS, &labels).record(elapsed.as_secs_f64() * 1000.0);
+        metrics::counter!(REQUEST_COUNT, &labels).increment(1);
+        if !ok {
+            metrics::counter!(ERROR_COUNT, &labels).increment(1);
+        }
+    };
+    match ctx.node_config().metrics_registry.as_deref() {
+        Some(recorder) => metrics::with_local_recorder(recorder, record),
+        None => record(),
+    }
+}
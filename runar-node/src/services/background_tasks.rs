@@ -0,0 +1,168 @@
+// Background Tasks
+//
+// This module keeps track of the async tasks services spawn through their
+// lifecycle context, so the node can cancel them when the service stops.
+
+use futures_util::FutureExt;
+use runar_common::logging::Logger;
+use std::collections::HashMap;
+use std::future::Future;
+use std::panic::AssertUnwindSafe;
+use std::sync::{Arc, Mutex};
+use tokio::task::AbortHandle;
+
+/// State of a background task
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TaskState {
+    /// The task is still running
+    Running,
+    /// The task returned
+    Finished,
+    /// The task panicked; the panic was logged
+    Panicked,
+    /// The task was cancelled before it returned
+    Aborted,
+}
+
+/// Description of a background task, as listed by the lifecycle context
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TaskInfo {
+    /// Name given when the task was spawned
+    pub name: String,
+    /// Path of the service owning the task
+    pub service_path: String,
+    pub state: TaskState,
+}
+
+/// Handle of a background task, used to cancel it
+#[derive(Debug, Clone)]
+pub struct TaskHandle {
+    name: String,
+    abort: AbortHandle,
+    state: Arc<Mutex<TaskState>>,
+}
+
+impl TaskHandle {
+    /// Name given when the task was spawned
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Cancel the task at its next await point
+    pub fn abort(&self) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        if *state == TaskState::Running {
+            *state = TaskState::Aborted;
+        }
+        self.abort.abort();
+    }
+
+    /// Current state of the task
+    pub fn state(&self) -> TaskState {
+        *self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Background tasks of the local services, by service path
+///
+/// INTENTION: Let a service run periodic work without leaking it past its
+/// own lifetime. Tasks are registered under the path of the service that
+/// spawned them and aborted together once that service stops. A panic in a
+/// task is caught and logged so it only ends that task.
+#[derive(Default)]
+pub struct BackgroundTasks {
+    tasks: Mutex<HashMap<String, Vec<TaskHandle>>>,
+}
+
+impl BackgroundTasks {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Spawn `task` on behalf of the service at `service_path`
+    pub fn spawn<F>(
+        &self,
+        service_path: &str,
+        name: &str,
+        task: F,
+        logger: Arc<Logger>,
+    ) -> TaskHandle
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        let state = Arc::new(Mutex::new(TaskState::Running));
+        let task_state = state.clone();
+        let task_name = name.to_string();
+        let task_service = service_path.to_string();
+        let join = tokio::spawn(async move {
+            let outcome = AssertUnwindSafe(task).catch_unwind().await;
+            let mut state = task_state.lock().unwrap_or_else(|e| e.into_inner());
+            match outcome {
+                // A task aborted while finishing stays aborted
+                Ok(()) if *state == TaskState::Running => *state = TaskState::Finished,
+                Ok(()) => {}
+                Err(panic) => {
+                    *state = TaskState::Panicked;
+                    let message = panic
+                        .downcast_ref::<&str>()
+                        .map(|s| s.to_string())
+                        .or_else(|| panic.downcast_ref::<String>().cloned())
+                        .unwrap_or_else(|| "unknown panic".to_string());
+                    logger.error(format!(
+                        "Background task {task_name} of service {task_service} panicked: {message}"
+                    ));
+                }
+            }
+        });
+
+        let handle = TaskHandle {
+            name: name.to_string(),
+            abort: join.abort_handle(),
+            state,
+        };
+        let mut tasks = self.tasks.lock().unwrap_or_else(|e| e.into_inner());
+        let service_tasks = tasks.entry(service_path.to_string()).or_default();
+        // Tasks that ended are only kept until the service spawns another
+        service_tasks.retain(|task| task.state() == TaskState::Running);
+        service_tasks.push(handle.clone());
+        handle
+    }
+
+    /// Tasks of the service at `service_path`, in the order they were spawned
+    pub fn list(&self, service_path: &str) -> Vec<TaskInfo> {
+        let tasks = self.tasks.lock().unwrap_or_else(|e| e.into_inner());
+        tasks
+            .get(service_path)
+            .map(|service_tasks| {
+                service_tasks
+                    .iter()
+                    .map(|task| TaskInfo {
+                        name: task.name.clone(),
+                        service_path: service_path.to_string(),
+                        state: task.state(),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Abort and forget every task of the service at `service_path`
+    ///
+    /// Returns the number of tasks that were still running.
+    pub fn abort_service(&self, service_path: &str) -> usize {
+        let removed = self
+            .tasks
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(service_path)
+            .unwrap_or_default();
+        removed
+            .iter()
+            .filter(|task| {
+                let running = task.state() == TaskState::Running;
+                task.abort();
+                running
+            })
+            .count()
+    }
+}
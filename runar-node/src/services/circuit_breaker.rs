@@ -0,0 +1,219 @@
+// Circuit Breaker Implementation
+//
+// This module provides a per-service circuit breaker used by the Node to stop
+// routing requests to remote services that keep failing.
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::network::transport::NetworkError;
+
+/// Configuration for a circuit breaker guarding one service path
+///
+/// INTENTION: Describe when a failing service should be cut off and how long
+/// to wait before probing it again.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CircuitBreakerConfig {
+    /// Failure rate (0.0 - 1.0) within the window at which the circuit opens
+    pub failure_threshold: f64,
+    /// Requests the window must hold before the failure rate is evaluated
+    pub minimum_requests: usize,
+    /// Sliding window over which request outcomes are tracked
+    pub window: Duration,
+    /// Time the circuit stays open before a probe request is let through
+    pub half_open_timeout: Duration,
+}
+
+impl Default for CircuitBreakerConfig {
+    fn default() -> Self {
+        Self {
+            failure_threshold: 0.5,
+            minimum_requests: 10,
+            window: Duration::from_secs(30),
+            half_open_timeout: Duration::from_secs(10),
+        }
+    }
+}
+
+impl CircuitBreakerConfig {
+    /// Create a new circuit breaker configuration
+    pub fn new(failure_threshold: f64, window: Duration, half_open_timeout: Duration) -> Self {
+        Self {
+            failure_threshold,
+            window,
+            half_open_timeout,
+            ..Self::default()
+        }
+    }
+
+    /// Evaluate the failure rate only once the window holds `minimum_requests` outcomes
+    pub fn with_minimum_requests(mut self, minimum_requests: usize) -> Self {
+        self.minimum_requests = minimum_requests;
+        self
+    }
+}
+
+/// Observable state of a circuit breaker
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitState {
+    /// Requests flow normally
+    Closed,
+    /// Requests are rejected without being sent
+    Open,
+    /// A single probe request is allowed through
+    HalfOpen,
+}
+
+#[derive(Debug)]
+enum BreakerState {
+    Closed,
+    Open { since: Instant },
+    HalfOpen { probe_started: Instant },
+}
+
+#[derive(Debug)]
+struct BreakerInner {
+    state: BreakerState,
+    /// Outcomes inside the sliding window: (time, succeeded)
+    outcomes: VecDeque<(Instant, bool)>,
+}
+
+/// Circuit breaker tracking the failure rate of requests to one service path
+///
+/// INTENTION: Fail fast when a service keeps failing instead of letting every
+/// caller wait for its own timeout. When the failure rate inside the window
+/// reaches the threshold, over at least `minimum_requests` requests, the
+/// circuit opens and `try_acquire` refuses requests.
+/// After `half_open_timeout` a single probe is allowed through: success closes
+/// the circuit, failure opens it again and restarts the timer.
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    config: CircuitBreakerConfig,
+    inner: Mutex<BreakerInner>,
+}
+
+impl CircuitBreaker {
+    /// Create a new closed circuit breaker
+    pub fn new(config: CircuitBreakerConfig) -> Self {
+        Self {
+            config,
+            inner: Mutex::new(BreakerInner {
+                state: BreakerState::Closed,
+                outcomes: VecDeque::new(),
+            }),
+        }
+    }
+
+    /// Get the configuration of this circuit breaker
+    pub fn config(&self) -> &CircuitBreakerConfig {
+        &self.config
+    }
+
+    /// Get the current state of the circuit
+    pub fn state(&self) -> CircuitState {
+        match self.lock().state {
+            BreakerState::Closed => CircuitState::Closed,
+            BreakerState::Open { .. } => CircuitState::Open,
+            BreakerState::HalfOpen { .. } => CircuitState::HalfOpen,
+        }
+    }
+
+    /// Check whether a request may be sent
+    ///
+    /// Moving from open to half-open happens here, so the caller that gets
+    /// `true` for a half-open circuit is the probe and must report its outcome.
+    /// A probe that never reports back (e.g. its caller was dropped) is
+    /// replaced by a new one after another `half_open_timeout`.
+    pub fn try_acquire(&self) -> bool {
+        let mut inner = self.lock();
+        let now = Instant::now();
+        match inner.state {
+            BreakerState::Closed => true,
+            BreakerState::Open { since }
+            | BreakerState::HalfOpen {
+                probe_started: since,
+            } => {
+                if now.duration_since(since) >= self.config.half_open_timeout {
+                    inner.state = BreakerState::HalfOpen { probe_started: now };
+                    true
+                } else {
+                    false
+                }
+            }
+        }
+    }
+
+    /// Record a successful request
+    pub fn record_success(&self) {
+        let mut inner = self.lock();
+        match inner.state {
+            BreakerState::HalfOpen { .. } => {
+                inner.state = BreakerState::Closed;
+                inner.outcomes.clear();
+            }
+            BreakerState::Closed => self.push_outcome(&mut inner, true),
+            // A request admitted before the circuit opened; the probe decides
+            BreakerState::Open { .. } => {}
+        }
+    }
+
+    /// Record a failed request
+    ///
+    /// Only failures to reach the service count, see `is_failure`.
+    pub fn record_failure(&self) {
+        let mut inner = self.lock();
+        let now = Instant::now();
+        match inner.state {
+            BreakerState::HalfOpen { .. } => {
+                inner.state = BreakerState::Open { since: now };
+            }
+            BreakerState::Closed => {
+                self.push_outcome(&mut inner, false);
+                if inner.outcomes.len() >= self.config.minimum_requests
+                    && self.failure_rate(&inner) >= self.config.failure_threshold
+                {
+                    inner.state = BreakerState::Open { since: now };
+                    inner.outcomes.clear();
+                }
+            }
+            BreakerState::Open { .. } => {}
+        }
+    }
+
+    /// Whether `error` counts as a failure of the service
+    ///
+    /// Errors returned by the service's own handler show that it is reachable
+    /// and are recorded as successes; only retryable `NetworkError`s, such as
+    /// an unreachable peer or a timeout, count against the circuit.
+    pub fn is_failure(error: &anyhow::Error) -> bool {
+        error
+            .downcast_ref::<NetworkError>()
+            .is_some_and(NetworkError::is_retryable)
+    }
+
+    fn push_outcome(&self, inner: &mut BreakerInner, succeeded: bool) {
+        let now = Instant::now();
+        inner.outcomes.push_back((now, succeeded));
+        while let Some((at, _)) = inner.outcomes.front() {
+            if now.duration_since(*at) > self.config.window {
+                inner.outcomes.pop_front();
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn failure_rate(&self, inner: &BreakerInner) -> f64 {
+        if inner.outcomes.is_empty() {
+            return 0.0;
+        }
+        let failures = inner.outcomes.iter().filter(|(_, ok)| !ok).count();
+        failures as f64 / inner.outcomes.len() as f64
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, BreakerInner> {
+        // The state stays consistent even if a holder panicked, so recover it
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
@@ -0,0 +1,117 @@
+// Dead-Letter Queue
+//
+// This module provides the bounded queue that keeps event deliveries whose
+// subscriber handler failed, so they can be inspected and replayed later.
+
+use runar_common::types::ArcValue;
+use std::collections::{HashSet, VecDeque};
+use std::time::SystemTime;
+
+/// Default number of failed deliveries kept in the dead-letter queue
+pub const DEFAULT_DEAD_LETTER_QUEUE_SIZE: usize = 1000;
+
+/// An event delivery whose subscriber handler returned an error
+#[derive(Debug, Clone)]
+pub struct DeadLetterEntry {
+    /// Full topic of the event, including the network ID
+    pub event_topic: String,
+    /// Event payload; null for events published without one
+    pub payload: ArcValue,
+    /// Topic (or pattern) the failing subscriber subscribed to
+    pub subscriber_path: String,
+    /// ID of the failing subscription, used to replay to the same subscriber
+    pub subscription_id: String,
+    /// Error returned by the last delivery attempt
+    pub error: String,
+    /// When the last delivery attempt failed
+    pub failed_at: SystemTime,
+    /// Number of failed delivery attempts
+    pub attempt_count: u32,
+}
+
+/// Bounded queue of failed event deliveries
+///
+/// INTENTION: Keep failed deliveries instead of only logging them, so an
+/// operator can see what was lost and replay it once the subscriber is fixed.
+/// When the queue is full the oldest entry is evicted. A maximum size of 0
+/// disables capture. Subscriptions registered with `dead_letter: false` are
+/// never captured.
+#[derive(Debug)]
+pub struct DeadLetterQueue {
+    max_size: usize,
+    entries: VecDeque<DeadLetterEntry>,
+    excluded_subscriptions: HashSet<String>,
+}
+
+impl DeadLetterQueue {
+    /// Create a queue keeping at most `max_size` entries
+    pub fn new(max_size: usize) -> Self {
+        Self {
+            max_size,
+            entries: VecDeque::new(),
+            excluded_subscriptions: HashSet::new(),
+        }
+    }
+
+    /// Add a failed delivery, evicting the oldest entry when full
+    ///
+    /// Returns false if the entry was not captured, because the queue is
+    /// disabled or the subscription opted out.
+    pub fn push(&mut self, entry: DeadLetterEntry) -> bool {
+        if self.max_size == 0 || !self.captures(&entry.subscription_id) {
+            return false;
+        }
+        if self.entries.len() >= self.max_size {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(entry);
+        true
+    }
+
+    /// Stop capturing failed deliveries of a subscription
+    pub fn exclude_subscription(&mut self, subscription_id: impl Into<String>) {
+        self.excluded_subscriptions.insert(subscription_id.into());
+    }
+
+    /// Forget a subscription that was removed
+    pub fn remove_subscription(&mut self, subscription_id: &str) {
+        self.excluded_subscriptions.remove(subscription_id);
+    }
+
+    /// Whether failed deliveries of a subscription are captured
+    pub fn captures(&self, subscription_id: &str) -> bool {
+        !self.excluded_subscriptions.contains(subscription_id)
+    }
+
+    /// All entries, oldest first
+    pub fn entries(&self) -> Vec<DeadLetterEntry> {
+        self.entries.iter().cloned().collect()
+    }
+
+    /// Remove and return the entries matching `filter`, oldest first
+    pub fn take_matching(
+        &mut self,
+        filter: impl Fn(&DeadLetterEntry) -> bool,
+    ) -> Vec<DeadLetterEntry> {
+        let (taken, kept): (VecDeque<_>, VecDeque<_>) =
+            self.entries.drain(..).partition(|entry| filter(entry));
+        self.entries = kept;
+        taken.into()
+    }
+
+    /// Number of entries in the queue
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether the queue has no entries
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+impl Default for DeadLetterQueue {
+    fn default() -> Self {
+        Self::new(DEFAULT_DEAD_LETTER_QUEUE_SIZE)
+    }
+}
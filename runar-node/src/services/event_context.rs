@@ -14,7 +14,7 @@ use crate::node::Node; // Added for concrete type
 use crate::routing::TopicPath;
 use crate::services::PublishOptions; // Restored
 use crate::NodeDelegate; // Keep one instance
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use runar_common::logging::{Component, Logger, LoggingContext}; // Restored
 use runar_common::types::ArcValue;
 use runar_common::types::AsArcValue; // Corrected: Only AsArcValue needed here
@@ -44,6 +44,9 @@ pub struct EventContext {
 
     /// Delivery options used when publishing this event
     pub delivery_options: Option<PublishOptions>,
+
+    /// Sequence number of an event published with `ordered: true`
+    pub sequence: Option<u64>,
 }
 
 impl fmt::Debug for EventContext {
@@ -52,6 +55,7 @@ impl fmt::Debug for EventContext {
             .field("topic_path", &self.topic_path)
             .field("logger", &"<Logger>") // Avoid trying to Debug the Logger
             .field("delivery_options", &self.delivery_options)
+            .field("sequence", &self.sequence)
             .finish()
     }
 }
@@ -93,6 +97,7 @@ impl EventContext {
             logger: event_logger,
             node_delegate,
             delivery_options: None,
+            sequence: None,
         }
     }
 
@@ -112,6 +117,22 @@ impl EventContext {
         self
     }
 
+    /// Sequence number of the event within its topic and publisher
+    ///
+    /// Only set for events published with `ordered: true`.
+    pub fn sequence(&self) -> Option<u64> {
+        self.sequence
+    }
+
+    /// Get the path of an action of the service the event was published under
+    ///
+    /// INTENTION: Build the full path of an action from this event's network
+    /// and the first segment of its topic, without hardcoding the network ID.
+    pub fn relative_path(&self, action: &str) -> Result<TopicPath> {
+        TopicPath::relative(&self.topic_path.service_path(), action, &self.topic_path)
+            .map_err(|e| anyhow!("Invalid action {action}: {e}"))
+    }
+
     /// Helper method to log debug level message
     pub fn debug(&self, message: impl Into<String>) {
         self.logger.debug(message);
@@ -0,0 +1,117 @@
+// Event Deduplication
+//
+// This module provides the hash that identifies a published event and
+// the sliding window of recently seen event IDs used to drop duplicates.
+
+use std::collections::{HashSet, VecDeque};
+
+/// Default number of recent events remembered for deduplication
+pub const DEFAULT_EVENT_DEDUP_WINDOW: usize = 1000;
+
+/// Compute the deduplication ID of an event
+///
+/// The ID is the first 8 bytes of SHA-256 over the publisher's peer id, a
+/// nonce the publisher draws for each event, the topic and the serialized
+/// payload, each length-prefixed so that the boundary between them cannot be
+/// shifted. The nonce keeps events that repeat a payload distinct, so only
+/// copies of the same event share an ID.
+pub fn event_dedup_id(publisher: &str, nonce: u64, topic: &str, value_bytes: &[u8]) -> [u8; 8] {
+    let mut ctx = ring::digest::Context::new(&ring::digest::SHA256);
+    ctx.update(&(publisher.len() as u64).to_be_bytes());
+    ctx.update(publisher.as_bytes());
+    ctx.update(&nonce.to_be_bytes());
+    ctx.update(&(topic.len() as u64).to_be_bytes());
+    ctx.update(topic.as_bytes());
+    ctx.update(&(value_bytes.len() as u64).to_be_bytes());
+    ctx.update(value_bytes);
+
+    let mut id = [0u8; 8];
+    id.copy_from_slice(&ctx.finish().as_ref()[..8]);
+    id
+}
+
+/// Compute the deduplication ID of an ordered event
+///
+/// Like `event_dedup_id`, with the event's sequence number in place of the
+/// nonce, so that ordered events repeating a payload are not mistaken for
+/// duplicates.
+pub fn sequenced_event_dedup_id(
+    publisher: &str,
+    topic: &str,
+    sequence: u64,
+    value_bytes: &[u8],
+) -> [u8; 8] {
+    let mut ctx = ring::digest::Context::new(&ring::digest::SHA256);
+    ctx.update(&(publisher.len() as u64).to_be_bytes());
+    ctx.update(publisher.as_bytes());
+    ctx.update(&(topic.len() as u64).to_be_bytes());
+    ctx.update(topic.as_bytes());
+    ctx.update(&sequence.to_be_bytes());
+    ctx.update(&(value_bytes.len() as u64).to_be_bytes());
+    ctx.update(value_bytes);
+
+    let mut id = [0u8; 8];
+    id.copy_from_slice(&ctx.finish().as_ref()[..8]);
+    id
+}
+
+/// Sliding window of the IDs of the last N events
+///
+/// INTENTION: Let a node receive the same event over several paths, such as
+/// directly from the publisher and again through a relay, while subscribers
+/// see it once. A window of 0 disables deduplication.
+#[derive(Debug)]
+pub struct EventDedupCache {
+    window: usize,
+    order: VecDeque<[u8; 8]>,
+    seen: HashSet<[u8; 8]>,
+}
+
+impl EventDedupCache {
+    /// Create a cache remembering the last `window` events
+    pub fn new(window: usize) -> Self {
+        Self {
+            window,
+            order: VecDeque::with_capacity(window),
+            seen: HashSet::with_capacity(window),
+        }
+    }
+
+    /// Record an event ID, returning false if it is already in the window
+    pub fn insert(&mut self, id: [u8; 8]) -> bool {
+        if self.window == 0 {
+            return true;
+        }
+        if !self.seen.insert(id) {
+            return false;
+        }
+        self.order.push_back(id);
+        if self.order.len() > self.window {
+            if let Some(oldest) = self.order.pop_front() {
+                self.seen.remove(&oldest);
+            }
+        }
+        true
+    }
+
+    /// Whether an event ID is in the window
+    pub fn contains(&self, id: &[u8; 8]) -> bool {
+        self.seen.contains(id)
+    }
+
+    /// Number of event IDs in the window
+    pub fn len(&self) -> usize {
+        self.order.len()
+    }
+
+    /// Whether no event has been recorded
+    pub fn is_empty(&self) -> bool {
+        self.order.is_empty()
+    }
+}
+
+impl Default for EventDedupCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_EVENT_DEDUP_WINDOW)
+    }
+}
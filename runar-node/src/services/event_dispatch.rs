@@ -0,0 +1,163 @@
+// Event Dispatch
+//
+// This module provides the per-subscription queues that let a publisher
+// return before slow subscribers have handled its events.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::{mpsc, Notify};
+
+/// How `Node::publish_with_options` hands an event to local subscribers
+///
+/// Requests are not affected: their handler always runs before the
+/// request returns.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DispatchMode {
+    /// Run each subscriber in turn before returning
+    Sync,
+    /// Queue the event for each subscriber and return immediately
+    #[default]
+    Async,
+    /// Queue the event for each subscriber, waiting while a subscriber
+    /// already has `queue_size` events queued
+    BoundedAsync { queue_size: usize },
+}
+
+/// Queue of one subscription, drained by its own task
+struct SubscriberQueue<T> {
+    sender: mpsc::UnboundedSender<T>,
+    /// Events queued and not yet handled
+    pending: Arc<AtomicUsize>,
+    /// Notified each time the subscriber handled an event
+    handled: Arc<Notify>,
+}
+
+impl<T> Clone for SubscriberQueue<T> {
+    fn clone(&self) -> Self {
+        Self {
+            sender: self.sender.clone(),
+            pending: self.pending.clone(),
+            handled: self.handled.clone(),
+        }
+    }
+}
+
+/// Background delivery of events, one queue per subscription
+///
+/// INTENTION: Keep a slow subscriber from stalling the publisher without
+/// reordering its events. Each subscription gets a queue and a task handing
+/// it the queued events in order, so a slow subscriber only delays its own
+/// events. With a queue size, the publisher waits for room in the queue of
+/// each subscriber instead, which bounds the memory held by a subscriber
+/// that cannot keep up.
+pub struct EventDispatcher<T> {
+    queues: Mutex<HashMap<String, SubscriberQueue<T>>>,
+}
+
+impl<T> Default for EventDispatcher<T> {
+    fn default() -> Self {
+        Self {
+            queues: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl<T: Send + 'static> EventDispatcher<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queue `event` for `subscription_id`
+    ///
+    /// The first event of a subscription starts a task calling the function
+    /// made by `make_deliver` for each of its events. With `queue_size`,
+    /// waits while the subscription has that many events queued.
+    pub async fn dispatch<F, Fut>(
+        &self,
+        subscription_id: &str,
+        event: T,
+        queue_size: Option<usize>,
+        make_deliver: impl FnOnce() -> F,
+    ) where
+        F: Fn(T) -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let queue = self.queue(subscription_id, make_deliver);
+        match queue_size {
+            Some(queue_size) => loop {
+                // Registered before checking, so a handled event is not missed
+                let handled = queue.handled.notified();
+                let pending = queue.pending.load(Ordering::SeqCst);
+                if pending < queue_size.max(1) {
+                    if queue
+                        .pending
+                        .compare_exchange(pending, pending + 1, Ordering::SeqCst, Ordering::SeqCst)
+                        .is_ok()
+                    {
+                        break;
+                    }
+                    continue;
+                }
+                handled.await;
+            },
+            None => {
+                queue.pending.fetch_add(1, Ordering::SeqCst);
+            }
+        }
+        // The task only stops once every sender is dropped
+        let _ = queue.sender.send(event);
+    }
+
+    /// Drop the queue of `subscription_id`; queued events are still delivered
+    pub fn remove(&self, subscription_id: &str) {
+        self.queues
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(subscription_id);
+    }
+
+    /// Drop every queue; queued events are still delivered
+    pub fn clear(&self) {
+        self.queues
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clear();
+    }
+
+    /// Get the queue of `subscription_id`, starting its task on first use
+    fn queue<F, Fut>(
+        &self,
+        subscription_id: &str,
+        make_deliver: impl FnOnce() -> F,
+    ) -> SubscriberQueue<T>
+    where
+        F: Fn(T) -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let mut queues = self.queues.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(queue) = queues.get(subscription_id) {
+            return queue.clone();
+        }
+
+        let (sender, mut receiver) = mpsc::unbounded_channel();
+        let queue = SubscriberQueue {
+            sender,
+            pending: Arc::new(AtomicUsize::new(0)),
+            handled: Arc::new(Notify::new()),
+        };
+        let pending = queue.pending.clone();
+        let handled = queue.handled.clone();
+        let deliver = make_deliver();
+        tokio::spawn(async move {
+            while let Some(event) = receiver.recv().await {
+                deliver(event).await;
+                pending.fetch_sub(1, Ordering::SeqCst);
+                handled.notify_waiters();
+            }
+        });
+        queues.insert(subscription_id.to_string(), queue.clone());
+        queue
+    }
+}
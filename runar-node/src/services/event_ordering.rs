@@ -0,0 +1,186 @@
+// Ordered Event Delivery
+//
+// This module provides the buffer that puts sequenced events from remote
+// publishers back in order before they are delivered to local subscribers.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Default number of out-of-order events buffered across all publishers
+pub const DEFAULT_ORDERED_EVENT_BUFFER_SIZE: usize = 1000;
+
+/// Default time an out-of-order event waits for the events before it
+pub const DEFAULT_ORDERED_EVENT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Events of one topic from one publisher
+#[derive(Debug)]
+struct SequencedStream<T> {
+    /// Sequence number of the next event to deliver
+    next: u64,
+    /// Events that arrived ahead of `next`, with their arrival time
+    pending: BTreeMap<u64, (Instant, T)>,
+}
+
+/// Reorders sequenced events per publisher and topic
+///
+/// INTENTION: Publishers stamp events published with `ordered: true` with a
+/// per-topic sequence number starting at 1. Events can still reach a node out
+/// of order, e.g. when one copy is relayed; this buffer holds back events that
+/// arrive ahead of a gap and releases them once the gap is filled.
+///
+/// The first event seen from a publisher on a topic starts its stream, so a
+/// node that joins late does not wait for events it will never receive.
+/// Sequence 1 after later events means the publisher restarted and starts the
+/// stream again. Events older than the next expected one are duplicates and are
+/// dropped.
+///
+/// Buffering is bounded: an event arriving ahead of a gap while `max_size`
+/// events are already buffered is discarded, and when a gap stays open for
+/// longer than `timeout` the events buffered behind it are discarded and the
+/// stream continues after them. A maximum size of 0 disables buffering.
+/// Gaps are only checked by `expire`, which the owner calls periodically.
+///
+/// Events released by one call must be delivered before those released by the
+/// next call for the same stream. Callers hold the stream's `delivery_lock`
+/// across `accept` and the delivery, so that the buffer itself is only locked
+/// briefly and other streams are delivered meanwhile.
+///
+/// Ordering only holds for events of one publisher on one topic, as seen by
+/// this node. Ordering events of several publishers, or across nodes, needs a
+/// coordination service.
+#[derive(Debug)]
+pub struct OrderedEventBuffer<T> {
+    max_size: usize,
+    timeout: Duration,
+    streams: HashMap<(String, String), SequencedStream<T>>,
+    delivery_locks: HashMap<(String, String), Arc<tokio::sync::Mutex<()>>>,
+    buffered: usize,
+}
+
+impl<T> OrderedEventBuffer<T> {
+    /// Create a buffer holding at most `max_size` events for up to `timeout`
+    pub fn new(max_size: usize, timeout: Duration) -> Self {
+        Self {
+            max_size,
+            timeout,
+            streams: HashMap::new(),
+            delivery_locks: HashMap::new(),
+            buffered: 0,
+        }
+    }
+
+    /// Lock serializing the delivery of the events of a stream
+    pub fn delivery_lock(&mut self, publisher: &str, topic: &str) -> Arc<tokio::sync::Mutex<()>> {
+        self.delivery_locks
+            .entry((publisher.to_string(), topic.to_string()))
+            .or_default()
+            .clone()
+    }
+
+    /// Accept an event, returning the events now ready for delivery in order
+    ///
+    /// The returned events carry their sequence numbers. The result is empty
+    /// when the event is a duplicate, was buffered or was discarded.
+    pub fn accept(
+        &mut self,
+        publisher: &str,
+        topic: &str,
+        sequence: u64,
+        event: T,
+    ) -> Vec<(u64, T)> {
+        let stream = self
+            .streams
+            .entry((publisher.to_string(), topic.to_string()))
+            .or_insert_with(|| SequencedStream {
+                next: sequence,
+                pending: BTreeMap::new(),
+            });
+
+        if sequence == 1 && stream.next > 1 {
+            // The publisher restarted and its counter started again
+            self.buffered -= stream.pending.len();
+            stream.pending.clear();
+            stream.next = 1;
+        }
+
+        if sequence < stream.next || stream.pending.contains_key(&sequence) {
+            return Vec::new();
+        }
+
+        if sequence > stream.next {
+            if self.buffered < self.max_size {
+                stream.pending.insert(sequence, (Instant::now(), event));
+                self.buffered += 1;
+            }
+            return Vec::new();
+        }
+
+        let mut ready = vec![(sequence, event)];
+        stream.next = sequence + 1;
+        while let Some((_, event)) = stream.pending.remove(&stream.next) {
+            ready.push((stream.next, event));
+            stream.next += 1;
+            self.buffered -= 1;
+        }
+        ready
+    }
+
+    /// Give up on gaps open for longer than the timeout
+    ///
+    /// Discards the events buffered behind each such gap and moves the stream
+    /// past them. Returns the number of discarded events.
+    pub fn expire(&mut self, now: Instant) -> usize {
+        let mut discarded = 0;
+        for stream in self.streams.values_mut() {
+            let expired = stream
+                .pending
+                .values()
+                .next()
+                .is_some_and(|(received_at, _)| now.duration_since(*received_at) > self.timeout);
+            if !expired {
+                continue;
+            }
+            if let Some((&last, _)) = stream.pending.last_key_value() {
+                stream.next = last + 1;
+            }
+            discarded += stream.pending.len();
+            stream.pending.clear();
+        }
+        self.buffered -= discarded;
+        discarded
+    }
+
+    /// Forget the streams of a publisher, e.g. when it disconnects
+    pub fn remove_publisher(&mut self, publisher: &str) {
+        let buffered = &mut self.buffered;
+        self.streams.retain(|(stream_publisher, _), stream| {
+            let keep = stream_publisher != publisher;
+            if !keep {
+                *buffered -= stream.pending.len();
+            }
+            keep
+        });
+        self.delivery_locks
+            .retain(|(stream_publisher, _), _| stream_publisher != publisher);
+    }
+
+    /// Number of events waiting for a gap to be filled
+    pub fn len(&self) -> usize {
+        self.buffered
+    }
+
+    /// Whether no events are waiting
+    pub fn is_empty(&self) -> bool {
+        self.buffered == 0
+    }
+}
+
+impl<T> Default for OrderedEventBuffer<T> {
+    fn default() -> Self {
+        Self::new(
+            DEFAULT_ORDERED_EVENT_BUFFER_SIZE,
+            DEFAULT_ORDERED_EVENT_TIMEOUT,
+        )
+    }
+}
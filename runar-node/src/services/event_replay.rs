@@ -0,0 +1,122 @@
+// Event Replay
+//
+// This module provides the ring buffers of recent events per topic that let
+// subscribers joining late receive the events published before they joined.
+
+use std::collections::{HashMap, VecDeque};
+
+/// Default number of bytes of retained events per topic
+pub const DEFAULT_RETAIN_MAX_BYTES_PER_TOPIC: usize = 1024 * 1024;
+
+/// Which retained events a new subscription receives first
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReplaySince {
+    /// The last `n` retained events of the topic
+    Last(usize),
+    /// The retained events numbered after this sequence number
+    After(u64),
+}
+
+/// A retained event with its sequence number and serialized size
+#[derive(Debug)]
+struct RetainedEvent<T> {
+    sequence: u64,
+    size: usize,
+    event: T,
+}
+
+/// Retained events of one topic, oldest first
+#[derive(Debug)]
+struct TopicEvents<T> {
+    events: VecDeque<RetainedEvent<T>>,
+    bytes: usize,
+}
+
+/// Keeps the last events published on each topic for replay
+///
+/// INTENTION: Events published with `PublishOptions::retain_last` are kept in
+/// a ring buffer of their topic, so that a subscriber joining later (e.g. a
+/// service that restarted) can receive them before live events. Each topic
+/// keeps at most the number of events the last publish asked for, and at most
+/// `max_bytes_per_topic` bytes of serialized events; the oldest events are
+/// evicted first. An event larger than the byte limit is not retained.
+///
+/// Replay is best effort: evicted events are gone, and nothing survives a
+/// restart of the node. Durable replay needs an event log.
+#[derive(Debug)]
+pub struct EventReplayBuffer<T> {
+    max_bytes_per_topic: usize,
+    topics: HashMap<String, TopicEvents<T>>,
+}
+
+impl<T: Clone> EventReplayBuffer<T> {
+    /// Create a buffer retaining at most `max_bytes_per_topic` bytes per topic
+    pub fn new(max_bytes_per_topic: usize) -> Self {
+        Self {
+            max_bytes_per_topic,
+            topics: HashMap::new(),
+        }
+    }
+
+    /// Retain an event, keeping the last `retain_last` events of its topic
+    ///
+    /// `size` is the serialized size of the event. Returns false when the
+    /// event was not retained because it is larger than the per-topic limit.
+    pub fn retain(
+        &mut self,
+        topic: &str,
+        sequence: u64,
+        event: T,
+        size: usize,
+        retain_last: usize,
+    ) -> bool {
+        if size > self.max_bytes_per_topic || retain_last == 0 {
+            return false;
+        }
+        let topic_events = self
+            .topics
+            .entry(topic.to_string())
+            .or_insert_with(|| TopicEvents {
+                events: VecDeque::new(),
+                bytes: 0,
+            });
+        topic_events.events.push_back(RetainedEvent {
+            sequence,
+            size,
+            event,
+        });
+        topic_events.bytes += size;
+        while topic_events.events.len() > retain_last
+            || topic_events.bytes > self.max_bytes_per_topic
+        {
+            match topic_events.events.pop_front() {
+                Some(evicted) => topic_events.bytes -= evicted.size,
+                None => break,
+            }
+        }
+        true
+    }
+
+    /// The retained events of a topic selected by `since`, oldest first
+    ///
+    /// The events carry their sequence numbers.
+    pub fn replay(&self, topic: &str, since: ReplaySince) -> Vec<(u64, T)> {
+        let Some(topic_events) = self.topics.get(topic) else {
+            return Vec::new();
+        };
+        let skip = match since {
+            ReplaySince::Last(n) => topic_events.events.len().saturating_sub(n),
+            ReplaySince::After(sequence) => topic_events
+                .events
+                .iter()
+                .take_while(|retained| retained.sequence <= sequence)
+                .count(),
+        };
+        topic_events
+            .events
+            .iter()
+            .skip(skip)
+            .map(|retained| (retained.sequence, retained.event.clone()))
+            .collect()
+    }
+}
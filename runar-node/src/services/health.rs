@@ -0,0 +1,90 @@
+// Service Health
+//
+// This module provides the health reports nodes gossip to their peers, and the
+// cache of the last report received from each peer.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crate::network::transport::PeerId;
+use crate::services::abstract_service::ServiceState;
+
+/// Default interval between two health reports sent to the peers
+pub const DEFAULT_HEALTH_GOSSIP_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Health of a node's local services
+///
+/// INTENTION: Give operators and load balancers one view of a node's health.
+/// A node is healthy when none of its services is in the `Error` or
+/// `Unhealthy` state.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HealthReport {
+    /// Peer ID of the node the report describes
+    pub peer_id: String,
+    /// Whether no service of the node is in the `Error` or `Unhealthy` state
+    pub healthy: bool,
+    /// State of each local service, keyed by service path
+    pub services: HashMap<String, ServiceState>,
+    /// Whether the report is older than two gossip intervals
+    pub stale: bool,
+}
+
+impl HealthReport {
+    /// Report the health of a node from the states of its services
+    pub fn new(peer_id: &PeerId, services: HashMap<String, ServiceState>) -> Self {
+        let healthy = !services
+            .values()
+            .any(|state| matches!(state, ServiceState::Error | ServiceState::Unhealthy));
+        Self {
+            peer_id: peer_id.to_string(),
+            healthy,
+            services,
+            stale: false,
+        }
+    }
+}
+
+/// Keeps the last health report received from each peer
+///
+/// INTENTION: Every node sends its `HealthReport` to its peers each gossip
+/// interval, so any node can answer for the whole cluster. A report not
+/// refreshed within two intervals is returned marked as stale, since its peer
+/// may be unreachable; stale reports are kept until a newer one arrives.
+#[derive(Debug)]
+pub struct HealthGossip {
+    interval: Duration,
+    reports: HashMap<PeerId, (HealthReport, Instant)>,
+}
+
+impl HealthGossip {
+    /// Create a cache for reports gossiped every `interval`
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            reports: HashMap::new(),
+        }
+    }
+
+    /// Interval between two reports of a peer
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    /// Record the report received from `peer`, replacing its previous one
+    pub fn record(&mut self, peer: PeerId, report: HealthReport) {
+        self.reports.insert(peer, (report, Instant::now()));
+    }
+
+    /// The last report of each peer, marked as stale when outdated
+    pub fn reports(&self) -> HashMap<PeerId, HealthReport> {
+        self.reports
+            .iter()
+            .map(|(peer, (report, received_at))| {
+                let mut report = report.clone();
+                report.stale = received_at.elapsed() > self.interval * 2;
+                (peer.clone(), report)
+            })
+            .collect()
+    }
+}
@@ -10,8 +10,9 @@
 // - internal/registry/services/{service_path}
 // - internal/registry/services/{service_path}/state
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
 use crate::services::{KeysDelegate, LifecycleContext, RequestContext};
@@ -19,6 +20,13 @@ use crate::AbstractService;
 use runar_common::logging::Logger;
 use runar_common::types::ArcValue;
 
+/// Payload of the `$keys/store_symmetric_key` action
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StoreSymmetricKeyRequest {
+    pub key_name: String,
+    pub key: Vec<u8>,
+}
+
 /// Registry Info Service - provides information about registered services without holding state
 pub struct KeysService {
     /// Logger instance
@@ -62,6 +70,41 @@ impl KeysService {
         Ok(())
     }
 
+    /// Register the store_symmetric_key action
+    ///
+    /// INTENTION: Let a service replace the key it got from
+    /// `ensure_symmetric_key`, e.g. after re-encrypting its data, so that the
+    /// new key is returned from then on.
+    async fn register_store_symmetric_key_action(&self, context: &LifecycleContext) -> Result<()> {
+        let self_clone = self.clone();
+
+        context
+            .register_action(
+                "store_symmetric_key",
+                Arc::new(move |params: Option<ArcValue>, ctx| {
+                    let inner_self = self_clone.clone();
+                    Box::pin(async move {
+                        let request = params
+                            .ok_or_else(|| {
+                                anyhow!("StoreSymmetricKeyRequest parameter is required")
+                            })?
+                            .as_type::<StoreSymmetricKeyRequest>()?;
+                        ctx.logger.debug("store_symmetric_key");
+                        inner_self
+                            .keys_delegate
+                            .store_symmetric_key(&request.key_name, request.key)
+                            .await?;
+                        Ok(ArcValue::new_primitive(true))
+                    })
+                }),
+            )
+            .await?;
+        context
+            .logger
+            .debug("Registered store_symmetric_key action");
+        Ok(())
+    }
+
     /// Handler for ensuring symmetric key exists
     async fn handle_ensure_symmetric_key(
         &self,
@@ -115,6 +158,7 @@ impl AbstractService for KeysService {
         context
             .logger
             .debug("Registered handler for ensure_symmetric_key action");
+        self.register_store_symmetric_key_action(&context).await?;
 
         context.logger.info("Keys Service initialization complete");
 
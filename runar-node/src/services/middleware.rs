@@ -0,0 +1,21 @@
+// Request Middleware
+//
+// This module defines the hook the Node runs before dispatching a request to
+// a local action handler. Middleware registered with `Node::add_middleware`
+// runs in registration order and can reject a request before the handler
+// sees it.
+
+use anyhow::Result;
+
+use crate::services::RequestContext;
+
+/// Check run on every request before it reaches a local action handler
+///
+/// INTENTION: Let cross-cutting policies such as rate limiting apply to all
+/// services of a node without each action implementing them. Returning an
+/// error rejects the request: the handler is not called and the caller
+/// receives the error.
+pub trait Middleware: Send + Sync {
+    /// Inspect the request; an error stops it from reaching the handler
+    fn before_request(&self, context: &RequestContext) -> Result<()>;
+}
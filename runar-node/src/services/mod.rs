@@ -18,15 +18,32 @@
 
 // Module declarations
 pub mod abstract_service;
+pub mod access_policy;
+pub mod action_metrics;
+pub mod background_tasks;
+pub mod circuit_breaker;
+pub mod dead_letter;
 pub mod event_context;
+pub mod event_dedup;
+pub mod event_dispatch;
+pub mod event_ordering;
+pub mod event_replay;
+pub mod health;
 pub mod keys_service;
 pub mod load_balancing;
+pub mod middleware;
+pub mod rate_limit;
 pub mod registry_service;
 pub mod remote_service;
 pub mod request_context;
+pub mod response_stream;
+pub mod retry_budget;
 pub mod service_registry;
+pub mod service_restart;
+pub mod subscription_groups;
 
 // Import necessary components
+use crate::network::transport::PeerId;
 use crate::node::Node; // Added for concrete type Node
 use crate::routing::TopicPath;
 use anyhow::{anyhow, Result};
@@ -38,16 +55,21 @@ use std::fmt::Debug;
 use std::future::Future;
 use std::pin::Pin;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::RwLock;
 
 // Import types from submodules
 use crate::services::abstract_service::ServiceState;
+use crate::services::background_tasks::{TaskHandle, TaskInfo};
+use crate::services::event_dispatch::DispatchMode;
+use crate::services::health::HealthReport;
 use crate::services::remote_service::RemoteService;
 use runar_common::types::schemas::ServiceMetadata;
 
 // Re-export the context types from their dedicated modules
 pub use crate::services::event_context::EventContext;
 pub use crate::services::request_context::RequestContext;
+pub use crate::services::response_stream::{ResponseStream, ResponseStreamSender};
 
 /// Handler for a service action
 ///
@@ -143,6 +165,40 @@ impl LifecycleContext {
         self.logger.error(message);
     }
 
+    /// Peer ID of the node the service runs on, as remote peers know it
+    pub fn peer_id(&self) -> PeerId {
+        self.node_delegate.peer_id.clone()
+    }
+
+    /// Spawn an async task owned by the service
+    ///
+    /// INTENTION: Give services a safe way to run periodic work, such as
+    /// polling an external API. The task is aborted when the service stops,
+    /// and a panic in it is logged instead of reaching the node.
+    pub fn spawn_background_task(
+        &self,
+        name: &str,
+        task: impl Future<Output = ()> + Send + 'static,
+    ) -> TaskHandle {
+        self.logger.debug(format!(
+            "Spawning background task {name} for service {}",
+            self.service_path
+        ));
+        self.node_delegate.background_tasks.spawn(
+            &self.service_path,
+            name,
+            task,
+            self.logger.clone(),
+        )
+    }
+
+    /// Background tasks of the service, in the order they were spawned
+    ///
+    /// Tasks that ended are listed until the service spawns another task.
+    pub fn list_background_tasks(&self) -> Vec<TaskInfo> {
+        self.node_delegate.background_tasks.list(&self.service_path)
+    }
+
     /// Make a service request from the lifecycle context.
     ///
     /// INTENTION: Allow services during their lifecycle (e.g., init, shutdown)
@@ -535,6 +591,28 @@ pub struct PublishOptions {
 
     /// Target a specific node or service instead of all subscribers
     pub target: Option<String>,
+
+    /// Capacity of the channel bridging events into a subscription stream
+    /// (see `Node::subscribe_stream_with_options`). None uses the default capacity.
+    pub stream_channel_size: Option<usize>,
+
+    /// How long the event stays deliverable to remote nodes. Peers drop copies
+    /// that arrive after it has passed. None means the event never expires.
+    pub ttl: Option<Duration>,
+
+    /// Number the event within its topic so that remote subscribers receive
+    /// this node's events in publish order. Ordering is per publishing node;
+    /// ordering events across nodes needs a coordination service.
+    pub ordered: bool,
+
+    /// Keep the last N events of the topic, this one included, for
+    /// subscribers joining later (see `Node::subscribe_with_replay`).
+    /// None or 0 retains nothing.
+    pub retain_last: Option<usize>,
+
+    /// How the event is handed to local subscribers; by default it is queued
+    /// and `publish` returns without waiting for them.
+    pub dispatch: DispatchMode,
 }
 
 /// Options for registering an action handler
@@ -543,12 +621,30 @@ pub struct PublishOptions {
 /// reducing the need for services to define complete metadata upfront.
 // #[derive(Debug, Clone, Default)] // This line was redundant and removed
 /// Options for registering an event subscription with metadata.
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug)]
 pub struct EventRegistrationOptions {
     /// Description of what the event signifies.
     pub description: Option<String>,
     /// Schema for the event data, for validation and documentation.
     pub data_schema: Option<FieldSchema>,
+    /// Whether failed deliveries to this subscription go to the node's
+    /// dead-letter queue (defaults to true).
+    pub dead_letter: bool,
+    /// Subscription group competing for the events (see `SubscriptionGroups`).
+    /// Each event goes to one member of the group; None or an empty name
+    /// delivers every event to this subscription.
+    pub group: Option<String>,
+}
+
+impl Default for EventRegistrationOptions {
+    fn default() -> Self {
+        Self {
+            description: None,
+            data_schema: None,
+            dead_letter: true,
+            group: None,
+        }
+    }
 }
 
 pub struct ActionRegistrationOptions {
@@ -752,6 +848,9 @@ pub trait NodeDelegate: Send + Sync {
 #[async_trait::async_trait]
 pub trait KeysDelegate: Send + Sync {
     async fn ensure_symmetric_key(&self, key_name: &str) -> Result<ArcValue>;
+
+    /// Replace the symmetric key stored under `key_name`
+    async fn store_symmetric_key(&self, key_name: &str, key: Vec<u8>) -> Result<()>;
 }
 
 /// Registry Delegate trait for registry service operations
@@ -793,6 +892,9 @@ pub trait RegistryDelegate: Send + Sync {
     ) -> Result<()>;
 
     async fn remove_remote_action_handler(&self, topic_path: &TopicPath) -> Result<()>;
+
+    /// Get the health of every node of the cluster known to this node
+    async fn cluster_health(&self) -> HashMap<PeerId, HealthReport>;
 }
 
 /// Remote service lifecycle context
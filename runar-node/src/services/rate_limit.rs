@@ -0,0 +1,175 @@
+// Rate Limiting Middleware
+//
+// This module provides token bucket rate limiting for service requests,
+// either per calling peer or shared by all callers of a service.
+
+use std::collections::HashMap;
+use std::time::Instant;
+
+use anyhow::Result;
+use dashmap::DashMap;
+use thiserror::Error;
+
+use crate::network::transport::PeerId;
+use crate::services::middleware::Middleware;
+use crate::services::RequestContext;
+
+/// Request quota for the services matching one path glob
+///
+/// INTENTION: Describe how many requests per second a service accepts and
+/// how large a burst it tolerates before rejecting callers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RateQuota {
+    /// Sustained requests per second
+    pub max_rps: u32,
+    /// Requests that may be made at once before the sustained rate applies
+    pub burst: u32,
+    /// Give every calling peer its own bucket instead of sharing one
+    pub per_caller: bool,
+}
+
+impl RateQuota {
+    /// Create a new quota
+    pub fn new(max_rps: u32, burst: u32, per_caller: bool) -> Self {
+        Self {
+            max_rps,
+            burst,
+            per_caller,
+        }
+    }
+}
+
+/// Error returned when a request is over its quota
+///
+/// Local callers can downcast the request error to this type. Remote callers
+/// receive a `NetworkError` with `ErrorCode::RateLimited`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+#[error("rate limit exceeded, retry after {retry_after_ms} ms")]
+pub struct RateLimitExceeded {
+    /// Time until the next request would be accepted
+    pub retry_after_ms: u64,
+}
+
+#[derive(Debug)]
+struct TokenBucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn full(quota: &RateQuota) -> Self {
+        Self {
+            tokens: capacity(quota),
+            last_refill: Instant::now(),
+        }
+    }
+
+    /// Take a token, or return how long until one is available
+    fn try_take(&mut self, quota: &RateQuota) -> Result<(), RateLimitExceeded> {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.last_refill).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * quota.max_rps as f64).min(capacity(quota));
+        self.last_refill = now;
+
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            return Ok(());
+        }
+        let retry_after_ms = if quota.max_rps == 0 {
+            u64::MAX
+        } else {
+            ((1.0 - self.tokens) * 1000.0 / quota.max_rps as f64).ceil() as u64
+        };
+        Err(RateLimitExceeded { retry_after_ms })
+    }
+}
+
+/// Bucket size; a burst of zero still lets one request through
+fn capacity(quota: &RateQuota) -> f64 {
+    quota.burst.max(1) as f64
+}
+
+/// Match a service path against a glob where `*` stands for any characters
+fn glob_matches(pattern: &str, path: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = path.strip_prefix(first) else {
+        return false;
+    };
+    let mut parts: Vec<&str> = parts.collect();
+    let Some(last) = parts.pop() else {
+        // No wildcard: the whole path must match
+        return rest.is_empty();
+    };
+    for part in parts {
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(last)
+}
+
+/// Middleware rejecting requests that exceed the quota of their service
+///
+/// INTENTION: Keep a single runaway client from saturating a node. Quotas are
+/// keyed by a service path glob (`*` matches any characters); when several
+/// globs match a service the longest one applies. Buckets live in a DashMap
+/// so concurrent requests to different services do not contend on one lock.
+#[derive(Debug, Default)]
+pub struct RateLimitMiddleware {
+    quotas: HashMap<String, RateQuota>,
+    // Keyed by quota glob and, for per-caller quotas, the calling peer
+    buckets: DashMap<(String, Option<PeerId>), TokenBucket>,
+}
+
+impl RateLimitMiddleware {
+    /// Create a middleware enforcing the given quotas
+    pub fn new(quotas: HashMap<String, RateQuota>) -> Self {
+        Self {
+            quotas,
+            buckets: DashMap::new(),
+        }
+    }
+
+    /// Add the quota for services matching `service_glob`
+    pub fn with_quota(mut self, service_glob: impl Into<String>, quota: RateQuota) -> Self {
+        self.quotas.insert(service_glob.into(), quota);
+        self
+    }
+
+    /// Take a token for a request to `service_path` made by `caller`
+    ///
+    /// `caller` is `None` for requests made on this node.
+    pub fn check(
+        &self,
+        service_path: &str,
+        caller: Option<&PeerId>,
+    ) -> Result<(), RateLimitExceeded> {
+        let Some((glob, quota)) = self
+            .quotas
+            .iter()
+            .filter(|(glob, _)| glob_matches(glob, service_path))
+            .max_by_key(|(glob, _)| glob.len())
+        else {
+            return Ok(());
+        };
+
+        let caller = if quota.per_caller {
+            caller.cloned()
+        } else {
+            None
+        };
+        self.buckets
+            .entry((glob.clone(), caller))
+            .or_insert_with(|| TokenBucket::full(quota))
+            .try_take(quota)
+    }
+}
+
+impl Middleware for RateLimitMiddleware {
+    fn before_request(&self, context: &RequestContext) -> Result<()> {
+        self.check(&context.service_path(), context.peer_info())?;
+        Ok(())
+    }
+}
@@ -9,13 +9,16 @@
 // - internal/registry/services/list
 // - internal/registry/services/{service_path}
 // - internal/registry/services/{service_path}/state
+// - internal/registry/health/cluster
 
 use anyhow::{anyhow, Result};
 use async_trait::async_trait;
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use crate::routing::TopicPath;
 use crate::services::abstract_service::ServiceState;
+use crate::services::health::HealthReport;
 use crate::services::{LifecycleContext, RegistryDelegate, RequestContext};
 use crate::AbstractService;
 use runar_common::logging::Logger;
@@ -140,6 +143,35 @@ impl RegistryService {
         Ok(())
     }
 
+    /// Register the cluster health action
+    async fn register_cluster_health_action(&self, context: &LifecycleContext) -> Result<()> {
+        let self_clone = self.clone();
+        context
+            .register_action(
+                "health/cluster",
+                Arc::new(move |_params, ctx| {
+                    let inner_self = self_clone.clone();
+                    Box::pin(async move { inner_self.handle_cluster_health(ctx).await })
+                }),
+            )
+            .await?;
+        context.logger.debug("Registered health/cluster action");
+        Ok(())
+    }
+
+    /// Handler for the health of every node of the cluster, keyed by peer ID
+    async fn handle_cluster_health(&self, ctx: RequestContext) -> Result<ArcValue> {
+        ctx.logger.debug("Reporting cluster health");
+        let reports: HashMap<String, HealthReport> = self
+            .registry_delegate
+            .cluster_health()
+            .await
+            .into_iter()
+            .map(|(peer_id, report)| (peer_id.to_string(), report))
+            .collect();
+        Ok(ArcValue::from_struct(reports))
+    }
+
     /// Handler for listing all services
     async fn handle_list_services(
         &self,
@@ -279,6 +311,8 @@ impl AbstractService for RegistryService {
             .logger
             .debug("Registered handler for service state with path parameter");
 
+        self.register_cluster_health_action(&context).await?;
+
         context
             .logger
             .info("Registry Service initialization complete");
@@ -289,6 +323,11 @@ impl AbstractService for RegistryService {
             .write()
             .await
             .register::<ServiceState>()?;
+        context
+            .serializer
+            .write()
+            .await
+            .register_debug::<HealthReport>()?;
 
         Ok(())
     }
@@ -12,7 +12,7 @@ use tokio::sync::RwLock;
 use uuid::Uuid;
 
 use crate::network::transport::{
-    NetworkMessage, NetworkMessagePayloadItem, NetworkTransport, PeerId,
+    ErrorCode, NetworkError, NetworkMessage, NetworkMessagePayloadItem, NetworkTransport, PeerId,
 };
 use crate::routing::TopicPath;
 use crate::services::abstract_service::AbstractService;
@@ -48,12 +48,22 @@ pub struct RemoteService {
     local_node_id: PeerId,
 
     /// Pending requests awaiting responses
-    pending_requests: Arc<RwLock<HashMap<String, tokio::sync::oneshot::Sender<Result<ArcValue>>>>>,
+    pending_requests: PendingRequests,
 
     /// Request timeout in milliseconds
     request_timeout_ms: u64,
 }
 
+/// A request sent to a remote peer, awaiting its response
+pub struct PendingRequest {
+    /// Peer the request was sent to
+    pub peer_id: PeerId,
+    pub sender: tokio::sync::oneshot::Sender<Result<ArcValue>>,
+}
+
+/// Requests awaiting a response, by correlation ID
+pub type PendingRequests = Arc<RwLock<HashMap<String, PendingRequest>>>;
+
 /// Configuration for creating a RemoteService instance.
 pub struct RemoteServiceConfig {
     pub name: String,
@@ -69,8 +79,7 @@ pub struct RemoteServiceDependencies {
     pub network_transport: Arc<RwLock<Option<Box<dyn NetworkTransport>>>>,
     pub serializer: Arc<RwLock<SerializerRegistry>>,
     pub local_node_id: PeerId, // ID of the local node
-    pub pending_requests:
-        Arc<RwLock<HashMap<String, tokio::sync::oneshot::Sender<Result<ArcValue>>>>>,
+    pub pending_requests: PendingRequests,
     pub logger: Arc<Logger>,
 }
 
@@ -228,10 +237,13 @@ impl RemoteService {
                 let (tx, rx) = tokio::sync::oneshot::channel();
 
                 // Store the response channel
-                pending_requests
-                    .write()
-                    .await
-                    .insert(request_id.clone(), tx);
+                pending_requests.write().await.insert(
+                    request_id.clone(),
+                    PendingRequest {
+                        peer_id: peer_id.clone(),
+                        sender: tx,
+                    },
+                );
 
                 logger.debug(format!(
                     "📝 [RemoteService] Stored response channel for request ID: {request_id}"
@@ -267,7 +279,13 @@ impl RemoteService {
                         action_topic_path.as_str().to_string(),
                         payload_vec,
                         request_id.clone(),
-                    )],
+                    )
+                    .with_format(serializer.backend())],
+                    signature: None,
+                    hop_count: 0,
+                    visited_peers: Vec::new(),
+                    auth_tag: None,
+                    expires_at: None,
                 };
 
                 // Send the request
@@ -278,7 +296,8 @@ impl RemoteService {
                         ));
                         // Clean up the pending request
                         pending_requests.write().await.remove(&request_id);
-                        return Err(anyhow::anyhow!("Failed to send request: {e}"));
+                        // Keep the NetworkError so callers can inspect its code
+                        return Err(anyhow::Error::from(e).context("Failed to send request"));
                     } else {
                         logger.info(format!(
                             "✅ [RemoteService] Request sent successfully - ID: {request_id}, waiting for response..."
@@ -288,7 +307,11 @@ impl RemoteService {
                     logger.error(format!(
                         "❌ [RemoteService] No transport available for request {request_id}"
                     ));
-                    return Err(anyhow::anyhow!("Network transport not available"));
+                    return Err(NetworkError::TransportError(
+                        ErrorCode::NotRunning,
+                        "Network transport not available".to_string(),
+                    )
+                    .into());
                 }
 
                 logger.info(format!(
@@ -309,7 +332,7 @@ impl RemoteService {
                         logger.error(format!(
                             "❌ [RemoteService] Remote service error for request {request_id}: {e}"
                         ));
-                        Err(anyhow::anyhow!("Remote service error: {e}"))
+                        Err(e.context("Remote service error"))
                     }
                     Ok(Err(_)) => {
                         // Clean up the pending request
@@ -317,7 +340,11 @@ impl RemoteService {
                         logger.error(format!(
                             "❌ [RemoteService] Response channel closed for request {request_id}",
                         ));
-                        Err(anyhow::anyhow!("Response channel closed"))
+                        Err(NetworkError::ConnectionError(
+                            ErrorCode::ConnectionFailed,
+                            format!("Response channel closed for request {request_id}"),
+                        )
+                        .into())
                     }
                     Err(_) => {
                         // Clean up the pending request
@@ -325,7 +352,11 @@ impl RemoteService {
                         logger.error(format!(
                             "⏰ [RemoteService] Request timeout after {request_timeout_ms}ms - ID: {request_id}",
                         ));
-                        Err(anyhow::anyhow!("Request timeout"))
+                        Err(NetworkError::ConnectionError(
+                            ErrorCode::Timeout,
+                            format!("Request timed out after {request_timeout_ms}ms"),
+                        )
+                        .into())
                     }
                 }
             })
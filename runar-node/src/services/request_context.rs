@@ -11,16 +11,19 @@
 // and consistent handling. The context avoids data duplication by
 // deriving values from the TopicPath when needed.
 
-use crate::node::Node; // Added for concrete type
+use crate::network::transport::PeerId;
+use crate::node::{Node, NodeConfig}; // Added for concrete type
 use crate::routing::TopicPath;
 use crate::services::NodeDelegate;
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use runar_common::{
     logging::{Component, Logger, LoggingContext},
     types::ArcValue,   // Added ValueCategory for AsArcValue for S
     types::AsArcValue, // Moved from this file
 };
 use std::fmt::Debug;
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
 
 // AsArcValue trait and implementations moved to runar_common::types
 // -----------------------------------------------------------------------------
@@ -56,6 +59,12 @@ pub struct RequestContext {
 
     /// Node delegate for making requests or publishing events
     pub(crate) node_delegate: Arc<Node>,
+
+    /// Cancelled when the caller gives up on this request (drop or timeout)
+    cancel_token: CancellationToken,
+
+    /// Peer that sent this request, or None when it was made on this node
+    caller: Option<PeerId>,
 }
 
 // Manual implementation of Debug for RequestContext
@@ -68,6 +77,8 @@ impl fmt::Debug for RequestContext {
             .field("metadata", &self.metadata)
             .field("logger", &"<Logger>") // Avoid trying to Debug the Logger
             .field("path_params", &self.path_params)
+            .field("cancelled", &self.cancel_token.is_cancelled())
+            .field("caller", &self.caller)
             .finish()
     }
 }
@@ -81,6 +92,8 @@ impl Clone for RequestContext {
             logger: self.logger.clone(),
             path_params: self.path_params.clone(),
             node_delegate: self.node_delegate.clone(),
+            cancel_token: self.cancel_token.clone(),
+            caller: self.caller.clone(),
         }
     }
 }
@@ -116,9 +129,44 @@ impl RequestContext {
             logger: action_logger,
             node_delegate,
             path_params: HashMap::new(),
+            cancel_token: CancellationToken::new(),
+            caller: None,
         }
     }
 
+    /// Use the given cancel token for this request
+    ///
+    /// Use builder-style methods instead of specialized constructors.
+    pub fn with_cancel_token(mut self, cancel_token: CancellationToken) -> Self {
+        self.cancel_token = cancel_token;
+        self
+    }
+
+    /// Get the cancel token for this request
+    ///
+    /// INTENTION: Let long-running actions cooperatively stop work once the
+    /// caller has gone away or the request timed out, by checking
+    /// `is_cancelled()` at yield points or awaiting `cancelled()`.
+    pub fn cancel_token(&self) -> &CancellationToken {
+        &self.cancel_token
+    }
+
+    /// Mark the request as sent by a remote peer
+    ///
+    /// Use builder-style methods instead of specialized constructors.
+    pub fn with_peer_info(mut self, caller: PeerId) -> Self {
+        self.caller = Some(caller);
+        self
+    }
+
+    /// Get the peer that sent this request
+    ///
+    /// INTENTION: Let per-caller policies such as rate limits tell remote
+    /// callers apart. Returns `None` for requests made on this node.
+    pub fn peer_info(&self) -> Option<&PeerId> {
+        self.caller.as_ref()
+    }
+
     /// Add metadata to a RequestContext
     ///
     /// Use builder-style methods instead of specialized constructors.
@@ -137,6 +185,16 @@ impl RequestContext {
         self.topic_path.service_path()
     }
 
+    /// Get the path of another action of this service
+    ///
+    /// INTENTION: Build the full path of a sibling action from this request's
+    /// network and service, e.g. to pass it to `request` or store it, without
+    /// hardcoding the network ID.
+    pub fn relative_path(&self, action: &str) -> Result<TopicPath> {
+        TopicPath::relative(&self.topic_path.service_path(), action, &self.topic_path)
+            .map_err(|e| anyhow!("Invalid action {action}: {e}"))
+    }
+
     /// Helper method to log debug level message
     ///
     /// INTENTION: Provide a convenient way to log debug messages with the
@@ -217,15 +275,57 @@ impl RequestContext {
     /// - Full path with network ID: "network:service/action" (used as is)
     /// - Path with service: "service/action" (network ID added)
     /// - Simple action: "action" (both service path and network ID added - calls own service)
+    ///
+    /// The nested request gets a child of this request's cancel token, so
+    /// cancelling the outer request cancels the whole call tree, while a
+    /// nested call timing out does not cancel its caller.
     pub async fn request<P, T>(&self, path: impl Into<String>, payload: Option<P>) -> Result<T>
     where
         P: AsArcValue + Send + Sync,
         T: 'static + Send + Sync + Clone + Debug + for<'de> serde::Deserialize<'de>,
     {
-        let path_string = path.into();
+        let full_path = self.resolve_path(path.into());
+
+        self.logger
+            .debug(format!("Making request to processed path: {full_path}"));
+
+        // Call Node::request_with_cancel_token, specifying the generic types P and T.
+        // The node itself will handle deserialization to T.
+        self.node_delegate
+            .request_with_cancel_token::<P, T>(full_path, payload, self.cancel_token.child_token())
+            .await
+    }
+
+    /// Make a service request that must complete within `timeout`
+    ///
+    /// INTENTION: Let an action give a sub-call part of its own remaining
+    /// budget (see `NodeConfig::default_request_timeout`). Paths are resolved
+    /// and cancellation is inherited as for `request`.
+    pub async fn request_with_timeout<P, T>(
+        &self,
+        path: impl Into<String>,
+        payload: Option<P>,
+        timeout: Duration,
+    ) -> Result<T>
+    where
+        P: AsArcValue + Send + Sync,
+        T: 'static + Send + Sync + Clone + Debug + for<'de> serde::Deserialize<'de>,
+    {
+        let full_path = self.resolve_path(path.into());
+        self.node_delegate
+            .request_within::<P, T>(
+                full_path,
+                payload,
+                self.cancel_token.child_token(),
+                Some(timeout),
+            )
+            .await
+    }
 
+    /// Resolve a request path relative to this request's network and service
+    fn resolve_path(&self, path_string: String) -> String {
         // Process the path based on its format
-        let full_path = if path_string.contains(':') {
+        if path_string.contains(':') {
             // Already has network ID, use as is
             path_string
         } else if path_string.contains('/') {
@@ -242,14 +342,12 @@ impl RequestContext {
                 self.topic_path.service_path(),
                 path_string
             )
-        };
-
-        self.logger
-            .debug(format!("Making request to processed path: {full_path}"));
+        }
+    }
 
-        // Call Node::request, specifying the generic types P and T.
-        // Node::request itself will handle deserialization to T.
-        self.node_delegate.request::<P, T>(full_path, payload).await
+    /// Configuration of the node handling this request
+    pub fn node_config(&self) -> &NodeConfig {
+        &self.node_delegate.config
     }
 }
 
@@ -0,0 +1,196 @@
+// Response Streams
+//
+// This module provides the output type for actions that return their result
+// as a sequence of values instead of a single ArcValue.
+
+use anyhow::Result;
+use runar_common::types::{ArcValue, ErasedArc, ValueCategory};
+use std::fmt;
+use std::pin::Pin;
+use std::sync::Mutex;
+use std::task::{Context, Poll};
+use tokio::sync::mpsc;
+use tokio_stream::{Stream, StreamExt};
+
+/// Number of items buffered between the producer and the consumer of a stream
+pub const DEFAULT_RESPONSE_STREAM_BUFFER: usize = 32;
+
+/// Number of frames of a remote stream the requesting node buffers, out of
+/// order or waiting for the consumer, before it fails the stream
+pub const MAX_BUFFERED_STREAM_FRAMES: usize = 1024;
+
+/// Sequence of values produced by an action
+///
+/// INTENTION: Let actions with large result sets (database queries, file
+/// reads) hand out values as they are produced instead of buffering them in
+/// one response. An action returning `Result<ResponseStream<T>>` is streamed
+/// by the `#[action]` macro; callers consume it with `Node::request_stream`.
+/// Remote callers receive the values as individual frames, so `T` must be
+/// serializable like any other action result.
+///
+/// The stream ends after the last value, or after the first error.
+pub struct ResponseStream<T> {
+    receiver: mpsc::Receiver<Result<T>>,
+}
+
+/// Producer side of a `ResponseStream`
+pub struct ResponseStreamSender<T> {
+    sender: mpsc::Sender<Result<T>>,
+}
+
+impl<T> ResponseStreamSender<T> {
+    /// Send the next value, waiting while the consumer is behind
+    ///
+    /// Returns false once the consumer has dropped the stream, so the
+    /// producer can stop early.
+    pub async fn send(&self, value: T) -> bool {
+        self.sender.send(Ok(value)).await.is_ok()
+    }
+
+    /// End the stream with an error
+    pub async fn fail(self, error: anyhow::Error) {
+        let _ = self.sender.send(Err(error)).await;
+    }
+}
+
+impl<T: Send + 'static> ResponseStream<T> {
+    /// Create a stream and the sender used to produce its values
+    ///
+    /// The stream ends when the sender is dropped.
+    pub fn channel(buffer: usize) -> (ResponseStreamSender<T>, Self) {
+        let (sender, receiver) = mpsc::channel(buffer.max(1));
+        (ResponseStreamSender { sender }, Self { receiver })
+    }
+
+    /// Create a stream yielding the given values
+    pub fn from_items<I>(items: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        I::IntoIter: Send + 'static,
+    {
+        let (sender, stream) = Self::channel(DEFAULT_RESPONSE_STREAM_BUFFER);
+        let items = items.into_iter();
+        tokio::spawn(async move {
+            for item in items {
+                if !sender.send(item).await {
+                    break;
+                }
+            }
+        });
+        stream
+    }
+
+    /// Create a stream forwarding the values of another stream
+    pub fn from_stream<S>(stream: S) -> Self
+    where
+        S: Stream<Item = T> + Send + 'static,
+    {
+        Self::from_try_stream(stream.map(Ok))
+    }
+
+    /// Create a stream forwarding the results of another stream
+    ///
+    /// The stream ends after the first error.
+    pub fn from_try_stream<S>(stream: S) -> Self
+    where
+        S: Stream<Item = Result<T>> + Send + 'static,
+    {
+        let (sender, response_stream) = Self::channel(DEFAULT_RESPONSE_STREAM_BUFFER);
+        tokio::spawn(async move {
+            let mut stream = Box::pin(stream);
+            while let Some(item) = stream.next().await {
+                match item {
+                    Ok(value) => {
+                        if !sender.send(value).await {
+                            break;
+                        }
+                    }
+                    Err(e) => {
+                        sender.fail(e).await;
+                        break;
+                    }
+                }
+            }
+        });
+        response_stream
+    }
+
+    /// Wrap the stream in an ArcValue so it can be returned by an action handler
+    ///
+    /// `convert` turns each value into the ArcValue sent to the caller; the
+    /// `#[action]` macro passes `new_primitive` or `from_struct` as appropriate.
+    pub fn into_arc_value<F>(self, convert: F) -> ArcValue
+    where
+        F: Fn(T) -> ArcValue + Send + 'static,
+    {
+        let stream = self.try_map(move |value| Ok(convert(value)));
+        ArcValue::new(
+            ErasedArc::from_value(ResponseStreamHandle(Mutex::new(Some(stream)))),
+            ValueCategory::Struct,
+        )
+    }
+
+    /// Convert every value, ending the stream at the first conversion error
+    pub(crate) fn try_map<U, F>(mut self, convert: F) -> ResponseStream<U>
+    where
+        U: Send + 'static,
+        F: Fn(T) -> Result<U> + Send + 'static,
+    {
+        let (sender, stream) = ResponseStream::channel(DEFAULT_RESPONSE_STREAM_BUFFER);
+        tokio::spawn(async move {
+            while let Some(item) = self.receiver.recv().await {
+                match item.and_then(&convert) {
+                    Ok(value) => {
+                        if !sender.send(value).await {
+                            break;
+                        }
+                    }
+                    Err(e) => {
+                        sender.fail(e).await;
+                        break;
+                    }
+                }
+            }
+        });
+        stream
+    }
+}
+
+impl ResponseStream<ArcValue> {
+    /// Take the stream out of a value created by `into_arc_value`
+    ///
+    /// Returns None if the value does not hold a stream or the stream was
+    /// already taken.
+    pub fn take_from(value: &ArcValue) -> Option<Self> {
+        let handle = value
+            .value
+            .as_ref()?
+            .as_arc::<ResponseStreamHandle>()
+            .ok()?;
+        let stream = handle.0.lock().unwrap_or_else(|e| e.into_inner()).take();
+        stream
+    }
+}
+
+impl<T> Stream for ResponseStream<T> {
+    type Item = Result<T>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.receiver.poll_recv(cx)
+    }
+}
+
+impl<T> fmt::Debug for ResponseStream<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ResponseStream").finish_non_exhaustive()
+    }
+}
+
+/// Holder placed in an ArcValue; the stream can be taken out exactly once
+struct ResponseStreamHandle(Mutex<Option<ResponseStream<ArcValue>>>);
+
+impl fmt::Debug for ResponseStreamHandle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ResponseStreamHandle")
+    }
+}
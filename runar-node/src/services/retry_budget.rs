@@ -0,0 +1,133 @@
+// Retry Budget
+//
+// This module provides the retry budget the Node applies to the retries of
+// requests to one service path, so that callers retrying at once cannot
+// multiply the load on a service that is already failing, and the backoff
+// between two attempts.
+
+use rand::Rng;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Seconds of the minimum retry rate the budget can save up
+const BUDGET_WINDOW_SECS: f64 = 10.0;
+
+/// Upper bound of the delay before the first retry
+pub const RETRY_BACKOFF_BASE: Duration = Duration::from_millis(50);
+
+/// Upper bound of the delay before any retry
+pub const RETRY_BACKOFF_MAX: Duration = Duration::from_secs(2);
+
+/// Delay before retry number `attempt` (starting at 1) of a request
+///
+/// INTENTION: Give a failing service time to recover, and spread the
+/// retries of callers that failed together. The bound doubles with every
+/// attempt, from `RETRY_BACKOFF_BASE` up to `RETRY_BACKOFF_MAX`, and the
+/// delay is drawn at random below it ("full jitter").
+pub fn retry_backoff(attempt: u32) -> Duration {
+    let factor = 1u32 << attempt.saturating_sub(1).min(16);
+    let bound = RETRY_BACKOFF_BASE
+        .saturating_mul(factor)
+        .min(RETRY_BACKOFF_MAX);
+    bound.mul_f64(rand::rng().random::<f64>())
+}
+
+/// Retries allowed for the requests to one service path
+///
+/// INTENTION: Bound retries to a fraction of the traffic, with a floor so
+/// that a service receiving few requests can still be retried. Every request
+/// adds `max_retry_fraction` tokens to the budget, the budget refills at
+/// `min_retries_per_second`, and every retry takes one token.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryBudget {
+    /// Retries allowed per request made (0.0 - 1.0)
+    pub max_retry_fraction: f64,
+    /// Retries allowed per second regardless of the request rate
+    pub min_retries_per_second: u32,
+}
+
+impl Default for RetryBudget {
+    fn default() -> Self {
+        Self {
+            max_retry_fraction: 0.2,
+            min_retries_per_second: 10,
+        }
+    }
+}
+
+impl RetryBudget {
+    /// Create a new retry budget
+    pub fn new(max_retry_fraction: f64, min_retries_per_second: u32) -> Self {
+        Self {
+            max_retry_fraction,
+            min_retries_per_second,
+        }
+    }
+
+    /// Largest number of retries the budget saves up
+    fn capacity(&self) -> f64 {
+        (self.min_retries_per_second as f64 * BUDGET_WINDOW_SECS).max(1.0)
+    }
+}
+
+#[derive(Debug)]
+struct BucketState {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+/// Token bucket shared by all callers retrying requests to one service path
+///
+/// INTENTION: Spend the retry budget of a service node-wide, so that one
+/// runaway service exhausts only its own budget. The bucket starts with one
+/// second of the minimum retry rate.
+#[derive(Debug)]
+pub struct RetryBucket {
+    budget: RetryBudget,
+    state: Mutex<BucketState>,
+}
+
+impl RetryBucket {
+    /// Create a new bucket for `budget`
+    pub fn new(budget: RetryBudget) -> Self {
+        Self {
+            state: Mutex::new(BucketState {
+                tokens: budget.min_retries_per_second as f64,
+                last_refill: Instant::now(),
+            }),
+            budget,
+        }
+    }
+
+    /// Get the budget of this bucket
+    pub fn budget(&self) -> &RetryBudget {
+        &self.budget
+    }
+
+    /// Record a request, adding its share of retries to the budget
+    pub fn record_request(&self) {
+        let mut state = self.refilled();
+        state.tokens = (state.tokens + self.budget.max_retry_fraction).min(self.budget.capacity());
+    }
+
+    /// Take a token for a retry, or return false when the budget is exhausted
+    pub fn try_retry(&self) -> bool {
+        let mut state = self.refilled();
+        if state.tokens >= 1.0 {
+            state.tokens -= 1.0;
+            return true;
+        }
+        false
+    }
+
+    /// Lock the state after adding the tokens refilled since the last call
+    fn refilled(&self) -> std::sync::MutexGuard<'_, BucketState> {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let now = Instant::now();
+        let elapsed = now.duration_since(state.last_refill).as_secs_f64();
+        state.tokens = (state.tokens + elapsed * self.budget.min_retries_per_second as f64)
+            .min(self.budget.capacity());
+        state.last_refill = now;
+        state
+    }
+}
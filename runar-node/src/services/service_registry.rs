@@ -26,8 +26,10 @@ use std::sync::Arc;
 use tokio::sync::RwLock;
 use uuid::Uuid;
 
+use crate::network::transport::PeerId;
 use crate::routing::{PathTrie, TopicPath};
 use crate::services::abstract_service::{AbstractService, ServiceState};
+use crate::services::health::HealthReport;
 use crate::services::{ActionHandler, EventContext, RemoteService};
 use runar_common::logging::Logger;
 use runar_common::types::schemas::{ActionMetadata, EventMetadata, ServiceMetadata};
@@ -258,21 +260,108 @@ impl ServiceRegistry {
         Ok(())
     }
 
-    pub async fn remove_remote_service(&self, service_topic: &TopicPath) -> Result<()> {
+    /// Remove a local service
+    ///
+    /// INTENTION: Undo `register_local_service` and everything registered under
+    /// the service path: its action handlers, the event subscriptions on its
+    /// topics and its lifecycle state. Returns the removed entry so the caller
+    /// can stop the service.
+    pub async fn remove_local_service(
+        &self,
+        service_topic: &TopicPath,
+    ) -> Result<Arc<ServiceEntry>> {
+        let service_entry = self
+            .local_services_list
+            .write()
+            .await
+            .remove(service_topic)
+            .ok_or_else(|| anyhow!("Service not found for topic: {}", service_topic))?;
+        self.logger
+            .info(format!("Removing local service: {service_topic}"));
+
+        self.local_services
+            .write()
+            .await
+            .remove_values(service_topic);
+        self.service_states_by_service_path
+            .write()
+            .await
+            .remove(service_topic.as_str());
+
+        // Every action registered under the service path, including templates
+        let all_actions = TopicPath::new(
+            &format!("{}/>", service_topic.service_path()),
+            &service_topic.network_id(),
+        )
+        .map_err(|e| anyhow!("Invalid service path {service_topic}: {e}"))?;
+        {
+            let mut handlers = self.local_action_handlers.write().await;
+            let registered: Vec<TopicPath> = handlers
+                .find_matches(&all_actions)
+                .into_iter()
+                .map(|mat| mat.content.1)
+                .collect();
+            for action_topic in &registered {
+                handlers.remove_values(action_topic);
+            }
+        }
+
+        let subscription_ids: Vec<String> = self
+            .subscription_id_to_service_topic_path
+            .read()
+            .await
+            .iter()
+            .filter(|(_, topic)| *topic == service_topic)
+            .map(|(id, _)| id.clone())
+            .collect();
+        for subscription_id in subscription_ids {
+            if let Err(e) = self.unsubscribe_local(&subscription_id).await {
+                self.logger.warn(format!(
+                    "Failed to remove subscription {subscription_id} of {service_topic}: {e}"
+                ));
+            }
+            self.subscription_id_to_service_topic_path
+                .write()
+                .await
+                .remove(&subscription_id);
+        }
+        self.local_events_by_service
+            .write()
+            .await
+            .remove_values(service_topic);
+
+        Ok(service_entry)
+    }
+
+    /// Remove the service a peer provides at a topic
+    ///
+    /// INTENTION: Several peers can provide a service at the same path, e.g. the
+    /// members of a cluster. Remote action handlers are not tied to their peer,
+    /// so all the handlers of the services at this path are removed and the
+    /// services of the other peers register theirs again.
+    pub async fn remove_remote_service(
+        &self,
+        service_topic: &TopicPath,
+        peer_id: &PeerId,
+    ) -> Result<()> {
         //get the service.. so we can call .stop() on it
         let services = self.remote_services.read().await.find(service_topic);
 
-        if services.is_empty() {
-            return Err(anyhow!("Service not found for topic: {}", service_topic));
+        if !services.iter().any(|service| service.peer_id() == peer_id) {
+            return Err(anyhow!(
+                "Service not found for topic: {} from peer: {}",
+                service_topic,
+                peer_id
+            ));
         }
 
         let registry_delegate = Arc::new(self.clone());
-        for service in services {
+        for service in &services {
             let context =
                 super::RemoteLifecycleContext::new(&service.service_topic, self.logger.clone())
                     .with_registry_delegate(registry_delegate.clone());
 
-            // Initialize the service - this triggers handler registration via the context
+            // Stopping the service removes the handlers of its actions
             if let Err(e) = service.stop(context).await {
                 self.logger.error(format!(
                     "Failed to stop remote service '{}' error: {}",
@@ -286,7 +375,23 @@ impl ServiceRegistry {
         self.remote_services
             .write()
             .await
-            .remove_values(service_topic);
+            .remove_handler(service_topic, |service| service.peer_id() == peer_id);
+
+        for service in services
+            .iter()
+            .filter(|service| service.peer_id() != peer_id)
+        {
+            let context =
+                super::RemoteLifecycleContext::new(&service.service_topic, self.logger.clone())
+                    .with_registry_delegate(registry_delegate.clone());
+            if let Err(e) = service.init(context).await {
+                self.logger.error(format!(
+                    "Failed to restore remote service '{}' error: {}",
+                    service.path(),
+                    e
+                ));
+            }
+        }
 
         Ok(())
     }
@@ -294,6 +399,7 @@ impl ServiceRegistry {
     /// Register a remote service
     ///
     /// INTENTION: Register a service that exists on a remote node, making it available for local requests.
+    /// Each peer provides at most one service for a given topic.
     pub async fn register_remote_service(&self, service: Arc<RemoteService>) -> Result<()> {
         let service_topic = service.service_topic.clone();
         let service_path = service.path().to_string();
@@ -306,18 +412,20 @@ impl ServiceRegistry {
         // Add to remote services using PathTrie
         {
             let mut services = self.remote_services.write().await;
-            let matches = services.find_matches(&service_topic);
+            let registered = services
+                .find_matches(&service_topic)
+                .iter()
+                .any(|existing| existing.content.peer_id() == &peer_id);
 
-            if matches.is_empty() {
-                // No existing services for this topic
-                services.set_value(service_topic, service);
-            } else {
-                //return an error.. just one service shuold exist for a given topic
+            if registered {
+                //return an error.. just one service per peer shuold exist for a given topic
                 return Err(anyhow!(
-                    "Service already exists for topic: {}",
-                    service_topic
+                    "Service already exists for topic: {} from peer: {}",
+                    service_topic,
+                    peer_id
                 ));
             }
+            services.set_value(service_topic, service);
         }
 
         Ok(())
@@ -587,6 +695,32 @@ impl ServiceRegistry {
         result
     }
 
+    /// Get the topics (or patterns) with local subscribers, without duplicates
+    ///
+    /// INTENTION: Tell peers which events this node listens to, so that they
+    /// only send it those.
+    pub async fn get_local_subscription_topics(&self) -> Vec<String> {
+        let local_ids = self.subscription_id_to_service_topic_path.read().await;
+        let topics = self.subscription_id_to_topic_path.read().await;
+        let mut result: Vec<String> = local_ids
+            .keys()
+            .filter_map(|subscription_id| topics.get(subscription_id))
+            .map(|topic_path| topic_path.as_str().to_string())
+            .collect();
+        result.sort();
+        result.dedup();
+        result
+    }
+
+    /// Get the topic (or pattern) a subscription was registered for
+    pub async fn get_subscription_topic(&self, subscription_id: &str) -> Option<TopicPath> {
+        self.subscription_id_to_topic_path
+            .read()
+            .await
+            .get(subscription_id)
+            .cloned()
+    }
+
     /// Get remote event subscribers
     ///
     /// INTENTION: Find all remote subscribers for a specific event topic.
@@ -900,8 +1034,14 @@ impl ServiceRegistry {
             let service_topic_path =
                 TopicPath::new(search_path.as_str(), &network_id_string).unwrap();
 
-            // Get actions metadata for this service - create a wildcard path
-            let actions = self.get_actions_metadata(&service_topic_path).await;
+            // Get actions metadata for this service, including actions nested
+            // below it such as `raft/request_vote/{peer_id}`
+            let actions_topic_path = TopicPath::new(
+                &format!("{service_path}/>", service_path = service.path()),
+                &network_id_string,
+            )
+            .unwrap();
+            let actions = self.get_actions_metadata(&actions_topic_path).await;
 
             // Get events metadata for this service - create a wildcard path
             let events = self.get_events_metadata(&service_topic_path).await;
@@ -920,6 +1060,7 @@ impl ServiceRegistry {
                     events,
                     registration_time: service_entry.registration_time,
                     last_start_time: service_entry.last_start_time,
+                    visibility: service.visibility(),
                 },
             );
         }
@@ -961,8 +1102,14 @@ impl crate::services::RegistryDelegate for ServiceRegistry {
             let service_topic_path =
                 TopicPath::new(search_path.as_str(), &network_id_string).unwrap();
 
-            // Get actions metadata for this service - create a wildcard path
-            let actions = self.get_actions_metadata(&service_topic_path).await;
+            // Get actions metadata for this service, including actions nested
+            // below it such as `raft/request_vote/{peer_id}`
+            let actions_topic_path = TopicPath::new(
+                &format!("{service_path}/>", service_path = service.path()),
+                &network_id_string,
+            )
+            .unwrap();
+            let actions = self.get_actions_metadata(&actions_topic_path).await;
 
             // Get events metadata for this service - create a wildcard path
             let events = self.get_events_metadata(&service_topic_path).await;
@@ -978,6 +1125,7 @@ impl crate::services::RegistryDelegate for ServiceRegistry {
                 events,
                 registration_time: service_entry.registration_time,
                 last_start_time: service_entry.last_start_time,
+                visibility: service.visibility(),
             });
         }
 
@@ -1007,4 +1155,9 @@ impl crate::services::RegistryDelegate for ServiceRegistry {
     async fn remove_remote_action_handler(&self, topic_path: &TopicPath) -> Result<()> {
         self.remove_remote_action_handler(topic_path).await
     }
+
+    /// The registry does not track peers; the node reports the cluster health
+    async fn cluster_health(&self) -> HashMap<PeerId, HealthReport> {
+        HashMap::new()
+    }
 }
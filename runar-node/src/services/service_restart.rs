@@ -0,0 +1,165 @@
+// Service Restart
+//
+// This module provides the policy a node follows when an action of one of its
+// local services panics, and the restart counts it keeps per service.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use crate::routing::TopicPath;
+
+/// Default number of restarts before a service is marked unhealthy
+pub const DEFAULT_MAX_RESTARTS: u32 = 3;
+
+/// Default delay between a panic and the restart of the service
+pub const DEFAULT_RESTART_BACKOFF: Duration = Duration::from_secs(1);
+
+/// How a service is restarted after one of its actions panicked
+///
+/// INTENTION: A panic may leave the service's state inconsistent, so the
+/// node stops and initializes it again after `restart_backoff`. Once a
+/// service was restarted `max_restarts` times, the next panic marks it
+/// `Unhealthy` and requests are no longer dispatched to it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ServiceRestartPolicy {
+    /// Restarts allowed over the lifetime of the service
+    pub max_restarts: u32,
+    /// Delay between the panic and the restart
+    pub restart_backoff: Duration,
+}
+
+impl ServiceRestartPolicy {
+    pub fn new(max_restarts: u32, restart_backoff: Duration) -> Self {
+        Self {
+            max_restarts,
+            restart_backoff,
+        }
+    }
+}
+
+impl Default for ServiceRestartPolicy {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_RESTARTS, DEFAULT_RESTART_BACKOFF)
+    }
+}
+
+/// What to do with a service whose action panicked
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum RestartDecision {
+    /// Restart the service; this is its `n`th restart
+    Restart(u32),
+    /// A restart is already pending, it covers this panic too
+    Pending,
+    /// The service used up its restarts
+    Unhealthy,
+}
+
+#[derive(Debug, Default)]
+struct RestartState {
+    restarts: u32,
+    pending: bool,
+    unhealthy: bool,
+}
+
+/// Restart counts of the local services, by network ID and service path
+///
+/// A service path registered under several networks is a separate service
+/// in each of them, with its own restart budget.
+#[derive(Debug, Default)]
+pub(crate) struct ServiceRestarts {
+    services: Mutex<HashMap<(String, String), RestartState>>,
+}
+
+/// The key of the service of `topic_path`, which may be a service or action topic
+fn service_key(topic_path: &TopicPath) -> (String, String) {
+    (topic_path.network_id(), topic_path.service_path())
+}
+
+impl ServiceRestarts {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a panic of an action of the service of `topic_path` and decide
+    /// what to do
+    pub(crate) fn record_panic(
+        &self,
+        topic_path: &TopicPath,
+        policy: &ServiceRestartPolicy,
+    ) -> RestartDecision {
+        let mut services = self.services.lock().unwrap_or_else(|e| e.into_inner());
+        let state = services.entry(service_key(topic_path)).or_default();
+        if state.unhealthy {
+            return RestartDecision::Unhealthy;
+        }
+        if state.pending {
+            return RestartDecision::Pending;
+        }
+        if state.restarts >= policy.max_restarts {
+            state.unhealthy = true;
+            return RestartDecision::Unhealthy;
+        }
+        state.restarts += 1;
+        state.pending = true;
+        RestartDecision::Restart(state.restarts)
+    }
+
+    /// Record that the pending restart of the service of `topic_path` completed
+    pub(crate) fn restart_finished(&self, topic_path: &TopicPath) {
+        let mut services = self.services.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(state) = services.get_mut(&service_key(topic_path)) {
+            state.pending = false;
+        }
+    }
+
+    /// Whether the service of `topic_path` used up its restarts
+    pub(crate) fn is_unhealthy(&self, topic_path: &TopicPath) -> bool {
+        let services = self.services.lock().unwrap_or_else(|e| e.into_inner());
+        // Checked on every request; most nodes never had a panicking service
+        !services.is_empty()
+            && services
+                .get(&service_key(topic_path))
+                .is_some_and(|state| state.unhealthy)
+    }
+
+    /// Forget the restarts of a removed service
+    pub(crate) fn remove(&self, service_topic: &TopicPath) {
+        let mut services = self.services.lock().unwrap_or_else(|e| e.into_inner());
+        services.remove(&service_key(service_topic));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test that restart budgets are kept per network
+    ///
+    /// INTENTION: The same service path under two networks is two services;
+    /// using up the restarts of one leaves the other healthy and restartable.
+    #[test]
+    fn test_restart_budget_per_network() {
+        let restarts = ServiceRestarts::new();
+        let policy = ServiceRestartPolicy::new(1, Duration::from_millis(50));
+        let main = TopicPath::new("main:fragile/echo", "main").unwrap();
+        let other = TopicPath::new("other:fragile/echo", "main").unwrap();
+
+        assert_eq!(
+            restarts.record_panic(&main, &policy),
+            RestartDecision::Restart(1)
+        );
+        restarts.restart_finished(&main);
+        assert_eq!(
+            restarts.record_panic(&main, &policy),
+            RestartDecision::Unhealthy
+        );
+        assert!(restarts.is_unhealthy(&main));
+
+        assert!(!restarts.is_unhealthy(&other));
+        assert_eq!(
+            restarts.record_panic(&other, &policy),
+            RestartDecision::Restart(1)
+        );
+    }
+}
@@ -0,0 +1,221 @@
+// Subscription Groups
+//
+// This module provides the routing table of subscription groups: subscriptions
+// that share a group name compete for events instead of each receiving a copy.
+
+use crate::routing::TopicPath;
+use runar_common::types::ArcValue;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::time::{Duration, Instant};
+
+/// Default number of events queued for a group without an available member
+pub const DEFAULT_SUBSCRIPTION_GROUP_QUEUE_SIZE: usize = 100;
+
+/// Default time a queued event, or a group without members, is kept
+pub const DEFAULT_SUBSCRIPTION_GROUP_QUEUE_TTL: Duration = Duration::from_secs(30);
+
+/// An event waiting for a member of its group to become available
+#[derive(Debug, Clone)]
+pub struct PendingGroupEvent {
+    /// Topic the event was published to
+    pub topic_path: TopicPath,
+    /// Event payload
+    pub data: Option<ArcValue>,
+    /// Sequence number of an ordered event
+    pub sequence: Option<u64>,
+}
+
+/// Members of one group on one topic (or pattern)
+#[derive(Debug)]
+struct SubscriptionGroup {
+    topic: TopicPath,
+    members: Vec<String>,
+    /// Index of the member whose turn is next
+    cursor: usize,
+    /// Queued events, with the instant each was queued
+    pending: VecDeque<(Instant, PendingGroupEvent)>,
+    /// When the last member left, while the group has none
+    emptied_at: Option<Instant>,
+}
+
+impl SubscriptionGroup {
+    /// Drop the queued events older than `ttl`
+    fn expire(&mut self, now: Instant, ttl: Duration) {
+        while let Some((queued_at, _)) = self.pending.front() {
+            if now.duration_since(*queued_at) < ttl {
+                break;
+            }
+            self.pending.pop_front();
+        }
+    }
+
+    /// Whether the group has had no members nor events for `ttl`
+    fn is_abandoned(&self, now: Instant, ttl: Duration) -> bool {
+        self.members.is_empty()
+            && self.pending.is_empty()
+            && self
+                .emptied_at
+                .is_none_or(|emptied_at| now.duration_since(emptied_at) >= ttl)
+    }
+}
+
+/// Routing table of subscription groups
+///
+/// INTENTION: Let replicas of a worker share a topic so that each event is
+/// processed once. A group is identified by its name and the topic its
+/// members subscribed to; each event matching the topic goes to one member,
+/// chosen round-robin. When no member is available the event is queued, up
+/// to `queue_size` events per group with the oldest dropped first, and handed
+/// to the next member that joins if that happens within `queue_ttl`. A group
+/// whose members all left is forgotten once it had neither members nor
+/// queued events for `queue_ttl`. Groups are local to a node: every node with
+/// members of a group delivers its own copy of a broadcast event.
+#[derive(Debug)]
+pub struct SubscriptionGroups {
+    queue_size: usize,
+    queue_ttl: Duration,
+    groups: HashMap<(String, String), SubscriptionGroup>,
+    /// Group of each member subscription
+    memberships: HashMap<String, (String, String)>,
+}
+
+impl SubscriptionGroups {
+    /// Create a routing table queueing at most `queue_size` events per group
+    pub fn new(queue_size: usize) -> Self {
+        Self {
+            queue_size,
+            queue_ttl: DEFAULT_SUBSCRIPTION_GROUP_QUEUE_TTL,
+            groups: HashMap::new(),
+            memberships: HashMap::new(),
+        }
+    }
+
+    /// Set how long queued events, and groups without members, are kept
+    pub fn with_queue_ttl(mut self, queue_ttl: Duration) -> Self {
+        self.queue_ttl = queue_ttl;
+        self
+    }
+
+    /// Add a subscription to a group, creating the group if needed
+    ///
+    /// Returns the events queued while the group had no available member;
+    /// they are now due to the joining subscription.
+    pub fn join(
+        &mut self,
+        topic: &TopicPath,
+        group: &str,
+        subscription_id: impl Into<String>,
+    ) -> Vec<PendingGroupEvent> {
+        let subscription_id = subscription_id.into();
+        self.expire(Instant::now());
+        let key = (topic.as_str().to_string(), group.to_string());
+        let entry = self
+            .groups
+            .entry(key.clone())
+            .or_insert_with(|| SubscriptionGroup {
+                topic: topic.clone(),
+                members: Vec::new(),
+                cursor: 0,
+                pending: VecDeque::new(),
+                emptied_at: None,
+            });
+        entry.members.push(subscription_id.clone());
+        entry.emptied_at = None;
+        self.memberships.insert(subscription_id, key);
+        entry.pending.drain(..).map(|(_, event)| event).collect()
+    }
+
+    /// Remove a subscription from its group
+    ///
+    /// The group itself is kept for `queue_ttl`, so that its events are
+    /// queued until another member joins.
+    pub fn leave(&mut self, subscription_id: &str) {
+        let now = Instant::now();
+        let key = self.memberships.remove(subscription_id);
+        if let Some(group) = key.and_then(|key| self.groups.get_mut(&key)) {
+            if let Some(index) = group.members.iter().position(|id| id == subscription_id) {
+                group.members.remove(index);
+                if index < group.cursor {
+                    group.cursor -= 1;
+                }
+            }
+            if group.members.is_empty() {
+                group.emptied_at = Some(now);
+            }
+        }
+        self.expire(now);
+    }
+
+    /// Whether a subscription belongs to a group
+    pub fn is_member(&self, subscription_id: &str) -> bool {
+        self.memberships.contains_key(subscription_id)
+    }
+
+    /// Choose the members receiving an event, one per matching group
+    ///
+    /// `available` holds the subscriptions that can currently receive the
+    /// event; members missing from it are skipped. Groups without an
+    /// available member queue the event instead.
+    pub fn route(&mut self, event: &PendingGroupEvent, available: &HashSet<&str>) -> Vec<String> {
+        let now = Instant::now();
+        self.expire(now);
+        let mut chosen = Vec::new();
+        for group in self.groups.values_mut() {
+            if !group.topic.matches(&event.topic_path) {
+                continue;
+            }
+            let count = group.members.len();
+            let member = (0..count)
+                .map(|offset| (group.cursor + offset) % count)
+                .find(|index| available.contains(group.members[*index].as_str()));
+            match member {
+                Some(index) => {
+                    group.cursor = (index + 1) % count;
+                    chosen.push(group.members[index].clone());
+                }
+                None if self.queue_size > 0 => {
+                    if group.pending.len() >= self.queue_size {
+                        group.pending.pop_front();
+                    }
+                    group.pending.push_back((now, event.clone()));
+                }
+                None => {}
+            }
+        }
+        chosen
+    }
+
+    /// Number of events queued for a group
+    pub fn pending_len(&self, topic: &TopicPath, group: &str) -> usize {
+        let now = Instant::now();
+        self.groups
+            .get(&(topic.as_str().to_string(), group.to_string()))
+            .map_or(0, |group| {
+                group
+                    .pending
+                    .iter()
+                    .filter(|(queued_at, _)| now.duration_since(*queued_at) < self.queue_ttl)
+                    .count()
+            })
+    }
+
+    /// Number of groups, including the ones waiting for a member
+    pub fn group_count(&self) -> usize {
+        self.groups.len()
+    }
+
+    /// Drop expired queued events, then the groups left without members or events
+    fn expire(&mut self, now: Instant) {
+        let ttl = self.queue_ttl;
+        self.groups.retain(|_, group| {
+            group.expire(now, ttl);
+            !group.is_abandoned(now, ttl)
+        });
+    }
+}
+
+impl Default for SubscriptionGroups {
+    fn default() -> Self {
+        Self::new(DEFAULT_SUBSCRIPTION_GROUP_QUEUE_SIZE)
+    }
+}
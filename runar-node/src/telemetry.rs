@@ -0,0 +1,131 @@
+// Telemetry Module
+//
+// INTENTION: Export the spans produced by a node to a tracing backend such as
+// Jaeger through OTLP over HTTP. A node created with a telemetry configuration
+// builds an exporter, and the application adds its `tracing` layer to the
+// subscriber it installs.
+
+use anyhow::{anyhow, Result};
+use opentelemetry::trace::TracerProvider as _;
+use opentelemetry::KeyValue;
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::trace::SdkTracer;
+use opentelemetry_sdk::trace::{Sampler, SdkTracerProvider};
+use opentelemetry_sdk::Resource;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tracing::Subscriber;
+use tracing_opentelemetry::OpenTelemetryLayer;
+use tracing_subscriber::registry::LookupSpan;
+
+/// Set by `disable` to keep nodes from installing an exporter
+static DISABLED: AtomicBool = AtomicBool::new(false);
+
+/// Prevent exporters from being built in this process
+///
+/// INTENTION: Let tests create nodes configured with telemetry without
+/// sending spans anywhere. Exporters built before the call keep running.
+pub fn disable() {
+    DISABLED.store(true, Ordering::SeqCst);
+}
+
+/// Whether `disable` was called
+pub fn is_disabled() -> bool {
+    DISABLED.load(Ordering::SeqCst)
+}
+
+/// Configuration of the OTLP span exporter
+#[derive(Clone, Debug, PartialEq)]
+pub struct OtlpConfig {
+    /// OTLP/HTTP traces endpoint, e.g. `http://localhost:4318/v1/traces`
+    pub endpoint: String,
+    /// Service name the spans are reported under
+    pub service_name: String,
+    /// Fraction of traces exported, from 0.0 (none) to 1.0 (all)
+    pub sample_rate: f64,
+}
+
+impl OtlpConfig {
+    /// Export every trace to `endpoint` under `service_name`
+    pub fn new(endpoint: impl Into<String>, service_name: impl Into<String>) -> Self {
+        Self {
+            endpoint: endpoint.into(),
+            service_name: service_name.into(),
+            sample_rate: 1.0,
+        }
+    }
+
+    /// Set the fraction of traces exported, clamped to 0.0..=1.0
+    pub fn with_sample_rate(mut self, sample_rate: f64) -> Self {
+        self.sample_rate = sample_rate.clamp(0.0, 1.0);
+        self
+    }
+}
+
+/// Exporter sending `tracing` spans to an OTLP endpoint
+///
+/// INTENTION: Bridge the `tracing` spans of the node to OpenTelemetry. Every
+/// span carries the node and network IDs as resource attributes; the spans
+/// of requests, remote requests and publishes also record the service and
+/// action or topic they target.
+///
+/// The exporter does not touch the global subscriber: the application adds
+/// the exporter's `layer` to its own, next to its other layers. Nodes
+/// sharing a process each have their own exporter and layer.
+pub struct OtlpExporter {
+    provider: SdkTracerProvider,
+}
+
+impl OtlpExporter {
+    /// Build the exporter for a node
+    ///
+    /// Returns None when telemetry was disabled with `disable`, and an error
+    /// when the exporter cannot be built.
+    pub fn new(config: &OtlpConfig, node_id: &str, network_id: &str) -> Result<Option<Self>> {
+        if is_disabled() {
+            return Ok(None);
+        }
+
+        let exporter = opentelemetry_otlp::SpanExporter::builder()
+            .with_http()
+            .with_endpoint(config.endpoint.clone())
+            .build()
+            .map_err(|e| anyhow!("Failed to build the OTLP exporter: {e}"))?;
+        let resource = Resource::builder()
+            .with_service_name(config.service_name.clone())
+            .with_attributes([
+                KeyValue::new("node.id", node_id.to_string()),
+                KeyValue::new("network.id", network_id.to_string()),
+            ])
+            .build();
+        let provider = SdkTracerProvider::builder()
+            .with_batch_exporter(exporter)
+            .with_sampler(Sampler::ParentBased(Box::new(Sampler::TraceIdRatioBased(
+                config.sample_rate,
+            ))))
+            .with_resource(resource)
+            .build();
+
+        Ok(Some(Self { provider }))
+    }
+
+    /// A `tracing` layer exporting the spans of the subscriber it is added to
+    pub fn layer<S>(&self) -> OpenTelemetryLayer<S, SdkTracer>
+    where
+        S: Subscriber + for<'span> LookupSpan<'span>,
+    {
+        tracing_opentelemetry::layer().with_tracer(self.provider.tracer("runar_node"))
+    }
+
+    /// Export the spans that are still buffered
+    pub fn flush(&self) -> Result<()> {
+        self.provider
+            .force_flush()
+            .map_err(|e| anyhow!("Failed to flush spans: {e}"))
+    }
+}
+
+impl Drop for OtlpExporter {
+    fn drop(&mut self) {
+        let _ = self.provider.shutdown();
+    }
+}
@@ -0,0 +1,645 @@
+// Test Node
+//
+// This module provides a node wired up for unit tests: fresh credentials,
+// quiet logging and the services and types under test, started with a single
+// call and stopped when dropped, optionally networked over QUIC or an
+// in-memory transport. It also provides that in-memory transport, whose links
+// can be cut to simulate network partitions.
+
+use std::collections::HashSet;
+use std::fmt::Debug;
+use std::future::Future;
+use std::net::{Ipv4Addr, SocketAddr};
+use std::ops::{Deref, DerefMut};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex as StdMutex, RwLock as StdRwLock};
+
+use anyhow::Result;
+use async_trait::async_trait;
+use runar_common::logging::{Component, Logger};
+use runar_common::types::{AsArcValue, SerializerRegistry};
+use runar_keys::compact_ids;
+use runar_keys::{mobile::MobileKeyManager, node::NodeKeyManager};
+use serde::{Deserialize, Serialize};
+
+use crate::config::{LogLevel, LoggingConfig};
+use crate::network::discovery::multicast_discovery::PeerInfo;
+use crate::network::discovery::{NodeInfo, NodeInfoDiff};
+use crate::network::network_config::NetworkConfig;
+use crate::network::transport::{
+    pick_free_port, ErrorCode, NetworkError, NetworkMessage, NetworkTransport, PeerId,
+};
+use crate::network::QuicTransportOptions;
+use crate::node::{Node, NodeConfig, TransportFactory};
+use crate::services::abstract_service::AbstractService;
+
+type AddService = Box<
+    dyn for<'a> FnOnce(&'a mut Node) -> Pin<Box<dyn Future<Output = Result<()>> + Send + 'a>>
+        + Send,
+>;
+type RegisterType = Box<dyn FnOnce(&mut SerializerRegistry) -> Result<()> + Send>;
+type Configure = Box<dyn FnOnce(NodeConfig) -> NodeConfig + Send>;
+
+/// The transport a [`TestNode`] runs over
+enum TestTransport {
+    /// No networking, the node never opens a port
+    None,
+    /// QUIC on a free port of the loopback interface
+    Quic,
+    /// The in-memory network of a [`NetworkPartition`]
+    InMemory(TransportFactory),
+}
+
+/// Builder of a [`TestNode`]
+///
+/// INTENTION: Replace the boilerplate of creating credentials, a config and a
+/// node, adding services, registering types and starting it, with one chain.
+pub struct TestNodeBuilder {
+    services: Vec<AddService>,
+    serializer_types: Vec<RegisterType>,
+    log_level: LogLevel,
+    credentials: Option<TestCredentials>,
+    transport: TestTransport,
+    configure: Vec<Configure>,
+}
+
+impl TestNodeBuilder {
+    fn new() -> Self {
+        Self {
+            services: Vec::new(),
+            serializer_types: Vec::new(),
+            log_level: LogLevel::Warn,
+            credentials: None,
+            transport: TestTransport::None,
+            configure: Vec::new(),
+        }
+    }
+
+    /// Add a service to the node, in the order given
+    pub fn with_service<S: AbstractService + 'static>(mut self, service: S) -> Self {
+        self.services
+            .push(Box::new(move |node| Box::pin(node.add_service(service))));
+        self
+    }
+
+    /// Register a type with the node's serializer
+    pub fn with_serializer_type<T>(mut self) -> Self
+    where
+        T: 'static + Serialize + for<'de> Deserialize<'de> + Clone + Send + Sync,
+    {
+        self.serializer_types
+            .push(Box::new(|registry| registry.register::<T>()));
+        self
+    }
+
+    /// Set the log level of the node, `Warn` by default
+    pub fn with_log_level(mut self, log_level: LogLevel) -> Self {
+        self.log_level = log_level;
+        self
+    }
+
+    /// Issue the node's keys from `credentials`, so that it can talk to the
+    /// other nodes built with them
+    ///
+    /// Without it the node gets credentials and a network of its own.
+    pub fn with_credentials(mut self, credentials: &TestCredentials) -> Self {
+        self.credentials = Some(credentials.clone());
+        self
+    }
+
+    /// Run the node over QUIC, bound to a free port of the loopback interface
+    ///
+    /// Discovery stays disabled; nodes are connected with [`TestNode::connect`].
+    pub fn with_quic_transport(mut self) -> Self {
+        self.transport = TestTransport::Quic;
+        self
+    }
+
+    /// Run the node over the in-memory network of `network`
+    ///
+    /// Discovery stays disabled; nodes are connected with [`TestNode::connect`].
+    pub fn with_in_memory_transport(mut self, network: &NetworkPartition) -> Self {
+        self.transport = TestTransport::InMemory(network.transport_factory());
+        self
+    }
+
+    /// Adjust the node config before the node is created
+    ///
+    /// Called with the config the builder set up, in the order given.
+    pub fn with_config(
+        mut self,
+        configure: impl FnOnce(NodeConfig) -> NodeConfig + Send + 'static,
+    ) -> Self {
+        self.configure.push(Box::new(configure));
+        self
+    }
+
+    /// Create and start the node
+    ///
+    /// Without a transport the node never opens a port.
+    pub async fn build(self) -> Result<TestNode> {
+        let credentials = match self.credentials {
+            Some(credentials) => credentials,
+            None => TestCredentials::new()?,
+        };
+        let (config, quic_options) = credentials.issue_node()?;
+        let mut config =
+            config.with_logging_config(LoggingConfig::new().with_default_level(self.log_level));
+        let address = match self.transport {
+            TestTransport::None => None,
+            TestTransport::Quic => {
+                let port = pick_free_port(50000..51000).unwrap_or(0);
+                let address = SocketAddr::from((Ipv4Addr::LOCALHOST, port));
+                let mut network_config = NetworkConfig::with_quic(quic_options);
+                network_config.transport_options.bind_address = address;
+                config = config.with_network_config(network_config);
+                Some(address)
+            }
+            TestTransport::InMemory(factory) => {
+                config = config
+                    .with_network_config(NetworkConfig::with_quic(quic_options))
+                    .with_transport_factory(factory);
+                // The in-memory transport ignores peer addresses
+                Some(SocketAddr::from((Ipv4Addr::LOCALHOST, 1)))
+            }
+        };
+        for configure in self.configure {
+            config = configure(config);
+        }
+
+        let mut node = Node::new(config).await?;
+        {
+            let mut serializer = node.serializer.write().await;
+            for register in self.serializer_types {
+                register(&mut serializer)?;
+            }
+        }
+        for add_service in self.services {
+            add_service(&mut node).await?;
+        }
+        node.start().await?;
+        let peer_id = node.get_local_node_info().await?.peer_id;
+        Ok(TestNode {
+            node,
+            peer_id,
+            address,
+        })
+    }
+}
+
+/// A started in-process node for unit tests
+///
+/// INTENTION: Give tests a ready node they can use as a [`Node`] through
+/// `Deref`, with `call` as a shorthand for requests with a payload. Dropping
+/// it stops the node in the background; tests wanting to wait for the stop
+/// call `stop` themselves.
+///
+/// ```ignore
+/// let node = TestNode::builder().with_service(MathService::new("math", "math")).build().await?;
+/// let sum: f64 = node.call("math/add", params).await?;
+/// ```
+pub struct TestNode {
+    node: Node,
+    peer_id: PeerId,
+    address: Option<SocketAddr>,
+}
+
+impl TestNode {
+    /// Start building a test node
+    pub fn builder() -> TestNodeBuilder {
+        TestNodeBuilder::new()
+    }
+
+    /// Request an action with the given payload
+    pub async fn call<P, T>(&self, path: impl Into<String>, payload: P) -> Result<T>
+    where
+        P: AsArcValue + Send + Sync,
+        T: 'static + Send + Sync + Clone + Debug + for<'de> Deserialize<'de>,
+    {
+        self.node.request(path, Some(payload)).await
+    }
+
+    /// The peer ID the node is known by on the network
+    pub fn peer_id(&self) -> &PeerId {
+        &self.peer_id
+    }
+
+    /// Connect to `other`, which must be on the same transport and credentials
+    pub async fn connect(&mut self, other: &TestNode) -> Result<()> {
+        let address = other
+            .address
+            .ok_or_else(|| anyhow::anyhow!("{} runs without a transport", other.peer_id))?;
+        self.node
+            .add_remote_peer(address, other.peer_id.clone())
+            .await
+    }
+}
+
+impl Deref for TestNode {
+    type Target = Node;
+
+    fn deref(&self) -> &Node {
+        &self.node
+    }
+}
+
+impl DerefMut for TestNode {
+    fn deref_mut(&mut self) -> &mut Node {
+        &mut self.node
+    }
+}
+
+impl Drop for TestNode {
+    fn drop(&mut self) {
+        // Stopping is async; without a runtime left the node goes away with it
+        if let Ok(runtime) = tokio::runtime::Handle::try_current() {
+            let mut node = self.node.clone();
+            runtime.spawn(async move { node.stop().await });
+        }
+    }
+}
+
+/// Credentials of a network whose nodes can talk to each other
+///
+/// INTENTION: Issue the keys and certificates of test nodes from one user and
+/// one network, the way a mobile app sets up the nodes of its user. Cloning
+/// shares the credentials; pass them to [`TestNodeBuilder::with_credentials`]
+/// for every node that should join the network.
+#[derive(Clone)]
+pub struct TestCredentials {
+    mobile_keys: Arc<StdMutex<MobileKeyManager>>,
+    network_id: String,
+}
+
+impl TestCredentials {
+    /// Create a user root key and a network to issue nodes from
+    pub fn new() -> Result<Self> {
+        let logger = Arc::new(Logger::new_root(Component::Keys, "test_node"));
+        let mut mobile_keys = MobileKeyManager::new(logger)?;
+        mobile_keys.initialize_user_root_key()?;
+        let network_id = mobile_keys.generate_network_data_key()?;
+        Ok(Self {
+            mobile_keys: Arc::new(StdMutex::new(mobile_keys)),
+            network_id,
+        })
+    }
+
+    /// Issue the keys of a new node, returning its config and the QUIC
+    /// options with its certificates
+    fn issue_node(&self) -> Result<(NodeConfig, QuicTransportOptions)> {
+        let logger = Arc::new(Logger::new_root(Component::Keys, "test_node"));
+        let mut mobile_keys = self.mobile_keys.lock().unwrap();
+
+        let mut node_keys_manager = NodeKeyManager::new(logger)?;
+        let node_public_key = node_keys_manager.get_node_public_key();
+        let node_id = compact_ids::compact_node_id(&node_public_key);
+        let setup_token = node_keys_manager.generate_csr()?;
+        let cert_message = mobile_keys.process_setup_token(&setup_token)?;
+        let network_key_message =
+            mobile_keys.create_network_key_message(&self.network_id, &node_public_key)?;
+        node_keys_manager.install_certificate(cert_message)?;
+        node_keys_manager.install_network_key(network_key_message)?;
+
+        let cert_config = node_keys_manager.get_quic_certificate_config()?;
+        let quic_options = QuicTransportOptions::new()
+            .with_certificates(cert_config.certificate_chain)
+            .with_private_key(cert_config.private_key)
+            .with_root_certificates(vec![mobile_keys
+                .get_ca_certificate()
+                .to_rustls_certificate()]);
+
+        let key_state = bincode::serialize(&node_keys_manager.export_state())?;
+        let config =
+            NodeConfig::new(node_id, self.network_id.clone()).with_key_manager_state(key_state);
+        Ok((config, quic_options))
+    }
+}
+
+type MockMessageHandler = Box<dyn Fn(NetworkMessage) -> Result<(), NetworkError> + Send + Sync>;
+
+/// A node attached to the in-memory network of a [`NetworkPartition`]
+struct MockPeer {
+    node_info: StdRwLock<NodeInfo>,
+    message_handler: MockMessageHandler,
+    peer_node_info_sender: tokio::sync::broadcast::Sender<NodeInfo>,
+    connected: StdRwLock<HashSet<PeerId>>,
+    running: AtomicBool,
+}
+
+/// State shared by a [`NetworkPartition`] and its transports
+#[derive(Default)]
+struct MockNetwork {
+    /// Attached peers, in the order they were attached
+    peers: StdRwLock<Vec<Arc<MockPeer>>>,
+    /// Cut links, stored in both directions
+    cut_links: StdRwLock<HashSet<(PeerId, PeerId)>>,
+}
+
+impl MockNetwork {
+    fn peer(&self, peer_id: &PeerId) -> Option<Arc<MockPeer>> {
+        self.peers
+            .read()
+            .unwrap()
+            .iter()
+            .find(|peer| &peer.node_info.read().unwrap().peer_id == peer_id)
+            .cloned()
+    }
+
+    fn is_cut(&self, a: &PeerId, b: &PeerId) -> bool {
+        self.cut_links
+            .read()
+            .unwrap()
+            .contains(&(a.clone(), b.clone()))
+    }
+}
+
+/// In-memory network transport for tests
+///
+/// INTENTION: Connect nodes in the same process without sockets or
+/// certificates, so tests can control which messages get through. Messages
+/// are handed directly to the destination's message handler. Transports are
+/// created by [`NetworkPartition::add_transport`], which decides which links
+/// are up.
+pub struct MockNetworkTransport {
+    local: Arc<MockPeer>,
+    network: Arc<MockNetwork>,
+}
+
+impl MockNetworkTransport {
+    fn local_peer_id(&self) -> PeerId {
+        self.local.node_info.read().unwrap().peer_id.clone()
+    }
+
+    fn check_running(&self) -> Result<(), NetworkError> {
+        if self.local.running.load(Ordering::SeqCst) {
+            Ok(())
+        } else {
+            Err(NetworkError::TransportError(
+                ErrorCode::NotRunning,
+                "Mock transport is not running".to_string(),
+            ))
+        }
+    }
+
+    /// Send the local node info to the connected peers that can be reached
+    fn send_node_info(&self) {
+        let local_id = self.local_peer_id();
+        let node_info = self.local.node_info.read().unwrap().clone();
+        let connected: Vec<PeerId> = self
+            .local
+            .connected
+            .read()
+            .unwrap()
+            .iter()
+            .cloned()
+            .collect();
+        for peer_id in connected {
+            if self.network.is_cut(&local_id, &peer_id) {
+                continue;
+            }
+            if let Some(peer) = self.network.peer(&peer_id) {
+                let _ = peer.peer_node_info_sender.send(node_info.clone());
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl NetworkTransport for MockNetworkTransport {
+    async fn start(&self) -> Result<(), NetworkError> {
+        self.local.running.store(true, Ordering::SeqCst);
+        Ok(())
+    }
+
+    async fn stop(&self) -> Result<(), NetworkError> {
+        self.local.running.store(false, Ordering::SeqCst);
+        let local_id = self.local_peer_id();
+        let connected: Vec<PeerId> = self.local.connected.write().unwrap().drain().collect();
+        for peer_id in connected {
+            if let Some(peer) = self.network.peer(&peer_id) {
+                peer.connected.write().unwrap().remove(&local_id);
+            }
+        }
+        Ok(())
+    }
+
+    async fn disconnect(&self, node_id: PeerId) -> Result<(), NetworkError> {
+        self.local.connected.write().unwrap().remove(&node_id);
+        if let Some(peer) = self.network.peer(&node_id) {
+            peer.connected
+                .write()
+                .unwrap()
+                .remove(&self.local_peer_id());
+        }
+        Ok(())
+    }
+
+    async fn is_connected(&self, node_id: PeerId) -> bool {
+        self.local.connected.read().unwrap().contains(&node_id)
+    }
+
+    /// Hand the message to the destination, or drop it if the link is cut
+    async fn send_message(&self, message: NetworkMessage) -> Result<(), NetworkError> {
+        self.check_running()?;
+        if !self
+            .local
+            .connected
+            .read()
+            .unwrap()
+            .contains(&message.destination)
+        {
+            return Err(NetworkError::ConnectionError(
+                ErrorCode::NotConnected,
+                format!("Not connected to peer {}", message.destination),
+            ));
+        }
+        // A partition loses messages on the way, it does not reject them
+        if self.network.is_cut(&message.source, &message.destination) {
+            return Ok(());
+        }
+        let peer = self.network.peer(&message.destination).ok_or_else(|| {
+            NetworkError::ConnectionError(
+                ErrorCode::PeerUnreachable,
+                format!("Peer {} is not on the network", message.destination),
+            )
+        })?;
+        (peer.message_handler)(message)
+    }
+
+    /// Connect to an attached peer, exchanging node infos
+    ///
+    /// Fails while the link to the peer is cut, like a dial into a partition.
+    async fn connect_peer(&self, discovery_msg: PeerInfo) -> Result<(), NetworkError> {
+        self.check_running()?;
+        let local_id = self.local_peer_id();
+        let peer_id = PeerId::new(discovery_msg.public_key);
+        let peer = self
+            .network
+            .peer(&peer_id)
+            .filter(|peer| peer.running.load(Ordering::SeqCst))
+            .ok_or_else(|| {
+                NetworkError::ConnectionError(
+                    ErrorCode::PeerUnreachable,
+                    format!("Peer {peer_id} is not on the network"),
+                )
+            })?;
+        if self.network.is_cut(&local_id, &peer_id) {
+            return Err(NetworkError::ConnectionError(
+                ErrorCode::PeerUnreachable,
+                format!("Peer {peer_id} is partitioned from {local_id}"),
+            ));
+        }
+
+        self.local
+            .connected
+            .write()
+            .unwrap()
+            .insert(peer_id.clone());
+        peer.connected.write().unwrap().insert(local_id);
+        let local_info = self.local.node_info.read().unwrap().clone();
+        let peer_info = peer.node_info.read().unwrap().clone();
+        let _ = peer.peer_node_info_sender.send(local_info);
+        let _ = self.local.peer_node_info_sender.send(peer_info);
+        Ok(())
+    }
+
+    fn get_local_address(&self) -> String {
+        format!("mock://{}", self.local_peer_id())
+    }
+
+    async fn update_peers(&self, node_info: NodeInfo) -> Result<(), NetworkError> {
+        *self.local.node_info.write().unwrap() = node_info;
+        self.send_node_info();
+        Ok(())
+    }
+
+    /// Apply the diff locally and send peers the resulting node info
+    async fn broadcast_service_diff(&self, diff: NodeInfoDiff) -> Result<(), NetworkError> {
+        self.local
+            .node_info
+            .write()
+            .unwrap()
+            .apply_diff(&diff)
+            .map_err(|e| NetworkError::MessageError(ErrorCode::InvalidMessage, e.to_string()))?;
+        self.send_node_info();
+        Ok(())
+    }
+
+    async fn subscribe_to_peer_node_info(&self) -> tokio::sync::broadcast::Receiver<NodeInfo> {
+        self.local.peer_node_info_sender.subscribe()
+    }
+}
+
+/// In-memory network whose links can be cut, to simulate network partitions
+///
+/// INTENTION: Let integration tests check partition tolerance without
+/// stopping nodes. Every [`MockNetworkTransport`] created by `add_transport`
+/// is attached to the same network; `partition` cuts the link between two
+/// peers, silently dropping (not queueing) the messages between them while
+/// messages to and from other peers pass, and `heal` restores it. Existing
+/// connections stay up across a partition.
+///
+/// ```ignore
+/// let mut network = NetworkPartition::new();
+/// let a = network.add_transport(a_info, a_handler);
+/// let b = network.add_transport(b_info, b_handler);
+/// network.partition(&a_id, &b_id);
+/// // ... a and b no longer hear from each other
+/// network.heal(&a_id, &b_id);
+/// ```
+#[derive(Default)]
+pub struct NetworkPartition {
+    network: Arc<MockNetwork>,
+}
+
+impl NetworkPartition {
+    /// Create an empty network with all links up
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Attach a new transport for the node `local_node_info` to the network
+    ///
+    /// Messages for the node are passed to `message_handler`. The transport
+    /// must be started before it can connect or send.
+    pub fn add_transport(
+        &self,
+        local_node_info: NodeInfo,
+        message_handler: impl Fn(NetworkMessage) -> Result<(), NetworkError> + Send + Sync + 'static,
+    ) -> MockNetworkTransport {
+        let (peer_node_info_sender, _) = tokio::sync::broadcast::channel(32);
+        let local = Arc::new(MockPeer {
+            node_info: StdRwLock::new(local_node_info),
+            message_handler: Box::new(message_handler),
+            peer_node_info_sender,
+            connected: StdRwLock::new(HashSet::new()),
+            running: AtomicBool::new(false),
+        });
+        self.network.peers.write().unwrap().push(local.clone());
+        MockNetworkTransport {
+            local,
+            network: self.network.clone(),
+        }
+    }
+
+    /// Factory attaching the transport of a [`Node`] to the network
+    ///
+    /// Pass it to `NodeConfig::with_transport_factory` to run whole nodes
+    /// over the network.
+    pub fn transport_factory(&self) -> TransportFactory {
+        let network = Self {
+            network: self.network.clone(),
+        };
+        TransportFactory::new(move |local_node_info, message_handler| {
+            Box::new(network.add_transport(local_node_info, message_handler))
+        })
+    }
+
+    /// Cut the link between `a` and `b`, in both directions
+    pub fn partition(&mut self, a: &PeerId, b: &PeerId) {
+        let mut cut_links = self.network.cut_links.write().unwrap();
+        cut_links.insert((a.clone(), b.clone()));
+        cut_links.insert((b.clone(), a.clone()));
+    }
+
+    /// Restore the link between `a` and `b`
+    pub fn heal(&mut self, a: &PeerId, b: &PeerId) {
+        let mut cut_links = self.network.cut_links.write().unwrap();
+        cut_links.remove(&(a.clone(), b.clone()));
+        cut_links.remove(&(b.clone(), a.clone()));
+    }
+
+    /// Split the peers into two groups that cannot reach each other
+    ///
+    /// The first half of the peers, in the order they were attached, forms
+    /// the first group; peers within a group still reach each other. Returns
+    /// both groups.
+    pub fn partition_all(&mut self) -> (Vec<PeerId>, Vec<PeerId>) {
+        let mut peer_ids: Vec<PeerId> = self
+            .network
+            .peers
+            .read()
+            .unwrap()
+            .iter()
+            .map(|peer| peer.node_info.read().unwrap().peer_id.clone())
+            .collect();
+        let second = peer_ids.split_off(peer_ids.len().div_ceil(2));
+        for a in &peer_ids {
+            for b in &second {
+                self.partition(a, b);
+            }
+        }
+        (peer_ids, second)
+    }
+
+    /// Restore every link
+    pub fn heal_all(&mut self) {
+        self.network.cut_links.write().unwrap().clear();
+    }
+
+    /// Whether the link between `a` and `b` is cut
+    pub fn is_partitioned(&self, a: &PeerId, b: &PeerId) -> bool {
+        self.network.is_cut(a, b)
+    }
+}
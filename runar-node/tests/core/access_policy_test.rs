@@ -0,0 +1,129 @@
+// Tests for service access policies
+//
+// The Node checks the caller of every request against the access policy of
+// the target service before its action handler runs.
+
+use anyhow::Result;
+use runar_common::hmap;
+use runar_common::types::ArcValue;
+use runar_node::network::transport::PeerId;
+use runar_node::{Node, ServiceAccessPolicy};
+use runar_test_utils::create_node_test_config;
+
+use crate::fixtures::forwarding_service::ForwardingService;
+use crate::fixtures::math_service::MathService;
+
+/// Test which callers each policy accepts
+///
+/// INTENTION: Remote callers need `allow_remote` and a place in
+/// `allowed_peers`, local callers a place in `allowed_local_paths`, and the
+/// default policy accepts everyone.
+#[test]
+fn test_policy_checks_callers() {
+    let alice = PeerId::new("alice".to_string());
+    let bob = PeerId::new("bob".to_string());
+
+    let allow_all = ServiceAccessPolicy::default();
+    assert_eq!(allow_all, ServiceAccessPolicy::allow_all());
+    assert!(allow_all.check(Some(&alice), None).is_ok());
+    assert!(allow_all.check(None, None).is_ok());
+
+    let local_only = ServiceAccessPolicy::local_only();
+    assert!(local_only.check(None, None).is_ok());
+    assert!(local_only.check(None, Some("billing")).is_ok());
+    let error = local_only.check(Some(&alice), None).unwrap_err();
+    assert_eq!(error.to_string(), "access denied");
+
+    let peers = ServiceAccessPolicy::peers(vec![alice.clone()]);
+    assert!(peers.check(Some(&alice), None).is_ok());
+    assert!(peers.check(Some(&bob), None).is_err());
+    assert!(peers.check(None, None).is_ok());
+
+    let internal =
+        ServiceAccessPolicy::local_only().with_allowed_local_paths(vec!["billing".to_string()]);
+    assert!(internal.check(None, Some("billing")).is_ok());
+    assert!(internal.check(None, Some("shop")).is_err());
+    assert!(internal.check(None, None).is_err());
+    assert!(internal.check(Some(&alice), Some("billing")).is_err());
+}
+
+/// Test that the Node enforces policies and applies updates
+///
+/// INTENTION: A service restricted to some local services only serves
+/// requests made from their actions, a denied request never reaches the
+/// handler, and `update_service_policy` takes effect for the next request.
+#[tokio::test]
+async fn test_node_enforces_local_paths() -> Result<()> {
+    let mut config = create_node_test_config()?;
+    config.network_config = None;
+    let mut node = Node::new(config).await?;
+    node.add_service(MathService::new("Math Service", "math"))
+        .await?;
+    node.add_service(ForwardingService::new("Billing", "billing"))
+        .await?;
+    node.add_service(ForwardingService::new("Shop", "shop"))
+        .await?;
+    node.start().await?;
+
+    let params = ArcValue::new_map(hmap! {
+        "a" => 5.0,
+        "b" => 3.0
+    });
+    let forward = ArcValue::new_map(hmap! {
+        "path" => ArcValue::new_primitive("math/add".to_string()),
+        "params" => params.clone()
+    });
+
+    // Every caller is allowed by default
+    let result: f64 = node.request("math/add", Some(params.clone())).await?;
+    assert_eq!(result, 8.0);
+    let result: f64 = node.request("shop/forward", Some(forward.clone())).await?;
+    assert_eq!(result, 8.0);
+
+    node.update_service_policy(
+        "math",
+        ServiceAccessPolicy::allow_all().with_allowed_local_paths(vec!["billing".to_string()]),
+    )
+    .await?;
+
+    let result: f64 = node
+        .request("billing/forward", Some(forward.clone()))
+        .await?;
+    assert_eq!(result, 8.0);
+    let error = node
+        .request::<ArcValue, f64>("shop/forward", Some(forward.clone()))
+        .await
+        .unwrap_err();
+    assert!(error.to_string().contains("access denied"), "{error}");
+    let error = node
+        .request::<ArcValue, f64>("math/add", Some(params.clone()))
+        .await
+        .unwrap_err();
+    assert!(error.to_string().contains("access denied"), "{error}");
+
+    // Only the service making the request counts, not those before it
+    let result: f64 = node.request("shop/forward", Some(nested_forward())).await?;
+    assert_eq!(result, 1.0);
+
+    assert!(node
+        .update_service_policy("unknown", ServiceAccessPolicy::local_only())
+        .await
+        .is_err());
+
+    node.stop().await?;
+    Ok(())
+}
+
+/// Ask `shop` to have `billing` forward a request to `math`
+fn nested_forward() -> ArcValue {
+    ArcValue::new_map(hmap! {
+        "path" => ArcValue::new_primitive("billing/forward".to_string()),
+        "params" => ArcValue::new_map(hmap! {
+            "path" => ArcValue::new_primitive("math/subtract".to_string()),
+            "params" => ArcValue::new_map(hmap! {
+                "a" => 4.0,
+                "b" => 3.0
+            })
+        })
+    })
+}
@@ -0,0 +1,168 @@
+// Tests for the background tasks of services
+//
+// These tests verify that tasks spawned through the lifecycle context are
+// listed, can be aborted through their handle, are aborted when their service
+// stops, and that a panicking task is caught without affecting the node.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use runar_node::services::abstract_service::AbstractService;
+use runar_node::services::LifecycleContext;
+use runar_node::testing::TestNode;
+use runar_node::TaskState;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::time::sleep;
+
+const TICK: Duration = Duration::from_millis(10);
+
+/// A service polling in the background from the moment it starts
+#[derive(Clone)]
+struct PollingService {
+    network_id: Option<String>,
+    ticks: Arc<AtomicUsize>,
+    /// Context of the started service, to spawn and list tasks from tests
+    context: Arc<Mutex<Option<LifecycleContext>>>,
+}
+
+impl PollingService {
+    fn new() -> Self {
+        Self {
+            network_id: None,
+            ticks: Arc::new(AtomicUsize::new(0)),
+            context: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    fn context(&self) -> LifecycleContext {
+        self.context.lock().unwrap().clone().unwrap()
+    }
+}
+
+#[async_trait]
+impl AbstractService for PollingService {
+    fn name(&self) -> &str {
+        "Polling"
+    }
+
+    fn version(&self) -> &str {
+        "1.0.0"
+    }
+
+    fn path(&self) -> &str {
+        "polling"
+    }
+
+    fn description(&self) -> &str {
+        "Background task test service"
+    }
+
+    fn network_id(&self) -> Option<String> {
+        self.network_id.clone()
+    }
+
+    fn set_network_id(&mut self, network_id: String) {
+        self.network_id = Some(network_id);
+    }
+
+    async fn init(&self, _context: LifecycleContext) -> Result<()> {
+        Ok(())
+    }
+
+    async fn start(&self, context: LifecycleContext) -> Result<()> {
+        let ticks = self.ticks.clone();
+        context.spawn_background_task("poll", async move {
+            loop {
+                ticks.fetch_add(1, Ordering::SeqCst);
+                sleep(TICK).await;
+            }
+        });
+        *self.context.lock().unwrap() = Some(context);
+        Ok(())
+    }
+
+    async fn stop(&self, _context: LifecycleContext) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Test that stopping the node aborts the tasks of its services
+///
+/// INTENTION: A task spawned in `start` is listed as running while the
+/// service runs, and makes no more progress once the service stopped.
+#[tokio::test]
+async fn test_stop_aborts_background_tasks() {
+    let service = PollingService::new();
+    let mut node = TestNode::builder()
+        .with_service(service.clone())
+        .build()
+        .await
+        .unwrap();
+    sleep(TICK * 5).await;
+
+    let tasks = service.context().list_background_tasks();
+    assert_eq!(tasks.len(), 1);
+    assert_eq!(tasks[0].name, "poll");
+    assert_eq!(tasks[0].service_path, "polling");
+    assert_eq!(tasks[0].state, TaskState::Running);
+    assert!(service.ticks.load(Ordering::SeqCst) > 0);
+
+    node.stop().await.unwrap();
+    sleep(TICK * 2).await;
+    let ticks = service.ticks.load(Ordering::SeqCst);
+    sleep(TICK * 5).await;
+    assert_eq!(service.ticks.load(Ordering::SeqCst), ticks);
+    assert!(service.context().list_background_tasks().is_empty());
+}
+
+/// Test that a task handle cancels its task and reports its state
+///
+/// INTENTION: `TaskHandle::abort` cancels only that task, and tasks that
+/// return or panic are reported as such while the node keeps running.
+#[tokio::test]
+async fn test_task_handle_abort_and_panic() {
+    let service = PollingService::new();
+    let mut node = TestNode::builder()
+        .with_service(service.clone())
+        .build()
+        .await
+        .unwrap();
+    let context = service.context();
+
+    let waiting = context.spawn_background_task("wait", sleep(Duration::from_secs(60)));
+    let finishing = context.spawn_background_task("finish", async {});
+    let panicking = context.spawn_background_task("panic", async {
+        panic!("background failure");
+    });
+    sleep(TICK * 5).await;
+
+    assert_eq!(waiting.name(), "wait");
+    assert_eq!(waiting.state(), TaskState::Running);
+    assert_eq!(finishing.state(), TaskState::Finished);
+    assert_eq!(panicking.state(), TaskState::Panicked);
+
+    waiting.abort();
+    assert_eq!(waiting.state(), TaskState::Aborted);
+
+    let states: Vec<(String, TaskState)> = context
+        .list_background_tasks()
+        .into_iter()
+        .map(|task| (task.name, task.state))
+        .collect();
+    assert_eq!(
+        states,
+        vec![
+            ("poll".to_string(), TaskState::Running),
+            ("wait".to_string(), TaskState::Aborted),
+            ("finish".to_string(), TaskState::Finished),
+            ("panic".to_string(), TaskState::Panicked),
+        ]
+    );
+
+    // The polling task and the node were not affected by the panic
+    let ticks = service.ticks.load(Ordering::SeqCst);
+    sleep(TICK * 5).await;
+    assert!(service.ticks.load(Ordering::SeqCst) > ticks);
+    node.stop().await.unwrap();
+}
@@ -0,0 +1,99 @@
+// Tests for the CircuitBreaker used by Node request routing
+
+use anyhow::anyhow;
+use runar_node::network::transport::{ErrorCode, NetworkError};
+use runar_node::{CircuitBreaker, CircuitBreakerConfig, CircuitState};
+use std::time::Duration;
+
+fn test_breaker() -> CircuitBreaker {
+    CircuitBreaker::new(
+        CircuitBreakerConfig::new(0.5, Duration::from_secs(10), Duration::from_millis(100))
+            .with_minimum_requests(4),
+    )
+}
+
+/// Record failures until the circuit opens
+fn open(breaker: &CircuitBreaker) {
+    for _ in 0..4 {
+        breaker.record_failure();
+    }
+    assert_eq!(breaker.state(), CircuitState::Open);
+}
+
+/// Test that the circuit opens once the failure rate reaches the threshold
+///
+/// INTENTION: Verify that failures below the threshold keep the circuit
+/// closed and that an open circuit rejects requests.
+#[test]
+fn test_circuit_opens_on_failure_rate() {
+    let breaker = test_breaker();
+
+    for _ in 0..3 {
+        assert!(breaker.try_acquire());
+        breaker.record_success();
+    }
+    breaker.record_failure();
+    breaker.record_failure();
+    assert_eq!(breaker.state(), CircuitState::Closed);
+
+    // Three failures out of six requests reaches the 50% threshold
+    breaker.record_failure();
+    assert_eq!(breaker.state(), CircuitState::Open);
+    assert!(!breaker.try_acquire());
+}
+
+/// Test that the failure rate is only evaluated over enough requests
+///
+/// INTENTION: A single failure of a fresh service must not open the circuit,
+/// even though its failure rate is 100%.
+#[test]
+fn test_circuit_needs_minimum_requests() {
+    let breaker = CircuitBreaker::new(CircuitBreakerConfig::default());
+
+    for _ in 0..9 {
+        breaker.record_failure();
+        assert_eq!(breaker.state(), CircuitState::Closed);
+    }
+    breaker.record_failure();
+    assert_eq!(breaker.state(), CircuitState::Open);
+}
+
+/// Test which errors count against the circuit
+///
+/// INTENTION: Only failures to reach the service count; an error returned by
+/// the service's handler shows it is up.
+#[test]
+fn test_circuit_failure_classification() {
+    let unreachable = NetworkError::ConnectionError(ErrorCode::PeerUnreachable, "down".into());
+    assert!(CircuitBreaker::is_failure(&unreachable.into()));
+
+    let not_found = NetworkError::MessageError(ErrorCode::ServiceNotFound, "missing".into());
+    assert!(!CircuitBreaker::is_failure(&not_found.into()));
+    assert!(!CircuitBreaker::is_failure(&anyhow!("invalid input")));
+}
+
+/// Test the half-open probe behaviour
+///
+/// INTENTION: Verify that only one probe is let through after the timeout,
+/// that a failed probe restarts the timer and a successful one closes the circuit.
+#[test]
+fn test_circuit_half_open_probe() {
+    let breaker = test_breaker();
+    open(&breaker);
+
+    std::thread::sleep(Duration::from_millis(150));
+    assert!(breaker.try_acquire(), "A probe should be allowed");
+    assert_eq!(breaker.state(), CircuitState::HalfOpen);
+    assert!(!breaker.try_acquire(), "Only one probe should be allowed");
+
+    // A failed probe opens the circuit again and restarts the timer
+    breaker.record_failure();
+    assert_eq!(breaker.state(), CircuitState::Open);
+    assert!(!breaker.try_acquire());
+
+    std::thread::sleep(Duration::from_millis(150));
+    assert!(breaker.try_acquire());
+    breaker.record_success();
+    assert_eq!(breaker.state(), CircuitState::Closed);
+    assert!(breaker.try_acquire());
+}
@@ -0,0 +1,91 @@
+// Tests for NodeConfig validation
+//
+// These tests verify that conflicting options are reported together by
+// NodeConfig::validate, NodeConfigBuilder::build and Node::new.
+
+use runar_node::config::{ConfigurationError, LogLevel, LoggingConfig};
+use runar_node::network::network_config::{DiscoveryProviderConfig, NetworkConfig};
+use runar_node::network::transport::PeerId;
+use runar_node::{Node, NodeConfig};
+use runar_test_utils::create_node_test_config;
+
+/// Test that every problem is reported, not just the first one
+#[test]
+fn test_validate_reports_all_errors() {
+    let logging = LoggingConfig::new().with_service_path_level("", LogLevel::Debug);
+    let errors = NodeConfig::builder("node-1", "network-1")
+        .initial_peers(vec![(
+            "127.0.0.1:50000".parse().unwrap(),
+            PeerId::new("peer-1".to_string()),
+        )])
+        .logging_config(logging)
+        .build()
+        .unwrap_err();
+
+    assert_eq!(errors.len(), 2, "{errors:?}");
+    assert!(matches!(
+        errors[0],
+        ConfigurationError::NetworkRequiredForDiscovery
+    ));
+    assert!(matches!(
+        &errors[1],
+        ConfigurationError::InvalidLogLevel { target, .. } if target.is_empty()
+    ));
+}
+
+/// Test the checks on discovery settings
+///
+/// INTENTION: Discovery options without providers and a provider listed
+/// twice cannot work; a default configuration is valid.
+#[test]
+fn test_validate_discovery() {
+    assert!(NodeConfig::new("node-1", "network-1").validate().is_ok());
+
+    let mut network_config = NetworkConfig::new();
+    let errors = NodeConfig::new("node-1", "network-1")
+        .with_network_config(network_config.clone())
+        .validate()
+        .unwrap_err();
+    assert!(matches!(
+        errors[..],
+        [ConfigurationError::MissingDiscoveryProviders]
+    ));
+
+    network_config = network_config
+        .with_discovery_provider(DiscoveryProviderConfig::default_multicast())
+        .with_discovery_provider(DiscoveryProviderConfig::default_multicast());
+    let errors = NodeConfig::builder("node-1", "network-1")
+        .network_config(network_config)
+        .build()
+        .unwrap_err();
+    assert!(matches!(
+        errors[..],
+        [ConfigurationError::ConflictingDiscoveryModes(_)]
+    ));
+
+    let config = NodeConfig::builder("node-1", "network-1")
+        .network_config(NetworkConfig::new().with_mdns_discovery())
+        .build()
+        .unwrap();
+    assert!(config.network_config.is_some());
+}
+
+/// Test that Node::new refuses an invalid configuration
+#[tokio::test]
+async fn test_node_new_rejects_invalid_config() {
+    let mut config = create_node_test_config().expect("Error creating test config");
+    config.network_config = None;
+    let config = config.with_initial_peers(vec![(
+        "127.0.0.1:50000".parse().unwrap(),
+        PeerId::new("peer-1".to_string()),
+    )]);
+
+    let error = Node::new(config)
+        .await
+        .err()
+        .expect("Node::new should fail");
+    assert!(
+        error.to_string().contains("networking is disabled"),
+        "{error}"
+    );
+}
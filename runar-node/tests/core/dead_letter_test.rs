@@ -0,0 +1,161 @@
+// Tests for the dead-letter queue
+//
+// These tests verify that failed event deliveries are captured by the node,
+// bounded by the configured size and replayed to the failing subscriber.
+
+use anyhow::{anyhow, Result};
+use runar_common::types::ArcValue;
+use runar_node::services::{EventContext, EventRegistrationOptions, PublishOptions};
+use runar_node::testing::TestNode;
+use runar_node::{DispatchMode, Node, NodeDelegate};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+
+type EventFuture = Pin<Box<dyn Future<Output = Result<()>> + Send>>;
+
+/// Publish an event, returning once the subscribers have handled it
+async fn publish_sync(node: &Node, topic: &str, payload: String) {
+    node.publish_with_options(
+        topic,
+        Some(ArcValue::new_primitive(payload)),
+        PublishOptions {
+            dispatch: DispatchMode::Sync,
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+}
+
+/// Subscribe a handler that fails until `healthy` is set
+async fn subscribe_flaky(
+    node: &Node,
+    topic: &str,
+    healthy: Arc<AtomicBool>,
+    delivered: Arc<AtomicUsize>,
+) -> String {
+    node.subscribe(
+        topic.to_string(),
+        Box::new(move |_ctx: Arc<EventContext>, _data: Option<ArcValue>| {
+            let healthy = healthy.clone();
+            let delivered = delivered.clone();
+            Box::pin(async move {
+                if !healthy.load(Ordering::SeqCst) {
+                    return Err(anyhow!("order store unavailable"));
+                }
+                delivered.fetch_add(1, Ordering::SeqCst);
+                Ok(())
+            }) as EventFuture
+        }),
+    )
+    .await
+    .unwrap()
+}
+
+/// Test capturing and replaying a failed delivery
+///
+/// INTENTION: A failing subscriber leaves an entry describing the delivery;
+/// a replay that fails again keeps the entry with a higher attempt count and
+/// a replay that succeeds removes it. Subscriptions registered with
+/// `dead_letter: false` are never captured.
+#[tokio::test]
+async fn test_dead_letter_capture_and_replay() {
+    let node = TestNode::builder()
+        .with_config(|config| config.with_dead_letter_queue_size(10))
+        .build()
+        .await
+        .unwrap();
+    let healthy = Arc::new(AtomicBool::new(false));
+    let delivered = Arc::new(AtomicUsize::new(0));
+    let subscription_id =
+        subscribe_flaky(&node, "orders/created", healthy.clone(), delivered.clone()).await;
+
+    node.subscribe_with_options(
+        "orders/created".to_string(),
+        Box::new(|_ctx: Arc<EventContext>, _data: Option<ArcValue>| {
+            Box::pin(async { Err(anyhow!("audit log unavailable")) }) as EventFuture
+        }),
+        EventRegistrationOptions {
+            dead_letter: false,
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+
+    publish_sync(&node, "orders/created", "order-1".to_string()).await;
+
+    let entries = node.dead_letters();
+    assert_eq!(entries.len(), 1, "{entries:?}");
+    let entry = &entries[0];
+    assert!(entry.event_topic.ends_with("orders/created"));
+    assert!(entry.subscriber_path.ends_with("orders/created"));
+    assert_eq!(entry.subscription_id, subscription_id);
+    assert!(entry.error.contains("order store unavailable"));
+    assert_eq!(entry.attempt_count, 1);
+    assert_eq!(
+        entry.payload.clone().as_type::<String>().unwrap(),
+        "order-1"
+    );
+
+    // Still failing: the entry is queued again
+    assert_eq!(node.replay_dead_letters(None).await.unwrap(), 0);
+    let entries = node.dead_letters();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].attempt_count, 2);
+
+    // A filter that does not match leaves the entry alone
+    healthy.store(true, Ordering::SeqCst);
+    assert_eq!(
+        node.replay_dead_letters(Some("payments/*".to_string()))
+            .await
+            .unwrap(),
+        0
+    );
+    assert_eq!(node.dead_letters().len(), 1);
+
+    assert_eq!(
+        node.replay_dead_letters(Some("orders/*".to_string()))
+            .await
+            .unwrap(),
+        1
+    );
+    assert!(node.dead_letters().is_empty());
+    assert_eq!(delivered.load(Ordering::SeqCst), 1);
+}
+
+/// Test that the queue keeps the most recent failures
+///
+/// INTENTION: Once the configured size is reached the oldest entry is
+/// evicted, and entries of a removed subscription are dropped on replay.
+#[tokio::test]
+async fn test_dead_letter_eviction() {
+    let node = TestNode::builder()
+        .with_config(|config| config.with_dead_letter_queue_size(2))
+        .build()
+        .await
+        .unwrap();
+    let healthy = Arc::new(AtomicBool::new(false));
+    let delivered = Arc::new(AtomicUsize::new(0));
+    let subscription_id =
+        subscribe_flaky(&node, "orders/*", healthy.clone(), delivered.clone()).await;
+
+    for order in 1..=3 {
+        publish_sync(&node, "orders/created", format!("order-{order}")).await;
+    }
+
+    let payloads: Vec<String> = node
+        .dead_letters()
+        .into_iter()
+        .map(|entry| entry.payload.clone().as_type::<String>().unwrap())
+        .collect();
+    assert_eq!(payloads, vec!["order-2", "order-3"]);
+
+    node.unsubscribe(Some(&subscription_id)).await.unwrap();
+    healthy.store(true, Ordering::SeqCst);
+    assert_eq!(node.replay_dead_letters(None).await.unwrap(), 0);
+    assert!(node.dead_letters().is_empty());
+    assert_eq!(delivered.load(Ordering::SeqCst), 0);
+}
@@ -0,0 +1,113 @@
+// Tests for building a NodeConfig from environment variables and TOML files
+//
+// Each test uses its own variable prefix so tests running in parallel do not
+// see each other's environment.
+
+use runar_node::config::{ConfigurationError, LogLevel};
+use runar_node::network::network_config::DiscoveryProviderConfig;
+use runar_node::NodeConfig;
+use std::net::SocketAddr;
+
+/// Test that missing required variables are reported by name
+#[test]
+fn test_from_env_missing_required() {
+    std::env::set_var("ENVTEST_MISSING_NETWORK_ID", "network-1");
+
+    let error = NodeConfig::from_env_with_prefix("ENVTEST_MISSING").unwrap_err();
+    match error.downcast_ref::<ConfigurationError>() {
+        Some(ConfigurationError::Missing(name)) => assert_eq!(name, "ENVTEST_MISSING_NODE_ID"),
+        other => panic!("Expected a missing variable error, got {other:?}"),
+    }
+}
+
+/// Test that all supported variables are applied
+///
+/// INTENTION: Verify the node identity, logging, bind address, seed peers and
+/// multicast settings end up in the NodeConfig and its NetworkConfig.
+#[test]
+fn test_from_env_with_prefix() {
+    std::env::set_var("ENVTEST_FULL_NODE_ID", "node-1");
+    std::env::set_var("ENVTEST_FULL_NETWORK_ID", "network-1");
+    std::env::set_var("ENVTEST_FULL_BIND_ADDR", "127.0.0.1:50123");
+    std::env::set_var("ENVTEST_FULL_LOG_LEVEL", "debug");
+    std::env::set_var("ENVTEST_FULL_SEED_PEERS", "10.0.0.2:50000, 10.0.0.3:50000");
+    std::env::set_var("ENVTEST_FULL_MULTICAST_ENABLED", "true");
+
+    let config = NodeConfig::from_env_with_prefix("ENVTEST_FULL").unwrap();
+    assert_eq!(config.node_id, "node-1");
+    assert_eq!(config.default_network_id, "network-1");
+    assert_eq!(
+        config.logging_config.unwrap().default_level,
+        LogLevel::Debug
+    );
+
+    let network_config = config.network_config.expect("Networking should be enabled");
+    assert_eq!(
+        network_config.transport_options.bind_address,
+        "127.0.0.1:50123".parse::<SocketAddr>().unwrap()
+    );
+    assert!(network_config
+        .discovery_providers
+        .iter()
+        .any(|p| matches!(p, DiscoveryProviderConfig::Multicast(_))));
+    let seeds = network_config
+        .discovery_providers
+        .iter()
+        .find_map(|p| match p {
+            DiscoveryProviderConfig::Static(options) => Some(options.node_addresses.clone()),
+            _ => None,
+        })
+        .expect("Seed peers should be configured");
+    assert_eq!(seeds, vec!["10.0.0.2:50000", "10.0.0.3:50000"]);
+}
+
+/// Test that disabling multicast alone does not enable networking
+#[test]
+fn test_from_env_multicast_disabled() {
+    std::env::set_var("ENVTEST_NO_MULTICAST_NODE_ID", "node-1");
+    std::env::set_var("ENVTEST_NO_MULTICAST_NETWORK_ID", "network-1");
+    std::env::set_var("ENVTEST_NO_MULTICAST_MULTICAST_ENABLED", "false");
+
+    let config = NodeConfig::from_env_with_prefix("ENVTEST_NO_MULTICAST").unwrap();
+    assert!(config.network_config.is_none());
+}
+
+/// Test that environment variables override values from the config file
+#[test]
+fn test_from_env_merges_config_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("node.toml");
+    std::fs::write(
+        &path,
+        "node_id = \"file-node\"\nnetwork_id = \"file-network\"\nlog_level = \"warn\"\n",
+    )
+    .unwrap();
+
+    std::env::set_var("ENVTEST_FILE_CONFIG_FILE", &path);
+    std::env::set_var("ENVTEST_FILE_NODE_ID", "env-node");
+
+    let config = NodeConfig::from_env_with_prefix("ENVTEST_FILE").unwrap();
+    assert_eq!(config.node_id, "env-node");
+    assert_eq!(config.default_network_id, "file-network");
+    assert_eq!(config.logging_config.unwrap().default_level, LogLevel::Warn);
+    assert!(config.network_config.is_none());
+
+    let config = NodeConfig::from_toml_file(&path).unwrap();
+    assert_eq!(config.node_id, "file-node");
+}
+
+/// Test that malformed values are rejected with the variable name
+#[test]
+fn test_from_env_invalid_value() {
+    std::env::set_var("ENVTEST_INVALID_NODE_ID", "node-1");
+    std::env::set_var("ENVTEST_INVALID_NETWORK_ID", "network-1");
+    std::env::set_var("ENVTEST_INVALID_BIND_ADDR", "not-an-address");
+
+    let error = NodeConfig::from_env_with_prefix("ENVTEST_INVALID").unwrap_err();
+    match error.downcast_ref::<ConfigurationError>() {
+        Some(ConfigurationError::InvalidValue { name, .. }) => {
+            assert_eq!(name, "ENVTEST_INVALID_BIND_ADDR")
+        }
+        other => panic!("Expected an invalid value error, got {other:?}"),
+    }
+}
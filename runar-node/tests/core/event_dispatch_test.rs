@@ -0,0 +1,127 @@
+// Tests for the dispatch of published events
+//
+// These tests verify that publishing does not wait for slow subscribers by
+// default, that each subscriber still receives its events in order, and that
+// a bounded queue makes the publisher wait for a slow subscriber.
+
+use anyhow::Result;
+use runar_common::types::ArcValue;
+use runar_node::services::{EventContext, PublishOptions};
+use runar_node::testing::TestNode;
+use runar_node::{DispatchMode, Node, NodeDelegate};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::time::sleep;
+
+type EventFuture = Pin<Box<dyn Future<Output = Result<()>> + Send>>;
+type Received = Arc<Mutex<Vec<i64>>>;
+
+const SLOW_HANDLER: Duration = Duration::from_millis(100);
+
+/// Subscribe a handler taking `delay` per event, recording the events
+async fn subscribe_recording(node: &Node, topic: &str, delay: Duration, received: Received) {
+    node.subscribe(
+        topic.to_string(),
+        Box::new(move |_ctx: Arc<EventContext>, data: Option<ArcValue>| {
+            let received = received.clone();
+            Box::pin(async move {
+                sleep(delay).await;
+                received
+                    .lock()
+                    .unwrap()
+                    .push(data.unwrap().as_type::<i64>()?);
+                Ok(())
+            }) as EventFuture
+        }),
+    )
+    .await
+    .unwrap();
+}
+
+/// Publish 0..count and return how long it took
+async fn publish_numbers(node: &Node, topic: &str, count: i64, dispatch: DispatchMode) -> Duration {
+    let start = Instant::now();
+    for number in 0..count {
+        node.publish_with_options(
+            topic,
+            Some(ArcValue::new_primitive(number)),
+            PublishOptions {
+                dispatch,
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    }
+    start.elapsed()
+}
+
+async fn wait_for(received: &Received, count: usize) {
+    for _ in 0..100 {
+        if received.lock().unwrap().len() >= count {
+            return;
+        }
+        sleep(Duration::from_millis(20)).await;
+    }
+}
+
+/// Test that a slow subscriber does not stall the publisher
+///
+/// INTENTION: With the default dispatch, `publish` returns before the slow
+/// subscriber handled the events, a fast subscriber is not held up behind
+/// it, and both receive every event in publish order.
+#[tokio::test]
+async fn test_async_dispatch_does_not_block_publisher() {
+    let node = TestNode::builder().build().await.unwrap();
+    let slow = Received::default();
+    let fast = Received::default();
+    subscribe_recording(&node, "metrics/tick", SLOW_HANDLER, slow.clone()).await;
+    subscribe_recording(&node, "metrics/tick", Duration::ZERO, fast.clone()).await;
+
+    // The default publish dispatches in the background too
+    node.publish(
+        "metrics/tick".to_string(),
+        Some(ArcValue::new_primitive(0i64)),
+    )
+    .await
+    .unwrap();
+    let elapsed = publish_numbers(&node, "metrics/tick", 3, DispatchMode::default()).await;
+    assert!(elapsed < SLOW_HANDLER, "publish took {elapsed:?}");
+
+    wait_for(&fast, 4).await;
+    assert_eq!(*fast.lock().unwrap(), vec![0, 0, 1, 2]);
+    assert!(slow.lock().unwrap().len() < 4);
+    wait_for(&slow, 4).await;
+    assert_eq!(*slow.lock().unwrap(), vec![0, 0, 1, 2]);
+}
+
+/// Test the publisher waiting on a full queue or on the subscribers
+///
+/// INTENTION: `BoundedAsync` lets the publisher run ahead of a slow
+/// subscriber by the queue size only, and `Sync` returns once every
+/// subscriber handled the event.
+#[tokio::test]
+async fn test_bounded_and_sync_dispatch_wait_for_subscribers() {
+    let node = TestNode::builder().build().await.unwrap();
+    let received = Received::default();
+    subscribe_recording(&node, "metrics/tick", SLOW_HANDLER, received.clone()).await;
+
+    // Two events fit in the queue, the third waits for the first to be handled
+    let elapsed = publish_numbers(
+        &node,
+        "metrics/tick",
+        3,
+        DispatchMode::BoundedAsync { queue_size: 2 },
+    )
+    .await;
+    assert!(elapsed >= SLOW_HANDLER, "publish took {elapsed:?}");
+    wait_for(&received, 3).await;
+    assert_eq!(*received.lock().unwrap(), vec![0, 1, 2]);
+
+    received.lock().unwrap().clear();
+    let elapsed = publish_numbers(&node, "metrics/tick", 2, DispatchMode::Sync).await;
+    assert!(elapsed >= SLOW_HANDLER * 2, "publish took {elapsed:?}");
+    assert_eq!(*received.lock().unwrap(), vec![0, 1]);
+}
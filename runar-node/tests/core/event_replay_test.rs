@@ -0,0 +1,127 @@
+// Tests for the replay of retained events
+//
+// These tests verify that events published with `retain_last` are replayed
+// in order to subscribers joining later, before live events, and that the
+// ring buffer of a topic stays within its limits.
+
+use anyhow::Result;
+use runar_common::types::ArcValue;
+use runar_node::services::{EventContext, PublishOptions};
+use runar_node::testing::TestNode;
+use runar_node::{DispatchMode, EventReplayBuffer, Node, ReplaySince};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+
+type EventFuture = Pin<Box<dyn Future<Output = Result<()>> + Send>>;
+/// Sequence number and value of each received event
+type Received = Arc<Mutex<Vec<(Option<u64>, i64)>>>;
+
+async fn publish_retained(node: &Node, topic: &str, numbers: std::ops::Range<i64>) {
+    for number in numbers {
+        node.publish_with_options(
+            topic,
+            Some(ArcValue::new_primitive(number)),
+            PublishOptions {
+                retain_last: Some(3),
+                dispatch: DispatchMode::Sync,
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    }
+}
+
+/// Subscribe with replay, recording the sequence and value of each event
+async fn subscribe_recording(
+    node: &Node,
+    topic: &str,
+    since: ReplaySince,
+    received: Received,
+) -> Result<String> {
+    node.subscribe_with_replay(
+        topic,
+        Box::new(move |ctx: Arc<EventContext>, data: Option<ArcValue>| {
+            let received = received.clone();
+            Box::pin(async move {
+                let value = data.unwrap().as_type::<i64>()?;
+                received.lock().unwrap().push((ctx.sequence, value));
+                Ok(())
+            }) as EventFuture
+        }),
+        since,
+    )
+    .await
+}
+
+/// Test that late subscribers receive the retained events first
+///
+/// INTENTION: Only the last `retain_last` events are kept. A new subscriber
+/// receives those selected by `since` in publish order, then live events.
+#[tokio::test]
+async fn test_late_subscriber_receives_retained_events() {
+    let node = TestNode::builder().build().await.unwrap();
+    publish_retained(&node, "sensors/temperature", 1..6).await;
+
+    let last_two = Arc::new(Mutex::new(Vec::new()));
+    subscribe_recording(
+        &node,
+        "sensors/temperature",
+        ReplaySince::Last(2),
+        last_two.clone(),
+    )
+    .await
+    .unwrap();
+    let after_first = Arc::new(Mutex::new(Vec::new()));
+    subscribe_recording(
+        &node,
+        "sensors/temperature",
+        ReplaySince::After(1),
+        after_first.clone(),
+    )
+    .await
+    .unwrap();
+
+    publish_retained(&node, "sensors/temperature", 6..7).await;
+
+    assert_eq!(
+        *last_two.lock().unwrap(),
+        vec![(Some(4), 4), (Some(5), 5), (Some(6), 6)]
+    );
+    // Events 1 and 2 were evicted, so replay starts at 3
+    assert_eq!(
+        *after_first.lock().unwrap(),
+        vec![(Some(3), 3), (Some(4), 4), (Some(5), 5), (Some(6), 6)]
+    );
+
+    // Patterns cannot be replayed
+    let received = Arc::new(Mutex::new(Vec::new()));
+    assert!(
+        subscribe_recording(&node, "sensors/*", ReplaySince::Last(1), received)
+            .await
+            .is_err()
+    );
+}
+
+/// Test the limits of the ring buffer
+///
+/// INTENTION: A topic keeps at most `retain_last` events and at most its byte
+/// limit; an event larger than the limit is not retained at all.
+#[test]
+fn test_replay_buffer_limits() {
+    let mut buffer = EventReplayBuffer::new(100);
+    assert!(buffer.retain("a", 1, "one", 40, 5));
+    assert!(buffer.retain("a", 2, "two", 40, 5));
+    // Over the byte limit: the oldest event is evicted
+    assert!(buffer.retain("a", 3, "three", 40, 5));
+    assert_eq!(
+        buffer.replay("a", ReplaySince::Last(10)),
+        vec![(2, "two"), (3, "three")]
+    );
+
+    assert!(!buffer.retain("a", 4, "huge", 101, 5));
+    assert!(buffer.retain("a", 5, "five", 10, 1));
+    assert_eq!(buffer.replay("a", ReplaySince::After(0)), vec![(5, "five")]);
+    assert!(buffer.replay("b", ReplaySince::Last(1)).is_empty());
+}
@@ -0,0 +1,73 @@
+// Tests for per-component and per-service log level rules
+
+use log::LevelFilter;
+use runar_common::logging::Component;
+use runar_node::config::{LogLevel, LoggingConfig};
+
+/// Test that the most specific rule decides the level
+///
+/// INTENTION: Verify that service path rules beat component rules, that
+/// component rules beat the default, and that paths match whole segments.
+#[test]
+fn test_log_filter_rule_precedence() {
+    let filter = LoggingConfig::new()
+        .with_default_level(LogLevel::Info)
+        .with_component_level(Component::Network, LogLevel::Warn)
+        .with_component_level(Component::Service, LogLevel::Debug)
+        .with_service_path_level("math", LogLevel::Error)
+        .with_service_path_level("math/admin", LogLevel::Trace)
+        .log_filter();
+
+    assert_eq!(filter.level_for(Component::Node, None), LevelFilter::Info);
+    assert_eq!(
+        filter.level_for(Component::Network, None),
+        LevelFilter::Warn
+    );
+    // NetworkDiscovery shares the Network rule
+    assert_eq!(
+        filter.level_for(Component::NetworkDiscovery, None),
+        LevelFilter::Warn
+    );
+    assert_eq!(
+        filter.level_for(Component::Service, None),
+        LevelFilter::Debug
+    );
+
+    assert_eq!(
+        filter.level_for(Component::Service, Some("math/add")),
+        LevelFilter::Error
+    );
+    assert_eq!(
+        filter.level_for(Component::Service, Some("math")),
+        LevelFilter::Error
+    );
+    // The longest matching service path wins
+    assert_eq!(
+        filter.level_for(Component::Service, Some("math/admin/reset")),
+        LevelFilter::Trace
+    );
+    // "mathematics" is not under the "math" service
+    assert_eq!(
+        filter.level_for(Component::Service, Some("mathematics/add")),
+        LevelFilter::Debug
+    );
+
+    assert_eq!(filter.max_level(), LevelFilter::Trace);
+}
+
+/// Test that sampling rates configured per component reach the filter
+///
+/// INTENTION: `with_sampling_rate` gives every logger name of the component a
+/// sampler with that rate, and leaves the other components unsampled.
+#[test]
+fn test_log_filter_sampling_rates() {
+    let filter = LoggingConfig::new()
+        .with_sampling_rate(Component::Network, 0.25)
+        .log_filter();
+
+    for component in [Component::Network, Component::NetworkDiscovery] {
+        let sampler = filter.sampler_for(component).expect("sampled component");
+        assert_eq!(sampler.rate(), 0.25);
+    }
+    assert!(filter.sampler_for(Component::Service).is_none());
+}
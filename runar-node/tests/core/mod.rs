@@ -1,11 +1,28 @@
 // Core tests for the runar-node-new crate
 
+pub mod access_policy_test;
+pub mod background_task_test;
+pub mod circuit_breaker_test;
+pub mod config_validation_test;
+pub mod dead_letter_test;
+pub mod env_config_test;
+pub mod event_dispatch_test;
+pub mod event_replay_test;
 pub mod node_test;
+pub mod rate_limit_test;
 pub mod registry_service_test;
+pub mod request_fallback_test;
+pub mod retry_budget_test;
 pub mod service_registry_test;
+pub mod service_restart_test;
+pub mod shutdown_test;
+pub mod subscription_group_test;
+pub mod telemetry_test;
+pub mod test_node_test;
 pub mod topic_path_template_test;
 pub mod topic_path_test;
 pub mod topic_path_wildcard_test;
 
 pub mod event_metadata_test;
+pub mod logging_config_test;
 pub mod path_trie_test;
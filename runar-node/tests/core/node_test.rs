@@ -7,6 +7,7 @@ use runar_common::hmap;
 use runar_common::types::schemas::ServiceMetadata;
 use runar_common::types::ArcValue;
 use runar_node::config::logging_config::{LogLevel, LoggingConfig};
+use runar_node::network::transport::{ErrorCode, NetworkError};
 use runar_node::Node;
 use runar_test_utils::create_node_test_config;
 use std::future::Future;
@@ -15,6 +16,8 @@ use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::time::timeout;
+use tokio_stream::StreamExt;
+use tokio_util::sync::CancellationToken;
 
 use runar_node::services::EventContext;
 use runar_node::NodeDelegate;
@@ -22,6 +25,7 @@ use runar_node::NodeDelegate;
 use std::collections::HashMap;
 
 // Import the test fixtures
+use crate::fixtures::cancellable_service::CancellableService;
 use crate::fixtures::math_service::MathService;
 use crate::fixtures::path_params_service::PathParamsService;
 use anyhow::Result;
@@ -143,6 +147,51 @@ async fn test_node_request() {
     }
 }
 
+/// Test removing a service while the node runs
+///
+/// INTENTION: After `remove_service` the service's actions must no longer be
+/// routed and the service must be gone from the registry listing.
+#[tokio::test]
+async fn test_node_remove_service() -> Result<()> {
+    let mut config = create_node_test_config()?;
+    config.network_config = None;
+    let mut node = Node::new(config).await?;
+    node.add_service(MathService::new("Math Service", "math"))
+        .await?;
+    node.start().await?;
+
+    let params = ArcValue::new_map(hmap! {
+        "a" => 5.0,
+        "b" => 3.0
+    });
+    let result: f64 = node.request("math/add", Some(params.clone())).await?;
+    assert_eq!(result, 8.0);
+
+    node.remove_service("math").await?;
+
+    let err = node
+        .request::<ArcValue, f64>("math/add", Some(params))
+        .await
+        .expect_err("removed service must not handle requests");
+    match err.downcast_ref::<NetworkError>() {
+        Some(NetworkError::MessageError(ErrorCode::ServiceNotFound, reason)) => {
+            assert!(reason.ends_with(":math/add"), "{reason}")
+        }
+        other => panic!("expected service not found, got {other:?}"),
+    }
+
+    let services: Vec<ServiceMetadata> = node
+        .request("$registry/services/list", Option::<ArcValue>::None)
+        .await?;
+    assert!(services.iter().all(|s| s.service_path != "math"));
+
+    // Removing it again reports the missing service
+    assert!(node.remove_service("math").await.is_err());
+
+    node.stop().await?;
+    Ok(())
+}
+
 /// Test that verifies node lifecycle methods work correctly
 ///
 /// INTENTION: This test validates that the Node can properly:
@@ -371,3 +420,162 @@ async fn test_path_params_in_context() {
     assert_eq!(params_map.get("param_1").unwrap(), "abc123");
     assert_eq!(params_map.get("param_2").unwrap(), "xyz789");
 }
+
+/// Test that events can be consumed through a typed subscription stream
+///
+/// INTENTION: This test validates that the Node can properly:
+/// - Bridge published events into a typed async stream
+/// - Cancel the subscription once the stream is dropped
+#[tokio::test]
+async fn test_node_subscribe_stream() {
+    match timeout(Duration::from_secs(10), async {
+        let mut config = create_node_test_config().expect("Error creating test config");
+        config.network_config = None;
+        let node = Node::new(config).await.unwrap();
+
+        let mut stream = node
+            .subscribe_stream::<String>("test/stream")
+            .await
+            .unwrap();
+
+        for message in ["first", "second"] {
+            node.publish(
+                "test/stream".to_string(),
+                Some(ArcValue::new_primitive(message.to_string())),
+            )
+            .await
+            .unwrap();
+        }
+
+        let mut received = Vec::new();
+        while let Some(event) = stream.next().await {
+            received.push(event.unwrap());
+            if received.len() == 2 {
+                break;
+            }
+        }
+        assert_eq!(received, vec!["first".to_string(), "second".to_string()]);
+
+        // Dropping the stream cancels the subscription; publishing keeps working
+        drop(stream);
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        node.publish(
+            "test/stream".to_string(),
+            Some(ArcValue::new_primitive("third".to_string())),
+        )
+        .await
+        .unwrap();
+    })
+    .await
+    {
+        Ok(_) => (),
+        Err(_) => panic!("Test timed out after 10 seconds"),
+    }
+}
+
+/// Test that cancelling a request mid-flight is observed by the action body
+///
+/// INTENTION: This test validates that:
+/// - Actions receive a cancel token through their RequestContext
+/// - Cancelling the caller's token reaches nested requests made by the action
+/// - The caller gets control back as soon as it gives up on the request
+#[tokio::test]
+async fn test_request_cancel_token() {
+    match timeout(Duration::from_secs(10), async {
+        let mut config = create_node_test_config().expect("Error creating test config");
+        config.network_config = None;
+        let mut node = Node::new(config).await.unwrap();
+
+        let observed = Arc::new(AtomicBool::new(false));
+        let service = CancellableService::new("Cancellable", "cancellable", observed.clone());
+        node.add_service(service).await.unwrap();
+        node.start().await.unwrap();
+
+        let cancel_token = CancellationToken::new();
+        let request = node.request_with_cancel_token::<(), bool>(
+            "cancellable/nested",
+            None,
+            cancel_token.clone(),
+        );
+
+        // Cancel once the action is running, then abandon the request
+        let cancel = async {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            assert!(!observed.load(Ordering::SeqCst));
+            cancel_token.cancel();
+        };
+        let (_, result) = tokio::join!(cancel, request);
+        assert!(
+            result.unwrap(),
+            "Action should report that it was cancelled"
+        );
+        assert!(observed.load(Ordering::SeqCst));
+
+        // Dropping an in-flight request also cancels its action
+        observed.store(false, Ordering::SeqCst);
+        let abandoned = timeout(
+            Duration::from_millis(100),
+            node.request::<(), bool>("cancellable/wait", None),
+        )
+        .await;
+        assert!(abandoned.is_err());
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(observed.load(Ordering::SeqCst));
+    })
+    .await
+    {
+        Ok(_) => (),
+        Err(_) => panic!("Test timed out after 10 seconds"),
+    }
+}
+
+/// Test that the configured service timeout bounds requests without their own
+///
+/// INTENTION: This test validates that:
+/// - `NodeConfig::with_service_timeout` fails requests that run too long
+/// - The timed-out action sees its cancel token fire
+/// - A per-call timeout takes precedence over the configured one
+/// - A nested call's timeout cancels only that call
+#[tokio::test]
+async fn test_default_request_timeout() {
+    match timeout(Duration::from_secs(10), async {
+        let mut config = create_node_test_config().expect("Error creating test config");
+        config.network_config = None;
+        let config = config.with_service_timeout(Duration::from_millis(200));
+        assert_eq!(
+            config.default_request_timeout(),
+            Some(Duration::from_millis(200))
+        );
+        let mut node = Node::new(config).await.unwrap();
+
+        let observed = Arc::new(AtomicBool::new(false));
+        let service = CancellableService::new("Cancellable", "cancellable", observed.clone());
+        node.add_service(service).await.unwrap();
+        node.start().await.unwrap();
+
+        let result = node.request::<(), bool>("cancellable/wait", None).await;
+        let err = result.expect_err("Request should time out");
+        assert!(err.to_string().contains("timed out after 200ms"), "{err}");
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(observed.load(Ordering::SeqCst));
+
+        let started = std::time::Instant::now();
+        let result = node
+            .request_with_timeout::<(), bool>("cancellable/wait", None, Duration::from_millis(50))
+            .await;
+        assert!(result.is_err());
+        assert!(started.elapsed() < Duration::from_millis(200));
+
+        // A nested call timing out leaves the calling request running
+        let cancelled: bool = node
+            .request("cancellable/bounded", None::<()>)
+            .await
+            .unwrap();
+        assert!(!cancelled, "sub-call timeout cancelled its caller");
+    })
+    .await
+    {
+        Ok(_) => (),
+        Err(_) => panic!("Test timed out after 10 seconds"),
+    }
+}
@@ -485,6 +485,41 @@ mod tests {
         assert_eq!(matches7, Vec::<&str>::new());
     }
 
+    #[test]
+    fn test_path_trie_event_fan_out() {
+        // A published topic reaches its exact subscribers and every wildcard
+        // subscriber on its path, and nothing else
+        let mut trie = PathTrie::new();
+        trie.set_values(
+            TopicPath::new("orders/created", "network1").unwrap(),
+            vec!["EXACT_1", "EXACT_2"],
+        );
+        trie.set_value(TopicPath::new("orders/*", "network1").unwrap(), "SINGLE");
+        trie.set_value(TopicPath::new("orders/>", "network1").unwrap(), "MULTI");
+        trie.set_value(
+            TopicPath::new("orders/deleted", "network1").unwrap(),
+            "OTHER",
+        );
+        trie.set_value(
+            TopicPath::new("users/*", "network1").unwrap(),
+            "OTHER_WILDCARD",
+        );
+        for i in 0..1000 {
+            trie.set_value(
+                TopicPath::new(&format!("bulk{i}/event"), "network1").unwrap(),
+                "BULK",
+            );
+        }
+
+        let topic = TopicPath::new("orders/created", "network1").unwrap();
+        let mut matches = trie.find(&topic);
+        matches.sort();
+        assert_eq!(matches, vec!["EXACT_1", "EXACT_2", "MULTI", "SINGLE"]);
+
+        let topic = TopicPath::new("orders/created/eu", "network1").unwrap();
+        assert_eq!(trie.find(&topic), vec!["MULTI"]);
+    }
+
     #[test]
     fn test_path_trie_cross_network_search() {
         // This test verifies the behavior of find_matches when searching across networks
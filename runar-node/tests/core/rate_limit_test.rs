@@ -0,0 +1,183 @@
+// Tests for the RateLimitMiddleware and the Node middleware pipeline
+
+use anyhow::Result;
+use runar_common::hmap;
+use runar_common::types::schemas::ServiceMetadata;
+use runar_common::types::ArcValue;
+use runar_node::network::transport::PeerId;
+use runar_node::{Node, RateLimitExceeded, RateLimitMiddleware, RateQuota};
+use runar_test_utils::create_node_test_config;
+use std::time::Duration;
+use tokio::time::sleep;
+
+use crate::fixtures::math_service::MathService;
+
+/// Test that quota globs match service paths
+///
+/// INTENTION: `*` matches any characters and a quota without one only covers
+/// the exact service path.
+#[test]
+fn test_quota_globs() {
+    let limiter = RateLimitMiddleware::default()
+        .with_quota("math", RateQuota::new(1, 1, false))
+        .with_quota("billing/*", RateQuota::new(1, 1, false))
+        .with_quota("*/internal", RateQuota::new(1, 1, false));
+
+    for path in ["math", "billing/invoices", "admin/internal"] {
+        assert!(limiter.check(path, None).is_ok());
+        assert!(limiter.check(path, None).is_err(), "{path} is not limited");
+    }
+    // Unmatched services are never limited
+    for _ in 0..5 {
+        assert!(limiter.check("mathematics", None).is_ok());
+        assert!(limiter.check("billing", None).is_ok());
+    }
+}
+
+/// Test that the longest matching glob decides the quota
+#[test]
+fn test_most_specific_quota_applies() {
+    let limiter = RateLimitMiddleware::default()
+        .with_quota("*", RateQuota::new(1, 1, false))
+        .with_quota("math", RateQuota::new(1, 3, false));
+
+    for _ in 0..3 {
+        assert!(limiter.check("math", None).is_ok());
+    }
+    assert!(limiter.check("math", None).is_err());
+
+    assert!(limiter.check("other", None).is_ok());
+    let error = limiter.check("other", None).unwrap_err();
+    assert!(error.retry_after_ms > 0 && error.retry_after_ms <= 1000);
+}
+
+/// Test per-caller and shared buckets
+///
+/// INTENTION: A per-caller quota limits each peer on its own, a shared quota
+/// limits all callers together.
+#[test]
+fn test_per_caller_buckets() {
+    let alice = PeerId::new("alice".to_string());
+    let bob = PeerId::new("bob".to_string());
+
+    let per_caller = RateLimitMiddleware::default().with_quota("math", RateQuota::new(1, 1, true));
+    assert!(per_caller.check("math", Some(&alice)).is_ok());
+    assert!(per_caller.check("math", Some(&alice)).is_err());
+    assert!(per_caller.check("math", Some(&bob)).is_ok());
+
+    let shared = RateLimitMiddleware::default().with_quota("math", RateQuota::new(1, 1, false));
+    assert!(shared.check("math", Some(&alice)).is_ok());
+    assert!(shared.check("math", Some(&bob)).is_err());
+}
+
+/// Test that the bucket refills at the sustained rate
+#[tokio::test]
+async fn test_bucket_refills() {
+    let limiter = RateLimitMiddleware::default().with_quota("math", RateQuota::new(20, 1, false));
+    assert!(limiter.check("math", None).is_ok());
+    let error = limiter.check("math", None).unwrap_err();
+    assert!(error.retry_after_ms <= 50);
+
+    sleep(Duration::from_millis(100)).await;
+    assert!(limiter.check("math", None).is_ok());
+}
+
+/// Test that the Node rejects requests over quota before the handler runs
+///
+/// INTENTION: Middleware added with `Node::add_middleware` runs for local
+/// requests and its error reaches the caller as a typed `RateLimitExceeded`.
+#[tokio::test]
+async fn test_node_rate_limit_middleware() -> Result<()> {
+    let mut config = create_node_test_config()?;
+    config.network_config = None;
+    let mut node = Node::new(config).await?;
+    node.add_service(MathService::new("Math Service", "math"))
+        .await?;
+    node.add_middleware(
+        RateLimitMiddleware::default().with_quota("math", RateQuota::new(1, 2, false)),
+    )
+    .await;
+    node.start().await?;
+
+    let params = ArcValue::new_map(hmap! {
+        "a" => 5.0,
+        "b" => 3.0
+    });
+    for _ in 0..2 {
+        let result: f64 = node.request("math/add", Some(params.clone())).await?;
+        assert_eq!(result, 8.0);
+    }
+
+    let err = node
+        .request::<ArcValue, f64>("math/add", Some(params))
+        .await
+        .expect_err("third request exceeds the burst");
+    let rate_limited = err
+        .downcast_ref::<RateLimitExceeded>()
+        .expect("rate limit error");
+    assert!(rate_limited.retry_after_ms > 0);
+
+    // Services without a quota are unaffected
+    let services: Vec<ServiceMetadata> = node
+        .request("$registry/services/list", Option::<ArcValue>::None)
+        .await?;
+    assert!(!services.is_empty());
+
+    node.stop().await?;
+    Ok(())
+}
+
+/// Test that a peer over its quota gets a rate limit error
+///
+/// INTENTION: Per-caller quotas key their buckets on the remote peer, and the
+/// rejection reaches the caller with `ErrorCode::RateLimited`.
+#[cfg(unix)]
+#[tokio::test]
+async fn test_rate_limited_peer() -> Result<()> {
+    use crate::network::{socket_path, with_local_socket};
+    use runar_node::network::transport::{ErrorCode, NetworkError};
+
+    let configs = runar_test_utils::create_networked_node_test_config(2)?;
+    let node1_socket = socket_path("limit1");
+    let node2_socket = socket_path("limit2");
+
+    let mut node1 = Node::new(with_local_socket(configs[0].clone(), &node1_socket)).await?;
+    node1
+        .add_service(MathService::new("limited", "limited"))
+        .await?;
+    node1
+        .add_middleware(
+            RateLimitMiddleware::default().with_quota("limited", RateQuota::new(1, 2, true)),
+        )
+        .await;
+    node1.start().await?;
+
+    let mut node2 = Node::new(with_local_socket(configs[1].clone(), &node2_socket)).await?;
+    node2.start().await?;
+    node2.add_local_peer(&node1_socket).await?;
+
+    let params = ArcValue::new_map(hmap! {
+        "a" => 2.0,
+        "b" => 3.0
+    });
+    for _ in 0..2 {
+        let response: f64 = node2.request("limited/add", Some(params.clone())).await?;
+        assert_eq!(response, 5.0);
+    }
+    let err = node2
+        .request::<ArcValue, f64>("limited/add", Some(params.clone()))
+        .await
+        .expect_err("third request exceeds the burst");
+    match err.downcast_ref::<NetworkError>() {
+        Some(error) => assert_eq!(error.code(), ErrorCode::RateLimited),
+        None => panic!("expected a rate limit error, got {err}"),
+    }
+
+    // The peer's bucket is separate from the one used by node1 itself
+    let response: f64 = node1.request("limited/add", Some(params)).await?;
+    assert_eq!(response, 5.0);
+
+    node2.stop().await?;
+    node1.stop().await?;
+    Ok(())
+}
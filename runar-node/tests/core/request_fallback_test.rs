@@ -0,0 +1,190 @@
+// Tests for requests with a fallback provider
+//
+// These tests verify that `Node::request_with_fallback` returns the response
+// of a successful request, answers from the fallback when the request fails
+// with a retryable error or times out, and returns other errors unchanged,
+// for local services and for services of a remote node behind a circuit.
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use runar_common::types::ArcValue;
+use runar_node::network::transport::{ErrorCode, NetworkError};
+use runar_node::services::abstract_service::AbstractService;
+use runar_node::services::LifecycleContext;
+use runar_node::testing::{TestCredentials, TestNode};
+use runar_node::{CircuitBreakerConfig, Node};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// A service with actions that succeed, fail or hang
+#[derive(Clone)]
+struct RatesService {
+    network_id: Option<String>,
+}
+
+#[async_trait]
+impl AbstractService for RatesService {
+    fn name(&self) -> &str {
+        "rates"
+    }
+
+    fn version(&self) -> &str {
+        "1.0.0"
+    }
+
+    fn path(&self) -> &str {
+        "rates"
+    }
+
+    fn description(&self) -> &str {
+        "Request fallback test service"
+    }
+
+    fn network_id(&self) -> Option<String> {
+        self.network_id.clone()
+    }
+
+    fn set_network_id(&mut self, network_id: String) {
+        self.network_id = Some(network_id);
+    }
+
+    async fn init(&self, context: LifecycleContext) -> Result<()> {
+        context
+            .register_action(
+                "live",
+                Arc::new(|_params: Option<ArcValue>, _ctx| {
+                    Box::pin(async move { Ok(ArcValue::new_primitive(1.25f64)) })
+                }),
+            )
+            .await?;
+        context
+            .register_action(
+                "unreachable",
+                Arc::new(|_params: Option<ArcValue>, _ctx| {
+                    Box::pin(async move {
+                        Err(NetworkError::ConnectionError(
+                            ErrorCode::PeerUnreachable,
+                            "exchange offline".to_string(),
+                        )
+                        .into())
+                    })
+                }),
+            )
+            .await?;
+        context
+            .register_action(
+                "invalid",
+                Arc::new(|_params: Option<ArcValue>, _ctx| {
+                    Box::pin(async move { Err(anyhow!("unknown currency")) })
+                }),
+            )
+            .await?;
+        context
+            .register_action(
+                "slow",
+                Arc::new(|_params: Option<ArcValue>, _ctx| {
+                    Box::pin(async move {
+                        tokio::time::sleep(Duration::from_secs(5)).await;
+                        Ok(ArcValue::new_primitive(1.25f64))
+                    })
+                }),
+            )
+            .await
+    }
+
+    async fn start(&self, _context: LifecycleContext) -> Result<()> {
+        Ok(())
+    }
+
+    async fn stop(&self, _context: LifecycleContext) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Request `path`, answering 1.0 from the fallback and counting its calls
+async fn rate(node: &Node, path: &str, fallbacks: &AtomicUsize) -> Result<f64> {
+    node.request_with_fallback(path, None::<()>, |error| async move {
+        assert!(error.is_retryable(), "{error}");
+        fallbacks.fetch_add(1, Ordering::SeqCst);
+        Ok(1.0)
+    })
+    .await
+}
+
+/// Test that the fallback answers only for unreachable services
+///
+/// INTENTION: A successful request never calls the fallback; a retryable
+/// error or a timeout is answered by it; any other error is returned to the
+/// caller without calling it.
+#[tokio::test]
+async fn test_request_with_fallback() -> Result<()> {
+    let node = TestNode::builder()
+        .with_service(RatesService { network_id: None })
+        .with_config(|config| config.with_service_timeout(Duration::from_millis(200)))
+        .build()
+        .await?;
+    let fallbacks = AtomicUsize::new(0);
+
+    assert_eq!(rate(&node, "rates/live", &fallbacks).await?, 1.25);
+    assert_eq!(fallbacks.load(Ordering::SeqCst), 0);
+
+    assert_eq!(rate(&node, "rates/unreachable", &fallbacks).await?, 1.0);
+    assert_eq!(fallbacks.load(Ordering::SeqCst), 1);
+
+    assert_eq!(rate(&node, "rates/slow", &fallbacks).await?, 1.0);
+    assert_eq!(fallbacks.load(Ordering::SeqCst), 2);
+
+    let error = rate(&node, "rates/invalid", &fallbacks).await.unwrap_err();
+    assert!(error.to_string().contains("unknown currency"), "{error}");
+    assert_eq!(fallbacks.load(Ordering::SeqCst), 2);
+    Ok(())
+}
+
+/// Test the fallback for a service of another node
+///
+/// INTENTION: A remote request that times out reaches the fallback with a
+/// retryable `Timeout` error and counts against the service's circuit; once
+/// the circuit is open, requests are answered by the fallback with
+/// `CircuitOpen` without reaching the remote node.
+#[tokio::test]
+async fn test_request_with_fallback_to_remote_service() -> Result<()> {
+    let credentials = TestCredentials::new()?;
+    let node1 = TestNode::builder()
+        .with_credentials(&credentials)
+        .with_quic_transport()
+        .with_service(RatesService { network_id: None })
+        .build()
+        .await?;
+    let mut node2 = TestNode::builder()
+        .with_credentials(&credentials)
+        .with_quic_transport()
+        .with_config(|config| {
+            config.with_request_timeout(300).with_circuit_breaker(
+                "rates",
+                CircuitBreakerConfig::new(0.5, Duration::from_secs(10), Duration::from_secs(10))
+                    .with_minimum_requests(2),
+            )
+        })
+        .build()
+        .await?;
+    node2.connect(&node1).await?;
+
+    let codes = Mutex::new(Vec::new());
+    let rate = |path: &'static str| {
+        node2.request_with_fallback(path, None::<()>, |error| {
+            codes.lock().unwrap().push(error.code());
+            async { Ok(1.0f64) }
+        })
+    };
+
+    assert_eq!(rate("rates/live").await?, 1.25);
+    assert_eq!(rate("rates/slow").await?, 1.0);
+    assert_eq!(rate("rates/live").await?, 1.0);
+    assert_eq!(
+        *codes.lock().unwrap(),
+        vec![ErrorCode::Timeout, ErrorCode::CircuitOpen]
+    );
+
+    Ok(())
+}
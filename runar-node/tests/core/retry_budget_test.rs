@@ -0,0 +1,214 @@
+// Tests for request retries and the retry budget
+//
+// These tests verify that `Node::request_with_retries` retries calls failing
+// with a retryable error until one succeeds or the retries are used up, that
+// other errors are not retried, and that a service path's retry budget stops
+// retries once it is exhausted.
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use runar_common::types::ArcValue;
+use runar_node::network::transport::{ErrorCode, NetworkError};
+use runar_node::services::abstract_service::AbstractService;
+use runar_node::services::LifecycleContext;
+use runar_node::testing::TestNode;
+use runar_node::{RetryBucket, RetryBudget};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// A service whose action fails a given number of times before succeeding,
+/// and whose `invalid` action fails with an error that is not retryable
+#[derive(Clone)]
+struct FlakyService {
+    network_id: Option<String>,
+    failures: usize,
+    calls: Arc<AtomicUsize>,
+}
+
+impl FlakyService {
+    fn new(failures: usize) -> Self {
+        Self {
+            network_id: None,
+            failures,
+            calls: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    fn calls(&self) -> usize {
+        self.calls.load(Ordering::SeqCst)
+    }
+}
+
+#[async_trait]
+impl AbstractService for FlakyService {
+    fn name(&self) -> &str {
+        "Flaky"
+    }
+
+    fn version(&self) -> &str {
+        "1.0.0"
+    }
+
+    fn path(&self) -> &str {
+        "flaky"
+    }
+
+    fn description(&self) -> &str {
+        "Retry budget test service"
+    }
+
+    fn network_id(&self) -> Option<String> {
+        self.network_id.clone()
+    }
+
+    fn set_network_id(&mut self, network_id: String) {
+        self.network_id = Some(network_id);
+    }
+
+    async fn init(&self, context: LifecycleContext) -> Result<()> {
+        let failures = self.failures;
+        let calls = self.calls.clone();
+        context
+            .register_action(
+                "call",
+                Arc::new(move |_params: Option<ArcValue>, _ctx| {
+                    let call = calls.fetch_add(1, Ordering::SeqCst);
+                    Box::pin(async move {
+                        if call < failures {
+                            return Err(NetworkError::ConnectionError(
+                                ErrorCode::PeerUnreachable,
+                                format!("flaky failure {call}"),
+                            )
+                            .into());
+                        }
+                        Ok(ArcValue::new_primitive("ok".to_string()))
+                    })
+                }),
+            )
+            .await?;
+        let calls = self.calls.clone();
+        context
+            .register_action(
+                "invalid",
+                Arc::new(move |_params: Option<ArcValue>, _ctx| {
+                    calls.fetch_add(1, Ordering::SeqCst);
+                    Box::pin(async move { Err(anyhow!("invalid request")) })
+                }),
+            )
+            .await
+    }
+
+    async fn start(&self, _context: LifecycleContext) -> Result<()> {
+        Ok(())
+    }
+
+    async fn stop(&self, _context: LifecycleContext) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Test that failed calls are retried until one succeeds
+///
+/// INTENTION: Without a retry budget a request is attempted up to
+/// `retries + 1` times, returning the first success or the last error.
+#[tokio::test]
+async fn test_request_retried_until_success() -> Result<()> {
+    let service = FlakyService::new(2);
+    let node = TestNode::builder()
+        .with_service(service.clone())
+        .build()
+        .await?;
+
+    let response: String = node
+        .request_with_retries("flaky/call", None::<()>, 2)
+        .await?;
+    assert_eq!(response, "ok");
+    assert_eq!(service.calls(), 3);
+
+    let service = FlakyService::new(usize::MAX);
+    let node = TestNode::builder()
+        .with_service(service.clone())
+        .build()
+        .await?;
+    let error = node
+        .request_with_retries::<(), String>("flaky/call", None, 3)
+        .await
+        .unwrap_err();
+    assert!(error.to_string().contains("flaky failure 3"), "{error}");
+    assert_eq!(service.calls(), 4);
+    Ok(())
+}
+
+/// Test that errors which are not retryable are returned at once
+///
+/// INTENTION: Retrying cannot fix an error that is not a retryable
+/// `NetworkError`, such as a rejected request, so it is attempted once.
+#[tokio::test]
+async fn test_non_retryable_error_not_retried() -> Result<()> {
+    let service = FlakyService::new(0);
+    let node = TestNode::builder()
+        .with_service(service.clone())
+        .build()
+        .await?;
+    let error = node
+        .request_with_retries::<(), String>("flaky/invalid", None, 3)
+        .await
+        .unwrap_err();
+    assert!(error.to_string().contains("invalid request"), "{error}");
+    assert_eq!(service.calls(), 1);
+    Ok(())
+}
+
+/// Test that an exhausted retry budget stops retries
+///
+/// INTENTION: A budget allowing one retry per second and none per request
+/// lets the first failing request retry once; the following request fails
+/// after its first attempt because the budget has no token left.
+#[tokio::test]
+async fn test_retry_budget_exhausted_returns_error() -> Result<()> {
+    let service = FlakyService::new(usize::MAX);
+    let node = TestNode::builder()
+        .with_service(service.clone())
+        .with_config(|config| config.with_retry_budget("flaky", RetryBudget::new(0.0, 1)))
+        .build()
+        .await?;
+
+    assert!(node
+        .request_with_retries::<(), String>("flaky/call", None, 3)
+        .await
+        .is_err());
+    assert_eq!(service.calls(), 2);
+
+    let error = node
+        .request_with_retries::<(), String>("flaky/call", None, 3)
+        .await
+        .unwrap_err();
+    assert!(error.to_string().contains("flaky failure 2"), "{error}");
+    assert_eq!(service.calls(), 3);
+    Ok(())
+}
+
+/// Test the token accounting of a retry bucket
+///
+/// INTENTION: Requests add `max_retry_fraction` of a retry each, and the
+/// bucket refills at `min_retries_per_second` over time.
+#[tokio::test]
+async fn test_retry_bucket_tokens() {
+    let bucket = RetryBucket::new(RetryBudget::new(0.5, 0));
+    assert!(!bucket.try_retry());
+
+    bucket.record_request();
+    assert!(!bucket.try_retry());
+    bucket.record_request();
+    assert!(bucket.try_retry());
+    assert!(!bucket.try_retry());
+
+    let bucket = RetryBucket::new(RetryBudget::new(0.0, 20));
+    for _ in 0..20 {
+        assert!(bucket.try_retry());
+    }
+    assert!(!bucket.try_retry());
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert!(bucket.try_retry());
+}
@@ -0,0 +1,197 @@
+// Tests for the restart of services whose actions panic
+//
+// These tests verify that a panicking action makes the node restart its
+// service after the backoff of the restart policy and announce the restart,
+// and that a service panicking more often than its policy allows is marked
+// unhealthy and no longer receives requests.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use runar_common::types::ArcValue;
+use runar_node::services::abstract_service::{AbstractService, ServiceState};
+use runar_node::services::{EventContext, LifecycleContext};
+use runar_node::{Node, NodeDelegate, ServiceRestartPolicy};
+use runar_test_utils::create_node_test_config;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::time::sleep;
+
+type EventFuture = Pin<Box<dyn Future<Output = Result<()>> + Send>>;
+
+const BACKOFF: Duration = Duration::from_millis(50);
+
+/// A service with an action that panics when asked to
+#[derive(Clone)]
+struct FragileService {
+    network_id: Option<String>,
+    inits: Arc<AtomicUsize>,
+    stops: Arc<AtomicUsize>,
+}
+
+impl FragileService {
+    fn new() -> Self {
+        Self {
+            network_id: None,
+            inits: Arc::new(AtomicUsize::new(0)),
+            stops: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+}
+
+#[async_trait]
+impl AbstractService for FragileService {
+    fn name(&self) -> &str {
+        "Fragile"
+    }
+
+    fn version(&self) -> &str {
+        "1.0.0"
+    }
+
+    fn path(&self) -> &str {
+        "fragile"
+    }
+
+    fn description(&self) -> &str {
+        "Service restart test service"
+    }
+
+    fn network_id(&self) -> Option<String> {
+        self.network_id.clone()
+    }
+
+    fn set_network_id(&mut self, network_id: String) {
+        self.network_id = Some(network_id);
+    }
+
+    fn restart_policy(&self) -> ServiceRestartPolicy {
+        ServiceRestartPolicy::new(2, BACKOFF)
+    }
+
+    async fn init(&self, context: LifecycleContext) -> Result<()> {
+        self.inits.fetch_add(1, Ordering::SeqCst);
+        context
+            .register_action(
+                "echo",
+                Arc::new(|params: Option<ArcValue>, _ctx| {
+                    Box::pin(async move {
+                        let mut params = params.unwrap_or_else(ArcValue::null);
+                        let panic: bool = params.as_type()?;
+                        if panic {
+                            panic!("fragile action failed");
+                        }
+                        Ok(ArcValue::new_primitive("ok".to_string()))
+                    })
+                }),
+            )
+            .await
+    }
+
+    async fn start(&self, _context: LifecycleContext) -> Result<()> {
+        Ok(())
+    }
+
+    async fn stop(&self, _context: LifecycleContext) -> Result<()> {
+        self.stops.fetch_add(1, Ordering::SeqCst);
+        Ok(())
+    }
+}
+
+async fn echo(node: &Node, panic: bool) -> Result<String> {
+    node.request("fragile/echo", Some(panic)).await
+}
+
+async fn service_state(node: &Node) -> Result<ServiceState> {
+    node.request("$registry/services/fragile/state", None::<()>)
+        .await
+}
+
+/// Wait until the service was initialized `inits` times, then call it
+async fn echo_after_init(node: &Node, service: &FragileService, inits: usize) -> Result<String> {
+    for _ in 0..50 {
+        if service.inits.load(Ordering::SeqCst) >= inits {
+            break;
+        }
+        sleep(BACKOFF / 5).await;
+    }
+    // Let the restart register the service again
+    sleep(BACKOFF / 5).await;
+    echo(node, false).await
+}
+
+/// Test that a panicking action restarts its service
+///
+/// INTENTION: The failing request returns an error, the service is stopped
+/// and initialized again after the backoff, a `$registry/service/restarted`
+/// event names it, and its actions answer again.
+#[tokio::test]
+async fn test_panicking_action_restarts_service() -> Result<()> {
+    let mut config = create_node_test_config()?;
+    config.network_config = None;
+    let mut node = Node::new(config).await?;
+    let service = FragileService::new();
+    node.add_service(service.clone()).await?;
+    node.start().await?;
+
+    let restarts = Arc::new(Mutex::new(Vec::new()));
+    let received = restarts.clone();
+    node.subscribe(
+        "$registry/service/restarted".to_string(),
+        Box::new(move |_ctx: Arc<EventContext>, data: Option<ArcValue>| {
+            let received = received.clone();
+            Box::pin(async move {
+                let mut data = data.unwrap();
+                let service: String = data.index_map("service")?.as_type()?;
+                let restarts: u32 = data.index_map("restarts")?.as_type()?;
+                received.lock().unwrap().push((service, restarts));
+                Ok(())
+            }) as EventFuture
+        }),
+    )
+    .await?;
+
+    assert_eq!(echo(&node, false).await?, "ok");
+    assert!(echo(&node, true).await.is_err());
+    assert_eq!(echo_after_init(&node, &service, 2).await?, "ok");
+
+    assert_eq!(service.inits.load(Ordering::SeqCst), 2);
+    assert_eq!(service.stops.load(Ordering::SeqCst), 1);
+    assert_eq!(service_state(&node).await?, ServiceState::Running);
+    assert_eq!(*restarts.lock().unwrap(), vec![("fragile".to_string(), 1)]);
+
+    node.stop().await?;
+    Ok(())
+}
+
+/// Test that a service panicking too often is marked unhealthy
+///
+/// INTENTION: After `max_restarts` restarts the next panic does not restart
+/// the service again; it is reported `Unhealthy` and requests to it fail
+/// without reaching its handlers.
+#[tokio::test]
+async fn test_service_marked_unhealthy_after_max_restarts() -> Result<()> {
+    let mut config = create_node_test_config()?;
+    config.network_config = None;
+    let mut node = Node::new(config).await?;
+    let service = FragileService::new();
+    node.add_service(service.clone()).await?;
+    node.start().await?;
+
+    for restart in 1..=2 {
+        assert!(echo(&node, true).await.is_err());
+        assert_eq!(echo_after_init(&node, &service, restart + 1).await?, "ok");
+    }
+    assert!(echo(&node, true).await.is_err());
+    sleep(BACKOFF * 3).await;
+
+    assert_eq!(service.inits.load(Ordering::SeqCst), 3);
+    assert_eq!(service_state(&node).await?, ServiceState::Unhealthy);
+    let error = echo(&node, false).await.unwrap_err();
+    assert!(error.to_string().contains("unhealthy"), "{error}");
+
+    node.stop().await?;
+    Ok(())
+}
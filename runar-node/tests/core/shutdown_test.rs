@@ -0,0 +1,106 @@
+// Tests for the graceful shutdown of a node
+//
+// These tests verify that `Node::stop` waits for in-flight requests during
+// the shutdown drain period, that `Node::wait_for_shutdown` completes once
+// the node has stopped, and that SIGTERM stops a node configured with
+// `NodeConfig::with_unix_signal_handling`.
+
+use runar_node::testing::TestNode;
+use runar_node::Node;
+use runar_test_utils::create_node_test_config;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::fixtures::cancellable_service::CancellableService;
+
+/// Test that stop waits for in-flight requests up to the drain period
+///
+/// INTENTION: A request still running when the node stops delays the shutdown
+/// by at most the drain period, while a node without requests in flight stops
+/// right away.
+#[tokio::test]
+async fn test_stop_drains_in_flight_requests() {
+    let service = CancellableService::new(
+        "Cancellable",
+        "cancellable",
+        Arc::new(AtomicBool::new(false)),
+    );
+    let mut node = TestNode::builder()
+        .with_service(service)
+        .with_config(|config| config.with_shutdown_drain_period(Duration::from_millis(300)))
+        .build()
+        .await
+        .unwrap();
+
+    // The wait action runs until it is cancelled, outliving the drain period
+    let requester = node.clone();
+    let request = tokio::spawn(async move {
+        let _ = requester
+            .request::<(), bool>("cancellable/wait", None)
+            .await;
+    });
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    let started = Instant::now();
+    node.stop().await.unwrap();
+    assert!(started.elapsed() >= Duration::from_millis(300));
+    request.abort();
+
+    let mut idle_node = TestNode::builder()
+        .with_config(|config| config.with_shutdown_drain_period(Duration::from_secs(5)))
+        .build()
+        .await
+        .unwrap();
+    let started = Instant::now();
+    idle_node.stop().await.unwrap();
+    assert!(started.elapsed() < Duration::from_secs(1));
+}
+
+/// Test that wait_for_shutdown completes once the node has stopped
+///
+/// INTENTION: The future only completes after `stop` finished, including when
+/// it was obtained before the node started.
+#[tokio::test]
+async fn test_wait_for_shutdown_completes_after_stop() {
+    // Built by hand, as the test node builder starts the node
+    let mut node = Node::new(create_node_test_config().unwrap()).await.unwrap();
+    let shutdown = node.wait_for_shutdown();
+    node.start().await.unwrap();
+
+    let waiter = tokio::spawn(shutdown);
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert!(!waiter.is_finished());
+
+    node.stop().await.unwrap();
+    tokio::time::timeout(Duration::from_secs(5), waiter)
+        .await
+        .expect("wait_for_shutdown did not complete")
+        .unwrap();
+}
+
+/// Test that SIGTERM stops a node with Unix signal handling
+///
+/// INTENTION: The signal handler installed by `start` stops the node instead of
+/// letting the signal terminate the process, so `wait_for_shutdown` completes.
+#[cfg(unix)]
+#[tokio::test]
+async fn test_sigterm_stops_node() {
+    let mut node = TestNode::builder()
+        .with_config(|config| config.with_unix_signal_handling(true))
+        .build()
+        .await
+        .unwrap();
+
+    // SAFETY: raising a signal has no memory safety requirements
+    unsafe {
+        libc::raise(libc::SIGTERM);
+    }
+
+    tokio::time::timeout(Duration::from_secs(5), node.wait_for_shutdown())
+        .await
+        .expect("SIGTERM did not stop the node");
+
+    // The handler stopped the node, so stopping it again is a no-op
+    node.stop().await.unwrap();
+}
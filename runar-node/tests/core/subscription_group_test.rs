@@ -0,0 +1,178 @@
+// Tests for subscription groups
+//
+// These tests verify that the members of a group share the events of their
+// topic round-robin, and that events for a group without members are queued
+// in a bounded, expiring buffer until a member joins.
+
+use anyhow::Result;
+use runar_common::types::ArcValue;
+use runar_node::services::subscription_groups::{PendingGroupEvent, SubscriptionGroups};
+use runar_node::services::{EventContext, EventRegistrationOptions, PublishOptions};
+use runar_node::testing::TestNode;
+use runar_node::{DispatchMode, Node, NodeDelegate, TopicPath};
+use std::collections::HashSet;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+type EventFuture = Pin<Box<dyn Future<Output = Result<()>> + Send>>;
+
+/// Subscribe a handler recording the events it receives under `name`
+async fn subscribe_recording(
+    node: &Node,
+    topic: &str,
+    group: Option<&str>,
+    name: &'static str,
+    received: Arc<Mutex<Vec<(&'static str, i64)>>>,
+) -> String {
+    node.subscribe_with_options(
+        topic.to_string(),
+        Box::new(move |_ctx: Arc<EventContext>, data: Option<ArcValue>| {
+            let received = received.clone();
+            Box::pin(async move {
+                let value = data.unwrap().as_type::<i64>()?;
+                received.lock().unwrap().push((name, value));
+                Ok(())
+            }) as EventFuture
+        }),
+        EventRegistrationOptions {
+            group: group.map(str::to_string),
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap()
+}
+
+async fn publish_numbers(node: &Node, topic: &str, numbers: std::ops::Range<i64>) {
+    for number in numbers {
+        node.publish_with_options(
+            topic,
+            Some(ArcValue::new_primitive(number)),
+            PublishOptions {
+                dispatch: DispatchMode::Sync,
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    }
+}
+
+/// Test round-robin delivery within a group
+///
+/// INTENTION: Each event goes to exactly one member of the group, in turn,
+/// while subscriptions without a group (or with an empty one) receive every
+/// event. A member that unsubscribes is skipped from then on.
+#[tokio::test]
+async fn test_group_members_share_events() {
+    let node = TestNode::builder()
+        .with_config(|config| config.with_subscription_group_queue_size(10))
+        .build()
+        .await
+        .unwrap();
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let first =
+        subscribe_recording(&node, "jobs/new", Some("workers"), "a", received.clone()).await;
+    subscribe_recording(&node, "jobs/new", Some("workers"), "c", received.clone()).await;
+    subscribe_recording(&node, "jobs/new", None, "audit", received.clone()).await;
+    subscribe_recording(&node, "jobs/new", Some(""), "metrics", received.clone()).await;
+
+    publish_numbers(&node, "jobs/new", 0..4).await;
+
+    let events = std::mem::take(&mut *received.lock().unwrap());
+    let delivered_to = |name: &str| -> Vec<i64> {
+        events
+            .iter()
+            .filter(|(member, _)| *member == name)
+            .map(|(_, value)| *value)
+            .collect()
+    };
+    assert_eq!(delivered_to("audit"), vec![0, 1, 2, 3]);
+    assert_eq!(delivered_to("metrics"), vec![0, 1, 2, 3]);
+    let mut shared = delivered_to("a");
+    shared.extend(delivered_to("c"));
+    shared.sort();
+    assert_eq!(shared, vec![0, 1, 2, 3]);
+    assert_eq!(delivered_to("a").len(), 2);
+    assert_eq!(delivered_to("c").len(), 2);
+
+    node.unsubscribe(Some(&first)).await.unwrap();
+    publish_numbers(&node, "jobs/new", 4..6).await;
+    let events = std::mem::take(&mut *received.lock().unwrap());
+    let remaining: Vec<_> = events
+        .iter()
+        .filter(|(member, _)| *member == "a" || *member == "c")
+        .collect();
+    assert_eq!(remaining, vec![&("c", 4), &("c", 5)]);
+}
+
+/// Test queueing events for a group without members
+///
+/// INTENTION: Events published while every member of a group is gone are
+/// kept, up to the configured size with the oldest dropped first, and handed
+/// to the next member that joins.
+#[tokio::test]
+async fn test_group_queues_events_without_members() {
+    let node = TestNode::builder()
+        .with_config(|config| config.with_subscription_group_queue_size(2))
+        .build()
+        .await
+        .unwrap();
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let worker =
+        subscribe_recording(&node, "jobs/new", Some("workers"), "a", received.clone()).await;
+    node.unsubscribe(Some(&worker)).await.unwrap();
+
+    publish_numbers(&node, "jobs/new", 0..3).await;
+    assert!(received.lock().unwrap().is_empty());
+
+    subscribe_recording(&node, "jobs/new", Some("workers"), "b", received.clone()).await;
+    // Queued events are handed over in the background
+    for _ in 0..50 {
+        if received.lock().unwrap().len() == 2 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    assert_eq!(*received.lock().unwrap(), vec![("b", 1), ("b", 2)]);
+
+    // Delivery is direct again once the group has a member
+    publish_numbers(&node, "jobs/new", 3..4).await;
+    assert_eq!(received.lock().unwrap().last(), Some(&("b", 3)));
+}
+
+/// Test expiring queued events and groups without members
+///
+/// INTENTION: Events queued for a group are dropped once older than the
+/// queue TTL instead of being handed to a late member, and a group whose
+/// members all left is forgotten once it had neither members nor events
+/// for that long.
+#[tokio::test]
+async fn test_group_queue_expires() {
+    let ttl = Duration::from_millis(100);
+    let topic = TopicPath::new("jobs/new", "test-network").unwrap();
+    let event = PendingGroupEvent {
+        topic_path: topic.clone(),
+        data: Some(ArcValue::new_primitive(1i64)),
+        sequence: None,
+    };
+    let mut groups = SubscriptionGroups::new(10).with_queue_ttl(ttl);
+
+    groups.join(&topic, "workers", "a");
+    groups.leave("a");
+    assert!(groups.route(&event, &HashSet::new()).is_empty());
+    assert_eq!(groups.pending_len(&topic, "workers"), 1);
+    assert_eq!(groups.group_count(), 1);
+
+    tokio::time::sleep(ttl * 2).await;
+    assert_eq!(groups.pending_len(&topic, "workers"), 0);
+    assert!(groups.join(&topic, "workers", "b").is_empty());
+
+    groups.leave("b");
+    assert_eq!(groups.group_count(), 1);
+    tokio::time::sleep(ttl * 2).await;
+    groups.leave("unknown");
+    assert_eq!(groups.group_count(), 0);
+}
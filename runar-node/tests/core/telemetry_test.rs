@@ -0,0 +1,67 @@
+// Tests for the OTLP telemetry configuration
+//
+// These tests verify the telemetry configuration of a node. Exporting is
+// disabled for the test process so no spans leave it.
+
+use anyhow::Result;
+use runar_common::hmap;
+use runar_common::types::ArcValue;
+use runar_node::telemetry::{self, OtlpConfig};
+use runar_node::{Node, NodeDelegate};
+use runar_test_utils::create_node_test_config;
+use tracing_subscriber::Registry;
+
+use crate::fixtures::math_service::MathService;
+
+/// Test the sample rate of the OTLP configuration
+///
+/// INTENTION: Every trace is exported by default, and rates outside 0.0..=1.0
+/// are clamped rather than rejected.
+#[test]
+fn test_otlp_config_sample_rate() {
+    let config = OtlpConfig::new("http://localhost:4318/v1/traces", "orders");
+    assert_eq!(config.endpoint, "http://localhost:4318/v1/traces");
+    assert_eq!(config.service_name, "orders");
+    assert_eq!(config.sample_rate, 1.0);
+
+    assert_eq!(config.clone().with_sample_rate(0.25).sample_rate, 0.25);
+    assert_eq!(config.clone().with_sample_rate(4.0).sample_rate, 1.0);
+    assert_eq!(config.with_sample_rate(-1.0).sample_rate, 0.0);
+}
+
+/// Test a node configured with telemetry
+///
+/// INTENTION: The instrumented request and publish paths keep working, and a
+/// disabled exporter leaves the node running without a telemetry layer.
+#[tokio::test]
+async fn test_node_with_telemetry() -> Result<()> {
+    telemetry::disable();
+    assert!(telemetry::is_disabled());
+
+    let mut config = create_node_test_config()?.with_telemetry(
+        OtlpConfig::new("http://localhost:4318/v1/traces", "math").with_sample_rate(0.5),
+    );
+    config.network_config = None;
+    assert_eq!(config.telemetry.as_ref().unwrap().sample_rate, 0.5);
+
+    let mut node = Node::new(config).await?;
+    node.add_service(MathService::new("math", "math")).await?;
+    node.start().await?;
+    assert!(node.telemetry_layer::<Registry>().is_none());
+
+    let sum: f64 = node
+        .request(
+            "math/add",
+            Some(ArcValue::new_map(hmap! {
+                "a" => 2.0,
+                "b" => 3.0
+            })),
+        )
+        .await?;
+    assert_eq!(sum, 5.0);
+    node.publish("math/added".to_string(), Some(ArcValue::new_primitive(sum)))
+        .await?;
+
+    node.stop().await?;
+    Ok(())
+}
@@ -0,0 +1,152 @@
+// Tests for the TestNode helper
+//
+// `TestNode::builder()` creates and starts an in-process node with the given
+// services and serializer types, and stops it when dropped. Nodes built with
+// the same credentials and a transport can be connected to each other.
+
+use anyhow::Result;
+use runar_common::hmap;
+use runar_common::types::ArcValue;
+use runar_node::config::LogLevel;
+use runar_node::testing::{NetworkPartition, TestCredentials, TestNode, TestNodeBuilder};
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+use crate::fixtures::math_service::MathService;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Point {
+    x: i64,
+    y: i64,
+}
+
+/// Test that the built node serves its services right away
+///
+/// INTENTION: A test gets a started node with its services, types and log
+/// level in one chain, can call actions with `call` and use the node's own
+/// API through `Deref`, and pays little for booting it.
+#[tokio::test]
+async fn test_builder_starts_node_with_services() -> Result<()> {
+    let started = Instant::now();
+    let node = TestNode::builder()
+        .with_service(MathService::new("math", "math"))
+        .with_serializer_type::<Point>()
+        .with_log_level(LogLevel::Error)
+        .build()
+        .await?;
+    // Around 80ms in debug builds, with headroom for loaded machines
+    assert!(
+        started.elapsed() < Duration::from_millis(500),
+        "booting took {:?}",
+        started.elapsed()
+    );
+
+    let sum: f64 = node
+        .call(
+            "math/add",
+            ArcValue::new_map(hmap! {
+                "a" => 5.0,
+                "b" => 3.0
+            }),
+        )
+        .await?;
+    assert_eq!(sum, 8.0);
+
+    // The node's API is available as is
+    assert!(node.local_request("math/missing", None).await.is_err());
+    assert!(!node.get_local_node_info().await?.services.is_empty());
+    Ok(())
+}
+
+/// Test that dropping a test node stops it
+///
+/// INTENTION: Tests need no cleanup code; the node stops in the background
+/// once its handle is dropped.
+#[tokio::test]
+async fn test_drop_stops_node() -> Result<()> {
+    let node = TestNode::builder().build().await?;
+    let shutdown = node.wait_for_shutdown();
+    drop(node);
+    tokio::time::timeout(Duration::from_secs(5), shutdown).await?;
+    Ok(())
+}
+
+/// Ask `node` to add two numbers with the math service at `service`
+async fn add(node: &TestNode, service: &str) -> Result<f64> {
+    node.call(
+        format!("{service}/add"),
+        ArcValue::new_map(hmap! { "a" => 1.0, "b" => 2.0 }),
+    )
+    .await
+}
+
+/// Build two nodes from `builder`, each with a math service, and connect them
+async fn connected_pair(builder: impl Fn() -> TestNodeBuilder) -> Result<(TestNode, TestNode)> {
+    let node1 = builder()
+        .with_service(MathService::new("math1", "math1"))
+        .build()
+        .await?;
+    let mut node2 = builder()
+        .with_service(MathService::new("math2", "math2"))
+        .build()
+        .await?;
+    node2.connect(&node1).await?;
+
+    // node2 knows the services of node1 once connected, node1 learns about
+    // node2 in the background
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while add(&node1, "math2").await.is_err() && Instant::now() < deadline {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    Ok((node1, node2))
+}
+
+/// Test connecting test nodes over QUIC
+///
+/// INTENTION: Nodes sharing credentials and bound to free ports call each
+/// other's services once connected.
+#[tokio::test]
+async fn test_nodes_connected_over_quic() -> Result<()> {
+    let credentials = TestCredentials::new()?;
+    let (node1, node2) = connected_pair(|| {
+        TestNode::builder()
+            .with_credentials(&credentials)
+            .with_quic_transport()
+    })
+    .await?;
+
+    assert_eq!(add(&node2, "math1").await?, 3.0);
+    assert_eq!(add(&node1, "math2").await?, 3.0);
+    Ok(())
+}
+
+/// Test connecting test nodes over the in-memory transport
+///
+/// INTENTION: Nodes on a `NetworkPartition` reach each other without sockets,
+/// until the link between them is cut.
+#[tokio::test]
+async fn test_nodes_connected_in_memory() -> Result<()> {
+    let credentials = TestCredentials::new()?;
+    let mut network = NetworkPartition::new();
+    let (node1, node2) = connected_pair(|| {
+        TestNode::builder()
+            .with_credentials(&credentials)
+            .with_in_memory_transport(&network)
+            .with_config(|config| config.with_request_timeout(300))
+    })
+    .await?;
+
+    assert_eq!(add(&node2, "math1").await?, 3.0);
+    network.partition(node1.peer_id(), node2.peer_id());
+    assert!(add(&node2, "math1").await.is_err());
+    Ok(())
+}
+
+/// Test that nodes without a transport cannot be connected
+#[tokio::test]
+async fn test_connect_without_transport_fails() -> Result<()> {
+    let node1 = TestNode::builder().build().await?;
+    let mut node2 = TestNode::builder().build().await?;
+    assert!(node2.connect(&node1).await.is_err());
+    Ok(())
+}
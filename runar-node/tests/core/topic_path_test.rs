@@ -293,6 +293,23 @@ mod topic_path_tests {
         assert_eq!(event_path.action_path(), "auth/user_logged_in");
     }
 
+    #[test]
+    fn test_relative() {
+        // Paths built relative to a base keep the base's network ID
+        let base = TopicPath::new("main:auth/login", "default").expect("Valid path");
+
+        let sibling = TopicPath::relative("auth", "logout", &base).expect("Valid action path");
+        assert_eq!(sibling.network_id(), "main");
+        assert_eq!(sibling.service_path(), "auth");
+        assert_eq!(sibling.action_path(), "auth/logout");
+
+        let other = TopicPath::relative("users", "get", &base).expect("Valid action path");
+        assert_eq!(other.as_str(), "main:users/get");
+
+        // The action must still be a valid path segment
+        assert!(TopicPath::relative("users", "bad:action", &base).is_err());
+    }
+
     #[test]
     fn test_nested_action_path() {
         // Create a nested service path
@@ -393,3 +410,32 @@ mod topic_path_tests {
         assert_eq!(path3.action_path(), "auth/login");
     }
 }
+
+/// Test relative paths built from request and event contexts
+///
+/// INTENTION: Both contexts resolve an action against the network and service
+/// of their own topic path.
+#[tokio::test]
+async fn test_context_relative_path() -> anyhow::Result<()> {
+    use runar_common::logging::{Component, Logger};
+    use runar_node::services::{EventContext, RequestContext};
+    use runar_node::Node;
+    use runar_test_utils::create_node_test_config;
+    use std::sync::Arc;
+
+    let logger = Arc::new(Logger::new_root(Component::Node, "test"));
+    let node = Arc::new(Node::new(create_node_test_config()?).await?);
+
+    let request_path = TopicPath::new("main:billing/charge", "default").expect("Valid path");
+    let request_ctx = RequestContext::new(&request_path, node.clone(), logger.clone());
+    let path = request_ctx.relative_path("refund")?;
+    assert_eq!(path.as_str(), "main:billing/refund");
+
+    let event_path = TopicPath::new("main:billing/charged", "default").expect("Valid path");
+    let event_ctx = EventContext::new(&event_path, node, logger);
+    let path = event_ctx.relative_path("audit")?;
+    assert_eq!(path.as_str(), "main:billing/audit");
+
+    assert!(request_ctx.relative_path("bad:action").is_err());
+    Ok(())
+}
@@ -0,0 +1,162 @@
+// Cancellable Service test fixture
+//
+// This is a simple service implementation used for testing cooperative
+// cancellation of long-running actions through RequestContext::cancel_token.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use runar_common::types::ArcValue;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use runar_node::services::abstract_service::AbstractService;
+use runar_node::services::{LifecycleContext, RequestContext};
+
+/// A service whose actions run until their request is cancelled
+///
+/// The `observed` flag is set once the long-running action notices that its
+/// cancel token fired, so tests can verify the action body saw the cancellation.
+#[derive(Clone)]
+pub struct CancellableService {
+    name: String,
+    version: String,
+    path: String,
+    description: String,
+    network_id: Option<String>,
+    observed: Arc<AtomicBool>,
+}
+
+impl CancellableService {
+    /// Create a new CancellableService reporting cancellation through `observed`
+    pub fn new(name: &str, path: &str, observed: Arc<AtomicBool>) -> Self {
+        Self {
+            name: name.to_string(),
+            version: "1.0.0".to_string(),
+            path: path.to_string(),
+            description: "Cancellation test service".to_string(),
+            network_id: None, // will be set by the node
+            observed,
+        }
+    }
+
+    /// Handle the wait action - polls the cancel token until it fires
+    async fn handle_wait(&self, context: RequestContext) -> Result<ArcValue> {
+        loop {
+            if context.cancel_token().is_cancelled() {
+                context.info("Wait action observed cancellation".to_string());
+                self.observed.store(true, Ordering::SeqCst);
+                return Ok(ArcValue::new_primitive(true));
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    }
+
+    /// Handle the nested action - forwards to the wait action of this service
+    async fn handle_nested(&self, context: RequestContext) -> Result<ArcValue> {
+        context.request("wait", None::<()>).await
+    }
+
+    /// Handle the slow action - answers after one second
+    async fn handle_slow(&self, _context: RequestContext) -> Result<ArcValue> {
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        Ok(ArcValue::new_primitive(true))
+    }
+
+    /// Handle the bounded action - lets a nested wait time out, then reports
+    /// whether that also cancelled this request
+    async fn handle_bounded(&self, context: RequestContext) -> Result<ArcValue> {
+        let nested = context
+            .request_with_timeout::<(), bool>("wait", None, Duration::from_millis(50))
+            .await;
+        anyhow::ensure!(nested.is_err(), "nested wait should time out");
+        Ok(ArcValue::new_primitive(
+            context.cancel_token().is_cancelled(),
+        ))
+    }
+}
+
+#[async_trait]
+impl AbstractService for CancellableService {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn version(&self) -> &str {
+        &self.version
+    }
+
+    fn path(&self) -> &str {
+        &self.path
+    }
+
+    fn description(&self) -> &str {
+        &self.description
+    }
+
+    fn network_id(&self) -> Option<String> {
+        self.network_id.clone()
+    }
+    fn set_network_id(&mut self, network_id: String) {
+        self.network_id = Some(network_id);
+    }
+
+    async fn init(&self, context: LifecycleContext) -> Result<()> {
+        let owned_self = self.clone();
+        context
+            .register_action(
+                "wait",
+                Arc::new(move |_params, request_ctx| {
+                    let self_clone = owned_self.clone();
+                    Box::pin(async move { self_clone.handle_wait(request_ctx).await })
+                }),
+            )
+            .await?;
+
+        let owned_self = self.clone();
+        context
+            .register_action(
+                "nested",
+                Arc::new(move |_params, request_ctx| {
+                    let self_clone = owned_self.clone();
+                    Box::pin(async move { self_clone.handle_nested(request_ctx).await })
+                }),
+            )
+            .await?;
+
+        let owned_self = self.clone();
+        context
+            .register_action(
+                "bounded",
+                Arc::new(move |_params, request_ctx| {
+                    let self_clone = owned_self.clone();
+                    Box::pin(async move { self_clone.handle_bounded(request_ctx).await })
+                }),
+            )
+            .await?;
+
+        let owned_self = self.clone();
+        context
+            .register_action(
+                "slow",
+                Arc::new(move |_params, request_ctx| {
+                    let self_clone = owned_self.clone();
+                    Box::pin(async move { self_clone.handle_slow(request_ctx).await })
+                }),
+            )
+            .await?;
+
+        context.info("CancellableService initialized".to_string());
+        Ok(())
+    }
+
+    async fn start(&self, context: LifecycleContext) -> Result<()> {
+        context.info("CancellableService started".to_string());
+        Ok(())
+    }
+
+    async fn stop(&self, context: LifecycleContext) -> Result<()> {
+        context.info("CancellableService stopped".to_string());
+        Ok(())
+    }
+}
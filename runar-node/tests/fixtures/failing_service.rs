@@ -0,0 +1,117 @@
+// Failing Service test fixture
+//
+// This is a simple service implementation used for testing how the errors
+// returned by actions reach remote callers.
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use runar_node::services::abstract_service::AbstractService;
+use runar_node::services::{LifecycleContext, RequestContext};
+
+/// Typed error returned by the withdraw action
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, thiserror::Error)]
+#[error("insufficient funds: needed {needed}, available {available}")]
+pub struct InsufficientFunds {
+    pub needed: u64,
+    pub available: u64,
+}
+
+/// A service whose actions always fail
+///
+/// The `withdraw` action fails with `InsufficientFunds`, which the service
+/// registers as an error type; the `fail` action fails with a plain message.
+#[derive(Clone)]
+pub struct FailingService {
+    name: String,
+    version: String,
+    path: String,
+    description: String,
+    network_id: Option<String>,
+}
+
+impl FailingService {
+    /// Create a new FailingService
+    pub fn new(name: &str, path: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            version: "1.0.0".to_string(),
+            path: path.to_string(),
+            description: "Error propagation test service".to_string(),
+            network_id: None, // will be set by the node
+        }
+    }
+}
+
+#[async_trait]
+impl AbstractService for FailingService {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn version(&self) -> &str {
+        &self.version
+    }
+
+    fn path(&self) -> &str {
+        &self.path
+    }
+
+    fn description(&self) -> &str {
+        &self.description
+    }
+
+    fn network_id(&self) -> Option<String> {
+        self.network_id.clone()
+    }
+    fn set_network_id(&mut self, network_id: String) {
+        self.network_id = Some(network_id);
+    }
+
+    async fn init(&self, context: LifecycleContext) -> Result<()> {
+        context
+            .serializer
+            .write()
+            .await
+            .register_error::<InsufficientFunds>()?;
+
+        context
+            .register_action(
+                "withdraw",
+                Arc::new(|_params, _request_ctx: RequestContext| {
+                    Box::pin(async move {
+                        Err(anyhow::Error::new(InsufficientFunds {
+                            needed: 100,
+                            available: 40,
+                        })
+                        .context("Withdrawal rejected"))
+                    })
+                }),
+            )
+            .await?;
+
+        context
+            .register_action(
+                "fail",
+                Arc::new(|_params, _request_ctx| {
+                    Box::pin(async move { Err(anyhow!("plain failure")) })
+                }),
+            )
+            .await?;
+
+        context.info("FailingService initialized".to_string());
+        Ok(())
+    }
+
+    async fn start(&self, context: LifecycleContext) -> Result<()> {
+        context.info("FailingService started".to_string());
+        Ok(())
+    }
+
+    async fn stop(&self, context: LifecycleContext) -> Result<()> {
+        context.info("FailingService stopped".to_string());
+        Ok(())
+    }
+}
@@ -0,0 +1,107 @@
+// Forwarding Service test fixture
+//
+// This is a simple service implementation used for testing requests that
+// one service makes to another from within its actions.
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use runar_common::types::ArcValue;
+use std::sync::Arc;
+
+use runar_node::services::abstract_service::AbstractService;
+use runar_node::services::{LifecycleContext, RequestContext};
+
+/// A service whose `forward` action requests another action
+///
+/// The `forward` action takes a map with the `path` to request and the
+/// `params` to send, and returns the response.
+#[derive(Clone)]
+pub struct ForwardingService {
+    name: String,
+    version: String,
+    path: String,
+    description: String,
+    network_id: Option<String>,
+}
+
+impl ForwardingService {
+    /// Create a new ForwardingService
+    pub fn new(name: &str, path: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            version: "1.0.0".to_string(),
+            path: path.to_string(),
+            description: "Forwarding test service".to_string(),
+            network_id: None, // will be set by the node
+        }
+    }
+
+    /// Handle the forward action - requests the given path with the given params
+    async fn handle_forward(
+        &self,
+        params: Option<ArcValue>,
+        context: RequestContext,
+    ) -> Result<ArcValue> {
+        let mut params = params.ok_or_else(|| anyhow!("forward requires parameters"))?;
+        let map = params.as_map_ref::<String, ArcValue>()?;
+        let path: String = map
+            .get("path")
+            .cloned()
+            .ok_or_else(|| anyhow!("forward requires a path"))?
+            .as_type()?;
+        let forwarded = map.get("params").cloned();
+        context.request(path, forwarded).await
+    }
+}
+
+#[async_trait]
+impl AbstractService for ForwardingService {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn version(&self) -> &str {
+        &self.version
+    }
+
+    fn path(&self) -> &str {
+        &self.path
+    }
+
+    fn description(&self) -> &str {
+        &self.description
+    }
+
+    fn network_id(&self) -> Option<String> {
+        self.network_id.clone()
+    }
+    fn set_network_id(&mut self, network_id: String) {
+        self.network_id = Some(network_id);
+    }
+
+    async fn init(&self, context: LifecycleContext) -> Result<()> {
+        let owned_self = self.clone();
+        context
+            .register_action(
+                "forward",
+                Arc::new(move |params, request_ctx| {
+                    let self_clone = owned_self.clone();
+                    Box::pin(async move { self_clone.handle_forward(params, request_ctx).await })
+                }),
+            )
+            .await?;
+
+        context.info("ForwardingService initialized".to_string());
+        Ok(())
+    }
+
+    async fn start(&self, context: LifecycleContext) -> Result<()> {
+        context.info("ForwardingService started".to_string());
+        Ok(())
+    }
+
+    async fn stop(&self, context: LifecycleContext) -> Result<()> {
+        context.info("ForwardingService stopped".to_string());
+        Ok(())
+    }
+}
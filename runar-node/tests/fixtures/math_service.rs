@@ -437,6 +437,7 @@ impl AbstractService for MathService {
                 required: Some(vec!["updated_setting".to_string(), "new_value".to_string()]),
                 ..FieldSchema::new("ConfigUpdatePayload", SchemaDataType::Object) // Base with defaults
             }),
+            ..Default::default()
         };
 
         // let service_arc_for_event = self.clone(); // Clone self for the event callback
@@ -1,4 +1,8 @@
 // Test fixture services used in unit tests
 
+pub mod cancellable_service;
+pub mod failing_service;
+pub mod forwarding_service;
 pub mod math_service;
 pub mod path_params_service;
+pub mod stream_service;
@@ -0,0 +1,155 @@
+// Stream Service test fixture
+//
+// This is a simple service implementation used for testing actions that
+// return their result as a ResponseStream.
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use runar_common::types::ArcValue;
+use std::sync::Arc;
+
+use runar_node::services::abstract_service::AbstractService;
+use runar_node::services::{LifecycleContext, RequestContext, ResponseStream};
+
+/// A service whose actions stream their values
+///
+/// `count` streams the integers from 1 up to its parameter; `broken` streams
+/// a few values and then fails; `stalled` streams a few values and then
+/// never sends anything again.
+#[derive(Clone)]
+pub struct StreamService {
+    name: String,
+    version: String,
+    path: String,
+    description: String,
+    network_id: Option<String>,
+}
+
+impl StreamService {
+    /// Create a new StreamService with the specified name and path
+    pub fn new(name: &str, path: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            version: "1.0.0".to_string(),
+            path: path.to_string(),
+            description: "Streaming test service".to_string(),
+            network_id: None, // will be set by the node
+        }
+    }
+
+    /// Handle the count action - streams 1..=n
+    async fn handle_count(
+        &self,
+        params: Option<ArcValue>,
+        context: RequestContext,
+    ) -> Result<ArcValue> {
+        let up_to = params
+            .ok_or_else(|| anyhow!("Missing count parameter"))?
+            .as_type::<i64>()?;
+        context.debug(format!("Streaming {up_to} values"));
+        Ok(ResponseStream::from_items(1..=up_to).into_arc_value(ArcValue::new_primitive))
+    }
+
+    /// Handle the broken action - streams two values, then fails
+    async fn handle_broken(&self) -> Result<ArcValue> {
+        let (sender, stream) = ResponseStream::channel(1);
+        tokio::spawn(async move {
+            for value in 1..=2i64 {
+                if !sender.send(value).await {
+                    return;
+                }
+            }
+            sender.fail(anyhow!("Stream source failed")).await;
+        });
+        Ok(stream.into_arc_value(ArcValue::new_primitive))
+    }
+
+    /// Handle the stalled action - streams two values, then neither sends nor ends
+    async fn handle_stalled(&self) -> Result<ArcValue> {
+        let (sender, stream) = ResponseStream::channel(1);
+        tokio::spawn(async move {
+            for value in 1..=2i64 {
+                if !sender.send(value).await {
+                    return;
+                }
+            }
+            std::future::pending::<()>().await;
+            drop(sender);
+        });
+        Ok(stream.into_arc_value(ArcValue::new_primitive))
+    }
+}
+
+#[async_trait]
+impl AbstractService for StreamService {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn version(&self) -> &str {
+        &self.version
+    }
+
+    fn path(&self) -> &str {
+        &self.path
+    }
+
+    fn description(&self) -> &str {
+        &self.description
+    }
+
+    fn network_id(&self) -> Option<String> {
+        self.network_id.clone()
+    }
+    fn set_network_id(&mut self, network_id: String) {
+        self.network_id = Some(network_id);
+    }
+
+    async fn init(&self, context: LifecycleContext) -> Result<()> {
+        let owned_self = self.clone();
+        context
+            .register_action(
+                "count",
+                Arc::new(move |params, request_ctx| {
+                    let self_clone = owned_self.clone();
+                    Box::pin(async move { self_clone.handle_count(params, request_ctx).await })
+                }),
+            )
+            .await?;
+
+        let owned_self = self.clone();
+        context
+            .register_action(
+                "broken",
+                Arc::new(move |_params, _request_ctx| {
+                    let self_clone = owned_self.clone();
+                    Box::pin(async move { self_clone.handle_broken().await })
+                }),
+            )
+            .await?;
+
+        let owned_self = self.clone();
+        context
+            .register_action(
+                "stalled",
+                Arc::new(move |_params, _request_ctx| {
+                    let self_clone = owned_self.clone();
+                    Box::pin(async move { self_clone.handle_stalled().await })
+                }),
+            )
+            .await?;
+
+        context.info("StreamService initialized".to_string());
+        Ok(())
+    }
+
+    async fn start(&self, context: LifecycleContext) -> Result<()> {
+        context.info("StreamService started".to_string());
+        Ok(())
+    }
+
+    async fn stop(&self, context: LifecycleContext) -> Result<()> {
+        context.info("StreamService stopped".to_string());
+        Ok(())
+    }
+}
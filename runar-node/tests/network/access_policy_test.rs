@@ -0,0 +1,80 @@
+// Tests for service access policies applied to remote callers
+//
+// Requests arriving from a peer carry its PeerId, which the target node
+// checks against the service's access policy.
+
+use anyhow::Result;
+use runar_common::hmap;
+use runar_common::types::ArcValue;
+use runar_node::network::transport::{ErrorCode, NetworkError};
+use runar_node::node::Node;
+use runar_node::ServiceAccessPolicy;
+use runar_test_utils::{create_networked_node_test_config, without_discovery};
+
+use std::net::{Ipv4Addr, SocketAddr};
+
+use crate::fixtures::math_service::MathService;
+
+/// Request `math/add` of the remote math service
+async fn remote_add(node: &Node) -> Result<f64> {
+    let params = ArcValue::new_map(hmap! {
+        "a" => 5.0,
+        "b" => 3.0
+    });
+    node.request("math/add", Some(params)).await
+}
+
+/// Test that remote callers are filtered by the access policy
+///
+/// INTENTION: A local-only service rejects requests from peers with "access
+/// denied", and once its policy is updated to list a peer that peer is
+/// served.
+#[tokio::test]
+async fn test_remote_callers_follow_policy() -> Result<()> {
+    let configs = create_networked_node_test_config(2)?;
+    let node1_config = without_discovery(configs[0].clone());
+    let node1_port = node1_config
+        .network_config
+        .as_ref()
+        .unwrap()
+        .transport_options
+        .bind_address
+        .port();
+    let mut node1 = Node::new(node1_config).await?;
+    node1.add_service(MathService::new("math", "math")).await?;
+    node1
+        .update_service_policy("math", ServiceAccessPolicy::local_only())
+        .await?;
+    node1.start().await?;
+    let node1_peer_id = node1.get_local_node_info().await?.peer_id;
+    let node1_addr = SocketAddr::from((Ipv4Addr::LOCALHOST, node1_port));
+
+    let node2_config =
+        without_discovery(configs[1].clone()).with_initial_peers(vec![(node1_addr, node1_peer_id)]);
+    let mut node2 = Node::new(node2_config).await?;
+    node2.start().await?;
+    let node2_peer_id = node2.get_local_node_info().await?.peer_id;
+
+    let error = remote_add(&node2).await.unwrap_err();
+    let network_error = error
+        .downcast_ref::<NetworkError>()
+        .expect("remote errors are network errors");
+    assert_eq!(network_error.code(), ErrorCode::RemoteError);
+    assert_eq!(network_error.message(), "access denied");
+    // Local callers are still served
+    let params = ArcValue::new_map(hmap! {
+        "a" => 1.0,
+        "b" => 2.0
+    });
+    let result: f64 = node1.request("math/add", Some(params)).await?;
+    assert_eq!(result, 3.0);
+
+    node1
+        .update_service_policy("math", ServiceAccessPolicy::peers(vec![node2_peer_id]))
+        .await?;
+    assert_eq!(remote_add(&node2).await?, 8.0);
+
+    node2.stop().await?;
+    node1.stop().await?;
+    Ok(())
+}
@@ -3,7 +3,9 @@ use runar_common::{
     types::{ArcValue, SerializerRegistry},
     Component, Logger,
 };
-use runar_node::network::transport::{NetworkMessage, NetworkMessagePayloadItem, PeerId};
+use runar_node::network::transport::{
+    ErrorCode, NetworkMessage, NetworkMessagePayloadItem, PeerId,
+};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -86,6 +88,11 @@ fn test_network_message_serialization() -> Result<()> {
         destination: dest_id.clone(),
         message_type: "TestMessage".to_string(),
         payloads: vec![payload_item],
+        signature: None,
+        hop_count: 0,
+        visited_peers: Vec::new(),
+        auth_tag: None,
+        expires_at: None,
     };
 
     // Serialize the message
@@ -168,6 +175,11 @@ fn test_struct_serialization_in_network_message() -> Result<()> {
         destination: dest_id,
         message_type: "TestMessage".to_string(),
         payloads: vec![payload_item],
+        signature: None,
+        hop_count: 0,
+        visited_peers: Vec::new(),
+        auth_tag: None,
+        expires_at: None,
     };
 
     // Serialize the entire message using bincode
@@ -275,6 +287,11 @@ fn test_multiple_struct_types_in_message() -> Result<()> {
         destination: dest_id,
         message_type: "MultiStructMessage".to_string(),
         payloads: vec![user_payload, product_payload],
+        signature: None,
+        hop_count: 0,
+        visited_peers: Vec::new(),
+        auth_tag: None,
+        expires_at: None,
     };
 
     // Serialize the entire message
@@ -388,6 +405,11 @@ fn test_various_types_in_network_messages() -> Result<()> {
         destination: dest_id,
         message_type: "TestAllTypes".to_string(),
         payloads: vec![struct_payload, map_payload, array_payload],
+        signature: None,
+        hop_count: 0,
+        visited_peers: Vec::new(),
+        auth_tag: None,
+        expires_at: None,
     };
 
     // Serialize the message
@@ -445,3 +467,42 @@ fn test_various_types_in_network_messages() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_network_message_hop_tracking() -> Result<()> {
+    let source_id = PeerId::new("source-node".to_string());
+    let relay_id = PeerId::new("relay-node".to_string());
+    let mut message = NetworkMessage {
+        source: source_id.clone(),
+        destination: PeerId::new("dest-node".to_string()),
+        message_type: "Request".to_string(),
+        payloads: Vec::new(),
+        signature: None,
+        hop_count: 0,
+        visited_peers: Vec::new(),
+        auth_tag: None,
+        expires_at: None,
+    };
+    let digest = message.signing_digest();
+
+    // Relaying updates the hop fields without invalidating the signature
+    message.record_hop(relay_id.clone(), 2)?;
+    assert_eq!(message.hop_count, 1);
+    assert!(message.has_visited(&relay_id));
+    assert!(!message.has_visited(&source_id));
+    assert_eq!(message.signing_digest(), digest);
+
+    // The hop fields survive serialization
+    let deserialized: NetworkMessage = bincode::deserialize(&bincode::serialize(&message)?)?;
+    assert_eq!(deserialized.hop_count, 1);
+    assert!(deserialized.has_visited(&relay_id));
+
+    message.record_hop(source_id, 2)?;
+    let error = message
+        .record_hop(relay_id, 2)
+        .expect_err("third hop should exceed the limit");
+    assert_eq!(error.code(), ErrorCode::MaxHopsExceeded);
+    assert_eq!(message.hop_count, 2);
+
+    Ok(())
+}
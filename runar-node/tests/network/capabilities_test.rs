@@ -0,0 +1,150 @@
+// Tests for the capability exchange of the QUIC handshake
+//
+// Nodes send their `NodeCapabilities` with the handshake and keep the
+// capabilities both of them support in the peer registry. Nodes whose
+// protocol versions differ by more than the allowed skew are disconnected.
+
+use anyhow::Result;
+use runar_node::network::transport::capabilities::check_protocol_version;
+use runar_node::network::{
+    CompressionAlgorithm, ErrorCode, NodeCapabilities, PeerId, QuicTransportOptions,
+    PROTOCOL_VERSION,
+};
+use runar_node::node::{Node, NodeConfig};
+
+use crate::network::{negotiated_capabilities, start_pair};
+
+/// Advertise the given capabilities
+///
+/// The advertised message size is the transport limit, so it is taken from
+/// the capabilities into the transport options.
+fn configure(mut config: NodeConfig, capabilities: NodeCapabilities) -> NodeConfig {
+    let network_config = config
+        .network_config
+        .as_mut()
+        .expect("test config has networking");
+    network_config.transport_options.max_message_size = Some(capabilities.max_message_size);
+    let options: QuicTransportOptions = network_config.quic_options.take().unwrap_or_default();
+    network_config.quic_options = Some(options.with_capabilities(capabilities));
+    config
+}
+
+/// Start two nodes advertising the given capabilities
+async fn start_with_capabilities(
+    capabilities1: NodeCapabilities,
+    capabilities2: NodeCapabilities,
+) -> Result<(Node, PeerId, Node, PeerId)> {
+    start_pair(
+        |config| configure(config, capabilities1),
+        // Bounds the wait for a handshake response that never comes when incompatible
+        |config| configure(config, capabilities2).with_request_timeout(2000),
+    )
+    .await
+}
+
+/// Test that both peers record the intersection of their capabilities
+///
+/// INTENTION: The negotiated algorithms are those supported by both nodes, and
+/// the message size limit is the smaller of the two transport limits.
+#[tokio::test]
+async fn test_handshake_negotiates_shared_capabilities() -> Result<()> {
+    let capabilities1 = NodeCapabilities {
+        compression_algorithms: vec![CompressionAlgorithm::Zstd, CompressionAlgorithm::Lz4],
+        max_message_size: 4 * 1024 * 1024,
+        ..NodeCapabilities::default()
+    };
+    let capabilities2 = NodeCapabilities {
+        compression_algorithms: vec![CompressionAlgorithm::Lz4, CompressionAlgorithm::Deflate],
+        max_message_size: 1024 * 1024,
+        ..NodeCapabilities::default()
+    };
+    let (mut node1, node1_peer_id, mut node2, node2_peer_id) =
+        start_with_capabilities(capabilities1, capabilities2).await?;
+
+    let expected = NodeCapabilities {
+        compression_algorithms: vec![CompressionAlgorithm::Lz4],
+        max_message_size: 1024 * 1024,
+        ..NodeCapabilities::default()
+    };
+    assert_eq!(
+        negotiated_capabilities(&node1, &node2_peer_id).await,
+        Some(expected.clone())
+    );
+    assert_eq!(
+        negotiated_capabilities(&node2, &node1_peer_id).await,
+        Some(expected)
+    );
+
+    node2.stop().await?;
+    node1.stop().await?;
+    Ok(())
+}
+
+/// Test that peers within the version skew connect on the lower version
+///
+/// INTENTION: A node one protocol version ahead is still accepted with the
+/// default skew of 1, and both sides settle on the older version.
+#[tokio::test]
+async fn test_protocol_version_within_skew_connects() -> Result<()> {
+    let capabilities2 = NodeCapabilities {
+        protocol_version: PROTOCOL_VERSION + 1,
+        ..NodeCapabilities::default()
+    };
+    assert!(check_protocol_version(PROTOCOL_VERSION, PROTOCOL_VERSION + 1, 1).is_ok());
+
+    let (mut node1, node1_peer_id, mut node2, node2_peer_id) =
+        start_with_capabilities(NodeCapabilities::default(), capabilities2).await?;
+
+    let expected = NodeCapabilities::default();
+    assert_eq!(
+        negotiated_capabilities(&node1, &node2_peer_id).await,
+        Some(expected.clone())
+    );
+    assert_eq!(
+        negotiated_capabilities(&node2, &node1_peer_id).await,
+        Some(expected)
+    );
+
+    node2.stop().await?;
+    node1.stop().await?;
+    Ok(())
+}
+
+/// Test that peers beyond the version skew are disconnected
+///
+/// INTENTION: Incompatible nodes never become known peers of each other, and
+/// the mismatch is reported as a configuration error naming both versions.
+#[tokio::test]
+async fn test_incompatible_protocol_version_disconnects() -> Result<()> {
+    let capabilities2 = NodeCapabilities {
+        protocol_version: PROTOCOL_VERSION + 2,
+        ..NodeCapabilities::default()
+    };
+    let error = check_protocol_version(PROTOCOL_VERSION, PROTOCOL_VERSION + 2, 1).unwrap_err();
+    assert_eq!(error.code(), ErrorCode::InvalidConfiguration);
+    assert_eq!(
+        error.message(),
+        "incompatible protocol version: local 1, remote 3, max skew 1"
+    );
+
+    let (mut node1, node1_peer_id, mut node2, node2_peer_id) =
+        start_with_capabilities(NodeCapabilities::default(), capabilities2).await?;
+
+    assert!(node1
+        .peer_registry()
+        .find_peer(node2_peer_id.public_key.clone())
+        .is_none());
+    assert!(node2
+        .peer_registry()
+        .find_peer(node1_peer_id.public_key.clone())
+        .and_then(|entry| entry.node_info)
+        .is_none());
+    assert_eq!(
+        node1.transport_stats().await.unwrap().current_connections,
+        0
+    );
+
+    node2.stop().await?;
+    node1.stop().await?;
+    Ok(())
+}
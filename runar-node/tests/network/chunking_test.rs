@@ -0,0 +1,309 @@
+// Tests for the chunked transfer of oversized messages
+//
+// A payload larger than the transport's message limit is split into chunks
+// by the sender and reassembled by the receiver, transparently for the
+// services on both sides. Chunks that do not all arrive in time are discarded.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use runar_common::types::ArcValue;
+use runar_node::network::transport::chunking::split_message;
+use runar_node::network::transport::{
+    ChunkReassembler, ErrorCode, NetworkError, NetworkMessage, NetworkMessagePayloadItem, PeerId,
+};
+use runar_node::node::{Node, NodeConfig};
+use runar_node::services::abstract_service::AbstractService;
+use runar_node::services::LifecycleContext;
+use runar_test_utils::{create_networked_node_test_config, without_discovery};
+
+use std::net::{Ipv4Addr, SocketAddr};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::time::sleep;
+
+fn test_message(value_bytes: Vec<u8>) -> NetworkMessage {
+    NetworkMessage {
+        source: PeerId::new("source".to_string()),
+        destination: PeerId::new("destination".to_string()),
+        message_type: "Response".to_string(),
+        payloads: vec![NetworkMessagePayloadItem::new(
+            "export/dump".to_string(),
+            value_bytes,
+            "correlation-1".to_string(),
+        )],
+        signature: None,
+        hop_count: 0,
+        visited_peers: Vec::new(),
+        auth_tag: None,
+        expires_at: None,
+    }
+}
+
+fn test_bytes(len: usize) -> Vec<u8> {
+    (0..len).map(|i| (i % 251) as u8).collect()
+}
+
+/// Test that chunks arriving in any order are reassembled
+///
+/// INTENTION: A split message yields chunks with their index and count; the
+/// reassembler holds them until the last one arrives and then returns the
+/// original payload without chunk fields.
+#[test]
+fn test_split_and_reassemble_out_of_order() -> Result<()> {
+    let bytes = test_bytes(10_000);
+    let mut chunks = split_message(test_message(bytes.clone()), 3_000);
+    assert_eq!(chunks.len(), 4);
+    for (index, chunk) in chunks.iter().enumerate() {
+        assert_eq!(chunk.payloads[0].chunk_index, Some(index as u32));
+        assert_eq!(chunk.payloads[0].total_chunks, Some(4));
+        assert!(chunk.payloads[0].value_bytes.len() <= 3_000);
+    }
+
+    chunks.reverse();
+    let reassembler = ChunkReassembler::new(Duration::from_secs(5));
+    let last = chunks.pop().unwrap();
+    for chunk in chunks {
+        assert!(reassembler.add(chunk)?.is_none());
+    }
+    assert_eq!(reassembler.pending(), 1);
+
+    let message = reassembler.add(last)?.expect("message is complete");
+    assert_eq!(message.payloads[0].value_bytes, bytes);
+    assert_eq!(message.payloads[0].chunk_index, None);
+    assert_eq!(message.payloads[0].total_chunks, None);
+    assert_eq!(reassembler.pending(), 0);
+
+    // Messages within the limit pass through unchanged
+    let small = split_message(test_message(vec![1, 2, 3]), 3_000);
+    assert_eq!(small.len(), 1);
+    assert!(reassembler.add(small[0].clone())?.is_some());
+    Ok(())
+}
+
+/// Test that incomplete chunked messages time out
+///
+/// INTENTION: Chunks of a message that do not all arrive within the timeout
+/// are discarded with a "chunk timeout" error, whether found by the periodic
+/// cleanup or by a late chunk.
+#[test]
+fn test_incomplete_chunks_time_out() -> Result<()> {
+    let timeout = Duration::from_millis(50);
+    let reassembler = ChunkReassembler::new(timeout);
+    let chunks = split_message(test_message(test_bytes(9_000)), 3_000);
+
+    assert!(reassembler.add(chunks[0].clone())?.is_none());
+    std::thread::sleep(timeout * 2);
+    let errors = reassembler.discard_expired();
+    assert_eq!(errors.len(), 1);
+    assert!(matches!(
+        &errors[0],
+        NetworkError::MessageError(ErrorCode::MessageExpired, msg) if msg == "chunk timeout"
+    ));
+    assert_eq!(reassembler.pending(), 0);
+
+    assert!(reassembler.add(chunks[0].clone())?.is_none());
+    std::thread::sleep(timeout * 2);
+    let error = reassembler.add(chunks[1].clone()).unwrap_err();
+    assert!(error.to_string().contains("chunk timeout"), "{error}");
+    Ok(())
+}
+
+/// Test that the reassembler bounds what peers can make it buffer
+///
+/// INTENTION: Chunks announcing a message larger than the reassembly limit,
+/// or larger than the chunk size, are refused outright; once the pending
+/// message or byte limit is reached new chunks are refused, and messages
+/// that timed out no longer count against it.
+#[test]
+fn test_reassembly_limits() -> Result<()> {
+    let timeout = Duration::from_millis(50);
+    let reassembler = ChunkReassembler::new(timeout)
+        .with_chunk_size(3_000)
+        .with_max_reassembled_size(10_000)
+        .with_max_pending(2, 7_000);
+
+    let too_many = split_message(test_message(test_bytes(13_000)), 3_000);
+    let error = reassembler.add(too_many[0].clone()).unwrap_err();
+    assert!(matches!(
+        error,
+        NetworkError::MessageError(ErrorCode::MessageTooLarge, _)
+    ));
+    let too_large = split_message(test_message(test_bytes(8_000)), 4_000);
+    let error = reassembler.add(too_large[0].clone()).unwrap_err();
+    assert!(matches!(
+        error,
+        NetworkError::MessageError(ErrorCode::MessageTooLarge, _)
+    ));
+    assert_eq!(reassembler.pending(), 0);
+
+    let message = |correlation_id: &str| {
+        let mut message = test_message(test_bytes(9_000));
+        message.payloads[0].correlation_id = correlation_id.to_string();
+        split_message(message, 3_000)
+    };
+    let first = message("correlation-1");
+    let second = message("correlation-2");
+    let third = message("correlation-3");
+    assert!(reassembler.add(first[0].clone())?.is_none());
+    assert!(reassembler.add(second[0].clone())?.is_none());
+    let error = reassembler.add(third[0].clone()).unwrap_err();
+    assert!(matches!(
+        error,
+        NetworkError::MessageError(ErrorCode::RateLimited, _)
+    ));
+    assert_eq!(reassembler.pending_bytes(), 6_000);
+    let error = reassembler.add(first[1].clone()).unwrap_err();
+    assert!(matches!(
+        error,
+        NetworkError::MessageError(ErrorCode::RateLimited, _)
+    ));
+
+    // Stale messages make room for new ones
+    std::thread::sleep(timeout * 2);
+    assert!(reassembler.add(third[0].clone())?.is_none());
+    assert_eq!(reassembler.pending(), 1);
+    assert_eq!(reassembler.pending_bytes(), 3_000);
+    Ok(())
+}
+
+/// A service echoing the payload it receives
+#[derive(Clone)]
+struct EchoService {
+    network_id: Option<String>,
+}
+
+#[async_trait]
+impl AbstractService for EchoService {
+    fn name(&self) -> &str {
+        "echo"
+    }
+
+    fn version(&self) -> &str {
+        "1.0.0"
+    }
+
+    fn path(&self) -> &str {
+        "echo"
+    }
+
+    fn description(&self) -> &str {
+        "Chunking test service"
+    }
+
+    fn network_id(&self) -> Option<String> {
+        self.network_id.clone()
+    }
+
+    fn set_network_id(&mut self, network_id: String) {
+        self.network_id = Some(network_id);
+    }
+
+    async fn init(&self, context: LifecycleContext) -> Result<()> {
+        context
+            .register_action(
+                "echo",
+                Arc::new(|params: Option<ArcValue>, _ctx| {
+                    Box::pin(async move { Ok(params.unwrap_or_else(ArcValue::null)) })
+                }),
+            )
+            .await
+    }
+
+    async fn start(&self, _context: LifecycleContext) -> Result<()> {
+        Ok(())
+    }
+
+    async fn stop(&self, _context: LifecycleContext) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Limit the transport to small messages so that test payloads are chunked
+fn with_message_limit(config: NodeConfig, max_message_size: usize) -> NodeConfig {
+    let mut config = without_discovery(config);
+    let network_config = config
+        .network_config
+        .as_mut()
+        .expect("test config has networking");
+    network_config.transport_options.max_message_size = Some(max_message_size);
+    config
+}
+
+/// Start an echo node and a node connected to it, with the given limits
+async fn start_echo_pair(limit1: usize, limit2: usize) -> Result<(Node, Node)> {
+    let configs = create_networked_node_test_config(2)?;
+    let node1_config = with_message_limit(configs[0].clone(), limit1);
+    let node1_port = node1_config
+        .network_config
+        .as_ref()
+        .unwrap()
+        .transport_options
+        .bind_address
+        .port();
+    let mut node1 = Node::new(node1_config).await?;
+    node1.add_service(EchoService { network_id: None }).await?;
+    node1.start().await?;
+    let node1_peer_id = node1.get_local_node_info().await?.peer_id;
+
+    let node2_config = with_message_limit(configs[1].clone(), limit2)
+        .with_request_timeout(5000)
+        .with_initial_peers(vec![(
+            SocketAddr::from((Ipv4Addr::LOCALHOST, node1_port)),
+            node1_peer_id,
+        )]);
+    let mut node2 = Node::new(node2_config).await?;
+    node2.start().await?;
+    Ok((node1, node2))
+}
+
+/// Request an echo of `bytes`, retrying until the nodes are connected
+async fn echo(node: &Node, bytes: &[u8]) -> Result<Vec<u8>> {
+    let deadline = Instant::now() + Duration::from_secs(10);
+    loop {
+        match node
+            .request("echo/echo", Some(ArcValue::new_bytes(bytes.to_vec())))
+            .await
+        {
+            Ok(echoed) => return Ok(echoed),
+            Err(e) if Instant::now() >= deadline => return Err(e),
+            Err(_) => sleep(Duration::from_millis(200)).await,
+        }
+    }
+}
+
+/// Test that a payload larger than the message limit crosses the network
+///
+/// INTENTION: With a 64 KiB limit, a request and its response carrying
+/// 1 MiB each are chunked and reassembled, and the caller receives the
+/// payload it sent.
+#[tokio::test]
+async fn test_oversized_payload_round_trip() -> Result<()> {
+    let (mut node1, mut node2) = start_echo_pair(64 * 1024, 64 * 1024).await?;
+
+    let bytes = test_bytes(1024 * 1024);
+    let echoed = echo(&node2, &bytes).await?;
+    assert_eq!(echoed.len(), bytes.len());
+    assert!(echoed == bytes);
+
+    node2.stop().await?;
+    node1.stop().await?;
+    Ok(())
+}
+
+/// Test that messages are sized for the peer with the smaller limit
+///
+/// INTENTION: A node allowing 512 KiB messages sends a 256 KiB response in
+/// chunks to a peer allowing only 64 KiB, instead of a single frame the peer
+/// would refuse as too large.
+#[tokio::test]
+async fn test_chunks_sized_for_peer_limit() -> Result<()> {
+    let (mut node1, mut node2) = start_echo_pair(512 * 1024, 64 * 1024).await?;
+
+    let bytes = test_bytes(256 * 1024);
+    let echoed = echo(&node2, &bytes).await?;
+    assert!(echoed == bytes);
+
+    node2.stop().await?;
+    node1.stop().await?;
+    Ok(())
+}
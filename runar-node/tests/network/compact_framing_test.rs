@@ -0,0 +1,192 @@
+// Tests for compact framing between nodes
+//
+// Nodes with compact framing seal their serializer registry and advertise a
+// fingerprint of its type IDs in the handshake. Payloads sent to a peer with
+// the same fingerprint name their type by ID; other peers get regular frames.
+
+use anyhow::Result;
+use runar_common::hmap;
+use runar_common::types::ArcValue;
+use runar_node::network::transport::RequestAuthConfig;
+use runar_node::network::{NodeCapabilities, PeerId, QuicTransportOptions};
+use runar_node::node::{Node, NodeConfig};
+
+use std::time::{Duration, Instant};
+use tokio::time::sleep;
+
+use crate::fixtures::math_service::MathService;
+use crate::network::{negotiated_capabilities, start_pair};
+
+/// Set compact framing
+fn configure(mut config: NodeConfig, compact_framing: bool) -> NodeConfig {
+    let network_config = config
+        .network_config
+        .as_mut()
+        .expect("test config has networking");
+    let options: QuicTransportOptions = network_config.quic_options.take().unwrap_or_default();
+    network_config.quic_options = Some(options.with_compact_framing(compact_framing));
+    config
+}
+
+/// Start two nodes with the given framing, adjusting both configs with `adjust`
+async fn start_with_framing(
+    compact1: bool,
+    compact2: bool,
+    adjust: impl Fn(NodeConfig) -> NodeConfig,
+) -> Result<(Node, PeerId, Node, PeerId)> {
+    start_pair(
+        |config| adjust(configure(config, compact1)),
+        |config| adjust(configure(config, compact2)),
+    )
+    .await
+}
+
+/// Poll `path` on `node` until it can be called, or give up
+async fn wait_for_add(node: &Node, path: &str) -> Result<f64> {
+    let deadline = Instant::now() + Duration::from_secs(5);
+    loop {
+        let result = node
+            .request(
+                path,
+                Some(ArcValue::new_map(hmap! {
+                    "a" => 1.0,
+                    "b" => 2.0
+                })),
+            )
+            .await;
+        if result.is_ok() || Instant::now() >= deadline {
+            return result;
+        }
+        sleep(Duration::from_millis(50)).await;
+    }
+}
+
+/// Test that only equal type tables are negotiated
+///
+/// INTENTION: Compact frames are only understood by a node with the same
+/// type IDs, so a missing or different fingerprint disables them.
+#[test]
+fn test_negotiate_type_table() {
+    let with_table = |type_table| NodeCapabilities {
+        type_table,
+        ..NodeCapabilities::default()
+    };
+
+    assert_eq!(
+        with_table(Some(7))
+            .negotiate(&with_table(Some(7)))
+            .type_table,
+        Some(7)
+    );
+    assert_eq!(
+        with_table(Some(7))
+            .negotiate(&with_table(Some(8)))
+            .type_table,
+        None
+    );
+    assert_eq!(
+        with_table(Some(7)).negotiate(&with_table(None)).type_table,
+        None
+    );
+    assert_eq!(
+        with_table(None).negotiate(&with_table(Some(7))).type_table,
+        None
+    );
+}
+
+/// Test requests between nodes that both use compact framing
+///
+/// INTENTION: Both nodes register the same types, agree on their type table
+/// and decode each other's compact requests and responses.
+#[tokio::test]
+async fn test_compact_nodes_call_each_other() -> Result<()> {
+    let (mut node1, node1_peer_id, mut node2, node2_peer_id) =
+        start_with_framing(true, true, |config| config).await?;
+
+    let capabilities = negotiated_capabilities(&node1, &node2_peer_id)
+        .await
+        .expect("capabilities negotiated");
+    assert!(capabilities.type_table.is_some());
+    assert_eq!(
+        negotiated_capabilities(&node2, &node1_peer_id).await,
+        Some(capabilities)
+    );
+
+    assert_eq!(wait_for_add(&node2, "math1/add").await?, 3.0);
+    assert_eq!(wait_for_add(&node1, "math2/add").await?, 3.0);
+
+    node2.stop().await?;
+    node1.stop().await?;
+    Ok(())
+}
+
+/// Test that services cannot be added once the type table is sealed
+///
+/// INTENTION: A service added to the running node could register types the
+/// negotiated type table lacks, so it is refused instead of failing halfway
+/// through its initialization. Nodes without compact framing accept it.
+#[tokio::test]
+async fn test_add_service_after_start_with_compact_framing() -> Result<()> {
+    let (mut node1, _, mut node2, _) = start_with_framing(true, false, |config| config).await?;
+
+    let error = node1
+        .add_service(MathService::new("late", "late"))
+        .await
+        .unwrap_err();
+    assert!(error.to_string().contains("compact framing"), "{error}");
+    node2.add_service(MathService::new("late", "late")).await?;
+
+    node2.stop().await?;
+    node1.stop().await?;
+    Ok(())
+}
+
+/// Test that a node with compact framing falls back for other peers
+///
+/// INTENTION: A peer without compact framing advertises no type table, so
+/// the compact node sends it regular frames and requests still succeed.
+#[tokio::test]
+async fn test_compact_node_falls_back_to_verbose_frames() -> Result<()> {
+    let (mut node1, node1_peer_id, mut node2, node2_peer_id) =
+        start_with_framing(true, false, |config| config).await?;
+
+    let capabilities = negotiated_capabilities(&node1, &node2_peer_id)
+        .await
+        .expect("capabilities negotiated");
+    assert_eq!(capabilities.type_table, None);
+
+    assert_eq!(wait_for_add(&node2, "math1/add").await?, 3.0);
+    assert_eq!(wait_for_add(&node1, "math2/add").await?, 3.0);
+    assert!(negotiated_capabilities(&node2, &node1_peer_id)
+        .await
+        .is_some());
+
+    node2.stop().await?;
+    node1.stop().await?;
+    Ok(())
+}
+
+/// Test compact framing between nodes that authenticate their requests
+///
+/// INTENTION: The authentication tag covers the payloads, so it must be
+/// computed over the compact frames the peer receives, not over the regular
+/// frames they replace.
+#[tokio::test]
+async fn test_compact_frames_with_request_auth() -> Result<()> {
+    let (mut node1, _, mut node2, node2_peer_id) = start_with_framing(true, true, |config| {
+        config.with_request_auth(RequestAuthConfig::new(b"cluster key".to_vec()))
+    })
+    .await?;
+
+    let capabilities = negotiated_capabilities(&node1, &node2_peer_id)
+        .await
+        .expect("capabilities negotiated");
+    assert!(capabilities.type_table.is_some());
+
+    assert_eq!(wait_for_add(&node2, "math1/add").await?, 3.0);
+    assert_eq!(wait_for_add(&node1, "math2/add").await?, 3.0);
+
+    node2.stop().await?;
+    node1.stop().await?;
+    Ok(())
+}
@@ -0,0 +1,173 @@
+// Tests for the connection callback of the QUIC transport
+//
+// The transport reports peers completing their first handshake, peers
+// reconnecting after a disconnect and lost connections to the callback set in
+// its options, one event at a time and in order.
+
+use anyhow::Result;
+use runar_node::network::{
+    ConnectionCallback, ConnectionEvent, ConnectionEventType, QuicTransportOptions,
+};
+use runar_node::node::{Node, NodeConfig};
+use runar_test_utils::{create_networked_node_test_config, without_discovery};
+
+use std::net::{Ipv4Addr, SocketAddr};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+/// Remove the discovery providers and adjust the QUIC options
+fn configure(
+    config: NodeConfig,
+    quic_options: impl FnOnce(QuicTransportOptions) -> QuicTransportOptions,
+) -> NodeConfig {
+    let mut config = without_discovery(config);
+    let network_config = config
+        .network_config
+        .as_mut()
+        .expect("test config has networking");
+    let options = network_config.quic_options.take().unwrap_or_default();
+    network_config.quic_options = Some(quic_options(options));
+    config
+}
+
+/// Wait for the next connection event
+async fn next_event(events: &mut mpsc::UnboundedReceiver<ConnectionEvent>) -> ConnectionEvent {
+    tokio::time::timeout(Duration::from_secs(10), events.recv())
+        .await
+        .expect("timeout waiting for a connection event")
+        .expect("callback dropped")
+}
+
+/// Test that first connects, disconnects and reconnects are told apart
+///
+/// INTENTION: A peer's first handshake is a `FirstConnect` carrying its node
+/// info, losing it is a `Disconnect`, and its next handshake is a `Reconnect`
+/// reporting how long it was gone.
+#[tokio::test]
+async fn test_callback_reports_reconnects() -> Result<()> {
+    let (sender, mut events) = mpsc::unbounded_channel();
+    let callback: ConnectionCallback = Arc::new(move |event| {
+        let sender = sender.clone();
+        Box::pin(async move {
+            sender.send(event)?;
+            Ok(())
+        })
+    });
+
+    let configs = create_networked_node_test_config(3)?;
+    let node1_config = configure(configs[0].clone(), |options| {
+        options.with_connection_callback(callback)
+    });
+    let node1_port = node1_config
+        .network_config
+        .as_ref()
+        .unwrap()
+        .transport_options
+        .bind_address
+        .port();
+    let mut node1 = Node::new(node1_config).await?;
+    node1.start().await?;
+    let node1_peer_id = node1.get_local_node_info().await?.peer_id;
+    let node1_addr = SocketAddr::from((Ipv4Addr::LOCALHOST, node1_port));
+
+    let node2_config = configure(configs[1].clone(), |options| options)
+        .with_initial_peers(vec![(node1_addr, node1_peer_id)]);
+
+    let mut node2 = Node::new(node2_config.clone()).await?;
+    node2.start().await?;
+    let node2_peer_id = node2.get_local_node_info().await?.peer_id;
+
+    let event = next_event(&mut events).await;
+    assert_eq!(event.peer_id, node2_peer_id);
+    assert_eq!(event.event_type, ConnectionEventType::FirstConnect);
+    assert_eq!(
+        event.node_info.map(|node_info| node_info.peer_id),
+        Some(node2_peer_id.clone())
+    );
+
+    node2.stop().await?;
+    let event = next_event(&mut events).await;
+    assert_eq!(event.peer_id, node2_peer_id);
+    assert_eq!(event.event_type, ConnectionEventType::Disconnect);
+    assert!(event.node_info.is_none());
+
+    // The old port may still be held by the closed endpoint
+    let mut node2_config = node2_config;
+    node2_config
+        .network_config
+        .as_mut()
+        .unwrap()
+        .transport_options
+        .bind_address = configs[2]
+        .network_config
+        .as_ref()
+        .unwrap()
+        .transport_options
+        .bind_address;
+    let mut node2 = Node::new(node2_config).await?;
+    node2.start().await?;
+    let event = next_event(&mut events).await;
+    assert_eq!(event.peer_id, node2_peer_id);
+    match event.event_type {
+        ConnectionEventType::Reconnect {
+            disconnect_duration,
+        } => assert!(disconnect_duration > Duration::ZERO),
+        other => panic!("expected a reconnect, got {other:?}"),
+    }
+    assert!(event.node_info.is_some());
+
+    node2.stop().await?;
+    node1.stop().await?;
+    Ok(())
+}
+
+/// Test that the callback sees the events of a peer in order
+///
+/// INTENTION: A callback that is slow to handle a connect must still get the
+/// following disconnect after it, never before.
+#[tokio::test]
+async fn test_callback_events_are_ordered() -> Result<()> {
+    let (sender, mut events) = mpsc::unbounded_channel();
+    let callback: ConnectionCallback = Arc::new(move |event: ConnectionEvent| {
+        let sender = sender.clone();
+        Box::pin(async move {
+            if event.event_type == ConnectionEventType::FirstConnect {
+                tokio::time::sleep(Duration::from_millis(500)).await;
+            }
+            sender.send(event)?;
+            Ok(())
+        })
+    });
+
+    let configs = create_networked_node_test_config(2)?;
+    let node1_config = configure(configs[0].clone(), |options| {
+        options.with_connection_callback(callback)
+    });
+    let node1_port = node1_config
+        .network_config
+        .as_ref()
+        .unwrap()
+        .transport_options
+        .bind_address
+        .port();
+    let mut node1 = Node::new(node1_config).await?;
+    node1.start().await?;
+    let node1_peer_id = node1.get_local_node_info().await?.peer_id;
+    let node1_addr = SocketAddr::from((Ipv4Addr::LOCALHOST, node1_port));
+
+    let node2_config = configure(configs[1].clone(), |options| options)
+        .with_initial_peers(vec![(node1_addr, node1_peer_id)]);
+    let mut node2 = Node::new(node2_config).await?;
+    node2.start().await?;
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    node2.stop().await?;
+
+    let event = next_event(&mut events).await;
+    assert_eq!(event.event_type, ConnectionEventType::FirstConnect);
+    let event = next_event(&mut events).await;
+    assert_eq!(event.event_type, ConnectionEventType::Disconnect);
+
+    node1.stop().await?;
+    Ok(())
+}
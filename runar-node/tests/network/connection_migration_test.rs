@@ -0,0 +1,96 @@
+// Tests for QUIC connection migration
+//
+// A node with connection migration enabled rebinds its endpoint to a new
+// socket, and its existing connections keep working on the new socket.
+
+use anyhow::Result;
+use runar_common::hmap;
+use runar_common::types::ArcValue;
+use runar_node::network::QuicTransportOptions;
+use runar_node::node::{Node, NodeConfig};
+use runar_test_utils::{create_networked_node_test_config, without_discovery};
+
+use std::net::{Ipv4Addr, SocketAddr};
+use std::time::Duration;
+
+use crate::fixtures::math_service::MathService;
+
+/// Remove the discovery providers and adjust the QUIC options
+fn configure(
+    config: NodeConfig,
+    quic_options: impl FnOnce(QuicTransportOptions) -> QuicTransportOptions,
+) -> NodeConfig {
+    let mut config = without_discovery(config);
+    let network_config = config
+        .network_config
+        .as_mut()
+        .expect("test config has networking");
+    let options = network_config.quic_options.take().unwrap_or_default();
+    network_config.quic_options = Some(quic_options(options));
+    config
+}
+
+async fn add(node: &Node, a: f64, b: f64) -> Result<f64> {
+    node.request(
+        "math/add",
+        Some(ArcValue::new_map(hmap! {
+            "a" => a,
+            "b" => b
+        })),
+    )
+    .await
+}
+
+/// Test that connections survive a rebind of the endpoint
+///
+/// INTENTION: Migrating moves the connections to a new socket on the same
+/// port, so requests keep flowing to the peer without a new handshake. The
+/// address polling task runs alongside without disturbing them.
+#[tokio::test]
+async fn test_connections_survive_migration() -> Result<()> {
+    let configs = create_networked_node_test_config(2)?;
+    let node1_config = configure(configs[0].clone(), |options| options);
+    let node1_port = node1_config
+        .network_config
+        .as_ref()
+        .unwrap()
+        .transport_options
+        .bind_address
+        .port();
+    let mut node1 = Node::new(node1_config).await?;
+    node1.add_service(MathService::new("math", "math")).await?;
+    node1.start().await?;
+    let node1_peer_id = node1.get_local_node_info().await?.peer_id;
+    let node1_addr = SocketAddr::from((Ipv4Addr::LOCALHOST, node1_port));
+
+    let node2_config = configure(configs[1].clone(), |options| {
+        options
+            .with_connection_migration(true)
+            .with_migration_check_interval(Duration::from_millis(50))
+    })
+    .with_initial_peers(vec![(node1_addr, node1_peer_id)]);
+    let mut node2 = Node::new(node2_config).await?;
+    node2.start().await?;
+
+    assert_eq!(add(&node2, 2.0, 3.0).await?, 5.0);
+    let connections = node2.transport_stats().await.unwrap();
+
+    node2.migrate_connections().await?;
+    // Let the polling task run a few times as well
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    assert_eq!(add(&node2, 4.0, 5.0).await?, 9.0);
+    let stats = node2.transport_stats().await.unwrap();
+    assert_eq!(
+        stats.total_connections_established,
+        connections.total_connections_established
+    );
+    assert_eq!(stats.current_connections, 1);
+
+    // Without migration enabled there is nothing to do
+    node1.migrate_connections().await?;
+
+    node2.stop().await?;
+    node1.stop().await?;
+    Ok(())
+}
@@ -0,0 +1,311 @@
+// Tests for the QUIC ConnectionPool
+//
+// These tests verify the pool statistics, the eviction policies applied when
+// the pool is full, the peers kept for their requests in flight, the health check of pooled connections and the callers
+// waiting for a connection.
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use runar_common::logging::{Component, Logger};
+use runar_node::network::transport::{
+    ConnectionPool, ConnectionPoolOptions, ConnectionPoolStats, ErrorCode, EvictionPolicy,
+    NetworkError, NetworkMessage, NetworkMessagePayloadItem, PeerId, StreamPoolOptions,
+};
+use rustls_pki_types::{CertificateDer, PrivateKeyDer, PrivatePkcs8KeyDer};
+
+fn test_logger() -> Arc<Logger> {
+    Arc::new(Logger::new_root(Component::Network, "connection_pool_test"))
+}
+
+fn pool(max_size: usize, eviction_policy: EvictionPolicy) -> ConnectionPool {
+    let options = ConnectionPoolOptions {
+        max_size,
+        eviction_policy,
+        ..Default::default()
+    };
+    ConnectionPool::with_options(options, test_logger())
+}
+
+fn add_peer(pool: &ConnectionPool, name: &str) -> PeerId {
+    let peer_id = PeerId::new(name.to_string());
+    pool.get_or_create_peer(
+        peer_id.clone(),
+        format!("{name}:4433"),
+        4,
+        StreamPoolOptions::default(),
+        test_logger(),
+    );
+    peer_id
+}
+
+fn pooled(pool: &ConnectionPool, name: &str) -> bool {
+    pool.peers.contains_key(&PeerId::new(name.to_string()))
+}
+
+/// Connect a client endpoint to a server endpoint on localhost
+///
+/// Returns the client side of the connection; the server endpoint is returned
+/// too so it stays alive for the duration of the test.
+async fn connect_loopback() -> Result<(quinn::Connection, quinn::Endpoint)> {
+    if rustls::crypto::CryptoProvider::get_default().is_none() {
+        let _ = rustls::crypto::ring::default_provider().install_default();
+    }
+
+    let cert = rcgen::generate_simple_self_signed(vec!["localhost".to_string()])?;
+    let cert_der = CertificateDer::from(cert.serialize_der()?);
+    let key_der = PrivateKeyDer::Pkcs8(PrivatePkcs8KeyDer::from(cert.serialize_private_key_der()));
+
+    let server_config = quinn::ServerConfig::with_single_cert(vec![cert_der.clone()], key_der)?;
+    let server = quinn::Endpoint::server(server_config, "127.0.0.1:0".parse()?)?;
+    let server_addr = server.local_addr()?;
+
+    let accepting_server = server.clone();
+    tokio::spawn(async move {
+        while let Some(incoming) = accepting_server.accept().await {
+            if let Ok(connection) = incoming.await {
+                // Keep the connection open until the client closes it
+                let _ = connection.closed().await;
+            }
+        }
+    });
+
+    let mut roots = rustls::RootCertStore::empty();
+    roots.add(cert_der)?;
+    let mut client = quinn::Endpoint::client("127.0.0.1:0".parse()?)?;
+    client.set_default_client_config(quinn::ClientConfig::with_root_certificates(Arc::new(
+        roots,
+    ))?);
+    let connection = client.connect(server_addr, "localhost")?.await?;
+
+    Ok((connection, server))
+}
+
+#[tokio::test]
+async fn test_connection_pool_stats() {
+    let pool = pool(10, EvictionPolicy::Lru);
+    let peer_a = add_peer(&pool, "a");
+    add_peer(&pool, "a");
+    assert!(pool.get_peer(&peer_a).is_some());
+    assert!(pool.get_peer(&PeerId::new("missing".to_string())).is_none());
+
+    assert_eq!(
+        pool.stats(),
+        ConnectionPoolStats {
+            current_size: 1,
+            max_size: 10,
+            hits: 2,
+            misses: 2,
+            evictions: 0,
+        }
+    );
+
+    pool.remove_peer(&peer_a).await.unwrap();
+    assert_eq!(pool.stats().current_size, 0);
+}
+
+#[tokio::test]
+async fn test_lru_eviction() {
+    let pool = pool(2, EvictionPolicy::Lru);
+    let peer_a = add_peer(&pool, "a");
+    add_peer(&pool, "b");
+    // "a" is now the most recently used peer
+    pool.get_peer(&peer_a);
+
+    add_peer(&pool, "c");
+    assert!(pooled(&pool, "a"));
+    assert!(!pooled(&pool, "b"));
+    assert!(pooled(&pool, "c"));
+    assert_eq!(pool.stats().evictions, 1);
+}
+
+fn message(peer_id: &PeerId, message_type: &str) -> NetworkMessage {
+    NetworkMessage {
+        source: peer_id.clone(),
+        destination: PeerId::new("local".to_string()),
+        message_type: message_type.to_string(),
+        payloads: vec![NetworkMessagePayloadItem::new(
+            "math/add".to_string(),
+            Vec::new(),
+            "request-1".to_string(),
+        )],
+        signature: None,
+        hop_count: 0,
+        visited_peers: Vec::new(),
+        auth_tag: None,
+        expires_at: None,
+    }
+}
+
+#[tokio::test]
+async fn test_eviction_skips_peers_with_requests_in_flight() {
+    let pool = pool(2, EvictionPolicy::Lru);
+    let peer_a = add_peer(&pool, "a");
+    let peer_b = add_peer(&pool, "b");
+    // "a" is the least recently used peer, but awaits a response
+    let peer_state = pool.get_peer(&peer_a).unwrap();
+    pool.get_peer(&peer_b);
+    peer_state.track_message(&message(&peer_a, "Request"));
+    assert!(peer_state.has_in_flight_requests());
+
+    add_peer(&pool, "c");
+    assert!(pooled(&pool, "a"));
+    assert!(!pooled(&pool, "b"));
+
+    // Once answered, the request no longer protects the peer
+    peer_state.track_message(&message(&peer_a, "Response"));
+    assert!(!peer_state.has_in_flight_requests());
+    pool.get_peer(&PeerId::new("c".to_string()));
+    add_peer(&pool, "d");
+    assert!(!pooled(&pool, "a"));
+}
+
+#[tokio::test]
+async fn test_lfu_eviction() {
+    let pool = pool(2, EvictionPolicy::Lfu);
+    let peer_a = add_peer(&pool, "a");
+    let peer_b = add_peer(&pool, "b");
+    pool.get_peer(&peer_a);
+    pool.get_peer(&peer_a);
+    // "b" is the most recently used but the least often used peer
+    pool.get_peer(&peer_b);
+
+    add_peer(&pool, "c");
+    assert!(pooled(&pool, "a"));
+    assert!(!pooled(&pool, "b"));
+    assert!(pooled(&pool, "c"));
+}
+
+#[tokio::test]
+async fn test_random_eviction_and_unlimited_pool() {
+    let pool = pool(2, EvictionPolicy::Random);
+    add_peer(&pool, "a");
+    add_peer(&pool, "b");
+    add_peer(&pool, "c");
+    assert!(pooled(&pool, "c"));
+    assert_eq!(pool.stats().current_size, 2);
+    assert_eq!(pool.stats().evictions, 1);
+
+    let unlimited = self::pool(0, EvictionPolicy::Lru);
+    for name in ["a", "b", "c"] {
+        add_peer(&unlimited, name);
+    }
+    assert_eq!(unlimited.stats().current_size, 3);
+    assert_eq!(unlimited.stats().evictions, 0);
+}
+
+/// Test that the health check evicts peers without a live connection
+///
+/// INTENTION: A peer with an open connection is reported healthy and kept;
+/// a peer whose connection was never established or was closed is reported
+/// unhealthy and removed from the pool.
+#[tokio::test]
+async fn test_health_check_all() -> Result<()> {
+    let (connection, _server) = connect_loopback().await?;
+    let (closed_connection, _closed_server) = connect_loopback().await?;
+
+    let pool = pool(10, EvictionPolicy::Lru);
+    let live = add_peer(&pool, "live");
+    let never_connected = add_peer(&pool, "never-connected");
+    let closed = add_peer(&pool, "closed");
+    pool.get_peer(&live)
+        .unwrap()
+        .set_connection(connection)
+        .await;
+    closed_connection.close(0u32.into(), b"done");
+    pool.get_peer(&closed)
+        .unwrap()
+        .set_connection(closed_connection)
+        .await;
+
+    let mut results = pool.health_check_all().await;
+    results.sort_by(|a, b| a.0.public_key.cmp(&b.0.public_key));
+    assert_eq!(
+        results,
+        vec![
+            (closed, false),
+            (live.clone(), true),
+            (never_connected, false)
+        ]
+    );
+    assert_eq!(pool.stats().current_size, 1);
+    assert_eq!(pool.stats().evictions, 2);
+    assert!(pool.is_peer_connected(&live).await);
+    Ok(())
+}
+
+/// Test waiting for a peer to connect
+///
+/// INTENTION: A caller waiting for a peer is woken when its connection is
+/// set, while a caller waiting for a peer that never connects fails with a
+/// timeout once its deadline passes.
+#[tokio::test]
+async fn test_wait_for_connection() -> Result<()> {
+    let (connection, _server) = connect_loopback().await?;
+    let pool = Arc::new(pool(10, EvictionPolicy::Lru));
+    let peer = add_peer(&pool, "reconnecting");
+
+    let waiter = {
+        let pool = pool.clone();
+        let peer = peer.clone();
+        tokio::spawn(async move {
+            let deadline = Instant::now() + Duration::from_secs(5);
+            pool.wait_for_connection(&peer, deadline).await
+        })
+    };
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert!(!waiter.is_finished());
+
+    let peer_state = pool.get_peer(&peer).unwrap();
+    pool.set_peer_connection(&peer_state, connection).await;
+    let connected = waiter.await??;
+    assert_eq!(connected.peer_id, peer);
+
+    // An already connected peer is returned right away
+    let deadline = Instant::now();
+    assert!(pool.wait_for_connection(&peer, deadline).await.is_ok());
+
+    let deadline = Instant::now() + Duration::from_millis(50);
+    match pool
+        .wait_for_connection(&PeerId::new("missing".to_string()), deadline)
+        .await
+    {
+        Err(NetworkError::ConnectionError(code, message)) => {
+            assert_eq!(code, ErrorCode::NotConnected);
+            assert_eq!(message, "timeout waiting for connection");
+        }
+        other => panic!("expected a timeout, got {other:?}"),
+    }
+    Ok(())
+}
+
+/// Test the limit on callers waiting for connections
+///
+/// INTENTION: Once `max_connection_waiters` callers are parked, further
+/// callers fail right away instead of piling up.
+#[tokio::test]
+async fn test_wait_for_connection_waiter_limit() {
+    let options = ConnectionPoolOptions {
+        max_connection_waiters: 1,
+        ..Default::default()
+    };
+    let pool = Arc::new(ConnectionPool::with_options(options, test_logger()));
+    let peer = PeerId::new("offline".to_string());
+
+    let waiter = {
+        let pool = pool.clone();
+        let peer = peer.clone();
+        tokio::spawn(async move {
+            let deadline = Instant::now() + Duration::from_millis(200);
+            pool.wait_for_connection(&peer, deadline).await
+        })
+    };
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    match pool.wait_for_connection(&peer, Instant::now()).await {
+        Err(NetworkError::ConnectionError(code, _)) => assert_eq!(code, ErrorCode::RateLimited),
+        other => panic!("expected the waiter limit, got {other:?}"),
+    }
+    assert!(waiter.await.unwrap().is_err());
+}
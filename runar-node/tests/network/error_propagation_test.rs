@@ -0,0 +1,81 @@
+// Tests for error propagation across the network
+//
+// Errors returned by remote actions keep their type when it is registered on
+// both nodes, and keep their code and message otherwise.
+
+use anyhow::Result;
+use runar_node::network::transport::{ErrorCode, NetworkError};
+use runar_node::node::Node;
+use runar_test_utils::{create_networked_node_test_config, without_discovery};
+
+use std::net::{Ipv4Addr, SocketAddr};
+
+use crate::fixtures::failing_service::{FailingService, InsufficientFunds};
+
+/// Test that remote action errors reach the caller typed
+///
+/// INTENTION: Both nodes register `InsufficientFunds` (through their own
+/// instance of the failing service), so the caller can downcast the error of
+/// a remote withdraw to it, as well as to the NetworkError carrying the code.
+/// A plain error still arrives as a NetworkError with its message.
+#[tokio::test]
+async fn test_typed_errors_cross_the_network() -> Result<()> {
+    let configs = create_networked_node_test_config(2)?;
+    let node1_config = without_discovery(configs[0].clone());
+    let node1_port = node1_config
+        .network_config
+        .as_ref()
+        .unwrap()
+        .transport_options
+        .bind_address
+        .port();
+    let mut node1 = Node::new(node1_config).await?;
+    node1
+        .add_service(FailingService::new("payments", "payments"))
+        .await?;
+    node1.start().await?;
+    let node1_peer_id = node1.get_local_node_info().await?.peer_id;
+
+    let node1_addr = SocketAddr::from((Ipv4Addr::LOCALHOST, node1_port));
+    let node2_config =
+        without_discovery(configs[1].clone()).with_initial_peers(vec![(node1_addr, node1_peer_id)]);
+    let mut node2 = Node::new(node2_config).await?;
+    node2
+        .add_service(FailingService::new("local_payments", "local_payments"))
+        .await?;
+    node2.start().await?;
+
+    let error = node2
+        .request::<(), bool>("payments/withdraw", None)
+        .await
+        .unwrap_err();
+    let funds = error
+        .downcast_ref::<InsufficientFunds>()
+        .expect("typed error is rebuilt");
+    assert_eq!(
+        *funds,
+        InsufficientFunds {
+            needed: 100,
+            available: 40
+        }
+    );
+    let network_error = error
+        .downcast_ref::<NetworkError>()
+        .expect("code is kept as context");
+    assert_eq!(network_error.code(), ErrorCode::RemoteError);
+    assert!(network_error.message().contains("Withdrawal rejected"));
+
+    // Errors of unregistered types fall back to the message
+    let error = node2
+        .request::<(), bool>("payments/fail", None)
+        .await
+        .unwrap_err();
+    assert!(error.downcast_ref::<InsufficientFunds>().is_none());
+    let network_error = error.downcast_ref::<NetworkError>().unwrap();
+    assert_eq!(network_error.code(), ErrorCode::RemoteError);
+    assert_eq!(network_error.message(), "plain failure");
+
+    node2.stop().await?;
+    node1.stop().await?;
+    Ok(())
+}
@@ -0,0 +1,216 @@
+#![cfg(unix)]
+// Tests for event deduplication
+//
+// Broadcast events carry an ID derived from the publisher, a per-event nonce
+// and the content; a node that receives the same event again within its dedup
+// window does not deliver it a second time.
+
+use anyhow::Result;
+use runar_common::logging::{Component, Logger};
+use runar_common::types::{ArcValue, SerializerRegistry};
+use runar_node::network::discovery::multicast_discovery::PeerInfo;
+use runar_node::network::transport::{
+    NetworkMessage, NetworkMessagePayloadItem, NetworkTransport, PeerId,
+};
+use runar_node::network::NodeInfo;
+use runar_node::node::Node;
+use runar_node::services::EventContext;
+use runar_node::testing::NetworkPartition;
+use runar_node::{event_dedup_id, EventDedupCache, NodeDelegate};
+use runar_test_utils::{create_networked_node_test_config, without_discovery};
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::time::sleep;
+
+use crate::network::{socket_path, with_local_socket};
+
+#[test]
+fn test_event_dedup_id() {
+    let id = event_dedup_id("node-a", 1, "events/ping", b"ping");
+    assert_eq!(id, event_dedup_id("node-a", 1, "events/ping", b"ping"));
+    assert_ne!(id, event_dedup_id("node-b", 1, "events/ping", b"ping"));
+    assert_ne!(id, event_dedup_id("node-a", 2, "events/ping", b"ping"));
+    assert_ne!(id, event_dedup_id("node-a", 1, "events/pong", b"ping"));
+    assert_ne!(id, event_dedup_id("node-a", 1, "events/ping", b"pong"));
+    // The boundary between topic and payload is part of the hash
+    assert_ne!(
+        event_dedup_id("node-a", 1, "events/pin", b"gping"),
+        event_dedup_id("node-a", 1, "events/ping", b"ping")
+    );
+}
+
+/// Test that the cache only remembers the last `window` events
+#[test]
+fn test_dedup_window_slides() {
+    let mut cache = EventDedupCache::new(2);
+    assert!(cache.insert([1; 8]));
+    assert!(!cache.insert([1; 8]));
+    assert!(cache.insert([2; 8]));
+    assert!(cache.insert([3; 8]));
+    assert_eq!(cache.len(), 2);
+
+    // [1; 8] fell out of the window and is new again
+    assert!(!cache.contains(&[1; 8]));
+    assert!(cache.insert([1; 8]));
+    assert!(!cache.insert([3; 8]));
+
+    let mut disabled = EventDedupCache::new(0);
+    assert!(disabled.insert([1; 8]));
+    assert!(disabled.insert([1; 8]));
+    assert!(disabled.is_empty());
+}
+
+#[test]
+fn test_signing_digest_covers_dedup_id() {
+    let mut payload =
+        NetworkMessagePayloadItem::new("events/ping".to_string(), vec![1, 2, 3], String::new());
+    let mut message = NetworkMessage {
+        source: PeerId::new("source".to_string()),
+        destination: PeerId::new("destination".to_string()),
+        message_type: "Event".to_string(),
+        payloads: vec![payload.clone()],
+        signature: None,
+        hop_count: 0,
+        visited_peers: Vec::new(),
+        auth_tag: None,
+        expires_at: None,
+    };
+    let unstamped = message.signing_digest();
+
+    payload.dedup_id = Some(event_dedup_id("source", 1, "events/ping", &[1, 2, 3]));
+    message.payloads = vec![payload];
+    assert_ne!(unstamped, message.signing_digest());
+}
+
+/// Subscribe a handler recording the string events of `topic`
+async fn record_events(node: &Node, topic: &str) -> Result<Arc<Mutex<Vec<String>>>> {
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let events = received.clone();
+    node.subscribe(
+        topic.to_string(),
+        Box::new(move |_ctx: Arc<EventContext>, data: Option<ArcValue>| {
+            let value = data
+                .and_then(|mut data| data.as_type::<String>().ok())
+                .unwrap_or_default();
+            events.lock().unwrap().push(value);
+            Box::pin(async { Ok(()) }) as Pin<Box<dyn Future<Output = Result<()>> + Send>>
+        }),
+    )
+    .await?;
+    Ok(received)
+}
+
+/// Test that a node delivers a stamped event only once
+///
+/// INTENTION: A publisher on the in-memory network sends the same stamped
+/// event twice, as a node reached over two paths would receive it; the
+/// subscriber sees it once, while an event with another ID is delivered.
+#[tokio::test]
+async fn test_duplicate_event_delivered_once() -> Result<()> {
+    let network = NetworkPartition::new();
+    let config = without_discovery(create_networked_node_test_config(1)?.remove(0))
+        .with_transport_factory(network.transport_factory());
+    let network_id = config.default_network_id.clone();
+
+    let mut node = Node::new(config).await?;
+    let received = record_events(&node, "events/reading").await?;
+    node.start().await?;
+    let node_id = node.get_local_node_info().await?.peer_id;
+
+    let publisher_id = PeerId::new("publisher".to_string());
+    let publisher = network.add_transport(
+        NodeInfo {
+            peer_id: publisher_id.clone(),
+            network_ids: vec![network_id],
+            addresses: Vec::new(),
+            services: Vec::new(),
+            version: 0,
+            subscriptions: Vec::new(),
+        },
+        |_message: NetworkMessage| Ok(()),
+    );
+    publisher.start().await?;
+    publisher
+        .connect_peer(PeerInfo::new(node_id.public_key.clone(), Vec::new()))
+        .await?;
+
+    let registry = SerializerRegistry::with_defaults(Arc::new(Logger::new_root(
+        Component::Custom("Test"),
+        "publisher",
+    )));
+    let event = |reading: &str, nonce: u64| -> Result<NetworkMessage> {
+        let value_bytes = registry
+            .serialize_value(&ArcValue::new_primitive(reading.to_string()))?
+            .to_vec();
+        let mut payload = NetworkMessagePayloadItem::new(
+            "events/reading".to_string(),
+            value_bytes.clone(),
+            String::new(),
+        );
+        payload.dedup_id = Some(event_dedup_id(
+            "publisher",
+            nonce,
+            "events/reading",
+            &value_bytes,
+        ));
+        Ok(NetworkMessage {
+            source: publisher_id.clone(),
+            destination: node_id.clone(),
+            message_type: "Event".to_string(),
+            payloads: vec![payload],
+            signature: None,
+            hop_count: 0,
+            visited_peers: Vec::new(),
+            auth_tag: None,
+            expires_at: None,
+        })
+    };
+
+    publisher.send_message(event("42", 1)?).await?;
+    publisher.send_message(event("42", 1)?).await?;
+    publisher.send_message(event("42", 2)?).await?;
+    sleep(Duration::from_millis(500)).await;
+    assert_eq!(*received.lock().unwrap(), vec!["42", "42"]);
+
+    node.stop().await?;
+    Ok(())
+}
+
+/// Test that events repeating a payload are all delivered
+///
+/// INTENTION: Each publish draws a new nonce, so a reading published twice in
+/// a row is two events to the receiving node, not a duplicate.
+#[tokio::test]
+async fn test_repeated_payloads_are_delivered() -> Result<()> {
+    let configs = create_networked_node_test_config(2)?;
+    let node1_socket = socket_path("dedup1");
+    let node2_socket = socket_path("dedup2");
+
+    let mut node1 = Node::new(with_local_socket(configs[0].clone(), &node1_socket)).await?;
+    // Subscribed before the handshake, so node2 learns about it from node1's node info
+    let received = record_events(&node1, "events/reading").await?;
+    node1.start().await?;
+    let mut node2 = Node::new(with_local_socket(configs[1].clone(), &node2_socket)).await?;
+    node2.start().await?;
+    node2.add_local_peer(&node1_socket).await?;
+    // node1 learns about node2 in the background after the handshake
+    sleep(Duration::from_secs(1)).await;
+
+    for reading in ["42", "42", "43"] {
+        node2
+            .publish(
+                "events/reading".to_string(),
+                Some(ArcValue::new_primitive(reading.to_string())),
+            )
+            .await?;
+    }
+    sleep(Duration::from_millis(500)).await;
+    assert_eq!(*received.lock().unwrap(), vec!["42", "42", "43"]);
+
+    node2.stop().await?;
+    node1.stop().await?;
+    Ok(())
+}
@@ -0,0 +1,215 @@
+#![cfg(unix)]
+// Tests for ordered event delivery
+//
+// Events published with `ordered: true` carry a per-topic sequence number;
+// receiving nodes hold back events that arrive ahead of a gap and deliver
+// them in sequence order.
+
+use anyhow::Result;
+use runar_common::types::ArcValue;
+use runar_node::node::Node;
+use runar_node::services::{EventContext, PublishOptions};
+use runar_node::{sequenced_event_dedup_id, NodeDelegate, OrderedEventBuffer};
+use runar_test_utils::create_networked_node_test_config;
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::time::sleep;
+
+use crate::network::{socket_path, with_local_socket};
+
+fn sequences(events: Vec<(u64, &str)>) -> Vec<u64> {
+    events.into_iter().map(|(sequence, _)| sequence).collect()
+}
+
+/// Test that events arriving ahead of a gap wait for it to be filled
+#[test]
+fn test_buffer_reorders_events() {
+    let mut buffer = OrderedEventBuffer::new(10, Duration::from_secs(5));
+
+    // The first event starts the stream
+    assert_eq!(
+        sequences(buffer.accept("node-a", "orders", 1, "one")),
+        vec![1]
+    );
+    assert!(buffer.accept("node-a", "orders", 3, "three").is_empty());
+    assert!(buffer.accept("node-a", "orders", 4, "four").is_empty());
+    assert_eq!(buffer.len(), 2);
+    assert_eq!(
+        buffer.accept("node-a", "orders", 2, "two"),
+        vec![(2, "two"), (3, "three"), (4, "four")]
+    );
+    assert!(buffer.is_empty());
+
+    // Duplicates of delivered events are dropped
+    assert!(buffer.accept("node-a", "orders", 3, "three").is_empty());
+
+    // Publishers and topics are ordered independently
+    assert_eq!(
+        sequences(buffer.accept("node-b", "orders", 7, "seven")),
+        vec![7]
+    );
+    assert_eq!(
+        sequences(buffer.accept("node-a", "stock", 1, "one")),
+        vec![1]
+    );
+
+    // Sequence 1 again means the publisher restarted
+    assert!(buffer.accept("node-a", "orders", 6, "six").is_empty());
+    assert_eq!(
+        sequences(buffer.accept("node-a", "orders", 1, "one")),
+        vec![1]
+    );
+    assert!(buffer.is_empty());
+}
+
+/// Test the size and time bounds of the buffer
+#[test]
+fn test_buffer_limits() {
+    let mut buffer = OrderedEventBuffer::new(1, Duration::from_millis(50));
+    assert_eq!(
+        sequences(buffer.accept("node-a", "orders", 1, "one")),
+        vec![1]
+    );
+
+    // Only one event fits behind the gap
+    assert!(buffer.accept("node-a", "orders", 3, "three").is_empty());
+    assert!(buffer.accept("node-a", "orders", 4, "four").is_empty());
+    assert_eq!(buffer.len(), 1);
+
+    // A gap open for longer than the timeout is given up
+    assert_eq!(buffer.expire(Instant::now()), 0);
+    assert_eq!(
+        buffer.expire(Instant::now() + Duration::from_millis(100)),
+        1
+    );
+    assert!(buffer.is_empty());
+    assert!(buffer.accept("node-a", "orders", 2, "two").is_empty());
+    assert_eq!(
+        sequences(buffer.accept("node-a", "orders", 4, "four")),
+        vec![4]
+    );
+
+    // Forgetting a publisher drops what it had buffered
+    assert!(buffer.accept("node-a", "orders", 6, "six").is_empty());
+    buffer.remove_publisher("node-a");
+    assert!(buffer.is_empty());
+    assert_eq!(
+        sequences(buffer.accept("node-a", "orders", 9, "nine")),
+        vec![9]
+    );
+}
+
+/// Test that delivery is serialized per stream
+///
+/// INTENTION: Deliveries of one publisher's topic share a lock, so that
+/// events released later wait for the earlier ones, while other streams are
+/// delivered independently.
+#[tokio::test]
+async fn test_buffer_delivery_locks() {
+    let mut buffer = OrderedEventBuffer::<&str>::default();
+    let orders = buffer.delivery_lock("node-a", "orders");
+    assert!(Arc::ptr_eq(
+        &orders,
+        &buffer.delivery_lock("node-a", "orders")
+    ));
+    assert!(!Arc::ptr_eq(
+        &orders,
+        &buffer.delivery_lock("node-a", "payments")
+    ));
+    assert!(!Arc::ptr_eq(
+        &orders,
+        &buffer.delivery_lock("node-b", "orders")
+    ));
+
+    let _delivering = orders.lock().await;
+    assert!(buffer.delivery_lock("node-a", "orders").try_lock().is_err());
+    assert!(buffer.delivery_lock("node-b", "orders").try_lock().is_ok());
+
+    buffer.remove_publisher("node-a");
+    assert!(!Arc::ptr_eq(
+        &orders,
+        &buffer.delivery_lock("node-a", "orders")
+    ));
+}
+
+#[test]
+fn test_sequenced_event_dedup_id() {
+    let id = sequenced_event_dedup_id("node-a", "events/ping", 1, b"ping");
+    assert_eq!(
+        id,
+        sequenced_event_dedup_id("node-a", "events/ping", 1, b"ping")
+    );
+    assert_ne!(
+        id,
+        sequenced_event_dedup_id("node-a", "events/ping", 2, b"ping")
+    );
+    assert_ne!(
+        id,
+        sequenced_event_dedup_id("node-b", "events/ping", 1, b"ping")
+    );
+}
+
+/// Test ordered events published by a remote node
+///
+/// INTENTION: Ordered events reach the subscriber in publish order with their
+/// sequence numbers, and repeating a payload does not make an ordered event a
+/// duplicate.
+#[tokio::test]
+async fn test_ordered_events_between_nodes() -> Result<()> {
+    let configs = create_networked_node_test_config(2)?;
+    let node1_socket = socket_path("ordered1");
+    let node2_socket = socket_path("ordered2");
+
+    let mut node1 = Node::new(with_local_socket(configs[0].clone(), &node1_socket)).await?;
+    // Subscribed before the handshake, so node2 learns about it from node1's node info
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let events = received.clone();
+    node1
+        .subscribe(
+            "events/state".to_string(),
+            Box::new(move |ctx: Arc<EventContext>, data: Option<ArcValue>| {
+                let value = data
+                    .and_then(|mut data| data.as_type::<String>().ok())
+                    .unwrap_or_default();
+                events.lock().unwrap().push((ctx.sequence(), value));
+                Box::pin(async { Ok(()) }) as Pin<Box<dyn Future<Output = Result<()>> + Send>>
+            }),
+        )
+        .await?;
+    node1.start().await?;
+    let mut node2 = Node::new(with_local_socket(configs[1].clone(), &node2_socket)).await?;
+    node2.start().await?;
+    node2.add_local_peer(&node1_socket).await?;
+    // node1 learns about node2 in the background after the handshake
+    sleep(Duration::from_secs(1)).await;
+
+    for state in ["open", "open", "closed"] {
+        node2
+            .publish_with_options(
+                "events/state",
+                Some(ArcValue::new_primitive(state.to_string())),
+                PublishOptions {
+                    broadcast: true,
+                    ordered: true,
+                    ..Default::default()
+                },
+            )
+            .await?;
+    }
+    sleep(Duration::from_millis(500)).await;
+    assert_eq!(
+        *received.lock().unwrap(),
+        vec![
+            (Some(1), "open".to_string()),
+            (Some(2), "open".to_string()),
+            (Some(3), "closed".to_string()),
+        ]
+    );
+
+    node2.stop().await?;
+    node1.stop().await?;
+    Ok(())
+}
@@ -0,0 +1,103 @@
+// Tests for the gossip of service health between nodes
+//
+// Nodes configured with a health gossip interval send their health report to
+// their peers, so that `Node::cluster_health` answers for the whole mesh.
+
+use anyhow::Result;
+use runar_node::network::PeerId;
+use runar_node::node::{Node, NodeConfig};
+use runar_node::{HealthGossip, HealthReport, ServiceState};
+use runar_test_utils::{create_networked_node_test_config, without_discovery};
+
+use std::collections::HashMap;
+use std::net::{Ipv4Addr, SocketAddr};
+use std::time::Duration;
+
+use crate::fixtures::math_service::MathService;
+
+const GOSSIP_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Remove the discovery providers and enable the health gossip
+fn configure(config: NodeConfig) -> NodeConfig {
+    without_discovery(config).with_health_gossip_interval(GOSSIP_INTERVAL)
+}
+
+/// Test that each node learns the health of its peers
+///
+/// INTENTION: A node's cluster view holds its own report and the latest one
+/// gossiped by each peer. Once a peer stops gossiping, its last report is
+/// kept but marked as stale.
+#[tokio::test]
+async fn test_cluster_health_includes_peer_reports() -> Result<()> {
+    let configs = create_networked_node_test_config(2)?;
+    let node1_config = configure(configs[0].clone());
+    let node1_port = node1_config
+        .network_config
+        .as_ref()
+        .unwrap()
+        .transport_options
+        .bind_address
+        .port();
+    let mut node1 = Node::new(node1_config).await?;
+    node1.start().await?;
+    let node1_peer_id = node1.get_local_node_info().await?.peer_id;
+    let node1_addr = SocketAddr::from((Ipv4Addr::LOCALHOST, node1_port));
+
+    let node2_config =
+        configure(configs[1].clone()).with_initial_peers(vec![(node1_addr, node1_peer_id.clone())]);
+    let mut node2 = Node::new(node2_config).await?;
+    node2.add_service(MathService::new("math", "math")).await?;
+    node2.start().await?;
+    let node2_peer_id = node2.get_local_node_info().await?.peer_id;
+
+    // Wait for the first report of node2, connecting can take a while
+    let mut cluster = node1.cluster_health().await;
+    for _ in 0..50 {
+        if cluster.contains_key(&node2_peer_id) {
+            break;
+        }
+        tokio::time::sleep(GOSSIP_INTERVAL).await;
+        cluster = node1.cluster_health().await;
+    }
+    assert_eq!(cluster.len(), 2);
+    assert!(cluster[&node1_peer_id].healthy);
+    let node2_report = &cluster[&node2_peer_id];
+    assert!(node2_report.healthy);
+    assert!(!node2_report.stale);
+    assert_eq!(node2_report.peer_id, node2_peer_id.to_string());
+    assert!(node2_report.services.contains_key("math"));
+    tokio::time::sleep(GOSSIP_INTERVAL * 2).await;
+    assert!(node2.cluster_health().await.contains_key(&node1_peer_id));
+
+    node2.stop().await?;
+    tokio::time::sleep(GOSSIP_INTERVAL * 4).await;
+    let cluster = node1.cluster_health().await;
+    assert!(cluster[&node2_peer_id].stale);
+    assert!(!cluster[&node1_peer_id].stale);
+
+    node1.stop().await?;
+    Ok(())
+}
+
+/// Test that reports are marked as stale after two gossip intervals
+///
+/// INTENTION: Staleness is computed when reading, so a report recorded again
+/// is fresh again, and a node with a failed service is reported unhealthy.
+#[tokio::test]
+async fn test_health_gossip_marks_outdated_reports_stale() {
+    let peer = PeerId::new("peer".to_string());
+    let mut services = HashMap::new();
+    services.insert("math".to_string(), ServiceState::Error);
+    let report = HealthReport::new(&peer, services);
+    assert!(!report.healthy);
+
+    let mut gossip = HealthGossip::new(Duration::from_millis(50));
+    gossip.record(peer.clone(), report.clone());
+    assert!(!gossip.reports()[&peer].stale);
+
+    tokio::time::sleep(Duration::from_millis(150)).await;
+    assert!(gossip.reports()[&peer].stale);
+
+    gossip.record(peer.clone(), report);
+    assert!(!gossip.reports()[&peer].stale);
+}
@@ -0,0 +1,139 @@
+// Tests for the disconnection of idle peers
+//
+// A node disconnects peers it exchanged no message with for longer than its
+// idle timeout, unless they still owe it a response, and reconnects when it
+// next sends them a message.
+
+use anyhow::Result;
+use runar_common::hmap;
+use runar_common::types::ArcValue;
+use runar_node::network::QuicTransportOptions;
+use runar_node::node::{Node, NodeConfig};
+use runar_test_utils::{create_networked_node_test_config, without_discovery};
+
+use std::net::{Ipv4Addr, SocketAddr};
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::fixtures::cancellable_service::CancellableService;
+use crate::fixtures::math_service::MathService;
+
+/// Remove the discovery providers and adjust the QUIC options
+fn configure(
+    config: NodeConfig,
+    quic_options: impl FnOnce(QuicTransportOptions) -> QuicTransportOptions,
+) -> NodeConfig {
+    let mut config = without_discovery(config);
+    let network_config = config
+        .network_config
+        .as_mut()
+        .expect("test config has networking");
+    let options = network_config.quic_options.take().unwrap_or_default();
+    network_config.quic_options = Some(quic_options(options));
+    config
+}
+
+async fn add(node: &Node, a: f64, b: f64) -> Result<f64> {
+    node.request(
+        "math/add",
+        Some(ArcValue::new_map(hmap! {
+            "a" => a,
+            "b" => b
+        })),
+    )
+    .await
+}
+
+/// Test that idle peers are disconnected and reconnected on demand
+///
+/// INTENTION: Keep-alives hold an unused connection open, so the node tracks
+/// messages itself. Once the peer has been idle past the timeout its
+/// connection is closed, and the next request opens a new one.
+#[tokio::test]
+async fn test_idle_peer_reconnects_on_next_message() -> Result<()> {
+    let configs = create_networked_node_test_config(2)?;
+    let node1_config = configure(configs[0].clone(), |options| options);
+    let node1_port = node1_config
+        .network_config
+        .as_ref()
+        .unwrap()
+        .transport_options
+        .bind_address
+        .port();
+    let mut node1 = Node::new(node1_config).await?;
+    node1.add_service(MathService::new("math", "math")).await?;
+    node1.start().await?;
+    let node1_peer_id = node1.get_local_node_info().await?.peer_id;
+    let node1_addr = SocketAddr::from((Ipv4Addr::LOCALHOST, node1_port));
+
+    let node2_config = configure(configs[1].clone(), |options| {
+        options.with_idle_disconnect_timeout(Duration::from_millis(300))
+    })
+    .with_initial_peers(vec![(node1_addr, node1_peer_id)]);
+    let mut node2 = Node::new(node2_config).await?;
+    node2.start().await?;
+
+    assert_eq!(add(&node2, 2.0, 3.0).await?, 5.0);
+    let stats = node2.transport_stats().await.unwrap();
+    assert_eq!(stats.current_connections, 1);
+
+    tokio::time::sleep(Duration::from_millis(1000)).await;
+    let idle_stats = node2.transport_stats().await.unwrap();
+    assert_eq!(idle_stats.current_connections, 0);
+
+    assert_eq!(add(&node2, 4.0, 5.0).await?, 9.0);
+    let stats = node2.transport_stats().await.unwrap();
+    assert_eq!(stats.current_connections, 1);
+    assert!(stats.total_connections_established > idle_stats.total_connections_established);
+
+    node2.stop().await?;
+    node1.stop().await?;
+    Ok(())
+}
+
+/// Test that a peer is not idle while a request to it is in flight
+///
+/// INTENTION: A slow action exchanges no message until it answers; its
+/// connection must stay open for the response to arrive.
+#[tokio::test]
+async fn test_peer_with_request_in_flight_is_not_idle() -> Result<()> {
+    let configs = create_networked_node_test_config(2)?;
+    let node1_config = configure(configs[0].clone(), |options| options);
+    let node1_port = node1_config
+        .network_config
+        .as_ref()
+        .unwrap()
+        .transport_options
+        .bind_address
+        .port();
+    let mut node1 = Node::new(node1_config).await?;
+    let observed = Arc::new(AtomicBool::new(false));
+    node1
+        .add_service(CancellableService::new("slow", "slow", observed))
+        .await?;
+    node1.start().await?;
+    let node1_peer_id = node1.get_local_node_info().await?.peer_id;
+    let node1_addr = SocketAddr::from((Ipv4Addr::LOCALHOST, node1_port));
+
+    let node2_config = configure(configs[1].clone(), |options| {
+        options.with_idle_disconnect_timeout(Duration::from_millis(300))
+    })
+    .with_initial_peers(vec![(node1_addr, node1_peer_id)]);
+    let mut node2 = Node::new(node2_config).await?;
+    node2.start().await?;
+    let connected = node2.transport_stats().await.unwrap();
+
+    let answered: bool = node2.request("slow/slow", Option::<ArcValue>::None).await?;
+    assert!(answered);
+    let stats = node2.transport_stats().await.unwrap();
+    assert_eq!(stats.current_connections, 1);
+    assert_eq!(
+        stats.total_connections_established,
+        connected.total_connections_established
+    );
+
+    node2.stop().await?;
+    node1.stop().await?;
+    Ok(())
+}
@@ -0,0 +1,98 @@
+// mDNS Discovery Tests
+//
+// Tests for the mDNS / DNS-SD discovery implementation
+
+use anyhow::Result;
+use runar_common::logging::{Component, Logger};
+use runar_node::network::discovery::multicast_discovery::PeerInfo;
+use runar_node::network::discovery::{
+    DiscoveryOptions, MdnsDiscovery, NodeDiscovery, NodeInfo, DEFAULT_MDNS_SERVICE_TYPE,
+};
+use runar_node::network::transport::PeerId;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::time::timeout;
+
+fn test_node_info(network_id: &str, node_id: &str, port: u16) -> NodeInfo {
+    NodeInfo {
+        peer_id: PeerId::new(node_id.to_string()),
+        network_ids: vec![network_id.to_string()],
+        addresses: vec![format!("0.0.0.0:{port}")],
+        services: Vec::new(),
+        version: 0,
+        subscriptions: Vec::new(),
+    }
+}
+
+async fn create_test_discovery(node_info: NodeInfo) -> Result<MdnsDiscovery> {
+    let logger = Logger::new_root(Component::NetworkDiscovery, &node_info.peer_id.public_key);
+    let discovery = MdnsDiscovery::new(node_info, DEFAULT_MDNS_SERVICE_TYPE, logger).await?;
+    discovery.init(DiscoveryOptions::default()).await?;
+    Ok(discovery)
+}
+
+/// Forward every discovered peer to a channel
+async fn listen(discovery: &MdnsDiscovery) -> Result<mpsc::UnboundedReceiver<PeerInfo>> {
+    let (tx, rx) = mpsc::unbounded_channel();
+    discovery
+        .set_discovery_listener(Arc::new(move |peer_info: PeerInfo| {
+            let _ = tx.send(peer_info);
+            Box::pin(async {})
+        }))
+        .await?;
+    Ok(rx)
+}
+
+/// Test that nodes in the same network find each other over mDNS
+///
+/// INTENTION: The TXT records must yield the same PeerInfo MulticastDiscovery
+/// reports: the node ID as public key and addresses on the QUIC port. Nodes
+/// announcing another network are ignored.
+#[tokio::test]
+async fn test_mdns_announce_and_discover() -> Result<()> {
+    let network_id = format!("mdns-test-{}", std::process::id());
+    let node1_id = format!("mdns-node-1-{}", std::process::id());
+    let node2_id = format!("mdns-node-2-{}", std::process::id());
+    let outsider_id = format!("mdns-outsider-{}", std::process::id());
+
+    let discovery1 = create_test_discovery(test_node_info(&network_id, &node1_id, 45001)).await?;
+    let discovery2 = create_test_discovery(test_node_info(&network_id, &node2_id, 45002)).await?;
+    let outsider =
+        create_test_discovery(test_node_info("other-network", &outsider_id, 45003)).await?;
+    let mut found_by_1 = listen(&discovery1).await?;
+    let mut found_by_2 = listen(&discovery2).await?;
+
+    outsider.start_announcing().await?;
+    discovery1.start_announcing().await?;
+    discovery2.start_announcing().await?;
+
+    let peer = timeout(Duration::from_secs(10), async {
+        loop {
+            let peer = found_by_1.recv().await.expect("listener dropped");
+            assert_ne!(peer.public_key, outsider_id, "other network was reported");
+            if peer.public_key == node2_id {
+                return peer;
+            }
+        }
+    })
+    .await?;
+    assert!(!peer.addresses.is_empty());
+    assert!(peer.addresses.iter().all(|a| a.ends_with(":45002")));
+
+    let peer = timeout(Duration::from_secs(10), async {
+        loop {
+            let peer = found_by_2.recv().await.expect("listener dropped");
+            if peer.public_key == node1_id {
+                return peer;
+            }
+        }
+    })
+    .await?;
+    assert!(peer.addresses.iter().all(|a| a.ends_with(":45001")));
+
+    discovery1.shutdown().await?;
+    discovery2.shutdown().await?;
+    outsider.shutdown().await?;
+    Ok(())
+}
@@ -0,0 +1,214 @@
+#![cfg(unix)]
+// Tests for NetworkMessage expiry
+//
+// Events published with a TTL carry an expiry time; the receiving transport
+// drops copies that arrive after it has passed. Events are only sent to the
+// peers subscribed to their topic.
+
+use anyhow::Result;
+use runar_common::types::ArcValue;
+use runar_node::network::transport::{
+    ErrorCode, NetworkError, NetworkMessage, NetworkMessagePayloadItem, PeerId,
+};
+use runar_node::network::QuicTransportOptions;
+use runar_node::node::{Node, NodeConfig};
+use runar_node::services::EventContext;
+use runar_node::{NodeDelegate, PublishOptions};
+use runar_test_utils::{create_networked_node_test_config, without_discovery};
+
+use std::future::Future;
+use std::net::{Ipv4Addr, SocketAddr};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tokio::time::sleep;
+
+use crate::network::{socket_path, with_local_socket};
+
+fn test_message(expires_at: Option<SystemTime>) -> NetworkMessage {
+    NetworkMessage {
+        source: PeerId::new("source".to_string()),
+        destination: PeerId::new("destination".to_string()),
+        message_type: "Event".to_string(),
+        payloads: vec![NetworkMessagePayloadItem::new(
+            "events/ping".to_string(),
+            vec![1, 2, 3],
+            String::new(),
+        )],
+        signature: None,
+        hop_count: 0,
+        visited_peers: Vec::new(),
+        auth_tag: None,
+        expires_at,
+    }
+}
+
+fn ttl_options(ttl: Duration) -> PublishOptions {
+    PublishOptions {
+        broadcast: true,
+        ttl: Some(ttl),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_message_expiry_check() {
+    assert!(test_message(None).check_expiry().is_ok());
+
+    let future = SystemTime::now() + Duration::from_secs(60);
+    assert!(test_message(Some(future)).check_expiry().is_ok());
+
+    let past = SystemTime::now() - Duration::from_secs(1);
+    let message = test_message(Some(past));
+    assert!(message.is_expired());
+    match message.check_expiry() {
+        Err(NetworkError::MessageError(ErrorCode::MessageExpired, reason)) => {
+            assert_eq!(reason, "message expired")
+        }
+        other => panic!("expected an expiry error, got {other:?}"),
+    }
+    assert_eq!(ErrorCode::from_u16(2007), Some(ErrorCode::MessageExpired));
+}
+
+#[test]
+fn test_signing_digest_covers_expiry() {
+    let expires_at = SystemTime::now() + Duration::from_secs(60);
+    let message = test_message(Some(expires_at));
+    let extended = test_message(Some(expires_at + Duration::from_secs(1)));
+
+    assert_ne!(message.signing_digest(), extended.signing_digest());
+    assert_ne!(
+        message.signing_digest(),
+        test_message(None).signing_digest()
+    );
+}
+
+/// Test that expired events are dropped by the receiving node
+///
+/// INTENTION: An event whose TTL has run out by the time it reaches a peer
+/// must not be delivered to the peer's subscribers.
+#[tokio::test]
+async fn test_expired_events_are_dropped() -> Result<()> {
+    let configs = create_networked_node_test_config(2)?;
+    let node1_socket = socket_path("ttl1");
+    let node2_socket = socket_path("ttl2");
+
+    let mut node1 = Node::new(with_local_socket(configs[0].clone(), &node1_socket)).await?;
+    // Subscribed before the handshake, so node2 learns about it from node1's node info
+    let received = Arc::new(AtomicUsize::new(0));
+    let counter = received.clone();
+    node1
+        .subscribe(
+            "events/ping".to_string(),
+            Box::new(move |_ctx: Arc<EventContext>, _data: Option<ArcValue>| {
+                counter.fetch_add(1, Ordering::SeqCst);
+                Box::pin(async { Ok(()) }) as Pin<Box<dyn Future<Output = Result<()>> + Send>>
+            }),
+        )
+        .await?;
+    node1.start().await?;
+    let mut node2 = Node::new(with_local_socket(configs[1].clone(), &node2_socket)).await?;
+    node2.start().await?;
+    node2.add_local_peer(&node1_socket).await?;
+    // node1 learns about node2 in the background after the handshake
+    sleep(Duration::from_secs(1)).await;
+
+    let data = ArcValue::new_primitive("ping".to_string());
+    node2
+        .publish_with_options(
+            "events/ping",
+            Some(data.clone()),
+            ttl_options(Duration::ZERO),
+        )
+        .await?;
+    sleep(Duration::from_millis(500)).await;
+    assert_eq!(received.load(Ordering::SeqCst), 0);
+
+    node2
+        .publish_with_options(
+            "events/ping",
+            Some(data),
+            ttl_options(Duration::from_secs(60)),
+        )
+        .await?;
+    sleep(Duration::from_millis(500)).await;
+    assert_eq!(received.load(Ordering::SeqCst), 1);
+
+    node2.stop().await?;
+    node1.stop().await?;
+    Ok(())
+}
+
+/// Connect over QUIC only, sending node info updates after `debounce_ms`
+fn with_update_debounce(config: NodeConfig, debounce_ms: u64) -> NodeConfig {
+    let mut config = without_discovery(config);
+    let network_config = config
+        .network_config
+        .as_mut()
+        .expect("test config has networking");
+    let options: QuicTransportOptions = network_config.quic_options.take().unwrap_or_default();
+    network_config.quic_options = Some(options.with_service_update_debounce_ms(debounce_ms));
+    config
+}
+
+/// Test that events are only sent to the peers subscribed to their topic
+///
+/// INTENTION: Peers advertise their subscriptions in their node info. An
+/// event no peer listens to is not sent at all, and a subscription made
+/// after the handshake reaches the publisher with the next node info update.
+#[tokio::test]
+async fn test_events_only_sent_to_subscribed_peers() -> Result<()> {
+    let configs = create_networked_node_test_config(2)?;
+    let node1_config = with_update_debounce(configs[0].clone(), 100);
+    let node1_port = node1_config
+        .network_config
+        .as_ref()
+        .unwrap()
+        .transport_options
+        .bind_address
+        .port();
+    let mut node1 = Node::new(node1_config).await?;
+    node1.start().await?;
+    let node1_peer_id = node1.get_local_node_info().await?.peer_id;
+    let node1_addr = SocketAddr::from((Ipv4Addr::LOCALHOST, node1_port));
+
+    let mut node2 = Node::new(
+        with_update_debounce(configs[1].clone(), 100)
+            .with_initial_peers(vec![(node1_addr, node1_peer_id)]),
+    )
+    .await?;
+    node2.start().await?;
+    sleep(Duration::from_millis(500)).await;
+
+    let data = ArcValue::new_primitive("ping".to_string());
+    let sent = node2.transport_stats().await.unwrap().total_messages_sent;
+    node2
+        .publish("events/ping".to_string(), Some(data.clone()))
+        .await?;
+    assert_eq!(
+        node2.transport_stats().await.unwrap().total_messages_sent,
+        sent
+    );
+
+    let received = Arc::new(AtomicUsize::new(0));
+    let counter = received.clone();
+    node1
+        .subscribe(
+            "events/ping".to_string(),
+            Box::new(move |_ctx: Arc<EventContext>, _data: Option<ArcValue>| {
+                counter.fetch_add(1, Ordering::SeqCst);
+                Box::pin(async { Ok(()) }) as Pin<Box<dyn Future<Output = Result<()>> + Send>>
+            }),
+        )
+        .await?;
+    sleep(Duration::from_millis(500)).await;
+
+    node2.publish("events/ping".to_string(), Some(data)).await?;
+    sleep(Duration::from_millis(500)).await;
+    assert_eq!(received.load(Ordering::SeqCst), 1);
+
+    node2.stop().await?;
+    node1.stop().await?;
+    Ok(())
+}
@@ -1,7 +1,117 @@
 // Network tests
+//
+// Helpers shared by several network test modules live here; the test modules
+// import them from `crate::network`.
 
+use anyhow::Result;
+use runar_node::network::{NodeCapabilities, PeerId};
+use runar_node::node::{Node, NodeConfig};
+use runar_test_utils::{create_networked_node_test_config, without_discovery};
+
+use std::net::{Ipv4Addr, SocketAddr};
+#[cfg(unix)]
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::time::sleep;
+
+use crate::fixtures::math_service::MathService;
+
+pub mod access_policy_test;
 pub mod binary_serialization_test;
+pub mod capabilities_test;
+pub mod chunking_test;
+pub mod compact_framing_test;
+pub mod connection_callback_test;
+pub mod connection_migration_test;
+pub mod connection_pool_test;
+pub mod error_propagation_test;
+pub mod event_dedup_test;
+pub mod event_ordering_test;
+pub mod health_gossip_test;
+pub mod idle_disconnect_test;
+pub mod mdns_discovery_test;
+pub mod message_ttl_test;
 pub mod multicast_discovery_test;
+pub mod network_error_test;
+pub mod network_partition_test;
+pub mod peer_filter_test;
+pub mod peer_registry_test;
+pub mod proxy_test;
 pub mod quic_transport_test;
+pub mod request_all_test;
+pub mod request_auth_test;
+pub mod serialization_format_test;
+pub mod service_update_test;
+pub mod service_visibility_test;
+pub mod static_peer_test;
+pub mod stream_pool_test;
+pub mod unix_socket_test;
 
 pub mod remote_action_test;
+
+/// Start two nodes with a math service each, the second connecting to the first
+///
+/// Discovery is disabled on both nodes; `configure1` and `configure2` adjust
+/// the config of the first and second node before it is created.
+pub async fn start_pair(
+    configure1: impl FnOnce(NodeConfig) -> NodeConfig,
+    configure2: impl FnOnce(NodeConfig) -> NodeConfig,
+) -> Result<(Node, PeerId, Node, PeerId)> {
+    let configs = create_networked_node_test_config(2)?;
+    let node1_config = configure1(without_discovery(configs[0].clone()));
+    let node1_port = node1_config
+        .network_config
+        .as_ref()
+        .expect("test config has networking")
+        .transport_options
+        .bind_address
+        .port();
+    let mut node1 = Node::new(node1_config).await?;
+    node1
+        .add_service(MathService::new("math1", "math1"))
+        .await?;
+    node1.start().await?;
+    let node1_peer_id = node1.get_local_node_info().await?.peer_id;
+
+    let node2_config =
+        configure2(without_discovery(configs[1].clone())).with_initial_peers(vec![(
+            SocketAddr::from((Ipv4Addr::LOCALHOST, node1_port)),
+            node1_peer_id.clone(),
+        )]);
+    let mut node2 = Node::new(node2_config).await?;
+    node2
+        .add_service(MathService::new("math2", "math2"))
+        .await?;
+    node2.start().await?;
+    let node2_peer_id = node2.get_local_node_info().await?.peer_id;
+    Ok((node1, node1_peer_id, node2, node2_peer_id))
+}
+
+/// Wait until `node` knows the capabilities negotiated with `peer`
+pub async fn negotiated_capabilities(node: &Node, peer: &PeerId) -> Option<NodeCapabilities> {
+    for _ in 0..50 {
+        let entry = node.peer_registry().find_peer(peer.public_key.clone());
+        if let Some(capabilities) = entry.and_then(|entry| entry.capabilities) {
+            return Some(capabilities);
+        }
+        sleep(Duration::from_millis(100)).await;
+    }
+    None
+}
+
+/// Listen on `socket_path` next to QUIC, with discovery disabled
+#[cfg(unix)]
+pub fn with_local_socket(config: NodeConfig, socket_path: &Path) -> NodeConfig {
+    let mut config = without_discovery(config);
+    let network_config = config
+        .network_config
+        .take()
+        .expect("test config has networking");
+    config.with_network_config(network_config.with_local_socket_path(socket_path))
+}
+
+/// A socket path in the temp directory, unique to this test process
+#[cfg(unix)]
+pub fn socket_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("runar-{}-{name}.sock", std::process::id()))
+}
@@ -7,29 +7,19 @@ use runar_common::logging::{Component, Logger};
 use runar_common::types::{ActionMetadata, EventMetadata, ServiceMetadata};
 use runar_node::network::discovery::DEFAULT_MULTICAST_ADDR;
 use runar_node::network::discovery::{
-    DiscoveryOptions, MulticastDiscovery, NodeDiscovery, NodeInfo,
+    DiscoveryOptions, MulticastDiscovery, NodeDiscovery, NodeInfo, ServiceAdvertisement,
 };
 use runar_node::network::transport::PeerId;
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::mpsc;
 
-use runar_node::network::discovery::multicast_discovery::PeerInfo;
+use runar_node::network::discovery::multicast_discovery::{PeerInfo, MAX_ANNOUNCEMENT_SIZE};
 use std::time::SystemTime;
 use tokio::sync::oneshot;
 
-async fn create_test_discovery(network_id: &str, node_id: &str) -> Result<MulticastDiscovery> {
-    let options = DiscoveryOptions {
-        multicast_group: format!("{DEFAULT_MULTICAST_ADDR}:45678"),
-        announce_interval: Duration::from_secs(1), // Use shorter interval for tests
-        ..DiscoveryOptions::default()
-    };
-
-    // Create a logger for testing
-    let logger = Logger::new_root(Component::NetworkDiscovery, node_id);
-
-    // Create a test node info
-    let node_info = NodeInfo {
+fn create_test_node_info(network_id: &str, node_id: &str) -> NodeInfo {
+    NodeInfo {
         peer_id: PeerId::new(node_id.to_string()),
         network_ids: vec![network_id.to_string()],
         addresses: vec!["127.0.0.1:8000".to_string()],
@@ -49,6 +39,7 @@ async fn create_test_discovery(network_id: &str, node_id: &str) -> Result<Multic
                     .unwrap()
                     .as_secs(),
             ),
+            visibility: Default::default(),
             actions: vec![ActionMetadata {
                 name: "request".to_string(),
                 description: "Test request".to_string(),
@@ -62,8 +53,23 @@ async fn create_test_discovery(network_id: &str, node_id: &str) -> Result<Multic
             }],
         }],
         version: 0,
+        subscriptions: Vec::new(),
+    }
+}
+
+async fn create_test_discovery(network_id: &str, node_id: &str) -> Result<MulticastDiscovery> {
+    let options = DiscoveryOptions {
+        multicast_group: format!("{DEFAULT_MULTICAST_ADDR}:45678"),
+        announce_interval: Duration::from_secs(1), // Use shorter interval for tests
+        ..DiscoveryOptions::default()
     };
 
+    // Create a logger for testing
+    let logger = Logger::new_root(Component::NetworkDiscovery, node_id);
+
+    // Create a test node info
+    let node_info = create_test_node_info(network_id, node_id);
+
     // Create the discovery instance with proper parameters
     let discovery = MulticastDiscovery::new(node_info, options, logger).await?;
 
@@ -184,3 +190,65 @@ async fn test_multicast_announce_and_discover() -> Result<()> {
 
     Ok(())
 }
+
+/// Test that announcements advertise the node's services
+///
+/// INTENTION: The announcement built from the local node info lists the path
+/// and version of each service, so receivers can tell which peers host a
+/// service they need before connecting, and the list survives the wire format.
+#[test]
+fn test_announcement_advertises_services() -> Result<()> {
+    let node_info = create_test_node_info("test-network", "test-node-1");
+    let announcement = PeerInfo::from_node_info(&node_info);
+    assert_eq!(announcement.public_key, "test-node-1");
+    assert_eq!(announcement.addresses, node_info.addresses);
+    assert_eq!(
+        announcement.services,
+        vec![ServiceAdvertisement {
+            path: "service".to_string(),
+            version: "1.0.0".to_string(),
+        }]
+    );
+    assert!(announcement.advertises_service("service"));
+    assert!(!announcement.advertises_service("math"));
+
+    let decoded: PeerInfo = bincode::deserialize(&bincode::serialize(&announcement)?)?;
+    assert_eq!(decoded, announcement);
+
+    // Peers known only by address advertise nothing
+    assert!(PeerInfo::new("peer".to_string(), Vec::new())
+        .services
+        .is_empty());
+    Ok(())
+}
+
+/// Test that announcements of nodes with many services stay small
+///
+/// INTENTION: An announcement listing every service of a large node would not
+/// fit a datagram, so services past `MAX_ANNOUNCEMENT_SIZE` are left out and
+/// the list is marked incomplete; receivers then treat any service as
+/// possibly hosted and learn the full list from the node info after connecting.
+#[test]
+fn test_announcement_size_is_capped() -> Result<()> {
+    let mut node_info = create_test_node_info("test-network", "test-node-1");
+    let template = node_info.services[0].clone();
+    node_info.services = (0..200)
+        .map(|index| ServiceMetadata {
+            service_path: format!("service-with-a-rather-long-path-{index}"),
+            ..template.clone()
+        })
+        .collect();
+
+    let announcement = PeerInfo::from_node_info(&node_info);
+    assert!(!announcement.services_complete);
+    assert!(!announcement.services.is_empty());
+    assert!(announcement.services.len() < node_info.services.len());
+    assert!(announcement.encoded_size() <= MAX_ANNOUNCEMENT_SIZE);
+    assert!(announcement.advertises_service("service-with-a-rather-long-path-199"));
+
+    // A node with few services is advertised in full
+    let small = PeerInfo::from_node_info(&create_test_node_info("test-network", "test-node-2"));
+    assert!(small.services_complete);
+    assert!(!small.advertises_service("math"));
+    Ok(())
+}
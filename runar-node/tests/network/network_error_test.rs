@@ -0,0 +1,34 @@
+// Tests for NetworkError codes
+
+use runar_node::network::transport::{ErrorCode, NetworkError};
+
+/// Test that codes round-trip and classify errors
+///
+/// INTENTION: Verify the numeric codes are stable, that relayed codes are
+/// rebuilt into the variant of their category, and the retry classification.
+#[test]
+fn test_network_error_codes() {
+    assert_eq!(ErrorCode::PeerUnreachable.as_u16(), 1001);
+    assert_eq!(ErrorCode::HandshakeFailed.as_u16(), 1002);
+    assert_eq!(ErrorCode::from_u16(4002), Some(ErrorCode::CircuitOpen));
+    assert_eq!(ErrorCode::from_u16(9999), None);
+
+    let error = NetworkError::from_code(1001, "peer gone");
+    assert!(matches!(
+        error,
+        NetworkError::ConnectionError(ErrorCode::PeerUnreachable, _)
+    ));
+    assert_eq!(error.message(), "peer gone");
+    assert!(error.is_retryable());
+    assert!(!error.is_fatal());
+
+    let error = NetworkError::from_code(5001, "no key");
+    assert!(matches!(error, NetworkError::ConfigurationError(..)));
+    assert!(error.is_fatal());
+    assert!(!error.is_retryable());
+
+    // Unknown codes from newer peers are kept as generic remote errors
+    let error = NetworkError::from_code(9999, "unknown");
+    assert_eq!(error.code(), ErrorCode::RemoteError);
+    assert_eq!(error.to_string(), "Message error [2005]: unknown");
+}
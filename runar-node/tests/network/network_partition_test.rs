@@ -0,0 +1,223 @@
+// Tests for simulating network partitions
+//
+// These tests verify that the in-memory transports of a `NetworkPartition`
+// drop messages between partitioned peers, keep delivering the others, and
+// deliver again once the partition heals, both on their own and under whole
+// nodes.
+
+use anyhow::Result;
+use runar_common::hmap;
+use runar_common::types::ArcValue;
+use runar_node::network::discovery::multicast_discovery::PeerInfo;
+use runar_node::network::transport::{NetworkMessage, NetworkTransport, PeerId};
+use runar_node::network::NodeInfo;
+use runar_node::node::{Node, NodeConfig};
+use runar_node::testing::{MockNetworkTransport, NetworkPartition};
+use runar_test_utils::{create_networked_node_test_config, without_discovery};
+use std::net::{Ipv4Addr, SocketAddr};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::fixtures::math_service::MathService;
+
+type Inbox = Arc<Mutex<Vec<PeerId>>>;
+
+fn node_info(name: &str) -> NodeInfo {
+    NodeInfo {
+        peer_id: PeerId::new(name.to_string()),
+        network_ids: vec!["test".to_string()],
+        addresses: Vec::new(),
+        services: Vec::new(),
+        version: 0,
+        subscriptions: Vec::new(),
+    }
+}
+
+/// Attach a started transport for `name`, recording the senders of the
+/// messages it receives
+async fn attach(network: &NetworkPartition, name: &str) -> (MockNetworkTransport, Inbox) {
+    let inbox: Inbox = Arc::new(Mutex::new(Vec::new()));
+    let received = inbox.clone();
+    let transport = network.add_transport(node_info(name), move |message: NetworkMessage| {
+        received.lock().unwrap().push(message.source);
+        Ok(())
+    });
+    transport.start().await.unwrap();
+    (transport, inbox)
+}
+
+fn message(from: &str, to: &str) -> NetworkMessage {
+    NetworkMessage {
+        source: PeerId::new(from.to_string()),
+        destination: PeerId::new(to.to_string()),
+        message_type: "Event".to_string(),
+        payloads: Vec::new(),
+        signature: None,
+        hop_count: 0,
+        visited_peers: Vec::new(),
+        auth_tag: None,
+        expires_at: None,
+    }
+}
+
+fn peer(name: &str) -> PeerInfo {
+    PeerInfo::new(name.to_string(), Vec::new())
+}
+
+/// Test partitioning two peers while a third one stays reachable
+///
+/// INTENTION: While a and b are partitioned their messages are dropped
+/// without an error and are not delivered after the partition heals, while
+/// both keep reaching c. Once healed, a and b reach each other again over
+/// the connection that stayed up.
+#[tokio::test]
+async fn test_partition_and_heal() {
+    let mut network = NetworkPartition::new();
+    let (a, _a_inbox) = attach(&network, "a").await;
+    let (b, b_inbox) = attach(&network, "b").await;
+    let (_c, c_inbox) = attach(&network, "c").await;
+    let mut a_peers = a.subscribe_to_peer_node_info().await;
+    a.connect_peer(peer("b")).await.unwrap();
+    a.connect_peer(peer("c")).await.unwrap();
+    b.connect_peer(peer("c")).await.unwrap();
+    assert_eq!(
+        a_peers.recv().await.unwrap().peer_id,
+        PeerId::new("b".to_string())
+    );
+
+    let (a_id, b_id) = (PeerId::new("a".to_string()), PeerId::new("b".to_string()));
+    network.partition(&a_id, &b_id);
+    assert!(network.is_partitioned(&b_id, &a_id));
+    a.send_message(message("a", "b")).await.unwrap();
+    a.send_message(message("a", "c")).await.unwrap();
+    b.send_message(message("b", "c")).await.unwrap();
+    assert!(b_inbox.lock().unwrap().is_empty());
+    assert_eq!(c_inbox.lock().unwrap().len(), 2);
+    assert!(a.is_connected(b_id.clone()).await);
+
+    network.heal(&a_id, &b_id);
+    a.send_message(message("a", "b")).await.unwrap();
+    assert_eq!(*b_inbox.lock().unwrap(), vec![a_id]);
+}
+
+/// Test splitting all peers into two groups
+///
+/// INTENTION: `partition_all` cuts every link between the two halves of the
+/// peers, keeps the links within each half, and `heal_all` restores them.
+#[tokio::test]
+async fn test_partition_all() {
+    let mut network = NetworkPartition::new();
+    let mut transports = Vec::new();
+    for name in ["a", "b", "c", "d"] {
+        transports.push(attach(&network, name).await);
+    }
+    let (a, b, c) = (&transports[0].0, &transports[1].0, &transports[2].0);
+    a.connect_peer(peer("b")).await.unwrap();
+    a.connect_peer(peer("c")).await.unwrap();
+
+    let (first, second) = network.partition_all();
+    assert_eq!(
+        first,
+        vec![PeerId::new("a".to_string()), PeerId::new("b".to_string())]
+    );
+    assert_eq!(
+        second,
+        vec![PeerId::new("c".to_string()), PeerId::new("d".to_string())]
+    );
+    // New connections across the partition fail
+    assert!(b.connect_peer(peer("d")).await.is_err());
+    assert!(c.connect_peer(peer("d")).await.is_ok());
+
+    a.send_message(message("a", "b")).await.unwrap();
+    a.send_message(message("a", "c")).await.unwrap();
+    assert_eq!(transports[1].1.lock().unwrap().len(), 1);
+    assert!(transports[2].1.lock().unwrap().is_empty());
+
+    network.heal_all();
+    a.send_message(message("a", "c")).await.unwrap();
+    assert_eq!(transports[2].1.lock().unwrap().len(), 1);
+}
+
+/// Run a node over `network`, without discovery
+fn partitioned_config(config: NodeConfig, network: &NetworkPartition) -> NodeConfig {
+    without_discovery(config)
+        .with_transport_factory(network.transport_factory())
+        .with_request_timeout(500)
+}
+
+/// Ask `node` to add two numbers with the math service at `service`
+async fn add(node: &Node, service: &str) -> Result<f64> {
+    node.request(
+        format!("{service}/add"),
+        Some(ArcValue::new_map(hmap! { "a" => 1.0, "b" => 2.0 })),
+    )
+    .await
+}
+
+/// Test partitioning two nodes of three and healing the partition
+///
+/// INTENTION: Nodes a, b and c run over the in-memory network, all connected
+/// to each other. While a and b are partitioned their requests to each other
+/// time out, while both keep reaching c; once healed they reach each other
+/// again without reconnecting.
+#[tokio::test]
+async fn test_nodes_partition_and_heal() -> Result<()> {
+    let mut network = NetworkPartition::new();
+    let mut nodes = Vec::new();
+    let mut ids = Vec::new();
+    for (index, config) in create_networked_node_test_config(3)?
+        .into_iter()
+        .enumerate()
+    {
+        let mut node = Node::new(partitioned_config(config, &network)).await?;
+        let service = format!("math{index}");
+        node.add_service(MathService::new(&service, &service))
+            .await?;
+        node.start().await?;
+        ids.push(node.get_local_node_info().await?.peer_id);
+        nodes.push(node);
+    }
+    let unused_addr = SocketAddr::from((Ipv4Addr::LOCALHOST, 1));
+    nodes[1]
+        .add_remote_peer(unused_addr, ids[0].clone())
+        .await?;
+    nodes[2]
+        .add_remote_peer(unused_addr, ids[0].clone())
+        .await?;
+    nodes[2]
+        .add_remote_peer(unused_addr, ids[1].clone())
+        .await?;
+    let (a_id, b_id, c_id) = (&ids[0], &ids[1], &ids[2]);
+
+    // Every node reaches the services of the others
+    for (index, node) in nodes.iter().enumerate() {
+        for other in (0..3).filter(|other| *other != index) {
+            let mut result = add(node, &format!("math{other}")).await;
+            for _ in 0..20 {
+                if result.is_ok() {
+                    break;
+                }
+                tokio::time::sleep(Duration::from_millis(100)).await;
+                result = add(node, &format!("math{other}")).await;
+            }
+            assert_eq!(result?, 3.0);
+        }
+    }
+
+    network.partition(a_id, b_id);
+    assert!(add(&nodes[0], "math1").await.is_err());
+    assert!(add(&nodes[1], "math0").await.is_err());
+    assert_eq!(add(&nodes[0], "math2").await?, 3.0);
+    assert_eq!(add(&nodes[1], "math2").await?, 3.0);
+    assert!(network.is_partitioned(b_id, a_id));
+    assert!(!network.is_partitioned(a_id, c_id));
+
+    network.heal(a_id, b_id);
+    assert_eq!(add(&nodes[0], "math1").await?, 3.0);
+    assert_eq!(add(&nodes[1], "math0").await?, 3.0);
+
+    for mut node in nodes.into_iter().rev() {
+        node.stop().await?;
+    }
+    Ok(())
+}
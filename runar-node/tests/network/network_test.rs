@@ -276,6 +276,11 @@ mod tests {
             destination: PeerId::new("node-2".to_string()),
             message_type: "Request".to_string(),
             payloads: vec![(topic.clone(), params.clone(), correlation_id.clone())],
+            signature: None,
+            hop_count: 0,
+            visited_peers: Vec::new(),
+            auth_tag: None,
+            expires_at: None,
         };
         
         transport.send_message(message.clone()).await?;
@@ -369,6 +374,11 @@ mod tests {
             destination: PeerId::new("node-1".to_string()),
             message_type: "Request".to_string(),
             payloads: vec![(topic.clone(), params.clone(), correlation_id.clone())],
+            signature: None,
+            hop_count: 0,
+            visited_peers: Vec::new(),
+            auth_tag: None,
+            expires_at: None,
         };
         
         // Send the message using send_message
@@ -0,0 +1,169 @@
+// Tests for the peer whitelist and blacklist
+//
+// A node only connects with the peers its filter accepts, does not record the
+// others in its peer registry, and disconnects peers rejected after the lists
+// were updated at runtime.
+
+use anyhow::Result;
+use runar_common::hmap;
+use runar_common::types::ArcValue;
+use runar_node::network::discovery::multicast_discovery::PeerInfo;
+use runar_node::network::{PeerFilter, PeerId, PeerRegistry};
+use runar_node::node::Node;
+
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::time::sleep;
+
+use crate::network::start_pair;
+
+async fn add(node: &Node, path: &str) -> Result<f64> {
+    node.request(
+        path,
+        Some(ArcValue::new_map(hmap! {
+            "a" => 1.0,
+            "b" => 2.0
+        })),
+    )
+    .await
+}
+
+/// Poll `path` on `node` until it can be called, or give up
+async fn wait_for_add(node: &Node, path: &str) -> Result<f64> {
+    let deadline = Instant::now() + Duration::from_secs(5);
+    loop {
+        let result = add(node, path).await;
+        if result.is_ok() || Instant::now() >= deadline {
+            return result;
+        }
+        sleep(Duration::from_millis(50)).await;
+    }
+}
+
+/// Wait until `node` has recorded `peer`, or give up
+async fn wait_for_peer(node: &Node, peer: &PeerId) -> bool {
+    for _ in 0..50 {
+        if node
+            .peer_registry()
+            .find_peer(peer.public_key.clone())
+            .is_some()
+        {
+            return true;
+        }
+        sleep(Duration::from_millis(100)).await;
+    }
+    false
+}
+
+fn peer_info(peer_id: &PeerId) -> PeerInfo {
+    PeerInfo::new(peer_id.public_key.clone(), vec!["127.0.0.1:1".to_string()])
+}
+
+/// Test which peers the filter accepts
+///
+/// INTENTION: The blacklist wins over the whitelist, a missing whitelist
+/// accepts every peer, and rejections name the list that rejected the peer.
+#[test]
+fn test_peer_filter_lists() {
+    let allowed = PeerId::new("allowed".to_string());
+    let banned = PeerId::new("banned".to_string());
+    let unknown = PeerId::new("unknown".to_string());
+
+    let filter = PeerFilter::new(None, Some(HashSet::from([banned.clone()])));
+    assert!(filter.allows(&allowed));
+    assert!(filter.allows(&unknown));
+    let error = filter.check(&banned).unwrap_err();
+    assert!(error.to_string().contains("peer blacklisted"));
+
+    filter.set_whitelist(Some(HashSet::from([allowed.clone(), banned.clone()])));
+    assert!(filter.allows(&allowed));
+    assert!(!filter.allows(&banned));
+    let error = filter.check(&unknown).unwrap_err();
+    assert!(error.to_string().contains("peer not whitelisted"));
+
+    filter.set_blacklist(None);
+    assert!(filter.allows(&banned));
+}
+
+/// Test that the peer registry leaves out rejected peers
+///
+/// INTENTION: Neither discovered peers nor handshakes of blacklisted peers
+/// are recorded, so nothing reacts to them joining.
+#[test]
+fn test_peer_registry_skips_rejected_peers() -> Result<()> {
+    let allowed = PeerId::new("allowed".to_string());
+    let banned = PeerId::new("banned".to_string());
+    let filter = Arc::new(PeerFilter::new(None, Some(HashSet::from([banned.clone()]))));
+    let registry = PeerRegistry::new().with_peer_filter(filter.clone());
+
+    registry.add_peer(peer_info(&allowed))?;
+    registry.add_peer(peer_info(&banned))?;
+    assert!(registry.find_peer(allowed.public_key.clone()).is_some());
+    assert!(registry.find_peer(banned.public_key.clone()).is_none());
+
+    // Peers rejected later are kept until removed by the node
+    filter.set_blacklist(Some(HashSet::from([allowed.clone()])));
+    registry.add_peer(peer_info(&allowed))?;
+    assert!(registry.find_peer(allowed.public_key.clone()).is_some());
+    Ok(())
+}
+
+/// Test that a node does not connect to peers left out of its whitelist
+///
+/// INTENTION: node2 only whitelists another peer, so its connection to
+/// node1 is refused; neither node records the other.
+#[tokio::test]
+async fn test_whitelist_refuses_connection() -> Result<()> {
+    let other = PeerId::new("other".to_string());
+    let (mut node1, node1_peer_id, mut node2, node2_peer_id) = start_pair(
+        |config| config,
+        |config| {
+            config
+                .with_request_timeout(1000)
+                .with_peer_whitelist(HashSet::from([other]))
+        },
+    )
+    .await?;
+
+    sleep(Duration::from_millis(500)).await;
+    assert!(add(&node2, "math1/add").await.is_err());
+    assert!(node2
+        .peer_registry()
+        .find_peer(node1_peer_id.public_key.clone())
+        .is_none());
+    assert!(node1
+        .peer_registry()
+        .find_peer(node2_peer_id.public_key.clone())
+        .is_none());
+
+    node2.stop().await?;
+    node1.stop().await?;
+    Ok(())
+}
+
+/// Test that updating the whitelist disconnects the peers it now rejects
+///
+/// INTENTION: node1 first talks to node2, then an empty whitelist makes it
+/// drop node2 and the services it provided without restarting.
+#[tokio::test]
+async fn test_update_peer_whitelist_disconnects_peer() -> Result<()> {
+    let (mut node1, _node1_peer_id, mut node2, node2_peer_id) =
+        start_pair(|config| config, |config| config.with_request_timeout(1000)).await?;
+
+    assert_eq!(wait_for_add(&node2, "math1/add").await?, 3.0);
+    assert_eq!(wait_for_add(&node1, "math2/add").await?, 3.0);
+    assert!(wait_for_peer(&node1, &node2_peer_id).await);
+
+    node1.update_peer_whitelist(HashSet::new()).await?;
+    assert!(node1
+        .peer_registry()
+        .find_peer(node2_peer_id.public_key.clone())
+        .is_none());
+    sleep(Duration::from_millis(200)).await;
+    assert!(add(&node1, "math2/add").await.is_err());
+
+    node2.stop().await?;
+    node1.stop().await?;
+    Ok(())
+}
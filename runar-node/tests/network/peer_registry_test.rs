@@ -0,0 +1,172 @@
+// Tests for the PeerRegistry change notifications
+//
+// These tests verify that adding, updating and removing peers is broadcast
+// to subscribers as PeerEvents, and that peers can be looked up by the
+// services they host.
+
+use anyhow::Result;
+use runar_common::types::ServiceMetadata;
+use runar_node::network::discovery::multicast_discovery::PeerInfo;
+use runar_node::network::discovery::NodeInfo;
+use runar_node::network::transport::{
+    PeerEvent, PeerId, PeerRegistry, PeerRegistryOptions, PeerStatus,
+};
+use tokio::sync::broadcast::error::{RecvError, TryRecvError};
+
+fn node_info(public_key: &str, version: i64) -> NodeInfo {
+    NodeInfo {
+        peer_id: PeerId::new(public_key.to_string()),
+        network_ids: vec!["test_network".to_string()],
+        addresses: vec!["127.0.0.1:5000".to_string()],
+        services: Vec::new(),
+        version,
+        subscriptions: Vec::new(),
+    }
+}
+
+fn service(path: &str) -> ServiceMetadata {
+    ServiceMetadata {
+        network_id: "test_network".to_string(),
+        service_path: path.to_string(),
+        name: path.to_string(),
+        version: "1.0.0".to_string(),
+        description: String::new(),
+        actions: Vec::new(),
+        events: Vec::new(),
+        registration_time: 0,
+        last_start_time: None,
+        visibility: Default::default(),
+    }
+}
+
+fn node_info_with_services(public_key: &str, version: i64, paths: &[&str]) -> NodeInfo {
+    NodeInfo {
+        services: paths.iter().map(|path| service(path)).collect(),
+        ..node_info(public_key, version)
+    }
+}
+
+/// Public keys of the peers hosting `service_path`, sorted
+fn hosts(registry: &PeerRegistry, service_path: &str) -> Vec<String> {
+    let mut hosts: Vec<String> = registry
+        .find_peers_by_service(service_path)
+        .into_iter()
+        .map(|entry| entry.peer_info.public_key)
+        .collect();
+    hosts.sort();
+    hosts
+}
+
+#[test]
+fn test_peer_registry_broadcasts_changes() -> Result<()> {
+    let registry = PeerRegistry::new();
+    let mut events = registry.subscribe_to_changes();
+    let peer = PeerId::new("peer-a".to_string());
+
+    // Adding a peer announces it once; updating a known peer is silent
+    let info = PeerInfo::new("peer-a".to_string(), vec!["127.0.0.1:5000".to_string()]);
+    registry.add_peer(info.clone())?;
+    registry.add_peer(info)?;
+    match events.try_recv()? {
+        PeerEvent::Connected(entry) => {
+            assert_eq!(entry.peer_info.public_key, "peer-a");
+            assert_eq!(entry.status, PeerStatus::Discovered);
+        }
+        other => panic!("unexpected event: {other:?}"),
+    }
+    assert!(matches!(events.try_recv(), Err(TryRecvError::Empty)));
+
+    // Status updates report the old and new status
+    registry.update_peer_status(&peer, PeerStatus::Connecting)?;
+    match events.try_recv()? {
+        PeerEvent::StatusChanged {
+            peer: changed,
+            old,
+            new,
+        } => {
+            assert_eq!(changed, peer);
+            assert_eq!(old, PeerStatus::Discovered);
+            assert_eq!(new, PeerStatus::Connecting);
+        }
+        other => panic!("unexpected event: {other:?}"),
+    }
+
+    // A completed handshake marks the peer connected and carries its node info
+    registry.add_peer_node_info(node_info("peer-a", 3))?;
+    assert!(matches!(
+        events.try_recv()?,
+        PeerEvent::StatusChanged {
+            new: PeerStatus::Connected,
+            ..
+        }
+    ));
+    match events.try_recv()? {
+        PeerEvent::Connected(entry) => {
+            assert_eq!(entry.status, PeerStatus::Connected);
+            assert_eq!(entry.node_info.map(|info| info.version), Some(3));
+        }
+        other => panic!("unexpected event: {other:?}"),
+    }
+
+    // Older node info does not replace what is already known
+    registry.add_peer_node_info(node_info("peer-a", 2))?;
+    match events.try_recv()? {
+        PeerEvent::Connected(entry) => {
+            assert_eq!(entry.node_info.map(|info| info.version), Some(3));
+        }
+        other => panic!("unexpected event: {other:?}"),
+    }
+
+    registry.remove_peer(&peer)?;
+    match events.try_recv()? {
+        PeerEvent::Disconnected(removed) => assert_eq!(removed, peer),
+        other => panic!("unexpected event: {other:?}"),
+    }
+    assert!(registry.find_peer("peer-a".to_string()).is_none());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_peer_registry_channel_capacity() -> Result<()> {
+    let registry = PeerRegistry::with_options(PeerRegistryOptions {
+        event_channel_capacity: 2,
+        ..PeerRegistryOptions::default()
+    });
+    let mut events = registry.subscribe_to_changes();
+
+    for index in 0..4 {
+        registry.add_peer(PeerInfo::new(format!("peer-{index}"), Vec::new()))?;
+    }
+
+    // A subscriber that falls behind skips the oldest events
+    assert!(matches!(events.recv().await, Err(RecvError::Lagged(2))));
+    assert!(matches!(events.recv().await?, PeerEvent::Connected(_)));
+    assert!(matches!(events.recv().await?, PeerEvent::Connected(_)));
+    Ok(())
+}
+
+#[test]
+fn test_peer_registry_find_peers_by_service() -> Result<()> {
+    let registry = PeerRegistry::new();
+    registry.add_peer_node_info(node_info_with_services("peer-a", 1, &["math", "echo"]))?;
+    registry.add_peer_node_info(node_info_with_services("peer-b", 1, &["math"]))?;
+
+    assert_eq!(hosts(&registry, "math"), ["peer-a", "peer-b"]);
+    assert_eq!(hosts(&registry, "echo"), ["peer-a"]);
+    assert!(hosts(&registry, "unknown").is_empty());
+    let entry = registry.find_peer("peer-a".to_string()).unwrap();
+    assert_eq!(entry.services, ["echo", "math"]);
+
+    // Newer node info replaces the indexed services; older node info is ignored
+    registry.add_peer_node_info(node_info_with_services("peer-a", 2, &["echo", "storage"]))?;
+    registry.add_peer_node_info(node_info_with_services("peer-b", 0, &["storage"]))?;
+    assert_eq!(hosts(&registry, "math"), ["peer-b"]);
+    assert_eq!(hosts(&registry, "storage"), ["peer-a"]);
+
+    // Removed peers leave the index
+    registry.remove_peer(&PeerId::new("peer-b".to_string()))?;
+    assert!(hosts(&registry, "math").is_empty());
+    assert_eq!(hosts(&registry, "echo"), ["peer-a"]);
+    Ok(())
+}
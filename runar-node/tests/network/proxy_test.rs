@@ -0,0 +1,188 @@
+// Tests for tunneling QUIC through HTTP CONNECT and SOCKS5 proxies
+//
+// A node configured with a proxy reaches a peer that accepts tunneled
+// connections, with all of its traffic to that peer going through the proxy.
+
+use anyhow::Result;
+use runar_common::hmap;
+use runar_common::types::ArcValue;
+use runar_node::network::transport::{ProxyConfig, ProxyKind};
+use runar_node::network::QuicTransportOptions;
+use runar_node::node::{Node, NodeConfig};
+use runar_test_utils::{create_networked_node_test_config, without_discovery};
+
+use std::net::{Ipv4Addr, SocketAddr};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+use crate::fixtures::math_service::MathService;
+
+/// Remove the discovery providers and adjust the QUIC options
+fn configure(
+    config: NodeConfig,
+    quic_options: impl FnOnce(QuicTransportOptions) -> QuicTransportOptions,
+) -> NodeConfig {
+    let mut config = without_discovery(config);
+    let network_config = config
+        .network_config
+        .as_mut()
+        .expect("test config has networking");
+    let options = network_config.quic_options.take().unwrap_or_default();
+    network_config.quic_options = Some(quic_options(options));
+    config
+}
+
+/// Forward bytes between the client and the target until either side closes
+async fn relay(mut client: TcpStream, target: SocketAddr) {
+    if let Ok(mut upstream) = TcpStream::connect(target).await {
+        let _ = tokio::io::copy_bidirectional(&mut client, &mut upstream).await;
+    }
+}
+
+/// Minimal HTTP CONNECT proxy counting the tunnels it opens
+async fn start_http_proxy(tunnels: Arc<AtomicUsize>) -> Result<SocketAddr> {
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await?;
+    let addr = listener.local_addr()?;
+    tokio::spawn(async move {
+        while let Ok((mut client, _)) = listener.accept().await {
+            let tunnels = tunnels.clone();
+            tokio::spawn(async move {
+                let mut request = Vec::new();
+                while !request.ends_with(b"\r\n\r\n") {
+                    match client.read_u8().await {
+                        Ok(byte) => request.push(byte),
+                        Err(_) => return,
+                    }
+                }
+                let request = String::from_utf8_lossy(&request).to_string();
+                let Some(target) = request
+                    .strip_prefix("CONNECT ")
+                    .and_then(|rest| rest.split_whitespace().next())
+                    .and_then(|target| target.parse::<SocketAddr>().ok())
+                else {
+                    let _ = client.write_all(b"HTTP/1.1 400 Bad Request\r\n\r\n").await;
+                    return;
+                };
+                tunnels.fetch_add(1, Ordering::SeqCst);
+                if client
+                    .write_all(b"HTTP/1.1 200 Connection established\r\n\r\n")
+                    .await
+                    .is_ok()
+                {
+                    relay(client, target).await;
+                }
+            });
+        }
+    });
+    Ok(addr)
+}
+
+/// Minimal SOCKS5 proxy (no authentication, IPv4 CONNECT only) counting its tunnels
+async fn start_socks5_proxy(tunnels: Arc<AtomicUsize>) -> Result<SocketAddr> {
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await?;
+    let addr = listener.local_addr()?;
+    tokio::spawn(async move {
+        while let Ok((mut client, _)) = listener.accept().await {
+            let tunnels = tunnels.clone();
+            tokio::spawn(async move {
+                let result: std::io::Result<SocketAddr> = async {
+                    // Greeting: version, method count, methods
+                    let mut greeting = [0u8; 2];
+                    client.read_exact(&mut greeting).await?;
+                    let mut methods = vec![0u8; greeting[1] as usize];
+                    client.read_exact(&mut methods).await?;
+                    client.write_all(&[0x05, 0x00]).await?;
+
+                    // Request: version, command, reserved, address type, IPv4, port
+                    let mut request = [0u8; 10];
+                    client.read_exact(&mut request).await?;
+                    let ip = Ipv4Addr::new(request[4], request[5], request[6], request[7]);
+                    let port = u16::from_be_bytes([request[8], request[9]]);
+                    client
+                        .write_all(&[0x05, 0x00, 0x00, 0x01, 0, 0, 0, 0, 0, 0])
+                        .await?;
+                    Ok(SocketAddr::from((ip, port)))
+                }
+                .await;
+                if let Ok(target) = result {
+                    tunnels.fetch_add(1, Ordering::SeqCst);
+                    relay(client, target).await;
+                }
+            });
+        }
+    });
+    Ok(addr)
+}
+
+/// Call a service on a peer that is only reached through `proxy`
+async fn request_through_proxy(kind: ProxyKind, proxy_addr: SocketAddr) -> Result<()> {
+    let configs = create_networked_node_test_config(2)?;
+    let node1_config = configure(configs[0].clone(), |options| {
+        options.with_accept_tunneled_connections(true)
+    });
+    let node1_port = node1_config
+        .network_config
+        .as_ref()
+        .unwrap()
+        .transport_options
+        .bind_address
+        .port();
+    let mut node1 = Node::new(node1_config).await?;
+    node1
+        .add_service(MathService::new("proxied", "proxied"))
+        .await?;
+    node1.start().await?;
+    let node1_peer_id = node1.get_local_node_info().await?.peer_id;
+    let node1_addr = SocketAddr::from((Ipv4Addr::LOCALHOST, node1_port));
+
+    let node2_config = configure(configs[1].clone(), |options| {
+        options.with_proxy(ProxyConfig::new(kind, proxy_addr))
+    })
+    .with_initial_peers(vec![(node1_addr, node1_peer_id)]);
+    let mut node2 = Node::new(node2_config).await?;
+    node2.start().await?;
+
+    let response: f64 = node2
+        .request(
+            "proxied/add",
+            Some(ArcValue::new_map(hmap! {
+                "a" => 2.0,
+                "b" => 3.0
+            })),
+        )
+        .await?;
+    assert_eq!(response, 5.0);
+
+    node2.stop().await?;
+    node1.stop().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_quic_through_http_connect_proxy() -> Result<()> {
+    let tunnels = Arc::new(AtomicUsize::new(0));
+    let proxy_addr = start_http_proxy(tunnels.clone()).await?;
+    request_through_proxy(ProxyKind::HttpConnect, proxy_addr).await?;
+    assert!(tunnels.load(Ordering::SeqCst) >= 1);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_quic_through_socks5_proxy() -> Result<()> {
+    let tunnels = Arc::new(AtomicUsize::new(0));
+    let proxy_addr = start_socks5_proxy(tunnels.clone()).await?;
+    request_through_proxy(ProxyKind::Socks5, proxy_addr).await?;
+    assert!(tunnels.load(Ordering::SeqCst) >= 1);
+    Ok(())
+}
+
+#[test]
+fn test_proxy_credentials_are_redacted() {
+    let proxy = ProxyConfig::new(ProxyKind::HttpConnect, "127.0.0.1:3128".parse().unwrap())
+        .with_credentials("alice", "hunter2");
+    let debug = format!("{proxy:?}");
+    assert!(debug.contains("alice"));
+    assert!(!debug.contains("hunter2"));
+}
@@ -258,8 +258,10 @@ async fn test_quic_transport_complete_api_validation(
             }],
             registration_time: 1751181000,
             last_start_time: None,
+            visibility: Default::default(),
         }],
         version: 0,
+        subscriptions: Vec::new(),
     };
 
     let node2_info = NodeInfo {
@@ -293,8 +295,10 @@ async fn test_quic_transport_complete_api_validation(
             }],
             registration_time: 1751181000,
             last_start_time: None,
+            visibility: Default::default(),
         }],
         version: 0,
+        subscriptions: Vec::new(),
     };
 
     let transport1_options = QuicTransportOptions::new()
@@ -402,7 +406,18 @@ async fn test_quic_transport_complete_api_validation(
             path: "".to_string(),
             value_bytes: "Test announcement data".as_bytes().to_vec(),
             correlation_id: "announcement_test".to_string(),
+            error_code: None,
+            sequence: None,
+            dedup_id: None,
+            format: None,
+            chunk_index: None,
+            total_chunks: None,
         }],
+        signature: None,
+        hop_count: 0,
+        visited_peers: Vec::new(),
+        auth_tag: None,
+        expires_at: None,
     };
 
     sender_transport.send_message(announcement_message).await?;
@@ -464,7 +479,18 @@ async fn test_quic_transport_complete_api_validation(
             path: "test:math1/add".to_string(),
             value_bytes: bincode::serialize(&serde_json::json!({"a": 5, "b": 3})).unwrap(),
             correlation_id: "math-request-1".to_string(),
+            error_code: None,
+            sequence: None,
+            dedup_id: None,
+            format: None,
+            chunk_index: None,
+            total_chunks: None,
         }],
+        signature: None,
+        hop_count: 0,
+        visited_peers: Vec::new(),
+        auth_tag: None,
+        expires_at: None,
     };
 
     request_sender.send_message(request_message).await?;
@@ -490,7 +516,18 @@ async fn test_quic_transport_complete_api_validation(
             path: "test:math1/add".to_string(),
             value_bytes: bincode::serialize(&serde_json::json!({"result": 8})).unwrap(),
             correlation_id: "math-request-1".to_string(),
+            error_code: None,
+            sequence: None,
+            dedup_id: None,
+            format: None,
+            chunk_index: None,
+            total_chunks: None,
         }],
+        signature: None,
+        hop_count: 0,
+        visited_peers: Vec::new(),
+        auth_tag: None,
+        expires_at: None,
     };
 
     request_receiver.send_message(response_message).await?;
@@ -523,7 +560,18 @@ async fn test_quic_transport_complete_api_validation(
             value_bytes: bincode::serialize(&serde_json::json!({"operation": "add", "result": 8}))
                 .unwrap(),
             correlation_id: format!("event-{}", uuid::Uuid::new_v4()),
+            error_code: None,
+            sequence: None,
+            dedup_id: None,
+            format: None,
+            chunk_index: None,
+            total_chunks: None,
         }],
+        signature: None,
+        hop_count: 0,
+        visited_peers: Vec::new(),
+        auth_tag: None,
+        expires_at: None,
     };
 
     sender_transport.send_message(event_message).await?;
@@ -538,6 +586,53 @@ async fn test_quic_transport_complete_api_validation(
 
     logger.info("✅ [QUIC Transport API] Unidirectional event broadcasting working correctly");
 
+    // ==================================================
+    // STEP 12b: Test Source Authentication (Message Signatures)
+    // ==================================================
+
+    logger.info("🔏 Testing message source authentication...");
+
+    // A message claiming to come from another peer is signed with the sender's
+    // key, so the receiver must reject it
+    let forged_message = NetworkMessage {
+        source: receiver_info.peer_id.clone(),
+        destination: receiver_info.peer_id.clone(),
+        message_type: "FORGED".to_string(),
+        payloads: vec![NetworkMessagePayloadItem {
+            path: "test:math1/calculated".to_string(),
+            value_bytes: "forged".as_bytes().to_vec(),
+            correlation_id: "forged-1".to_string(),
+            error_code: None,
+            sequence: None,
+            dedup_id: None,
+            format: None,
+            chunk_index: None,
+            total_chunks: None,
+        }],
+        signature: None,
+        hop_count: 0,
+        visited_peers: Vec::new(),
+        auth_tag: None,
+        expires_at: None,
+    };
+
+    sender_transport.send_message(forged_message).await?;
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+
+    let receiver_msgs = receiver_messages.lock().await;
+    assert!(
+        !receiver_msgs.iter().any(|msg| msg.message_type == "FORGED"),
+        "Receiver should reject messages with a forged source"
+    );
+    assert!(
+        receiver_msgs.iter().all(|msg| msg.signature.is_some()),
+        "All delivered messages should carry a signature"
+    );
+    drop(receiver_msgs);
+
+    logger.info("✅ [QUIC Transport API] Message source authentication working correctly");
+
     // ==================================================
     // STEP 13: Wait and Analyze Results
     // ==================================================
@@ -637,6 +732,32 @@ async fn test_quic_transport_complete_api_validation(
         logger.info("  ✅ Event messages processed successfully");
     }
 
+    // Validate transport statistics
+    let stats1 = transport1.transport_stats().await;
+    let stats2 = transport2.transport_stats().await;
+    logger.info(format!("  📊 Node A transport stats: {stats1:?}"));
+    logger.info(format!("  📊 Node B transport stats: {stats2:?}"));
+    assert!(
+        stats1.total_connections_established + stats2.total_connections_established > 0,
+        "Transport stats should count established connections"
+    );
+    for stats in [&stats1, &stats2] {
+        assert!(
+            stats.total_messages_sent > 0,
+            "Messages sent should be counted"
+        );
+        assert!(
+            stats.total_messages_received > 0,
+            "Messages received should be counted"
+        );
+        assert!(stats.total_bytes_sent > 0, "Bytes sent should be counted");
+        assert!(
+            stats.total_bytes_received > 0,
+            "Bytes received should be counted"
+        );
+    }
+    logger.info("  ✅ Transport statistics collected successfully");
+
     // Clean up
     logger.info("\n🧹 Cleaning up...");
     transport1.stop().await?;
@@ -5,15 +5,18 @@ use runar_common::types::ArcValue;
 use runar_common::Component;
 use runar_node::config::{LogLevel, LoggingConfig};
 
+use runar_node::network::transport::{ErrorCode, NetworkError};
 use runar_node::node::Node;
 use runar_test_utils::create_networked_node_test_config;
 
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::time::sleep;
+use tokio_stream::StreamExt;
 
 // Import the fixture MathService
 use crate::fixtures::math_service::MathService;
+use crate::fixtures::stream_service::StreamService;
 
 // TODO issues we found in the last refactoru of this test ( to be addressewd later)
 // 1 - when I changed the params to use params!{} macro which wraps each element in a map with ArcValue the serializer
@@ -152,6 +155,25 @@ async fn test_remote_action_call() -> Result<()> {
     assert_eq!(response, 5.0);
     logger.info(format!("✅ Secure divide operation: 15 / 3 = {response}"));
 
+    // Errors raised by the remote handler are relayed with their error code
+    let error = node1
+        .request::<ArcValue, f64>(
+            "math2/divide",
+            Some(ArcValue::new_map(hmap! {
+                "a" => 1.0,
+                "b" => 0.0
+            })),
+        )
+        .await
+        .expect_err("Division by zero should fail");
+    let network_error = error
+        .downcast_ref::<NetworkError>()
+        .expect("Relayed error should be a NetworkError");
+    assert_eq!(network_error.code(), ErrorCode::RemoteError);
+    assert!(network_error.message().contains("Division by zero"));
+    assert!(!network_error.is_retryable());
+    logger.info(format!("✅ Remote error relayed: {network_error}"));
+
     // ==========================================
     // STEP 17: Cleanup
     // ==========================================
@@ -164,3 +186,104 @@ async fn test_remote_action_call() -> Result<()> {
 
     Ok(())
 }
+
+/// Test streamed action results between two nodes
+///
+/// INTENTION: Values of a remote action's ResponseStream arrive in order as
+/// individual frames, and an error raised by the remote stream ends the
+/// caller's stream with the relayed error.
+#[tokio::test]
+async fn test_remote_streaming_action() -> Result<()> {
+    let logging_config = LoggingConfig::new().with_default_level(LogLevel::Info);
+    logging_config.apply();
+
+    let configs =
+        create_networked_node_test_config(2).expect("Failed to create multiple node test configs");
+
+    let mut node1 = Node::new(configs[0].clone()).await?;
+    node1
+        .add_service(StreamService::new("stream", "stream"))
+        .await?;
+    node1.start().await?;
+
+    let mut node2 = Node::new(configs[1].clone()).await?;
+    node2.start().await?;
+
+    // Wait for multicast discovery and the QUIC connection
+    sleep(Duration::from_secs(5)).await;
+
+    let values: Vec<i64> = node2
+        .request_stream::<_, i64>("stream/count", Some(ArcValue::new_primitive(100i64)))
+        .await?
+        .map(|item| item.expect("stream item"))
+        .collect()
+        .await;
+    assert_eq!(values, (1..=100).collect::<Vec<i64>>());
+
+    let items: Vec<Result<i64>> = node2
+        .request_stream::<ArcValue, i64>("stream/broken", None)
+        .await?
+        .collect()
+        .await;
+    assert_eq!(items.len(), 3);
+    assert_eq!(*items[0].as_ref().unwrap(), 1);
+    assert_eq!(*items[1].as_ref().unwrap(), 2);
+    let error = items[2].as_ref().unwrap_err();
+    let network_error = error
+        .downcast_ref::<NetworkError>()
+        .expect("Stream error should be a NetworkError");
+    assert_eq!(network_error.code(), ErrorCode::RemoteError);
+    assert!(network_error.message().contains("Stream source failed"));
+
+    node1.stop().await?;
+    node2.stop().await?;
+    Ok(())
+}
+
+/// Test that a remote stream which stops sending frames expires
+///
+/// INTENTION: When the remote node neither sends another value nor ends the
+/// stream, the caller's stream fails with a timeout once no frame arrived for
+/// the request timeout, instead of waiting forever.
+#[tokio::test]
+async fn test_remote_stalled_stream_expires() -> Result<()> {
+    let logging_config = LoggingConfig::new().with_default_level(LogLevel::Info);
+    logging_config.apply();
+
+    let configs =
+        create_networked_node_test_config(2).expect("Failed to create multiple node test configs");
+
+    let mut node1 = Node::new(configs[0].clone()).await?;
+    node1
+        .add_service(StreamService::new("stream", "stream"))
+        .await?;
+    node1.start().await?;
+
+    let mut node2 = Node::new(configs[1].clone().with_request_timeout(2000)).await?;
+    node2.start().await?;
+
+    // Wait for multicast discovery and the QUIC connection
+    sleep(Duration::from_secs(5)).await;
+
+    let items: Vec<Result<i64>> = tokio::time::timeout(
+        Duration::from_secs(10),
+        node2
+            .request_stream::<ArcValue, i64>("stream/stalled", None)
+            .await?
+            .collect(),
+    )
+    .await
+    .expect("A stalled stream should expire");
+    assert_eq!(items.len(), 3);
+    assert_eq!(*items[0].as_ref().unwrap(), 1);
+    assert_eq!(*items[1].as_ref().unwrap(), 2);
+    let error = items[2].as_ref().unwrap_err();
+    let network_error = error
+        .downcast_ref::<NetworkError>()
+        .expect("Stream error should be a NetworkError");
+    assert_eq!(network_error.code(), ErrorCode::Timeout);
+
+    node1.stop().await?;
+    node2.stop().await?;
+    Ok(())
+}
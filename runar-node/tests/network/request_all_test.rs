@@ -0,0 +1,114 @@
+// Tests for Node::request_all
+//
+// A request sent with request_all reaches every node hosting the service,
+// including the local node, and reports each node's result separately.
+
+use anyhow::Result;
+use runar_common::hmap;
+use runar_common::types::ArcValue;
+use runar_node::node::{Node, NodeConfig};
+use runar_test_utils::{create_networked_node_test_config, without_discovery};
+
+use std::net::{Ipv4Addr, SocketAddr};
+use std::time::Duration;
+use tokio::time::sleep;
+
+use crate::fixtures::math_service::MathService;
+
+fn port_of(config: &NodeConfig) -> u16 {
+    config
+        .network_config
+        .as_ref()
+        .unwrap()
+        .transport_options
+        .bind_address
+        .port()
+}
+
+fn operands(a: f64, b: f64) -> Option<ArcValue> {
+    Some(ArcValue::new_map(hmap! {
+        "a" => a,
+        "b" => b
+    }))
+}
+
+/// Test fanning a request out to every node hosting a service
+///
+/// INTENTION: Two nodes host the math service and a third does not. A request
+/// from the third node reaches both hosts; a request from a host also runs
+/// locally. Failing calls are returned next to their peer instead of failing
+/// the whole request.
+#[tokio::test]
+async fn test_request_all_collects_every_host() -> Result<()> {
+    let configs = create_networked_node_test_config(3)?;
+
+    let node1_config = without_discovery(configs[0].clone());
+    let node1_addr = SocketAddr::from((Ipv4Addr::LOCALHOST, port_of(&node1_config)));
+    let mut node1 = Node::new(node1_config).await?;
+    node1.add_service(MathService::new("math", "math")).await?;
+    node1.start().await?;
+    let node1_peer_id = node1.get_local_node_info().await?.peer_id;
+
+    let node2_config = without_discovery(configs[1].clone())
+        .with_initial_peers(vec![(node1_addr, node1_peer_id.clone())]);
+    let node2_addr = SocketAddr::from((Ipv4Addr::LOCALHOST, port_of(&node2_config)));
+    let mut node2 = Node::new(node2_config).await?;
+    node2.add_service(MathService::new("math", "math")).await?;
+    node2.start().await?;
+    let node2_peer_id = node2.get_local_node_info().await?.peer_id;
+
+    let node3_config = without_discovery(configs[2].clone()).with_initial_peers(vec![
+        (node1_addr, node1_peer_id.clone()),
+        (node2_addr, node2_peer_id.clone()),
+    ]);
+    let mut node3 = Node::new(node3_config).await?;
+    node3.start().await?;
+    // Let every node register the others' services
+    sleep(Duration::from_secs(1)).await;
+
+    let timeout = Duration::from_secs(5);
+    let mut results = node3
+        .request_all::<ArcValue, f64>("math/add", operands(2.0, 3.0), timeout)
+        .await?;
+    results.sort_by_key(|(peer_id, _)| peer_id.to_string());
+    let mut expected = vec![node1_peer_id.clone(), node2_peer_id.clone()];
+    expected.sort_by_key(|peer_id| peer_id.to_string());
+    assert_eq!(
+        results
+            .iter()
+            .map(|(peer_id, _)| peer_id.clone())
+            .collect::<Vec<_>>(),
+        expected
+    );
+    for (_, result) in results {
+        assert_eq!(result?, 5.0);
+    }
+
+    // A host includes itself
+    let results = node1
+        .request_all::<ArcValue, f64>("math/multiply", operands(4.0, 5.0), timeout)
+        .await?;
+    assert_eq!(results.len(), 2);
+    assert!(results.iter().any(|(peer_id, _)| *peer_id == node1_peer_id));
+    assert!(results
+        .iter()
+        .all(|(_, result)| matches!(result, Ok(value) if *value == 20.0)));
+
+    // Failures are reported per peer
+    let results = node3
+        .request_all::<ArcValue, f64>("math/divide", operands(1.0, 0.0), timeout)
+        .await?;
+    assert_eq!(results.len(), 2);
+    assert!(results.iter().all(|(_, result)| result.is_err()));
+
+    // Nobody hosts the service
+    assert!(node3
+        .request_all::<ArcValue, f64>("missing/add", operands(1.0, 1.0), timeout)
+        .await
+        .is_err());
+
+    node3.stop().await?;
+    node2.stop().await?;
+    node1.stop().await?;
+    Ok(())
+}
@@ -0,0 +1,193 @@
+// Tests for request authentication between nodes
+//
+// Nodes configured with the same secret key can call each other's services;
+// a node with a different key is rejected before its request is dispatched.
+
+use anyhow::Result;
+use runar_common::hmap;
+use runar_common::types::ArcValue;
+use runar_node::network::transport::request_auth::retain_sent_request_failures;
+use runar_node::network::transport::{
+    ErrorCode, HmacAlgorithm, NetworkError, NetworkMessage, NetworkMessagePayloadItem, PeerId,
+    RequestAuthConfig, RequestAuthenticator,
+};
+use runar_node::node::Node;
+use runar_test_utils::{create_networked_node_test_config, without_discovery};
+
+use std::net::{Ipv4Addr, SocketAddr};
+use std::time::Duration;
+
+use crate::fixtures::math_service::MathService;
+
+fn test_message() -> NetworkMessage {
+    NetworkMessage {
+        source: PeerId::new("source".to_string()),
+        destination: PeerId::new("destination".to_string()),
+        message_type: "Request".to_string(),
+        payloads: vec![NetworkMessagePayloadItem::new(
+            "math/add".to_string(),
+            vec![1, 2, 3],
+            "correlation-1".to_string(),
+        )],
+        signature: None,
+        hop_count: 0,
+        visited_peers: Vec::new(),
+        auth_tag: None,
+        expires_at: None,
+    }
+}
+
+#[test]
+fn test_authenticator_tags() {
+    let config = RequestAuthConfig::new(b"shared secret".to_vec());
+    let sender = RequestAuthenticator::new(&config);
+    let receiver = RequestAuthenticator::new(&config);
+
+    let mut message = test_message();
+    assert!(!receiver.verify(&message), "untagged messages are rejected");
+    sender.sign(&mut message);
+    assert_eq!(message.auth_tag.as_ref().map(Vec::len), Some(32));
+    assert!(receiver.verify(&message));
+
+    // The tag covers the payload and the correlation id
+    let mut tampered = message.clone();
+    tampered.payloads[0].value_bytes = vec![9];
+    assert!(!receiver.verify(&tampered));
+    let mut tampered = message.clone();
+    tampered.payloads[0].correlation_id = "correlation-2".to_string();
+    assert!(!receiver.verify(&tampered));
+
+    // A different key or algorithm does not verify
+    let other_key = RequestAuthenticator::new(&RequestAuthConfig::new(b"other".to_vec()));
+    assert!(!other_key.verify(&message));
+    let other_algorithm =
+        RequestAuthenticator::new(&config.clone().with_algorithm(HmacAlgorithm::Sha512));
+    assert!(!other_algorithm.verify(&message));
+
+    // The secret never appears in debug output
+    assert!(!format!("{config:?}").contains("shared secret"));
+}
+
+#[test]
+fn test_authenticator_key_rotation() {
+    let config = RequestAuthConfig::new(b"old key".to_vec());
+    let sender = RequestAuthenticator::new(&config);
+    let receiver = RequestAuthenticator::new(&config);
+
+    let mut old_message = test_message();
+    sender.sign(&mut old_message);
+
+    // The old key is accepted during the overlap window
+    receiver.rotate_key(b"new key", Duration::from_secs(60));
+    assert!(receiver.verify(&old_message));
+    sender.rotate_key(b"new key", Duration::from_secs(60));
+    let mut new_message = test_message();
+    sender.sign(&mut new_message);
+    assert!(receiver.verify(&new_message));
+
+    // ... and rejected once it ends
+    receiver.rotate_key(b"newest key", Duration::ZERO);
+    assert!(!receiver.verify(&new_message));
+}
+
+/// Test that untagged authentication failures only end requests sent to their source
+///
+/// INTENTION: A peer without the key must not be able to fail requests the
+/// node sent to other peers, or requests it never received.
+#[test]
+fn test_authentication_failures_only_answer_requests_sent_to_source() {
+    let source = PeerId::new("source".to_string());
+    let mut message = test_message();
+    message.message_type = "Error".to_string();
+    message.payloads = ["sent-to-source", "sent-elsewhere", "unknown"]
+        .into_iter()
+        .map(|correlation_id| {
+            let mut payload = NetworkMessagePayloadItem::new(
+                "math/add".to_string(),
+                Vec::new(),
+                correlation_id.to_string(),
+            );
+            payload.error_code = Some(ErrorCode::AuthenticationFailed.as_u16());
+            payload
+        })
+        .collect();
+
+    let discarded =
+        retain_sent_request_failures(&mut message, |correlation_id| match correlation_id {
+            "sent-to-source" => Some(source.clone()),
+            "sent-elsewhere" => Some(PeerId::new("other".to_string())),
+            _ => None,
+        });
+    assert_eq!(discarded, 2);
+    assert_eq!(message.payloads.len(), 1);
+    assert_eq!(message.payloads[0].correlation_id, "sent-to-source");
+}
+
+/// Test that only nodes sharing the secret key can call each other
+#[tokio::test]
+async fn test_request_authentication_between_nodes() -> Result<()> {
+    let configs = create_networked_node_test_config(3)?;
+    let node1_config = without_discovery(configs[0].clone())
+        .with_request_auth(RequestAuthConfig::new(b"cluster key".to_vec()));
+    let node1_port = node1_config
+        .network_config
+        .as_ref()
+        .unwrap()
+        .transport_options
+        .bind_address
+        .port();
+
+    let mut node1 = Node::new(node1_config).await?;
+    node1
+        .add_service(MathService::new("auth1", "auth1"))
+        .await?;
+    node1.start().await?;
+    let node1_peer_id = node1.get_local_node_info().await?.peer_id;
+    let node1_addr = SocketAddr::from((Ipv4Addr::LOCALHOST, node1_port));
+
+    let node2_config = without_discovery(configs[1].clone())
+        .with_request_auth(RequestAuthConfig::new(b"cluster key".to_vec()))
+        .with_initial_peers(vec![(node1_addr, node1_peer_id.clone())]);
+    let mut node2 = Node::new(node2_config).await?;
+    node2.start().await?;
+
+    let node3_config = without_discovery(configs[2].clone())
+        .with_request_auth(RequestAuthConfig::new(b"wrong key".to_vec()))
+        .with_initial_peers(vec![(node1_addr, node1_peer_id)]);
+    let mut node3 = Node::new(node3_config).await?;
+    node3.start().await?;
+
+    let params = || {
+        Some(ArcValue::new_map(hmap! {
+            "a" => 2.0,
+            "b" => 3.0
+        }))
+    };
+
+    let response: f64 = node2.request("auth1/add", params()).await?;
+    assert_eq!(response, 5.0);
+
+    let error = node3
+        .request::<ArcValue, f64>("auth1/add", params())
+        .await
+        .expect_err("a node with the wrong key must be rejected");
+    let network_error = error
+        .downcast_ref::<NetworkError>()
+        .expect("the rejection is reported as a network error");
+    assert!(matches!(
+        network_error,
+        NetworkError::ConnectionError(ErrorCode::AuthenticationFailed, message)
+            if message == "authentication failed"
+    ));
+
+    // Rotating the key on both nodes keeps them talking to each other
+    node1.rotate_auth_key(b"rotated key".to_vec())?;
+    node2.rotate_auth_key(b"rotated key".to_vec())?;
+    let response: f64 = node2.request("auth1/add", params()).await?;
+    assert_eq!(response, 5.0);
+
+    node3.stop().await?;
+    node2.stop().await?;
+    node1.stop().await?;
+    Ok(())
+}
@@ -0,0 +1,175 @@
+// Tests for nodes using different serialization backends
+//
+// Every payload names the backend it was encoded with, so a node configured
+// with MessagePack and a node using the default bincode can call each other,
+// while peers that cannot decode a node's payloads are refused.
+
+use anyhow::Result;
+use runar_common::hmap;
+use runar_common::logging::{Component, Logger};
+use runar_common::types::{ArcValue, SerializationBackend, SerializerRegistry};
+use runar_node::network::transport::capabilities::check_payload_format;
+use runar_node::network::transport::NetworkMessagePayloadItem;
+use runar_node::network::{ErrorCode, NodeCapabilities, QuicTransportOptions};
+use runar_node::node::Node;
+use runar_test_utils::{create_networked_node_test_config, without_discovery};
+
+use std::net::{Ipv4Addr, SocketAddr};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::time::sleep;
+
+use crate::fixtures::math_service::MathService;
+
+async fn add(node: &Node, path: &str) -> Result<f64> {
+    node.request(
+        path,
+        Some(ArcValue::new_map(hmap! {
+            "a" => 1.0,
+            "b" => 2.0
+        })),
+    )
+    .await
+}
+
+/// Poll `path` on `node` until it can be called, or give up
+async fn wait_for(node: &Node, path: &str) -> Result<f64> {
+    let deadline = Instant::now() + Duration::from_secs(5);
+    loop {
+        let result = add(node, path).await;
+        if result.is_ok() || Instant::now() >= deadline {
+            return result;
+        }
+        sleep(Duration::from_millis(50)).await;
+    }
+}
+
+/// Test decoding a payload with the backend its sender named
+///
+/// INTENTION: A bincode registry reads MessagePack bytes when told to, and
+/// the payload constructor leaves the format to the receiver by default.
+#[test]
+fn test_deserialize_with_payload_backend() -> Result<()> {
+    let logger = Arc::new(Logger::new_root(Component::Custom("Test"), "test-node"));
+    let msgpack = SerializerRegistry::with_backend(logger.clone(), SerializationBackend::Msgpack);
+    let bincode = SerializerRegistry::with_defaults(logger);
+
+    let bytes = msgpack.serialize_value(&ArcValue::new_primitive("hello".to_string()))?;
+    let mut value = bincode.deserialize_value_with_backend(bytes, SerializationBackend::Msgpack)?;
+    assert_eq!(value.as_type::<String>()?, "hello");
+
+    let payload = NetworkMessagePayloadItem::new("path".to_string(), Vec::new(), String::new());
+    assert_eq!(payload.format, None);
+    let payload = payload.with_format(SerializationBackend::Msgpack);
+    assert_eq!(payload.format, Some(SerializationBackend::Msgpack));
+    Ok(())
+}
+
+/// Test requests between nodes using different backends
+///
+/// INTENTION: node1 encodes its payloads with MessagePack and node2 with
+/// bincode. Requests and responses in both directions are decoded with the
+/// sender's backend.
+#[tokio::test]
+async fn test_mixed_format_nodes_call_each_other() -> Result<()> {
+    let configs = create_networked_node_test_config(2)?;
+
+    let node1_config = without_discovery(configs[0].clone())
+        .with_serialization_backend(SerializationBackend::Msgpack);
+    let node1_port = node1_config
+        .network_config
+        .as_ref()
+        .unwrap()
+        .transport_options
+        .bind_address
+        .port();
+    let mut node1 = Node::new(node1_config).await?;
+    node1
+        .add_service(MathService::new("math1", "math1"))
+        .await?;
+    node1.start().await?;
+    let node1_peer_id = node1.get_local_node_info().await?.peer_id;
+
+    let node2_config = without_discovery(configs[1].clone()).with_initial_peers(vec![(
+        SocketAddr::from((Ipv4Addr::LOCALHOST, node1_port)),
+        node1_peer_id,
+    )]);
+    let mut node2 = Node::new(node2_config).await?;
+    node2
+        .add_service(MathService::new("math2", "math2"))
+        .await?;
+    node2.start().await?;
+
+    assert_eq!(wait_for(&node2, "math1/add").await?, 3.0);
+    assert_eq!(wait_for(&node1, "math2/add").await?, 3.0);
+
+    node2.stop().await?;
+    node1.stop().await?;
+    Ok(())
+}
+
+/// Test that peers unable to decode a node's payloads are refused
+///
+/// INTENTION: node1 decodes only bincode and node2 sends MessagePack. The
+/// handshake fails instead of every request, and neither node becomes a known
+/// peer of the other.
+#[tokio::test]
+async fn test_peer_without_payload_format_is_refused() -> Result<()> {
+    let bincode_only = NodeCapabilities {
+        supported_formats: vec![SerializationBackend::Bincode],
+        ..NodeCapabilities::default()
+    };
+    let msgpack = NodeCapabilities::default().with_payload_format(SerializationBackend::Msgpack);
+    assert_eq!(
+        msgpack.supported_formats,
+        vec![SerializationBackend::Msgpack, SerializationBackend::Bincode]
+    );
+    assert!(check_payload_format(&NodeCapabilities::default(), &msgpack).is_ok());
+    assert!(check_payload_format(&bincode_only, &NodeCapabilities::default()).is_ok());
+    let error = check_payload_format(&bincode_only, &msgpack).unwrap_err();
+    assert_eq!(error.code(), ErrorCode::InvalidConfiguration);
+    assert!(check_payload_format(&msgpack, &bincode_only).is_err());
+
+    let configs = create_networked_node_test_config(2)?;
+    let mut node1_config = without_discovery(configs[0].clone());
+    let network_config = node1_config.network_config.as_mut().unwrap();
+    let options: QuicTransportOptions = network_config.quic_options.take().unwrap_or_default();
+    network_config.quic_options = Some(options.with_capabilities(bincode_only));
+    let node1_port = node1_config
+        .network_config
+        .as_ref()
+        .unwrap()
+        .transport_options
+        .bind_address
+        .port();
+    let mut node1 = Node::new(node1_config).await?;
+    node1.start().await?;
+    let node1_peer_id = node1.get_local_node_info().await?.peer_id;
+
+    let node2_config = without_discovery(configs[1].clone())
+        .with_serialization_backend(SerializationBackend::Msgpack)
+        .with_request_timeout(2000)
+        .with_initial_peers(vec![(
+            SocketAddr::from((Ipv4Addr::LOCALHOST, node1_port)),
+            node1_peer_id.clone(),
+        )]);
+    let mut node2 = Node::new(node2_config).await?;
+    node2.start().await?;
+    let node2_peer_id = node2.get_local_node_info().await?.peer_id;
+    sleep(Duration::from_millis(500)).await;
+
+    assert!(node1
+        .peer_registry()
+        .find_peer(node2_peer_id.public_key.clone())
+        .and_then(|entry| entry.node_info)
+        .is_none());
+    assert!(node2
+        .peer_registry()
+        .find_peer(node1_peer_id.public_key.clone())
+        .and_then(|entry| entry.node_info)
+        .is_none());
+
+    node2.stop().await?;
+    node1.stop().await?;
+    Ok(())
+}
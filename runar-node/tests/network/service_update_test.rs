@@ -0,0 +1,171 @@
+// Tests for broadcasting local service changes
+//
+// Services added to or removed from a running node reach its peers without
+// any explicit call, batched by the service update debounce. After the first
+// update peers receive only the services that changed.
+
+use anyhow::Result;
+use runar_common::hmap;
+use runar_common::types::{ArcValue, ServiceMetadata};
+use runar_node::network::transport::PeerId;
+use runar_node::network::{NodeInfo, NodeInfoDiff, QuicTransportOptions};
+use runar_node::node::{Node, NodeConfig};
+use runar_test_utils::{create_networked_node_test_config, without_discovery};
+
+use std::net::{Ipv4Addr, SocketAddr};
+use std::time::{Duration, Instant};
+use tokio::time::sleep;
+
+use crate::fixtures::math_service::MathService;
+
+/// Remove the discovery providers and shorten the service update debounce
+fn configure(config: NodeConfig, debounce_ms: u64) -> NodeConfig {
+    let mut config = without_discovery(config);
+    let network_config = config
+        .network_config
+        .as_mut()
+        .expect("test config has networking");
+    let options: QuicTransportOptions = network_config.quic_options.take().unwrap_or_default();
+    network_config.quic_options = Some(options.with_service_update_debounce_ms(debounce_ms));
+    config
+}
+
+async fn add(node: &Node, path: &str) -> Result<f64> {
+    node.request(
+        path,
+        Some(ArcValue::new_map(hmap! {
+            "a" => 1.0,
+            "b" => 2.0
+        })),
+    )
+    .await
+}
+
+/// Poll `path` on `node` until its outcome is `available`, or give up
+async fn wait_for(node: &Node, path: &str, available: bool) -> bool {
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while Instant::now() < deadline {
+        if add(node, path).await.is_ok() == available {
+            return true;
+        }
+        sleep(Duration::from_millis(50)).await;
+    }
+    false
+}
+
+/// Test that service changes reach peers automatically
+///
+/// INTENTION: Two services added in quick succession are announced to the
+/// peer together once the debounce has passed, and removing one is announced
+/// the same way; the test never triggers an update itself.
+#[tokio::test]
+async fn test_service_changes_reach_peers() -> Result<()> {
+    let configs = create_networked_node_test_config(2)?;
+    let node1_config = configure(configs[0].clone(), 100);
+    let node1_port = node1_config
+        .network_config
+        .as_ref()
+        .unwrap()
+        .transport_options
+        .bind_address
+        .port();
+    let mut node1 = Node::new(node1_config).await?;
+    node1.start().await?;
+    let node1_peer_id = node1.get_local_node_info().await?.peer_id;
+
+    let node1_addr = SocketAddr::from((Ipv4Addr::LOCALHOST, node1_port));
+    let node2_config =
+        configure(configs[1].clone(), 100).with_initial_peers(vec![(node1_addr, node1_peer_id)]);
+    let mut node2 = Node::new(node2_config).await?;
+    node2.start().await?;
+    assert!(add(&node2, "late1/add").await.is_err());
+
+    node1
+        .add_service(MathService::new("late1", "late1"))
+        .await?;
+    node1
+        .add_service(MathService::new("late2", "late2"))
+        .await?;
+    assert!(wait_for(&node2, "late1/add", true).await);
+    assert!(wait_for(&node2, "late2/add", true).await);
+    assert_eq!(add(&node2, "late2/add").await?, 3.0);
+
+    node1.remove_service("late1").await?;
+    assert!(wait_for(&node2, "late1/add", false).await);
+    assert_eq!(add(&node2, "late2/add").await?, 3.0);
+
+    // Sent as a diff against the previous update
+    node1
+        .add_service(MathService::new("late3", "late3"))
+        .await?;
+    assert!(wait_for(&node2, "late3/add", true).await);
+    assert_eq!(add(&node2, "late2/add").await?, 3.0);
+    assert!(add(&node2, "late1/add").await.is_err());
+
+    node2.stop().await?;
+    node1.stop().await?;
+    Ok(())
+}
+
+fn service(path: &str, version: &str) -> ServiceMetadata {
+    ServiceMetadata {
+        network_id: "test".to_string(),
+        service_path: path.to_string(),
+        name: path.to_string(),
+        version: version.to_string(),
+        description: String::new(),
+        actions: Vec::new(),
+        events: Vec::new(),
+        registration_time: 0,
+        last_start_time: None,
+        visibility: Default::default(),
+    }
+}
+
+fn node_info(version: i64, services: Vec<ServiceMetadata>) -> NodeInfo {
+    NodeInfo {
+        peer_id: PeerId::new("node".to_string()),
+        network_ids: vec!["test".to_string()],
+        addresses: Vec::new(),
+        services,
+        version,
+        subscriptions: Vec::new(),
+    }
+}
+
+/// Test computing and applying a service diff
+///
+/// INTENTION: A diff lists added and changed services, the removed ones and
+/// the subscriptions if they changed, turns the base node info into the newer one, and is refused by a node
+/// info of another version.
+#[test]
+fn test_node_info_diff_round_trip() {
+    let base = node_info(1, vec![service("a", "1"), service("b", "1")]);
+    let newer = NodeInfo {
+        subscriptions: vec!["test:c/updated".to_string()],
+        ..node_info(2, vec![service("b", "2"), service("c", "1")])
+    };
+
+    let diff = base.diff(&newer);
+    assert_eq!(
+        diff,
+        NodeInfoDiff {
+            base_version: 1,
+            version: 2,
+            added_services: vec![service("b", "2"), service("c", "1")],
+            removed_services: vec!["test:a".to_string()],
+            subscriptions: Some(vec!["test:c/updated".to_string()]),
+        }
+    );
+    assert!(base.diff(&node_info(2, base.services.clone())).is_empty());
+
+    let mut applied = base.clone();
+    applied.apply_diff(&diff).unwrap();
+    assert_eq!(applied.version, 2);
+    assert_eq!(applied.services, newer.services);
+    assert_eq!(applied.subscriptions, newer.subscriptions);
+
+    // Already at version 2
+    assert!(applied.apply_diff(&diff).is_err());
+    assert_eq!(applied.services, newer.services);
+}
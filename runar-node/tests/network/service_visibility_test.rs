@@ -0,0 +1,131 @@
+// Tests for selectively advertising services to peers
+//
+// Services that are not public are never advertised, and services restricted
+// to some networks only reach peers in those networks, whichever side starts
+// the handshake.
+
+use anyhow::Result;
+use runar_common::hmap;
+use runar_common::types::ArcValue;
+use runar_node::network::discovery::multicast_discovery::PeerInfo;
+use runar_node::network::transport::PeerId;
+use runar_node::node::{Node, NodeConfig};
+use runar_node::{ServiceMetadata, ServiceVisibility};
+use runar_test_utils::{create_networked_node_test_config, without_discovery};
+
+use std::net::{Ipv4Addr, SocketAddr};
+use std::time::{Duration, Instant};
+use tokio::time::sleep;
+
+use crate::fixtures::math_service::MathService;
+
+fn address(config: &NodeConfig) -> SocketAddr {
+    let port = config
+        .network_config
+        .as_ref()
+        .unwrap()
+        .transport_options
+        .bind_address
+        .port();
+    SocketAddr::from((Ipv4Addr::LOCALHOST, port))
+}
+
+async fn add(node: &Node, path: &str) -> Result<f64> {
+    node.request(
+        path,
+        Some(ArcValue::new_map(hmap! {
+            "a" => 1.0,
+            "b" => 2.0
+        })),
+    )
+    .await
+}
+
+/// Poll `path` on `node` until it can be called, or give up
+async fn wait_for(node: &Node, path: &str) -> bool {
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while Instant::now() < deadline {
+        if add(node, path).await.is_ok() {
+            return true;
+        }
+        sleep(Duration::from_millis(50)).await;
+    }
+    false
+}
+
+/// Test that services reach only the peers allowed to see them
+///
+/// INTENTION: node1 hosts a public, a private and an admin-only service.
+/// node2 is in the admin network and is dialed by node1; node3 is not and
+/// dials node1. node2 sees the public and admin services, node3 only the
+/// public one, and neither the private one until its metadata is registered
+/// with `add_remote_service`. Discovery announcements only list the public
+/// service.
+#[tokio::test]
+async fn test_service_visibility_per_peer() -> Result<()> {
+    let configs = create_networked_node_test_config(3)?;
+
+    let node2_config =
+        without_discovery(configs[1].clone()).with_additional_networks(vec!["admin".to_string()]);
+    let node2_addr = address(&node2_config);
+    let mut node2 = Node::new(node2_config).await?;
+    node2.start().await?;
+    let node2_peer_id = node2.get_local_node_info().await?.peer_id;
+
+    let node1_config = without_discovery(configs[0].clone())
+        .with_capability_advertisement("private", ServiceVisibility::private())
+        .with_capability_advertisement(
+            "admin",
+            ServiceVisibility::networks(vec!["admin".to_string()]),
+        )
+        .with_initial_peers(vec![(node2_addr, node2_peer_id)]);
+    let node1_addr = address(&node1_config);
+    let mut node1 = Node::new(node1_config).await?;
+    node1
+        .add_service(MathService::new("public", "public"))
+        .await?;
+    node1
+        .add_service(MathService::new("private", "private"))
+        .await?;
+    node1
+        .add_service(MathService::new("admin", "admin"))
+        .await?;
+    node1.start().await?;
+    let node1_peer_id: PeerId = node1.get_local_node_info().await?.peer_id;
+
+    let node3_config = without_discovery(configs[2].clone())
+        .with_initial_peers(vec![(node1_addr, node1_peer_id.clone())]);
+    let mut node3 = Node::new(node3_config).await?;
+    node3.start().await?;
+
+    let node1_info = node1.get_local_node_info().await?;
+    let mut advertised: Vec<_> = node1_info
+        .services
+        .iter()
+        .map(|service| service.service_path.as_str())
+        .collect();
+    advertised.sort();
+    assert_eq!(advertised, vec!["admin", "public"]);
+    let announcement = PeerInfo::from_node_info(&node1_info);
+    assert!(announcement.advertises_service("public"));
+    assert!(!announcement.advertises_service("admin"));
+
+    assert!(wait_for(&node2, "public/add").await);
+    assert!(wait_for(&node2, "admin/add").await);
+    assert!(wait_for(&node3, "public/add").await);
+    assert!(add(&node3, "admin/add").await.is_err());
+    assert!(add(&node2, "private/add").await.is_err());
+    assert!(add(&node3, "private/add").await.is_err());
+
+    // Still callable by a peer that knows the service
+    let private: ServiceMetadata = node1
+        .request("$registry/services/private", None::<ArcValue>)
+        .await?;
+    node3.add_remote_service(node1_peer_id, private).await?;
+    assert_eq!(add(&node3, "private/add").await?, 3.0);
+
+    node3.stop().await?;
+    node2.stop().await?;
+    node1.stop().await?;
+    Ok(())
+}
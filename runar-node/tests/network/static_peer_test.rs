@@ -0,0 +1,75 @@
+use anyhow::Result;
+use runar_common::hmap;
+use runar_common::types::ArcValue;
+use runar_node::node::Node;
+use runar_test_utils::{create_networked_node_test_config, without_discovery};
+
+use std::net::{Ipv4Addr, SocketAddr};
+use std::time::Duration;
+use tokio::time::sleep;
+
+use crate::fixtures::math_service::MathService;
+
+/// Test connecting nodes through a static peer list
+///
+/// INTENTION: With discovery disabled, a node configured with initial peers
+/// must be connected to them when `start` returns, so remote services can be
+/// called right away in both directions.
+#[tokio::test]
+async fn test_initial_peers_without_discovery() -> Result<()> {
+    let configs = create_networked_node_test_config(2)?;
+    let node1_config = without_discovery(configs[0].clone());
+    let node1_port = node1_config
+        .network_config
+        .as_ref()
+        .unwrap()
+        .transport_options
+        .bind_address
+        .port();
+
+    let mut node1 = Node::new(node1_config).await?;
+    node1
+        .add_service(MathService::new("static1", "static1"))
+        .await?;
+    node1.start().await?;
+    let node1_peer_id = node1.get_local_node_info().await?.peer_id;
+
+    let node1_addr = SocketAddr::from((Ipv4Addr::LOCALHOST, node1_port));
+    let node2_config =
+        without_discovery(configs[1].clone()).with_initial_peers(vec![(node1_addr, node1_peer_id)]);
+    let mut node2 = Node::new(node2_config).await?;
+    node2
+        .add_service(MathService::new("static2", "static2"))
+        .await?;
+    node2.start().await?;
+
+    // No waiting: the handshake completed during start
+    let response: f64 = node2
+        .request(
+            "static1/add",
+            Some(ArcValue::new_map(hmap! {
+                "a" => 2.0,
+                "b" => 3.0
+            })),
+        )
+        .await?;
+    assert_eq!(response, 5.0);
+
+    // The handshake is symmetric, but node1 registers node2's services in the
+    // background after answering it
+    sleep(Duration::from_secs(1)).await;
+    let response: f64 = node1
+        .request(
+            "static2/multiply",
+            Some(ArcValue::new_map(hmap! {
+                "a" => 4.0,
+                "b" => 5.0
+            })),
+        )
+        .await?;
+    assert_eq!(response, 20.0);
+
+    node2.stop().await?;
+    node1.stop().await?;
+    Ok(())
+}
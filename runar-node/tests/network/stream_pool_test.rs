@@ -0,0 +1,160 @@
+// Tests for the QUIC StreamPool
+//
+// These tests verify the pool size limit and the idle stream cleanup.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Result;
+use runar_common::logging::{Component, Logger};
+use runar_node::network::transport::{StreamPool, StreamPoolOptions, StreamPoolStats};
+use rustls_pki_types::{CertificateDer, PrivateKeyDer, PrivatePkcs8KeyDer};
+
+fn test_logger() -> Arc<Logger> {
+    Arc::new(Logger::new_root(Component::Network, "stream_pool_test"))
+}
+
+/// Connect a client endpoint to a server endpoint on localhost
+///
+/// Returns the client side of the connection; the server endpoint is returned
+/// too so it stays alive for the duration of the test.
+async fn connect_loopback() -> Result<(quinn::Connection, quinn::Endpoint)> {
+    if rustls::crypto::CryptoProvider::get_default().is_none() {
+        let _ = rustls::crypto::ring::default_provider().install_default();
+    }
+
+    let cert = rcgen::generate_simple_self_signed(vec!["localhost".to_string()])?;
+    let cert_der = CertificateDer::from(cert.serialize_der()?);
+    let key_der = PrivateKeyDer::Pkcs8(PrivatePkcs8KeyDer::from(cert.serialize_private_key_der()));
+
+    let server_config = quinn::ServerConfig::with_single_cert(vec![cert_der.clone()], key_der)?;
+    let server = quinn::Endpoint::server(server_config, "127.0.0.1:0".parse()?)?;
+    let server_addr = server.local_addr()?;
+
+    let accepting_server = server.clone();
+    tokio::spawn(async move {
+        while let Some(incoming) = accepting_server.accept().await {
+            if let Ok(connection) = incoming.await {
+                // Keep the connection open until the client closes it
+                let _ = connection.closed().await;
+            }
+        }
+    });
+
+    let mut roots = rustls::RootCertStore::empty();
+    roots.add(cert_der)?;
+    let mut client = quinn::Endpoint::client("127.0.0.1:0".parse()?)?;
+    client.set_default_client_config(quinn::ClientConfig::with_root_certificates(Arc::new(
+        roots,
+    ))?);
+    let connection = client.connect(server_addr, "localhost")?.await?;
+
+    Ok((connection, server))
+}
+
+#[tokio::test]
+async fn test_stream_pool_blocks_when_full() {
+    let options = StreamPoolOptions {
+        max_idle_time: Duration::from_secs(30),
+        max_pool_size: 1,
+    };
+    let pool = Arc::new(StreamPool::with_options(4, options, test_logger()));
+
+    // No idle streams yet: the caller gets a slot and opens its own stream
+    assert!(pool.acquire().await.is_none());
+    assert_eq!(
+        pool.stats().await,
+        StreamPoolStats {
+            active: 1,
+            idle: 0,
+            evicted_total: 0
+        }
+    );
+
+    // The only slot is taken, so the next acquisition waits
+    let waiting_pool = pool.clone();
+    let waiter = tokio::spawn(async move { waiting_pool.acquire().await.is_none() });
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert!(!waiter.is_finished());
+
+    // Releasing the slot lets the waiting acquisition through
+    pool.release();
+    let acquired = tokio::time::timeout(Duration::from_secs(1), waiter)
+        .await
+        .expect("acquire should complete once a slot is released")
+        .unwrap();
+    assert!(acquired);
+    assert_eq!(pool.stats().await.active, 1);
+}
+
+#[tokio::test]
+async fn test_stream_pool_slots_are_not_released_twice() {
+    let options = StreamPoolOptions {
+        max_idle_time: Duration::from_secs(30),
+        max_pool_size: 1,
+    };
+    let pool = StreamPool::with_options(4, options, test_logger());
+
+    // A release without an acquisition must not add capacity
+    pool.release();
+    assert!(pool.acquire().await.is_none());
+    assert_eq!(pool.stats().await.active, 1);
+    assert!(
+        tokio::time::timeout(Duration::from_millis(50), pool.acquire())
+            .await
+            .is_err(),
+        "the pool should still hold a single slot"
+    );
+    pool.release();
+
+    // A reserved slot is freed exactly once, when its guard is dropped
+    let slot = pool.reserve().await;
+    assert_eq!(pool.stats().await.active, 1);
+    drop(slot);
+    assert_eq!(pool.stats().await.active, 0);
+    let slot = tokio::time::timeout(Duration::from_millis(50), pool.reserve()).await;
+    assert!(slot.is_ok(), "the slot should be free again");
+}
+
+#[tokio::test]
+async fn test_stream_pool_evicts_idle_streams() -> Result<()> {
+    let (connection, _server) = connect_loopback().await?;
+
+    let options = StreamPoolOptions {
+        max_idle_time: Duration::from_millis(100),
+        max_pool_size: 10,
+    };
+    let pool = StreamPool::with_options(10, options, test_logger());
+
+    let mut streams = Vec::new();
+    for _ in 0..3 {
+        assert!(pool.acquire().await.is_none());
+        streams.push(connection.open_uni().await?);
+    }
+    assert_eq!(pool.stats().await.active, 3);
+    for stream in streams {
+        pool.return_stream(stream).await?;
+    }
+
+    // A returned stream is reused while it is fresh
+    let reused = pool
+        .acquire()
+        .await
+        .expect("an idle stream should be reused");
+    pool.return_stream(reused).await?;
+    assert_eq!(pool.stats().await.idle, 3);
+
+    // Streams left unused past max_idle_time are closed by the cleanup task
+    tokio::time::sleep(Duration::from_millis(400)).await;
+    assert_eq!(
+        pool.stats().await,
+        StreamPoolStats {
+            active: 0,
+            idle: 0,
+            evicted_total: 3
+        }
+    );
+
+    connection.close(0u32.into(), b"done");
+    Ok(())
+}
@@ -0,0 +1,248 @@
+#![cfg(unix)]
+
+use anyhow::Result;
+use runar_common::hmap;
+use runar_common::types::ArcValue;
+use runar_node::network::transport::{NetworkMessage, NetworkMessagePayloadItem, PeerId};
+use runar_node::network::UnixSocketTransport;
+use runar_node::node::Node;
+use runar_test_utils::create_networked_node_test_config;
+
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::UnixStream;
+use tokio::time::sleep;
+
+use crate::fixtures::math_service::MathService;
+use crate::network::{socket_path, with_local_socket};
+
+/// Test calling services between co-located nodes over Unix sockets
+///
+/// INTENTION: Two nodes that only know each other's socket path must be able
+/// to call each other's services without any QUIC connection between them.
+#[tokio::test]
+async fn test_local_peer_over_unix_socket() -> Result<()> {
+    let configs = create_networked_node_test_config(2)?;
+    let node1_socket = socket_path("node1");
+    let node2_socket = socket_path("node2");
+
+    let mut node1 = Node::new(with_local_socket(configs[0].clone(), &node1_socket)).await?;
+    node1.add_service(MathService::new("ipc1", "ipc1")).await?;
+    node1.start().await?;
+
+    let node1_info = node1.get_local_node_info().await?;
+    assert!(node1_info
+        .addresses
+        .contains(&UnixSocketTransport::address_for_path(&node1_socket)));
+
+    let mut node2 = Node::new(with_local_socket(configs[1].clone(), &node2_socket)).await?;
+    node2.add_service(MathService::new("ipc2", "ipc2")).await?;
+    node2.start().await?;
+    node2.add_local_peer(&node1_socket).await?;
+
+    let response: f64 = node2
+        .request(
+            "ipc1/add",
+            Some(ArcValue::new_map(hmap! {
+                "a" => 2.0,
+                "b" => 3.0
+            })),
+        )
+        .await?;
+    assert_eq!(response, 5.0);
+
+    // node1 registers node2's services in the background after the handshake
+    sleep(Duration::from_secs(1)).await;
+    let response: f64 = node1
+        .request(
+            "ipc2/multiply",
+            Some(ArcValue::new_map(hmap! {
+                "a" => 4.0,
+                "b" => 5.0
+            })),
+        )
+        .await?;
+    assert_eq!(response, 20.0);
+
+    node2.stop().await?;
+    node1.stop().await?;
+    assert!(!node1_socket.exists());
+    Ok(())
+}
+
+async fn write_frame(stream: &mut UnixStream, message: &NetworkMessage) -> Result<()> {
+    let bytes = bincode::serialize(message)?;
+    stream
+        .write_all(&(bytes.len() as u32).to_be_bytes())
+        .await?;
+    stream.write_all(&bytes).await?;
+    Ok(())
+}
+
+async fn read_frame(stream: &mut UnixStream) -> Result<NetworkMessage> {
+    let mut len_bytes = [0u8; 4];
+    stream.read_exact(&mut len_bytes).await?;
+    let mut bytes = vec![0u8; u32::from_be_bytes(len_bytes) as usize];
+    stream.read_exact(&mut bytes).await?;
+    Ok(bincode::deserialize(&bytes)?)
+}
+
+fn handshake_message(
+    source: PeerId,
+    destination: PeerId,
+    message_type: &str,
+    payload: NetworkMessagePayloadItem,
+) -> NetworkMessage {
+    NetworkMessage {
+        source,
+        destination,
+        message_type: message_type.to_string(),
+        payloads: vec![payload],
+        signature: None,
+        hop_count: 0,
+        visited_peers: Vec::new(),
+        auth_tag: None,
+        expires_at: None,
+    }
+}
+
+/// Test that a socket peer cannot claim a node id it does not own
+///
+/// INTENTION: Answering the handshake challenge needs the node key, so a local
+/// process claiming another node's id is disconnected and never receives
+/// the traffic meant for that node.
+#[tokio::test]
+async fn test_socket_peer_must_prove_its_id() -> Result<()> {
+    let configs = create_networked_node_test_config(2)?;
+    let node1_socket = socket_path("impostor1");
+    let mut node1 = Node::new(with_local_socket(configs[0].clone(), &node1_socket)).await?;
+    node1.start().await?;
+    let node1_id = node1.get_local_node_info().await?.peer_id;
+
+    // Claim the id of a node whose key we do not hold
+    let victim_info = Node::new(configs[1].clone())
+        .await?
+        .get_local_node_info()
+        .await?;
+    let node1_path_id = UnixSocketTransport::peer_id_for_path(&node1_socket);
+    let mut stream = UnixStream::connect(&node1_socket).await?;
+    write_frame(
+        &mut stream,
+        &handshake_message(
+            victim_info.peer_id.clone(),
+            node1_path_id,
+            "NODE_INFO_HANDSHAKE",
+            NetworkMessagePayloadItem::new(
+                String::new(),
+                bincode::serialize(&victim_info)?,
+                "client-nonce".to_string(),
+            ),
+        ),
+    )
+    .await?;
+
+    // node1 proves its own id by signing our nonce
+    let response = read_frame(&mut stream).await?;
+    assert_eq!(response.message_type, "NODE_INFO_HANDSHAKE_RESPONSE");
+    assert_eq!(response.source, node1_id);
+    assert_eq!(response.payloads[0].correlation_id, "client-nonce");
+    response.verify_source_signature()?;
+
+    // An unsigned answer to node1's nonce gets the connection closed
+    write_frame(
+        &mut stream,
+        &handshake_message(
+            victim_info.peer_id,
+            node1_id,
+            "NODE_INFO_HANDSHAKE_CONFIRM",
+            NetworkMessagePayloadItem::new(
+                String::new(),
+                Vec::new(),
+                response.payloads[0].path.clone(),
+            ),
+        ),
+    )
+    .await?;
+    assert!(read_frame(&mut stream).await.is_err());
+
+    node1.stop().await?;
+    Ok(())
+}
+
+/// Test that the peer filter applies to socket peers
+///
+/// INTENTION: A blacklisted node must not get around the filter by
+/// connecting over the local socket instead of QUIC.
+#[tokio::test]
+async fn test_socket_peer_filter() -> Result<()> {
+    let configs = create_networked_node_test_config(2)?;
+    let node1_socket = socket_path("filter1");
+    let node2_socket = socket_path("filter2");
+
+    let mut node1 = Node::new(with_local_socket(configs[0].clone(), &node1_socket)).await?;
+    node1.add_service(MathService::new("ipc", "ipc")).await?;
+    node1.start().await?;
+    let mut node2 = Node::new(with_local_socket(configs[1].clone(), &node2_socket)).await?;
+    node2.start().await?;
+
+    let node2_id = node2.get_local_node_info().await?.peer_id;
+    node1
+        .update_peer_blacklist([node2_id].into_iter().collect())
+        .await?;
+    assert!(node2.add_local_peer(&node1_socket).await.is_err());
+    let result = node2
+        .request::<ArcValue, f64>(
+            "ipc/add",
+            Some(ArcValue::new_map(hmap! {
+                "a" => 2.0,
+                "b" => 3.0
+            })),
+        )
+        .await;
+    assert!(result.is_err(), "blacklisted peer reached the service");
+
+    node2.stop().await?;
+    node1.stop().await?;
+    Ok(())
+}
+
+/// Test that peers drop a service once it is removed
+///
+/// INTENTION: `Node::remove_service` pushes new node info, so a peer stops
+/// routing requests for the removed service to this node.
+#[tokio::test]
+async fn test_removed_service_disappears_from_peer() -> Result<()> {
+    let configs = create_networked_node_test_config(2)?;
+    let node1_socket = socket_path("remove1");
+    let node2_socket = socket_path("remove2");
+
+    let mut node1 = Node::new(with_local_socket(configs[0].clone(), &node1_socket)).await?;
+    node1
+        .add_service(MathService::new("plugin", "plugin"))
+        .await?;
+    node1.start().await?;
+
+    let mut node2 = Node::new(with_local_socket(configs[1].clone(), &node2_socket)).await?;
+    node2.start().await?;
+    node2.add_local_peer(&node1_socket).await?;
+
+    let params = ArcValue::new_map(hmap! {
+        "a" => 2.0,
+        "b" => 3.0
+    });
+    let response: f64 = node2.request("plugin/add", Some(params.clone())).await?;
+    assert_eq!(response, 5.0);
+
+    node1.remove_service("plugin").await?;
+    // Node info updates are debounced for two seconds
+    sleep(Duration::from_secs(4)).await;
+
+    let result = node2
+        .request::<ArcValue, f64>("plugin/add", Some(params))
+        .await;
+    assert!(result.is_err(), "peer still routes to the removed service");
+
+    node2.stop().await?;
+    node1.stop().await?;
+    Ok(())
+}
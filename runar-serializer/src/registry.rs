@@ -271,6 +271,8 @@ impl SerializerRegistry {
                 start_offset: payload_start,
                 end_offset: bytes_arc.len(),
                 deserializer: Some(wrapper),
+                backend: self.base_registry.backend(),
+                eager_cache: Default::default(),
             };
 
             let erased = ErasedArc::from_value(lazy);
@@ -320,6 +320,7 @@ impl CrudSqliteService {
             params: SqlParams {
                 values: value_params,
             },
+            transaction_id: None,
         };
 
         let action_path = self.sqlite_action_path("execute_query");
@@ -427,6 +428,7 @@ impl CrudSqliteService {
             params: SqlParams {
                 values: value_params,
             },
+            transaction_id: None,
         };
 
         let action_path = self.sqlite_action_path("execute_query");
@@ -0,0 +1,582 @@
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use rand::seq::IndexedRandom;
+use runar_common::types::ArcValue;
+use runar_node::network::transport::PeerId;
+use runar_node::services::{EventContext, LifecycleContext, RequestContext, ServiceFuture};
+use runar_node::AbstractService;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tokio::task::JoinHandle;
+
+/// Number of leaves of the Merkle tree exchanged during anti-entropy
+pub const DIGEST_BUCKETS: usize = 16;
+
+/// Configuration of a distributed key-value store, as seen from one of its members
+#[derive(Clone, Debug)]
+pub struct DistributedKvConfig {
+    /// The other members of the cluster; the local node is not listed
+    pub peers: Vec<PeerId>,
+    /// Number of random peers each anti-entropy round exchanges state with
+    pub replication_factor: u32,
+    /// How often an anti-entropy round runs
+    pub anti_entropy_interval: Duration,
+}
+
+impl DistributedKvConfig {
+    /// Create a config exchanging state with one peer every 30 seconds
+    pub fn new(peers: Vec<PeerId>) -> Self {
+        Self {
+            peers,
+            replication_factor: 1,
+            anti_entropy_interval: Duration::from_secs(30),
+        }
+    }
+
+    pub fn with_replication_factor(mut self, replication_factor: u32) -> Self {
+        self.replication_factor = replication_factor;
+        self
+    }
+
+    pub fn with_anti_entropy_interval(mut self, anti_entropy_interval: Duration) -> Self {
+        self.anti_entropy_interval = anti_entropy_interval;
+        self
+    }
+}
+
+/// A write to one key, as published on `{path}/mutation` and stored by every member
+///
+/// A `None` value is a deletion; it is kept as a tombstone so that
+/// anti-entropy does not bring the key back.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DkvMutation {
+    pub key: String,
+    pub value: Option<Vec<u8>>,
+    /// Number of writes to the key seen from each node, by node ID
+    pub vector_clock: HashMap<String, u64>,
+    /// Node that made the write
+    pub node_id: String,
+}
+
+/// Payload of the `set` action
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DkvSetRequest {
+    pub key: String,
+    pub value: Vec<u8>,
+}
+
+/// Result of the `get` action
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DkvValue {
+    pub value: Option<Vec<u8>>,
+}
+
+/// Merkle tree of a member's entries, one leaf per bucket of keys
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MerkleDigest {
+    pub root: u64,
+    pub buckets: Vec<u64>,
+}
+
+/// Payload of the `entries` action: the buckets whose entries are wanted
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DkvEntriesRequest {
+    pub buckets: Vec<u32>,
+}
+
+/// Entries exchanged during anti-entropy
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DkvEntries {
+    pub entries: Vec<DkvMutation>,
+}
+
+/// How two vector clocks relate
+fn compare_clocks(a: &HashMap<String, u64>, b: &HashMap<String, u64>) -> Option<Ordering> {
+    let mut ordering = Ordering::Equal;
+    for node in a.keys().chain(b.keys()) {
+        let (x, y) = (
+            a.get(node).copied().unwrap_or(0),
+            b.get(node).copied().unwrap_or(0),
+        );
+        match (ordering, x.cmp(&y)) {
+            (_, Ordering::Equal) => {}
+            (Ordering::Equal, other) => ordering = other,
+            (current, other) if current != other => return None,
+            _ => {}
+        }
+    }
+    Some(ordering)
+}
+
+/// FNV-1a, so that digests agree between nodes and builds
+fn fnv1a(hash: u64, bytes: &[u8]) -> u64 {
+    bytes.iter().fold(hash, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+
+fn bucket_of(key: &str) -> usize {
+    (fnv1a(FNV_OFFSET, key.as_bytes()) % DIGEST_BUCKETS as u64) as usize
+}
+
+/// Entries of one member, merged with last-write-wins
+#[derive(Debug, Default)]
+struct DkvState {
+    entries: BTreeMap<String, DkvMutation>,
+}
+
+impl DkvState {
+    /// Apply a mutation received from another node
+    ///
+    /// A mutation whose clock is newer than the stored one replaces it; an
+    /// older or equal one is ignored. Concurrent writes are resolved the same
+    /// way on every node: the write with the larger clock total wins, then
+    /// the one from the larger node ID, and the stored clock becomes the
+    /// element-wise maximum of both. Returns whether the value changed.
+    fn merge(&mut self, incoming: DkvMutation) -> bool {
+        let Some(existing) = self.entries.get_mut(&incoming.key) else {
+            self.entries.insert(incoming.key.clone(), incoming);
+            return true;
+        };
+        match compare_clocks(&existing.vector_clock, &incoming.vector_clock) {
+            Some(Ordering::Less) => {
+                *existing = incoming;
+                true
+            }
+            Some(_) => false,
+            None => {
+                let total = |clock: &HashMap<String, u64>| clock.values().sum::<u64>();
+                let incoming_wins = (total(&incoming.vector_clock), &incoming.node_id)
+                    > (total(&existing.vector_clock), &existing.node_id);
+                for (node, count) in &incoming.vector_clock {
+                    let entry = existing.vector_clock.entry(node.clone()).or_insert(0);
+                    *entry = (*entry).max(*count);
+                }
+                if incoming_wins {
+                    existing.value = incoming.value;
+                    existing.node_id = incoming.node_id;
+                }
+                incoming_wins
+            }
+        }
+    }
+
+    /// Record a local write, advancing this node's entry in the key's clock
+    fn write(&mut self, node_id: &str, key: String, value: Option<Vec<u8>>) -> DkvMutation {
+        let mut vector_clock = self
+            .entries
+            .get(&key)
+            .map(|entry| entry.vector_clock.clone())
+            .unwrap_or_default();
+        *vector_clock.entry(node_id.to_string()).or_insert(0) += 1;
+        let mutation = DkvMutation {
+            key: key.clone(),
+            value,
+            vector_clock,
+            node_id: node_id.to_string(),
+        };
+        self.entries.insert(key, mutation.clone());
+        mutation
+    }
+
+    fn get(&self, key: &str) -> Option<Vec<u8>> {
+        self.entries.get(key).and_then(|entry| entry.value.clone())
+    }
+
+    fn keys(&self) -> Vec<String> {
+        self.entries
+            .values()
+            .filter(|entry| entry.value.is_some())
+            .map(|entry| entry.key.clone())
+            .collect()
+    }
+
+    fn digest(&self) -> MerkleDigest {
+        let mut buckets = vec![FNV_OFFSET; DIGEST_BUCKETS];
+        // Entries are visited in key order, so equal contents hash equally
+        for entry in self.entries.values() {
+            let leaf = &mut buckets[bucket_of(&entry.key)];
+            *leaf = fnv1a(*leaf, entry.key.as_bytes());
+            let mut clock: Vec<_> = entry.vector_clock.iter().collect();
+            clock.sort();
+            for (node, count) in clock {
+                *leaf = fnv1a(*leaf, node.as_bytes());
+                *leaf = fnv1a(*leaf, &count.to_le_bytes());
+            }
+            *leaf = fnv1a(*leaf, entry.node_id.as_bytes());
+            match &entry.value {
+                Some(value) => *leaf = fnv1a(fnv1a(*leaf, &[1]), value),
+                None => *leaf = fnv1a(*leaf, &[0]),
+            }
+        }
+        let root = buckets
+            .iter()
+            .fold(FNV_OFFSET, |root, leaf| fnv1a(root, &leaf.to_le_bytes()));
+        MerkleDigest { root, buckets }
+    }
+
+    fn entries_in(&self, buckets: &[u32]) -> Vec<DkvMutation> {
+        self.entries
+            .values()
+            .filter(|entry| buckets.contains(&(bucket_of(&entry.key) as u32)))
+            .cloned()
+            .collect()
+    }
+}
+
+/// Key-value store replicated across nodes with eventual consistency
+///
+/// INTENTION: Share small amounts of state, such as configuration or
+/// membership data, between the nodes of a cluster without a central store.
+/// Reads are served from the local replica. Every write is published as a
+/// `mutation` event (a `DkvMutation`) on the service path and merged by the
+/// other members with last-write-wins over vector clocks. Writes missed while
+/// a member was unreachable are repaired by anti-entropy: every
+/// `anti_entropy_interval`, a member compares Merkle digests with
+/// `replication_factor` random peers and both sides exchange the entries of
+/// the buckets that differ.
+///
+/// Like `RaftService`, the anti-entropy actions are registered under the
+/// member's peer ID, e.g. `dkv/digest/{peer_id}`, so that requests reach that
+/// exact node.
+///
+/// Actions:
+/// - `set(DkvSetRequest) -> DkvMutation`
+/// - `get(String) -> DkvValue`
+/// - `delete(String) -> DkvMutation`
+/// - `keys() -> Vec<String>`
+/// - `digest/{peer_id}() -> MerkleDigest`
+/// - `entries/{peer_id}(DkvEntriesRequest) -> DkvEntries`
+/// - `merge/{peer_id}(DkvEntries) -> i64` (number of values that changed)
+pub struct DistributedKvService {
+    pub name: String,
+    pub path: String,
+    pub version: String,
+    pub description: String,
+    pub config: DistributedKvConfig,
+    state: Arc<Mutex<DkvState>>,
+    /// Peer ID of the local node, known once the service is initialized
+    node_id: Arc<std::sync::RwLock<String>>,
+    anti_entropy_task: Arc<Mutex<Option<JoinHandle<()>>>>,
+    network_id: Option<String>,
+}
+
+impl Clone for DistributedKvService {
+    fn clone(&self) -> Self {
+        Self {
+            name: self.name.clone(),
+            path: self.path.clone(),
+            version: self.version.clone(),
+            description: self.description.clone(),
+            config: self.config.clone(),
+            state: self.state.clone(),
+            node_id: self.node_id.clone(),
+            anti_entropy_task: self.anti_entropy_task.clone(),
+            network_id: self.network_id.clone(),
+        }
+    }
+}
+
+impl DistributedKvService {
+    pub fn new(name: String, path: String, config: DistributedKvConfig) -> Self {
+        Self {
+            name,
+            path,
+            version: "0.0.1".to_string(),
+            description: "Distributed key-value store service".to_string(),
+            config,
+            state: Arc::new(Mutex::new(DkvState::default())),
+            node_id: Arc::new(std::sync::RwLock::new(String::new())),
+            anti_entropy_task: Arc::new(Mutex::new(None)),
+            network_id: None,
+        }
+    }
+
+    fn node_id(&self) -> String {
+        self.node_id
+            .read()
+            .map(|node_id| node_id.clone())
+            .unwrap_or_default()
+    }
+
+    /// Store a local write and publish it to the other members
+    ///
+    /// Returns the published mutation.
+    async fn write(
+        &self,
+        context: &RequestContext,
+        key: String,
+        value: Option<Vec<u8>>,
+    ) -> Result<ArcValue> {
+        let mutation = self.state.lock().await.write(&self.node_id(), key, value);
+        context
+            .publish("mutation", Some(ArcValue::from_struct(mutation.clone())))
+            .await?;
+        Ok(ArcValue::from_struct(mutation))
+    }
+
+    /// Compare digests with one peer and exchange the entries that differ
+    async fn exchange_with(&self, context: &LifecycleContext, peer: &PeerId) -> Result<()> {
+        let path = |action: &str| format!("{}/{action}/{}", self.path, peer.public_key);
+        let remote: MerkleDigest = context.request(path("digest"), None::<ArcValue>).await?;
+        let local = self.state.lock().await.digest();
+        if remote.root == local.root {
+            return Ok(());
+        }
+
+        let buckets: Vec<u32> = (0..DIGEST_BUCKETS)
+            .filter(|index| remote.buckets.get(*index) != local.buckets.get(*index))
+            .map(|index| index as u32)
+            .collect();
+        let theirs: DkvEntries = context
+            .request(
+                path("entries"),
+                Some(ArcValue::from_struct(DkvEntriesRequest {
+                    buckets: buckets.clone(),
+                })),
+            )
+            .await?;
+        let ours = {
+            let mut state = self.state.lock().await;
+            let ours = state.entries_in(&buckets);
+            for entry in theirs.entries {
+                state.merge(entry);
+            }
+            ours
+        };
+        context.debug(format!(
+            "Anti-entropy with {peer}: {} bucket(s) differed",
+            buckets.len()
+        ));
+        context
+            .request::<ArcValue, i64>(
+                path("merge"),
+                Some(ArcValue::from_struct(DkvEntries { entries: ours })),
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// Run anti-entropy rounds until the service stops
+    async fn run_anti_entropy(self, context: LifecycleContext) {
+        loop {
+            tokio::time::sleep(self.config.anti_entropy_interval).await;
+            let peers: Vec<PeerId> = self
+                .config
+                .peers
+                .choose_multiple(&mut rand::rng(), self.config.replication_factor as usize)
+                .cloned()
+                .collect();
+            for peer in peers {
+                if let Err(e) = self.exchange_with(&context, &peer).await {
+                    context.debug(format!("Anti-entropy with {peer} failed: {e}"));
+                }
+            }
+        }
+    }
+}
+
+/// Read the struct payload of an action
+fn payload<T>(params: Option<ArcValue>, action: &str) -> Result<T>
+where
+    T: 'static + Clone + Send + Sync + std::fmt::Debug + for<'de> Deserialize<'de>,
+{
+    params
+        .ok_or_else(|| anyhow!("Missing payload for '{action}'"))?
+        .as_type::<T>()
+        .map_err(|e| anyhow!("Invalid payload for '{action}': {e}"))
+}
+
+#[async_trait]
+impl AbstractService for DistributedKvService {
+    fn name(&self) -> &str {
+        &self.name
+    }
+    fn version(&self) -> &str {
+        &self.version
+    }
+    fn path(&self) -> &str {
+        &self.path
+    }
+    fn description(&self) -> &str {
+        &self.description
+    }
+    fn network_id(&self) -> Option<String> {
+        self.network_id.clone()
+    }
+    fn set_network_id(&mut self, network_id: String) {
+        self.network_id = Some(network_id);
+    }
+
+    async fn init(&self, context: LifecycleContext) -> Result<()> {
+        context.info(format!("Initializing DistributedKvService: {}", self.name));
+        let node_id = context.peer_id().public_key;
+        *self
+            .node_id
+            .write()
+            .map_err(|e| anyhow!("Failed to acquire write lock on node_id: {}", e))? =
+            node_id.clone();
+
+        {
+            let mut serializer = context.serializer.write().await;
+            serializer.register::<DkvMutation>()?;
+            serializer.register::<DkvSetRequest>()?;
+            serializer.register::<DkvValue>()?;
+            serializer.register::<MerkleDigest>()?;
+            serializer.register::<DkvEntriesRequest>()?;
+            serializer.register::<DkvEntries>()?;
+        }
+        let service_arc = Arc::new(self.clone());
+
+        let set_handler = {
+            let s_arc = service_arc.clone();
+            Arc::new(move |params: Option<ArcValue>, ctx: RequestContext| {
+                let service = s_arc.clone();
+                Box::pin(async move {
+                    let request: DkvSetRequest = payload(params, "set")?;
+                    service.write(&ctx, request.key, Some(request.value)).await
+                }) as ServiceFuture
+            })
+        };
+        context.register_action("set", set_handler).await?;
+
+        let get_handler = {
+            let s_arc = service_arc.clone();
+            Arc::new(move |params: Option<ArcValue>, _ctx: RequestContext| {
+                let service = s_arc.clone();
+                Box::pin(async move {
+                    let key: String = payload(params, "get")?;
+                    let value = service.state.lock().await.get(&key);
+                    Ok(ArcValue::from_struct(DkvValue { value }))
+                }) as ServiceFuture
+            })
+        };
+        context.register_action("get", get_handler).await?;
+
+        let delete_handler = {
+            let s_arc = service_arc.clone();
+            Arc::new(move |params: Option<ArcValue>, ctx: RequestContext| {
+                let service = s_arc.clone();
+                Box::pin(async move {
+                    let key: String = payload(params, "delete")?;
+                    service.write(&ctx, key, None).await
+                }) as ServiceFuture
+            })
+        };
+        context.register_action("delete", delete_handler).await?;
+
+        let keys_handler = {
+            let s_arc = service_arc.clone();
+            Arc::new(move |_params: Option<ArcValue>, _ctx: RequestContext| {
+                let service = s_arc.clone();
+                Box::pin(async move { Ok(ArcValue::new_list(service.state.lock().await.keys())) })
+                    as ServiceFuture
+            })
+        };
+        context.register_action("keys", keys_handler).await?;
+
+        let digest_handler = {
+            let s_arc = service_arc.clone();
+            Arc::new(move |_params: Option<ArcValue>, _ctx: RequestContext| {
+                let service = s_arc.clone();
+                Box::pin(
+                    async move { Ok(ArcValue::from_struct(service.state.lock().await.digest())) },
+                ) as ServiceFuture
+            })
+        };
+        context
+            .register_action(format!("digest/{node_id}"), digest_handler)
+            .await?;
+
+        let entries_handler = {
+            let s_arc = service_arc.clone();
+            Arc::new(move |params: Option<ArcValue>, _ctx: RequestContext| {
+                let service = s_arc.clone();
+                Box::pin(async move {
+                    let request: DkvEntriesRequest = payload(params, "entries")?;
+                    let entries = service.state.lock().await.entries_in(&request.buckets);
+                    Ok(ArcValue::from_struct(DkvEntries { entries }))
+                }) as ServiceFuture
+            })
+        };
+        context
+            .register_action(format!("entries/{node_id}"), entries_handler)
+            .await?;
+
+        let merge_handler = {
+            let s_arc = service_arc.clone();
+            Arc::new(move |params: Option<ArcValue>, _ctx: RequestContext| {
+                let service = s_arc.clone();
+                Box::pin(async move {
+                    let request: DkvEntries = payload(params, "merge")?;
+                    let mut state = service.state.lock().await;
+                    let changed = request
+                        .entries
+                        .into_iter()
+                        .filter(|entry| state.merge(entry.clone()))
+                        .count();
+                    Ok(ArcValue::new_primitive(changed as i64))
+                }) as ServiceFuture
+            })
+        };
+        context
+            .register_action(format!("merge/{node_id}"), merge_handler)
+            .await?;
+
+        let mutation_handler = {
+            let s_arc = service_arc.clone();
+            Box::new(move |_ctx: Arc<EventContext>, data: Option<ArcValue>| {
+                let service = s_arc.clone();
+                Box::pin(async move {
+                    let mutation: DkvMutation = payload(data, "mutation")?;
+                    // Our own writes are already stored
+                    if mutation.node_id != service.node_id() {
+                        service.state.lock().await.merge(mutation);
+                    }
+                    Ok(())
+                }) as Pin<Box<dyn Future<Output = Result<()>> + Send>>
+            })
+        };
+        context
+            .subscribe(format!("{}/mutation", self.path), mutation_handler)
+            .await?;
+
+        context.info(format!(
+            "Actions registered for DistributedKvService: {}",
+            self.name
+        ));
+        Ok(())
+    }
+
+    async fn start(&self, context: LifecycleContext) -> Result<()> {
+        context.info(format!(
+            "DistributedKvService '{}' starting with {} peer(s)",
+            self.name,
+            self.config.peers.len()
+        ));
+        if self.config.peers.is_empty() || self.config.replication_factor == 0 {
+            return Ok(());
+        }
+        let task = tokio::spawn(self.clone().run_anti_entropy(context));
+        if let Some(previous) = self.anti_entropy_task.lock().await.replace(task) {
+            previous.abort();
+        }
+        Ok(())
+    }
+
+    async fn stop(&self, context: LifecycleContext) -> Result<()> {
+        context.info(format!("Stopping DistributedKvService: {}", self.name));
+        if let Some(task) = self.anti_entropy_task.lock().await.take() {
+            task.abort();
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,327 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use notify::event::{ModifyKind, RenameMode};
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use runar_common::logging::Logger;
+use runar_common::types::ArcValue;
+use runar_node::services::LifecycleContext;
+use runar_node::AbstractService;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+/// How long to wait before retrying paths that could not be watched
+const WATCH_RETRY_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Kind of filesystem change reported by the file watcher
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WatchEventKind {
+    Created,
+    Modified,
+    Deleted,
+    Renamed { from: String, to: String },
+}
+
+/// Payload of the `file_watcher/changed` event
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileChangedEvent {
+    /// Path affected by the change (the new path for renames)
+    pub path: String,
+    pub kind: WatchEventKind,
+}
+
+/// Configuration for the file watcher service.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct FileWatcherConfig {
+    /// Files or directories to watch
+    pub paths: Vec<PathBuf>,
+    /// Watch directories recursively
+    pub recursive: bool,
+    /// Quiet period used to coalesce bursts of changes (0 publishes immediately)
+    pub debounce_ms: u64,
+}
+
+impl FileWatcherConfig {
+    /// Create a new file watcher config for the given paths
+    pub fn new(paths: Vec<PathBuf>) -> Self {
+        Self {
+            paths,
+            recursive: false,
+            debounce_ms: 0,
+        }
+    }
+
+    pub fn with_recursive(mut self, recursive: bool) -> Self {
+        self.recursive = recursive;
+        self
+    }
+
+    pub fn with_debounce_ms(mut self, debounce_ms: u64) -> Self {
+        self.debounce_ms = debounce_ms;
+        self
+    }
+}
+
+/// Service publishing `changed` events for filesystem changes under the configured paths
+///
+/// INTENTION: Give services that hot-reload configuration or plugins, or tail
+/// log files, a single source of filesystem notifications. Paths that cannot
+/// be watched yet (e.g. they do not exist) are logged and retried instead of
+/// failing the service.
+pub struct FileWatcherService {
+    pub name: String,
+    pub path: String,
+    pub version: String,
+    pub description: String,
+    pub config: FileWatcherConfig,
+    watch_task: Arc<Mutex<Option<JoinHandle<()>>>>,
+    network_id: Option<String>,
+}
+
+impl Clone for FileWatcherService {
+    fn clone(&self) -> Self {
+        Self {
+            name: self.name.clone(),
+            path: self.path.clone(),
+            version: self.version.clone(),
+            description: self.description.clone(),
+            config: self.config.clone(),
+            watch_task: self.watch_task.clone(),
+            network_id: self.network_id.clone(),
+        }
+    }
+}
+
+impl FileWatcherService {
+    pub fn new(name: String, path: String, config: FileWatcherConfig) -> Self {
+        Self {
+            name,
+            path,
+            version: "0.0.1".to_string(),
+            description: "File watcher service".to_string(),
+            config,
+            watch_task: Arc::new(Mutex::new(None)),
+            network_id: None,
+        }
+    }
+}
+
+/// Translate a notify event into the changes published by the service
+fn to_watch_events(event: Event) -> Vec<FileChangedEvent> {
+    let to_string = |path: &PathBuf| path.to_string_lossy().to_string();
+    let each = |kind: WatchEventKind| {
+        event
+            .paths
+            .iter()
+            .map(|path| FileChangedEvent {
+                path: to_string(path),
+                kind: kind.clone(),
+            })
+            .collect()
+    };
+
+    match event.kind {
+        EventKind::Create(_) => each(WatchEventKind::Created),
+        EventKind::Remove(_) => each(WatchEventKind::Deleted),
+        EventKind::Modify(ModifyKind::Name(RenameMode::Both)) if event.paths.len() == 2 => {
+            let from = to_string(&event.paths[0]);
+            let to = to_string(&event.paths[1]);
+            vec![FileChangedEvent {
+                path: to.clone(),
+                kind: WatchEventKind::Renamed { from, to },
+            }]
+        }
+        // A file moved out of (or into) the watched tree only reports one side
+        EventKind::Modify(ModifyKind::Name(RenameMode::From)) => each(WatchEventKind::Deleted),
+        EventKind::Modify(ModifyKind::Name(RenameMode::To)) => each(WatchEventKind::Created),
+        EventKind::Modify(_) => each(WatchEventKind::Modified),
+        _ => Vec::new(),
+    }
+}
+
+/// Merge a change into the set of changes waiting for the debounce window to close
+fn merge_pending(pending: &mut Vec<FileChangedEvent>, change: FileChangedEvent) {
+    if let WatchEventKind::Renamed { from, to } = &change.kind {
+        // The single-sided rename notifications are superseded by the full rename
+        pending.retain(|p| p.path != *from && p.path != *to);
+        pending.push(change);
+        return;
+    }
+
+    match pending.iter_mut().find(|p| p.path == change.path) {
+        // A file created within the window is still reported as created
+        Some(existing)
+            if existing.kind == WatchEventKind::Created
+                && change.kind == WatchEventKind::Modified => {}
+        Some(existing) => existing.kind = change.kind,
+        None => pending.push(change),
+    }
+}
+
+/// Start watching every path not watched yet, returning the ones that failed
+fn watch_paths(
+    watcher: &mut RecommendedWatcher,
+    paths: Vec<PathBuf>,
+    mode: RecursiveMode,
+    logger: &Logger,
+) -> Vec<PathBuf> {
+    paths
+        .into_iter()
+        .filter(|path| match watcher.watch(path, mode) {
+            Ok(()) => {
+                logger.info(format!("Watching {}", path.display()));
+                false
+            }
+            Err(e) => {
+                logger.warn(format!(
+                    "Failed to watch {}: {e}. Retrying in {}ms",
+                    path.display(),
+                    WATCH_RETRY_INTERVAL.as_millis()
+                ));
+                true
+            }
+        })
+        .collect()
+}
+
+async fn publish_changes(context: &LifecycleContext, changes: Vec<FileChangedEvent>) {
+    for change in changes {
+        context.debug(format!("File change: {change:?}"));
+        if let Err(e) = context
+            .publish("changed", Some(ArcValue::from_struct(change)))
+            .await
+        {
+            context.error(format!("Failed to publish file change event: {e}"));
+        }
+    }
+}
+
+/// Watch loop: (re)registers paths, debounces notifications and publishes them
+async fn run_watcher(config: FileWatcherConfig, context: LifecycleContext) {
+    let (tx, mut rx) = mpsc::unbounded_channel::<notify::Result<Event>>();
+    let mut watcher = match notify::recommended_watcher(move |res| {
+        // The receiver only goes away when the service stops
+        let _ = tx.send(res);
+    }) {
+        Ok(watcher) => watcher,
+        Err(e) => {
+            context.error(format!("Failed to create file watcher: {e}"));
+            return;
+        }
+    };
+
+    let mode = if config.recursive {
+        RecursiveMode::Recursive
+    } else {
+        RecursiveMode::NonRecursive
+    };
+    let debounce = Duration::from_millis(config.debounce_ms);
+
+    let mut unwatched = watch_paths(&mut watcher, config.paths, mode, &context.logger);
+    let mut retry = tokio::time::interval(WATCH_RETRY_INTERVAL);
+    let mut pending: Vec<FileChangedEvent> = Vec::new();
+
+    loop {
+        // Only wait for the debounce window while changes are pending
+        let has_pending = !pending.is_empty();
+        let flush = async move {
+            if !has_pending {
+                std::future::pending::<()>().await
+            } else {
+                tokio::time::sleep(debounce).await
+            }
+        };
+
+        tokio::select! {
+            received = rx.recv() => match received {
+                Some(Ok(event)) => {
+                    for change in to_watch_events(event) {
+                        merge_pending(&mut pending, change);
+                    }
+                    if debounce.is_zero() {
+                        publish_changes(&context, std::mem::take(&mut pending)).await;
+                    }
+                }
+                Some(Err(e)) => context.warn(format!("File watcher error: {e}")),
+                None => break,
+            },
+            _ = flush => {
+                publish_changes(&context, std::mem::take(&mut pending)).await;
+            }
+            _ = retry.tick(), if !unwatched.is_empty() => {
+                unwatched = watch_paths(&mut watcher, unwatched, mode, &context.logger);
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl AbstractService for FileWatcherService {
+    fn name(&self) -> &str {
+        &self.name
+    }
+    fn version(&self) -> &str {
+        &self.version
+    }
+    fn path(&self) -> &str {
+        &self.path
+    }
+    fn description(&self) -> &str {
+        &self.description
+    }
+    fn network_id(&self) -> Option<String> {
+        self.network_id.clone()
+    }
+    fn set_network_id(&mut self, network_id: String) {
+        self.network_id = Some(network_id);
+    }
+
+    async fn init(&self, context: LifecycleContext) -> Result<()> {
+        context.info(format!("Initializing FileWatcherService: {}", self.name));
+
+        // registering custom types with the serializer
+        context
+            .serializer
+            .write()
+            .await
+            .register::<FileChangedEvent>()?;
+
+        Ok(())
+    }
+
+    async fn start(&self, context: LifecycleContext) -> Result<()> {
+        context.info(format!(
+            "FileWatcherService '{}' watching {} path(s)",
+            self.name,
+            self.config.paths.len()
+        ));
+
+        let task = tokio::spawn(run_watcher(self.config.clone(), context));
+
+        let mut guard = self
+            .watch_task
+            .lock()
+            .map_err(|e| anyhow::anyhow!("Failed to acquire lock on watch_task: {}", e))?;
+        if let Some(previous) = guard.replace(task) {
+            previous.abort();
+        }
+        Ok(())
+    }
+
+    async fn stop(&self, context: LifecycleContext) -> Result<()> {
+        context.info(format!("Stopping FileWatcherService: {}", self.name));
+        let task = self
+            .watch_task
+            .lock()
+            .map_err(|e| anyhow::anyhow!("Failed to acquire lock on watch_task: {}", e))?
+            .take();
+        if let Some(task) = task {
+            // Aborting drops the watcher, which stops the notify backend
+            task.abort();
+        }
+        Ok(())
+    }
+}
@@ -1,2 +1,9 @@
 pub mod crud_sqlite;
+pub mod dkv;
+pub mod file_watcher;
+pub mod raft;
+pub mod rate_limiter;
+pub mod redis_store;
 pub mod sqlite;
+#[cfg(feature = "wasm")]
+pub mod wasm;
@@ -0,0 +1,541 @@
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use rand::Rng;
+use runar_common::types::ArcValue;
+use runar_node::network::transport::PeerId;
+use runar_node::services::{LifecycleContext, RequestContext, ServiceFuture};
+use runar_node::AbstractService;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+
+/// Configuration of a Raft cluster, as seen from one of its members
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RaftConfig {
+    /// The other members of the cluster; the local node is not listed
+    pub peers: Vec<PeerId>,
+    /// Bounds of the randomized election timeout, in milliseconds
+    pub election_timeout_ms: (u32, u32),
+    /// How often the leader sends heartbeats, in milliseconds
+    pub heartbeat_interval_ms: u32,
+}
+
+impl RaftConfig {
+    /// Create a config with the usual Raft timings (150-300 ms elections, 50 ms heartbeats)
+    pub fn new(peers: Vec<PeerId>) -> Self {
+        Self {
+            peers,
+            election_timeout_ms: (150, 300),
+            heartbeat_interval_ms: 50,
+        }
+    }
+
+    pub fn with_election_timeout_ms(mut self, min: u32, max: u32) -> Self {
+        self.election_timeout_ms = (min, max);
+        self
+    }
+
+    pub fn with_heartbeat_interval_ms(mut self, heartbeat_interval_ms: u32) -> Self {
+        self.heartbeat_interval_ms = heartbeat_interval_ms;
+        self
+    }
+
+    /// Number of members, the local node included
+    fn cluster_size(&self) -> usize {
+        self.peers.len() + 1
+    }
+
+    fn election_timeout(&self) -> Duration {
+        let (min, max) = self.election_timeout_ms;
+        let millis = if min < max {
+            rand::rng().random_range(min..=max)
+        } else {
+            min
+        };
+        Duration::from_millis(u64::from(millis))
+    }
+}
+
+/// Role of a member in the current term
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RaftRole {
+    Follower,
+    Candidate,
+    Leader,
+}
+
+/// Payload of the `request_vote` RPC
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VoteRequest {
+    pub term: u64,
+    pub candidate_id: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VoteResponse {
+    pub term: u64,
+    pub vote_granted: bool,
+}
+
+/// Payload of the `append_entries` RPC; without log replication it is only a heartbeat
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AppendEntriesRequest {
+    pub term: u64,
+    pub leader_id: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AppendEntriesResponse {
+    pub term: u64,
+    pub success: bool,
+}
+
+/// Data of the `leader_elected` event
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LeaderElected {
+    pub leader_id: String,
+    pub term: u64,
+}
+
+/// Result of the `status` action
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RaftStatus {
+    pub node_id: String,
+    pub role: RaftRole,
+    pub term: u64,
+    pub leader_id: Option<String>,
+}
+
+/// Volatile Raft state; terms and votes restart from zero with the node, see
+/// the limitation documented on [`RaftService`]
+struct RaftState {
+    role: RaftRole,
+    current_term: u64,
+    voted_for: Option<String>,
+    leader_id: Option<String>,
+    /// Last time a leader or a granted vote reset the election timeout
+    last_heartbeat: Instant,
+}
+
+impl RaftState {
+    fn new() -> Self {
+        Self {
+            role: RaftRole::Follower,
+            current_term: 0,
+            voted_for: None,
+            leader_id: None,
+            last_heartbeat: Instant::now(),
+        }
+    }
+
+    /// Move to a newer term as a follower
+    fn step_down(&mut self, term: u64) {
+        if term > self.current_term {
+            self.current_term = term;
+            self.voted_for = None;
+            self.leader_id = None;
+        }
+        self.role = RaftRole::Follower;
+    }
+
+    fn handle_vote_request(&mut self, request: &VoteRequest) -> VoteResponse {
+        if request.term > self.current_term {
+            self.step_down(request.term);
+        }
+        let vote_granted = request.term == self.current_term
+            && self
+                .voted_for
+                .as_ref()
+                .is_none_or(|voted_for| *voted_for == request.candidate_id);
+        if vote_granted {
+            self.voted_for = Some(request.candidate_id.clone());
+            self.last_heartbeat = Instant::now();
+        }
+        VoteResponse {
+            term: self.current_term,
+            vote_granted,
+        }
+    }
+
+    fn handle_append_entries(&mut self, request: &AppendEntriesRequest) -> AppendEntriesResponse {
+        if request.term < self.current_term {
+            return AppendEntriesResponse {
+                term: self.current_term,
+                success: false,
+            };
+        }
+        self.step_down(request.term);
+        self.leader_id = Some(request.leader_id.clone());
+        self.last_heartbeat = Instant::now();
+        AppendEntriesResponse {
+            term: self.current_term,
+            success: true,
+        }
+    }
+}
+
+/// Leader election among a fixed set of nodes using Raft
+///
+/// INTENTION: Give distributed applications a leader for tasks such as shard
+/// assignment or coordinated writes. Only leader election is implemented;
+/// there is no log replication, and terms are not persisted across restarts.
+///
+/// Because `current_term` and `voted_for` are lost on restart, a member that
+/// restarts during an election can vote a second time in a term it already
+/// voted in, and two leaders may then be elected for the same term. Keep a
+/// restarted member out of the cluster for at least one election timeout, or
+/// do not rely on a single leader per term, until the state is persisted.
+///
+/// Every member registers its RPC actions under its own peer ID, e.g.
+/// `raft/request_vote/{peer_id}`, so that requests reach that exact node.
+/// The node that wins an election publishes `leader_elected`
+/// (a `LeaderElected`) on the service path.
+///
+/// Actions:
+/// - `request_vote/{peer_id}(VoteRequest) -> VoteResponse`
+/// - `append_entries/{peer_id}(AppendEntriesRequest) -> AppendEntriesResponse`
+/// - `status() -> RaftStatus`
+pub struct RaftService {
+    pub name: String,
+    pub path: String,
+    pub version: String,
+    pub description: String,
+    pub config: RaftConfig,
+    state: Arc<Mutex<RaftState>>,
+    /// Peer ID of the local node, known once the service is initialized
+    node_id: Arc<std::sync::RwLock<String>>,
+    election_task: Arc<Mutex<Option<JoinHandle<()>>>>,
+    network_id: Option<String>,
+}
+
+impl Clone for RaftService {
+    fn clone(&self) -> Self {
+        Self {
+            name: self.name.clone(),
+            path: self.path.clone(),
+            version: self.version.clone(),
+            description: self.description.clone(),
+            config: self.config.clone(),
+            state: self.state.clone(),
+            node_id: self.node_id.clone(),
+            election_task: self.election_task.clone(),
+            network_id: self.network_id.clone(),
+        }
+    }
+}
+
+impl RaftService {
+    pub fn new(name: String, path: String, config: RaftConfig) -> Self {
+        Self {
+            name,
+            path,
+            version: "0.0.1".to_string(),
+            description: "Raft leader election service".to_string(),
+            config,
+            state: Arc::new(Mutex::new(RaftState::new())),
+            node_id: Arc::new(std::sync::RwLock::new(String::new())),
+            election_task: Arc::new(Mutex::new(None)),
+            network_id: None,
+        }
+    }
+
+    fn node_id(&self) -> String {
+        self.node_id
+            .read()
+            .map(|node_id| node_id.clone())
+            .unwrap_or_default()
+    }
+
+    async fn status(&self) -> RaftStatus {
+        let state = self.state.lock().await;
+        RaftStatus {
+            node_id: self.node_id(),
+            role: state.role,
+            term: state.current_term,
+            leader_id: state.leader_id.clone(),
+        }
+    }
+
+    /// Send an RPC to the member with the given peer ID
+    ///
+    /// Unanswered RPCs count as refusals, so a single slow peer cannot hold
+    /// up an election or a heartbeat round.
+    async fn call_peer<P, T>(
+        &self,
+        context: &LifecycleContext,
+        peer: &PeerId,
+        rpc: &str,
+        payload: P,
+    ) -> Option<T>
+    where
+        P: 'static + Serialize + for<'de> Deserialize<'de> + Clone + Send + Sync + std::fmt::Debug,
+        T: 'static + Send + Sync + Clone + std::fmt::Debug + for<'de> Deserialize<'de>,
+    {
+        let path = format!("{}/{rpc}/{}", self.path, peer.public_key);
+        let timeout = Duration::from_millis(u64::from(self.config.election_timeout_ms.0));
+        match tokio::time::timeout(
+            timeout,
+            context.request::<ArcValue, T>(path, Some(ArcValue::from_struct(payload))),
+        )
+        .await
+        {
+            Ok(Ok(response)) => Some(response),
+            Ok(Err(e)) => {
+                context.debug(format!("Raft {rpc} to {peer} failed: {e}"));
+                None
+            }
+            Err(_) => None,
+        }
+    }
+
+    /// Become a candidate for the next term and ask the peers for their votes
+    async fn run_election(&self, context: &LifecycleContext) -> Result<()> {
+        let node_id = self.node_id();
+        let term = {
+            let mut state = self.state.lock().await;
+            state.current_term += 1;
+            state.role = RaftRole::Candidate;
+            state.voted_for = Some(node_id.clone());
+            state.leader_id = None;
+            state.last_heartbeat = Instant::now();
+            state.current_term
+        };
+        context.debug(format!("Starting Raft election for term {term}"));
+
+        let request = VoteRequest {
+            term,
+            candidate_id: node_id.clone(),
+        };
+        let responses = futures::future::join_all(self.config.peers.iter().map(|peer| {
+            self.call_peer::<VoteRequest, VoteResponse>(
+                context,
+                peer,
+                "request_vote",
+                request.clone(),
+            )
+        }))
+        .await;
+
+        let mut state = self.state.lock().await;
+        if state.current_term != term || state.role != RaftRole::Candidate {
+            // A newer term or a leader showed up while votes were collected
+            return Ok(());
+        }
+        let mut votes = 1; // Our own vote
+        for response in responses.into_iter().flatten() {
+            if response.term > state.current_term {
+                state.step_down(response.term);
+                return Ok(());
+            }
+            if response.vote_granted {
+                votes += 1;
+            }
+        }
+        if votes * 2 <= self.config.cluster_size() {
+            return Ok(());
+        }
+
+        state.role = RaftRole::Leader;
+        state.leader_id = Some(node_id.clone());
+        drop(state);
+        context.info(format!(
+            "Elected Raft leader for term {term} with {votes} of {} votes",
+            self.config.cluster_size()
+        ));
+        context
+            .publish(
+                "leader_elected",
+                Some(ArcValue::from_struct(LeaderElected {
+                    leader_id: node_id,
+                    term,
+                })),
+            )
+            .await
+    }
+
+    /// Assert leadership to every peer, stepping down if one has a newer term
+    async fn send_heartbeats(&self, context: &LifecycleContext) {
+        let term = self.state.lock().await.current_term;
+        let request = AppendEntriesRequest {
+            term,
+            leader_id: self.node_id(),
+        };
+        let responses = futures::future::join_all(self.config.peers.iter().map(|peer| {
+            self.call_peer::<AppendEntriesRequest, AppendEntriesResponse>(
+                context,
+                peer,
+                "append_entries",
+                request.clone(),
+            )
+        }))
+        .await;
+
+        let newest_term = responses
+            .into_iter()
+            .flatten()
+            .map(|response| response.term)
+            .max()
+            .unwrap_or(term);
+        let mut state = self.state.lock().await;
+        if newest_term > state.current_term {
+            context.info(format!(
+                "Stepping down as Raft leader: a peer is in term {newest_term}"
+            ));
+            state.step_down(newest_term);
+        }
+    }
+
+    /// Drive elections and heartbeats until the service stops
+    async fn run(self, context: LifecycleContext) {
+        let heartbeat_interval =
+            Duration::from_millis(u64::from(self.config.heartbeat_interval_ms));
+        loop {
+            let (role, last_heartbeat) = {
+                let state = self.state.lock().await;
+                (state.role, state.last_heartbeat)
+            };
+            if role == RaftRole::Leader {
+                self.send_heartbeats(&context).await;
+                tokio::time::sleep(heartbeat_interval).await;
+                continue;
+            }
+
+            let deadline = last_heartbeat + self.config.election_timeout();
+            if Instant::now() < deadline {
+                tokio::time::sleep_until(deadline).await;
+                // A heartbeat or vote may have pushed the deadline back
+                if self.state.lock().await.last_heartbeat != last_heartbeat {
+                    continue;
+                }
+            }
+            if let Err(e) = self.run_election(&context).await {
+                context.warn(format!("Failed to publish the Raft election result: {e}"));
+            }
+        }
+    }
+}
+
+/// Read the struct payload of an RPC action
+fn rpc_payload<T>(params: Option<ArcValue>, action: &str) -> Result<T>
+where
+    T: 'static + Clone + Send + Sync + std::fmt::Debug + for<'de> Deserialize<'de>,
+{
+    params
+        .ok_or_else(|| anyhow!("Missing payload for '{action}'"))?
+        .as_type::<T>()
+        .map_err(|e| anyhow!("Invalid payload for '{action}': {e}"))
+}
+
+#[async_trait]
+impl AbstractService for RaftService {
+    fn name(&self) -> &str {
+        &self.name
+    }
+    fn version(&self) -> &str {
+        &self.version
+    }
+    fn path(&self) -> &str {
+        &self.path
+    }
+    fn description(&self) -> &str {
+        &self.description
+    }
+    fn network_id(&self) -> Option<String> {
+        self.network_id.clone()
+    }
+    fn set_network_id(&mut self, network_id: String) {
+        self.network_id = Some(network_id);
+    }
+
+    async fn init(&self, context: LifecycleContext) -> Result<()> {
+        context.info(format!("Initializing RaftService: {}", self.name));
+        let node_id = context.peer_id().public_key;
+        *self
+            .node_id
+            .write()
+            .map_err(|e| anyhow!("Failed to acquire write lock on node_id: {}", e))? =
+            node_id.clone();
+
+        {
+            let mut serializer = context.serializer.write().await;
+            serializer.register::<VoteRequest>()?;
+            serializer.register::<VoteResponse>()?;
+            serializer.register::<AppendEntriesRequest>()?;
+            serializer.register::<AppendEntriesResponse>()?;
+            serializer.register::<LeaderElected>()?;
+            serializer.register::<RaftStatus>()?;
+        }
+        let service_arc = Arc::new(self.clone());
+
+        let request_vote_handler = {
+            let s_arc = service_arc.clone();
+            Arc::new(move |params: Option<ArcValue>, _ctx: RequestContext| {
+                let service = s_arc.clone();
+                Box::pin(async move {
+                    let request: VoteRequest = rpc_payload(params, "request_vote")?;
+                    let response = service.state.lock().await.handle_vote_request(&request);
+                    Ok(ArcValue::from_struct(response))
+                }) as ServiceFuture
+            })
+        };
+        context
+            .register_action(format!("request_vote/{node_id}"), request_vote_handler)
+            .await?;
+
+        let append_entries_handler = {
+            let s_arc = service_arc.clone();
+            Arc::new(move |params: Option<ArcValue>, _ctx: RequestContext| {
+                let service = s_arc.clone();
+                Box::pin(async move {
+                    let request: AppendEntriesRequest = rpc_payload(params, "append_entries")?;
+                    let response = service.state.lock().await.handle_append_entries(&request);
+                    Ok(ArcValue::from_struct(response))
+                }) as ServiceFuture
+            })
+        };
+        context
+            .register_action(format!("append_entries/{node_id}"), append_entries_handler)
+            .await?;
+
+        let status_handler = {
+            let s_arc = service_arc.clone();
+            Arc::new(move |_params: Option<ArcValue>, _ctx: RequestContext| {
+                let service = s_arc.clone();
+                Box::pin(async move { Ok(ArcValue::from_struct(service.status().await)) })
+                    as ServiceFuture
+            })
+        };
+        context.register_action("status", status_handler).await?;
+
+        context.info(format!("Actions registered for RaftService: {}", self.name));
+        Ok(())
+    }
+
+    async fn start(&self, context: LifecycleContext) -> Result<()> {
+        context.info(format!(
+            "RaftService '{}' starting with {} peer(s)",
+            self.name,
+            self.config.peers.len()
+        ));
+        self.state.lock().await.last_heartbeat = Instant::now();
+        let task = tokio::spawn(self.clone().run(context));
+        if let Some(previous) = self.election_task.lock().await.replace(task) {
+            previous.abort();
+        }
+        Ok(())
+    }
+
+    async fn stop(&self, context: LifecycleContext) -> Result<()> {
+        context.info(format!("Stopping RaftService: {}", self.name));
+        if let Some(task) = self.election_task.lock().await.take() {
+            task.abort();
+        }
+        let mut state = self.state.lock().await;
+        state.role = RaftRole::Follower;
+        state.leader_id = None;
+        Ok(())
+    }
+}
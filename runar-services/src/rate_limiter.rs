@@ -0,0 +1,416 @@
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use governor::clock::{Clock, DefaultClock};
+use governor::middleware::StateInformationMiddleware;
+use governor::state::{InMemoryState, NotKeyed};
+use governor::{Quota, RateLimiter};
+use runar_common::types::ArcValue;
+use runar_node::services::{LifecycleContext, RequestContext, ServiceFuture};
+use runar_node::AbstractService;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::num::NonZeroU32;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+type KeyLimiter = RateLimiter<NotKeyed, InMemoryState, DefaultClock, StateInformationMiddleware>;
+
+/// Number of buckets below which idle ones are not looked for
+const MIN_SWEEP_SIZE: usize = 64;
+
+/// Size and refill rate of a token bucket
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TokenBucketConfig {
+    /// Tokens the bucket holds when full
+    pub capacity: u64,
+    /// Tokens added back per second
+    pub refill_per_second: u64,
+}
+
+impl TokenBucketConfig {
+    pub fn new(capacity: u64, refill_per_second: u64) -> Self {
+        Self {
+            capacity,
+            refill_per_second,
+        }
+    }
+
+    /// Time an empty bucket takes to fill up again
+    fn refill_time(&self) -> Duration {
+        Duration::from_secs_f64(self.capacity as f64 / self.refill_per_second.max(1) as f64)
+    }
+
+    fn quota(&self) -> Result<Quota> {
+        let non_zero = |value: u64, field: &str| {
+            u32::try_from(value)
+                .ok()
+                .and_then(NonZeroU32::new)
+                .ok_or_else(|| anyhow!("{field} must be between 1 and {}", u32::MAX))
+        };
+        let capacity = non_zero(self.capacity, "capacity")?;
+        let refill_per_second = non_zero(self.refill_per_second, "refill_per_second")?;
+        Ok(Quota::per_second(refill_per_second).allow_burst(capacity))
+    }
+}
+
+/// Configuration for the rate limiter service.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RateLimiterConfig {
+    /// Bucket used for keys without an override
+    pub default_rate: TokenBucketConfig,
+    /// Buckets for specific keys
+    pub overrides: HashMap<String, TokenBucketConfig>,
+}
+
+impl RateLimiterConfig {
+    /// Create a new config applying `default_rate` to every key
+    pub fn new(default_rate: TokenBucketConfig) -> Self {
+        Self {
+            default_rate,
+            overrides: HashMap::new(),
+        }
+    }
+
+    pub fn with_override(mut self, key: impl Into<String>, rate: TokenBucketConfig) -> Self {
+        self.overrides.insert(key.into(), rate);
+        self
+    }
+
+    fn rate_for(&self, key: &str) -> &TokenBucketConfig {
+        self.overrides.get(key).unwrap_or(&self.default_rate)
+    }
+}
+
+/// Result of the `check` and `consume` actions
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RateLimitResult {
+    /// Whether the tokens were granted (and taken from the bucket)
+    pub allowed: bool,
+    /// Tokens left in the bucket
+    pub remaining: u64,
+    /// Unix time in milliseconds at which the bucket is full again if the
+    /// request was allowed, or at which it would be allowed otherwise
+    pub reset_at: u64,
+}
+
+/// Payload of the `consume` action
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConsumeRequest {
+    pub key: String,
+    pub tokens: u64,
+}
+
+/// Payload of the `rate_limiter/throttled` event
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ThrottledEvent {
+    pub key: String,
+    pub tokens_requested: u64,
+}
+
+/// Bucket of one key
+struct Bucket {
+    limiter: Arc<KeyLimiter>,
+    /// When a token was last asked for
+    last_used: Instant,
+}
+
+/// Buckets of the keys in use
+struct Buckets {
+    by_key: HashMap<String, Bucket>,
+    /// Number of buckets at which idle ones are looked for next
+    sweep_at: usize,
+}
+
+impl Buckets {
+    /// Drop the buckets that had time to fill up again since their last use
+    ///
+    /// Such a bucket is the same as the one created on the key's next use.
+    /// Sweeping again only once the number of buckets doubled keeps the cost
+    /// per new key constant.
+    fn sweep(&mut self, config: &RateLimiterConfig, now: Instant) {
+        self.by_key.retain(|key, bucket| {
+            now.duration_since(bucket.last_used) < config.rate_for(key).refill_time()
+        });
+        self.sweep_at = (self.by_key.len() * 2).max(MIN_SWEEP_SIZE);
+    }
+}
+
+/// Service enforcing token-bucket rate limits on behalf of other services
+///
+/// INTENTION: Give services one place to share rate limits, e.g. per user or
+/// per API key, through regular runar requests. Each key gets its own bucket
+/// (a `governor` limiter) the first time it is used, sized by its override
+/// or by the default rate. Buckets idle long enough to be full again are
+/// dropped as new keys come in, so memory follows the keys in use. Denied
+/// requests publish `throttled` events so limits being hit can be observed.
+///
+/// Actions:
+/// - `check(key: String) -> RateLimitResult`, taking one token
+/// - `consume(key: String, tokens: u64) -> RateLimitResult`, taking all of
+///   `tokens` or none
+/// - `reset(key: String) -> ()`, refilling the key's bucket
+pub struct RateLimiterService {
+    pub name: String,
+    pub path: String,
+    pub version: String,
+    pub description: String,
+    pub config: RateLimiterConfig,
+    limiters: Arc<Mutex<Buckets>>,
+    network_id: Option<String>,
+}
+
+impl Clone for RateLimiterService {
+    fn clone(&self) -> Self {
+        Self {
+            name: self.name.clone(),
+            path: self.path.clone(),
+            version: self.version.clone(),
+            description: self.description.clone(),
+            config: self.config.clone(),
+            limiters: self.limiters.clone(),
+            network_id: self.network_id.clone(),
+        }
+    }
+}
+
+impl RateLimiterService {
+    pub fn new(name: String, path: String, config: RateLimiterConfig) -> Self {
+        Self {
+            name,
+            path,
+            version: "0.0.1".to_string(),
+            description: "Token bucket rate limiter service".to_string(),
+            config,
+            limiters: Arc::new(Mutex::new(Buckets {
+                by_key: HashMap::new(),
+                sweep_at: MIN_SWEEP_SIZE,
+            })),
+            network_id: None,
+        }
+    }
+
+    /// Get the bucket of `key`, creating a full one on first use
+    fn limiter(&self, key: &str) -> Result<Arc<KeyLimiter>> {
+        let mut limiters = self
+            .limiters
+            .lock()
+            .map_err(|_| anyhow!("Rate limiter lock poisoned"))?;
+        let now = Instant::now();
+        if let Some(bucket) = limiters.by_key.get_mut(key) {
+            bucket.last_used = now;
+            return Ok(bucket.limiter.clone());
+        }
+        if limiters.by_key.len() >= limiters.sweep_at {
+            limiters.sweep(&self.config, now);
+        }
+        let quota = self.config.rate_for(key).quota()?;
+        let limiter = Arc::new(RateLimiter::direct(quota).with_middleware());
+        limiters.by_key.insert(
+            key.to_string(),
+            Bucket {
+                limiter: limiter.clone(),
+                last_used: now,
+            },
+        );
+        Ok(limiter)
+    }
+
+    /// Number of buckets currently kept
+    pub fn bucket_count(&self) -> usize {
+        self.limiters
+            .lock()
+            .map_or(0, |limiters| limiters.by_key.len())
+    }
+
+    /// Take `tokens` tokens from the bucket of `key` if it holds that many
+    fn consume(&self, key: &str, tokens: u64) -> Result<RateLimitResult> {
+        let rate = self.config.rate_for(key);
+        let n = u32::try_from(tokens)
+            .ok()
+            .and_then(NonZeroU32::new)
+            .ok_or_else(|| anyhow!("tokens must be between 1 and {}", u32::MAX))?;
+        let limiter = self.limiter(key)?;
+        let now = SystemTime::now();
+
+        match limiter.check_n(n).map_err(|_| {
+            anyhow!(
+                "Cannot consume {tokens} tokens for '{key}': capacity is {}",
+                rate.capacity
+            )
+        })? {
+            Ok(snapshot) => {
+                let remaining = u64::from(snapshot.remaining_burst_capacity());
+                let interval = snapshot.quota().replenish_interval();
+                let refill = interval * (rate.capacity - remaining) as u32;
+                Ok(RateLimitResult {
+                    allowed: true,
+                    remaining,
+                    reset_at: unix_millis(now + refill),
+                })
+            }
+            Err(not_until) => {
+                let wait = not_until.wait_time_from(DefaultClock::default().now());
+                let interval = not_until.quota().replenish_interval();
+                // Tokens still missing for the request to be allowed
+                let missing = wait.as_nanos().div_ceil(interval.as_nanos().max(1)) as u64;
+                Ok(RateLimitResult {
+                    allowed: false,
+                    remaining: tokens.saturating_sub(missing),
+                    reset_at: unix_millis(now + wait),
+                })
+            }
+        }
+    }
+
+    /// Refill the bucket of `key`
+    fn reset(&self, key: &str) -> Result<()> {
+        self.limiters
+            .lock()
+            .map_err(|_| anyhow!("Rate limiter lock poisoned"))?
+            .by_key
+            .remove(key);
+        Ok(())
+    }
+
+    /// Consume tokens, publishing a `throttled` event when denied
+    async fn consume_and_report(
+        &self,
+        ctx: &RequestContext,
+        key: String,
+        tokens: u64,
+    ) -> Result<ArcValue> {
+        let result = self.consume(&key, tokens)?;
+        if !result.allowed {
+            ctx.debug(format!("Rate limit exceeded for '{key}' ({tokens} tokens)"));
+            let event = ThrottledEvent {
+                key,
+                tokens_requested: tokens,
+            };
+            if let Err(e) = ctx
+                .publish("throttled", Some(ArcValue::from_struct(event)))
+                .await
+            {
+                ctx.error(format!("Failed to publish throttled event: {e}"));
+            }
+        }
+        Ok(ArcValue::from_struct(result))
+    }
+}
+
+fn unix_millis(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .unwrap_or(Duration::ZERO)
+        .as_millis() as u64
+}
+
+/// Read the payload of an action
+fn payload<T>(params: Option<ArcValue>, action: &str) -> Result<T>
+where
+    T: 'static + Clone + Send + Sync + std::fmt::Debug + for<'de> Deserialize<'de>,
+{
+    params
+        .ok_or_else(|| anyhow!("Missing payload for '{action}'"))?
+        .as_type::<T>()
+        .map_err(|e| anyhow!("Invalid payload for '{action}': {e}"))
+}
+
+#[async_trait]
+impl AbstractService for RateLimiterService {
+    fn name(&self) -> &str {
+        &self.name
+    }
+    fn version(&self) -> &str {
+        &self.version
+    }
+    fn path(&self) -> &str {
+        &self.path
+    }
+    fn description(&self) -> &str {
+        &self.description
+    }
+    fn network_id(&self) -> Option<String> {
+        self.network_id.clone()
+    }
+    fn set_network_id(&mut self, network_id: String) {
+        self.network_id = Some(network_id);
+    }
+
+    async fn init(&self, context: LifecycleContext) -> Result<()> {
+        context.info(format!("Initializing RateLimiterService: {}", self.name));
+        // Reject invalid buckets now rather than on first use
+        self.config
+            .default_rate
+            .quota()
+            .map_err(|e| anyhow!("Invalid default rate: {e}"))?;
+        for (key, rate) in &self.config.overrides {
+            rate.quota()
+                .map_err(|e| anyhow!("Invalid rate for '{key}': {e}"))?;
+        }
+
+        {
+            let mut serializer = context.serializer.write().await;
+            serializer.register::<RateLimitResult>()?;
+            serializer.register::<ConsumeRequest>()?;
+            serializer.register::<ThrottledEvent>()?;
+        }
+        let service_arc = Arc::new(self.clone());
+
+        let check_handler = {
+            let s_arc = service_arc.clone();
+            Arc::new(move |params: Option<ArcValue>, ctx: RequestContext| {
+                let service = s_arc.clone();
+                Box::pin(async move {
+                    let key: String = payload(params, "check")?;
+                    service.consume_and_report(&ctx, key, 1).await
+                }) as ServiceFuture
+            })
+        };
+        context.register_action("check", check_handler).await?;
+
+        let consume_handler = {
+            let s_arc = service_arc.clone();
+            Arc::new(move |params: Option<ArcValue>, ctx: RequestContext| {
+                let service = s_arc.clone();
+                Box::pin(async move {
+                    let request: ConsumeRequest = payload(params, "consume")?;
+                    service
+                        .consume_and_report(&ctx, request.key, request.tokens)
+                        .await
+                }) as ServiceFuture
+            })
+        };
+        context.register_action("consume", consume_handler).await?;
+
+        let reset_handler = {
+            let s_arc = service_arc.clone();
+            Arc::new(move |params: Option<ArcValue>, _ctx: RequestContext| {
+                let service = s_arc.clone();
+                Box::pin(async move {
+                    let key: String = payload(params, "reset")?;
+                    service.reset(&key)?;
+                    Ok(ArcValue::null())
+                }) as ServiceFuture
+            })
+        };
+        context.register_action("reset", reset_handler).await?;
+
+        context.info(format!(
+            "Actions registered for RateLimiterService: {}",
+            self.name
+        ));
+        Ok(())
+    }
+
+    async fn start(&self, context: LifecycleContext) -> Result<()> {
+        context.info(format!(
+            "RateLimiterService '{}' started successfully.",
+            self.name
+        ));
+        Ok(())
+    }
+
+    async fn stop(&self, context: LifecycleContext) -> Result<()> {
+        context.info(format!("Stopping RateLimiterService: {}", self.name));
+        Ok(())
+    }
+}
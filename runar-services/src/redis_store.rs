@@ -0,0 +1,349 @@
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use redis::aio::ConnectionManager;
+use redis::AsyncCommands;
+use runar_common::types::{ArcValue, SerializerRegistry};
+use runar_node::services::{LifecycleContext, RequestContext, ServiceFuture};
+use runar_node::AbstractService;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+/// Configuration for the Redis key-value service.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RedisServiceConfig {
+    /// Redis connection URL, e.g. `redis://127.0.0.1:6379/0`
+    pub url: String,
+    /// Number of multiplexed connections requests are spread over
+    pub pool_size: u32,
+    /// Prefix added to every key, to share one Redis between several services
+    pub key_prefix: String,
+}
+
+impl RedisServiceConfig {
+    /// Create a new Redis config with a single connection and no key prefix
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            pool_size: 1,
+            key_prefix: String::new(),
+        }
+    }
+
+    pub fn with_pool_size(mut self, pool_size: u32) -> Self {
+        self.pool_size = pool_size;
+        self
+    }
+
+    pub fn with_key_prefix(mut self, key_prefix: impl Into<String>) -> Self {
+        self.key_prefix = key_prefix.into();
+        self
+    }
+}
+
+/// Service exposing a Redis server as a shared cache
+///
+/// INTENTION: Let services cache values in Redis through regular runar
+/// requests, without depending on the Redis client. Values are stored as the
+/// bytes produced by the node's `SerializerRegistry`, so any `ArcValue` can be
+/// cached. Counters written by `increment` are plain Redis integers; `get`
+/// returns them as `i64`.
+///
+/// Actions:
+/// - `get(key: String) -> Option<ArcValue>`
+/// - `set(key: String, value: ArcValue, ttl_secs: Option<u64>) -> ()`
+/// - `delete(key: String) -> bool`
+/// - `exists(key: String) -> bool`
+/// - `increment(key: String, by: i64) -> i64`
+pub struct RedisService {
+    pub name: String,
+    pub path: String,
+    pub version: String,
+    pub description: String,
+    pub config: RedisServiceConfig,
+    connections: Arc<RwLock<Vec<ConnectionManager>>>,
+    next_connection: Arc<AtomicUsize>,
+    network_id: Option<String>,
+}
+
+impl Clone for RedisService {
+    fn clone(&self) -> Self {
+        Self {
+            name: self.name.clone(),
+            path: self.path.clone(),
+            version: self.version.clone(),
+            description: self.description.clone(),
+            config: self.config.clone(),
+            connections: self.connections.clone(),
+            next_connection: self.next_connection.clone(),
+            network_id: self.network_id.clone(),
+        }
+    }
+}
+
+impl RedisService {
+    pub fn new(name: String, path: String, config: RedisServiceConfig) -> Self {
+        Self {
+            name,
+            path,
+            version: "0.0.1".to_string(),
+            description: "Redis key-value service".to_string(),
+            config,
+            connections: Arc::new(RwLock::new(Vec::new())),
+            next_connection: Arc::new(AtomicUsize::new(0)),
+            network_id: None,
+        }
+    }
+
+    /// Pick the next connection of the pool (round-robin)
+    async fn connection(&self) -> Result<ConnectionManager> {
+        let connections = self.connections.read().await;
+        if connections.is_empty() {
+            return Err(anyhow!("RedisService '{}' is not started", self.name));
+        }
+        let index = self.next_connection.fetch_add(1, Ordering::Relaxed) % connections.len();
+        Ok(connections[index].clone())
+    }
+
+    fn prefixed(&self, key: &str) -> String {
+        format!("{}{key}", self.config.key_prefix)
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>> {
+        let mut conn = self.connection().await?;
+        Ok(conn.get(self.prefixed(key)).await?)
+    }
+
+    async fn set(&self, key: &str, bytes: Vec<u8>, ttl_secs: Option<u64>) -> Result<()> {
+        let mut conn = self.connection().await?;
+        let key = self.prefixed(key);
+        match ttl_secs {
+            Some(ttl_secs) => conn.set_ex::<_, _, ()>(key, bytes, ttl_secs).await?,
+            None => conn.set::<_, _, ()>(key, bytes).await?,
+        }
+        Ok(())
+    }
+
+    async fn delete(&self, key: &str) -> Result<bool> {
+        let mut conn = self.connection().await?;
+        let removed: i64 = conn.del(self.prefixed(key)).await?;
+        Ok(removed > 0)
+    }
+
+    async fn exists(&self, key: &str) -> Result<bool> {
+        let mut conn = self.connection().await?;
+        Ok(conn.exists(self.prefixed(key)).await?)
+    }
+
+    async fn increment(&self, key: &str, by: i64) -> Result<i64> {
+        let mut conn = self.connection().await?;
+        Ok(conn.incr(self.prefixed(key), by).await?)
+    }
+}
+
+/// Decode a stored value, reading `increment` counters as `i64`
+///
+/// Serialized values start with a category byte (0x01..=0x07), so they can
+/// never be mistaken for the ASCII digits Redis uses for integers.
+fn decode_value(serializer: &SerializerRegistry, bytes: Vec<u8>) -> Result<ArcValue> {
+    if let Some(counter) = std::str::from_utf8(&bytes)
+        .ok()
+        .and_then(|s| s.parse::<i64>().ok())
+    {
+        return Ok(ArcValue::new_primitive(counter));
+    }
+    serializer.deserialize_value(Arc::from(bytes))
+}
+
+/// Get a required parameter from the request payload
+///
+/// Like the `#[action]` macro, the payload is a map of parameter names; an
+/// action taking only `key` also accepts the key directly.
+fn required_param<T>(params: &Option<ArcValue>, name: &str, action: &str) -> Result<T>
+where
+    T: 'static + Clone + Send + Sync + std::fmt::Debug + for<'de> Deserialize<'de>,
+{
+    optional_param(params, name, action)?
+        .ok_or_else(|| anyhow!("Missing required parameter '{name}' for '{action}'"))
+}
+
+fn optional_param<T>(params: &Option<ArcValue>, name: &str, action: &str) -> Result<Option<T>>
+where
+    T: 'static + Clone + Send + Sync + std::fmt::Debug + for<'de> Deserialize<'de>,
+{
+    let Some(mut params) = params.clone() else {
+        return Ok(None);
+    };
+    match params.as_map_ref::<String, ArcValue>() {
+        Ok(map) => match map.get(name) {
+            Some(value) if !value.is_null() => value
+                .clone()
+                .as_type::<T>()
+                .map(Some)
+                .map_err(|e| anyhow!("Invalid parameter '{name}' for '{action}': {e}")),
+            _ => Ok(None),
+        },
+        Err(_) if name == "key" => params
+            .as_type::<T>()
+            .map(Some)
+            .map_err(|e| anyhow!("Invalid parameter '{name}' for '{action}': {e}")),
+        Err(e) => Err(anyhow!(
+            "Invalid payload for '{action}'. Expected a map of parameters: {e}"
+        )),
+    }
+}
+
+/// The `value` parameter is an `ArcValue` itself and is taken as is
+fn value_param(params: &Option<ArcValue>) -> Result<ArcValue> {
+    let mut params = params
+        .clone()
+        .ok_or_else(|| anyhow!("Missing payload for 'set'"))?;
+    let map: Arc<HashMap<String, ArcValue>> = params
+        .as_map_ref()
+        .map_err(|e| anyhow!("Invalid payload for 'set'. Expected a map of parameters: {e}"))?;
+    map.get("value")
+        .cloned()
+        .ok_or_else(|| anyhow!("Missing required parameter 'value' for 'set'"))
+}
+
+#[async_trait]
+impl AbstractService for RedisService {
+    fn name(&self) -> &str {
+        &self.name
+    }
+    fn version(&self) -> &str {
+        &self.version
+    }
+    fn path(&self) -> &str {
+        &self.path
+    }
+    fn description(&self) -> &str {
+        &self.description
+    }
+    fn network_id(&self) -> Option<String> {
+        self.network_id.clone()
+    }
+    fn set_network_id(&mut self, network_id: String) {
+        self.network_id = Some(network_id);
+    }
+
+    async fn init(&self, context: LifecycleContext) -> Result<()> {
+        context.info(format!("Initializing RedisService: {}", self.name));
+        let service_arc = Arc::new(self.clone());
+
+        let get_handler = {
+            let s_arc = service_arc.clone();
+            let serializer = context.serializer.clone();
+            Arc::new(move |params: Option<ArcValue>, _ctx: RequestContext| {
+                let service = s_arc.clone();
+                let serializer = serializer.clone();
+                Box::pin(async move {
+                    let key: String = required_param(&params, "key", "get")?;
+                    match service.get(&key).await? {
+                        Some(bytes) => decode_value(&*serializer.read().await, bytes),
+                        None => Ok(ArcValue::null()),
+                    }
+                }) as ServiceFuture
+            })
+        };
+        context.register_action("get", get_handler).await?;
+
+        let set_handler = {
+            let s_arc = service_arc.clone();
+            let serializer = context.serializer.clone();
+            Arc::new(move |params: Option<ArcValue>, _ctx: RequestContext| {
+                let service = s_arc.clone();
+                let serializer = serializer.clone();
+                Box::pin(async move {
+                    let key: String = required_param(&params, "key", "set")?;
+                    let value = value_param(&params)?;
+                    let ttl_secs: Option<u64> = optional_param(&params, "ttl_secs", "set")?;
+                    let bytes = serializer.read().await.serialize_value(&value)?.to_vec();
+                    service.set(&key, bytes, ttl_secs).await?;
+                    Ok(ArcValue::null())
+                }) as ServiceFuture
+            })
+        };
+        context.register_action("set", set_handler).await?;
+
+        let delete_handler = {
+            let s_arc = service_arc.clone();
+            Arc::new(move |params: Option<ArcValue>, _ctx: RequestContext| {
+                let service = s_arc.clone();
+                Box::pin(async move {
+                    let key: String = required_param(&params, "key", "delete")?;
+                    Ok(ArcValue::new_primitive(service.delete(&key).await?))
+                }) as ServiceFuture
+            })
+        };
+        context.register_action("delete", delete_handler).await?;
+
+        let exists_handler = {
+            let s_arc = service_arc.clone();
+            Arc::new(move |params: Option<ArcValue>, _ctx: RequestContext| {
+                let service = s_arc.clone();
+                Box::pin(async move {
+                    let key: String = required_param(&params, "key", "exists")?;
+                    Ok(ArcValue::new_primitive(service.exists(&key).await?))
+                }) as ServiceFuture
+            })
+        };
+        context.register_action("exists", exists_handler).await?;
+
+        let increment_handler = {
+            let s_arc = service_arc.clone();
+            Arc::new(move |params: Option<ArcValue>, _ctx: RequestContext| {
+                let service = s_arc.clone();
+                Box::pin(async move {
+                    let key: String = required_param(&params, "key", "increment")?;
+                    let by: i64 = required_param(&params, "by", "increment")?;
+                    Ok(ArcValue::new_primitive(service.increment(&key, by).await?))
+                }) as ServiceFuture
+            })
+        };
+        context
+            .register_action("increment", increment_handler)
+            .await?;
+
+        context.info(format!(
+            "Actions registered for RedisService: {}",
+            self.name
+        ));
+        Ok(())
+    }
+
+    async fn start(&self, context: LifecycleContext) -> Result<()> {
+        context.info(format!(
+            "RedisService '{}' connecting with {} connection(s)",
+            self.name,
+            self.config.pool_size.max(1)
+        ));
+
+        let client = redis::Client::open(self.config.url.as_str())
+            .map_err(|e| anyhow!("Invalid Redis URL '{}': {e}", self.config.url))?;
+        let mut connections = Vec::with_capacity(self.config.pool_size.max(1) as usize);
+        for _ in 0..self.config.pool_size.max(1) {
+            let connection = client
+                .get_connection_manager()
+                .await
+                .map_err(|e| anyhow!("Failed to connect to Redis: {e}"))?;
+            connections.push(connection);
+        }
+        *self.connections.write().await = connections;
+
+        context.info(format!(
+            "RedisService '{}' started successfully.",
+            self.name
+        ));
+        Ok(())
+    }
+
+    async fn stop(&self, context: LifecycleContext) -> Result<()> {
+        context.info(format!("Stopping RedisService: {}", self.name));
+        // Dropping the managers closes their connections
+        self.connections.write().await.clear();
+        Ok(())
+    }
+}
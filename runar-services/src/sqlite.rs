@@ -4,17 +4,20 @@ use runar_common::logging::Logger;
 use runar_common::types::erased_arc::ErasedArc;
 use runar_common::types::ArcValue;
 use runar_common::types::ValueCategory;
+use runar_node::services::keys_service::StoreSymmetricKeyRequest;
 use runar_node::services::{LifecycleContext, RequestContext, ServiceFuture};
 use runar_node::AbstractService;
 use rusqlite::types::ToSqlOutput;
 use rusqlite::types::{Null, ValueRef as RusqliteValueRef};
 use rusqlite::{params_from_iter, Connection, Result as RusqliteResult, ToSql};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 
-use std::sync::{Arc, RwLock};
+use std::sync::{Arc, Mutex, RwLock};
 use std::thread;
-use tokio::sync::{mpsc, oneshot}; // Added mpsc, oneshot, thread // Added for Arc<Logger>
+use std::time::{Duration, Instant};
+use tokio::sync::{mpsc, oneshot};
+use tokio::task::JoinHandle;
 
 // Schema definition structs
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -40,9 +43,26 @@ pub struct ColumnDefinition {
 pub struct TableDefinition {
     pub name: String,
     pub columns: Vec<ColumnDefinition>,
+    /// Full-text index maintained next to the table (see `Fts5Config`)
+    #[serde(default)]
+    pub fts5_config: Option<Fts5Config>,
     // Consider adding: table-level constraints (e.g., composite primary keys, foreign keys) if needed later
 }
 
+/// Full-text search configuration for a table
+///
+/// Intention: Let applications search user-generated content without managing
+/// FTS5 themselves. The service creates an external-content FTS5 table named
+/// `{table}_fts` over `content_columns` and keeps it in sync with triggers on
+/// insert, update and delete. Rows are matched back to the table by `rowid`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Fts5Config {
+    /// Text columns of the table to index
+    pub content_columns: Vec<String>,
+    /// FTS5 tokenizer specification, e.g. `"porter unicode61"` (SQLite's default when None)
+    pub tokenizer: Option<String>,
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct IndexDefinition {
     pub name: String,
@@ -57,6 +77,9 @@ pub struct Schema {
     pub indexes: Vec<IndexDefinition>,
 }
 
+/// Default number of statements that may wait for an open transaction
+pub const DEFAULT_MAX_WAITING_COMMANDS: usize = 1000;
+
 // Command enum for the SQLite worker thread
 pub enum SqliteWorkerCommand {
     ApplySchema {
@@ -71,18 +94,102 @@ pub enum SqliteWorkerCommand {
         query: SqlQuery, // Changed: Now takes SqlQuery
         reply_to: oneshot::Sender<Result<Vec<HashMap<String, Value>>, String>>, // Changed to Value
     },
+    Rekey {
+        new_key: Vec<u8>,
+        reply_to: oneshot::Sender<Result<(), String>>,
+    },
+    BeginTransaction {
+        reply_to: oneshot::Sender<Result<String, String>>,
+    },
+    CommitTransaction {
+        transaction_id: String,
+        reply_to: oneshot::Sender<Result<(), String>>,
+    },
+    RollbackTransaction {
+        transaction_id: String,
+        reply_to: oneshot::Sender<Result<(), String>>,
+    },
+    /// Insert rows with a prepared `INSERT` statement in one transaction
+    BulkInsert {
+        statement: String,
+        rows: Vec<Vec<Value>>,
+        /// Index of the first row in the request, to report failed rows by
+        first_row: usize,
+        reply_to: oneshot::Sender<Result<BulkInsertResult, String>>,
+    },
+    /// Roll back the open transaction if it has been open longer than `timeout`
+    RollbackExpiredTransaction {
+        timeout: Duration,
+        reply_to: oneshot::Sender<Result<(), String>>,
+    },
     Shutdown {
         // Added Shutdown command
         reply_to: oneshot::Sender<Result<(), String>>,
     },
 }
 
+impl SqliteWorkerCommand {
+    /// Whether the caller stopped waiting for the reply, e.g. after a timeout
+    fn is_abandoned(&self) -> bool {
+        match self {
+            SqliteWorkerCommand::ApplySchema { reply_to, .. }
+            | SqliteWorkerCommand::Rekey { reply_to, .. }
+            | SqliteWorkerCommand::CommitTransaction { reply_to, .. }
+            | SqliteWorkerCommand::RollbackTransaction { reply_to, .. }
+            | SqliteWorkerCommand::RollbackExpiredTransaction { reply_to, .. }
+            | SqliteWorkerCommand::Shutdown { reply_to } => reply_to.is_closed(),
+            SqliteWorkerCommand::Execute { reply_to, .. } => reply_to.is_closed(),
+            SqliteWorkerCommand::Query { reply_to, .. } => reply_to.is_closed(),
+            SqliteWorkerCommand::BeginTransaction { reply_to } => reply_to.is_closed(),
+            SqliteWorkerCommand::BulkInsert { reply_to, .. } => reply_to.is_closed(),
+        }
+    }
+
+    /// Reply with `error` without running the command
+    fn reject(self, error: String) {
+        match self {
+            SqliteWorkerCommand::ApplySchema { reply_to, .. }
+            | SqliteWorkerCommand::Rekey { reply_to, .. }
+            | SqliteWorkerCommand::CommitTransaction { reply_to, .. }
+            | SqliteWorkerCommand::RollbackTransaction { reply_to, .. }
+            | SqliteWorkerCommand::RollbackExpiredTransaction { reply_to, .. }
+            | SqliteWorkerCommand::Shutdown { reply_to } => {
+                let _ = reply_to.send(Err(error));
+            }
+            SqliteWorkerCommand::Execute { reply_to, .. } => {
+                let _ = reply_to.send(Err(error));
+            }
+            SqliteWorkerCommand::Query { reply_to, .. } => {
+                let _ = reply_to.send(Err(error));
+            }
+            SqliteWorkerCommand::BeginTransaction { reply_to } => {
+                let _ = reply_to.send(Err(error));
+            }
+            SqliteWorkerCommand::BulkInsert { reply_to, .. } => {
+                let _ = reply_to.send(Err(error));
+            }
+        }
+    }
+}
+
+/// Transaction started with `begin_transaction` and not yet committed or rolled back
+struct OpenTransaction {
+    id: String,
+    started_at: Instant,
+}
+
 // The SQLite worker struct
 pub struct SqliteWorker {
     connection: Connection,
     receiver: mpsc::Receiver<SqliteWorkerCommand>,
     logger: Arc<Logger>,                   // Added logger
     ready_tx: Option<oneshot::Sender<()>>, // To signal when worker is ready
+    /// The connection is shared, so at most one transaction is open at a time
+    transaction: Option<OpenTransaction>,
+    /// Commands waiting for the open transaction to end, in arrival order
+    waiting: VecDeque<SqliteWorkerCommand>,
+    /// Commands rejected once this many are waiting
+    max_waiting: usize,
 }
 
 impl SqliteWorker {
@@ -101,13 +208,9 @@ impl SqliteWorker {
 
         // If a symmetric key is provided, use it for encryption
         if let Some(key_bytes) = symmetric_key {
-            // Convert the key bytes to a hex string for SQLCipher
-            // SQLCipher expects the key as a hex string when using raw keys
-            let hex_key = format!("x'{}'", hex::encode(&key_bytes));
-
             // Set the raw key using PRAGMA
             connection
-                .pragma_update(None, "key", &hex_key)
+                .pragma_update(None, "key", raw_key_literal(&key_bytes))
                 .map_err(|e| {
                     let err_msg = format!("Failed to set database key: {e}");
                     logger.error(&err_msg);
@@ -126,10 +229,148 @@ impl SqliteWorker {
             receiver,
             logger,
             ready_tx: Some(ready_tx),
+            transaction: None,
+            waiting: VecDeque::new(),
+            max_waiting: DEFAULT_MAX_WAITING_COMMANDS,
         })
     }
 
+    /// Reject commands arriving while `max_waiting` wait for a transaction
+    pub fn with_max_waiting(mut self, max_waiting: usize) -> Self {
+        self.max_waiting = max_waiting;
+        self
+    }
+
+    /// Check that a statement may run given the open transaction, if any
+    ///
+    /// While a transaction is open only statements tagged with its ID run;
+    /// the others wait for it to end (see `must_wait`), so reaching this with
+    /// another ID means the statement's own transaction is gone.
+    fn check_transaction(&self, transaction_id: Option<&str>) -> Result<(), String> {
+        match (&self.transaction, transaction_id) {
+            (None, None) => Ok(()),
+            (Some(open), Some(id)) if open.id == id => Ok(()),
+            (Some(open), _) => Err(format!(
+                "Database is locked by open transaction {}",
+                open.id
+            )),
+            (None, Some(id)) => Err(format!(
+                "Transaction {id} is not open; it was committed, rolled back or timed out"
+            )),
+        }
+    }
+
+    fn begin_transaction(&mut self) -> Result<String, String> {
+        self.check_transaction(None)?;
+        self.connection
+            .execute_batch("BEGIN IMMEDIATE")
+            .map_err(|e| format!("Failed to begin transaction: {e}"))?;
+        let id = uuid::Uuid::new_v4().to_string();
+        self.logger.debug(format!("Transaction {id} started"));
+        self.transaction = Some(OpenTransaction {
+            id: id.clone(),
+            started_at: Instant::now(),
+        });
+        Ok(id)
+    }
+
+    /// Commit or roll back the open transaction, which must be `transaction_id`
+    fn end_transaction(&mut self, transaction_id: &str, commit: bool) -> Result<(), String> {
+        self.check_transaction(Some(transaction_id))?;
+        let statement = if commit { "COMMIT" } else { "ROLLBACK" };
+        let result = self
+            .connection
+            .execute_batch(statement)
+            .map_err(|e| format!("Failed to {statement} transaction {transaction_id}: {e}"));
+        // A failed COMMIT leaves the transaction open; only clear it once SQLite has ended it
+        if self.connection.is_autocommit() {
+            self.transaction = None;
+        }
+        result
+    }
+
+    fn rollback_expired_transaction(&mut self, timeout: Duration) -> Result<(), String> {
+        let (id, open_for) = match &self.transaction {
+            Some(open) if open.started_at.elapsed() > timeout => {
+                (open.id.clone(), open.started_at.elapsed())
+            }
+            _ => return Ok(()),
+        };
+        self.logger.warn(format!(
+            "Rolling back transaction {id} left open for {open_for:?} (timeout {timeout:?})"
+        ));
+        self.end_transaction(&id, false)
+    }
+
+    /// Whether `command` has to wait for the open transaction to end
+    ///
+    /// Statements outside the transaction would otherwise run inside it, on
+    /// the shared connection. Statements tagged with a transaction ID, the
+    /// commands ending a transaction and shutdown never wait.
+    fn must_wait(&self, command: &SqliteWorkerCommand) -> bool {
+        if self.transaction.is_none() {
+            return false;
+        }
+        match command {
+            SqliteWorkerCommand::Execute { query, .. }
+            | SqliteWorkerCommand::Query { query, .. } => query.transaction_id.is_none(),
+            SqliteWorkerCommand::ApplySchema { .. }
+            | SqliteWorkerCommand::Rekey { .. }
+            | SqliteWorkerCommand::BeginTransaction { .. }
+            | SqliteWorkerCommand::BulkInsert { .. } => true,
+            SqliteWorkerCommand::CommitTransaction { .. }
+            | SqliteWorkerCommand::RollbackTransaction { .. }
+            | SqliteWorkerCommand::RollbackExpiredTransaction { .. }
+            | SqliteWorkerCommand::Shutdown { .. } => false,
+        }
+    }
+
+    /// Next command to process: a waiting one once no transaction is open,
+    /// otherwise the next received one
+    ///
+    /// Waiting commands whose caller gave up are dropped instead, so that a
+    /// write reported as failed is never committed later.
+    async fn next_command(&mut self) -> Option<SqliteWorkerCommand> {
+        if self.transaction.is_none() {
+            while let Some(command) = self.waiting.pop_front() {
+                if !command.is_abandoned() {
+                    return Some(command);
+                }
+                self.logger
+                    .debug("Dropping queued command whose caller stopped waiting");
+            }
+        }
+        self.receiver.recv().await
+    }
+
+    /// Queue `command` until the open transaction ends, or reject it when
+    /// `max_waiting` commands whose callers still wait are already queued
+    fn queue(&mut self, command: SqliteWorkerCommand) {
+        if self.waiting.len() >= self.max_waiting {
+            self.waiting.retain(|waiting| !waiting.is_abandoned());
+        }
+        if self.waiting.len() >= self.max_waiting {
+            self.logger.warn(format!(
+                "Rejecting command: {} commands already wait for the open transaction",
+                self.waiting.len()
+            ));
+            command.reject(format!(
+                "Database is locked by an open transaction and {} commands are already waiting",
+                self.waiting.len()
+            ));
+            return;
+        }
+        self.logger
+            .debug("Queueing command until the open transaction ends");
+        self.waiting.push_back(command);
+    }
+
     // Main loop for the worker thread
+    //
+    // Commands arriving while a transaction is open and not part of it are
+    // queued and run in order once it is committed, rolled back or expired.
+    // Their callers wait meanwhile, bounded by their own request timeout; the
+    // queue holds at most `max_waiting` commands.
     pub async fn run(mut self) {
         // Signal that the worker is ready
         if let Some(tx) = self.ready_tx.take() {
@@ -140,7 +381,11 @@ impl SqliteWorker {
             }
         }
         self.logger.info("SqliteWorker started processing loop.");
-        while let Some(command) = self.receiver.recv().await {
+        while let Some(command) = self.next_command().await {
+            if self.must_wait(&command) {
+                self.queue(command);
+                continue;
+            }
             match command {
                 SqliteWorkerCommand::ApplySchema { schema, reply_to } => {
                     self.logger.debug("Processing ApplySchema command");
@@ -149,24 +394,74 @@ impl SqliteWorker {
                 }
                 SqliteWorkerCommand::Execute { query, reply_to } => {
                     self.logger.debug("Processing Execute command");
-                    let res = execute_internal(
-                        &self.connection,
-                        &query.statement,
-                        &query.params,
-                        &self.logger,
-                    );
+                    let res = self
+                        .check_transaction(query.transaction_id.as_deref())
+                        .and_then(|_| {
+                            execute_internal(
+                                &self.connection,
+                                &query.statement,
+                                &query.params,
+                                &self.logger,
+                            )
+                        });
                     let _ = reply_to.send(res);
                 }
                 SqliteWorkerCommand::Query { query, reply_to } => {
                     self.logger.debug("Processing Query command");
-                    let res = query_internal(
+                    let res = self
+                        .check_transaction(query.transaction_id.as_deref())
+                        .and_then(|_| {
+                            query_internal(
+                                &self.connection,
+                                &query.statement,
+                                &query.params,
+                                &self.logger,
+                            )
+                        });
+                    let _ = reply_to.send(res);
+                }
+                SqliteWorkerCommand::Rekey { new_key, reply_to } => {
+                    self.logger.debug("Processing Rekey command");
+                    let res = rekey_internal(&self.connection, &new_key, &self.logger);
+                    let _ = reply_to.send(res);
+                }
+                SqliteWorkerCommand::BeginTransaction { reply_to } => {
+                    self.logger.debug("Processing BeginTransaction command");
+                    let _ = reply_to.send(self.begin_transaction());
+                }
+                SqliteWorkerCommand::CommitTransaction {
+                    transaction_id,
+                    reply_to,
+                } => {
+                    self.logger.debug("Processing CommitTransaction command");
+                    let _ = reply_to.send(self.end_transaction(&transaction_id, true));
+                }
+                SqliteWorkerCommand::RollbackTransaction {
+                    transaction_id,
+                    reply_to,
+                } => {
+                    self.logger.debug("Processing RollbackTransaction command");
+                    let _ = reply_to.send(self.end_transaction(&transaction_id, false));
+                }
+                SqliteWorkerCommand::BulkInsert {
+                    statement,
+                    rows,
+                    first_row,
+                    reply_to,
+                } => {
+                    self.logger.debug("Processing BulkInsert command");
+                    let res = bulk_insert_internal(
                         &self.connection,
-                        &query.statement,
-                        &query.params,
+                        &statement,
+                        &rows,
+                        first_row,
                         &self.logger,
                     );
                     let _ = reply_to.send(res);
                 }
+                SqliteWorkerCommand::RollbackExpiredTransaction { timeout, reply_to } => {
+                    let _ = reply_to.send(self.rollback_expired_transaction(timeout));
+                }
                 SqliteWorkerCommand::Shutdown { reply_to } => {
                     self.logger.info("SqliteWorker received Shutdown command.");
                     let _ = reply_to.send(Ok(()));
@@ -178,6 +473,40 @@ impl SqliteWorker {
     }
 }
 
+/// Compare two secrets without leaking where they differ through timing
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// Format raw key bytes the way SQLCipher expects a raw (not passphrase) key
+fn raw_key_literal(key_bytes: &[u8]) -> String {
+    format!("x'{}'", hex::encode(key_bytes))
+}
+
+// Internal helper function for re-encrypting the database with a new key
+//
+// SQLCipher re-encrypts every page in a single transaction, so if `rekey`
+// fails the database is left readable with the original key.
+fn rekey_internal(conn: &Connection, new_key: &[u8], logger: &Logger) -> Result<(), String> {
+    conn.pragma_update(None, "rekey", raw_key_literal(new_key))
+        .map_err(|e| {
+            let err_msg = format!("Failed to rekey database: {e}");
+            logger.error(&err_msg);
+            err_msg
+        })?;
+    // Make sure the database is readable with the new key
+    conn.query_row("SELECT count(*) FROM sqlite_master", [], |row| {
+        row.get::<_, i64>(0)
+    })
+    .map_err(|e| {
+        let err_msg = format!("Database is not readable after rekey: {e}");
+        logger.error(&err_msg);
+        err_msg
+    })?;
+    logger.info("Database re-encrypted with the new key.");
+    Ok(())
+}
+
 // Internal helper function for applying schema
 fn apply_schema_internal(
     conn: &Connection,
@@ -231,6 +560,12 @@ fn apply_schema_internal(
             columns_ddl.join(", ")
         );
         ddl_batch.push_str(&table_ddl);
+
+        if let Some(fts5_config) = &table_def.fts5_config {
+            let fts_ddl =
+                fts5_ddl(&table_def.name, fts5_config).inspect_err(|e| logger.error(e))?;
+            ddl_batch.push_str(&fts_ddl);
+        }
     }
 
     // Index Creation DDLs
@@ -267,6 +602,49 @@ fn apply_schema_internal(
     })
 }
 
+/// Name of the FTS5 table indexing `table`
+fn fts_table_name(table: &str) -> String {
+    format!("{table}_fts")
+}
+
+// Generate the FTS5 virtual table and the triggers keeping it in sync with its content table
+fn fts5_ddl(table: &str, config: &Fts5Config) -> Result<String, String> {
+    if config.content_columns.is_empty() {
+        return Err(format!(
+            "Full-text index for table '{table}' has no content columns"
+        ));
+    }
+    let fts_table = fts_table_name(table);
+    let columns = config.content_columns.join(", ");
+    let new_values = config
+        .content_columns
+        .iter()
+        .map(|col| format!("new.{col}"))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let old_values = config
+        .content_columns
+        .iter()
+        .map(|col| format!("old.{col}"))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let tokenize = match &config.tokenizer {
+        Some(tokenizer) => format!(", tokenize = '{}'", tokenizer.replace('\'', "''")),
+        None => String::new(),
+    };
+
+    Ok(format!(
+        "CREATE VIRTUAL TABLE IF NOT EXISTS {fts_table} USING fts5({columns}, content = '{table}', content_rowid = 'rowid'{tokenize});\n\
+         CREATE TRIGGER IF NOT EXISTS {fts_table}_ai AFTER INSERT ON {table} BEGIN \
+         INSERT INTO {fts_table}(rowid, {columns}) VALUES (new.rowid, {new_values}); END;\n\
+         CREATE TRIGGER IF NOT EXISTS {fts_table}_ad AFTER DELETE ON {table} BEGIN \
+         INSERT INTO {fts_table}({fts_table}, rowid, {columns}) VALUES ('delete', old.rowid, {old_values}); END;\n\
+         CREATE TRIGGER IF NOT EXISTS {fts_table}_au AFTER UPDATE ON {table} BEGIN \
+         INSERT INTO {fts_table}({fts_table}, rowid, {columns}) VALUES ('delete', old.rowid, {old_values}); \
+         INSERT INTO {fts_table}(rowid, {columns}) VALUES (new.rowid, {new_values}); END;\n"
+    ))
+}
+
 // Internal helper function for executing non-query SQL
 fn execute_internal(
     conn: &Connection,
@@ -295,6 +673,52 @@ fn execute_internal(
     }
 }
 
+// Internal helper function for inserting rows in a single transaction
+//
+// Rows that fail (e.g. on a constraint) are reported and skipped; the other
+// rows are committed.
+fn bulk_insert_internal(
+    conn: &Connection,
+    sql: &str,
+    rows: &[Vec<Value>],
+    first_row: usize,
+    logger: &Arc<Logger>,
+) -> Result<BulkInsertResult, String> {
+    logger.debug(format!("Inserting {} rows with SQL: {sql}", rows.len()));
+    conn.execute_batch("BEGIN IMMEDIATE")
+        .map_err(|e| format!("Failed to begin bulk insert transaction: {e}"))?;
+
+    let result = (|| {
+        let mut stmt = conn
+            .prepare(sql)
+            .map_err(|e| format!("Error preparing statement for SQL '{sql}': {e}"))?;
+        let column_count = stmt.parameter_count();
+        let mut result = BulkInsertResult::default();
+        for (index, row) in rows.iter().enumerate() {
+            let outcome = if row.len() == column_count {
+                stmt.execute(params_from_iter(row.iter()))
+                    .map(|_| ())
+                    .map_err(|e| e.to_string())
+            } else {
+                Err(format!("Expected {column_count} values, got {}", row.len()))
+            };
+            match outcome {
+                Ok(()) => result.inserted_count += 1,
+                Err(e) => result.failed_rows.push((first_row + index, e)),
+            }
+        }
+        Ok(result)
+    })();
+
+    let end = if result.is_ok() { "COMMIT" } else { "ROLLBACK" };
+    conn.execute_batch(end).map_err(|e| {
+        let err_msg = format!("Failed to {end} bulk insert transaction: {e}");
+        logger.error(&err_msg);
+        err_msg
+    })?;
+    result
+}
+
 // Internal helper function for executing query SQL
 fn query_internal(
     conn: &Connection,
@@ -335,7 +759,7 @@ fn query_internal(
         "Executing SQL query: {sql} with params: {params:?}",
     ));
     let rows_iter = stmt
-        .query_map(params_from_iter(params_for_iter.into_iter()), |row| {
+        .query_map(params_from_iter(params_for_iter), |row| {
             let mut map = HashMap::new();
             for (i, name) in column_names.iter().enumerate() {
                 map.insert(name.clone(), value_ref_to_value(row.get_ref_unwrap(i)));
@@ -471,6 +895,9 @@ impl Params {
 pub struct SqlQuery {
     pub statement: String,
     pub params: Params,
+    /// Transaction from `begin_transaction` to run the statement in
+    #[serde(default)]
+    pub transaction_id: Option<String>,
 }
 
 impl SqlQuery {
@@ -478,12 +905,136 @@ impl SqlQuery {
         Self {
             statement: statement.to_string(),
             params: Params::new(),
+            transaction_id: None,
         }
     }
     pub fn with_params(mut self, params: Params) -> Self {
         self.params = params;
         self
     }
+    /// Run the statement in the given open transaction
+    pub fn in_transaction(mut self, transaction_id: impl Into<String>) -> Self {
+        self.transaction_id = Some(transaction_id.into());
+        self
+    }
+}
+
+/// Full-text search request for the `search` action
+///
+/// `query` uses the FTS5 query syntax (e.g. `"rust AND async"` or `"serv*"`).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FtsQuery {
+    /// Table whose `Fts5Config` index is searched
+    pub table: String,
+    pub query: String,
+    pub limit: u32,
+    /// Also return a highlighted excerpt of each match
+    #[serde(default)]
+    pub include_snippets: bool,
+}
+
+impl FtsQuery {
+    pub fn new(table: &str, query: &str, limit: u32) -> Self {
+        Self {
+            table: table.to_string(),
+            query: query.to_string(),
+            limit,
+            include_snippets: false,
+        }
+    }
+    pub fn with_snippets(mut self) -> Self {
+        self.include_snippets = true;
+        self
+    }
+}
+
+/// A row matched by a full-text search, best matches first
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FtsMatch {
+    /// `rowid` of the matching row in the content table
+    pub row_id: i64,
+    /// FTS5 bm25 rank; lower is a better match
+    pub rank: f64,
+    /// Excerpt with matched terms wrapped in `[` and `]`, when requested
+    pub snippet: Option<String>,
+}
+
+/// Request for the `rotate_key` action
+///
+/// `new_key` is a raw 32-byte key, like the keys the service gets from the
+/// node's keystore. `token` must match `SqliteConfig::key_rotation_token`
+/// unless the caller is one of `SqliteConfig::key_rotation_peers`.
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct RotateKeyRequest {
+    pub new_key: Vec<u8>,
+    #[serde(default)]
+    pub token: Option<String>,
+}
+
+impl RotateKeyRequest {
+    pub fn new(new_key: Vec<u8>) -> Self {
+        Self {
+            new_key,
+            token: None,
+        }
+    }
+    pub fn with_token(mut self, token: impl Into<String>) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+}
+
+// Manual Debug implementation so keys and tokens never end up in logs
+impl std::fmt::Debug for RotateKeyRequest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RotateKeyRequest")
+            .field("new_key", &format!("<{} bytes>", self.new_key.len()))
+            .field("token", &self.token.as_ref().map(|_| "<redacted>"))
+            .finish()
+    }
+}
+
+/// Request for the `bulk_insert` action
+///
+/// Every row holds one value per column, in the order of `columns`. Rows are
+/// inserted in a single transaction, or in one transaction per `batch_size`
+/// rows so that other statements can run between batches of a large insert.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BulkInsertRequest {
+    /// Table of the schema to insert into
+    pub table: String,
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<Value>>,
+    /// Rows inserted per transaction; all of them when None
+    #[serde(default)]
+    pub batch_size: Option<usize>,
+}
+
+impl BulkInsertRequest {
+    pub fn new(table: &str, columns: &[&str]) -> Self {
+        Self {
+            table: table.to_string(),
+            columns: columns.iter().map(|column| column.to_string()).collect(),
+            rows: Vec::new(),
+            batch_size: None,
+        }
+    }
+    pub fn with_row(mut self, row: Vec<Value>) -> Self {
+        self.rows.push(row);
+        self
+    }
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = Some(batch_size);
+        self
+    }
+}
+
+/// Outcome of the `bulk_insert` action
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BulkInsertResult {
+    pub inserted_count: u64,
+    /// Index in the request and error of each row that was not inserted
+    pub failed_rows: Vec<(usize, String)>,
 }
 
 /// Query operators for building advanced queries
@@ -564,6 +1115,33 @@ pub struct SqliteConfig {
     pub schema: Schema,
     /// Encryption flag
     pub encryption: bool,
+    /// Bearer token required by the `rotate_key` action (None = no token accepted)
+    #[serde(default)]
+    pub key_rotation_token: Option<String>,
+    /// IDs of remote peers allowed to call `rotate_key` without a token
+    #[serde(default)]
+    pub key_rotation_peers: Vec<String>,
+    /// Transactions open longer than this are rolled back as abandoned
+    #[serde(default = "default_transaction_timeout")]
+    pub transaction_timeout: Duration,
+    /// How often the service looks for abandoned transactions
+    #[serde(default = "default_transaction_cleanup_interval")]
+    pub transaction_cleanup_interval: Duration,
+    /// Statements rejected once this many wait for an open transaction
+    #[serde(default = "default_max_waiting_commands")]
+    pub max_waiting_commands: usize,
+}
+
+fn default_transaction_timeout() -> Duration {
+    Duration::from_secs(30)
+}
+
+fn default_transaction_cleanup_interval() -> Duration {
+    Duration::from_secs(10)
+}
+
+fn default_max_waiting_commands() -> usize {
+    DEFAULT_MAX_WAITING_COMMANDS
 }
 
 impl SqliteConfig {
@@ -573,8 +1151,57 @@ impl SqliteConfig {
             db_path: db_path.into(),
             schema,
             encryption,
+            key_rotation_token: None,
+            key_rotation_peers: Vec::new(),
+            transaction_timeout: default_transaction_timeout(),
+            transaction_cleanup_interval: default_transaction_cleanup_interval(),
+            max_waiting_commands: default_max_waiting_commands(),
         }
     }
+
+    /// Allow `rotate_key` requests carrying this bearer token
+    pub fn with_key_rotation_token(mut self, token: impl Into<String>) -> Self {
+        self.key_rotation_token = Some(token.into());
+        self
+    }
+
+    /// Allow `rotate_key` requests from this authenticated remote peer
+    pub fn with_key_rotation_peer(mut self, peer_id: impl Into<String>) -> Self {
+        self.key_rotation_peers.push(peer_id.into());
+        self
+    }
+
+    /// Roll back transactions left open longer than `timeout`
+    pub fn with_transaction_timeout(mut self, timeout: Duration) -> Self {
+        self.transaction_timeout = timeout;
+        self
+    }
+
+    /// Look for abandoned transactions every `interval`
+    pub fn with_transaction_cleanup_interval(mut self, interval: Duration) -> Self {
+        self.transaction_cleanup_interval = interval;
+        self
+    }
+
+    /// Reject statements once `max` wait for an open transaction to end
+    pub fn with_max_waiting_commands(mut self, max: usize) -> Self {
+        self.max_waiting_commands = max;
+        self
+    }
+}
+
+/// Run a statement action until its request is cancelled, e.g. by a timeout
+///
+/// Giving up drops the pending worker reply, so that a statement still
+/// waiting for an open transaction is discarded instead of run later.
+async fn until_cancelled<T>(
+    ctx: RequestContext,
+    action: impl std::future::Future<Output = Result<T>>,
+) -> Result<T> {
+    tokio::select! {
+        result = action => result,
+        _ = ctx.cancel_token().cancelled() => Err(anyhow!("Request was cancelled")),
+    }
 }
 
 pub struct SqliteService {
@@ -584,6 +1211,10 @@ pub struct SqliteService {
     pub description: String,
     pub config: SqliteConfig,
     worker_tx: Arc<RwLock<Option<mpsc::Sender<SqliteWorkerCommand>>>>,
+    /// Key the database is currently encrypted with; replaced by `rotate_key`
+    symmetric_key: Arc<RwLock<Option<Vec<u8>>>>,
+    /// Background task rolling back abandoned transactions while started
+    transaction_cleanup: Arc<Mutex<Option<JoinHandle<()>>>>,
     network_id: Option<String>,
 }
 
@@ -597,6 +1228,8 @@ impl Clone for SqliteService {
             description: self.description.clone(),
             config: self.config.clone(),
             worker_tx: self.worker_tx.clone(),
+            symmetric_key: self.symmetric_key.clone(),
+            transaction_cleanup: self.transaction_cleanup.clone(),
             //schema: self.schema.clone(), // Clone the new schema field
             network_id: self.network_id.clone(),
         }
@@ -613,6 +1246,8 @@ impl SqliteService {
             description: "SQLite service".to_string(),
             config,
             worker_tx: Arc::new(RwLock::new(None)),
+            symmetric_key: Arc::new(RwLock::new(None)),
+            transaction_cleanup: Arc::new(Mutex::new(None)),
             // schema: Some(schema_clone), // Store the cloned schema
             network_id: None,
         }
@@ -658,6 +1293,290 @@ impl SqliteService {
         }
     }
 
+    /// Insert the rows of a request, one transaction per batch
+    async fn bulk_insert(&self, request: BulkInsertRequest) -> Result<BulkInsertResult> {
+        // Only tables and columns from the schema are accepted, as the names are part of the SQL
+        let table_def = self
+            .config
+            .schema
+            .tables
+            .iter()
+            .find(|table| table.name == request.table)
+            .ok_or_else(|| anyhow!("Unknown table '{}'", request.table))?;
+        if request.columns.is_empty() {
+            return Err(anyhow!("No columns to insert into '{}'", request.table));
+        }
+        if let Some(column) = request
+            .columns
+            .iter()
+            .find(|column| !table_def.columns.iter().any(|def| &def.name == *column))
+        {
+            return Err(anyhow!(
+                "Unknown column '{column}' in table '{}'",
+                request.table
+            ));
+        }
+
+        let placeholders = vec!["?"; request.columns.len()].join(", ");
+        let statement = format!(
+            "INSERT INTO {} ({}) VALUES ({placeholders})",
+            table_def.name,
+            request.columns.join(", ")
+        );
+        let batch_size = request
+            .batch_size
+            .filter(|size| *size > 0)
+            .unwrap_or(request.rows.len())
+            .max(1);
+
+        let mut result = BulkInsertResult::default();
+        let mut rows = request.rows;
+        let mut first_row = 0;
+        while !rows.is_empty() {
+            let rest = rows.split_off(batch_size.min(rows.len()));
+            let batch_len = rows.len();
+            let batch: BulkInsertResult = self
+                .send_command(|reply_tx| SqliteWorkerCommand::BulkInsert {
+                    statement: statement.clone(),
+                    rows,
+                    first_row,
+                    reply_to: reply_tx,
+                })
+                .await
+                .map_err(|e: String| anyhow!(e))?;
+            result.inserted_count += batch.inserted_count;
+            result.failed_rows.extend(batch.failed_rows);
+            first_row += batch_len;
+            rows = rest;
+        }
+        Ok(result)
+    }
+
+    /// Run a full-text search against a table with an `Fts5Config`
+    async fn search(&self, fts_query: &FtsQuery) -> Result<Vec<FtsMatch>> {
+        // Only tables from the schema are accepted, as the name is part of the SQL
+        let table_def = self
+            .config
+            .schema
+            .tables
+            .iter()
+            .find(|table| table.name == fts_query.table)
+            .ok_or_else(|| anyhow!("Unknown table '{}'", fts_query.table))?;
+        if table_def.fts5_config.is_none() {
+            return Err(anyhow!(
+                "Table '{}' has no full-text index",
+                fts_query.table
+            ));
+        }
+
+        let fts_table = fts_table_name(&table_def.name);
+        let snippet_column = if fts_query.include_snippets {
+            format!(", snippet({fts_table}, -1, '[', ']', '...', 16) AS snippet")
+        } else {
+            String::new()
+        };
+        let query = SqlQuery::new(&format!(
+            "SELECT rowid AS row_id, rank{snippet_column} FROM {fts_table} WHERE {fts_table} MATCH ? ORDER BY rank LIMIT ?"
+        ))
+        .with_params(
+            Params::new()
+                .with_value(Value::Text(fts_query.query.clone()))
+                .with_value(Value::Integer(fts_query.limit as i64)),
+        );
+
+        let rows: Vec<HashMap<String, Value>> = self
+            .send_command(|reply_tx| SqliteWorkerCommand::Query {
+                query,
+                reply_to: reply_tx,
+            })
+            .await
+            .map_err(|e: String| anyhow!(e))?;
+
+        rows.into_iter()
+            .map(|row| {
+                let row_id = match row.get("row_id") {
+                    Some(Value::Integer(id)) => *id,
+                    other => return Err(anyhow!("Unexpected row_id in search result: {other:?}")),
+                };
+                let rank = match row.get("rank") {
+                    Some(Value::Real(rank)) => *rank,
+                    Some(Value::Integer(rank)) => *rank as f64,
+                    other => return Err(anyhow!("Unexpected rank in search result: {other:?}")),
+                };
+                let snippet = match row.get("snippet") {
+                    Some(Value::Text(snippet)) => Some(snippet.clone()),
+                    _ => None,
+                };
+                Ok(FtsMatch {
+                    row_id,
+                    rank,
+                    snippet,
+                })
+            })
+            .collect()
+    }
+
+    /// Check that the caller may rotate the encryption key
+    ///
+    /// Allowed with the configured bearer token, or for a remote peer listed
+    /// in `key_rotation_peers` (peers are authenticated by the transport).
+    fn authorize_key_rotation(
+        &self,
+        request: &RotateKeyRequest,
+        ctx: &RequestContext,
+    ) -> Result<()> {
+        let token_ok = match (&self.config.key_rotation_token, &request.token) {
+            (Some(expected), Some(given)) => {
+                constant_time_eq(expected.as_bytes(), given.as_bytes())
+            }
+            _ => false,
+        };
+        let peer_ok = ctx
+            .peer_info()
+            .is_some_and(|peer| self.config.key_rotation_peers.contains(&peer.public_key));
+        if token_ok || peer_ok {
+            Ok(())
+        } else {
+            Err(anyhow!(
+                "Not authorized to rotate the encryption key of SqliteService '{}'",
+                self.name
+            ))
+        }
+    }
+
+    /// Name of the database key in the node keystore
+    fn key_name(&self) -> String {
+        format!(
+            "sqlite_{}_{}_{}",
+            self.path,
+            self.version,
+            self.network_id.as_ref().expect("network_id is required")
+        )
+    }
+
+    /// Re-encrypt the database with a new key
+    ///
+    /// The new key replaces the old one in the node keystore, so that the
+    /// service opens the database with it after a restart. When the keystore
+    /// refuses it the database is re-encrypted with the old key again and the
+    /// rotation fails.
+    async fn rotate_key(&self, new_key: Vec<u8>, ctx: &RequestContext) -> Result<()> {
+        if !self.config.encryption {
+            return Err(anyhow!(
+                "SqliteService '{}' is not encrypted; there is no key to rotate",
+                self.name
+            ));
+        }
+        if new_key.len() != 32 {
+            return Err(anyhow!(
+                "Invalid key length {}; expected a 32-byte key",
+                new_key.len()
+            ));
+        }
+        let old_key = self
+            .symmetric_key
+            .read()
+            .map_err(|e| anyhow!("Failed to acquire read lock on symmetric_key: {}", e))?
+            .clone()
+            .ok_or_else(|| anyhow!("SqliteService '{}' has not been started", self.name))?;
+        self.send_command(|reply_tx| SqliteWorkerCommand::Rekey {
+            new_key: new_key.clone(),
+            reply_to: reply_tx,
+        })
+        .await
+        .map_err(|e: String| anyhow!(e))?;
+
+        let stored: Result<bool> = ctx
+            .request(
+                "$keys/store_symmetric_key",
+                Some(ArcValue::from_struct(StoreSymmetricKeyRequest {
+                    key_name: self.key_name(),
+                    key: new_key.clone(),
+                })),
+            )
+            .await;
+        if let Err(e) = stored {
+            self.send_command(|reply_tx| SqliteWorkerCommand::Rekey {
+                new_key: old_key,
+                reply_to: reply_tx,
+            })
+            .await
+            .map_err(|restore_error: String| {
+                anyhow!(
+                    "Failed to store the new key ({e}) and to restore the old one: {restore_error}"
+                )
+            })?;
+            return Err(anyhow!("Failed to store the new key, rotation undone: {e}"));
+        }
+
+        let mut symmetric_key = self
+            .symmetric_key
+            .write()
+            .map_err(|e| anyhow!("Failed to acquire write lock on symmetric_key: {}", e))?;
+        *symmetric_key = Some(new_key);
+        Ok(())
+    }
+
+    /// Send a transaction command for the transaction named by an action payload
+    async fn end_transaction(&self, params_opt: Option<ArcValue>, commit: bool) -> Result<()> {
+        let transaction_id = params_opt
+            .ok_or_else(|| anyhow!("Missing payload. Expected the transaction ID."))?
+            .as_type::<String>()
+            .map_err(|e| anyhow!("Invalid payload. Expected the transaction ID: {e:?}"))?;
+        self.send_command(|reply_tx| {
+            if commit {
+                SqliteWorkerCommand::CommitTransaction {
+                    transaction_id,
+                    reply_to: reply_tx,
+                }
+            } else {
+                SqliteWorkerCommand::RollbackTransaction {
+                    transaction_id,
+                    reply_to: reply_tx,
+                }
+            }
+        })
+        .await
+        .map_err(|e: String| anyhow!(e))
+    }
+
+    /// Start the task rolling back transactions open longer than `transaction_timeout`
+    ///
+    /// A caller that disconnects mid-transaction would otherwise keep the
+    /// database locked for good. The task goes through the worker channel like
+    /// commits and rollbacks do, so it cannot race them.
+    fn start_transaction_cleanup(&self) -> Result<()> {
+        let service = self.clone();
+        let timeout = self.config.transaction_timeout;
+        let period = self.config.transaction_cleanup_interval;
+        let task = tokio::spawn(async move {
+            let mut interval = tokio::time::interval(period);
+            interval.tick().await; // The first tick completes immediately
+            loop {
+                interval.tick().await;
+                let result = service
+                    .send_command(|reply_tx| SqliteWorkerCommand::RollbackExpiredTransaction {
+                        timeout,
+                        reply_to: reply_tx,
+                    })
+                    .await;
+                if result.is_err() {
+                    // The worker has stopped
+                    break;
+                }
+            }
+        });
+        let previous = self
+            .transaction_cleanup
+            .lock()
+            .map_err(|e| anyhow!("Failed to acquire lock on transaction_cleanup: {}", e))?
+            .replace(task);
+        if let Some(previous) = previous {
+            previous.abort();
+        }
+        Ok(())
+    }
+
     async fn apply_schema(&self, schema: Schema, context: &LifecycleContext) -> Result<()> {
         let schema_to_apply = schema; // Use the passed schema argument
         context.info(format!(
@@ -719,9 +1638,9 @@ impl AbstractService for SqliteService {
         let execute_query_handler = {
             let s_arc = service_arc.clone();
             Arc::new(
-                move |params_opt: Option<ArcValue>, _req_ctx: RequestContext| {
+                move |params_opt: Option<ArcValue>, req_ctx: RequestContext| {
                     let service_clone = s_arc.clone();
-                    Box::pin(async move {
+                    Box::pin(until_cancelled(req_ctx, async move {
                         let mut query_arc_value = params_opt // Made mutable
                             .ok_or_else(|| anyhow!("Missing payload for 'execute_query' action. Expected ArcValue wrapping SqlQuery."))?;
 
@@ -775,7 +1694,7 @@ impl AbstractService for SqliteService {
                                 .map_err(|e: String| anyhow!(e))?;
                             Ok(ArcValue::new_primitive(affected_rows as i64))
                         }
-                    }) as ServiceFuture // ServiceFuture is Pin<Box<dyn Future<Output = Result<ArcValue>> + Send>>
+                    })) as ServiceFuture // ServiceFuture is Pin<Box<dyn Future<Output = Result<ArcValue>> + Send>>
                 },
             )
         };
@@ -786,6 +1705,129 @@ impl AbstractService for SqliteService {
             "'execute_query' action registered for SqliteService: {}",
             self.name
         ));
+
+        let search_handler = {
+            let s_arc = service_arc.clone();
+            Arc::new(
+                move |params_opt: Option<ArcValue>, req_ctx: RequestContext| {
+                    let service_clone = s_arc.clone();
+                    Box::pin(until_cancelled(req_ctx, async move {
+                        let mut query_arc_value = params_opt.ok_or_else(|| {
+                            anyhow!("Missing payload for 'search' action. Expected ArcValue wrapping FtsQuery.")
+                        })?;
+                        let fts_query = query_arc_value.as_type::<FtsQuery>().map_err(|e| {
+                            anyhow!("Invalid payload type for 'search'. Expected FtsQuery: {e:?}")
+                        })?;
+                        let matches = service_clone.search(&fts_query).await?;
+                        Ok(ArcValue::new_list(matches))
+                    })) as ServiceFuture
+                },
+            )
+        };
+        context.register_action("search", search_handler).await?;
+        context.info(format!(
+            "'search' action registered for SqliteService: {}",
+            self.name
+        ));
+
+        let bulk_insert_handler = {
+            let s_arc = service_arc.clone();
+            Arc::new(
+                move |params_opt: Option<ArcValue>, _req_ctx: RequestContext| {
+                    let service_clone = s_arc.clone();
+                    Box::pin(async move {
+                        let mut request_arc_value = params_opt.ok_or_else(|| {
+                            anyhow!("Missing payload for 'bulk_insert' action. Expected ArcValue wrapping BulkInsertRequest.")
+                        })?;
+                        let request = request_arc_value.as_type::<BulkInsertRequest>().map_err(|e| {
+                            anyhow!("Invalid payload type for 'bulk_insert'. Expected BulkInsertRequest: {e:?}")
+                        })?;
+                        let result = service_clone.bulk_insert(request).await?;
+                        Ok(ArcValue::from_struct(result))
+                    }) as ServiceFuture
+                },
+            )
+        };
+        context
+            .register_action("bulk_insert", bulk_insert_handler)
+            .await?;
+        context.info(format!(
+            "'bulk_insert' action registered for SqliteService: {}",
+            self.name
+        ));
+
+        let rotate_key_handler = {
+            let s_arc = service_arc.clone();
+            Arc::new(
+                move |params_opt: Option<ArcValue>, req_ctx: RequestContext| {
+                    let service_clone = s_arc.clone();
+                    Box::pin(async move {
+                        let mut request_arc_value = params_opt.ok_or_else(|| {
+                            anyhow!("Missing payload for 'rotate_key' action. Expected ArcValue wrapping RotateKeyRequest.")
+                        })?;
+                        let request = request_arc_value.as_type::<RotateKeyRequest>().map_err(|e| {
+                            anyhow!("Invalid payload type for 'rotate_key'. Expected RotateKeyRequest: {e:?}")
+                        })?;
+                        service_clone.authorize_key_rotation(&request, &req_ctx)?;
+                        service_clone.rotate_key(request.new_key, &req_ctx).await?;
+                        req_ctx.info(format!(
+                            "Encryption key of SqliteService '{}' rotated",
+                            service_clone.name
+                        ));
+                        Ok(ArcValue::new_primitive(true))
+                    }) as ServiceFuture
+                },
+            )
+        };
+        context
+            .register_action("rotate_key", rotate_key_handler)
+            .await?;
+        context.info(format!(
+            "'rotate_key' action registered for SqliteService: {}",
+            self.name
+        ));
+
+        let begin_transaction_handler = {
+            let s_arc = service_arc.clone();
+            Arc::new(
+                move |_params_opt: Option<ArcValue>, _req_ctx: RequestContext| {
+                    let service_clone = s_arc.clone();
+                    Box::pin(async move {
+                        let transaction_id: String = service_clone
+                            .send_command(|reply_tx| SqliteWorkerCommand::BeginTransaction {
+                                reply_to: reply_tx,
+                            })
+                            .await
+                            .map_err(|e: String| anyhow!(e))?;
+                        Ok(ArcValue::new_primitive(transaction_id))
+                    }) as ServiceFuture
+                },
+            )
+        };
+        context
+            .register_action("begin_transaction", begin_transaction_handler)
+            .await?;
+
+        for (action, commit) in [
+            ("commit_transaction", true),
+            ("rollback_transaction", false),
+        ] {
+            let s_arc = service_arc.clone();
+            let handler = Arc::new(
+                move |params_opt: Option<ArcValue>, _req_ctx: RequestContext| {
+                    let service_clone = s_arc.clone();
+                    Box::pin(async move {
+                        service_clone.end_transaction(params_opt, commit).await?;
+                        Ok(ArcValue::new_primitive(true))
+                    }) as ServiceFuture
+                },
+            );
+            context.register_action(action, handler).await?;
+        }
+        context.info(format!(
+            "Transaction actions registered for SqliteService: {}",
+            self.name
+        ));
         Ok(())
     }
 
@@ -810,22 +1852,33 @@ impl AbstractService for SqliteService {
         let db_path_clone = self.config.db_path.clone();
         let schema_clone = self.config.schema.clone();
         let logger_clone_for_thread = context.logger.clone();
+        let max_waiting = self.config.max_waiting_commands;
 
         let mut encryption_key: Option<Vec<u8>> = None;
 
         if self.config.encryption {
-            context.info("SqliteService encryption enabled - requesting symmetric key.");
-            // request a symmetric key for this service,
-            // if one exists it will be returned, if not one will be created, stored and returned
-            let key_name = format!(
-                "sqlite_{}_{}_{}",
-                self.path,
-                self.version,
-                self.network_id.as_ref().expect("network_id is required")
-            );
-            let key: Vec<u8> = context
-                .request("$keys/ensure_symmetric_key", Some(key_name))
-                .await?;
+            // The key is fetched once, rotate_key keeps it up to date
+            let rotated_key = self
+                .symmetric_key
+                .read()
+                .map_err(|e| anyhow!("Failed to acquire read lock on symmetric_key: {}", e))?
+                .clone();
+            let key = match rotated_key {
+                Some(key) => key,
+                None => {
+                    context.info("SqliteService encryption enabled - requesting symmetric key.");
+                    // request a symmetric key for this service,
+                    // if one exists it will be returned, if not one will be created, stored and returned
+                    let key_name = self.key_name();
+                    let key: Vec<u8> = context
+                        .request("$keys/ensure_symmetric_key", Some(key_name))
+                        .await?;
+                    *self.symmetric_key.write().map_err(|e| {
+                        anyhow!("Failed to acquire write lock on symmetric_key: {}", e)
+                    })? = Some(key.clone());
+                    key
+                }
+            };
             encryption_key = Some(key);
         } else {
             context.warn("SqliteService encryption disabled.");
@@ -847,7 +1900,7 @@ impl AbstractService for SqliteService {
                 ) {
                     Ok(worker) => {
                         logger_clone_for_thread.info("SqliteWorker thread starting run loop.");
-                        worker.run().await;
+                        worker.with_max_waiting(max_waiting).run().await;
                         logger_clone_for_thread.info("SqliteWorker thread finished.");
                     }
                     Err(e) => {
@@ -867,6 +1920,7 @@ impl AbstractService for SqliteService {
 
         // Now that the worker is confirmed to be running, apply the schema
         self.apply_schema(schema_clone, &context).await?;
+        self.start_transaction_cleanup()?;
 
         context.info(format!(
             "SqliteService '{}' started successfully.",
@@ -877,6 +1931,11 @@ impl AbstractService for SqliteService {
 
     async fn stop(&self, context: LifecycleContext) -> Result<()> {
         context.info(format!("Stopping SqliteService: {}", self.name));
+        if let Ok(mut cleanup) = self.transaction_cleanup.lock() {
+            if let Some(task) = cleanup.take() {
+                task.abort();
+            }
+        }
         match self.send_command(|reply_tx| SqliteWorkerCommand::Shutdown { reply_to: reply_tx }).await {
             Ok(_) => context.info(format!("SqliteService '{}' worker acknowledged shutdown.", self.name)),
             Err(e) => context.error(format!("Error sending Shutdown to SqliteService '{}' worker: {e}. Worker might have already terminated.", self.name)),
@@ -0,0 +1,303 @@
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use runar_common::types::ArcValue;
+use runar_node::services::{LifecycleContext, RequestContext, ServiceFuture};
+use runar_node::AbstractService;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+use wasmtime::{
+    Config, Engine, Instance, Memory, Module, Store, StoreLimits, StoreLimitsBuilder, Trap,
+    TypedFunc,
+};
+
+/// Configuration of the sandbox a WebAssembly service runs in.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct WasmServiceConfig {
+    /// Path of the compiled `.wasm` module
+    pub wasm_path: PathBuf,
+    /// Exported functions registered as actions, under their export name
+    pub allowed_actions: Vec<String>,
+    /// Largest size the module's memory may grow to, in MiB
+    pub memory_limit_mb: u32,
+    /// Longest an action may run before it is interrupted, in milliseconds
+    pub cpu_timeout_ms: u64,
+}
+
+impl WasmServiceConfig {
+    /// Create a config exposing no action, with 64 MiB of memory and a 1s timeout
+    pub fn new(wasm_path: impl Into<PathBuf>) -> Self {
+        Self {
+            wasm_path: wasm_path.into(),
+            allowed_actions: Vec::new(),
+            memory_limit_mb: 64,
+            cpu_timeout_ms: 1000,
+        }
+    }
+
+    pub fn with_allowed_actions(mut self, allowed_actions: Vec<String>) -> Self {
+        self.allowed_actions = allowed_actions;
+        self
+    }
+
+    pub fn with_memory_limit_mb(mut self, memory_limit_mb: u32) -> Self {
+        self.memory_limit_mb = memory_limit_mb;
+        self
+    }
+
+    pub fn with_cpu_timeout_ms(mut self, cpu_timeout_ms: u64) -> Self {
+        self.cpu_timeout_ms = cpu_timeout_ms;
+        self
+    }
+}
+
+/// An instantiated module and the store it runs in
+struct WasmInstance {
+    engine: Engine,
+    store: Store<StoreLimits>,
+    memory: Memory,
+    alloc: TypedFunc<i32, i32>,
+    actions: HashMap<String, TypedFunc<(i32, i32), i64>>,
+}
+
+impl WasmInstance {
+    /// Compile and instantiate the module, resolving the allowed actions
+    fn load(config: &WasmServiceConfig) -> Result<Self> {
+        let mut engine_config = Config::new();
+        engine_config.epoch_interruption(true);
+        let engine = Engine::new(&engine_config)?;
+        let module = Module::from_file(&engine, &config.wasm_path).map_err(|e| {
+            anyhow!(
+                "Failed to load WASM module '{}': {e}",
+                config.wasm_path.display()
+            )
+        })?;
+
+        let limits = StoreLimitsBuilder::new()
+            .memory_size(config.memory_limit_mb as usize * 1024 * 1024)
+            .instances(1)
+            .build();
+        let mut store = Store::new(&engine, limits);
+        store.limiter(|limits| limits);
+        store.set_epoch_deadline(1);
+
+        // Modules are sandboxed: nothing is imported into them
+        let instance = Instance::new(&mut store, &module, &[])
+            .map_err(|e| anyhow!("Failed to instantiate WASM module: {e}"))?;
+        let memory = instance
+            .get_memory(&mut store, "memory")
+            .ok_or_else(|| anyhow!("WASM module does not export its 'memory'"))?;
+        let alloc = instance
+            .get_typed_func::<i32, i32>(&mut store, "alloc")
+            .map_err(|e| anyhow!("WASM module does not export 'alloc(i32) -> i32': {e}"))?;
+
+        let mut actions = HashMap::new();
+        for action in &config.allowed_actions {
+            let func = instance
+                .get_typed_func::<(i32, i32), i64>(&mut store, action)
+                .map_err(|e| {
+                    anyhow!("WASM module does not export action '{action}(i32, i32) -> i64': {e}")
+                })?;
+            actions.insert(action.clone(), func);
+        }
+
+        Ok(Self {
+            engine,
+            store,
+            memory,
+            alloc,
+            actions,
+        })
+    }
+
+    /// Run an action on the serialized request and read its serialized response
+    fn call(&mut self, action: &str, input: &[u8]) -> Result<Vec<u8>> {
+        let func = self
+            .actions
+            .get(action)
+            .ok_or_else(|| anyhow!("Action '{action}' is not allowed"))?
+            .clone();
+        self.store.set_epoch_deadline(1);
+
+        let input_len = i32::try_from(input.len())
+            .map_err(|_| anyhow!("Request of {} bytes is too large", input.len()))?;
+        let input_ptr = self.alloc.call(&mut self.store, input_len)?;
+        self.memory
+            .write(&mut self.store, input_ptr as u32 as usize, input)?;
+
+        let packed = func
+            .call(&mut self.store, (input_ptr, input_len))
+            .map_err(|e| match e.downcast_ref::<Trap>() {
+                Some(Trap::Interrupt) => anyhow!("Action '{action}' exceeded its CPU timeout"),
+                _ => anyhow!("Action '{action}' failed: {e}"),
+            })?;
+        let output_ptr = (packed as u64 >> 32) as usize;
+        let output_len = (packed as u64 & 0xffff_ffff) as usize;
+        let mut output = vec![0u8; output_len];
+        self.memory.read(&self.store, output_ptr, &mut output)?;
+        Ok(output)
+    }
+}
+
+/// Service running the actions of a WebAssembly module
+///
+/// INTENTION: Load service logic written in any language targeting WASM at
+/// runtime, without compiling it into the node. The module runs sandboxed: it
+/// imports nothing, its memory is capped at `memory_limit_mb` and an action
+/// running longer than `cpu_timeout_ms` is interrupted. Only the exports
+/// listed in `allowed_actions` are reachable.
+///
+/// ABI:
+/// - the module exports its `memory` and `alloc(len: i32) -> i32`, returning
+///   where the host may write a request of `len` bytes
+/// - each action is exported as `action(ptr: i32, len: i32) -> i64`; it reads
+///   the request at `ptr` and returns the response's pointer in the high 32
+///   bits and its length in the low 32 bits
+/// - requests and responses are `ArcValue`s in the node's wire format (the
+///   bytes of `SerializerRegistry::serialize_value`, bincode by default); a
+///   missing payload is sent as null (`[0x05]`)
+///
+/// Calls are serialized on a single instance, so the module's memory persists
+/// between calls and managing it is up to the module.
+pub struct WasmPluginService {
+    pub name: String,
+    pub path: String,
+    pub version: String,
+    pub description: String,
+    pub config: WasmServiceConfig,
+    instance: Arc<Mutex<Option<WasmInstance>>>,
+    network_id: Option<String>,
+}
+
+impl Clone for WasmPluginService {
+    fn clone(&self) -> Self {
+        Self {
+            name: self.name.clone(),
+            path: self.path.clone(),
+            version: self.version.clone(),
+            description: self.description.clone(),
+            config: self.config.clone(),
+            instance: self.instance.clone(),
+            network_id: self.network_id.clone(),
+        }
+    }
+}
+
+impl WasmPluginService {
+    pub fn new(name: String, path: String, config: WasmServiceConfig) -> Self {
+        Self {
+            name,
+            path,
+            version: "0.0.1".to_string(),
+            description: "WebAssembly plugin service".to_string(),
+            config,
+            instance: Arc::new(Mutex::new(None)),
+            network_id: None,
+        }
+    }
+
+    /// Run an action on a blocking thread, interrupting it after the CPU timeout
+    async fn call(&self, action: &str, input: Vec<u8>) -> Result<Vec<u8>> {
+        let mut instance = self.instance.clone().lock_owned().await;
+        let engine = instance
+            .as_ref()
+            .ok_or_else(|| anyhow!("WasmPluginService '{}' is not initialized", self.name))?
+            .engine
+            .clone();
+
+        let action_name = action.to_string();
+        let mut call = tokio::task::spawn_blocking(move || match instance.as_mut() {
+            Some(instance) => instance.call(&action_name, &input),
+            None => Err(anyhow!("WASM instance was unloaded")),
+        });
+        let timeout = Duration::from_millis(self.config.cpu_timeout_ms);
+        let result = match tokio::time::timeout(timeout, &mut call).await {
+            Ok(result) => result,
+            Err(_) => {
+                // Makes the running action trap at its next epoch check
+                engine.increment_epoch();
+                call.await
+            }
+        };
+        result.map_err(|e| anyhow!("Action '{action}' panicked: {e}"))?
+    }
+}
+
+#[async_trait]
+impl AbstractService for WasmPluginService {
+    fn name(&self) -> &str {
+        &self.name
+    }
+    fn version(&self) -> &str {
+        &self.version
+    }
+    fn path(&self) -> &str {
+        &self.path
+    }
+    fn description(&self) -> &str {
+        &self.description
+    }
+    fn network_id(&self) -> Option<String> {
+        self.network_id.clone()
+    }
+    fn set_network_id(&mut self, network_id: String) {
+        self.network_id = Some(network_id);
+    }
+
+    async fn init(&self, context: LifecycleContext) -> Result<()> {
+        context.info(format!(
+            "Initializing WasmPluginService: {} from {}",
+            self.name,
+            self.config.wasm_path.display()
+        ));
+        let config = self.config.clone();
+        let instance = tokio::task::spawn_blocking(move || WasmInstance::load(&config))
+            .await
+            .map_err(|e| anyhow!("Loading the WASM module panicked: {e}"))??;
+        *self.instance.lock().await = Some(instance);
+
+        let service_arc = Arc::new(self.clone());
+        for action in &self.config.allowed_actions {
+            let handler = {
+                let s_arc = service_arc.clone();
+                let serializer = context.serializer.clone();
+                let action = action.clone();
+                Arc::new(move |params: Option<ArcValue>, _ctx: RequestContext| {
+                    let service = s_arc.clone();
+                    let serializer = serializer.clone();
+                    let action = action.clone();
+                    Box::pin(async move {
+                        let params = params.unwrap_or_else(ArcValue::null);
+                        let input = serializer.read().await.serialize_value(&params)?.to_vec();
+                        let output = service.call(&action, input).await?;
+                        serializer.read().await.deserialize_value(Arc::from(output))
+                    }) as ServiceFuture
+                })
+            };
+            context.register_action(action, handler).await?;
+        }
+
+        context.info(format!(
+            "Actions registered for WasmPluginService: {}",
+            self.name
+        ));
+        Ok(())
+    }
+
+    async fn start(&self, context: LifecycleContext) -> Result<()> {
+        context.info(format!(
+            "WasmPluginService '{}' started successfully.",
+            self.name
+        ));
+        Ok(())
+    }
+
+    async fn stop(&self, context: LifecycleContext) -> Result<()> {
+        context.info(format!("Stopping WasmPluginService: {}", self.name));
+        *self.instance.lock().await = None;
+        Ok(())
+    }
+}
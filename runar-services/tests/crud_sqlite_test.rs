@@ -10,6 +10,7 @@ use runar_node::config::{LogLevel, LoggingConfig};
 
 use std::collections::HashMap;
 use std::sync::Arc; // For downcasting
+use std::time::Duration;
 
 // Assuming crud_sqlite.rs and sqlite.rs are part of the same crate (rust_services)
 use runar_services::crud_sqlite::{
@@ -17,7 +18,7 @@ use runar_services::crud_sqlite::{
 };
 use runar_services::sqlite::{
     ColumnDefinition, DataType, Schema as SqliteSchema, SqliteConfig, SqliteService,
-    TableDefinition,
+    TableDefinition, DEFAULT_MAX_WAITING_COMMANDS,
 };
 
 const SQLITE_SERVICE_NAME: &str = "test_sqlite_for_crud";
@@ -72,6 +73,7 @@ async fn setup_node_with_services() -> Result<Node> {
                         not_null: false,
                     },
                 ],
+                fts5_config: None,
             },
             TableDefinition {
                 name: "orders".to_string(),
@@ -105,6 +107,7 @@ async fn setup_node_with_services() -> Result<Node> {
                         not_null: false,
                     },
                 ],
+                fts5_config: None,
             },
             TableDefinition {
                 name: "products".to_string(),
@@ -138,6 +141,7 @@ async fn setup_node_with_services() -> Result<Node> {
                         not_null: false,
                     },
                 ],
+                fts5_config: None,
             },
         ],
         indexes: vec![], // No indexes for now
@@ -148,6 +152,11 @@ async fn setup_node_with_services() -> Result<Node> {
         db_path: ":memory:".to_string(),
         schema: (*app_schema).clone(), // SqliteService takes ownership of the schema for table creation
         encryption: false,
+        key_rotation_token: None,
+        key_rotation_peers: Vec::new(),
+        transaction_timeout: Duration::from_secs(30),
+        transaction_cleanup_interval: Duration::from_secs(10),
+        max_waiting_commands: DEFAULT_MAX_WAITING_COMMANDS,
     };
     let sqlite_service = SqliteService::new(
         SQLITE_SERVICE_NAME.to_string(),
@@ -0,0 +1,223 @@
+// Tests for the distributed key-value store service
+//
+// The first test runs a single node and feeds it mutations through the
+// anti-entropy actions; the second replicates between two connected nodes.
+
+use anyhow::Result;
+use runar_common::types::ArcValue;
+use runar_node::network::transport::PeerId;
+use runar_node::Node;
+use runar_services::dkv::{
+    DistributedKvConfig, DistributedKvService, DkvEntries, DkvEntriesRequest, DkvMutation,
+    DkvSetRequest, DkvValue, MerkleDigest, DIGEST_BUCKETS,
+};
+use runar_test_utils::{
+    create_networked_node_test_config, create_node_test_config, without_discovery,
+};
+use std::collections::HashMap;
+use std::net::{Ipv4Addr, SocketAddr};
+use std::time::Duration;
+use tokio::time::{sleep, timeout, Instant};
+
+fn set(key: &str, value: &[u8]) -> Option<ArcValue> {
+    Some(ArcValue::from_struct(DkvSetRequest {
+        key: key.to_string(),
+        value: value.to_vec(),
+    }))
+}
+
+async fn get(node: &Node, key: &str) -> Option<Vec<u8>> {
+    let value: DkvValue = node
+        .request("dkv/get", Some(ArcValue::new_primitive(key.to_string())))
+        .await
+        .unwrap();
+    value.value
+}
+
+fn mutation(key: &str, value: &[u8], clock: &[(&str, u64)], node_id: &str) -> DkvMutation {
+    DkvMutation {
+        key: key.to_string(),
+        value: Some(value.to_vec()),
+        vector_clock: clock
+            .iter()
+            .map(|(node, count)| (node.to_string(), *count))
+            .collect(),
+        node_id: node_id.to_string(),
+    }
+}
+
+/// Test local reads and writes and the merge rules
+///
+/// INTENTION: Writes are visible locally right away and advance the local
+/// node's clock entry. Mutations from other nodes replace a value only when
+/// their clock is newer; concurrent ones are resolved by the larger clock
+/// total, keeping the maximum of both clocks.
+#[tokio::test(flavor = "multi_thread")]
+async fn test_local_writes_and_merge_rules() {
+    timeout(Duration::from_secs(20), async {
+        let mut config = create_node_test_config().expect("Error creating test config");
+        config.network_config = None;
+        let mut node = Node::new(config).await.unwrap();
+        let node_id = node.get_local_node_info().await.unwrap().peer_id.public_key;
+        node.add_service(DistributedKvService::new(
+            "dkv".to_string(),
+            "dkv".to_string(),
+            DistributedKvConfig::new(vec![]),
+        ))
+        .await
+        .unwrap();
+        node.start().await.unwrap();
+
+        let written: DkvMutation = node.request("dkv/set", set("color", b"red")).await.unwrap();
+        assert_eq!(written.vector_clock, HashMap::from([(node_id.clone(), 1)]));
+        assert_eq!(get(&node, "color").await, Some(b"red".to_vec()));
+        let keys: Vec<String> = node.request("dkv/keys", None::<ArcValue>).await.unwrap();
+        assert_eq!(keys, vec!["color".to_string()]);
+
+        let buckets: Vec<u32> = (0..DIGEST_BUCKETS as u32).collect();
+        let entries =
+            |buckets: Vec<u32>| Some(ArcValue::from_struct(DkvEntriesRequest { buckets }));
+        let stored: DkvEntries = node
+            .request(format!("dkv/entries/{node_id}"), entries(buckets.clone()))
+            .await
+            .unwrap();
+        assert_eq!(stored.entries.len(), 1);
+        assert_eq!(
+            stored.entries[0].vector_clock,
+            HashMap::from([(node_id.clone(), 1)])
+        );
+
+        let merge_path = format!("dkv/merge/{node_id}");
+        let merge = |entries: Vec<DkvMutation>| Some(ArcValue::from_struct(DkvEntries { entries }));
+        // A write that saw ours replaces it, a stale one does not
+        let changed: i64 = node
+            .request(
+                &merge_path,
+                merge(vec![
+                    mutation("color", b"blue", &[(&node_id, 1), ("b", 1)], "b"),
+                    mutation("color", b"green", &[(&node_id, 1)], "c"),
+                ]),
+            )
+            .await
+            .unwrap();
+        assert_eq!(changed, 1);
+        assert_eq!(get(&node, "color").await, Some(b"blue".to_vec()));
+
+        // Concurrent writes: the larger clock total wins
+        let _: i64 = node
+            .request(
+                &merge_path,
+                merge(vec![
+                    mutation("color", b"pink", &[(&node_id, 1), ("b", 1), ("c", 2)], "c"),
+                    mutation("color", b"gray", &[(&node_id, 1), ("b", 2)], "b"),
+                ]),
+            )
+            .await
+            .unwrap();
+        assert_eq!(get(&node, "color").await, Some(b"pink".to_vec()));
+        let stored: DkvEntries = node
+            .request(format!("dkv/entries/{node_id}"), entries(buckets))
+            .await
+            .unwrap();
+        assert_eq!(
+            stored.entries[0].vector_clock,
+            HashMap::from([
+                (node_id.clone(), 1),
+                ("b".to_string(), 2),
+                ("c".to_string(), 2)
+            ])
+        );
+
+        // Deleting keeps a tombstone, which changes the digest
+        let before: MerkleDigest = node
+            .request(format!("dkv/digest/{node_id}"), None::<ArcValue>)
+            .await
+            .unwrap();
+        let _: DkvMutation = node
+            .request(
+                "dkv/delete",
+                Some(ArcValue::new_primitive("color".to_string())),
+            )
+            .await
+            .unwrap();
+        assert_eq!(get(&node, "color").await, None);
+        let after: MerkleDigest = node
+            .request(format!("dkv/digest/{node_id}"), None::<ArcValue>)
+            .await
+            .unwrap();
+        assert_ne!(before.root, after.root);
+
+        node.stop().await.unwrap();
+    })
+    .await
+    .expect("Test timed out");
+}
+
+/// Poll `key` on `node` until it holds `expected`
+async fn wait_for_value(node: &Node, key: &str, expected: &[u8]) -> bool {
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while Instant::now() < deadline {
+        if get(node, key).await.as_deref() == Some(expected) {
+            return true;
+        }
+        sleep(Duration::from_millis(50)).await;
+    }
+    false
+}
+
+/// Test replication between two nodes
+///
+/// INTENTION: A write made while the other node is connected reaches it
+/// through the mutation event; a write made before it joined reaches it
+/// through anti-entropy.
+#[tokio::test]
+async fn test_replication_between_nodes() -> Result<()> {
+    timeout(Duration::from_secs(30), async {
+        let configs = create_networked_node_test_config(2)?;
+        let node1_config = without_discovery(configs[0].clone());
+        let node1_port = node1_config
+            .network_config
+            .as_ref()
+            .unwrap()
+            .transport_options
+            .bind_address
+            .port();
+        let mut node1 = Node::new(node1_config).await?;
+        let node1_peer_id = node1.get_local_node_info().await?.peer_id;
+        let node1_addr = SocketAddr::from((Ipv4Addr::LOCALHOST, node1_port));
+        let mut node2 = Node::new(
+            without_discovery(configs[1].clone())
+                .with_initial_peers(vec![(node1_addr, node1_peer_id.clone())]),
+        )
+        .await?;
+        let node2_peer_id = node2.get_local_node_info().await?.peer_id;
+
+        let dkv = |peer: &PeerId| {
+            DistributedKvService::new(
+                "dkv".to_string(),
+                "dkv".to_string(),
+                DistributedKvConfig::new(vec![peer.clone()])
+                    .with_anti_entropy_interval(Duration::from_millis(200)),
+            )
+        };
+        node1.add_service(dkv(&node2_peer_id)).await?;
+        node2.add_service(dkv(&node1_peer_id)).await?;
+
+        node1.start().await?;
+        let _: DkvMutation = node1.request("dkv/set", set("early", b"1")).await?;
+        node2.start().await?;
+
+        assert!(wait_for_value(&node2, "early", b"1").await);
+
+        let _: DkvMutation = node1.request("dkv/set", set("live", b"2")).await?;
+        assert!(wait_for_value(&node2, "live", b"2").await);
+        let _: DkvMutation = node2.request("dkv/set", set("live", b"3")).await?;
+        assert!(wait_for_value(&node1, "live", b"3").await);
+
+        node2.stop().await?;
+        node1.stop().await?;
+        Ok(())
+    })
+    .await
+    .expect("Test timed out")
+}
@@ -0,0 +1,63 @@
+// Tests for the file watcher service
+//
+// These tests verify that filesystem changes under watched paths are
+// published as `file_watcher/changed` events.
+
+use runar_node::Node;
+use runar_services::file_watcher::{
+    FileChangedEvent, FileWatcherConfig, FileWatcherService, WatchEventKind,
+};
+use runar_test_utils::create_node_test_config;
+use std::time::Duration;
+use tokio::time::timeout;
+use tokio_stream::StreamExt;
+
+/// Test that changes are published, including for a path created after start
+///
+/// INTENTION: Verify that a watched path which does not exist yet does not
+/// fail the service, is picked up once it appears, and that file creation
+/// is reported with the file's path.
+#[tokio::test(flavor = "multi_thread")]
+async fn test_file_watcher_publishes_changes() {
+    timeout(Duration::from_secs(20), async {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let watched_dir = temp_dir.path().join("config");
+
+        let mut config = create_node_test_config().expect("Error creating test config");
+        config.network_config = None;
+        let mut node = Node::new(config).await.unwrap();
+
+        let watcher_config = FileWatcherConfig::new(vec![watched_dir.clone()]);
+        let service = FileWatcherService::new(
+            "file_watcher".to_string(),
+            "file_watcher".to_string(),
+            watcher_config,
+        );
+        node.add_service(service).await.unwrap();
+        node.start().await.unwrap();
+
+        let mut changes = node
+            .subscribe_stream::<FileChangedEvent>("file_watcher/changed")
+            .await
+            .unwrap();
+
+        // The directory appears after the service started; wait for the retry to pick it up
+        std::fs::create_dir(&watched_dir).unwrap();
+        tokio::time::sleep(Duration::from_millis(1500)).await;
+
+        let file_path = watched_dir.join("app.toml");
+        std::fs::write(&file_path, "key = 1").unwrap();
+
+        let expected_path = file_path.to_string_lossy().to_string();
+        loop {
+            let change = changes.next().await.unwrap().unwrap();
+            if change.path == expected_path && change.kind == WatchEventKind::Created {
+                break;
+            }
+        }
+
+        node.stop().await.unwrap();
+    })
+    .await
+    .expect("Test timed out");
+}
@@ -0,0 +1,255 @@
+// Tests for the Raft leader election service
+//
+// These tests run a single node: either alone in its cluster, where it must
+// elect itself, or with an unreachable peer while the RPC actions are called
+// directly. A cluster of three nodes must then agree on one leader.
+
+use anyhow::Result;
+use runar_common::types::ArcValue;
+use runar_node::network::transport::PeerId;
+use runar_node::testing::TestNode;
+use runar_node::Node;
+use runar_services::raft::{
+    AppendEntriesRequest, AppendEntriesResponse, LeaderElected, RaftConfig, RaftRole, RaftService,
+    RaftStatus, VoteRequest, VoteResponse,
+};
+use runar_test_utils::{create_networked_node_test_config, without_discovery};
+use std::net::{Ipv4Addr, SocketAddr};
+use std::time::Duration;
+use tokio::time::{sleep, timeout, Instant};
+use tokio_stream::StreamExt;
+
+/// Test that a node alone in its cluster elects itself
+///
+/// INTENTION: With no peers the node's own vote is a majority, so after one
+/// election timeout it becomes leader of term 1 and publishes
+/// `raft/leader_elected`.
+#[tokio::test(flavor = "multi_thread")]
+async fn test_single_node_elects_itself() {
+    timeout(Duration::from_secs(20), async {
+        let config = RaftConfig::new(vec![]).with_election_timeout_ms(500, 600);
+        let mut node = TestNode::builder()
+            .with_service(RaftService::new(
+                "raft".to_string(),
+                "raft".to_string(),
+                config,
+            ))
+            .build()
+            .await
+            .unwrap();
+        let mut elections = node
+            .subscribe_stream::<LeaderElected>("raft/leader_elected")
+            .await
+            .unwrap();
+
+        let elected = elections.next().await.unwrap().unwrap();
+        assert_eq!(elected.term, 1);
+
+        let status: RaftStatus = node.request("raft/status", None::<ArcValue>).await.unwrap();
+        assert_eq!(status.role, RaftRole::Leader);
+        assert_eq!(status.term, 1);
+        assert_eq!(status.leader_id, Some(elected.leader_id.clone()));
+        assert_eq!(status.node_id, elected.leader_id);
+
+        node.stop().await.unwrap();
+    })
+    .await
+    .expect("Test timed out");
+}
+
+/// Test the vote and heartbeat rules
+///
+/// INTENTION: A member grants one vote per term, refuses stale terms, and
+/// follows the leader of the newest term it hears from. The election timeout
+/// is long enough that the node does not start an election of its own.
+#[tokio::test(flavor = "multi_thread")]
+async fn test_vote_and_append_entries_rules() {
+    timeout(Duration::from_secs(20), async {
+        let config = RaftConfig::new(vec![PeerId::new("00ff".to_string())])
+            .with_election_timeout_ms(30_000, 30_000);
+        let mut node = TestNode::builder()
+            .with_service(RaftService::new(
+                "raft".to_string(),
+                "raft".to_string(),
+                config,
+            ))
+            .build()
+            .await
+            .unwrap();
+        let status: RaftStatus = node.request("raft/status", None::<ArcValue>).await.unwrap();
+        let request_vote = format!("raft/request_vote/{}", status.node_id);
+        let append_entries = format!("raft/append_entries/{}", status.node_id);
+
+        let vote = |term: u64, candidate_id: &str| {
+            Some(ArcValue::from_struct(VoteRequest {
+                term,
+                candidate_id: candidate_id.to_string(),
+            }))
+        };
+        let response: VoteResponse = node.request(&request_vote, vote(1, "a")).await.unwrap();
+        assert!(response.vote_granted);
+        // Asking again in the same term is fine, another candidate is refused
+        let response: VoteResponse = node.request(&request_vote, vote(1, "a")).await.unwrap();
+        assert!(response.vote_granted);
+        let response: VoteResponse = node.request(&request_vote, vote(1, "b")).await.unwrap();
+        assert!(!response.vote_granted);
+        let response: VoteResponse = node.request(&request_vote, vote(0, "c")).await.unwrap();
+        assert_eq!(
+            response,
+            VoteResponse {
+                term: 1,
+                vote_granted: false
+            }
+        );
+
+        let heartbeat = |term: u64, leader_id: &str| {
+            Some(ArcValue::from_struct(AppendEntriesRequest {
+                term,
+                leader_id: leader_id.to_string(),
+            }))
+        };
+        let response: AppendEntriesResponse = node
+            .request(&append_entries, heartbeat(2, "b"))
+            .await
+            .unwrap();
+        assert_eq!(
+            response,
+            AppendEntriesResponse {
+                term: 2,
+                success: true
+            }
+        );
+        let response: AppendEntriesResponse = node
+            .request(&append_entries, heartbeat(1, "a"))
+            .await
+            .unwrap();
+        assert!(!response.success);
+
+        let status: RaftStatus = node.request("raft/status", None::<ArcValue>).await.unwrap();
+        assert_eq!(status.role, RaftRole::Follower);
+        assert_eq!(status.term, 2);
+        assert_eq!(status.leader_id.as_deref(), Some("b"));
+
+        // A new term frees the vote
+        let response: VoteResponse = node.request(&request_vote, vote(3, "c")).await.unwrap();
+        assert_eq!(
+            response,
+            VoteResponse {
+                term: 3,
+                vote_granted: true
+            }
+        );
+
+        node.stop().await.unwrap();
+    })
+    .await
+    .expect("Test timed out");
+}
+
+/// Poll the status of every node until they agree on a leader
+async fn wait_for_leader(nodes: &[Node]) -> Result<Vec<RaftStatus>> {
+    let deadline = Instant::now() + Duration::from_secs(20);
+    loop {
+        let mut statuses = Vec::new();
+        for node in nodes {
+            statuses.push(
+                node.request::<ArcValue, RaftStatus>("raft/status", None)
+                    .await?,
+            );
+        }
+        let leaders = statuses
+            .iter()
+            .filter(|status| status.role == RaftRole::Leader)
+            .count();
+        let agreed = statuses
+            .iter()
+            .all(|status| status.leader_id.is_some() && status.leader_id == statuses[0].leader_id);
+        if (leaders == 1 && agreed) || Instant::now() >= deadline {
+            return Ok(statuses);
+        }
+        sleep(Duration::from_millis(100)).await;
+    }
+}
+
+/// Test leader election in a cluster of three nodes
+///
+/// INTENTION: Every member provides the service at the same path and reaches
+/// the others' RPC actions through the paths nested under their peer IDs. The
+/// members elect exactly one leader that all of them
+/// follow in the same term, and elect a new one among the remaining two once
+/// the leader stops.
+#[tokio::test]
+async fn test_three_nodes_elect_one_leader() -> Result<()> {
+    timeout(Duration::from_secs(60), async {
+        // Each node dials the nodes created before it
+        let mut nodes = Vec::new();
+        let mut initial_peers = Vec::new();
+        for config in create_networked_node_test_config(3)? {
+            let config = without_discovery(config);
+            let port = config
+                .network_config
+                .as_ref()
+                .unwrap()
+                .transport_options
+                .bind_address
+                .port();
+            let node = Node::new(config.with_initial_peers(initial_peers.clone())).await?;
+            let peer_id = node.get_local_node_info().await?.peer_id;
+            initial_peers.push((SocketAddr::from((Ipv4Addr::LOCALHOST, port)), peer_id));
+            nodes.push(node);
+        }
+
+        for (index, node) in nodes.iter_mut().enumerate() {
+            let peers = initial_peers
+                .iter()
+                .enumerate()
+                .filter(|(other, _)| *other != index)
+                .map(|(_, (_, peer_id))| peer_id.clone())
+                .collect();
+            let config = RaftConfig::new(peers)
+                .with_election_timeout_ms(1_000, 2_000)
+                .with_heartbeat_interval_ms(100);
+            node.add_service(RaftService::new(
+                "raft".to_string(),
+                "raft".to_string(),
+                config,
+            ))
+            .await?;
+        }
+        for node in &mut nodes {
+            node.start().await?;
+        }
+
+        let statuses = wait_for_leader(&nodes).await?;
+        let leader = statuses
+            .iter()
+            .position(|status| status.role == RaftRole::Leader)
+            .expect("a leader is elected");
+        let term = statuses[leader].term;
+        let stopped_id = statuses[leader].node_id.clone();
+        for status in &statuses {
+            assert_eq!(status.leader_id.as_ref(), Some(&statuses[leader].node_id));
+            if status.role != RaftRole::Leader {
+                assert_eq!(status.role, RaftRole::Follower);
+            }
+            assert_eq!(status.term, term);
+        }
+
+        nodes.remove(leader).stop().await?;
+        let statuses = wait_for_leader(&nodes).await?;
+        let leaders: Vec<_> = statuses
+            .iter()
+            .filter(|status| status.role == RaftRole::Leader)
+            .collect();
+        assert_eq!(leaders.len(), 1);
+        assert!(leaders[0].term > term);
+        assert_ne!(leaders[0].node_id, stopped_id);
+
+        for node in &mut nodes {
+            node.stop().await?;
+        }
+        Ok(())
+    })
+    .await
+    .expect("Test timed out")
+}
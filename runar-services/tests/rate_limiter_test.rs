@@ -0,0 +1,181 @@
+// Tests for the rate limiter service
+//
+// These tests verify that each key gets its own token bucket, that requests
+// beyond it are denied and published as `rate_limiter/throttled` events,
+// that `reset` refills a bucket and that idle buckets are dropped.
+
+use anyhow::Result;
+use runar_common::types::ArcValue;
+use runar_node::Node;
+use runar_services::rate_limiter::{
+    ConsumeRequest, RateLimitResult, RateLimiterConfig, RateLimiterService, ThrottledEvent,
+    TokenBucketConfig,
+};
+use runar_test_utils::create_node_test_config;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::time::timeout;
+use tokio_stream::StreamExt;
+
+async fn check(node: &Node, key: &str) -> Result<RateLimitResult> {
+    node.request(
+        "rate_limiter/check",
+        Some(ArcValue::new_primitive(key.to_string())),
+    )
+    .await
+}
+
+async fn consume(node: &Node, key: &str, tokens: u64) -> Result<RateLimitResult> {
+    node.request(
+        "rate_limiter/consume",
+        Some(ArcValue::from_struct(ConsumeRequest {
+            key: key.to_string(),
+            tokens,
+        })),
+    )
+    .await
+}
+
+/// Test that buckets are per key and that denials are reported
+///
+/// INTENTION: A key may take up to its capacity before being denied, with
+/// `remaining` counting down; the denial publishes a `throttled` event and
+/// leaves other keys untouched. Keys with an override use their own bucket,
+/// and `reset` makes a drained bucket full again.
+#[tokio::test(flavor = "multi_thread")]
+async fn test_rate_limits_per_key() {
+    timeout(Duration::from_secs(20), async {
+        let mut config = create_node_test_config().expect("Error creating test config");
+        config.network_config = None;
+        let mut node = Node::new(config).await.unwrap();
+
+        // Refill slowly enough that no token comes back during the test
+        let limiter_config = RateLimiterConfig::new(TokenBucketConfig::new(3, 1))
+            .with_override("bulk", TokenBucketConfig::new(10, 1));
+        node.add_service(RateLimiterService::new(
+            "rate_limiter".to_string(),
+            "rate_limiter".to_string(),
+            limiter_config,
+        ))
+        .await
+        .unwrap();
+        node.start().await.unwrap();
+
+        let mut throttled = node
+            .subscribe_stream::<ThrottledEvent>("rate_limiter/throttled")
+            .await
+            .unwrap();
+
+        let start_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as u64;
+        for remaining in [2, 1, 0] {
+            let result = check(&node, "user-1").await.unwrap();
+            assert!(result.allowed);
+            assert_eq!(result.remaining, remaining);
+        }
+        let denied = check(&node, "user-1").await.unwrap();
+        assert!(!denied.allowed);
+        assert_eq!(denied.remaining, 0);
+        assert!(denied.reset_at > start_ms);
+
+        let event = throttled.next().await.unwrap().unwrap();
+        assert_eq!(
+            event,
+            ThrottledEvent {
+                key: "user-1".to_string(),
+                tokens_requested: 1,
+            }
+        );
+
+        // Another key has its own bucket
+        assert!(check(&node, "user-2").await.unwrap().allowed);
+
+        // Overrides: all of the tokens or none
+        let result = consume(&node, "bulk", 8).await.unwrap();
+        assert!(result.allowed);
+        assert_eq!(result.remaining, 2);
+        let denied = consume(&node, "bulk", 3).await.unwrap();
+        assert!(!denied.allowed);
+        assert_eq!(denied.remaining, 2);
+        assert_eq!(throttled.next().await.unwrap().unwrap().tokens_requested, 3);
+
+        // More than the bucket can ever hold
+        assert!(consume(&node, "user-2", 4).await.is_err());
+        assert!(consume(&node, "user-2", 0).await.is_err());
+
+        // `reset` returns null, which `request` cannot convert to a type; its
+        // effect is checked below instead
+        let _ = node
+            .request::<_, ()>(
+                "rate_limiter/reset",
+                Some(ArcValue::new_primitive("user-1".to_string())),
+            )
+            .await;
+        let result = check(&node, "user-1").await.unwrap();
+        assert!(result.allowed);
+        assert_eq!(result.remaining, 2);
+
+        node.stop().await.unwrap();
+    })
+    .await
+    .expect("Test timed out");
+}
+
+/// Test that buckets idle long enough to be full again are dropped
+///
+/// INTENTION: A stream of one-off keys does not grow the service's memory
+/// without bound, while a key still refilling keeps its bucket.
+#[tokio::test(flavor = "multi_thread")]
+async fn test_idle_buckets_are_dropped() {
+    timeout(Duration::from_secs(20), async {
+        let mut config = create_node_test_config().expect("Error creating test config");
+        config.network_config = None;
+        let mut node = Node::new(config).await.unwrap();
+
+        // Default buckets are full again after 1ms, "vip" only after 1000s
+        let limiter_config = RateLimiterConfig::new(TokenBucketConfig::new(1, 1000))
+            .with_override("vip", TokenBucketConfig::new(1000, 1));
+        let service = RateLimiterService::new(
+            "rate_limiter".to_string(),
+            "rate_limiter".to_string(),
+            limiter_config,
+        );
+        node.add_service(service.clone()).await.unwrap();
+        node.start().await.unwrap();
+
+        assert_eq!(consume(&node, "vip", 10).await.unwrap().remaining, 990);
+        for i in 0..200 {
+            assert!(check(&node, &format!("user-{i}")).await.unwrap().allowed);
+            tokio::time::sleep(Duration::from_millis(2)).await;
+        }
+        assert!(service.bucket_count() < 100);
+        assert_eq!(check(&node, "vip").await.unwrap().remaining, 989);
+
+        node.stop().await.unwrap();
+    })
+    .await
+    .expect("Test timed out");
+}
+
+/// Test that invalid buckets are rejected when the service starts
+///
+/// INTENTION: A zero capacity or refill rate is a configuration error, not
+/// a bucket that denies every request.
+#[tokio::test(flavor = "multi_thread")]
+async fn test_invalid_config_fails_init() {
+    let mut config = create_node_test_config().expect("Error creating test config");
+    config.network_config = None;
+    let mut node = Node::new(config).await.unwrap();
+
+    let limiter_config = RateLimiterConfig::new(TokenBucketConfig::new(3, 1))
+        .with_override("broken", TokenBucketConfig::new(0, 1));
+    let result = node
+        .add_service(RateLimiterService::new(
+            "rate_limiter".to_string(),
+            "rate_limiter".to_string(),
+            limiter_config,
+        ))
+        .await;
+    assert!(result.is_err());
+}
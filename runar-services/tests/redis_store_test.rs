@@ -0,0 +1,102 @@
+// Tests for the Redis key-value service
+//
+// These tests need a Redis server and only run when `REDIS_URL` is set,
+// e.g. `REDIS_URL=redis://127.0.0.1:6379 cargo test --test redis_store_test`.
+
+use runar_common::types::ArcValue;
+use runar_node::Node;
+use runar_services::redis_store::{RedisService, RedisServiceConfig};
+use runar_test_utils::create_node_test_config;
+use std::collections::HashMap;
+use std::time::Duration;
+use tokio::time::timeout;
+
+fn params(entries: Vec<(&str, ArcValue)>) -> Option<ArcValue> {
+    let map: HashMap<String, ArcValue> = entries
+        .into_iter()
+        .map(|(name, value)| (name.to_string(), value))
+        .collect();
+    Some(ArcValue::new_map(map))
+}
+
+/// Test the full set of cache actions against a live Redis
+///
+/// INTENTION: Verify that values round-trip through the serializer, that
+/// `delete`/`exists` report the key state and that counters are readable
+/// with `get`.
+#[tokio::test(flavor = "multi_thread")]
+async fn test_redis_service_actions() {
+    let Ok(url) = std::env::var("REDIS_URL") else {
+        println!("REDIS_URL not set, skipping Redis test");
+        return;
+    };
+
+    timeout(Duration::from_secs(20), async {
+        let mut config = create_node_test_config().expect("Error creating test config");
+        config.network_config = None;
+        let mut node = Node::new(config).await.unwrap();
+
+        let prefix = format!("runar-test-{}:", std::process::id());
+        let redis_config = RedisServiceConfig::new(url)
+            .with_pool_size(2)
+            .with_key_prefix(prefix);
+        let service = RedisService::new("redis".to_string(), "redis".to_string(), redis_config);
+        node.add_service(service).await.unwrap();
+        node.start().await.unwrap();
+
+        let key = || ArcValue::new_primitive("greeting".to_string());
+        let value = ArcValue::new_list(vec![
+            ArcValue::new_primitive("hello".to_string()),
+            ArcValue::new_primitive(42i64),
+        ]);
+        let _: () = node
+            .request(
+                "redis/set",
+                params(vec![
+                    ("key", key()),
+                    ("value", value),
+                    ("ttl_secs", ArcValue::new_primitive(60u64)),
+                ]),
+            )
+            .await
+            .unwrap();
+
+        let exists: bool = node.request("redis/exists", Some(key())).await.unwrap();
+        assert!(exists);
+
+        let mut stored: ArcValue = node.request("redis/get", Some(key())).await.unwrap();
+        let list = stored.as_list_ref::<ArcValue>().unwrap();
+        assert_eq!(list.len(), 2);
+        assert_eq!(list[0].clone().as_type::<String>().unwrap(), "hello");
+        assert_eq!(list[1].clone().as_type::<i64>().unwrap(), 42);
+
+        let deleted: bool = node.request("redis/delete", Some(key())).await.unwrap();
+        assert!(deleted);
+        let deleted: bool = node.request("redis/delete", Some(key())).await.unwrap();
+        assert!(!deleted);
+        let missing: ArcValue = node.request("redis/get", Some(key())).await.unwrap();
+        assert!(missing.is_null());
+
+        let counter = || ArcValue::new_primitive("counter".to_string());
+        for (by, expected) in [(5i64, 5i64), (-2, 3)] {
+            let value: i64 = node
+                .request(
+                    "redis/increment",
+                    params(vec![
+                        ("key", counter()),
+                        ("by", ArcValue::new_primitive(by)),
+                    ]),
+                )
+                .await
+                .unwrap();
+            assert_eq!(value, expected);
+        }
+        let mut current: ArcValue = node.request("redis/get", Some(counter())).await.unwrap();
+        assert_eq!(current.as_type::<i64>().unwrap(), 3);
+        let _: bool = node.request("redis/delete", Some(counter())).await.unwrap();
+
+        node.stop().await.unwrap();
+    })
+    .await
+    .expect("Test timed out");
+}
@@ -4,6 +4,7 @@
 // to create a simple service with actions.
 
 use std::collections::HashMap;
+use std::time::Duration;
 
 // use futures::lock::Mutex; // Unused import removed
 // use runar_common::ServiceInfo; // Unused import removed
@@ -13,11 +14,13 @@ use runar_common::types::ArcValue; // Removed ErasedArc and ValueCategory
                                    // use std::time::Duration; // Unused import removed
                                    // use tempfile::tempdir; // Unused import removed
 use runar_services::sqlite::{
-    ColumnDefinition, DataType, Params, Schema, SqlQuery, SqliteConfig, SqliteService,
-    TableDefinition, Value,
+    BulkInsertRequest, BulkInsertResult, ColumnDefinition, DataType, Fts5Config, FtsMatch,
+    FtsQuery, Params, RotateKeyRequest, Schema, SqlQuery, SqliteConfig, SqliteService,
+    TableDefinition, Value, DEFAULT_MAX_WAITING_COMMANDS,
 };
 use serde::{Deserialize, Serialize}; // For User and MyData structs
 
+#[allow(dead_code)]
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 struct User {
     id: Option<i64>,
@@ -25,6 +28,7 @@ struct User {
     age: i32,
 }
 
+#[allow(dead_code)]
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 struct MyData {
     id: i32,
@@ -43,8 +47,12 @@ struct TestDbGuard {
 
 impl TestDbGuard {
     fn new() -> Self {
+        Self::with_name("users_db_test")
+    }
+
+    fn with_name(name: &str) -> Self {
         let current_dir = std::env::current_dir().unwrap();
-        let db_path = format!("{}/users_db_test.db", current_dir.display());
+        let db_path = format!("{}/{name}.db", current_dir.display());
         Self { db_path }
     }
 
@@ -107,6 +115,7 @@ mod tests {
                         not_null: false, // Age can be null for this test example
                     },
                 ],
+                fts5_config: None,
             }],
             indexes: vec![], // Ensure all fields of Schema are initialized
         };
@@ -121,6 +130,11 @@ mod tests {
             db_path: db_guard.path().to_string(),
             schema,
             encryption: true,
+            key_rotation_token: None,
+            key_rotation_peers: Vec::new(),
+            transaction_timeout: Duration::from_secs(30),
+            transaction_cleanup_interval: Duration::from_secs(10),
+            max_waiting_commands: DEFAULT_MAX_WAITING_COMMANDS,
         };
 
         let service = SqliteService::new(service_name, service_path, sqlite_config);
@@ -180,4 +194,588 @@ mod tests {
             .clone();
         assert_eq!(age_av.as_type::<i64>().unwrap(), 33);
     }
+
+    #[tokio::test]
+    async fn test_full_text_search() {
+        let config = create_node_test_config().expect("Error creating test config");
+        let mut node = Node::new(config).await.unwrap();
+
+        let column = |name: &str, data_type: DataType, primary_key: bool| ColumnDefinition {
+            name: name.to_string(),
+            data_type,
+            primary_key,
+            autoincrement: primary_key,
+            not_null: true,
+        };
+        let schema = Schema {
+            tables: vec![TableDefinition {
+                name: "posts".to_string(),
+                columns: vec![
+                    column("id", DataType::Integer, true),
+                    column("title", DataType::Text, false),
+                    column("body", DataType::Text, false),
+                ],
+                fts5_config: Some(Fts5Config {
+                    content_columns: vec!["title".to_string(), "body".to_string()],
+                    tokenizer: Some("porter unicode61".to_string()),
+                }),
+            }],
+            indexes: vec![],
+        };
+
+        let db_guard = TestDbGuard::with_name("posts_fts_test");
+        let sqlite_config = SqliteConfig::new(db_guard.path(), schema, false);
+        let service = SqliteService::new(
+            "posts_db".to_string(),
+            "posts_db".to_string(),
+            sqlite_config,
+        );
+        node.add_service(service).await.unwrap();
+        node.start().await.unwrap();
+
+        for (title, body) in [
+            ("Async Rust", "Futures are polled by an executor"),
+            ("Gardening", "Tomatoes need plenty of sun"),
+            ("Rust ownership", "Borrowing rules keep memory safe"),
+        ] {
+            let insert = SqlQuery::new("INSERT INTO posts (title, body) VALUES (?, ?)")
+                .with_params(
+                    Params::new()
+                        .with_value(Value::Text(title.to_string()))
+                        .with_value(Value::Text(body.to_string())),
+                );
+            let _: i64 = node
+                .request(
+                    "posts_db/execute_query",
+                    Some(ArcValue::from_struct(insert)),
+                )
+                .await
+                .unwrap();
+        }
+
+        let matches: Vec<FtsMatch> = node
+            .request(
+                "posts_db/search",
+                Some(ArcValue::from_struct(FtsQuery::new("posts", "rust", 10))),
+            )
+            .await
+            .unwrap();
+        let mut row_ids: Vec<i64> = matches.iter().map(|m| m.row_id).collect();
+        row_ids.sort();
+        assert_eq!(row_ids, vec![1, 3]);
+        assert!(matches.iter().all(|m| m.snippet.is_none()));
+
+        // The porter tokenizer matches "polling" against "polled"
+        let matches: Vec<FtsMatch> = node
+            .request(
+                "posts_db/search",
+                Some(ArcValue::from_struct(
+                    FtsQuery::new("posts", "polling", 10).with_snippets(),
+                )),
+            )
+            .await
+            .unwrap();
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].row_id, 1);
+        assert!(matches[0].snippet.as_deref().unwrap().contains("[polled]"));
+
+        // Updates and deletes are reflected in the index
+        let delete = SqlQuery::new("DELETE FROM posts WHERE id = ?")
+            .with_params(Params::new().with_value(Value::Integer(3)));
+        let _: i64 = node
+            .request(
+                "posts_db/execute_query",
+                Some(ArcValue::from_struct(delete)),
+            )
+            .await
+            .unwrap();
+        let update = SqlQuery::new("UPDATE posts SET body = ? WHERE id = ?").with_params(
+            Params::new()
+                .with_value(Value::Text(
+                    "Raised beds and rust-resistant tools".to_string(),
+                ))
+                .with_value(Value::Integer(2)),
+        );
+        let _: i64 = node
+            .request(
+                "posts_db/execute_query",
+                Some(ArcValue::from_struct(update)),
+            )
+            .await
+            .unwrap();
+        let matches: Vec<FtsMatch> = node
+            .request(
+                "posts_db/search",
+                Some(ArcValue::from_struct(FtsQuery::new("posts", "rust", 1))),
+            )
+            .await
+            .unwrap();
+        assert_eq!(matches.len(), 1);
+
+        // Only tables with a full-text index can be searched
+        let result: anyhow::Result<Vec<FtsMatch>> = node
+            .request(
+                "posts_db/search",
+                Some(ArcValue::from_struct(FtsQuery::new("users", "rust", 10))),
+            )
+            .await;
+        assert!(result.is_err());
+
+        node.stop().await.unwrap();
+    }
+
+    /// Test rotating the encryption key of an encrypted database
+    ///
+    /// INTENTION: Only callers presenting the configured token may rotate the
+    /// key; after a rotation the service keeps working and the file on disk
+    /// opens with the new key but no longer with the old one.
+    #[tokio::test]
+    async fn test_rotate_key() {
+        let config = create_node_test_config().expect("Error creating test config");
+        let mut node = Node::new(config).await.unwrap();
+
+        let schema = Schema {
+            tables: vec![TableDefinition {
+                name: "notes".to_string(),
+                columns: vec![ColumnDefinition {
+                    name: "text".to_string(),
+                    data_type: DataType::Text,
+                    primary_key: false,
+                    autoincrement: false,
+                    not_null: true,
+                }],
+                fts5_config: None,
+            }],
+            indexes: vec![],
+        };
+
+        let db_guard = TestDbGuard::with_name("notes_rekey_test");
+        let sqlite_config =
+            SqliteConfig::new(db_guard.path(), schema, true).with_key_rotation_token("s3cret");
+        let service = SqliteService::new(
+            "notes_db".to_string(),
+            "notes_db".to_string(),
+            sqlite_config,
+        );
+        node.add_service(service).await.unwrap();
+        node.start().await.unwrap();
+
+        let insert = SqlQuery::new("INSERT INTO notes (text) VALUES (?)")
+            .with_params(Params::new().with_value(Value::Text("hello".to_string())));
+        let _: i64 = node
+            .request(
+                "notes_db/execute_query",
+                Some(ArcValue::from_struct(insert)),
+            )
+            .await
+            .unwrap();
+
+        let new_key = vec![7u8; 32];
+
+        // Missing or wrong tokens are rejected
+        for request in [
+            RotateKeyRequest::new(new_key.clone()),
+            RotateKeyRequest::new(new_key.clone()).with_token("guess"),
+        ] {
+            let result: anyhow::Result<bool> = node
+                .request("notes_db/rotate_key", Some(ArcValue::from_struct(request)))
+                .await;
+            assert!(result.is_err());
+        }
+
+        // Keys must be 32 bytes
+        let result: anyhow::Result<bool> = node
+            .request(
+                "notes_db/rotate_key",
+                Some(ArcValue::from_struct(
+                    RotateKeyRequest::new(vec![1u8; 8]).with_token("s3cret"),
+                )),
+            )
+            .await;
+        assert!(result.is_err());
+
+        let rotated: bool = node
+            .request(
+                "notes_db/rotate_key",
+                Some(ArcValue::from_struct(
+                    RotateKeyRequest::new(new_key.clone()).with_token("s3cret"),
+                )),
+            )
+            .await
+            .unwrap();
+        assert!(rotated);
+
+        let select = SqlQuery::new("SELECT text FROM notes");
+        let rows: Vec<ArcValue> = node
+            .request(
+                "notes_db/execute_query",
+                Some(ArcValue::from_struct(select)),
+            )
+            .await
+            .unwrap();
+        assert_eq!(rows.len(), 1);
+
+        node.stop().await.unwrap();
+
+        let count_rows = |key: &[u8]| -> rusqlite::Result<i64> {
+            let conn = rusqlite::Connection::open(db_guard.path())?;
+            conn.execute_batch(&format!("PRAGMA key = \"x'{}'\";", hex::encode(key)))?;
+            conn.query_row("SELECT count(*) FROM notes", [], |row| row.get(0))
+        };
+        assert_eq!(count_rows(&new_key).unwrap(), 1);
+        // Any other key, including the original one, no longer opens the file
+        assert!(count_rows(&[0u8; 32]).is_err());
+    }
+
+    /// Test that a rotated key opens the database after a restart
+    ///
+    /// INTENTION: Rotating the key stores it in the node keystore, so a node
+    /// started from the exported key state opens the database with it and
+    /// reads the rows written before the rotation.
+    #[tokio::test]
+    async fn test_rotated_key_survives_restart() {
+        let config = create_node_test_config().expect("Error creating test config");
+        let schema = Schema {
+            tables: vec![TableDefinition {
+                name: "notes".to_string(),
+                columns: vec![ColumnDefinition {
+                    name: "text".to_string(),
+                    data_type: DataType::Text,
+                    primary_key: false,
+                    autoincrement: false,
+                    not_null: true,
+                }],
+                fts5_config: None,
+            }],
+            indexes: vec![],
+        };
+        let db_guard = TestDbGuard::with_name("notes_rekey_restart_test");
+        let service = || {
+            SqliteService::new(
+                "notes_db".to_string(),
+                "notes_db".to_string(),
+                SqliteConfig::new(db_guard.path(), schema.clone(), true)
+                    .with_key_rotation_token("s3cret"),
+            )
+        };
+
+        let mut node = Node::new(config.clone()).await.unwrap();
+        node.add_service(service()).await.unwrap();
+        node.start().await.unwrap();
+        let insert = SqlQuery::new("INSERT INTO notes (text) VALUES (?)")
+            .with_params(Params::new().with_value(Value::Text("hello".to_string())));
+        let _: i64 = node
+            .request(
+                "notes_db/execute_query",
+                Some(ArcValue::from_struct(insert)),
+            )
+            .await
+            .unwrap();
+        let rotated: bool = node
+            .request(
+                "notes_db/rotate_key",
+                Some(ArcValue::from_struct(
+                    RotateKeyRequest::new(vec![7u8; 32]).with_token("s3cret"),
+                )),
+            )
+            .await
+            .unwrap();
+        assert!(rotated);
+        let key_state = node.key_manager_state().await.unwrap();
+        node.stop().await.unwrap();
+
+        let mut node = Node::new(config.with_key_manager_state(key_state))
+            .await
+            .unwrap();
+        node.add_service(service()).await.unwrap();
+        node.start().await.unwrap();
+        let rows: Vec<ArcValue> = node
+            .request(
+                "notes_db/execute_query",
+                Some(ArcValue::from_struct(SqlQuery::new(
+                    "SELECT text FROM notes",
+                ))),
+            )
+            .await
+            .unwrap();
+        assert_eq!(rows.len(), 1);
+        node.stop().await.unwrap();
+    }
+
+    /// Test that abandoned transactions are rolled back after the timeout
+    ///
+    /// INTENTION: While a transaction is open, statements outside it wait for
+    /// it to end; a committed transaction keeps its writes, while one left open
+    /// past `transaction_timeout` is rolled back and the database unlocked.
+    #[tokio::test]
+    async fn test_abandoned_transaction_rolled_back() {
+        let config = create_node_test_config().expect("Error creating test config");
+        let mut node = Node::new(config).await.unwrap();
+
+        let schema = Schema {
+            tables: vec![TableDefinition {
+                name: "notes".to_string(),
+                columns: vec![ColumnDefinition {
+                    name: "text".to_string(),
+                    data_type: DataType::Text,
+                    primary_key: false,
+                    autoincrement: false,
+                    not_null: true,
+                }],
+                fts5_config: None,
+            }],
+            indexes: vec![],
+        };
+        let sqlite_config = SqliteConfig::new(":memory:", schema, false)
+            .with_transaction_timeout(Duration::from_millis(200))
+            .with_transaction_cleanup_interval(Duration::from_millis(50));
+        let service = SqliteService::new(
+            "notes_db".to_string(),
+            "notes_db".to_string(),
+            sqlite_config,
+        );
+        node.add_service(service).await.unwrap();
+        node.start().await.unwrap();
+
+        let insert = |text: &str| {
+            SqlQuery::new("INSERT INTO notes (text) VALUES (?)")
+                .with_params(Params::new().with_value(Value::Text(text.to_string())))
+        };
+
+        let committed: String = node
+            .request("notes_db/begin_transaction", None::<ArcValue>)
+            .await
+            .unwrap();
+        let _: i64 = node
+            .request(
+                "notes_db/execute_query",
+                Some(ArcValue::from_struct(
+                    insert("committed").in_transaction(committed.clone()),
+                )),
+            )
+            .await
+            .unwrap();
+        // Statements outside the open transaction wait for it to end
+        let outside = {
+            let node = node.clone();
+            let query = insert("outside");
+            tokio::spawn(async move {
+                node.request::<_, i64>("notes_db/execute_query", Some(ArcValue::from_struct(query)))
+                    .await
+            })
+        };
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(!outside.is_finished());
+        let _: bool = node
+            .request("notes_db/commit_transaction", Some(committed))
+            .await
+            .unwrap();
+        assert_eq!(outside.await.unwrap().unwrap(), 1);
+
+        let abandoned: String = node
+            .request("notes_db/begin_transaction", None::<ArcValue>)
+            .await
+            .unwrap();
+        let _: i64 = node
+            .request(
+                "notes_db/execute_query",
+                Some(ArcValue::from_struct(
+                    insert("abandoned").in_transaction(abandoned.clone()),
+                )),
+            )
+            .await
+            .unwrap();
+        tokio::time::sleep(Duration::from_millis(500)).await;
+
+        // The transaction was rolled back, so it can no longer be committed
+        let result: anyhow::Result<bool> = node
+            .request("notes_db/commit_transaction", Some(abandoned))
+            .await;
+        assert!(result.is_err());
+
+        // The database is unlocked and only the committed rows remain
+        let _: i64 = node
+            .request(
+                "notes_db/execute_query",
+                Some(ArcValue::from_struct(insert("after"))),
+            )
+            .await
+            .unwrap();
+        let rows: Vec<ArcValue> = node
+            .request(
+                "notes_db/execute_query",
+                Some(ArcValue::from_struct(SqlQuery::new(
+                    "SELECT text FROM notes",
+                ))),
+            )
+            .await
+            .unwrap();
+        assert_eq!(rows.len(), 3);
+
+        node.stop().await.unwrap();
+    }
+
+    /// Test that statements waiting for a transaction are bounded
+    ///
+    /// INTENTION: A waiting statement whose request timed out is dropped
+    /// rather than run once the transaction ends, and statements beyond
+    /// `max_waiting_commands` are rejected instead of queued.
+    #[tokio::test]
+    async fn test_waiting_statements_bounded() {
+        let config = create_node_test_config()
+            .expect("Error creating test config")
+            .with_request_timeout(300);
+        let mut node = Node::new(config).await.unwrap();
+
+        let schema = Schema {
+            tables: vec![TableDefinition {
+                name: "notes".to_string(),
+                columns: vec![ColumnDefinition {
+                    name: "text".to_string(),
+                    data_type: DataType::Text,
+                    primary_key: false,
+                    autoincrement: false,
+                    not_null: true,
+                }],
+                fts5_config: None,
+            }],
+            indexes: vec![],
+        };
+        let sqlite_config =
+            SqliteConfig::new(":memory:", schema, false).with_max_waiting_commands(1);
+        let service = SqliteService::new(
+            "notes_db".to_string(),
+            "notes_db".to_string(),
+            sqlite_config,
+        );
+        node.add_service(service).await.unwrap();
+        node.start().await.unwrap();
+
+        let spawn_insert = |text: &str| {
+            let node = node.clone();
+            let query = SqlQuery::new("INSERT INTO notes (text) VALUES (?)")
+                .with_params(Params::new().with_value(Value::Text(text.to_string())));
+            tokio::spawn(async move {
+                node.request::<_, i64>("notes_db/execute_query", Some(ArcValue::from_struct(query)))
+                    .await
+            })
+        };
+
+        let transaction: String = node
+            .request("notes_db/begin_transaction", None::<ArcValue>)
+            .await
+            .unwrap();
+
+        // A caller that timed out frees its place and its write never runs
+        let timed_out = spawn_insert("timed out").await.unwrap();
+        assert!(timed_out.is_err());
+
+        let waiting = spawn_insert("waiting");
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(!waiting.is_finished());
+
+        // The queue is full
+        let rejected = spawn_insert("rejected").await.unwrap();
+        assert!(rejected.is_err());
+
+        let _: bool = node
+            .request("notes_db/commit_transaction", Some(transaction))
+            .await
+            .unwrap();
+        assert_eq!(waiting.await.unwrap().unwrap(), 1);
+
+        let rows: Vec<ArcValue> = node
+            .request(
+                "notes_db/execute_query",
+                Some(ArcValue::from_struct(SqlQuery::new(
+                    "SELECT text FROM notes",
+                ))),
+            )
+            .await
+            .unwrap();
+        assert_eq!(rows.len(), 1);
+
+        node.stop().await.unwrap();
+    }
+
+    /// Test inserting many rows with one request
+    ///
+    /// INTENTION: Rows are inserted in batches of `batch_size`; rows that
+    /// violate a constraint or have the wrong number of values are reported
+    /// by their index and the others are still inserted.
+    #[tokio::test]
+    async fn test_bulk_insert() {
+        let config = create_node_test_config().expect("Error creating test config");
+        let mut node = Node::new(config).await.unwrap();
+
+        let column = |name: &str, data_type: DataType, not_null: bool| ColumnDefinition {
+            name: name.to_string(),
+            data_type,
+            primary_key: false,
+            autoincrement: false,
+            not_null,
+        };
+        let schema = Schema {
+            tables: vec![TableDefinition {
+                name: "readings".to_string(),
+                columns: vec![
+                    column("sensor", DataType::Text, true),
+                    column("value", DataType::Real, false),
+                ],
+                fts5_config: None,
+            }],
+            indexes: vec![],
+        };
+        let service = SqliteService::new(
+            "readings_db".to_string(),
+            "readings_db".to_string(),
+            SqliteConfig::new(":memory:", schema, false),
+        );
+        node.add_service(service).await.unwrap();
+        node.start().await.unwrap();
+
+        let reading =
+            |sensor: &str, value: f64| vec![Value::Text(sensor.to_string()), Value::Real(value)];
+        let request = BulkInsertRequest::new("readings", &["sensor", "value"])
+            .with_row(reading("a", 1.0))
+            .with_row(vec![Value::Null, Value::Real(2.0)])
+            .with_row(reading("b", 3.0))
+            .with_row(vec![Value::Text("c".to_string())])
+            .with_row(reading("d", 5.0))
+            .with_batch_size(2);
+        let result: BulkInsertResult = node
+            .request(
+                "readings_db/bulk_insert",
+                Some(ArcValue::from_struct(request)),
+            )
+            .await
+            .unwrap();
+        assert_eq!(result.inserted_count, 3);
+        let failed: Vec<usize> = result.failed_rows.iter().map(|(index, _)| *index).collect();
+        assert_eq!(failed, vec![1, 3]);
+
+        let rows: Vec<ArcValue> = node
+            .request(
+                "readings_db/execute_query",
+                Some(ArcValue::from_struct(SqlQuery::new(
+                    "SELECT sensor FROM readings",
+                ))),
+            )
+            .await
+            .unwrap();
+        assert_eq!(rows.len(), 3);
+
+        // Only columns of the schema are accepted
+        let request =
+            BulkInsertRequest::new("readings", &["sensor", "unknown"]).with_row(reading("e", 6.0));
+        let result: anyhow::Result<BulkInsertResult> = node
+            .request(
+                "readings_db/bulk_insert",
+                Some(ArcValue::from_struct(request)),
+            )
+            .await;
+        assert!(result.is_err());
+
+        node.stop().await.unwrap();
+    }
 }
@@ -0,0 +1,166 @@
+// Tests for the WebAssembly plugin service
+//
+// The plugin is written in the WebAssembly text format and compiled to a
+// `.wasm` file, which the service loads when the node starts.
+#![cfg(feature = "wasm")]
+
+use runar_common::types::ArcValue;
+use runar_node::testing::TestNode;
+use runar_services::wasm::{WasmPluginService, WasmServiceConfig};
+use std::path::Path;
+use std::time::{Duration, Instant};
+use tempfile::TempDir;
+
+/// Plugin with a bump allocator that never frees
+const PLUGIN: &str = r#"
+(module
+  (memory (export "memory") 1)
+  (global $next (mut i32) (i32.const 1024))
+
+  (func (export "alloc") (param $len i32) (result i32)
+    (local $ptr i32)
+    (local.set $ptr (global.get $next))
+    (global.set $next (i32.add (global.get $next) (local.get $len)))
+    (local.get $ptr))
+
+  (func $pack (param $ptr i32) (param $len i32) (result i64)
+    (i64.or
+      (i64.shl (i64.extend_i32_u (local.get $ptr)) (i64.const 32))
+      (i64.extend_i32_u (local.get $len))))
+
+  ;; Returns the request unchanged
+  (func (export "echo") (param $ptr i32) (param $len i32) (result i64)
+    (call $pack (local.get $ptr) (local.get $len)))
+
+  ;; Doubles an i64 primitive in place: [0x01, 3, "i64", 8 bytes of bincode]
+  (func (export "double") (param $ptr i32) (param $len i32) (result i64)
+    (i64.store offset=5 (local.get $ptr)
+      (i64.mul (i64.load offset=5 (local.get $ptr)) (i64.const 2)))
+    (call $pack (local.get $ptr) (local.get $len)))
+
+  (func (export "spin") (param i32 i32) (result i64)
+    (loop $forever (br $forever))
+    (i64.const 0))
+
+  ;; Grows the memory by 100 pages (6.25 MiB), trapping when refused
+  (func (export "grow") (param $ptr i32) (param $len i32) (result i64)
+    (if (i32.eq (memory.grow (i32.const 100)) (i32.const -1))
+      (then unreachable))
+    (call $pack (local.get $ptr) (local.get $len)))
+
+  (func (export "secret") (param $ptr i32) (param $len i32) (result i64)
+    (call $pack (local.get $ptr) (local.get $len))))
+"#;
+
+fn write_plugin(dir: &Path) -> std::path::PathBuf {
+    let wasm_path = dir.join("plugin.wasm");
+    std::fs::write(&wasm_path, wat::parse_str(PLUGIN).unwrap()).unwrap();
+    wasm_path
+}
+
+/// Test that allowed exports are callable as actions
+///
+/// INTENTION: Requests and responses cross the ABI in the node's wire format,
+/// so the plugin can read and write `ArcValue`s, and exports missing from
+/// `allowed_actions` are not reachable.
+#[tokio::test(flavor = "multi_thread")]
+async fn test_wasm_actions_exchange_wire_format() {
+    let dir = TempDir::new().unwrap();
+    let config = WasmServiceConfig::new(write_plugin(dir.path()))
+        .with_allowed_actions(vec!["echo".to_string(), "double".to_string()]);
+    let node = TestNode::builder()
+        .with_service(WasmPluginService::new(
+            "wasm".to_string(),
+            "wasm".to_string(),
+            config,
+        ))
+        .build()
+        .await
+        .unwrap();
+
+    let echoed: String = node
+        .request(
+            "wasm/echo",
+            Some(ArcValue::new_primitive("hello".to_string())),
+        )
+        .await
+        .unwrap();
+    assert_eq!(echoed, "hello");
+
+    let doubled: i64 = node
+        .request("wasm/double", Some(ArcValue::new_primitive(21i64)))
+        .await
+        .unwrap();
+    assert_eq!(doubled, 42);
+
+    assert!(node
+        .request::<ArcValue, ArcValue>("wasm/secret", None)
+        .await
+        .is_err());
+}
+
+/// Test that the sandbox limits are enforced
+///
+/// INTENTION: An action running past the CPU timeout is interrupted and one
+/// growing the memory past the limit fails, while the service keeps serving
+/// other requests.
+#[tokio::test(flavor = "multi_thread")]
+async fn test_wasm_sandbox_limits() {
+    let dir = TempDir::new().unwrap();
+    let config = WasmServiceConfig::new(write_plugin(dir.path()))
+        .with_allowed_actions(vec![
+            "echo".to_string(),
+            "spin".to_string(),
+            "grow".to_string(),
+        ])
+        .with_memory_limit_mb(2)
+        .with_cpu_timeout_ms(200);
+    let node = TestNode::builder()
+        .with_service(WasmPluginService::new(
+            "wasm".to_string(),
+            "wasm".to_string(),
+            config,
+        ))
+        .build()
+        .await
+        .unwrap();
+
+    let started = Instant::now();
+    let error = node
+        .request::<ArcValue, ArcValue>("wasm/spin", None)
+        .await
+        .unwrap_err();
+    assert!(error.to_string().contains("CPU timeout"), "{error}");
+    assert!(started.elapsed() < Duration::from_secs(5));
+
+    assert!(node
+        .request::<ArcValue, ArcValue>("wasm/grow", None)
+        .await
+        .is_err());
+
+    let echoed: i64 = node
+        .request("wasm/echo", Some(ArcValue::new_primitive(7i64)))
+        .await
+        .unwrap();
+    assert_eq!(echoed, 7);
+}
+
+/// Test that a module lacking an allowed action fails to load
+///
+/// INTENTION: Configuration mistakes surface when the service is added
+/// rather than on the first request.
+#[tokio::test(flavor = "multi_thread")]
+async fn test_wasm_missing_action_fails_init() {
+    let dir = TempDir::new().unwrap();
+    let config = WasmServiceConfig::new(write_plugin(dir.path()))
+        .with_allowed_actions(vec!["missing".to_string()]);
+    assert!(TestNode::builder()
+        .with_service(WasmPluginService::new(
+            "wasm".to_string(),
+            "wasm".to_string(),
+            config
+        ))
+        .build()
+        .await
+        .is_err());
+}
@@ -127,6 +127,20 @@ pub fn create_networked_node_test_config(total: u32) -> Result<Vec<NodeConfig>>
     Ok(configs)
 }
 
+/// Remove the discovery providers so nodes only connect to the peers a test adds.
+///
+/// The configurations from `create_networked_node_test_config` enable multicast
+/// discovery, which lets nodes of concurrently running tests find each other.
+pub fn without_discovery(mut config: NodeConfig) -> NodeConfig {
+    let network_config = config
+        .network_config
+        .as_mut()
+        .expect("test config has networking");
+    network_config.discovery_providers.clear();
+    network_config.discovery_options = None;
+    config
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -28,10 +28,7 @@ async fn main() -> Result<()> {
     println!("Starting micro-services demo application...");
 
     // Configure and create a Node
-    let logging_config = LoggingConfig {
-        default_level: LogLevel::Debug,
-        component_levels: HashMap::new(), // Initialize explicitly
-    };
+    let logging_config = LoggingConfig::new().with_default_level(LogLevel::Debug);
 
     let node_config = create_node_test_config()
         .expect("Error creating test config")
@@ -7,6 +7,7 @@ use runar_node::{
 };
 use runar_test_utils::create_node_test_config;
 use std::sync::{Arc, Mutex};
+use tokio_stream::StreamExt;
 
 #[service(
     name = "Math Service",
@@ -68,15 +69,32 @@ async fn main() -> Result<()> {
     node.add_service(MathService::default()).await?;
     node.add_service(StatsService::default()).await?;
 
+    // Consume math/added events as a typed stream instead of a callback
+    let mut added_events = node.subscribe_stream::<f64>("math/added").await?;
+
     // call math/add
     let sum: f64 = node
         .request("math/add", Some(params! { "a" => 1.0, "b" => 2.0 }))
         .await?;
     assert_eq!(sum, 3.0);
 
+    // call math/add once more so the stream yields two totals
+    let _: f64 = node
+        .request("math/add", Some(params! { "a" => 3.0, "b" => 4.0 }))
+        .await?;
+
+    let mut totals = Vec::new();
+    while let Some(event) = added_events.next().await {
+        totals.push(event?);
+        if totals.len() == 2 {
+            break;
+        }
+    }
+    assert_eq!(totals, vec![3.0, 7.0]);
+
     // Query stats count
     let count: usize = node.request("stats/count", None::<ArcValue>).await?;
-    assert_eq!(count, 1);
+    assert_eq!(count, 2);
     println!("All good – stats recorded {count} value(s)");
     Ok(())
 }